@@ -4,6 +4,13 @@
 
 use clap::Args;
 use owo_colors::OwoColorize;
+use std::sync::Arc;
+use synesis_core::routing::{Router, RouterConfig, RoutingReason};
+use synesis_core::{A2AManifest, AgentWeights, ConsensusConfig, Council, CouncilConfig};
+use synesis_models::hardware::HardwareDetector;
+use synesis_models::lockfile::{ModelLock, LOCKFILE_NAME};
+use synesis_models::{ModelPool, Scheduler, SchedulerConfig};
+use synesis_privacy::Destination;
 use uuid::Uuid;
 
 use crate::config::Config;
@@ -54,8 +61,13 @@ pub async fn run(args: AskArgs, config: &Config) -> anyhow::Result<()> {
         println!();
     }
 
-    // Step 1: Initialize redactor
-    let mut redactor = initialize_redactor(config, &session_id)?;
+    // Step 1: Initialize redactor for where the query may end up
+    let destination = if args.local {
+        Destination::Local
+    } else {
+        Destination::Cloud
+    };
+    let mut redactor = initialize_redactor(config, destination)?;
 
     // Step 2: Privacy redaction
     let (redacted_query, redaction_result) = redact_query(&args.query, &mut redactor, &session_id)?;
@@ -78,16 +90,28 @@ pub async fn run(args: AskArgs, config: &Config) -> anyhow::Result<()> {
         println!();
     }
 
-    // Step 3: Run through tripartite council
-    let response = run_council(&redacted_query, &args, config).await?;
+    // Step 3: Decide where the query should run
+    let routing = route_query(&redacted_query, &args, config)?;
+    if args.verbose {
+        println!(
+            "{} {:?} ({})",
+            "Routing:".dimmed(),
+            routing.decision,
+            routing.factors.join("; ")
+        );
+        println!();
+    }
 
-    // Step 4: Reinflate any tokens in response
+    // Step 4: Run through tripartite council
+    let response = run_council(&redacted_query, config).await?;
+
+    // Step 5: Reinflate any tokens in response
     let final_response = reinflate_response(&response.content, &mut redactor)?;
 
-    // Step 5: Clear session tokens
+    // Step 6: Clear session tokens
     cleanup_session(&mut redactor, &session_id)?;
 
-    // Step 6: Display response
+    // Step 7: Display response
     match args.format.as_str() {
         "json" => {
             let output = serde_json::json!({
@@ -96,6 +120,7 @@ pub async fn run(args: AskArgs, config: &Config) -> anyhow::Result<()> {
                 "session_id": session_id,
                 "metadata": {
                     "local": !response.used_cloud,
+                    "routing": routing.decision,
                     "consensus_rounds": response.rounds,
                     "confidence": response.confidence,
                     "redaction_stats": redaction_result.stats,
@@ -120,21 +145,21 @@ pub async fn run(args: AskArgs, config: &Config) -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Initialize the redactor with session context
+/// Initialize the redactor using the profile configured for the destination
 fn initialize_redactor(
-    _config: &Config,
-    _session_id: &str,
+    config: &Config,
+    destination: Destination,
 ) -> anyhow::Result<synesis_privacy::Redactor> {
-    use synesis_privacy::{Redactor, RedactorConfig, TokenVault};
+    use synesis_privacy::TokenVault;
 
     // Create in-memory vault for this session
     let vault = TokenVault::in_memory()
         .map_err(|e| anyhow::anyhow!("Failed to create token vault: {}", e))?;
 
-    // Create redactor with default config
-    let redactor_config = RedactorConfig::default();
-
-    Redactor::new(redactor_config, vault)
+    config
+        .privacy
+        .policy()?
+        .redactor_for(destination, vault)
         .map_err(|e| anyhow::anyhow!("Failed to create redactor: {}", e))
 }
 
@@ -148,30 +173,109 @@ fn redact_query(
     Ok((redacted.redacted_text.clone(), redacted))
 }
 
+/// Route the query, counting its tokens with the Pathos model's tokenizer
+///
+/// Falls back to estimated counts when that model is not installed.
+fn route_query(query: &str, args: &AskArgs, config: &Config) -> anyhow::Result<RoutingReason> {
+    let mut router = Router::new(RouterConfig {
+        force_local: args.local,
+        force_cloud: args.cloud,
+        ..RouterConfig::default()
+    });
+    if let Some(tokenizer) = config.model_tokenizer(&config.agents.pathos.model)? {
+        router = router.with_token_counter(tokenizer);
+    }
+    Ok(router.route(&A2AManifest::new(query.to_string())))
+}
+
 /// Run the query through the tripartite council
-async fn run_council(
-    query: &str,
-    args: &AskArgs,
-    _config: &Config,
-) -> anyhow::Result<CouncilResponse> {
-    // TODO: Implement actual council orchestration with consensus engine
-    // For now, return a placeholder response
+async fn run_council(query: &str, config: &Config) -> anyhow::Result<CouncilResponse> {
+    let mut council = build_council(config).await?;
+    council.initialize().await?;
+    let response = council.process(A2AManifest::new(query.to_string())).await?;
+
     Ok(CouncilResponse {
-        content: format!(
-            "I received your query: \"{}\"\n\n[Council processing not yet implemented]",
-            query
-        ),
-        used_cloud: args.cloud,
-        rounds: 1,
-        confidence: 0.95,
+        content: response.content,
+        used_cloud: response.used_cloud,
+        rounds: response.rounds,
+        confidence: response.confidence,
         agent_votes: AgentVotes {
-            pathos: 0.90,
-            logos: 0.95,
-            ethos: 0.92,
+            pathos: response.votes.pathos,
+            logos: response.votes.logos,
+            ethos: response.votes.ethos,
         },
     })
 }
 
+/// Build the council from the configuration
+///
+/// Pathos and Ethos run on their configured models through a shared
+/// scheduler when those models are installed; otherwise they fall back to
+/// heuristics.
+async fn build_council(config: &Config) -> anyhow::Result<Council> {
+    let council = Council::new(council_config(config));
+    Ok(match local_scheduler(config).await? {
+        Some(scheduler) => council.with_scheduler(scheduler),
+        None => council,
+    })
+}
+
+/// Council settings from the `[agents]` and `[consensus]` sections
+fn council_config(config: &Config) -> CouncilConfig {
+    let agent = |agent: &crate::config::AgentConfig| synesis_core::AgentConfig {
+        model: agent.model.clone(),
+        enabled: agent.enabled,
+        temperature: agent.temperature,
+        max_tokens: agent.max_tokens,
+        system_prompt: None,
+    };
+
+    CouncilConfig {
+        pathos: agent(&config.agents.pathos),
+        logos: agent(&config.agents.logos),
+        ethos: agent(&config.agents.ethos),
+        consensus: ConsensusConfig {
+            threshold: config.consensus.threshold,
+            max_rounds: config.consensus.max_rounds,
+            weights: AgentWeights {
+                pathos: config.consensus.weights.pathos,
+                logos: config.consensus.weights.logos,
+                ethos: config.consensus.weights.ethos,
+            },
+        },
+    }
+}
+
+/// Scheduler over a pool of the installed Pathos and Ethos models
+///
+/// Models are looked up by name in `models.lock`. Returns `None` when
+/// neither is installed.
+async fn local_scheduler(config: &Config) -> anyhow::Result<Option<Scheduler>> {
+    let models_dir = config.models_dir();
+    let lock = ModelLock::load(&models_dir.join(LOCKFILE_NAME))?;
+
+    let mut installed: Vec<_> = [&config.agents.pathos, &config.agents.ethos]
+        .into_iter()
+        .filter(|agent| agent.enabled)
+        .filter_map(|agent| lock.entries().iter().find(|e| e.model == agent.model))
+        .collect();
+    installed.dedup_by(|a, b| a.model == b.model);
+    if installed.is_empty() {
+        return Ok(None);
+    }
+
+    let pool = Arc::new(ModelPool::for_hardware(&HardwareDetector::detect()?));
+    for entry in installed {
+        pool.add(entry.model.clone(), models_dir.join(&entry.filename))
+            .await?;
+        if let Err(e) = pool.load(&entry.model).await {
+            tracing::warn!("Could not load {}: {}", entry.model, e);
+        }
+    }
+
+    Ok(Some(Scheduler::new(pool, SchedulerConfig::default())))
+}
+
 /// Reinflate tokens in the response
 fn reinflate_response(
     response: &str,
@@ -227,4 +331,72 @@ mod tests {
         assert_eq!(redacted, "Hello world");
         assert!(result.token_map.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_council_from_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config {
+            data_dir: dir.path().to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        config.agents.logos.model = "custom-logos".to_string();
+        config.consensus.max_rounds = 2;
+
+        // No models installed: the council runs on heuristics
+        assert!(local_scheduler(&config).await.unwrap().is_none());
+        let council = build_council(&config).await.unwrap();
+        let status = council.status();
+        assert_eq!(status.logos_model, "custom-logos");
+        assert_eq!(status.max_rounds, 2);
+
+        let response = run_council("How do I sort a list in Rust?", &config)
+            .await
+            .unwrap();
+        assert!((1..=2).contains(&response.rounds));
+    }
+
+    #[test]
+    fn test_route_query() {
+        use synesis_core::routing::RoutingDecision;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            data_dir: dir.path().to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        let args = |local: bool| AskArgs {
+            query: String::new(),
+            local,
+            cloud: false,
+            verbose: false,
+            format: "text".to_string(),
+            knowledge: None,
+            show_redactions: false,
+        };
+
+        // No Pathos model installed: lengths are estimated
+        assert!(config
+            .model_tokenizer(&config.agents.pathos.model)
+            .unwrap()
+            .is_none());
+        let long = "word ".repeat(4000);
+        let routed = route_query(&long, &args(false), &config).unwrap();
+        assert_eq!(routed.decision, RoutingDecision::Hybrid);
+        assert!(routed.factors[0].contains("5000 tokens"));
+
+        let routed = route_query(&long, &args(true), &config).unwrap();
+        assert_eq!(routed.decision, RoutingDecision::Local);
+    }
+
+    #[test]
+    fn test_initialize_redactor_uses_destination_profile() {
+        let mut config = Config::default();
+        config.privacy.profile = "code-only".to_string();
+
+        let local = initialize_redactor(&config, Destination::Local).unwrap();
+        assert!(!local.contains_sensitive("mail test@example.com"));
+
+        let cloud = initialize_redactor(&config, Destination::Cloud).unwrap();
+        assert!(cloud.contains_sensitive("mail test@example.com"));
+    }
 }
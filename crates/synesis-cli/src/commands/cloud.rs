@@ -16,10 +16,22 @@
 //! - `synesis cloud ask` - Send query to cloud LLM
 //! - `synesis cloud push` - Upload LoRA to cloud
 
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
 use clap::Subcommand;
 use comfy_table::{presets::UTF8_FULL, Table};
 use owo_colors::OwoColorize;
-use std::io::Write;
+use serde::de::DeserializeOwned;
+use synesis_cloud::escalation::types::UserPreferences;
+use synesis_cloud::escalation::{
+    CloudModel, EscalationClient, EscalationContext, EscalationRequest,
+};
+use synesis_cloud::lora::{LocalLora, LoraUploadClient};
+use synesis_cloud::tunnel::CloudTunnel;
+use synesis_models::downloader::sha256_file;
 
 use crate::config::Config;
 
@@ -44,11 +56,8 @@ const DEFAULT_MAX_TOKENS: u32 = 1024;
 /// - Cause processing timeouts
 const MAX_LORA_UPLOAD_SIZE_MB: u64 = 2 * 1024;
 
-/// Progress bar refresh interval (milliseconds)
-///
-/// Updates progress bar every 100ms for smooth animation
-/// without excessive terminal redraws.
-const PROGRESS_REFRESH_MS: u64 = 100;
+/// Timeout for a cloud escalation request
+const ESCALATION_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[derive(Subcommand)]
 pub enum CloudCommands {
@@ -203,7 +212,7 @@ pub struct InviteArgs {
     pub expires_hours: u32,
 }
 
-pub async fn run(cmd: CloudCommands, _config: &Config) -> anyhow::Result<()> {
+pub async fn run(cmd: CloudCommands, config: &Config) -> anyhow::Result<()> {
     match cmd {
         CloudCommands::Login(args) => login(args).await,
         CloudCommands::Logout => logout().await,
@@ -213,8 +222,8 @@ pub async fn run(cmd: CloudCommands, _config: &Config) -> anyhow::Result<()> {
         CloudCommands::Usage(args) => show_usage(args).await,
         CloudCommands::Ping => ping().await,
         CloudCommands::Sync => sync().await,
-        CloudCommands::Ask(args) => ask(args).await,
-        CloudCommands::Push(args) => push(args).await,
+        CloudCommands::Ask(args) => ask(args, config).await,
+        CloudCommands::Push(args) => push(args, config).await,
         CloudCommands::Invite(args) => invite(args).await,
     }
 }
@@ -404,7 +413,7 @@ async fn sync() -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn ask(args: AskArgs) -> anyhow::Result<()> {
+async fn ask(args: AskArgs, config: &Config) -> anyhow::Result<()> {
     // Get query from args or stdin
     let query = if let Some(q) = args.query {
         q
@@ -420,6 +429,22 @@ async fn ask(args: AskArgs) -> anyhow::Result<()> {
         return Ok(());
     }
 
+    let request = EscalationRequest {
+        query,
+        model: parse_model(&args.model)?,
+        max_tokens: args.max_tokens,
+        stream: args.stream,
+        context: EscalationContext {
+            user_preferences: Some(UserPreferences {
+                preferred_language: None,
+                verbosity: Some(parse_choice(&args.verbosity, "verbosity")?),
+                tone: Some(parse_choice(&args.tone, "tone")?),
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
     println!();
     println!("{}", "Escalating to cloud...".dimmed());
     println!("  Model: {}", args.model.cyan());
@@ -427,27 +452,33 @@ async fn ask(args: AskArgs) -> anyhow::Result<()> {
     println!("  Tone: {}", args.tone);
     println!("  Verbosity: {}", args.verbosity);
 
-    // TODO: Actually call cloud escalation API
+    let tunnel = connect(config).await?;
+    let client = escalation_client(config, tunnel)?;
+
     println!();
     println!("{}", "Thinking...".dimmed());
-
-    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+    let response = client.escalate(request).await?;
 
     println!();
     println!("{}", "Response:".bold());
-    println!("This is a mock response from the cloud. The actual implementation will");
-    println!("connect to the SuperInstance cloud API and return a real response based");
-    println!("on your query.");
-
-    if args.stream {
-        println!();
-        println!("{} Streaming would be enabled", "(streaming)".dimmed());
-    }
+    println!("{}", response.content);
+    println!();
+    println!(
+        "{}",
+        format!(
+            "{} · {} tokens · {}¢ · {} ms",
+            response.model_used,
+            response.tokens_used.total(),
+            response.cost_cents,
+            response.latency_ms
+        )
+        .dimmed()
+    );
 
     Ok(())
 }
 
-async fn push(args: PushArgs) -> anyhow::Result<()> {
+async fn push(args: PushArgs, config: &Config) -> anyhow::Result<()> {
     println!("{}", "Uploading LoRA to cloud".bold());
     println!();
 
@@ -505,25 +536,14 @@ async fn push(args: PushArgs) -> anyhow::Result<()> {
     println!();
     println!("{}", "Uploading...".dimmed());
 
-    // TODO: Actually upload via LoRA upload client
-    // Simulate upload with progress animation
-    let progress_chars = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
-    let steps = 20;
-
-    for (i, ch) in progress_chars.iter().cycle().take(steps).enumerate() {
-        let progress = (i + 1) * 5;
-        print!("\r {} Uploading {}%", ch, progress);
-        std::io::stdout().flush()?;
-        tokio::time::sleep(tokio::time::Duration::from_millis(PROGRESS_REFRESH_MS)).await;
-    }
-
+    let cloud_id = upload_lora(config, path, &args.name, &args.base_model).await?;
     println!();
     println!();
     println!("{} LoRA uploaded successfully!", "✓".green());
-    println!("Cloud ID: {}", "lora-cloud-abc123".cyan());
+    println!("Cloud ID: {}", cloud_id.cyan());
     println!();
     println!("You can now use this LoRA in cloud queries with:");
-    println!("  synesis cloud ask --lora lora-cloud-abc123 \"your query\"");
+    println!("  synesis cloud ask --lora {} \"your query\"", cloud_id);
 
     Ok(())
 }
@@ -569,3 +589,142 @@ async fn invite(args: InviteArgs) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Upload a LoRA file to the cloud and return its cloud ID
+pub(crate) async fn upload_lora(
+    config: &Config,
+    path: &Path,
+    name: &str,
+    base_model: &str,
+) -> anyhow::Result<String> {
+    let lora = local_lora(path, name, base_model).await?;
+    let tunnel = connect(config).await?;
+    let client = lora_upload_client(config, tunnel)?;
+    Ok(client.upload(&lora).await?)
+}
+
+/// Connect a tunnel to the configured cloud endpoint
+async fn connect(config: &Config) -> anyhow::Result<Arc<CloudTunnel>> {
+    let mut tunnel = CloudTunnel::new(config.tunnel_config())?;
+    tunnel.connect().await.map_err(|e| {
+        anyhow::anyhow!(
+            "Cloud connection failed: {}. Run `synesis cloud login` first.",
+            e
+        )
+    })?;
+    Ok(Arc::new(tunnel))
+}
+
+/// Escalation client guarded by the privacy policy and recording every
+/// sent request in the privacy audit log
+fn escalation_client(
+    config: &Config,
+    tunnel: Arc<CloudTunnel>,
+) -> anyhow::Result<EscalationClient> {
+    let policy = config.privacy.policy()?;
+    let audit_log = Arc::new(config.open_audit_log()?);
+    let client = EscalationClient::new(tunnel, String::new(), ESCALATION_TIMEOUT, &policy)?;
+    Ok(client.with_audit_log(audit_log))
+}
+
+/// LoRA upload client that records every completed upload in the privacy
+/// audit log
+fn lora_upload_client(
+    config: &Config,
+    tunnel: Arc<CloudTunnel>,
+) -> anyhow::Result<LoraUploadClient> {
+    let audit_log = Arc::new(config.open_audit_log()?);
+    Ok(LoraUploadClient::new(tunnel).with_audit_log(audit_log))
+}
+
+async fn local_lora(path: &Path, name: &str, base_model: &str) -> anyhow::Result<LocalLora> {
+    Ok(LocalLora {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        base_model: base_model.to_string(),
+        path: path.to_path_buf(),
+        size_bytes: std::fs::metadata(path)?.len(),
+        checksum: sha256_file(path).await?,
+        created_at: Utc::now(),
+        uploaded: false,
+        cloud_id: None,
+        uploaded_at: None,
+    })
+}
+
+fn parse_model(model: &str) -> anyhow::Result<CloudModel> {
+    match model {
+        "auto" => Ok(CloudModel::Auto),
+        "sonnet" => Ok(CloudModel::ClaudeSonnet),
+        "opus" => Ok(CloudModel::ClaudeOpus),
+        other => anyhow::bail!(
+            "Unknown cloud model '{}' (expected sonnet, opus or auto)",
+            other
+        ),
+    }
+}
+
+/// Parse a snake_case option such as a tone or verbosity
+fn parse_choice<T: DeserializeOwned>(value: &str, what: &str) -> anyhow::Result<T> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| anyhow::anyhow!("Unknown {} '{}'", what, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use synesis_cloud::escalation::types::{Tone, Verbosity};
+    use synesis_cloud::CloudError;
+
+    #[tokio::test]
+    async fn test_clients_use_configured_policy_and_audit_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            data_dir: dir.path().join("data").to_string_lossy().into_owned(),
+            ..Config::default()
+        };
+        // Configured but never connected, so nothing leaves the machine
+        let tunnel = Arc::new(CloudTunnel::new(config.tunnel_config()).unwrap());
+
+        let client = escalation_client(&config, tunnel.clone()).unwrap();
+        let request = EscalationRequest {
+            query: "Mail the summary to alice@example.com".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            client.escalate(request).await,
+            Err(CloudError::SensitiveDataBlocked { .. })
+        ));
+        let request = EscalationRequest {
+            query: "What is a LoRA?".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            client.escalate(request).await,
+            Err(CloudError::TunnelConnection(_))
+        ));
+
+        let lora_path = dir.path().join("adapter.gguf");
+        std::fs::write(&lora_path, b"adapter weights").unwrap();
+        let lora = local_lora(&lora_path, "adapter", "phi-3").await.unwrap();
+        let client = lora_upload_client(&config, tunnel).unwrap();
+        assert!(matches!(
+            client.upload(&lora).await,
+            Err(CloudError::TunnelConnection(_))
+        ));
+
+        // Neither the blocked request nor the unsent ones reached the log
+        let log = config.open_audit_log().unwrap();
+        assert!(log.is_empty().unwrap());
+    }
+
+    #[test]
+    fn test_parse_ask_options() {
+        assert_eq!(parse_model("opus").unwrap(), CloudModel::ClaudeOpus);
+        assert!(parse_model("gpt-2").is_err());
+
+        let tone: Tone = parse_choice("technical", "tone").unwrap();
+        assert_eq!(tone, Tone::Technical);
+        assert!(parse_choice::<Verbosity>("loud", "verbosity").is_err());
+    }
+}
@@ -4,7 +4,9 @@ use clap::Subcommand;
 use comfy_table::{presets::UTF8_FULL, Table};
 use owo_colors::OwoColorize;
 
-use crate::config::Config;
+use synesis_privacy::Destination;
+
+use crate::config::{self, Config};
 
 #[derive(Subcommand)]
 pub enum ConfigCommands {
@@ -25,6 +27,9 @@ pub enum ConfigCommands {
 
     /// Show config file path
     Path,
+
+    /// List redaction profiles or select the active one
+    Profile(ProfileArgs),
 }
 
 #[derive(clap::Args)]
@@ -52,6 +57,12 @@ pub struct ResetArgs {
     pub force: bool,
 }
 
+#[derive(clap::Args)]
+pub struct ProfileArgs {
+    /// Profile to activate (e.g., "strict", "internal", "code-only")
+    pub name: Option<String>,
+}
+
 pub async fn run(cmd: ConfigCommands, config: &Config) -> anyhow::Result<()> {
     match cmd {
         ConfigCommands::Show => show_config(config).await,
@@ -60,6 +71,7 @@ pub async fn run(cmd: ConfigCommands, config: &Config) -> anyhow::Result<()> {
         ConfigCommands::Reset(args) => reset_config(args).await,
         ConfigCommands::Edit => edit_config().await,
         ConfigCommands::Path => show_path().await,
+        ConfigCommands::Profile(args) => select_profile(args, config).await,
     }
 }
 
@@ -91,11 +103,23 @@ async fn show_config(config: &Config) -> anyhow::Result<()> {
     println!("{}", "[privacy]".cyan());
     let mut table = Table::new();
     table.load_preset(UTF8_FULL);
-    table.add_row(vec!["redact_emails", "true"]);
-    table.add_row(vec!["redact_phones", "true"]);
-    table.add_row(vec!["redact_paths", "true"]);
-    table.add_row(vec!["redact_api_keys", "true"]);
-    table.add_row(vec!["custom_patterns", "[]"]);
+    let privacy = &config.privacy;
+    table.add_row(vec!["profile".to_string(), privacy.profile.clone()]);
+    table.add_row(vec!["redact_emails".to_string(), privacy.redact_emails.to_string()]);
+    table.add_row(vec!["redact_phones".to_string(), privacy.redact_phones.to_string()]);
+    table.add_row(vec!["redact_paths".to_string(), privacy.redact_paths.to_string()]);
+    table.add_row(vec!["redact_api_keys".to_string(), privacy.redact_api_keys.to_string()]);
+    table.add_row(vec![
+        "custom_patterns".to_string(),
+        privacy.custom_patterns.len().to_string(),
+    ]);
+    table.add_row(vec![
+        "allow_list".to_string(),
+        (privacy.allow_list.values.len() + privacy.allow_list.patterns.len()).to_string(),
+    ]);
+    table.add_row(vec!["destinations.local".to_string(), privacy.destinations.local.clone()]);
+    table.add_row(vec!["destinations.cloud".to_string(), privacy.destinations.cloud.clone()]);
+    table.add_row(vec!["outbound".to_string(), format!("{:?}", privacy.outbound).to_lowercase()]);
     println!("{table}");
     println!();
 
@@ -181,6 +205,53 @@ async fn edit_config() -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn select_profile(args: ProfileArgs, config: &Config) -> anyhow::Result<()> {
+    let mut policy = config.privacy.policy()?;
+
+    let Some(name) = args.name else {
+        println!("{}", "Redaction Profiles".bold());
+        println!();
+
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL);
+        table.set_header(vec!["Profile", "Active", "Used For"]);
+        for name in policy.profile_names() {
+            let used_for: Vec<String> = [Destination::Local, Destination::Cloud]
+                .into_iter()
+                .filter(|d| policy.profile_name_for(*d) == Some(name))
+                .map(|d| d.to_string())
+                .collect();
+            let active = if name == policy.active_profile { "✓" } else { "" };
+            table.add_row(vec![name.to_string(), active.to_string(), used_for.join(", ")]);
+        }
+        println!("{table}");
+
+        if !policy.redacts(Destination::Local) {
+            println!();
+            println!("{}", "Local-only processing is not redacted.".dimmed());
+        }
+        return Ok(());
+    };
+
+    policy.set_active(&name)?;
+
+    let mut updated = config.clone();
+    updated.privacy.profile = name.clone();
+    config::save_config(&updated, None)?;
+
+    println!("{} Active redaction profile: {}", "✓".green(), name.cyan());
+    if let Some(cloud) = policy.profile_name_for(Destination::Cloud) {
+        if cloud != name {
+            println!(
+                "{}",
+                format!("Note: cloud escalation still uses the '{}' profile.", cloud).dimmed()
+            );
+        }
+    }
+
+    Ok(())
+}
+
 async fn show_path() -> anyhow::Result<()> {
     let config_path = dirs::home_dir()
         .ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?
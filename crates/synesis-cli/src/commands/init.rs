@@ -4,16 +4,16 @@
 
 use clap::Args;
 use dialoguer::{theme::ColorfulTheme, Confirm};
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize;
 use std::sync::Arc;
 use tokio::signal::ctrl_c;
 
-use crate::config::{save_config, Config};
+use crate::config::{save_config, Config, DownloadsConfig};
 use synesis_knowledge::vault::KnowledgeVault;
+use synesis_models::download_queue::DownloadQueue;
 use synesis_models::downloader::{
-    known_models, DownloadPhase, DownloadProgress as ModelDownloadProgress,
-    Downloader as ModelDownloader,
+    known_models, DownloadPhase, DownloadProgress as ModelDownloadProgress, ProgressCallback,
 };
 use synesis_models::hardware::HardwareDetector;
 
@@ -36,7 +36,7 @@ pub struct InitArgs {
     pub yes: bool,
 }
 
-pub async fn run(args: InitArgs, _config: &Config) -> anyhow::Result<()> {
+pub async fn run(args: InitArgs, config: &Config) -> anyhow::Result<()> {
     print_banner();
 
     // Step 1: Detect hardware
@@ -132,7 +132,13 @@ pub async fn run(args: InitArgs, _config: &Config) -> anyhow::Result<()> {
         }
 
         print_step(4, "Downloading models...");
-        download_models(&recommended_models, &base_dir, args.force).await?;
+        download_models(
+            &recommended_models,
+            &base_dir,
+            &config.downloads,
+            args.force,
+        )
+        .await?;
         println!();
     } else {
         print_step(4, "Skipping model downloads (--skip-models)");
@@ -244,88 +250,85 @@ async fn create_directories() -> anyhow::Result<std::path::PathBuf> {
 async fn download_models(
     models: &[(&'static str, synesis_models::downloader::DownloadSpec)],
     base_dir: &std::path::Path,
-    _force: bool,
+    downloads: &DownloadsConfig,
+    force: bool,
 ) -> anyhow::Result<()> {
     let models_dir = base_dir.join("models");
-    let downloader = std::sync::Arc::new(ModelDownloader::new(models_dir.clone()));
-
-    // Set up Ctrl+C handler
-    let ctrl_c = tokio::spawn(async move {
-        ctrl_c().await.ok();
-    });
-
-    let mut download_futures = Vec::new();
+    let downloader = Arc::new(downloads.downloader(models_dir.clone()));
 
+    // Queue the models on disk, so an interrupted init resumes them
+    let mut queue = DownloadQueue::open(&models_dir)?;
     for (name, spec) in models {
-        let name = name.to_string();
-        let name_for_callback = name.clone();
-        let spec = spec.clone();
-
-        // Create progress bar for this model
-        let total_size = spec.size_bytes.unwrap_or(100);
-        let pb = ProgressBar::new(total_size);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template(&format!(
-                    "  {} [{{bar:40.cyan/blue}}] {{bytes}}/{{total_bytes}} ({{eta}})",
-                    name
-                ))?
-                .progress_chars("█▓░"),
-        );
-        pb.set_message(format!("Downloading {}", name));
+        queue.enqueue(name, spec.clone());
+        if force {
+            // Fetch again from scratch
+            downloader.discard(spec).await?;
+            queue.reset(name);
+        }
+    }
+    queue.save()?;
 
-        let pb_clone = pb.clone();
+    let bars = MultiProgress::new();
+    let style = ProgressStyle::default_bar()
+        .template("  {prefix:20} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta}) {msg}")?
+        .progress_chars("█▓░");
 
-        let progress_callback =
-            Arc::new(
-                move |progress: ModelDownloadProgress| match progress.phase {
-                    DownloadPhase::Checking => {
-                        pb_clone.set_message(format!("Checking {}", name_for_callback));
-                    },
-                    DownloadPhase::Downloading => {
-                        if let Some(total) = progress.total {
-                            pb_clone.set_length(total);
-                        }
-                        pb_clone.set_position(progress.downloaded);
-                    },
-                    DownloadPhase::Verifying => {
-                        pb_clone.set_message(format!("Verifying {}", name_for_callback));
-                    },
-                    DownloadPhase::Complete => {
-                        pb_clone.finish_with_message(format!("{} ✓", name_for_callback.cyan()));
+    // Wait for all downloads to complete or Ctrl+C
+    let report = {
+        let download = queue.run(downloader.clone(), downloads.max_concurrent, |entry| {
+            // Create progress bar for this model
+            let pb = bars.add(ProgressBar::new(entry.spec.size_bytes.unwrap_or(100)));
+            pb.set_style(style.clone());
+            pb.set_prefix(entry.name.clone());
+
+            let name = entry.name.clone();
+            let progress_callback: ProgressCallback =
+                Arc::new(
+                    move |progress: ModelDownloadProgress| match progress.phase {
+                        DownloadPhase::Checking => {
+                            pb.set_message("checking");
+                        },
+                        DownloadPhase::Downloading => {
+                            if let Some(total) = progress.total {
+                                pb.set_length(total);
+                            }
+                            pb.set_position(progress.downloaded);
+                        },
+                        DownloadPhase::Verifying => {
+                            pb.set_message("verifying");
+                        },
+                        DownloadPhase::Complete => {
+                            pb.finish_with_message(format!("{} ✓", name.cyan()));
+                        },
+                        _ => {},
                     },
-                    _ => {},
-                },
-            );
-
-        let downloader_ref = downloader.clone();
-        let spec_clone = spec.clone();
-
-        download_futures.push(async move {
-            tokio::task::spawn_blocking(move || {
-                let rt = tokio::runtime::Handle::current();
-                rt.block_on(async {
-                    if let Err(e) = downloader_ref
-                        .download(&spec_clone, Some(progress_callback))
-                        .await
-                    {
-                        eprintln!("  {} {}: {}", "Error:".red(), name, e);
-                    }
-                })
-            })
-            .await
-            .ok();
+                );
+            Some(progress_callback)
         });
-    }
-
-    // Wait for all downloads to complete or Ctrl+C
-    tokio::select! {
-        _ = async { futures::future::join_all(download_futures).await } => {},
-        _ = ctrl_c => {
-            println!();
-            println!("  {} Downloads cancelled by user", "Interrupted:".yellow());
-            return Ok(());
+        tokio::pin!(download);
+
+        tokio::select! {
+            report = &mut download => report?,
+            _ = ctrl_c() => {
+                // Let running downloads checkpoint before stopping
+                downloader.cancel();
+                download.await?;
+                println!();
+                println!(
+                    "  {} Downloads paused; run `synesis init` again to resume",
+                    "Interrupted:".yellow()
+                );
+                return Ok(());
+            }
         }
+    };
+
+    for (name, error) in &report.failed {
+        eprintln!("  {} {}: {}", "Error:".red(), name, error);
+    }
+    if report.failed.is_empty() {
+        queue.remove_completed();
+        queue.save()?;
     }
 
     Ok(())
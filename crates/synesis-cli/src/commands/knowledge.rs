@@ -2,14 +2,34 @@
 
 use clap::Subcommand;
 use comfy_table::{presets::UTF8_FULL, Table};
+use dialoguer::{theme::ColorfulTheme, Confirm};
 use indicatif::{ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::signal::ctrl_c;
+use tokio::sync::{mpsc, Mutex};
 
 use crate::config::Config;
-use synesis_knowledge::{FileWatcher, KnowledgeVault, PlaceholderEmbedder, WatchConfig};
+use crate::display::{format_bytes, format_relative_time};
+use synesis_knowledge::search::HybridSearch;
+use synesis_knowledge::{
+    migrate_embeddings, CrossEncoderReranker, DateRange, Document, DocumentIndexer, EmbeddingModel,
+    EmbeddingProvider, FileWatcher, FusionStrategy, IndexOutcome, IndexerConfig, KnowledgeVault,
+    LocalEmbedder, MetadataCondition, PlaceholderEmbedder, SearchFacets, SearchFilter,
+    SearchOptions, SearchResult, WatchConfig,
+};
+use synesis_models::TokenCounter;
+
+/// Default embedding model, whose dimensions a new vault takes
+const EMBEDDING_MODEL: &str = "bge-micro";
+
+/// Hybrid search weights (vector, keyword)
+const VECTOR_WEIGHT: f32 = 0.7;
+const KEYWORD_WEIGHT: f32 = 0.3;
+
+/// Characters of chunk content shown in search results
+const PREVIEW_CHARS: usize = 80;
 
 #[derive(Subcommand)]
 pub enum KnowledgeCommands {
@@ -33,6 +53,12 @@ pub enum KnowledgeCommands {
 
     /// Watch a directory for changes
     Watch(WatchArgs),
+
+    /// Re-embed the vault with a different embedding model
+    Migrate(MigrateArgs),
+
+    /// Tag documents, or list tags
+    Tag(TagArgs),
 }
 
 #[derive(clap::Args)]
@@ -90,12 +116,89 @@ pub struct SearchArgs {
     /// Minimum similarity threshold (0.0-1.0)
     #[arg(long, default_value = "0.5")]
     pub threshold: f32,
+
+    /// How to combine vector and keyword results: rrf, weighted
+    #[arg(long, default_value = "rrf")]
+    pub fusion: String,
+
+    /// Rerank results with a cross-encoder model (file path or name in the models directory)
+    #[arg(long)]
+    pub rerank: Option<String>,
+
+    /// Number of candidates to rerank
+    #[arg(long, default_value = "20", requires = "rerank")]
+    pub rerank_top: usize,
+
+    /// Diversify results (0.0-1.0; lower favours variety over relevance)
+    #[arg(long)]
+    pub diversity: Option<f32>,
+
+    /// Only documents whose full path matches this glob (e.g. "*/design/*"); repeatable
+    #[arg(long = "path", value_name = "GLOB")]
+    pub paths: Vec<String>,
+
+    /// Only documents with this tag; repeat to require several
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Only documents updated on or after this date (YYYY-MM-DD, RFC 3339, or e.g. 90d, 2w)
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Only documents updated before this date
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// Apply --since/--until to when documents were first indexed instead
+    #[arg(long)]
+    pub indexed: bool,
+
+    /// Metadata condition such as "project=atlas" or "chunk.page>=3"; repeatable
+    #[arg(long = "where", value_name = "CONDITION")]
+    pub conditions: Vec<MetadataCondition>,
+
+    /// Show how many matching documents there are per type and tag
+    #[arg(long)]
+    pub facets: bool,
 }
 
 #[derive(clap::Args)]
 pub struct ReindexArgs {
     /// Only reindex specific document
     pub document: Option<String>,
+
+    /// Only rebuild the vector search index (no re-embedding)
+    #[arg(long, conflicts_with = "document")]
+    pub ann: bool,
+}
+
+#[derive(clap::Args)]
+pub struct MigrateArgs {
+    /// Embedding model: "placeholder-sha256", or a file path or name in the models directory
+    #[arg(long, required_unless_present = "abort")]
+    pub embedder: Option<String>,
+
+    /// Chunks embedded per batch
+    #[arg(long, default_value = "32")]
+    pub batch_size: usize,
+
+    /// Abandon an unfinished migration
+    #[arg(long, conflicts_with = "embedder")]
+    pub abort: bool,
+}
+
+#[derive(clap::Args)]
+pub struct TagArgs {
+    /// Document ID or path pattern; lists every tag when omitted
+    pub pattern: Option<String>,
+
+    /// Tags to add; lists the documents' tags when omitted
+    #[arg(requires = "pattern")]
+    pub tags: Vec<String>,
+
+    /// Remove the tags instead of adding them
+    #[arg(long, requires = "tags")]
+    pub remove: bool,
 }
 
 #[derive(clap::Args)]
@@ -108,186 +211,862 @@ pub struct WatchArgs {
     pub include: Option<Vec<String>>,
 }
 
-pub async fn run(cmd: KnowledgeCommands, _config: &Config) -> anyhow::Result<()> {
+pub async fn run(cmd: KnowledgeCommands, config: &Config) -> anyhow::Result<()> {
     match cmd {
-        KnowledgeCommands::Add(args) => add_documents(args).await,
-        KnowledgeCommands::Remove(args) => remove_documents(args).await,
-        KnowledgeCommands::List(args) => list_documents(args).await,
-        KnowledgeCommands::Search(args) => search_vault(args).await,
-        KnowledgeCommands::Reindex(args) => reindex_vault(args).await,
-        KnowledgeCommands::Stats => show_stats().await,
-        KnowledgeCommands::Watch(args) => watch_directory(args).await,
+        KnowledgeCommands::Add(args) => add_documents(args, config).await,
+        KnowledgeCommands::Remove(args) => remove_documents(args, config).await,
+        KnowledgeCommands::List(args) => list_documents(args, config).await,
+        KnowledgeCommands::Search(args) => search_vault(args, config).await,
+        KnowledgeCommands::Reindex(args) => reindex_vault(args, config).await,
+        KnowledgeCommands::Stats => show_stats(config).await,
+        KnowledgeCommands::Watch(args) => watch_directory(args, config).await,
+        KnowledgeCommands::Migrate(args) => migrate_vault(args, config).await,
+        KnowledgeCommands::Tag(args) => tag_documents(args, config).await,
     }
 }
 
-async fn add_documents(args: AddArgs) -> anyhow::Result<()> {
-    println!("{}", "Adding documents to knowledge vault...".bold());
-    println!();
+fn open_vault(config: &Config) -> anyhow::Result<KnowledgeVault> {
+    let path = config.knowledge_db_path();
+    KnowledgeVault::open(&path, default_dimensions(config)?).map_err(|e| {
+        anyhow::anyhow!("Failed to open knowledge vault {}: {}", path.display(), e)
+    })
+}
 
-    for path in &args.paths {
-        println!("  {} {}", "→".dimmed(), path);
+/// Embedding dimensions for a vault that hasn't recorded a model
+///
+/// Read from the installed default model's header; the placeholder's
+/// otherwise. Vaults with a recorded model use that model's dimensions.
+fn default_dimensions(config: &Config) -> anyhow::Result<u32> {
+    let installed = config
+        .model_metadata(EMBEDDING_MODEL)?
+        .and_then(|metadata| metadata.embedding_length)
+        .and_then(|length| u32::try_from(length).ok());
+    Ok(installed.unwrap_or_else(|| PlaceholderEmbedder::default().dimensions()))
+}
+
+/// Embedder used for indexing and queries
+///
+/// The model the vault's embeddings came from, so query vectors match
+/// stored ones; the placeholder for vaults that haven't recorded one.
+fn embedder(vault: &KnowledgeVault, config: &Config) -> anyhow::Result<Box<dyn EmbeddingProvider>> {
+    match vault.embedding_model()? {
+        Some(model) => load_embedder(&model.name, model.dimensions, config),
+        None => Ok(Box::new(PlaceholderEmbedder::new(vault.embedding_dimensions()))),
+    }
+}
+
+/// Load an embedding model by name
+///
+/// `dimensions` only applies to the placeholder; real models have their own.
+fn load_embedder(
+    name: &str,
+    dimensions: u32,
+    config: &Config,
+) -> anyhow::Result<Box<dyn EmbeddingProvider>> {
+    let placeholder = PlaceholderEmbedder::new(dimensions);
+    if name == placeholder.model_name() {
+        return Ok(Box::new(placeholder));
     }
-    println!();
 
-    // TODO: Use synesis_knowledge::indexer
-    let pb = ProgressBar::new(100);
+    let path = resolve_model_path(name, config);
+    let model = LocalEmbedder::load(&path).map_err(|e| {
+        anyhow::anyhow!("Failed to load embedding model {}: {}", path.display(), e)
+    })?;
+    Ok(Box::new(model))
+}
+
+fn progress_bar(len: u64) -> anyhow::Result<ProgressBar> {
+    let pb = ProgressBar::new(len);
     pb.set_style(
         ProgressStyle::default_bar()
-            .template("{msg} [{bar:40.cyan/blue}] {pos}/{len}")?
+            .template("{msg:30} [{bar:40.cyan/blue}] {pos}/{len}")?
             .progress_chars("█▓░"),
     );
+    Ok(pb)
+}
+
+/// Match a file name against a simple wildcard pattern (`*` and `?`)
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = backtrack {
+            p = star_p + 1;
+            n = star_n + 1;
+            backtrack = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Files selected by `knowledge add`
+///
+/// Hidden entries inside directories are skipped. Files given explicitly
+/// are always included; include/exclude patterns apply to directory walks
+/// and are matched against the file name.
+fn collect_files(args: &AddArgs) -> anyhow::Result<Vec<PathBuf>> {
+    let selected = |path: &Path| {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let included = args
+            .include
+            .as_ref()
+            .map(|patterns| patterns.iter().any(|p| matches_pattern(p, &name)))
+            .unwrap_or(true);
+        let excluded = args
+            .exclude
+            .as_ref()
+            .map(|patterns| patterns.iter().any(|p| matches_pattern(p, &name)))
+            .unwrap_or(false);
+        included && !excluded
+    };
+
+    let mut files = Vec::new();
+    for path in &args.paths {
+        let path = PathBuf::from(path);
+        if path.is_file() {
+            files.push(path);
+            continue;
+        }
+        if !path.is_dir() {
+            anyhow::bail!("Path does not exist: {}", path.display());
+        }
+
+        let mut stack = vec![path];
+        while let Some(dir) = stack.pop() {
+            for entry in std::fs::read_dir(&dir)? {
+                let entry_path = entry?.path();
+                let hidden = entry_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().starts_with('.'))
+                    .unwrap_or(false);
+                if hidden {
+                    continue;
+                }
+                if entry_path.is_dir() {
+                    if args.recursive {
+                        stack.push(entry_path);
+                    }
+                } else if entry_path.is_file() && selected(&entry_path) {
+                    files.push(entry_path);
+                }
+            }
+        }
+    }
+
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Totals from a batch of indexing outcomes
+#[derive(Default)]
+struct IndexSummary {
+    indexed: usize,
+    updated: usize,
+    skipped: usize,
+    chunks: u64,
+    reused: u64,
+    embedded: u64,
+    failures: Vec<(String, String)>,
+}
+
+impl IndexSummary {
+    fn record(&mut self, outcome: IndexOutcome) {
+        let label = outcome
+            .path
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "(content)".to_string());
+        match outcome.result {
+            Ok(result) if result.skipped => self.skipped += 1,
+            Ok(result) => {
+                if result.updated {
+                    self.updated += 1;
+                    self.reused += u64::from(result.reused_chunks);
+                    self.embedded += u64::from(result.embedded_chunks);
+                } else {
+                    self.indexed += 1;
+                }
+                self.chunks += u64::from(result.chunk_count);
+            },
+            Err(e) => self.failures.push((label, e.to_string())),
+        }
+    }
+
+    fn print(&self, verb: &str) {
+        println!(
+            "{} {} {} documents ({} chunks)",
+            "✓".green(),
+            verb,
+            self.indexed + self.updated,
+            self.chunks
+        );
+        if self.updated > 0 {
+            println!(
+                "  {} {} updated in place ({} chunks reused, {} embedded)",
+                "↻".dimmed(),
+                self.updated,
+                self.reused,
+                self.embedded
+            );
+        }
+        if self.skipped > 0 {
+            println!(
+                "  {} {} skipped (already indexed with identical content)",
+                "→".dimmed(),
+                self.skipped
+            );
+        }
+        if !self.failures.is_empty() {
+            println!("  {} {} failed:", "✗".red(), self.failures.len());
+            for (path, error) in &self.failures {
+                println!("    {} {}", path, error.dimmed());
+            }
+        }
+    }
+}
 
-    pb.set_message("Processing");
-    for i in 0..=100 {
-        pb.set_position(i);
-        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+/// Wait for `count` outcomes, advancing the progress bar per file
+async fn collect_outcomes(
+    results: &mut mpsc::UnboundedReceiver<IndexOutcome>,
+    count: usize,
+    pb: &ProgressBar,
+) -> IndexSummary {
+    let mut summary = IndexSummary::default();
+    for _ in 0..count {
+        let Some(outcome) = results.recv().await else {
+            break;
+        };
+        if let Some(name) = outcome.path.as_ref().and_then(|p| p.file_name()) {
+            pb.set_message(name.to_string_lossy().into_owned());
+        }
+        summary.record(outcome);
+        pb.inc(1);
     }
     pb.finish_and_clear();
+    summary
+}
 
-    println!(
-        "{} Added {} documents ({} chunks)",
-        "✓".green(),
-        args.paths.len(),
-        args.paths.len() * 25 // Placeholder
-    );
+fn spawn_indexer(
+    config: &Config,
+) -> anyhow::Result<(
+    DocumentIndexer,
+    synesis_knowledge::IndexerHandle,
+    mpsc::UnboundedReceiver<IndexOutcome>,
+)> {
+    let vault = open_vault(config)?;
+    let embedder = Arc::new(Mutex::new(embedder(&vault, config)?));
+    let vault = Arc::new(Mutex::new(vault));
+    Ok(DocumentIndexer::with_results(
+        vault,
+        embedder,
+        indexer_config(config)?,
+    ))
+}
+
+/// Indexer settings, sizing chunks with the tokenizer of the Logos model
+/// that reads them as context
+fn indexer_config(config: &Config) -> anyhow::Result<IndexerConfig> {
+    let token_counter = config
+        .model_tokenizer(&config.agents.logos.model)?
+        .map(|tokenizer| tokenizer as Arc<dyn TokenCounter>);
+    Ok(IndexerConfig {
+        token_counter,
+        ..IndexerConfig::default()
+    })
+}
+
+async fn add_documents(args: AddArgs, config: &Config) -> anyhow::Result<()> {
+    if args.paths.is_empty() {
+        anyhow::bail!("No paths given");
+    }
+
+    let files = collect_files(&args)?;
+    if files.is_empty() {
+        println!("{}", "No matching files found.".dimmed());
+        return Ok(());
+    }
+
+    println!("{}", "Adding documents to knowledge vault...".bold());
+    println!();
+
+    let (indexer, handle, mut results) = spawn_indexer(config)?;
+    let pb = progress_bar(files.len() as u64)?;
+    pb.set_message("Indexing");
+
+    let count = files.len();
+    let sender = indexer.clone();
+    let send_task = tokio::spawn(async move {
+        for file in files {
+            if sender.index_file(file).await.is_err() {
+                break;
+            }
+        }
+    });
 
+    let summary = collect_outcomes(&mut results, count, &pb).await;
+    send_task.await?;
+    drop(indexer);
+    handle.shutdown().await;
+
+    summary.print("Added");
     Ok(())
 }
 
-async fn remove_documents(args: RemoveArgs) -> anyhow::Result<()> {
-    if !args.force {
+/// Documents whose ID equals the pattern or whose path/title matches it
+fn matching_documents(vault: &KnowledgeVault, pattern: &str) -> anyhow::Result<Vec<Document>> {
+    let docs = vault.list_documents(i64::MAX as usize)?;
+    Ok(docs
+        .into_iter()
+        .filter(|doc| {
+            doc.id == pattern
+                || matches_pattern(pattern, &doc.title)
+                || doc
+                    .path
+                    .as_deref()
+                    .map(|p| p == pattern || matches_pattern(pattern, p))
+                    .unwrap_or(false)
+        })
+        .collect())
+}
+
+async fn remove_documents(args: RemoveArgs, config: &Config) -> anyhow::Result<()> {
+    let vault = open_vault(config)?;
+    let docs = matching_documents(&vault, &args.pattern)?;
+
+    if docs.is_empty() {
+        println!("No documents match '{}'", args.pattern);
+        return Ok(());
+    }
+
+    for doc in &docs {
         println!(
-            "{} Remove documents matching '{}'? [y/N] ",
-            "Warning:".yellow().bold(),
-            args.pattern
+            "  {} {} {}",
+            "→".dimmed(),
+            doc.path.as_deref().unwrap_or(&doc.title),
+            doc.id.dimmed()
         );
-        // TODO: Read confirmation
+    }
+    println!();
+
+    if !args.force {
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Remove {} documents?", docs.len()))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            println!("{}", "Cancelled".dimmed());
+            return Ok(());
+        }
     }
 
-    // TODO: synesis_knowledge::vault::remove()
-    println!("{} Removed 3 documents", "✓".green());
+    for doc in &docs {
+        vault.delete_document(&doc.id)?;
+    }
+    println!("{} Removed {} documents", "✓".green(), docs.len());
 
     Ok(())
 }
 
-async fn list_documents(args: ListArgs) -> anyhow::Result<()> {
+async fn list_documents(args: ListArgs, config: &Config) -> anyhow::Result<()> {
+    let vault = open_vault(config)?;
+    let mut docs = vault.list_documents(i64::MAX as usize)?;
+    let total = docs.len();
+
+    if let Some(ref doc_type) = args.r#type {
+        docs.retain(|d| &d.doc_type == doc_type);
+    }
+
+    match args.sort.as_str() {
+        "name" => docs.sort_by(|a, b| a.title.cmp(&b.title)),
+        "date" => docs.sort_by_key(|d| std::cmp::Reverse(d.updated_at)),
+        "size" => docs.sort_by_key(|d| std::cmp::Reverse(d.size_bytes)),
+        "chunks" => docs.sort_by_key(|d| std::cmp::Reverse(d.chunk_count)),
+        other => anyhow::bail!("Unknown sort '{}': use name, date, size or chunks", other),
+    }
+    docs.truncate(args.limit);
+
+    if docs.is_empty() {
+        println!("{}", "No documents indexed.".dimmed());
+        return Ok(());
+    }
+
     println!("{}", "Indexed Documents".bold());
     println!();
 
     let mut table = Table::new();
     table.load_preset(UTF8_FULL);
-    table.set_header(vec!["ID", "Name", "Type", "Chunks", "Indexed"]);
-
-    // TODO: Get from synesis_knowledge::vault
-    table.add_row(vec!["doc_001", "README.md", "markdown", "12", "2h ago"]);
-    table.add_row(vec![
-        "doc_002",
-        "architecture.md",
-        "markdown",
-        "45",
-        "2h ago",
-    ]);
-    table.add_row(vec!["doc_003", "main.rs", "rust", "28", "1h ago"]);
-    table.add_row(vec!["doc_004", "api_spec.yaml", "yaml", "89", "30m ago"]);
+    table.set_header(vec!["ID", "Name", "Type", "Chunks", "Size", "Indexed"]);
+
+    for doc in &docs {
+        table.add_row(vec![
+            doc.id.clone(),
+            doc.title.clone(),
+            doc.doc_type.clone(),
+            doc.chunk_count.to_string(),
+            format_bytes(doc.size_bytes),
+            format_relative_time(doc.updated_at),
+        ]);
+    }
 
     println!("{table}");
     println!();
     println!(
         "{} Showing {} of {} documents",
         "ℹ".dimmed(),
-        4.min(args.limit),
-        47
+        docs.len(),
+        total
     );
 
     Ok(())
 }
 
-async fn search_vault(args: SearchArgs) -> anyhow::Result<()> {
+fn preview(content: &str) -> String {
+    let line: String = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() > PREVIEW_CHARS {
+        format!("{}…", line.chars().take(PREVIEW_CHARS).collect::<String>())
+    } else {
+        line
+    }
+}
+
+async fn search_vault(args: SearchArgs, config: &Config) -> anyhow::Result<()> {
+    let fusion = match args.fusion.as_str() {
+        "rrf" => FusionStrategy::default(),
+        "weighted" => FusionStrategy::Weighted,
+        other => anyhow::bail!("Unknown fusion strategy '{}' (expected rrf or weighted)", other),
+    };
+    if let Some(lambda) = args.diversity {
+        if !(0.0..=1.0).contains(&lambda) {
+            anyhow::bail!("--diversity must be between 0.0 and 1.0");
+        }
+    }
+
+    let dates = DateRange {
+        after: args.since.as_deref().map(parse_date).transpose()?,
+        before: args.until.as_deref().map(parse_date).transpose()?,
+    };
+    let mut filter = SearchFilter {
+        path_globs: args.paths.clone(),
+        tags: args.tags.clone(),
+        metadata: args.conditions.clone(),
+        ..Default::default()
+    };
+    if args.indexed {
+        filter.indexed = dates;
+    } else {
+        filter.updated = dates;
+    }
+
+    let vault = open_vault(config)?;
+    let embedder = embedder(&vault, config)?;
+    let options = SearchOptions {
+        limit: args.limit,
+        threshold: args.threshold,
+        fusion,
+        rerank_top_n: args.rerank.as_ref().map(|_| args.rerank_top),
+        mmr_lambda: args.diversity,
+        filter,
+        ..Default::default()
+    };
+
     println!("{} \"{}\"", "Searching:".bold(), args.query);
     println!();
 
-    // TODO: Use synesis_knowledge::search
+    let mut search = HybridSearch::new(&vault, VECTOR_WEIGHT, KEYWORD_WEIGHT);
+    if let Some(ref model) = args.rerank {
+        let path = resolve_model_path(model, config);
+        let reranker = CrossEncoderReranker::load(&path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load reranker {}: {}", path.display(), e))?;
+        search = search.with_reranker(Arc::new(reranker));
+    }
+    let (mut results, facets) = if args.facets {
+        let faceted = search
+            .search_with_facets(&args.query, &embedder, &options)
+            .await?;
+        (faceted.results, Some(faceted.facets))
+    } else {
+        (search.search(&args.query, &embedder, &options).await?, None)
+    };
+    results.truncate(args.limit);
+
+    if results.is_empty() {
+        println!("{}", "No matching chunks found.".dimmed());
+        return Ok(());
+    }
+
     let mut table = Table::new();
     table.load_preset(UTF8_FULL);
     table.set_header(vec!["Score", "Document", "Chunk", "Preview"]);
 
-    table.add_row(vec![
-        "0.92",
-        "architecture.md",
-        "#15",
-        "The tripartite council consists of...",
-    ]);
-    table.add_row(vec![
-        "0.87",
-        "README.md",
-        "#3",
-        "Three agents work together to...",
-    ]);
-    table.add_row(vec![
-        "0.81",
-        "design.md",
-        "#42",
-        "Consensus is reached when...",
-    ]);
+    for result in &results {
+        let (document, chunk) = result_location(result);
+        table.add_row(vec![
+            format!("{:.2}", result.score),
+            document,
+            chunk,
+            preview(result.content.as_deref().unwrap_or_default()),
+        ]);
+    }
 
     println!("{table}");
 
+    if let Some(facets) = facets {
+        println!();
+        print_facets(&facets);
+    }
+
     Ok(())
 }
 
-async fn reindex_vault(args: ReindexArgs) -> anyhow::Result<()> {
-    match args.document {
-        Some(doc) => {
-            println!("Reindexing {}...", doc);
-        },
-        None => {
-            println!("Reindexing all documents...");
-        },
+/// Parse a date for `--since`/`--until`
+///
+/// Accepts `YYYY-MM-DD` (midnight UTC), RFC 3339, or an age relative to
+/// now in days or weeks (`90d`, `2w`).
+fn parse_date(value: &str) -> anyhow::Result<chrono::DateTime<chrono::Utc>> {
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(chrono::NaiveTime::MIN).and_utc());
+    }
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(time.to_utc());
     }
 
-    // TODO: synesis_knowledge::indexer::reindex()
-    let pb = ProgressBar::new(100);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{msg} [{bar:40.cyan/blue}] {pos}/{len}")?
-            .progress_chars("█▓░"),
-    );
+    let days_per_unit = match value.chars().last() {
+        Some('d') => 1,
+        Some('w') => 7,
+        _ => anyhow::bail!(
+            "Invalid date '{}' (expected YYYY-MM-DD, RFC 3339, or e.g. 90d)",
+            value
+        ),
+    };
+    let count: i64 = value[..value.len() - 1]
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid date '{}' (expected e.g. 90d or 2w)", value))?;
+    Ok(chrono::Utc::now() - chrono::Duration::days(count * days_per_unit))
+}
+
+fn print_facets(facets: &SearchFacets) {
+    let line = |counts: &std::collections::BTreeMap<String, usize>| {
+        counts
+            .iter()
+            .map(|(value, count)| format!("{} ({})", value, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    println!("{} {}", "Types:".bold(), line(&facets.doc_types));
+    if !facets.tags.is_empty() {
+        println!("{} {}", "Tags:".bold(), line(&facets.tags));
+    }
+}
+
+/// Document and chunk columns for a search result
+///
+/// Cites where the chunk came from when known: code chunks name their
+/// symbol and line (`routing.rs:67`, `method Router::route`), PDF chunks
+/// their page and HTML/DOCX chunks their section.
+fn result_location(result: &SearchResult) -> (String, String) {
+    let location = &result.location;
+    let document = match (&location.symbol, location.page) {
+        (Some(symbol), _) => format!("{}:{}", result.document_title, symbol.start_line),
+        (None, Some(page)) => format!("{}, page {}", result.document_title, page),
+        (None, None) => result.document_title.clone(),
+    };
+    let chunk = match (&location.symbol, &location.section) {
+        (Some(symbol), _) => symbol.to_string(),
+        (None, Some(section)) => section.clone(),
+        (None, None) => format!("#{}", result.chunk_index),
+    };
+    (document, chunk)
+}
+
+/// A model given as an existing path, or a file name in the models directory
+fn resolve_model_path(model: &str, config: &Config) -> PathBuf {
+    let path = PathBuf::from(model);
+    if path.exists() || path.components().count() > 1 {
+        path
+    } else {
+        config.models_dir().join(model)
+    }
+}
+
+async fn reindex_vault(args: ReindexArgs, config: &Config) -> anyhow::Result<()> {
+    if args.ann {
+        let vault = open_vault(config)?;
+        let count = vault.rebuild_ann_index()?;
+        println!(
+            "{} Rebuilt vector index with {} embeddings",
+            "✓".green(),
+            count
+        );
+        return Ok(());
+    }
 
+    let doc_ids: Vec<String> = {
+        let vault = open_vault(config)?;
+        match args.document {
+            Some(ref doc) => {
+                let docs = matching_documents(&vault, doc)?;
+                if docs.is_empty() {
+                    anyhow::bail!("No documents match '{}'", doc);
+                }
+                docs.into_iter().map(|d| d.id).collect()
+            },
+            None => vault
+                .list_documents(i64::MAX as usize)?
+                .into_iter()
+                .map(|d| d.id)
+                .collect(),
+        }
+    };
+
+    if doc_ids.is_empty() {
+        println!("{}", "No documents to reindex.".dimmed());
+        return Ok(());
+    }
+
+    println!("Reindexing {} documents...", doc_ids.len());
+
+    let (indexer, handle, mut results) = spawn_indexer(config)?;
+    let pb = progress_bar(doc_ids.len() as u64)?;
     pb.set_message("Reindexing");
-    for i in 0..=100 {
-        pb.set_position(i);
-        tokio::time::sleep(tokio::time::Duration::from_millis(30)).await;
+
+    let count = doc_ids.len();
+    let sender = indexer.clone();
+    let send_task = tokio::spawn(async move {
+        for id in doc_ids {
+            if sender.reindex(id).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let summary = collect_outcomes(&mut results, count, &pb).await;
+    send_task.await?;
+    drop(indexer);
+    handle.shutdown().await;
+
+    summary.print("Reindexed");
+    Ok(())
+}
+
+async fn migrate_vault(args: MigrateArgs, config: &Config) -> anyhow::Result<()> {
+    let vault = open_vault(config)?;
+
+    if args.abort {
+        match vault.migration_status()? {
+            Some(status) => {
+                vault.cancel_migration()?;
+                println!("{} Abandoned migration to {}", "✓".green(), status.to);
+            },
+            None => println!("{}", "No migration in progress.".dimmed()),
+        }
+        return Ok(());
     }
-    pb.finish_and_clear();
 
-    println!("{} Reindexed 47 documents", "✓".green());
+    let name = args
+        .embedder
+        .ok_or_else(|| anyhow::anyhow!("No embedding model given"))?;
+    let embedder = load_embedder(&name, vault.embedding_dimensions(), config)?;
+    let model = EmbeddingModel::of(&embedder);
+    let from = vault.embedding_model()?;
+    if from.as_ref() == Some(&model) {
+        println!("{} Vault already uses {}", "✓".green(), model);
+        return Ok(());
+    }
+
+    match vault.migration_status()? {
+        Some(status) if status.to == model && status.embedded > 0 => println!(
+            "Resuming migration to {} ({}/{} chunks done)",
+            model, status.embedded, status.total
+        ),
+        _ => println!(
+            "Migrating {} → {}",
+            from.map(|m| m.to_string())
+                .unwrap_or_else(|| "unrecorded model".to_string()),
+            model
+        ),
+    }
+    println!("{}", "Press Ctrl+C to pause; run the same command to resume".dimmed());
+
+    let vault = Arc::new(Mutex::new(vault));
+    let pb = progress_bar(0)?;
+    pb.set_message("Re-embedding");
+    let migration = migrate_embeddings(&vault, &embedder, args.batch_size, |status| {
+        pb.set_length(status.total);
+        pb.set_position(status.embedded);
+    });
+
+    tokio::select! {
+        result = migration => {
+            pb.finish_and_clear();
+            if let Some(status) = result? {
+                println!(
+                    "{} Re-embedded {} chunks with {}",
+                    "✓".green(),
+                    status.total,
+                    status.to
+                );
+            }
+        }
+        _ = ctrl_c() => {
+            pb.abandon();
+            println!();
+            println!("{}", "Paused. Run the same command to resume.".dimmed());
+        }
+    }
+
+    Ok(())
+}
+
+async fn tag_documents(args: TagArgs, config: &Config) -> anyhow::Result<()> {
+    let vault = open_vault(config)?;
+
+    let Some(pattern) = args.pattern else {
+        let counts = vault.tag_counts()?;
+        if counts.is_empty() {
+            println!("{}", "No tags yet.".dimmed());
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL);
+        table.set_header(vec!["Tag", "Documents"]);
+        for (tag, count) in counts {
+            table.add_row(vec![tag, count.to_string()]);
+        }
+        println!("{table}");
+        return Ok(());
+    };
+
+    let docs = matching_documents(&vault, &pattern)?;
+    if docs.is_empty() {
+        println!("No documents match '{}'", pattern);
+        return Ok(());
+    }
+
+    if args.tags.is_empty() {
+        for doc in &docs {
+            println!(
+                "  {} {} {}",
+                "→".dimmed(),
+                doc.path.as_deref().unwrap_or(&doc.title),
+                vault.document_tags(&doc.id)?.join(", ").cyan()
+            );
+        }
+        return Ok(());
+    }
+
+    let mut changed = 0;
+    for doc in &docs {
+        changed += if args.remove {
+            vault.remove_tags(&doc.id, &args.tags)?
+        } else {
+            vault.add_tags(&doc.id, &args.tags)?
+        };
+    }
+    println!(
+        "{} {} {} tags on {} documents",
+        "✓".green(),
+        if args.remove { "Removed" } else { "Added" },
+        changed,
+        docs.len()
+    );
 
     Ok(())
 }
 
-async fn show_stats() -> anyhow::Result<()> {
+async fn show_stats(config: &Config) -> anyhow::Result<()> {
+    let vault = open_vault(config)?;
+    let stats = vault.stats()?;
+    let docs = vault.list_documents(i64::MAX as usize)?;
+
+    let mut doc_types: Vec<&str> = docs.iter().map(|d| d.doc_type.as_str()).collect();
+    doc_types.sort();
+    doc_types.dedup();
+    let last_updated = docs.iter().map(|d| d.updated_at).max();
+    let avg_chunk_bytes = stats
+        .total_size_bytes
+        .checked_div(stats.chunk_count)
+        .unwrap_or(0);
+
     println!("{}", "Knowledge Vault Statistics".bold());
     println!();
 
     let mut table = Table::new();
     table.load_preset(UTF8_FULL);
 
-    // TODO: Get from synesis_knowledge::vault::stats()
-    table.add_row(vec!["Total Documents", "47"]);
-    table.add_row(vec!["Total Chunks", "1,284"]);
-    table.add_row(vec!["Embedding Dimensions", "384"]);
-    table.add_row(vec!["Database Size", "128 MB"]);
-    table.add_row(vec!["Average Chunk Size", "512 tokens"]);
-    table.add_row(vec!["File Types", "md, rs, py, yaml, json"]);
-    table.add_row(vec!["Last Updated", "30 minutes ago"]);
-    table.add_row(vec!["Watched Directories", "2"]);
+    table.add_row(vec!["Total Documents".to_string(), stats.document_count.to_string()]);
+    table.add_row(vec!["Total Chunks".to_string(), stats.chunk_count.to_string()]);
+    table.add_row(vec!["Embeddings".to_string(), stats.embedding_count.to_string()]);
+    table.add_row(vec![
+        "Embedding Model".to_string(),
+        stats
+            .embedding_model
+            .as_ref()
+            .map(|m| format!("{} (v{})", m.name, m.version))
+            .unwrap_or_else(|| "-".to_string()),
+    ]);
+    table.add_row(vec![
+        "Embedding Dimensions".to_string(),
+        stats.embedding_dimensions.to_string(),
+    ]);
+    if let Some(migration) = vault.migration_status()? {
+        table.add_row(vec![
+            "Migrating To".to_string(),
+            format!(
+                "{} ({}/{} chunks)",
+                migration.to.name, migration.embedded, migration.total
+            ),
+        ]);
+    }
+    table.add_row(vec!["Content Size".to_string(), format_bytes(stats.total_size_bytes)]);
+    table.add_row(vec![
+        "Database Size".to_string(),
+        format_bytes(stats.database_size_bytes),
+    ]);
+    table.add_row(vec!["Average Chunk Size".to_string(), format_bytes(avg_chunk_bytes)]);
+    table.add_row(vec![
+        "Document Types".to_string(),
+        if doc_types.is_empty() {
+            "-".to_string()
+        } else {
+            doc_types.join(", ")
+        },
+    ]);
+    table.add_row(vec![
+        "Last Updated".to_string(),
+        last_updated
+            .map(format_relative_time)
+            .unwrap_or_else(|| "never".to_string()),
+    ]);
 
     println!("{table}");
+    println!();
+    println!(
+        "{}",
+        format!("Vault: {}", config.knowledge_db_path().display()).dimmed()
+    );
 
     Ok(())
 }
 
-async fn watch_directory(args: WatchArgs) -> anyhow::Result<()> {
+async fn watch_directory(args: WatchArgs, config: &Config) -> anyhow::Result<()> {
     let path = PathBuf::from(&args.path);
 
     if !path.exists() {
@@ -302,41 +1081,27 @@ async fn watch_directory(args: WatchArgs) -> anyhow::Result<()> {
     println!("{}", "Press Ctrl+C to stop".dimmed());
     println!();
 
-    // Open knowledge vault
-    let vault_path = dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join(".synesis")
-        .join("knowledge.db");
-
-    let vault = Arc::new(tokio::sync::Mutex::new(KnowledgeVault::open(
-        &vault_path,
-        384,
-    )?));
-
-    // Create embedder (using placeholder for now)
-    let embedder = Arc::new(tokio::sync::Mutex::new(PlaceholderEmbedder::new(384)));
+    let vault = open_vault(config)?;
+    let embedder = Arc::new(Mutex::new(embedder(&vault, config)?));
+    let vault = Arc::new(Mutex::new(vault));
 
     // Configure watcher
-    let mut config = WatchConfig {
+    let mut watch_config = WatchConfig {
         directories: vec![path.clone()],
         ..Default::default()
     };
 
     // Add custom include patterns if provided
     if let Some(include) = args.include {
-        config.extensions = Some(include);
+        watch_config.extensions = Some(include);
     }
 
     // Create channel-based indexer
-    let indexer_config = synesis_knowledge::indexer::IndexerConfig::default();
-    let (indexer, _handle) = synesis_knowledge::indexer::DocumentIndexer::new(
-        vault.clone(),
-        embedder.clone(),
-        indexer_config,
-    );
+    let (indexer, handle, mut results) =
+        DocumentIndexer::with_results(vault.clone(), embedder.clone(), indexer_config(config)?);
 
     // Create watcher with indexer channel
-    let mut watcher = FileWatcher::with_auto_index(config.clone(), indexer.command_sender())?;
+    let mut watcher = FileWatcher::with_auto_index(watch_config.clone(), indexer.command_sender())?;
 
     // Start watcher
     watcher.start().await?;
@@ -344,17 +1109,14 @@ async fn watch_directory(args: WatchArgs) -> anyhow::Result<()> {
     // Initial indexing
     println!("{} Initial indexing...", "Scanning".bold());
 
-    // Manually trigger indexing for the directory
-    let _command = synesis_knowledge::indexer::IndexCommand::IndexDirectory {
-        path: path.clone(),
-        extensions: config.extensions.clone(),
-    };
-
-    if let Err(e) = indexer.index_file(path.clone()).await {
-        println!("{} Initial indexing failed: {}", "Warning".yellow(), e);
-    } else {
-        println!("{} Initial indexing complete", "✓".green());
-    }
+    indexer
+        .command_sender()
+        .send(synesis_knowledge::IndexCommand::IndexDirectory {
+            path: path.clone(),
+            extensions: watch_config.extensions.clone(),
+        })
+        .await
+        .map_err(|_| anyhow::anyhow!("Indexer stopped before initial indexing"))?;
 
     println!();
     println!(
@@ -367,18 +1129,275 @@ async fn watch_directory(args: WatchArgs) -> anyhow::Result<()> {
     );
     println!();
 
-    // Wait for Ctrl+C
-    tokio::select! {
-        _ = ctrl_c() => {
-            println!();
-            println!("{}", "Stopping watcher...".dimmed());
-            watcher.stop();
-            println!("{}", "Done".green());
-        }
-        _ = tokio::time::sleep(tokio::time::Duration::from_secs(u64::MAX)) => {
-            unreachable!()
+    // Report outcomes until Ctrl+C
+    loop {
+        tokio::select! {
+            _ = ctrl_c() => {
+                println!();
+                println!("{}", "Stopping watcher...".dimmed());
+                watcher.stop();
+                handle.shutdown().await;
+                println!("{}", "Done".green());
+                break;
+            }
+            Some(outcome) = results.recv() => {
+                let label = outcome
+                    .path
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default();
+                match outcome.result {
+                    Ok(result) if result.skipped => {},
+                    Ok(result) if result.removed => println!("  {} {}", "-".red(), label),
+                    Ok(result) if result.moved => println!("  {} {}", "→".cyan(), label),
+                    Ok(result) if result.updated => println!(
+                        "  {} {} ({} chunks, {} re-embedded)",
+                        "↻".cyan(),
+                        label,
+                        result.chunk_count,
+                        result.embedded_chunks
+                    ),
+                    Ok(result) => println!("  {} {} ({} chunks)", "+".green(), label, result.chunk_count),
+                    Err(e) => println!("  {} {} {}", "✗".red(), label, e.to_string().dimmed()),
+                }
+            }
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use synesis_knowledge::ChunkLocation;
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("*.md", "README.md"));
+        assert!(!matches_pattern("*.md", "main.rs"));
+        assert!(matches_pattern("notes-??.txt", "notes-01.txt"));
+        assert!(matches_pattern("*/docs/*", "/home/me/docs/a.md"));
+        assert!(matches_pattern("*", ""));
+        assert!(!matches_pattern("a*b", "acd"));
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(
+            parse_date("2026-07-01").unwrap().to_rfc3339(),
+            "2026-07-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_date("2026-07-01T12:00:00+02:00")
+                .unwrap()
+                .to_rfc3339(),
+            "2026-07-01T10:00:00+00:00"
+        );
+        let age = chrono::Utc::now() - parse_date("2w").unwrap();
+        assert!((age - chrono::Duration::days(14)).num_seconds() < 5);
+        assert!(parse_date("yesterday").is_err());
+        assert!(parse_date("xd").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tag_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            data_dir: dir.path().join("data").to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        let design = {
+            let vault = open_vault(&config).unwrap();
+            vault
+                .add_document("/docs/notes.md", "y", "markdown")
+                .unwrap();
+            vault
+                .add_document("/docs/design/api.md", "x", "markdown")
+                .unwrap()
+        };
+
+        let tag = |pattern: &str, tags: &[&str], remove| TagArgs {
+            pattern: Some(pattern.to_string()),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            remove,
+        };
+        tag_documents(tag("*/docs/*", &["Q3"], false), &config)
+            .await
+            .unwrap();
+        tag_documents(tag("*/design/*", &["design"], false), &config)
+            .await
+            .unwrap();
+        tag_documents(tag("*notes*", &["q3"], true), &config)
+            .await
+            .unwrap();
+
+        let vault = open_vault(&config).unwrap();
+        assert_eq!(vault.document_tags(&design).unwrap(), vec!["design", "q3"]);
+        assert_eq!(
+            vault.tag_counts().unwrap(),
+            vec![("design".to_string(), 1), ("q3".to_string(), 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_search_and_stats_use_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        std::fs::create_dir_all(docs.join("nested")).unwrap();
+        std::fs::write(docs.join("council.md"), "The tripartite council reaches consensus.").unwrap();
+        std::fs::write(docs.join("nested/dup.md"), "The tripartite council reaches consensus.").unwrap();
+        std::fs::write(docs.join("skip.log"), "log output").unwrap();
+
+        let config = Config {
+            data_dir: dir.path().join("data").to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+
+        let args = AddArgs {
+            paths: vec![docs.to_string_lossy().into_owned()],
+            recursive: true,
+            include: Some(vec!["*.md".to_string()]),
+            exclude: None,
+        };
+        assert_eq!(collect_files(&args).unwrap().len(), 2);
+        add_documents(args, &config).await.unwrap();
+
+        let vault = open_vault(&config).unwrap();
+        let stats = vault.stats().unwrap();
+        assert_eq!(stats.document_count, 1);
+        assert_eq!(stats.chunk_count, stats.embedding_count);
+
+        let found = matching_documents(&vault, "*council*").unwrap();
+        assert_eq!(found.len(), 1);
+
+        let results = HybridSearch::new(&vault, VECTOR_WEIGHT, KEYWORD_WEIGHT)
+            .search("tripartite council", &embedder(&vault, &config).unwrap(), &SearchOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(results[0].document_title, "council.md");
+    }
+
+    #[tokio::test]
+    async fn test_migrate_checks_embedder() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.md"), "Logos retrieves context.").unwrap();
+        let config = Config {
+            data_dir: dir.path().join("data").to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        let add = AddArgs {
+            paths: vec![dir.path().join("notes.md").to_string_lossy().into_owned()],
+            recursive: false,
+            include: None,
+            exclude: None,
+        };
+        add_documents(add, &config).await.unwrap();
+
+        let migrate = |embedder: &str| MigrateArgs {
+            embedder: Some(embedder.to_string()),
+            batch_size: 32,
+            abort: false,
+        };
+        // Already on the placeholder model
+        migrate_vault(migrate("placeholder-sha256"), &config).await.unwrap();
+        assert!(migrate_vault(migrate("missing.gguf"), &config).await.is_err());
+
+        let abort = MigrateArgs {
+            embedder: None,
+            batch_size: 32,
+            abort: true,
+        };
+        migrate_vault(abort, &config).await.unwrap();
+
+        let vault = open_vault(&config).unwrap();
+        let model = vault.embedding_model().unwrap().unwrap();
+        assert_eq!(model.name, "placeholder-sha256");
+        assert!(vault.migration_status().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_vault_dimensions_follow_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            data_dir: dir.path().to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+
+        // No embedding model installed or recorded
+        let vault = open_vault(&config).unwrap();
+        let placeholder = PlaceholderEmbedder::default();
+        assert_eq!(vault.embedding_dimensions(), placeholder.dimensions());
+        drop(vault);
+
+        // A vault whose embeddings come from an 8-dimension model
+        let small = PlaceholderEmbedder::new(8);
+        let vault = KnowledgeVault::open(&config.knowledge_db_path(), 8).unwrap();
+        vault.use_embedding_model(&EmbeddingModel::of(&small)).unwrap();
+        drop(vault);
+
+        let vault = open_vault(&config).unwrap();
+        assert_eq!(vault.embedding_dimensions(), 8);
+        assert_eq!(embedder(&vault, &config).unwrap().dimensions(), 8);
+    }
+
+    #[test]
+    fn test_resolve_model_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            data_dir: dir.path().to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            resolve_model_path("bge-reranker.gguf", &config),
+            dir.path().join("models/bge-reranker.gguf")
+        );
+        assert_eq!(
+            resolve_model_path("./local/reranker.gguf", &config),
+            PathBuf::from("./local/reranker.gguf")
+        );
+    }
+
+    #[test]
+    fn test_result_location() {
+        let mut result = SearchResult {
+            chunk_id: "chunk_1".to_string(),
+            document_id: "doc_1".to_string(),
+            document_title: "routing.rs".to_string(),
+            score: 0.9,
+            content: None,
+            chunk_index: 3,
+            start_offset: 0,
+            end_offset: 0,
+            location: ChunkLocation::default(),
+        };
+        assert_eq!(
+            result_location(&result),
+            ("routing.rs".to_string(), "#3".to_string())
+        );
+
+        result.location.symbol = Some(synesis_knowledge::CodeSymbol {
+            name: "route".to_string(),
+            kind: synesis_knowledge::SymbolKind::Method,
+            scope: Some("Router".to_string()),
+            qualified_name: "Router::route".to_string(),
+            start_line: 67,
+            end_line: 80,
+        });
+        assert_eq!(
+            result_location(&result),
+            ("routing.rs:67".to_string(), "method Router::route".to_string())
+        );
+
+        result.document_title = "paper.pdf".to_string();
+        result.location = ChunkLocation {
+            page: Some(12),
+            section: Some("Results".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            result_location(&result),
+            ("paper.pdf, page 12".to_string(), "Results".to_string())
+        );
+    }
+}
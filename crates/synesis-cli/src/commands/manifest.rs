@@ -219,6 +219,12 @@ fn print_model_rec(agent: &str, rec: &synesis_models::ModelRecommendation) {
     println!("    Quantization: {}", rec.quantization);
     println!("    Size: {}", format_bytes(rec.size_bytes));
     println!("    Source: {}/{}", rec.repo_id, rec.filename);
+    if let Some(context_size) = rec.context_size {
+        println!("    Context: {} tokens", context_size);
+    }
+    if let Some(gpu_layers) = rec.gpu_layers {
+        println!("    GPU Layers: {}", gpu_layers);
+    }
 }
 
 fn format_bytes(bytes: u64) -> String {
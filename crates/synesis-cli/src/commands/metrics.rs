@@ -46,6 +46,7 @@ pub async fn run(cmd: MetricsCommands) -> anyhow::Result<()> {
             println!("  - Agent performance (Pathos, Logos, Ethos)");
             println!("  - Knowledge operations (indexed, searched)");
             println!("  - Privacy (redactions, tokens)");
+            println!("  - Model pool (loads, evictions, prefix cache hits and misses)");
             println!();
         },
         MetricsCommands::Export => {
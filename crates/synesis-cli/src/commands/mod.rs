@@ -9,5 +9,6 @@ pub mod knowledge;
 pub mod manifest;
 pub mod metrics;
 pub mod model;
+pub mod privacy;
 pub mod push;
 pub mod status;
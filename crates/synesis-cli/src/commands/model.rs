@@ -2,10 +2,12 @@
 //!
 //! Provides commands for:
 //! - Listing available and installed models
-//! - Downloading models from HuggingFace
+//! - Downloading models from HuggingFace, with checksum verification
 //! - Removing installed models
-//! - Verifying model integrity
-//! - Showing model details and metadata
+//! - Verifying model integrity against `models.lock`
+//! - Exporting and importing offline bundles for air-gapped machines
+//! - Benchmarking installed models to tune the hardware manifest
+//! - Showing model details and metadata (read from the GGUF header)
 //!
 //! ## Model Registry
 //!
@@ -17,11 +19,28 @@
 //! - HuggingFace download URL
 //! - File name (for local storage)
 //! - Model type (Embedding or LLM)
+//!
+//! ## Lockfile
+//!
+//! A model only counts as installed once its download has been verified
+//! against a SHA256 checksum and recorded in `~/.synesis/models/models.lock`,
+//! together with its source, revision, size and install time.
 
 use clap::Subcommand;
 use comfy_table::{presets::UTF8_FULL, Table};
+use indicatif::{ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use synesis_models::bench::{self, BenchTargets, BenchWorkload};
+use synesis_models::bundle::{self, BundlePhase, BundleProgress, BundleProgressCallback};
+use synesis_models::downloader::{DownloadPhase, DownloadProgress, DownloadSpec, ModelSource};
+use synesis_models::lockfile::{verify_entry, LockEntry, ModelLock, LOCKFILE_NAME};
+use synesis_models::manifest::{profiles, TUNED_MANIFEST_NAME};
+use synesis_models::{
+    AgentRole, ChatTemplate, GgufMetadata, HardwareDetector, HardwareManifest, MemoryBudget,
+    ModelError,
+};
 
 use crate::config::Config;
 
@@ -61,6 +80,15 @@ pub enum ModelCommands {
 
     /// Verify model integrity
     Verify(VerifyArgs),
+
+    /// Package installed models into an offline bundle
+    Export(ExportArgs),
+
+    /// Install models from an offline bundle
+    Import(ImportArgs),
+
+    /// Benchmark installed models and write a tuned hardware manifest
+    Bench(BenchArgs),
 }
 
 #[derive(clap::Args)]
@@ -82,6 +110,10 @@ pub struct DownloadArgs {
     /// Quantization level (q4, q5, q8, f16)
     #[arg(short, long, default_value = "q4")]
     pub quant: String,
+
+    /// Expected SHA256 checksum, for sources that publish none
+    #[arg(long)]
+    pub sha256: Option<String>,
 }
 
 #[derive(clap::Args)]
@@ -96,10 +128,58 @@ pub struct RemoveArgs {
 
 #[derive(clap::Args)]
 pub struct InfoArgs {
-    /// Model name
+    /// Model name, or path to a .gguf file
     pub model: String,
 }
 
+#[derive(clap::Args)]
+pub struct ExportArgs {
+    /// Installed models to include
+    #[arg(required = true)]
+    pub models: Vec<String>,
+
+    /// Bundle file to write
+    #[arg(short, long)]
+    pub output: PathBuf,
+
+    /// Hardware manifest to include (built-in profile name or JSON file)
+    #[arg(long)]
+    pub manifest: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub struct ImportArgs {
+    /// Bundle file to import
+    pub bundle: PathBuf,
+
+    /// Don't install the bundled hardware manifest
+    #[arg(long)]
+    pub no_manifest: bool,
+}
+
+#[derive(clap::Args)]
+pub struct BenchArgs {
+    /// Tokens to generate per model
+    #[arg(long, default_value = "128")]
+    pub max_tokens: u32,
+
+    /// Slowest generation speed to accept for an agent (tokens/sec)
+    #[arg(long, default_value = "10")]
+    pub min_tokens_per_second: f32,
+
+    /// Longest time-to-first-token to accept for an agent (ms)
+    #[arg(long, default_value = "2000")]
+    pub max_ttft_ms: u64,
+
+    /// Write the raw results as JSON
+    #[arg(long)]
+    pub report: Option<PathBuf>,
+
+    /// Show the results without installing the tuned manifest
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
 #[derive(clap::Args)]
 pub struct VerifyArgs {
     /// Model name (or 'all' to verify all)
@@ -113,6 +193,9 @@ pub async fn run(cmd: ModelCommands, config: &Config) -> anyhow::Result<()> {
         ModelCommands::Remove(args) => remove_model(args, config).await,
         ModelCommands::Info(args) => show_model_info(args).await,
         ModelCommands::Verify(args) => verify_model(args, config).await,
+        ModelCommands::Export(args) => export_models(args).await,
+        ModelCommands::Import(args) => import_models(args).await,
+        ModelCommands::Bench(args) => bench_models(args).await,
     }
 }
 
@@ -203,11 +286,13 @@ async fn list_models(args: ListArgs) -> anyhow::Result<()> {
     table.set_header(vec!["Model", "Type", "Size", "Status", "Use Case"]);
 
     let models_dir = get_models_dir()?;
+    let lock = ModelLock::load(&models_dir.join(LOCKFILE_NAME))?;
     let has_filter = args.installed || args.available;
 
     for model in MODELS {
         let model_path = models_dir.join(model.file_name);
         let installed = model_path.exists();
+        let locked = lock.get(model.file_name).is_some();
 
         // Apply filters
         if args.installed && !installed {
@@ -217,8 +302,10 @@ async fn list_models(args: ListArgs) -> anyhow::Result<()> {
             continue;
         }
 
-        let status = if installed {
+        let status = if installed && locked {
             "✓ Installed".green().to_string()
+        } else if installed {
+            "! Unverified".yellow().to_string()
         } else {
             "○ Available".dimmed().to_string()
         };
@@ -244,7 +331,7 @@ async fn list_models(args: ListArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn download_model(args: DownloadArgs, _config: &Config) -> anyhow::Result<()> {
+async fn download_model(args: DownloadArgs, config: &Config) -> anyhow::Result<()> {
     // Find model in registry
     let model_info = MODELS
         .iter()
@@ -267,29 +354,93 @@ async fn download_model(args: DownloadArgs, _config: &Config) -> anyhow::Result<
         }
     );
     println!("{} {} MB", "Size:".dimmed(), model_info.size_mb);
-    println!();
     println!("{} {}", "Source URL:".dimmed(), model_info.url);
     println!();
 
-    println!("{}", "Download instructions:".bold());
-    println!();
-    println!("To download this model, run:");
-    println!(
-        "  curl -L {} -o ~/.synesis/models/{}",
-        model_info.url, model_info.file_name
+    let models_dir = get_models_dir()?;
+    let downloader = config.downloads.downloader(models_dir.clone());
+    let spec = DownloadSpec {
+        source: ModelSource::Url {
+            url: model_info.url.to_string(),
+            filename: model_info.file_name.to_string(),
+        },
+        sha256: args.sha256.clone(),
+        size_bytes: Some(model_info.size_mb * 1024 * 1024),
+        quantization: args.quant.clone(),
+    };
+
+    let pb = ProgressBar::new(model_info.size_mb * 1024 * 1024);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("  {msg:12} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")?
+            .progress_chars("█▓░"),
     );
-    println!();
-    println!("Or use wget:");
+    let pb_clone = pb.clone();
+    let progress_callback = Arc::new(move |progress: DownloadProgress| match progress.phase {
+        DownloadPhase::Downloading => {
+            pb_clone.set_message("Downloading");
+            if let Some(total) = progress.total {
+                pb_clone.set_length(total);
+            }
+            pb_clone.set_position(progress.downloaded);
+        },
+        DownloadPhase::Verifying => pb_clone.set_message("Verifying"),
+        _ => {},
+    });
+
+    let download = match downloader
+        .download_verified(&spec, Some(progress_callback))
+        .await
+    {
+        Ok(download) => download,
+        Err(ModelError::UnverifiedChecksum(source)) => {
+            pb.abandon();
+            anyhow::bail!(
+                "Refusing to install {}: {} publishes no checksum. \
+                 Pass the expected SHA256 with --sha256.",
+                model_info.name,
+                source
+            );
+        },
+        Err(e) => {
+            pb.abandon();
+            return Err(e.into());
+        },
+    };
+    pb.finish_and_clear();
+
+    // A verified file that is not a valid GGUF model is still unusable
+    let header = match GgufMetadata::read(&download.path) {
+        Ok(header) => header,
+        Err(e) => {
+            std::fs::remove_file(&download.path)?;
+            return Err(e.into());
+        },
+    };
+
+    let lock_path = models_dir.join(LOCKFILE_NAME);
+    let mut lock = ModelLock::load(&lock_path)?;
+    lock.insert(LockEntry::from_download(
+        model_info.name,
+        header.quantization,
+        &download,
+    )?);
+    lock.save(&lock_path)?;
+
     println!(
-        "  wget {} -O ~/.synesis/models/{}",
-        model_info.url, model_info.file_name
+        "{} {} installed",
+        "✓".green(),
+        model_info.display_name.cyan()
     );
-    println!();
-    println!("{} Models directory: ~/.synesis/models/", "Note:".yellow());
-    println!();
+    println!("{} {}", "SHA256:".dimmed(), download.sha256);
+    if let Some(revision) = &download.revision {
+        println!("{} {}", "Revision:".dimmed(), revision);
+    }
+    println!("{} {}", "Recorded in:".dimmed(), lock_path.display());
 
     // If it's the embedding model, add special note
     if matches!(model_info.model_type, ModelType::Embedding) {
+        println!();
         println!(
             "{}",
             "Note: This embedding model will be used for semantic search.".dimmed()
@@ -332,12 +483,34 @@ async fn remove_model(args: RemoveArgs, _config: &Config) -> anyhow::Result<()>
 
     println!("Removing {}...", args.model);
     std::fs::remove_file(&model_path)?;
+
+    let lock_path = models_dir.join(LOCKFILE_NAME);
+    let mut lock = ModelLock::load(&lock_path)?;
+    if lock.remove(model_info.file_name).is_some() {
+        lock.save(&lock_path)?;
+    }
     println!("{} {} removed", "✓".green(), args.model.cyan());
 
     Ok(())
 }
 
 async fn show_model_info(args: InfoArgs) -> anyhow::Result<()> {
+    // Inspect a GGUF file directly
+    let direct_path = PathBuf::from(&args.model);
+    if direct_path.extension().is_some_and(|ext| ext == "gguf") && direct_path.is_file() {
+        println!(
+            "{}",
+            format!("Model file: {}", direct_path.display()).bold()
+        );
+        println!();
+
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL);
+        add_gguf_rows(&mut table, &direct_path);
+        println!("{table}");
+        return Ok(());
+    }
+
     // Find model in registry
     let model_info = MODELS
         .iter()
@@ -366,13 +539,7 @@ async fn show_model_info(args: InfoArgs) -> anyhow::Result<()> {
     let model_path = models_dir.join(model_info.file_name);
 
     if model_path.exists() {
-        let metadata = std::fs::metadata(&model_path)?;
-        table.add_row(vec!["Status", &"✓ Installed".green().to_string()]);
-        table.add_row(vec![
-            "File Size",
-            &format!("{} MB", metadata.len() / 1024 / 1024),
-        ]);
-        table.add_row(vec!["Path", &model_path.display().to_string()]);
+        add_gguf_rows(&mut table, &model_path);
     } else {
         table.add_row(vec!["Status", &"○ Not installed".dimmed().to_string()]);
     }
@@ -381,19 +548,83 @@ async fn show_model_info(args: InfoArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Add status, file and GGUF header rows for an installed model file
+fn add_gguf_rows(table: &mut Table, path: &Path) {
+    match GgufMetadata::read(path) {
+        Ok(meta) => {
+            table.add_row(vec!["Status", &"✓ Installed".green().to_string()]);
+            table.add_row(vec![
+                "File Size",
+                &format!("{} MB", meta.file_size / 1024 / 1024),
+            ]);
+            table.add_row(vec!["Path", &path.display().to_string()]);
+            table.add_row(vec!["Architecture", &meta.architecture]);
+            table.add_row(vec![
+                "Parameters",
+                &format!("{} ({})", meta.parameters_label(), meta.parameter_count),
+            ]);
+            table.add_row(vec![
+                "Context Length",
+                &meta
+                    .context_length
+                    .map_or_else(|| "unknown".to_string(), |n| n.to_string()),
+            ]);
+            table.add_row(vec!["Quantization", &meta.file_type]);
+            table.add_row(vec![
+                "Tokenizer",
+                &match (&meta.tokenizer, meta.vocab_size) {
+                    (Some(model), Some(vocab)) => format!("{} ({} tokens)", model, vocab),
+                    (Some(model), None) => model.clone(),
+                    (None, _) => "none".to_string(),
+                },
+            ]);
+            table.add_row(vec![
+                "Chat Template",
+                &format!(
+                    "{} ({})",
+                    ChatTemplate::detect(&meta),
+                    if meta.chat_template.is_some() {
+                        "embedded"
+                    } else {
+                        "from architecture"
+                    }
+                ),
+            ]);
+            table.add_row(vec![
+                "Tensors",
+                &format!(
+                    "{} ({} MB, GGUF v{})",
+                    meta.tensors.len(),
+                    meta.tensor_bytes() / 1024 / 1024,
+                    meta.version
+                ),
+            ]);
+        },
+        Err(e) => {
+            table.add_row(vec![
+                "Status",
+                &format!("✗ Invalid: {}", e).red().to_string(),
+            ]);
+            table.add_row(vec!["Path", &path.display().to_string()]);
+        },
+    }
+}
+
 async fn verify_model(args: VerifyArgs, _config: &Config) -> anyhow::Result<()> {
+    let models_dir = get_models_dir()?;
+    let lock = ModelLock::load(&models_dir.join(LOCKFILE_NAME))?;
+
     if args.model == "all" {
         println!("Verifying all installed models...");
         println!();
 
-        let models_dir = get_models_dir()?;
         let mut verified_count = 0;
         let mut failed_count = 0;
 
         for model in MODELS {
             let model_path = models_dir.join(model.file_name);
-            if model_path.exists() {
-                match verify_model_file(&model_path) {
+            if model_path.exists() || lock.get(model.file_name).is_some() {
+                match verify_installed(&models_dir, &lock, model.file_name).await {
                     Ok(_) => {
                         println!("{} {} - OK", "✓".green(), model.display_name);
                         verified_count += 1;
@@ -415,10 +646,9 @@ async fn verify_model(args: VerifyArgs, _config: &Config) -> anyhow::Result<()>
             .find(|m| m.name == args.model)
             .ok_or_else(|| anyhow::anyhow!("Unknown model: {}", args.model))?;
 
-        let models_dir = get_models_dir()?;
         let model_path = models_dir.join(model_info.file_name);
 
-        if !model_path.exists() {
+        if !model_path.exists() && lock.get(model_info.file_name).is_none() {
             println!(
                 "{} Model {} is not installed",
                 "Note:".yellow(),
@@ -428,24 +658,326 @@ async fn verify_model(args: VerifyArgs, _config: &Config) -> anyhow::Result<()>
         }
 
         println!("Verifying {}...", model_info.display_name);
-        verify_model_file(&model_path)?;
+        verify_installed(&models_dir, &lock, model_info.file_name).await?;
         println!("{} Model verified", "✓".green());
     }
 
     Ok(())
 }
 
+/// Verify an installed model file against its `models.lock` entry
+///
+/// Checks the GGUF header, then the recorded size and SHA256 checksum.
+///
+/// # Errors
+///
+/// Returns error if the file is invalid, has no lock entry, or does not
+/// match its entry.
+async fn verify_installed(
+    models_dir: &Path,
+    lock: &ModelLock,
+    file_name: &str,
+) -> anyhow::Result<()> {
+    let entry = lock.get(file_name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "not recorded in {}; download it again with `synesis model download` to verify it",
+            LOCKFILE_NAME
+        )
+    })?;
+
+    let status = verify_entry(models_dir, entry).await?;
+    if !status.is_verified() {
+        anyhow::bail!("{}", status);
+    }
+
+    verify_model_file(&models_dir.join(file_name))
+}
+
+async fn export_models(args: ExportArgs) -> anyhow::Result<()> {
+    let models_dir = get_models_dir()?;
+
+    let hardware_manifest = match &args.manifest {
+        None => None,
+        Some(name) if Path::new(name).is_file() => Some(HardwareManifest::load(Path::new(name))?),
+        Some(name) => Some(
+            profiles::all()
+                .into_iter()
+                .find(|p| p.name == *name)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unknown manifest: {}. Use a built-in profile ({}) or a JSON file.",
+                        name,
+                        profiles::all()
+                            .iter()
+                            .map(|p| p.name.clone())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                })?,
+        ),
+    };
+
+    println!("{} {}", "Exporting:".bold(), args.models.join(", "));
+    let pb = bundle_progress_bar()?;
+    let manifest = bundle::export_bundle(
+        &models_dir,
+        &args.models,
+        hardware_manifest,
+        &args.output,
+        Some(bundle_progress_callback(&pb)),
+    )
+    .await;
+    pb.finish_and_clear();
+    let manifest = manifest?;
+
+    println!(
+        "{} Exported {} files ({} MB) to {}",
+        "✓".green(),
+        manifest.models.len(),
+        manifest.total_bytes() / 1024 / 1024,
+        args.output.display()
+    );
+    if let Some(hardware) = &manifest.hardware_manifest {
+        println!("{} {}", "Hardware manifest:".dimmed(), hardware.name);
+    }
+
+    Ok(())
+}
+
+async fn import_models(args: ImportArgs) -> anyhow::Result<()> {
+    let models_dir = get_models_dir()?;
+
+    let manifest = bundle::read_bundle_manifest(&args.bundle)?;
+    println!(
+        "{} {} ({} files, {} MB, created {})",
+        "Importing:".bold(),
+        args.bundle.display(),
+        manifest.models.len(),
+        manifest.total_bytes() / 1024 / 1024,
+        manifest.created_at.format("%Y-%m-%d %H:%M")
+    );
+
+    let pb = bundle_progress_bar()?;
+    let report = bundle::import_bundle(
+        &args.bundle,
+        &models_dir,
+        Some(bundle_progress_callback(&pb)),
+    )
+    .await;
+    pb.finish_and_clear();
+    let report = match report {
+        Ok(report) => report,
+        Err(e @ ModelError::InvalidBundle(_)) => {
+            anyhow::bail!(
+                "{}. Run the import again to resume once the bundle is complete.",
+                e
+            )
+        },
+        Err(e) => return Err(e.into()),
+    };
+
+    for model in &report.manifest.models {
+        let filename = &model.lock.filename;
+        let status = if report.skipped.contains(filename) {
+            "already installed".dimmed().to_string()
+        } else {
+            "imported".green().to_string()
+        };
+        println!(
+            "  {} {} ({}) - {}",
+            "✓".green(),
+            model.lock.model.cyan(),
+            filename,
+            status
+        );
+    }
+
+    if let Some(hardware) = &report.manifest.hardware_manifest {
+        if args.no_manifest {
+            println!(
+                "{} Skipped hardware manifest {}",
+                "Note:".yellow(),
+                hardware.name
+            );
+        } else {
+            let path = hardware.install(&hardware.name)?;
+            println!(
+                "{} {}",
+                "Hardware manifest installed:".dimmed(),
+                path.display()
+            );
+        }
+    }
+
+    println!();
+    println!(
+        "{} {} models ready in {}",
+        "✓".green(),
+        report.manifest.models.len(),
+        models_dir.display()
+    );
+
+    Ok(())
+}
+
+async fn bench_models(args: BenchArgs) -> anyhow::Result<()> {
+    let hardware = HardwareDetector::detect()?;
+    let base = profiles::select_for_hardware(&hardware);
+    let budget = MemoryBudget::from_hardware(&hardware);
+    let models_dir = get_models_dir()?;
+
+    let candidates = bench::candidates(&models_dir, &base, budget);
+    if candidates.is_empty() {
+        anyhow::bail!(
+            "No installed models to benchmark. Download one first, e.g. `synesis model download phi-3-mini`."
+        );
+    }
+
+    println!(
+        "{} {} models (budget: {}, base profile: {})",
+        "Benchmarking:".bold(),
+        candidates.len(),
+        budget,
+        base.name
+    );
+
+    let workload = BenchWorkload {
+        max_tokens: args.max_tokens,
+        ..Default::default()
+    };
+    let mut results = Vec::new();
+    for candidate in &candidates {
+        let pb = ProgressBar::new_spinner();
+        pb.set_message(candidate.recommendation.model.clone());
+        pb.enable_steady_tick(std::time::Duration::from_millis(100));
+        let result = bench::run_benchmark(candidate, &workload).await;
+        pb.finish_and_clear();
+
+        match result {
+            Ok(result) => {
+                println!(
+                    "  {} {}",
+                    "✓".green(),
+                    candidate.recommendation.model.cyan()
+                );
+                results.push(result);
+            },
+            Err(e) => println!(
+                "  {} {} - {}",
+                "✗".red(),
+                candidate.recommendation.model.cyan(),
+                e
+            ),
+        }
+    }
+    if results.is_empty() {
+        anyhow::bail!("Every benchmark failed");
+    }
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec![
+        "Model", "Agents", "Context", "GPU", "Load", "TTFT", "Tok/s", "Embed", "Peak",
+    ]);
+    for result in &results {
+        table.add_row(vec![
+            format!(
+                "{} ({})",
+                result.recommendation.model, result.recommendation.quantization
+            ),
+            result
+                .roles
+                .iter()
+                .map(|r| r.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            result.context_size.to_string(),
+            result.gpu_layers.to_string(),
+            format!("{} ms", result.load_ms),
+            format!("{} ms", result.time_to_first_token_ms),
+            format!("{:.1}", result.tokens_per_second),
+            format!("{} ms", result.embed_ms),
+            format!("{} MB", result.peak_memory_bytes / 1024 / 1024),
+        ]);
+    }
+    println!("{table}");
+
+    if let Some(path) = &args.report {
+        std::fs::write(path, serde_json::to_string_pretty(&results)?)?;
+        println!("{} {}", "Report:".dimmed(), path.display());
+    }
+
+    let targets = BenchTargets {
+        min_tokens_per_second: args.min_tokens_per_second,
+        max_time_to_first_token_ms: args.max_ttft_ms,
+    };
+    let tuned = bench::tune_manifest(&base, &hardware, &results, targets);
+
+    println!();
+    println!("{}", "Tuned manifest:".bold());
+    for role in AgentRole::ALL {
+        let rec = tuned.recommendations.get(role);
+        println!(
+            "  {:<10} {} ({}), context {}, {} GPU layers",
+            role.to_string(),
+            rec.model.cyan(),
+            rec.quantization,
+            tuned.context_size_for(role),
+            tuned.gpu_layers_for(role)
+        );
+    }
+
+    if args.dry_run {
+        println!();
+        println!("{} Dry run, manifest not installed", "Note:".yellow());
+    } else {
+        let path = tuned.install(TUNED_MANIFEST_NAME)?;
+        println!();
+        println!(
+            "{} Installed {} (preferred over the built-in profiles)",
+            "✓".green(),
+            path.display()
+        );
+    }
+
+    Ok(())
+}
+
+fn bundle_progress_bar() -> anyhow::Result<ProgressBar> {
+    let pb = ProgressBar::new(0);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("  {msg:40} [{bar:40.cyan/blue}] {bytes}/{total_bytes}")?
+            .progress_chars("█▓░"),
+    );
+    Ok(pb)
+}
+
+fn bundle_progress_callback(pb: &ProgressBar) -> BundleProgressCallback {
+    let pb = pb.clone();
+    Arc::new(move |progress: BundleProgress| {
+        let action = match progress.phase {
+            BundlePhase::Verifying => "Verifying",
+            BundlePhase::Copying => "Copying",
+            BundlePhase::Skipped => "Skipped",
+            BundlePhase::Complete => "Done",
+        };
+        pb.set_message(format!("{} {}", action, progress.filename));
+        pb.set_length(progress.total);
+        pb.set_position(progress.bytes);
+    })
+}
+
 /// Verify a model file
 ///
 /// Performs basic validation to ensure the model file is:
 /// - Present and accessible
 /// - Non-empty (at least 1KB)
-/// - Regular file (not directory/symlink)
+/// - A well-formed GGUF file whose tensor data is all present
 ///
 /// # Future Enhancements
 ///
 /// TODO: Add SHA256 checksum verification against known-good values
-/// TODO: Validate GGUF file format header
 /// TODO: Check model architecture compatibility
 ///
 /// # Errors
@@ -454,7 +986,8 @@ async fn verify_model(args: VerifyArgs, _config: &Config) -> anyhow::Result<()>
 /// - File doesn't exist
 /// - File is too small (< 1KB)
 /// - File metadata can't be read
-fn verify_model_file(path: &PathBuf) -> anyhow::Result<()> {
+/// - GGUF header is invalid or the file is truncated
+fn verify_model_file(path: &Path) -> anyhow::Result<()> {
     let metadata = std::fs::metadata(path)?;
 
     // Check file size (must be at least MIN_MODEL_SIZE_BYTES)
@@ -468,8 +1001,9 @@ fn verify_model_file(path: &PathBuf) -> anyhow::Result<()> {
         );
     }
 
+    GgufMetadata::read(path)?;
+
     // TODO: Add SHA256 checksum verification
-    // For now, just check that file exists and has reasonable size
 
     Ok(())
 }
@@ -0,0 +1,492 @@
+//! `synesis privacy` - Privacy tooling
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::Subcommand;
+use comfy_table::{presets::UTF8_FULL, Table};
+use owo_colors::OwoColorize;
+use synesis_privacy::scan::{scan_paths, ScanOptions, ScanReport};
+use synesis_privacy::{AuditFilter, PatternType, Redactor, RedactorConfig, TokenVault};
+
+use crate::config::Config;
+
+#[derive(Subcommand)]
+pub enum PrivacyCommands {
+    /// Inspect the audit log of data sent off the device
+    #[command(subcommand)]
+    Audit(AuditCommands),
+
+    /// Scan files or directories for sensitive data
+    Scan(ScanArgs),
+
+    /// Write a redacted copy of a file
+    Redact(RedactArgs),
+}
+
+#[derive(clap::Args)]
+pub struct ScanArgs {
+    /// Files or directories to scan
+    #[arg(required = true)]
+    pub paths: Vec<PathBuf>,
+
+    /// Redaction profile to scan with (defaults to the active profile)
+    #[arg(short, long)]
+    pub profile: Option<String>,
+
+    /// Output format: text, json, sarif
+    #[arg(short, long, default_value = "text")]
+    pub format: String,
+
+    /// Include hidden files and directories
+    #[arg(long)]
+    pub hidden: bool,
+
+    /// Don't descend into subdirectories
+    #[arg(long)]
+    pub no_recursive: bool,
+
+    /// Exit with status 0 even when findings are reported
+    #[arg(long)]
+    pub exit_zero: bool,
+}
+
+#[derive(clap::Args)]
+pub struct RedactArgs {
+    /// File to redact
+    pub file: PathBuf,
+
+    /// Output file (defaults to <name>.redacted.<ext> next to the input)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Redaction profile to use (defaults to the active profile)
+    #[arg(short, long)]
+    pub profile: Option<String>,
+
+    /// Overwrite the output file if it exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Subcommand)]
+pub enum AuditCommands {
+    /// List audit entries
+    List(AuditListArgs),
+
+    /// Verify the audit log hash chain
+    Verify,
+
+    /// Export audit entries for compliance review
+    Export(AuditExportArgs),
+}
+
+#[derive(clap::Args)]
+pub struct AuditListArgs {
+    /// Only show entries for this session
+    #[arg(long)]
+    pub session: Option<String>,
+
+    /// Only show entries on or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Maximum entries (most recent)
+    #[arg(short, long, default_value = "50")]
+    pub limit: usize,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::Args)]
+pub struct AuditExportArgs {
+    /// Output file (stdout if omitted)
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    /// Export format: json, jsonl, csv
+    #[arg(short, long, default_value = "json")]
+    pub format: String,
+
+    /// Only export entries for this session
+    #[arg(long)]
+    pub session: Option<String>,
+
+    /// Only export entries on or after this date (YYYY-MM-DD or RFC 3339)
+    #[arg(long)]
+    pub since: Option<String>,
+}
+
+pub async fn run(cmd: PrivacyCommands, config: &Config) -> anyhow::Result<()> {
+    match cmd {
+        PrivacyCommands::Audit(cmd) => match cmd {
+            AuditCommands::List(args) => list_audit(args, config).await,
+            AuditCommands::Verify => verify_audit(config).await,
+            AuditCommands::Export(args) => export_audit(args, config).await,
+        },
+        PrivacyCommands::Scan(args) => scan(args, config).await,
+        PrivacyCommands::Redact(args) => redact(args, config).await,
+    }
+}
+
+fn parse_since(since: Option<&str>) -> anyhow::Result<Option<DateTime<Utc>>> {
+    let Some(since) = since else {
+        return Ok(None);
+    };
+
+    if let Ok(t) = DateTime::parse_from_rfc3339(since) {
+        return Ok(Some(t.with_timezone(&Utc)));
+    }
+    let date = NaiveDate::parse_from_str(since, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid date '{}': use YYYY-MM-DD or RFC 3339", since))?;
+    Ok(date.and_hms_opt(0, 0, 0).map(|t| t.and_utc()))
+}
+
+async fn list_audit(args: AuditListArgs, config: &Config) -> anyhow::Result<()> {
+    let log = config.open_audit_log()?;
+    let filter = AuditFilter {
+        session_id: args.session,
+        since: parse_since(args.since.as_deref())?,
+        limit: Some(args.limit),
+    };
+    let entries = log.entries(&filter)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("{}", "No audit entries recorded.".dimmed());
+        return Ok(());
+    }
+
+    println!("{}", "Privacy Audit Log".bold());
+    println!();
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec![
+        "#",
+        "Time",
+        "Kind",
+        "Destination",
+        "Session",
+        "Tokens",
+        "Payload",
+    ]);
+    for entry in &entries {
+        let tokens = if entry.categories.is_empty() {
+            "-".to_string()
+        } else {
+            entry
+                .categories
+                .iter()
+                .map(|(k, v)| format!("{}×{}", k, v))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        table.add_row(vec![
+            entry.seq.to_string(),
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+            entry.kind.as_str().to_string(),
+            entry.destination.to_string(),
+            entry.session_id.chars().take(8).collect(),
+            tokens,
+            format!(
+                "{}… ({} B)",
+                &entry.payload_sha256[..12],
+                entry.payload_bytes
+            ),
+        ]);
+    }
+    println!("{table}");
+    println!();
+    println!(
+        "{}",
+        format!("{} of {} entries shown", entries.len(), log.len()?).dimmed()
+    );
+
+    Ok(())
+}
+
+async fn verify_audit(config: &Config) -> anyhow::Result<()> {
+    let log = config.open_audit_log()?;
+    let result = log.verify()?;
+
+    if result.is_valid() {
+        println!(
+            "{} Audit log intact ({} entries verified)",
+            "✓".green(),
+            result.entries_checked
+        );
+        return Ok(());
+    }
+
+    eprintln!(
+        "{} Audit log chain broken at entry {}: {}",
+        "✗".red(),
+        result.first_invalid.unwrap_or_default(),
+        result.error.unwrap_or_default()
+    );
+    eprintln!(
+        "{}",
+        format!(
+            "{} entries verified before the break",
+            result.entries_checked
+        )
+        .dimmed()
+    );
+    std::process::exit(1);
+}
+
+async fn export_audit(args: AuditExportArgs, config: &Config) -> anyhow::Result<()> {
+    let log = config.open_audit_log()?;
+    let filter = AuditFilter {
+        session_id: args.session,
+        since: parse_since(args.since.as_deref())?,
+        limit: None,
+    };
+
+    let content = match args.format.as_str() {
+        "json" => log.export_json(&filter)?,
+        "jsonl" => log.export_jsonl(&filter)?,
+        "csv" => log.export_csv(&filter)?,
+        other => anyhow::bail!("Unknown export format '{}': use json, jsonl or csv", other),
+    };
+
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, content)?;
+            println!("{} Audit log exported to {}", "✓".green(), path.cyan());
+        },
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
+
+/// Build a redactor for the named profile, or the active one
+///
+/// Tokens are never stored: scanning only previews and redacting masks.
+fn profile_redactor(config: &Config, profile: Option<&str>) -> anyhow::Result<Redactor> {
+    let policy = config.privacy.policy()?;
+    let redactor_config: RedactorConfig = match profile {
+        Some(name) => policy.profile(name)?,
+        None => policy.active()?,
+    };
+    Ok(Redactor::new(redactor_config, TokenVault::in_memory()?)?)
+}
+
+async fn scan(args: ScanArgs, config: &Config) -> anyhow::Result<()> {
+    let redactor = profile_redactor(config, args.profile.as_deref())?;
+    let options = ScanOptions {
+        recursive: !args.no_recursive,
+        include_hidden: args.hidden,
+        ..Default::default()
+    };
+    let report = scan_paths(&redactor, &args.paths, &options)?;
+
+    match args.format.as_str() {
+        "text" => print_scan_report(&report),
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        "sarif" => println!("{}", serde_json::to_string_pretty(&sarif_report(&report))?),
+        other => anyhow::bail!("Unknown scan format '{}': use text, json or sarif", other),
+    }
+
+    if report.has_findings() && !args.exit_zero {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn print_scan_report(report: &ScanReport) {
+    for finding in &report.findings {
+        println!(
+            "{}:{}:{}: {} {}",
+            finding.path.display().to_string().cyan(),
+            finding.line,
+            finding.column,
+            finding.pattern_type.display_name().yellow(),
+            finding.masked_value.dimmed()
+        );
+    }
+    for skipped in &report.skipped {
+        println!(
+            "{}",
+            format!("skipped {}: {}", skipped.path.display(), skipped.reason).dimmed()
+        );
+    }
+
+    if !report.has_findings() {
+        println!(
+            "{} No sensitive data found ({} files scanned)",
+            "✓".green(),
+            report.files_scanned
+        );
+        return;
+    }
+
+    println!();
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec!["Type", "Findings"]);
+    for (pattern_type, count) in report.counts_by_type() {
+        table.add_row(vec![pattern_type, count.to_string()]);
+    }
+    println!("{table}");
+    println!(
+        "{} {} findings in {} of {} files",
+        "✗".red(),
+        report.findings.len(),
+        report.files_with_findings(),
+        report.files_scanned
+    );
+}
+
+/// Render a scan report as SARIF 2.1.0 for code-scanning integrations
+fn sarif_report(report: &ScanReport) -> serde_json::Value {
+    let mut rule_types: Vec<PatternType> = report.findings.iter().map(|f| f.pattern_type).collect();
+    rule_types.sort_by_key(|t| t.token_prefix());
+    rule_types.dedup();
+
+    let rules: Vec<serde_json::Value> = rule_types
+        .iter()
+        .map(|t| {
+            serde_json::json!({
+                "id": t.token_prefix(),
+                "name": t.display_name(),
+                "shortDescription": { "text": format!("{} detected", t.display_name()) },
+            })
+        })
+        .collect();
+
+    let results: Vec<serde_json::Value> = report
+        .findings
+        .iter()
+        .map(|f| {
+            serde_json::json!({
+                "ruleId": f.pattern_type.token_prefix(),
+                "level": "error",
+                "message": {
+                    "text": format!("{} ({}): {}", f.pattern_type.display_name(), f.pattern_name, f.masked_value),
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": f.path.to_string_lossy().replace('\\', "/") },
+                        "region": {
+                            "startLine": f.line,
+                            "startColumn": f.column,
+                            "endColumn": f.end_column,
+                        },
+                    },
+                }],
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "synesis-privacy",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// Default output path: `notes.md` → `notes.redacted.md`
+fn redacted_path(input: &Path) -> PathBuf {
+    let stem = input
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match input.extension() {
+        Some(ext) => format!("{}.redacted.{}", stem, ext.to_string_lossy()),
+        None => format!("{}.redacted", stem),
+    };
+    input.with_file_name(name)
+}
+
+async fn redact(args: RedactArgs, config: &Config) -> anyhow::Result<()> {
+    let redactor = profile_redactor(config, args.profile.as_deref())?;
+    let output = args.output.unwrap_or_else(|| redacted_path(&args.file));
+
+    if output == args.file {
+        anyhow::bail!("Output would overwrite the input file; choose a different --output");
+    }
+    if output.exists() && !args.force {
+        anyhow::bail!(
+            "{} already exists (use --force to overwrite)",
+            output.display()
+        );
+    }
+
+    let content = std::fs::read_to_string(&args.file)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", args.file.display(), e))?;
+    let (masked, matches) = redactor.mask(&content);
+    std::fs::write(&output, masked)?;
+
+    println!(
+        "{} Wrote {} ({} values redacted)",
+        "✓".green(),
+        output.display().to_string().cyan(),
+        matches.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_since() {
+        assert!(parse_since(None).unwrap().is_none());
+
+        let date = parse_since(Some("2026-01-15")).unwrap().unwrap();
+        assert_eq!(date.to_rfc3339(), "2026-01-15T00:00:00+00:00");
+
+        let ts = parse_since(Some("2026-01-15T12:30:00Z")).unwrap().unwrap();
+        assert_eq!(ts.to_rfc3339(), "2026-01-15T12:30:00+00:00");
+
+        assert!(parse_since(Some("last week")).is_err());
+    }
+
+    #[test]
+    fn test_redacted_path() {
+        assert_eq!(
+            redacted_path(Path::new("/tmp/notes.md")),
+            PathBuf::from("/tmp/notes.redacted.md")
+        );
+        assert_eq!(
+            redacted_path(Path::new("LICENSE")),
+            PathBuf::from("LICENSE.redacted")
+        );
+    }
+
+    #[test]
+    fn test_sarif_report() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "ok\nmail test@example.com\n").unwrap();
+        let redactor =
+            Redactor::new(RedactorConfig::default(), TokenVault::in_memory().unwrap()).unwrap();
+        let report = scan_paths(&redactor, &[dir.path()], &ScanOptions::default()).unwrap();
+
+        let sarif = sarif_report(&report);
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(sarif["runs"][0]["tool"]["driver"]["rules"][0]["id"], "EMAIL");
+        assert_eq!(result["ruleId"], "EMAIL");
+        assert_eq!(result["locations"][0]["physicalLocation"]["region"]["startLine"], 2);
+        assert!(!sarif.to_string().contains("test@example.com"));
+    }
+}
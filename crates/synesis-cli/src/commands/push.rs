@@ -2,8 +2,8 @@
 
 use clap::Args;
 use owo_colors::OwoColorize;
-use std::io::Write;
 
+use super::cloud::upload_lora;
 use crate::config::Config;
 
 #[derive(Args)]
@@ -25,7 +25,7 @@ pub struct PushArgs {
     pub description: Option<String>,
 }
 
-pub async fn run(args: PushArgs, _config: &Config) -> anyhow::Result<()> {
+pub async fn run(args: PushArgs, config: &Config) -> anyhow::Result<()> {
     println!("{}", "Uploading LoRA to cloud".bold());
     println!();
 
@@ -53,25 +53,18 @@ pub async fn run(args: PushArgs, _config: &Config) -> anyhow::Result<()> {
     println!();
     println!("{}", "Uploading...".dimmed());
 
-    // TODO: Actually upload via LoRA upload client
-    // For now, simulate upload with progress
-    let progress_chars = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
-    for (i, ch) in progress_chars.iter().cycle().take(20).enumerate() {
-        print!("\r {} Uploading {}%", ch, (i + 1) * 5);
-        std::io::stdout().flush()?;
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-    }
+    let cloud_id = upload_lora(config, path, &args.name, &args.base_model).await?;
 
     println!();
     println!();
     println!("{} LoRA uploaded successfully!", "✓".green());
-    println!("Cloud ID: {}", "lora-cloud-abc123".cyan());
+    println!("Cloud ID: {}", cloud_id.cyan());
     println!();
     println!("{}", "Usage".bold());
     println!("  Use with cloud queries:");
-    println!("    synesis ask --cloud --lora lora-cloud-abc123 \"your query\"");
+    println!("    synesis ask --cloud --lora {} \"your query\"", cloud_id);
     println!("  Or via cloud command:");
-    println!("    synesis cloud ask --lora lora-cloud-abc123 \"your query\"");
+    println!("    synesis cloud ask --lora {} \"your query\"", cloud_id);
 
     Ok(())
 }
@@ -35,7 +35,7 @@ pub async fn run(args: StatusArgs, config: &Config) -> anyhow::Result<()> {
 
     // Hardware section
     let hw_info = get_hardware_info(config)?;
-    table.add_row(vec![format!("Hardware")]);
+    table.add_row(vec!["Hardware".to_string()]);
     table.add_row(vec![format!("  GPU: {}", hw_info.gpu)]);
     table.add_row(vec![format!("  RAM: {}", hw_info.ram)]);
     table.add_row(vec![format!("  NPU: {}", hw_info.npu)]);
@@ -43,7 +43,7 @@ pub async fn run(args: StatusArgs, config: &Config) -> anyhow::Result<()> {
 
     // Models section
     let models_info = get_models_info(config)?;
-    table.add_row(vec![format!("Models")]);
+    table.add_row(vec!["Models".to_string()]);
     for model in &models_info.models {
         table.add_row(vec![format!(
             "  {} {} ({}GB) - {}",
@@ -57,7 +57,7 @@ pub async fn run(args: StatusArgs, config: &Config) -> anyhow::Result<()> {
 
     // Knowledge Vault section
     let knowledge_info = get_knowledge_info(config)?;
-    table.add_row(vec![format!("Knowledge Vault")]);
+    table.add_row(vec!["Knowledge Vault".to_string()]);
     table.add_row(vec![format!("  Documents: {}", knowledge_info.documents)]);
     table.add_row(vec![format!("  Embeddings: {}", knowledge_info.embeddings)]);
     table.add_row(vec![format!("  Last sync: {}", knowledge_info.last_sync)]);
@@ -65,7 +65,7 @@ pub async fn run(args: StatusArgs, config: &Config) -> anyhow::Result<()> {
 
     // Agents section
     let agents_info = get_agents_info(config)?;
-    table.add_row(vec![format!("Agents")]);
+    table.add_row(vec!["Agents".to_string()]);
     table.add_row(vec![format!("  Pathos: {}", agents_info.pathos)]);
     table.add_row(vec![format!("  Logos: {}", agents_info.logos)]);
     table.add_row(vec![format!("  Ethos: {}", agents_info.ethos)]);
@@ -1,7 +1,18 @@
 //! Configuration loading and management
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+use synesis_cloud::tunnel::TunnelConfig;
+use synesis_models::download_queue::DEFAULT_CONCURRENT_DOWNLOADS;
+use synesis_models::downloader::{BandwidthLimiter, Downloader};
+use synesis_models::lockfile::{ModelLock, LOCKFILE_NAME};
+use synesis_models::{GgufMetadata, Tokenizer};
+use synesis_privacy::{
+    AllowList, AuditLog, CustomPatternConfig, DestinationRules, OutboundAction, PrivacyPolicy,
+    RedactorConfig,
+};
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +40,10 @@ pub struct Config {
     /// Consensus settings
     #[serde(default)]
     pub consensus: ConsensusConfig,
+
+    /// Model download settings
+    #[serde(default)]
+    pub downloads: DownloadsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -97,6 +112,27 @@ pub struct PrivacyConfig {
     /// Custom regex patterns to redact
     #[serde(default)]
     pub custom_patterns: Vec<CustomPattern>,
+
+    /// Active redaction profile ("default" is built from the flags above)
+    #[serde(default = "default_profile")]
+    pub profile: String,
+
+    /// Additional named profiles (e.g., `[privacy.profiles.team]`)
+    #[serde(default)]
+    pub profiles: BTreeMap<String, RedactorConfig>,
+
+    /// Values that are never redacted, in any profile
+    #[serde(default)]
+    pub allow_list: AllowList,
+
+    /// Profile used per destination ("active", "none", or a profile name)
+    #[serde(default)]
+    pub destinations: DestinationRules,
+
+    /// What to do when an outbound cloud payload still contains sensitive
+    /// data: "block" or "redact"
+    #[serde(default)]
+    pub outbound: OutboundAction,
 }
 
 impl Default for PrivacyConfig {
@@ -109,6 +145,51 @@ impl Default for PrivacyConfig {
             redact_ips: true,
             redact_ssns: true,
             custom_patterns: vec![],
+            profile: default_profile(),
+            profiles: BTreeMap::new(),
+            allow_list: AllowList::default(),
+            destinations: DestinationRules::default(),
+            outbound: OutboundAction::default(),
+        }
+    }
+}
+
+impl PrivacyConfig {
+    /// Build the redaction policy described by this configuration
+    ///
+    /// The flat `redact_*` flags and `custom_patterns` form the `default`
+    /// profile; `profiles` are layered on top of the built-in ones.
+    pub fn policy(&self) -> anyhow::Result<PrivacyPolicy> {
+        let mut policy = PrivacyPolicy::builtin();
+        policy.add_profile(DEFAULT_PROFILE, self.default_profile_config());
+        for (name, profile) in &self.profiles {
+            policy.add_profile(name, profile.clone());
+        }
+        policy.allow_list = self.allow_list.clone();
+        policy.destinations = self.destinations.clone();
+        policy.outbound = self.outbound;
+        policy.set_active(&self.profile)?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    fn default_profile_config(&self) -> RedactorConfig {
+        RedactorConfig {
+            redact_emails: self.redact_emails,
+            redact_phones: self.redact_phones,
+            redact_ssns: self.redact_ssns,
+            redact_api_keys: self.redact_api_keys,
+            redact_ips: self.redact_ips,
+            redact_paths: self.redact_paths,
+            custom_patterns: self
+                .custom_patterns
+                .iter()
+                .map(|p| CustomPatternConfig {
+                    name: p.name.clone(),
+                    pattern: p.pattern.clone(),
+                })
+                .collect(),
+            ..RedactorConfig::default()
         }
     }
 }
@@ -143,6 +224,10 @@ pub struct CloudConfig {
     /// Require explicit consent for each cloud request
     #[serde(default)]
     pub require_consent: bool,
+
+    /// Device identifier issued at login
+    #[serde(default)]
+    pub device_id: String,
 }
 
 impl Default for CloudConfig {
@@ -153,6 +238,7 @@ impl Default for CloudConfig {
             auto_escalate: true,
             max_local_tokens: 4096,
             require_consent: false,
+            device_id: String::new(),
         }
     }
 }
@@ -199,7 +285,53 @@ impl Default for ConsensusWeights {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadsConfig {
+    /// HuggingFace-compatible base URLs tried in order (e.g. an internal
+    /// artifact proxy); empty means huggingface.co
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+
+    /// Models downloaded at once
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent: usize,
+
+    /// Bandwidth cap across all downloads, in bytes per second
+    #[serde(default)]
+    pub max_bytes_per_second: Option<u64>,
+}
+
+impl Default for DownloadsConfig {
+    fn default() -> Self {
+        Self {
+            mirrors: Vec::new(),
+            max_concurrent: default_max_concurrent_downloads(),
+            max_bytes_per_second: None,
+        }
+    }
+}
+
+impl DownloadsConfig {
+    /// Downloader for `models_dir` using these mirrors and bandwidth cap
+    pub fn downloader(&self, models_dir: PathBuf) -> Downloader {
+        let downloader = Downloader::new(models_dir).with_mirrors(self.mirrors.clone());
+        match self.max_bytes_per_second {
+            Some(rate) if rate > 0 => {
+                downloader.with_bandwidth_limit(Arc::new(BandwidthLimiter::new(rate)))
+            },
+            _ => downloader,
+        }
+    }
+}
+
+/// Name of the profile built from the flat `[privacy]` flags
+pub const DEFAULT_PROFILE: &str = "default";
+
 // Default value functions
+fn default_profile() -> String {
+    DEFAULT_PROFILE.to_string()
+}
+
 fn default_data_dir() -> String {
     dirs::home_dir()
         .map(|p| p.join(".superinstance").to_string_lossy().to_string())
@@ -238,6 +370,10 @@ fn default_max_rounds() -> u8 {
     3
 }
 
+fn default_max_concurrent_downloads() -> usize {
+    DEFAULT_CONCURRENT_DOWNLOADS
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -247,6 +383,7 @@ impl Default for Config {
             privacy: PrivacyConfig::default(),
             cloud: CloudConfig::default(),
             consensus: ConsensusConfig::default(),
+            downloads: DownloadsConfig::default(),
         }
     }
 }
@@ -258,6 +395,44 @@ impl Config {
         Some(PathBuf::from(&self.data_dir).join("vault.db"))
     }
 
+    /// Get the path to the privacy audit log database
+    pub fn privacy_audit_path(&self) -> PathBuf {
+        PathBuf::from(&self.data_dir).join("privacy_audit.db")
+    }
+
+    /// Open the privacy audit log, creating its directory if needed
+    pub fn open_audit_log(&self) -> anyhow::Result<AuditLog> {
+        let path = self.privacy_audit_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        AuditLog::open(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to open audit log {}: {}", path.display(), e))
+    }
+
+    /// Get the directory holding the cloud device certificate and key
+    pub fn cloud_dir(&self) -> PathBuf {
+        PathBuf::from(&self.data_dir).join("cloud")
+    }
+
+    /// Tunnel settings for the configured cloud endpoint
+    pub fn tunnel_config(&self) -> TunnelConfig {
+        let endpoint = &self.cloud.endpoint;
+        let cloud_url = if endpoint.contains("://") {
+            endpoint.clone()
+        } else {
+            format!("https://{}:443", endpoint)
+        };
+
+        TunnelConfig {
+            cloud_url,
+            device_id: self.cloud.device_id.clone(),
+            cert_path: self.cloud_dir().join("device.crt"),
+            key_path: self.cloud_dir().join("device.key"),
+            ..TunnelConfig::default()
+        }
+    }
+
     /// Get the path to the knowledge database
     #[allow(dead_code)]
     pub fn knowledge_db_path(&self) -> PathBuf {
@@ -265,10 +440,40 @@ impl Config {
     }
 
     /// Get the models directory
-    #[allow(dead_code)]
     pub fn models_dir(&self) -> PathBuf {
         PathBuf::from(&self.data_dir).join("models")
     }
+
+    /// Tokenizer of an installed model, looked up by name in `models.lock`
+    ///
+    /// `None` when the model is not installed or has no readable tokenizer;
+    /// token counts are then estimated.
+    pub fn model_tokenizer(&self, model: &str) -> anyhow::Result<Option<Arc<Tokenizer>>> {
+        Ok(self
+            .installed_model_path(model)?
+            .and_then(|path| Tokenizer::for_model(&path))
+            .map(Arc::new))
+    }
+
+    /// GGUF header of an installed model, looked up by name in `models.lock`
+    ///
+    /// `None` when the model is not installed or its header is unreadable.
+    pub fn model_metadata(&self, model: &str) -> anyhow::Result<Option<GgufMetadata>> {
+        Ok(self
+            .installed_model_path(model)?
+            .and_then(|path| GgufMetadata::read(&path).ok()))
+    }
+
+    /// File of an installed model, looked up by name in `models.lock`
+    fn installed_model_path(&self, model: &str) -> anyhow::Result<Option<PathBuf>> {
+        let models_dir = self.models_dir();
+        let lock = ModelLock::load(&models_dir.join(LOCKFILE_NAME))?;
+        Ok(lock
+            .entries()
+            .iter()
+            .find(|entry| entry.model == model)
+            .map(|entry| models_dir.join(&entry.filename)))
+    }
 }
 
 /// Load configuration from file or return defaults
@@ -324,6 +529,45 @@ mod tests {
         assert!(config.privacy.redact_emails);
     }
 
+    #[test]
+    fn test_privacy_policy_from_config() {
+        let mut config = Config::default();
+        config.privacy.redact_paths = false;
+
+        let policy = config.privacy.policy().unwrap();
+        assert_eq!(policy.active_profile, DEFAULT_PROFILE);
+        assert!(!policy.active().unwrap().redact_paths);
+        assert!(policy.profile("strict").unwrap().redact_paths);
+
+        config.privacy.profile = "code-only".to_string();
+        assert!(!config.privacy.policy().unwrap().active().unwrap().redact_emails);
+
+        config.privacy.profile = "missing".to_string();
+        assert!(config.privacy.policy().is_err());
+    }
+
+    #[test]
+    fn test_privacy_profiles_from_toml() {
+        let config: Config = toml::from_str(
+            r#"
+            [privacy]
+            profile = "team"
+            allow_list = { values = ["support@superinstance.ai"] }
+            destinations = { local = "none", cloud = "strict" }
+
+            [privacy.profiles.team]
+            redact_urls = false
+            "#,
+        )
+        .unwrap();
+
+        let policy = config.privacy.policy().unwrap();
+        let team = policy.active().unwrap();
+        assert!(!team.redact_urls);
+        assert_eq!(team.allow_list.values, vec!["support@superinstance.ai"]);
+        assert!(!policy.redacts(synesis_privacy::Destination::Local));
+    }
+
     #[test]
     fn test_config_serialization() {
         let config = Config::default();
@@ -54,6 +54,10 @@ pub enum Commands {
     #[command(subcommand)]
     Knowledge(commands::knowledge::KnowledgeCommands),
 
+    /// Privacy tools (scan, redact, audit log)
+    #[command(subcommand)]
+    Privacy(commands::privacy::PrivacyCommands),
+
     /// Manage cloud connection
     #[command(subcommand)]
     Cloud(commands::cloud::CloudCommands),
@@ -96,6 +100,7 @@ async fn main() -> anyhow::Result<()> {
         Commands::Manifest(cmd) => commands::manifest::run(cmd, &config).await,
         Commands::Model(cmd) => commands::model::run(cmd, &config).await,
         Commands::Knowledge(cmd) => commands::knowledge::run(cmd, &config).await,
+        Commands::Privacy(cmd) => commands::privacy::run(cmd, &config).await,
         Commands::Cloud(cmd) => commands::cloud::run(cmd, &config).await,
         Commands::Push(args) => commands::push::run(args, &config).await,
         Commands::Invite(cmd) => commands::invite::run(cmd, &config).await,
@@ -64,6 +64,22 @@ pub enum CloudError {
     /// Telemetry errors
     #[error("Telemetry error: {0}")]
     Telemetry(String),
+
+    /// Outbound payload still contained sensitive data and was not sent
+    ///
+    /// Lists the request fields and token categories involved, never the
+    /// values themselves.
+    #[error("Escalation blocked: unredacted sensitive data ({categories:?}) in {fields:?}")]
+    SensitiveDataBlocked {
+        /// Request fields that contained sensitive data
+        fields: Vec<String>,
+        /// Categories of sensitive data found (e.g., "EMAIL")
+        categories: Vec<String>,
+    },
+
+    /// Privacy audit log errors (the transfer is not sent)
+    #[error("Audit log error: {0}")]
+    Audit(String),
 }
 
 impl CloudError {
@@ -111,6 +127,11 @@ impl CloudError {
     pub fn telemetry(msg: impl Into<String>) -> Self {
         Self::Telemetry(msg.into())
     }
+
+    /// Create an audit log error
+    pub fn audit(msg: impl Into<String>) -> Self {
+        Self::Audit(msg.into())
+    }
 }
 
 #[cfg(test)]
@@ -5,10 +5,12 @@
 //! ## Request Flow
 //!
 //! 1. Validate request (query length, token limits, timeout)
-//! 2. Serialize request to JSON
-//! 3. Send via QUIC tunnel with timeout
-//! 4. Deserialize and validate response
-//! 5. Verify request ID matches (prevent mixing responses)
+//! 2. Run the outbound leak guard
+//! 3. Serialize request to JSON
+//! 4. Send via QUIC tunnel with timeout
+//! 5. Record the sent payload in the privacy audit log (if configured)
+//! 6. Deserialize and validate response
+//! 7. Verify request ID matches (prevent mixing responses)
 //!
 //! ## Performance
 //!
@@ -25,10 +27,12 @@
 //! - Large context: 10-30 seconds
 
 use crate::error::{CloudError, CloudResult};
+use crate::escalation::guard::LeakGuard;
 use crate::escalation::types::{EscalationRequest, EscalationResponse, CloudModel};
 use crate::tunnel::tunnel::CloudTunnel;
 use std::sync::Arc;
 use std::time::Duration;
+use synesis_privacy::{AuditEventKind, AuditLog, AuditRecord, Destination, PrivacyPolicy};
 use uuid::Uuid;
 
 // ============================================================================
@@ -93,6 +97,8 @@ pub struct EscalationClient {
     api_key: String,
     timeout: Duration,
     default_model: CloudModel,
+    audit_log: Option<Arc<AuditLog>>,
+    leak_guard: LeakGuard,
 }
 
 impl EscalationClient {
@@ -103,6 +109,11 @@ impl EscalationClient {
     /// * `tunnel` - Connected QUIC tunnel (must already be authenticated)
     /// * `api_key` - API key for cloud authentication (reserved for future use)
     /// * `timeout` - Request timeout (default: 30 seconds)
+    /// * `policy` - Privacy policy; its cloud profile and outbound action
+    ///   configure the leak guard
+    ///
+    /// # Errors
+    /// Returns an error if the policy's cloud profile cannot be compiled.
     ///
     /// # Example
     ///
@@ -111,24 +122,48 @@ impl EscalationClient {
     /// # use synesis_cloud::tunnel::tunnel::CloudTunnel;
     /// # use std::sync::Arc;
     /// # use std::time::Duration;
+    /// # use synesis_privacy::PrivacyPolicy;
     /// # let tunnel = Arc::new(CloudTunnel::new(Default::default()).unwrap());
     /// let client = EscalationClient::new(
     ///     tunnel,
     ///     "api-key".to_string(),
     ///     Duration::from_secs(30),
-    /// );
+    ///     &PrivacyPolicy::default(),
+    /// ).unwrap();
     /// ```
     pub fn new(
         tunnel: Arc<CloudTunnel>,
         api_key: String,
         timeout: Duration,
-    ) -> Self {
-        Self {
+        policy: &PrivacyPolicy,
+    ) -> CloudResult<Self> {
+        Ok(Self {
             tunnel,
             api_key,
             timeout,
             default_model: CloudModel::Auto,
-        }
+            audit_log: None,
+            leak_guard: LeakGuard::from_policy(policy)?,
+        })
+    }
+
+    /// Replace the leak guard built from the policy
+    ///
+    /// Depending on the guard's action, offending requests are refused with
+    /// [`CloudError::SensitiveDataBlocked`] or masked before sending.
+    pub fn with_leak_guard(mut self, guard: LeakGuard) -> Self {
+        self.leak_guard = guard;
+        self
+    }
+
+    /// Record every outbound request in a privacy audit log
+    ///
+    /// A request is recorded once the cloud has received it; requests that
+    /// fail to send are not. If the entry cannot be written the escalation
+    /// fails rather than return an unrecorded response.
+    pub fn with_audit_log(mut self, audit_log: Arc<AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
     }
 
     /// Set default model
@@ -146,6 +181,7 @@ impl EscalationClient {
     /// * Escalation response from cloud
     ///
     /// # Errors
+    /// * Sensitive data blocked by the leak guard
     /// * Tunnel connection error
     /// * Timeout error
     /// * Cloud API error
@@ -163,6 +199,9 @@ impl EscalationClient {
         // Validate request
         Self::validate_request(&request)?;
 
+        // Final check for anything that escaped upstream redaction
+        self.leak_guard.apply(&mut request)?;
+
         // Serialize request
         let payload = serde_json::to_vec(&request)
             .map_err(CloudError::Serialization)?;
@@ -176,6 +215,9 @@ impl EscalationClient {
         .map_err(|_| CloudError::Timeout(self.timeout))?
         .map_err(|e| CloudError::tunnel_connection(format!("Escalation failed: {}", e)))?;
 
+        // Record what left the device
+        self.record_audit(&request.session_id, &payload)?;
+
         // Parse response
         let response: EscalationResponse = serde_json::from_slice(&response_data)
             .map_err(CloudError::Serialization)?;
@@ -199,6 +241,22 @@ impl EscalationClient {
         Ok(response)
     }
 
+    /// Append an audit entry for a sent payload
+    fn record_audit(&self, session_id: &str, payload: &[u8]) -> CloudResult<()> {
+        if let Some(ref audit_log) = self.audit_log {
+            let record = AuditRecord::from_payload(
+                AuditEventKind::Escalation,
+                Destination::Cloud,
+                session_id,
+                payload,
+            );
+            audit_log
+                .append(record)
+                .map_err(|e| CloudError::audit(e.to_string()))?;
+        }
+        Ok(())
+    }
+
     /// Escalate with streaming
     ///
     /// TODO: Implement streaming in Session 2.10
@@ -316,7 +374,8 @@ mod tests {
             tunnel,
             "test-key".to_string(),
             Duration::from_secs(30),
-        );
+            &PrivacyPolicy::default(),
+        ).unwrap();
 
         assert_eq!(client.api_key, "test-key");
         assert_eq!(client.timeout, Duration::from_secs(30));
@@ -333,11 +392,115 @@ mod tests {
             tunnel.clone(),
             "test-key".to_string(),
             Duration::from_secs(30),
-        ).with_default_model(CloudModel::ClaudeOpus);
+            &PrivacyPolicy::default(),
+        ).unwrap().with_default_model(CloudModel::ClaudeOpus);
 
         assert_eq!(client.default_model, CloudModel::ClaudeOpus);
     }
 
+    #[test]
+    fn test_record_audit() {
+        let tunnel = Arc::new(crate::tunnel::tunnel::CloudTunnel::new(
+            make_test_config()
+        ).unwrap());
+        let audit_log = Arc::new(AuditLog::in_memory().unwrap());
+
+        let client = EscalationClient::new(
+            tunnel,
+            "test-key".to_string(),
+            Duration::from_secs(30),
+            &PrivacyPolicy::default(),
+        ).unwrap().with_audit_log(audit_log.clone());
+
+        client
+            .record_audit("session-1", br#"{"query":"mail [EMAIL_0001]"}"#)
+            .unwrap();
+
+        let entries = audit_log.entries(&Default::default()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].session_id, "session-1");
+        assert_eq!(entries[0].categories.get("EMAIL"), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn test_escalate_blocked_by_leak_guard() {
+        let tunnel = Arc::new(crate::tunnel::tunnel::CloudTunnel::new(
+            make_test_config()
+        ).unwrap());
+        let audit_log = Arc::new(AuditLog::in_memory().unwrap());
+        let guard = LeakGuard::new(
+            synesis_privacy::RedactorConfig::strict(),
+            synesis_privacy::OutboundAction::Block,
+        ).unwrap();
+
+        let client = EscalationClient::new(
+            tunnel,
+            "test-key".to_string(),
+            Duration::from_secs(30),
+            &PrivacyPolicy::default(),
+        )
+        .unwrap()
+        .with_audit_log(audit_log.clone())
+        .with_leak_guard(guard);
+
+        let request = EscalationRequest {
+            query: "Email alice@example.com the report".to_string(),
+            ..Default::default()
+        };
+
+        let result = client.escalate(request).await;
+        assert!(matches!(result, Err(CloudError::SensitiveDataBlocked { .. })));
+        // Nothing left the device, so nothing was audited
+        assert!(audit_log.is_empty().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_failed_escalation_not_audited() {
+        // Never connected, so the request cannot be sent
+        let tunnel = Arc::new(crate::tunnel::tunnel::CloudTunnel::new(
+            make_test_config()
+        ).unwrap());
+        let audit_log = Arc::new(AuditLog::in_memory().unwrap());
+
+        let client = EscalationClient::new(
+            tunnel,
+            "test-key".to_string(),
+            Duration::from_secs(30),
+            &PrivacyPolicy::default(),
+        ).unwrap().with_audit_log(audit_log.clone());
+
+        let request = EscalationRequest {
+            query: "What is a LoRA?".to_string(),
+            ..Default::default()
+        };
+
+        let result = client.escalate(request).await;
+        assert!(matches!(result, Err(CloudError::TunnelConnection(_))));
+        assert!(audit_log.is_empty().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_escalate_guarded_by_default() {
+        let tunnel = Arc::new(crate::tunnel::tunnel::CloudTunnel::new(
+            make_test_config()
+        ).unwrap());
+
+        let client = EscalationClient::new(
+            tunnel,
+            "test-key".to_string(),
+            Duration::from_secs(30),
+            &PrivacyPolicy::default(),
+        ).unwrap();
+
+        let request = EscalationRequest {
+            query: "Email alice@example.com the report".to_string(),
+            ..Default::default()
+        };
+
+        let result = client.escalate(request).await;
+        assert!(matches!(result, Err(CloudError::SensitiveDataBlocked { .. })));
+    }
+
     #[test]
     fn test_validate_request_empty_query() {
         let request = EscalationRequest {
@@ -0,0 +1,290 @@
+//! Outbound leak guard
+//!
+//! Last check on an escalation request before it is serialized and sent
+//! through the tunnel. Redaction normally happens upstream (in `ask` and
+//! the consensus engine), but knowledge chunks, conversation history and
+//! agent framing can reach [`EscalationClient`](super::EscalationClient)
+//! without ever passing through the redactor. The guard scans every text
+//! field of the request and either refuses to send or masks what it finds.
+//!
+//! ## Scanned Fields
+//!
+//! - `query`
+//! - `context.pathos_framing`
+//! - `context.local_knowledge[i].source` and `.content`
+//! - `context.conversation_history[i].content`
+//! - `context.constraints[i]`
+//!
+//! Identifiers (request/session IDs, LoRA IDs) are not scanned: UUIDs
+//! contain digit runs that look like phone numbers and would block every
+//! request.
+//!
+//! ## Actions
+//!
+//! - [`OutboundAction::Block`]: return [`CloudError::SensitiveDataBlocked`]
+//! - [`OutboundAction::Redact`]: replace matches with `[REDACTED_CATEGORY]`
+//!   markers, which are irreversible and never reinflated
+
+use crate::error::{CloudError, CloudResult};
+use crate::escalation::types::EscalationRequest;
+use synesis_privacy::{
+    Destination, OutboundAction, PatternType, PrivacyPolicy, Redactor, RedactorConfig, TokenVault,
+};
+
+/// A sensitive value found in an outbound request
+///
+/// Never carries the value itself, only where it was and what it looked like.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeakFinding {
+    /// Request field path (e.g., `context.local_knowledge[2].content`)
+    pub field: String,
+    /// Type of sensitive data
+    pub pattern_type: PatternType,
+}
+
+/// Result of running the guard over a request that was allowed through
+#[derive(Debug, Clone, Default)]
+pub struct LeakReport {
+    /// Everything the guard found
+    pub findings: Vec<LeakFinding>,
+    /// Whether any field was masked
+    pub masked: bool,
+}
+
+impl LeakReport {
+    /// Whether the request was clean
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+/// Outbound leak guard
+///
+/// Built from a [`RedactorConfig`] (normally the cloud profile of the
+/// active [`PrivacyPolicy`]) so allow-lists and disabled categories apply
+/// here exactly as they do upstream.
+pub struct LeakGuard {
+    redactor: Redactor,
+    action: OutboundAction,
+}
+
+impl LeakGuard {
+    /// Create a guard from a redactor configuration
+    pub fn new(config: RedactorConfig, action: OutboundAction) -> CloudResult<Self> {
+        // Masking never stores tokens, so the vault stays empty
+        let vault = TokenVault::in_memory()
+            .map_err(|e| CloudError::other(format!("Leak guard vault: {}", e)))?;
+        let redactor = Redactor::new(config, vault)
+            .map_err(|e| CloudError::validation(format!("Leak guard patterns: {}", e)))?;
+
+        Ok(Self { redactor, action })
+    }
+
+    /// Create a guard using the policy's cloud profile and outbound action
+    pub fn from_policy(policy: &PrivacyPolicy) -> CloudResult<Self> {
+        let config = policy
+            .config_for(Destination::Cloud)
+            .map_err(|e| CloudError::validation(e.to_string()))?;
+        Self::new(config, policy.outbound)
+    }
+
+    /// Action taken when sensitive data is found
+    pub fn action(&self) -> OutboundAction {
+        self.action
+    }
+
+    /// Find sensitive values in a request without changing it
+    pub fn inspect(&self, request: &EscalationRequest) -> Vec<LeakFinding> {
+        let mut findings = Vec::new();
+        for (field, text) in text_fields(request) {
+            if !self.redactor.contains_sensitive(text) {
+                continue;
+            }
+            for m in self.redactor.preview(text) {
+                findings.push(LeakFinding {
+                    field: field.clone(),
+                    pattern_type: m.pattern_type,
+                });
+            }
+        }
+        findings
+    }
+
+    /// Check a request before it leaves the device
+    ///
+    /// # Errors
+    /// Returns [`CloudError::SensitiveDataBlocked`] if sensitive data is
+    /// found and the action is [`OutboundAction::Block`].
+    pub fn apply(&self, request: &mut EscalationRequest) -> CloudResult<LeakReport> {
+        let findings = self.inspect(request);
+        if findings.is_empty() {
+            return Ok(LeakReport::default());
+        }
+
+        match self.action {
+            OutboundAction::Block => {
+                let mut fields: Vec<String> = findings.iter().map(|f| f.field.clone()).collect();
+                fields.dedup();
+                let mut categories: Vec<String> = findings
+                    .iter()
+                    .map(|f| f.pattern_type.token_prefix().to_string())
+                    .collect();
+                categories.sort();
+                categories.dedup();
+
+                tracing::warn!(
+                    fields = ?fields,
+                    categories = ?categories,
+                    "Blocked escalation containing unredacted sensitive data"
+                );
+                Err(CloudError::SensitiveDataBlocked { fields, categories })
+            },
+            OutboundAction::Redact => {
+                for text in text_fields_mut(request) {
+                    let (masked, matches) = self.redactor.mask(text);
+                    if !matches.is_empty() {
+                        *text = masked;
+                    }
+                }
+
+                tracing::warn!(
+                    count = findings.len(),
+                    "Masked unredacted sensitive data in escalation"
+                );
+                Ok(LeakReport {
+                    findings,
+                    masked: true,
+                })
+            },
+        }
+    }
+}
+
+/// Every scanned text field of a request with its path
+fn text_fields(request: &EscalationRequest) -> Vec<(String, &String)> {
+    let context = &request.context;
+    let mut fields = vec![("query".to_string(), &request.query)];
+
+    if let Some(ref framing) = context.pathos_framing {
+        fields.push(("context.pathos_framing".to_string(), framing));
+    }
+    for (i, chunk) in context.local_knowledge.iter().enumerate() {
+        fields.push((format!("context.local_knowledge[{}].source", i), &chunk.source));
+        fields.push((format!("context.local_knowledge[{}].content", i), &chunk.content));
+    }
+    for (i, message) in context.conversation_history.iter().enumerate() {
+        fields.push((format!("context.conversation_history[{}].content", i), &message.content));
+    }
+    for (i, constraint) in context.constraints.iter().enumerate() {
+        fields.push((format!("context.constraints[{}]", i), constraint));
+    }
+
+    fields
+}
+
+/// Mutable counterpart of [`text_fields`] (same fields, same order)
+fn text_fields_mut(request: &mut EscalationRequest) -> Vec<&mut String> {
+    let context = &mut request.context;
+    let mut fields = vec![&mut request.query];
+
+    if let Some(ref mut framing) = context.pathos_framing {
+        fields.push(framing);
+    }
+    for chunk in context.local_knowledge.iter_mut() {
+        fields.push(&mut chunk.source);
+        fields.push(&mut chunk.content);
+    }
+    for message in context.conversation_history.iter_mut() {
+        fields.push(&mut message.content);
+    }
+    fields.extend(context.constraints.iter_mut());
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::escalation::types::KnowledgeChunk;
+    use crate::escalation::EscalationContextBuilder;
+
+    fn leaky_request() -> EscalationRequest {
+        EscalationRequest {
+            request_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            query: "Summarize the notes for [EMAIL_0001]".to_string(),
+            context: EscalationContextBuilder::new()
+                .add_knowledge(KnowledgeChunk {
+                    source: "notes.md".to_string(),
+                    content: "Reach Bob at bob@example.com".to_string(),
+                    relevance: 0.9,
+                })
+                .user("my key is sk-abcdefghijklmnopqrstuvwxyz123456")
+                .build(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_clean_request_passes() {
+        let guard = LeakGuard::new(RedactorConfig::strict(), OutboundAction::Block).unwrap();
+        let mut request = EscalationRequest {
+            request_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            query: "What is [EMAIL_0001]'s role?".to_string(),
+            ..Default::default()
+        };
+
+        let report = guard.apply(&mut request).unwrap();
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn test_block_reports_fields_not_values() {
+        let guard = LeakGuard::new(RedactorConfig::strict(), OutboundAction::Block).unwrap();
+        let mut request = leaky_request();
+
+        let err = guard.apply(&mut request).unwrap_err();
+        assert!(!err.to_string().contains("bob@example.com"));
+        match err {
+            CloudError::SensitiveDataBlocked { fields, categories } => {
+                assert_eq!(
+                    fields,
+                    vec![
+                        "context.local_knowledge[0].content",
+                        "context.conversation_history[0].content"
+                    ]
+                );
+                assert!(categories.contains(&"EMAIL".to_string()));
+                assert!(categories.contains(&"APIKEY".to_string()));
+            },
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_redact_masks_in_place() {
+        let guard = LeakGuard::new(RedactorConfig::strict(), OutboundAction::Redact).unwrap();
+        let mut request = leaky_request();
+
+        let report = guard.apply(&mut request).unwrap();
+        assert!(report.masked);
+        assert_eq!(report.findings.len(), 2);
+
+        assert_eq!(request.context.local_knowledge[0].content, "Reach Bob at [REDACTED_EMAIL]");
+        assert!(!request.context.conversation_history[0].content.contains("sk-"));
+        assert_eq!(request.query, "Summarize the notes for [EMAIL_0001]");
+        assert!(guard.inspect(&request).is_empty());
+    }
+
+    #[test]
+    fn test_from_policy_uses_cloud_profile_and_allow_list() {
+        let mut policy = PrivacyPolicy::builtin();
+        policy.allow_list.values.push("bob@example.com".to_string());
+        policy.destinations.cloud = "code-only".to_string();
+
+        let guard = LeakGuard::from_policy(&policy).unwrap();
+        let findings = guard.inspect(&leaky_request());
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].pattern_type, PatternType::ApiKey);
+    }
+}
@@ -5,6 +5,7 @@
 pub mod r#types;
 pub mod client;
 pub mod context;
+pub mod guard;
 
 pub use r#types::{
     CloudModel, EscalationRequest, EscalationResponse, EscalationContext,
@@ -12,3 +13,4 @@ pub use r#types::{
 };
 pub use client::{EscalationClient, ClientStats};
 pub use context::EscalationContextBuilder;
+pub use guard::{LeakFinding, LeakGuard, LeakReport};
@@ -5,5 +5,8 @@
 pub mod r#types;
 pub mod upload;
 
-pub use r#types::{LocalLora, CloudLora, UploadProgress, LoraStatus, UploadStatus};
+pub use r#types::{
+    LocalLora, CloudLora, UploadProgress, LoraStatus, UploadStatus, UploadMessage, UploadChunk,
+    UploadComplete,
+};
 pub use upload::{LoraUploadClient, LoraHotSwap};
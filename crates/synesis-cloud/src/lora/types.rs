@@ -117,3 +117,45 @@ pub enum UploadStatus {
         error: String,
     },
 }
+
+/// Upload message sent over the tunnel
+///
+/// The cloud answers each [`Chunk`](Self::Chunk) with an [`UploadProgress`]
+/// and the [`Complete`](Self::Complete) message with the registered
+/// [`CloudLora`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum UploadMessage {
+    /// Part of the LoRA file
+    Chunk(UploadChunk),
+    /// Every chunk was sent; register the LoRA
+    Complete(UploadComplete),
+}
+
+/// Part of a LoRA file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadChunk {
+    /// Unique upload identifier
+    pub upload_id: String,
+    /// Position of this chunk, from 0
+    pub index: u32,
+    /// Total number of chunks
+    pub total: u32,
+    /// Chunk bytes
+    pub data: Vec<u8>,
+}
+
+/// Registration of a fully uploaded LoRA
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadComplete {
+    /// Unique upload identifier
+    pub upload_id: String,
+    /// Human-readable name
+    pub name: String,
+    /// Base model
+    pub base_model: String,
+    /// File size in bytes
+    pub size_bytes: u64,
+    /// SHA256 checksum of the whole file
+    pub checksum: String,
+}
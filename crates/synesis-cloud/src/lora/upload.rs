@@ -3,24 +3,24 @@
 //! Handles uploading local LoRAs to cloud storage
 
 use crate::error::{CloudError, CloudResult};
-use crate::lora::types::{LocalLora, CloudLora, UploadProgress, UploadStatus};
+use crate::lora::types::{
+    LocalLora, CloudLora, UploadChunk, UploadComplete, UploadMessage, UploadProgress, UploadStatus,
+};
 use crate::tunnel::tunnel::CloudTunnel;
+use serde::de::DeserializeOwned;
 use std::sync::Arc;
-use std::time::Duration;
+use synesis_privacy::{AuditEventKind, AuditLog, AuditRecord, Destination};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
 /// LoRA upload client for cloud storage
 ///
 /// Handles uploading local LoRA files to cloud storage with chunked uploads.
-///
-/// # Production Status
-///
-/// The `tunnel` field is reserved for future QUIC tunnel integration.
+/// Each chunk is one [`UploadMessage`] request over the tunnel.
 pub struct LoraUploadClient {
-    #[allow(dead_code)]
     tunnel: Arc<CloudTunnel>,
     chunk_size: usize,
+    audit_log: Option<Arc<AuditLog>>,
 }
 
 impl LoraUploadClient {
@@ -29,9 +29,20 @@ impl LoraUploadClient {
         Self {
             tunnel,
             chunk_size: 1024 * 1024, // 1MB chunks
+            audit_log: None,
         }
     }
 
+    /// Record every upload in a privacy audit log
+    ///
+    /// Uploads are recorded under the LoRA ID as the session, once the
+    /// cloud has registered them; uploads that fail are not. If the entry
+    /// cannot be written the upload is reported as failed.
+    pub fn with_audit_log(mut self, audit_log: Arc<AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
     /// Set chunk size
     pub fn with_chunk_size(mut self, size: usize) -> Self {
         self.chunk_size = size;
@@ -53,26 +64,65 @@ impl LoraUploadClient {
             .map_err(CloudError::Io)?;
 
         // Calculate chunks
-        let total_chunks = (data.len() / self.chunk_size) + 1;
-
-        // Initiate upload
-        let _upload_id = Uuid::new_v4().to_string();
+        let total_chunks = data.len().div_ceil(self.chunk_size) as u32;
+        let upload_id = Uuid::new_v4().to_string();
 
         // Upload chunks
-        for (i, _chunk) in data.chunks(self.chunk_size).enumerate() {
+        for (i, chunk) in data.chunks(self.chunk_size).enumerate() {
             tracing::debug!("Uploading chunk {}/{}", i + 1, total_chunks);
 
-            // TODO: Send chunk via tunnel
-            // For now, simulate upload
-            tokio::time::sleep(Duration::from_millis(100)).await;
+            let message = UploadMessage::Chunk(UploadChunk {
+                upload_id: upload_id.clone(),
+                index: i as u32,
+                total: total_chunks,
+                data: chunk.to_vec(),
+            });
+            let progress: UploadProgress = self.send(&message).await?;
+            if progress.upload_id != upload_id {
+                return Err(CloudError::validation(format!(
+                    "Upload ID mismatch: expected {}, got {}",
+                    upload_id, progress.upload_id
+                )));
+            }
+            if let UploadStatus::Failed { error } = progress.status {
+                return Err(CloudError::other(format!("LoRA upload failed: {}", error)));
+            }
         }
 
         // Register LoRA in cloud
-        let cloud_id = Uuid::new_v4().to_string();
+        let message = UploadMessage::Complete(UploadComplete {
+            upload_id,
+            name: lora.name.clone(),
+            base_model: lora.base_model.clone(),
+            size_bytes: data.len() as u64,
+            checksum: lora.checksum.clone(),
+        });
+        let cloud_lora: CloudLora = self.send(&message).await?;
+
+        // Record what left the device
+        if let Some(ref audit_log) = self.audit_log {
+            let record = AuditRecord::from_payload(
+                AuditEventKind::Upload,
+                Destination::Cloud,
+                &lora.id,
+                &data,
+            );
+            audit_log
+                .append(record)
+                .map_err(|e| CloudError::audit(e.to_string()))?;
+        }
+
+        tracing::info!("LoRA upload complete: {}", cloud_lora.id);
 
-        tracing::info!("LoRA upload complete: {}", cloud_id);
+        Ok(cloud_lora.id)
+    }
 
-        Ok(cloud_id)
+    /// Send one upload message and parse the cloud's answer
+    async fn send<T: DeserializeOwned>(&self, message: &UploadMessage) -> CloudResult<T> {
+        let payload = serde_json::to_vec(message).map_err(CloudError::Serialization)?;
+        let response = self.tunnel.request(&payload).await
+            .map_err(|e| CloudError::tunnel_connection(format!("LoRA upload failed: {}", e)))?;
+        serde_json::from_slice(&response).map_err(CloudError::Serialization)
     }
 
     /// Get upload progress
@@ -197,7 +247,6 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
-    #[allow(dead_code)]
     fn make_test_lora() -> LocalLora {
         LocalLora {
             id: "lora-123".to_string(),
@@ -213,6 +262,27 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_failed_upload_not_audited() {
+        let mut lora = make_test_lora();
+        lora.path = std::env::temp_dir().join(format!("lora-{}.gguf", Uuid::new_v4()));
+        std::fs::write(&lora.path, b"adapter weights").unwrap();
+
+        // Never connected, so no chunk can be sent
+        let tunnel = Arc::new(CloudTunnel::new(crate::tunnel::types::TunnelConfig {
+            cert_path: "/tmp/test-cert.pem".into(),
+            key_path: "/tmp/test-key.pem".into(),
+            ..Default::default()
+        }).unwrap());
+        let audit_log = Arc::new(AuditLog::in_memory().unwrap());
+        let client = LoraUploadClient::new(tunnel).with_audit_log(audit_log.clone());
+
+        let result = client.upload(&lora).await;
+        std::fs::remove_file(&lora.path).unwrap();
+        assert!(matches!(result, Err(CloudError::TunnelConnection(_))));
+        assert!(audit_log.is_empty().unwrap());
+    }
+
     #[test]
     fn test_hotswap_load_unload() {
         let manager = LoraHotSwap::new();
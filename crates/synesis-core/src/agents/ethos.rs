@@ -16,11 +16,12 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use synesis_models::{ChatMessage, Scheduler};
 use tracing::{debug, info, instrument, warn};
 
 use super::{
-    Agent, AgentConfig, AgentInput, AgentOutput, ConsensusVote, Constraint, ConstraintType,
-    Severity,
+    generate_json, Agent, AgentConfig, AgentInput, AgentOutput, ConsensusVote, Constraint,
+    ConstraintType, OutputSource, Severity,
 };
 use crate::consensus::Verdict;
 use crate::manifest::A2AManifest;
@@ -33,6 +34,8 @@ pub struct EthosAgent {
     ready: Arc<std::sync::atomic::AtomicBool>,
     // Dangerous patterns for veto scenarios (immutable collection)
     veto_patterns: Arc<Vec<VetoPattern>>,
+    /// Runs the model review; heuristic fact and quality checks when unset
+    scheduler: Option<Scheduler>,
 }
 
 /// A dangerous pattern that triggers automatic veto
@@ -68,6 +71,48 @@ pub struct EthosVerdict {
 
     /// Overall confidence score (0.0-1.0)
     pub confidence: f32,
+
+    /// Whether the model review or heuristic checks produced the verdict
+    #[serde(default)]
+    pub source: OutputSource,
+}
+
+/// Review returned by the model, constrained to [`review_schema`]
+#[derive(Debug, Deserialize)]
+struct ModelReview {
+    verdict: Verdict,
+    confidence: f32,
+    concerns: Vec<Constraint>,
+}
+
+/// JSON schema the model's review is constrained to
+///
+/// Values use the serde names of [`Verdict`], [`ConstraintType`] and
+/// [`Severity`], so the output deserializes directly.
+fn review_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "verdict": {"enum": ["Approved", "NeedsRevision", "Veto"]},
+            "confidence": {"type": "number", "minimum": 0, "maximum": 1},
+            "concerns": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "constraint_type": {"enum": ["Fact", "Hardware", "Safety", "Quality"]},
+                        "severity": {"enum": ["Warning", "Error", "Critical"]},
+                        "description": {"type": "string"},
+                        "suggestion": {"type": "string"},
+                    },
+                    "required": ["constraint_type", "severity", "description", "suggestion"],
+                    "additionalProperties": false,
+                },
+            },
+        },
+        "required": ["verdict", "confidence", "concerns"],
+        "additionalProperties": false,
+    })
 }
 
 /// Prefetch data for Ethos verification (computed in parallel with Logos)
@@ -172,9 +217,16 @@ impl EthosAgent {
             config,
             ready: Arc::new(std::sync::atomic::AtomicBool::new(false)),
             veto_patterns: Arc::new(veto_patterns),
+            scheduler: None,
         }
     }
 
+    /// Review solutions with the configured model through `scheduler`
+    pub fn with_scheduler(mut self, scheduler: Scheduler) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
     /// Initialize the agent (load model)
     pub async fn initialize(&mut self) -> CoreResult<()> {
         info!("Initializing Ethos agent with model: {}", self.config.model);
@@ -252,6 +304,7 @@ impl EthosAgent {
                 feedback,
                 confidence: 0.0,
                 constraints_violated: constraints,
+                source: OutputSource::Heuristic,
             });
         }
 
@@ -260,22 +313,41 @@ impl EthosAgent {
             constraints.extend(self.check_hardware_constraints(solution).await?);
         }
 
-        // 3. Fact-checking (placeholder for model-based verification)
-        if self.should_check_facts(manifest) {
-            constraints.extend(self.check_facts(solution).await?);
-        }
-
-        // 4. Code quality checks (if code present)
-        if self.contains_code(solution) {
-            constraints.extend(self.check_code_quality(solution).await?);
+        // 3-4. Model review of facts and quality, or heuristic checks if no
+        // model is attached or the review fails
+        let review = self.review_with_model(manifest, solution).await;
+        match &review {
+            Some(review) => {
+                constraints.extend(review.concerns.iter().cloned().map(|mut concern| {
+                    concern.source.get_or_insert_with(|| "model-review".to_string());
+                    concern
+                }));
+            },
+            None => {
+                if self.should_check_facts(manifest) {
+                    constraints.extend(self.check_facts(solution).await?);
+                }
+                if self.contains_code(solution) {
+                    constraints.extend(self.check_code_quality(solution).await?);
+                }
+            },
         }
 
         // 5. Thermal limit checks
         constraints.extend(self.check_thermal_limits(solution).await?);
 
-        // Determine final verdict
-        let verdict = self.determine_verdict(&constraints);
-        let confidence = self.calculate_confidence(&constraints);
+        // Determine final verdict, no more lenient than the model's
+        let mut verdict = self.determine_verdict(&constraints);
+        let mut confidence = self.calculate_confidence(&constraints);
+        if let Some(review) = &review {
+            verdict = stricter(verdict, review.verdict);
+            confidence = confidence.min(review.confidence);
+        }
+        let source = if review.is_some() {
+            OutputSource::Model
+        } else {
+            OutputSource::Heuristic
+        };
         let feedback = self.generate_feedback(&constraints, verdict);
 
         let elapsed = start.elapsed();
@@ -289,9 +361,50 @@ impl EthosAgent {
             feedback,
             confidence,
             constraints_violated: constraints,
+            source,
         })
     }
 
+    /// Ask the model to review the solution, constrained to
+    /// [`review_schema`]
+    ///
+    /// Returns `None` when no model is attached or the review fails.
+    async fn review_with_model(&self, manifest: &A2AManifest, solution: &str) -> Option<ModelReview> {
+        let scheduler = self.scheduler.as_ref()?;
+        let prompt = self.build_review_prompt(manifest, solution);
+        match generate_json(scheduler, &self.config, prompt, review_schema()).await {
+            Ok(review) => Some(review),
+            Err(e) => {
+                warn!(
+                    "Review with {} failed, falling back to heuristic checks: {}",
+                    self.config.model, e
+                );
+                None
+            },
+        }
+    }
+
+    /// Build the review prompt in the model's chat format
+    fn build_review_prompt(&self, manifest: &A2AManifest, solution: &str) -> String {
+        let system = "You are Ethos, the Verification Agent in the SuperInstance system.\n\n\
+             Review the proposed solution for factual accuracy, safety, feasibility on \
+             local hardware, and code quality.\n\n\
+             Respond ONLY with a JSON object:\n\
+             {\"verdict\": \"Approved\" | \"NeedsRevision\" | \"Veto\", \
+             \"confidence\": number between 0 and 1, \
+             \"concerns\": [{\"constraint_type\": \"Fact\" | \"Hardware\" | \"Safety\" | \"Quality\", \
+             \"severity\": \"Warning\" | \"Error\" | \"Critical\", \
+             \"description\": string, \"suggestion\": string}]}\n\n\
+             Use Veto only for solutions that would cause harm if followed.";
+        let request = format!(
+            "## Query\n{}\n\n## Proposed Solution\n{}",
+            manifest.query, solution
+        );
+
+        let messages = [ChatMessage::system(system), ChatMessage::user(request)];
+        self.config.chat_template().render(&messages, true)
+    }
+
     /// Check for dangerous safety patterns (VETO scenarios)
     async fn check_safety_patterns(&self, solution: &str) -> CoreResult<Vec<Constraint>> {
         let mut constraints = Vec::new();
@@ -637,6 +750,20 @@ impl EthosAgent {
     }
 }
 
+/// The stricter of two verdicts
+fn stricter(a: Verdict, b: Verdict) -> Verdict {
+    let rank = |v: Verdict| match v {
+        Verdict::Approved => 0,
+        Verdict::NeedsRevision => 1,
+        Verdict::Veto => 2,
+    };
+    if rank(b) > rank(a) {
+        b
+    } else {
+        a
+    }
+}
+
 impl Default for EthosAgent {
     fn default() -> Self {
         Self::new(AgentConfig {
@@ -685,6 +812,10 @@ impl Agent for EthosAgent {
             "feedback".to_string(),
             serde_json::Value::String(verdict.feedback.clone()),
         );
+        metadata.insert(
+            "verdict_source".to_string(),
+            serde_json::to_value(verdict.source).unwrap_or_default(),
+        );
 
         Ok(AgentOutput {
             agent: self.name().to_string(),
@@ -796,6 +927,38 @@ mod tests {
             .any(|c| matches!(c.constraint_type, ConstraintType::Fact)));
     }
 
+    #[test]
+    fn test_model_review_schema() {
+        let output = r#"{"verdict": "NeedsRevision", "confidence": 0.6, "concerns": [
+            {"constraint_type": "Fact", "severity": "Error",
+             "description": "Rust 1.0 was released in 2015, not 2012",
+             "suggestion": "Fix the release year"}
+        ]}"#;
+        let constraint = synesis_models::OutputConstraint::JsonSchema(review_schema());
+        constraint.check(output).unwrap();
+        constraint.to_gbnf().unwrap();
+
+        let review: ModelReview = serde_json::from_str(output).unwrap();
+        assert_eq!(review.verdict, Verdict::NeedsRevision);
+        assert_eq!(review.concerns[0].severity, Severity::Error);
+
+        assert_eq!(stricter(Verdict::Approved, review.verdict), Verdict::NeedsRevision);
+        assert_eq!(stricter(Verdict::Veto, review.verdict), Verdict::Veto);
+    }
+
+    #[tokio::test]
+    async fn test_heuristic_source_without_model() {
+        let ethos = EthosAgent::default();
+        let mut manifest = A2AManifest::new("Test query".to_string());
+        manifest.set_logos_result("def hello(): return 'Hello'".to_string(), 0.9);
+
+        let verdict = ethos.verify(&AgentInput::new(manifest)).await.unwrap();
+        assert_eq!(verdict.source, OutputSource::Heuristic);
+        assert!(ethos
+            .build_review_prompt(&A2AManifest::new("q".to_string()), "def hello()")
+            .starts_with("<|system|>\nYou are Ethos"));
+    }
+
     #[tokio::test]
     async fn test_agent_trait() {
         let config = AgentConfig {
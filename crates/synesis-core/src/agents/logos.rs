@@ -8,6 +8,7 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use synesis_models::ChatMessage;
 use tracing::{debug, info, instrument, warn};
 
 use super::{Agent, AgentConfig, AgentInput, AgentOutput};
@@ -297,30 +298,41 @@ impl LogosAgent {
     }
 
     /// Build synthesis prompt from manifest and context
+    ///
+    /// Rendered in the chat format of the configured model: persona and
+    /// instructions as the system message, earlier turns as their own
+    /// messages, and framing, context and task as the final user message.
     fn build_synthesis_prompt(&self, manifest: &A2AManifest, context: &[RetrievedChunk]) -> String {
-        let mut prompt = String::new();
+        let mut messages = vec![ChatMessage::system(self.build_system_message(!context.is_empty()))];
+
+        // Add conversation context if available
+        for turn in &manifest.history {
+            messages.push(match turn.role.as_str() {
+                "assistant" => ChatMessage::assistant(turn.content.clone()),
+                _ => ChatMessage::user(turn.content.clone()),
+            });
+        }
 
-        // System prompt
-        prompt.push_str("You are Logos, the Logic Agent in the SuperInstance system.\n\n");
+        let mut request = String::new();
 
         // Add Pathos framing if available
         if let Some(framing) = &manifest.pathos_framing {
-            prompt.push_str("## Intent Understanding (from Pathos)\n");
-            prompt.push_str(framing);
-            prompt.push_str("\n\n");
+            request.push_str("## Intent Understanding (from Pathos)\n");
+            request.push_str(framing);
+            request.push_str("\n\n");
         }
 
         // Add retrieved context if available with proper formatting
         if !context.is_empty() {
-            prompt.push_str("## Relevant Context\n");
-            prompt.push_str(&format!(
+            request.push_str("## Relevant Context\n");
+            request.push_str(&format!(
                 "Found {} relevant chunks from the knowledge vault:\n\n",
                 context.len()
             ));
 
             for chunk in context.iter() {
                 // Format: [SOURCE: path/to/file.rs:42-58]
-                prompt.push_str(&format!(
+                request.push_str(&format!(
                     "[SOURCE: {} (relevance: {:.2}, type: {})]\n",
                     chunk.source, chunk.relevance, chunk.doc_type
                 ));
@@ -334,41 +346,37 @@ impl LogosAgent {
                     _ => "text",
                 };
 
-                prompt.push_str(&format!("```{}\n{}\n```\n\n", lang, chunk.content));
+                request.push_str(&format!("```{}\n{}\n```\n\n", lang, chunk.content));
             }
         }
 
         // Add the actual query
-        prompt.push_str("## Task\n");
-        prompt.push_str(&manifest.query);
-        prompt.push_str("\n\n");
+        request.push_str("## Task\n");
+        request.push_str(&manifest.query);
+        messages.push(ChatMessage::user(request));
 
-        // Add conversation context if available
-        if !manifest.history.is_empty() {
-            prompt.push_str("## Conversation Context\n");
-            for turn in &manifest.history {
-                prompt.push_str(&format!("{}: {}\n", turn.role, turn.content));
-            }
-            prompt.push('\n');
-        }
+        self.config.chat_template().render(&messages, true)
+    }
+
+    /// System message: persona and instructions
+    fn build_system_message(&self, has_context: bool) -> String {
+        let mut system = String::new();
+        system.push_str("You are Logos, the Logic Agent in the SuperInstance system.\n\n");
 
         // Add instructions
-        prompt.push_str("## Instructions\n");
-        prompt.push_str("1. Use the provided context when relevant\n");
-        prompt.push_str("2. Provide a complete, well-reasoned solution\n");
-        prompt.push_str("3. If generating code, ensure it's complete and runnable\n");
-        prompt.push_str(
+        system.push_str("## Instructions\n");
+        system.push_str("1. Use the provided context when relevant\n");
+        system.push_str("2. Provide a complete, well-reasoned solution\n");
+        system.push_str("3. If generating code, ensure it's complete and runnable\n");
+        system.push_str(
             "4. Cite sources using [SOURCE: path] notation when using retrieved information\n",
         );
-        prompt.push_str("5. Show your reasoning process\n");
-        if !context.is_empty() {
-            prompt.push_str("6. Prioritize information from high-relevance sources (>0.7)\n");
+        system.push_str("5. Show your reasoning process\n");
+        if has_context {
+            system.push_str("6. Prioritize information from high-relevance sources (>0.7)\n");
         }
-        prompt.push('\n');
-
-        prompt.push_str("## Response\n");
 
-        prompt
+        system
     }
 
     /// Generate solution using model
@@ -631,6 +639,7 @@ struct GeneratedSolution {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::manifest::ConversationTurn;
 
     #[test]
     fn test_logos_creation() {
@@ -702,6 +711,31 @@ mod tests {
         assert!(prompt.contains("Instructions"));
     }
 
+    #[test]
+    fn test_prompt_uses_model_chat_template() {
+        let config = AgentConfig {
+            model: "llama-3.2-8b".to_string(),
+            ..AgentConfig::default()
+        };
+        let agent = LogosAgent::new(config);
+
+        let manifest = A2AManifest::with_session(
+            "And in Rust?".to_string(),
+            "s1".to_string(),
+            vec![
+                ConversationTurn::user("Sort a list in Python".to_string()),
+                ConversationTurn::assistant("Use sorted()".to_string()),
+            ],
+        );
+        let prompt = agent.build_synthesis_prompt(&manifest, &[]);
+
+        assert!(prompt.starts_with("<|begin_of_text|><|start_header_id|>system"));
+        assert!(prompt.contains(
+            "<|start_header_id|>assistant<|end_header_id|>\n\nUse sorted()<|eot_id|>"
+        ));
+        assert!(prompt.ends_with("<|start_header_id|>assistant<|end_header_id|>\n\n"));
+    }
+
     #[test]
     fn test_relevance_scoring() {
         let config = AgentConfig::default();
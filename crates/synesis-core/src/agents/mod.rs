@@ -102,11 +102,13 @@ pub mod logos;
 pub mod pathos;
 
 use async_trait::async_trait;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use synesis_models::{ChatTemplate, InferenceRequest, RequestOptions, Scheduler};
 
 use crate::manifest::A2AManifest;
-use crate::SynesisResult as CoreResult;
+use crate::{SynesisError, SynesisResult as CoreResult};
 
 // ============================================================================
 // Core Agent Trait
@@ -722,6 +724,55 @@ impl Default for AgentConfig {
     }
 }
 
+impl AgentConfig {
+    /// Prompt format for the configured model
+    pub fn chat_template(&self) -> ChatTemplate {
+        ChatTemplate::for_model(&self.model)
+    }
+}
+
+// ============================================================================
+// Structured Output
+// ============================================================================
+
+/// Where an agent's structured output came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputSource {
+    /// Schema-constrained model output
+    Model,
+    /// Heuristics, used when no model is attached or the model failed
+    #[default]
+    Heuristic,
+}
+
+/// Run schema-constrained inference and deserialize the JSON output
+///
+/// Fails if the model is unavailable, the request is rejected or times
+/// out, or the output does not match `schema`; callers fall back to
+/// heuristics on error.
+pub(crate) async fn generate_json<T: DeserializeOwned>(
+    scheduler: &Scheduler,
+    config: &AgentConfig,
+    prompt: String,
+    schema: serde_json::Value,
+) -> CoreResult<T> {
+    let request = InferenceRequest::new(prompt)
+        .with_max_tokens(config.max_tokens)
+        .with_temperature(config.temperature)
+        .with_json_schema(schema);
+    let response = scheduler
+        .infer(&config.model, request, None, RequestOptions::interactive())
+        .await?;
+
+    serde_json::from_str(response.text.trim()).map_err(|e| {
+        SynesisError::ModelInferenceFailed(format!(
+            "{} returned JSON of the wrong shape: {}",
+            config.model, e
+        ))
+    })
+}
+
 // ============================================================================
 // Re-exports
 // ============================================================================
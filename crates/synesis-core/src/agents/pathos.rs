@@ -15,9 +15,10 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tracing::{debug, info, instrument};
+use synesis_models::{ChatMessage, Scheduler};
+use tracing::{debug, info, instrument, warn};
 
-use super::{Agent, AgentConfig, AgentInput, AgentOutput};
+use super::{generate_json, Agent, AgentConfig, AgentInput, AgentOutput, OutputSource};
 use crate::{SynesisError as CoreError, SynesisResult as CoreResult};
 
 /// Pathos agent for intent extraction
@@ -27,6 +28,8 @@ pub struct PathosAgent {
     ready: Arc<std::sync::atomic::AtomicBool>,
     // Placeholder for model - will be integrated with synesis-models
     model: Arc<Option<ModelPlaceholder>>,
+    /// Runs intent extraction on the local model; heuristics only when unset
+    scheduler: Option<Scheduler>,
 }
 
 /// Placeholder for the actual model interface
@@ -44,9 +47,16 @@ impl PathosAgent {
             config,
             ready: Arc::new(std::sync::atomic::AtomicBool::new(false)),
             model: Arc::new(None),
+            scheduler: None,
         }
     }
 
+    /// Extract intent with the configured model through `scheduler`
+    pub fn with_scheduler(mut self, scheduler: Scheduler) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
     /// Create a Pathos agent with default configuration for phi-3-mini
     pub fn with_phi3() -> Self {
         Self::new(AgentConfig {
@@ -76,22 +86,41 @@ impl PathosAgent {
     }
 
     /// Extract structured intent from user query using the model
+    ///
+    /// The model's output is constrained to the [`PathosIntent`] schema.
+    /// Heuristics are used only when no model is attached or inference
+    /// fails; the returned [`OutputSource`] says which path was taken.
     #[instrument(skip(self, query))]
-    async fn extract_intent(&self, query: &str) -> CoreResult<PathosIntent> {
-        debug!("Extracting intent from query using {}", self.config.model);
-
-        // Build the system prompt for intent extraction
-        let _system_prompt = self.build_system_prompt();
-
-        // In a full implementation, we would:
-        // 1. Call the model with the system prompt and user query
-        // 2. Parse the JSON response
-        // 3. Validate the structure
+    async fn extract_intent(&self, query: &str) -> CoreResult<(PathosIntent, OutputSource)> {
+        let Some(scheduler) = &self.scheduler else {
+            debug!("No model attached, extracting intent heuristically");
+            let intent = self.heuristic_intent_extraction(query).await;
+            return Ok((intent, OutputSource::Heuristic));
+        };
 
-        // For now, use heuristic analysis as placeholder
-        let intent = self.heuristic_intent_extraction(query).await;
+        debug!("Extracting intent from query using {}", self.config.model);
+        let prompt = self.build_intent_prompt(query);
+        match generate_json(scheduler, &self.config, prompt, PathosIntent::json_schema()).await {
+            Ok(intent) => Ok((intent, OutputSource::Model)),
+            Err(e) => {
+                warn!(
+                    "Intent extraction with {} failed, falling back to heuristics: {}",
+                    self.config.model, e
+                );
+                let intent = self.heuristic_intent_extraction(query).await;
+                Ok((intent, OutputSource::Heuristic))
+            },
+        }
+    }
 
-        Ok(intent)
+    /// Build the full prompt for intent extraction: the system prompt and
+    /// the user's query, rendered with the model's chat template
+    fn build_intent_prompt(&self, query: &str) -> String {
+        let messages = [
+            ChatMessage::system(self.build_system_prompt()),
+            ChatMessage::user(query),
+        ];
+        self.config.chat_template().render(&messages, true)
     }
 
     /// Build the system prompt for intent extraction
@@ -522,7 +551,7 @@ impl Agent for PathosAgent {
         let manifest = &input.manifest;
 
         // Extract structured intent from the user query
-        let intent = self.extract_intent(&manifest.query).await?;
+        let (intent, source) = self.extract_intent(&manifest.query).await?;
 
         // Calculate confidence
         let confidence = self.calculate_confidence(&intent, &manifest.query);
@@ -548,6 +577,10 @@ impl Agent for PathosAgent {
             "domain".to_string(),
             serde_json::Value::String(intent.context_hints.domain.clone()),
         );
+        metadata.insert(
+            "intent_source".to_string(),
+            serde_json::to_value(source).unwrap_or_default(),
+        );
 
         Ok(AgentOutput {
             agent: self.name().to_string(),
@@ -628,6 +661,46 @@ pub struct PathosIntent {
     pub verification_scope: VerificationScope,
 }
 
+impl PathosIntent {
+    /// JSON schema the model's intent output is constrained to
+    pub fn json_schema() -> serde_json::Value {
+        fn object(properties: serde_json::Value) -> serde_json::Value {
+            let required: Vec<&String> = properties.as_object().map_or_else(Vec::new, |p| p.keys().collect());
+            serde_json::json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false,
+            })
+        }
+        let strings = serde_json::json!({"type": "array", "items": {"type": "string"}});
+
+        object(serde_json::json!({
+            "intent": object(serde_json::json!({
+                "telos": {"type": "string"},
+                "query_type": {"enum": ["generate", "analyze", "transform", "verify", "explain"]},
+                "constraints": strings,
+                "priority": {"enum": ["speed", "quality", "cost"]},
+            })),
+            "persona": object(serde_json::json!({
+                "expertise_level": {"enum": ["novice", "intermediate", "expert"]},
+                "communication_style": {"enum": ["formal", "casual", "technical"]},
+                "known_preferences": strings,
+            })),
+            "context_hints": object(serde_json::json!({
+                "relevant_files": strings,
+                "related_queries": strings,
+                "domain": {"type": "string"},
+            })),
+            "verification_scope": object(serde_json::json!({
+                "check_facts": {"type": "boolean"},
+                "check_hardware": {"type": "boolean"},
+                "check_safety": {"type": "boolean"},
+            })),
+        }))
+    }
+}
+
 /// Core intent details
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IntentDetails {
@@ -639,6 +712,7 @@ pub struct IntentDetails {
 
 /// Query type classification
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum QueryType {
     Generate,
     Analyze,
@@ -657,6 +731,7 @@ pub struct PersonaDetails {
 
 /// User expertise level
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ExpertiseLevel {
     Novice,
     Intermediate,
@@ -665,6 +740,7 @@ pub enum ExpertiseLevel {
 
 /// Communication style preference
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum CommunicationStyle {
     Formal,
     Casual,
@@ -673,6 +749,7 @@ pub enum CommunicationStyle {
 
 /// Processing priority
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Priority {
     Speed,
     Quality,
@@ -710,6 +787,70 @@ mod tests {
         assert_eq!(agent.model(), "phi-3-mini-4k");
     }
 
+    #[test]
+    fn test_intent_prompt_uses_phi3_template() {
+        let agent = PathosAgent::with_phi3();
+        let prompt = agent.build_intent_prompt("What is Rust?");
+
+        assert!(prompt.starts_with("<|system|>\nYou are Pathos"));
+        assert!(prompt.ends_with("<|user|>\nWhat is Rust?<|end|>\n<|assistant|>\n"));
+    }
+
+    #[tokio::test]
+    async fn test_model_output_matches_schema() {
+        let output = r#"{
+            "intent": {"telos": "Explain Rust ownership", "query_type": "explain",
+                       "constraints": [], "priority": "quality"},
+            "persona": {"expertise_level": "novice", "communication_style": "casual",
+                        "known_preferences": []},
+            "context_hints": {"relevant_files": [], "related_queries": [], "domain": "rust"},
+            "verification_scope": {"check_facts": true, "check_hardware": false,
+                                   "check_safety": false}
+        }"#;
+        let constraint = synesis_models::OutputConstraint::JsonSchema(PathosIntent::json_schema());
+        constraint.check(output).unwrap();
+        constraint.to_gbnf().unwrap();
+
+        let intent: PathosIntent = serde_json::from_str(output).unwrap();
+        assert!(matches!(intent.intent.query_type, QueryType::Explain));
+        assert!(matches!(intent.persona.expertise_level, ExpertiseLevel::Novice));
+
+        // Heuristic intents serialize to the same shape
+        let agent = PathosAgent::with_phi3();
+        let heuristic = agent.heuristic_intent_extraction("Write a Rust parser").await;
+        constraint
+            .check(&serde_json::to_string(&heuristic).unwrap())
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_heuristic_source_without_model() {
+        let mut agent = PathosAgent::with_phi3();
+        agent.initialize().await.unwrap();
+
+        let input = AgentInput::new(A2AManifest::new("What is Rust?".to_string()));
+        let response = agent.process(input).await.unwrap();
+        assert_eq!(
+            response.metadata.get("intent_source"),
+            Some(&serde_json::json!("heuristic"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_when_model_fails() {
+        use synesis_models::{MemoryBudget, ModelPool, SchedulerConfig};
+
+        // The model is not in the pool, so inference fails
+        let pool = Arc::new(ModelPool::new(MemoryBudget::unlimited()));
+        let mut agent = PathosAgent::with_phi3()
+            .with_scheduler(Scheduler::new(pool, SchedulerConfig::default()));
+        agent.initialize().await.unwrap();
+
+        let (intent, source) = agent.extract_intent("Write a Rust parser").await.unwrap();
+        assert_eq!(source, OutputSource::Heuristic);
+        assert!(matches!(intent.intent.query_type, QueryType::Generate));
+    }
+
     #[tokio::test]
     async fn test_simple_question() {
         let agent = PathosAgent::with_phi3();
@@ -106,6 +106,7 @@
 
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument, warn};
+use synesis_models::Scheduler;
 
 use crate::agents::{Agent, AgentConfig, AgentInput, EthosAgent, LogosAgent, PathosAgent};
 use crate::consensus::{ConsensusConfig, ConsensusEngine, ConsensusResult};
@@ -189,6 +190,22 @@ impl Council {
         }
     }
 
+    /// Run Pathos and Ethos on their local models through `scheduler`
+    ///
+    /// Intent extraction and verification then use schema-constrained
+    /// model output, falling back to heuristics if inference fails.
+    pub fn with_scheduler(mut self, scheduler: Scheduler) -> Self {
+        self.pathos = self.pathos.with_scheduler(scheduler.clone());
+        self.ethos = self.ethos.with_scheduler(scheduler);
+        self.consensus = ConsensusEngine::new(
+            self.config.consensus.clone(),
+            self.pathos.clone(),
+            LogosAgent::new(self.config.logos.clone()),
+            self.ethos.clone(),
+        );
+        self
+    }
+
     /// Initialize all agents (load models)
     pub async fn initialize(&mut self) -> CoreResult<()> {
         info!("Initializing tripartite council");
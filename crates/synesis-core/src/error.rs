@@ -240,6 +240,9 @@ impl From<synesis_knowledge::KnowledgeError> for SynesisError {
             synesis_knowledge::KnowledgeError::EmbeddingError(msg) => {
                 SynesisError::EmbeddingError(msg)
             }
+            synesis_knowledge::KnowledgeError::ModelMismatch(msg) => {
+                SynesisError::EmbeddingError(msg)
+            }
             synesis_knowledge::KnowledgeError::DatabaseError(msg) => {
                 SynesisError::DatabaseQuery(msg)
             }
@@ -252,6 +255,9 @@ impl From<synesis_knowledge::KnowledgeError> for SynesisError {
             synesis_knowledge::KnowledgeError::WatchError(msg) => {
                 SynesisError::WatchError(msg)
             }
+            synesis_knowledge::KnowledgeError::RerankError(msg) => {
+                SynesisError::RetrievalFailed(msg)
+            }
             synesis_knowledge::KnowledgeError::Internal(msg) => {
                 SynesisError::Internal(msg)
             }
@@ -265,6 +271,12 @@ impl From<synesis_privacy::PrivacyError> for SynesisError {
             synesis_privacy::PrivacyError::PatternError(msg) => {
                 SynesisError::PatternError(msg)
             }
+            synesis_privacy::PrivacyError::PolicyError(msg) => {
+                SynesisError::ConfigValidation(msg)
+            }
+            synesis_privacy::PrivacyError::AuditError(msg) => {
+                SynesisError::Internal(msg)
+            }
             synesis_privacy::PrivacyError::VaultError(msg) => {
                 SynesisError::TokenVaultError(msg)
             }
@@ -296,12 +308,45 @@ impl From<synesis_models::ModelError> for SynesisError {
             synesis_models::ModelError::DownloadFailed(msg) => {
                 SynesisError::NetworkConnection(msg)
             }
+            synesis_models::ModelError::UnverifiedChecksum(msg) => {
+                SynesisError::ModelUnavailable(format!("No verified checksum for {}", msg))
+            }
+            synesis_models::ModelError::InvalidBundle(msg) => {
+                SynesisError::ModelLoadFailed(format!("Invalid model bundle: {}", msg))
+            }
+            synesis_models::ModelError::InvalidLockfile(msg) => {
+                SynesisError::ConfigParse(msg)
+            }
+            synesis_models::ModelError::InvalidDownloadQueue(msg) => {
+                SynesisError::ConfigParse(msg)
+            }
+            synesis_models::ModelError::InvalidGguf(msg) => {
+                SynesisError::ModelLoadFailed(msg)
+            }
+            synesis_models::ModelError::UnsupportedTokenizer(msg) => {
+                SynesisError::ModelLoadFailed(format!("Unsupported tokenizer: {}", msg))
+            }
             synesis_models::ModelError::ChecksumMismatch { model, expected, actual } => {
                 SynesisError::ChecksumMismatch { model, expected, actual }
             }
+            synesis_models::ModelError::InvalidGrammar(msg) => {
+                SynesisError::ModelInferenceFailed(format!("Invalid output constraint: {}", msg))
+            }
+            synesis_models::ModelError::ConstraintViolated(msg) => {
+                SynesisError::ModelInferenceFailed(format!("Output does not match constraint: {}", msg))
+            }
             synesis_models::ModelError::InsufficientResources(msg) => {
                 SynesisError::InsufficientResources(msg)
             }
+            synesis_models::ModelError::QueueFull(msg) => {
+                SynesisError::InsufficientResources(format!("Inference queue full: {}", msg))
+            }
+            synesis_models::ModelError::Cancelled(msg) => {
+                SynesisError::ModelInferenceFailed(format!("Request cancelled: {}", msg))
+            }
+            synesis_models::ModelError::DeadlineExceeded(msg) => {
+                SynesisError::AgentTimeout(format!("Deadline exceeded waiting for {}", msg))
+            }
             synesis_models::ModelError::InferenceError(msg) => {
                 SynesisError::ModelInferenceFailed(msg)
             }
@@ -7,6 +7,8 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use synesis_models::{PoolEvent, PoolEventCallback};
+
 /// System-wide metrics collector
 #[derive(Debug, Clone)]
 pub struct Metrics {
@@ -44,6 +46,16 @@ struct MetricsInner {
     // Privacy metrics
     redactions_performed: AtomicU64,
     tokens_generated: AtomicU64,
+
+    // Model pool metrics
+    models_loaded: AtomicU64,
+    models_evicted: AtomicU64,
+    model_memory_bytes: AtomicU64,
+
+    // Prefix cache metrics
+    prefix_cache_hits: AtomicU64,
+    prefix_cache_misses: AtomicU64,
+    prefix_cache_tokens_reused: AtomicU64,
 }
 
 impl Default for MetricsInner {
@@ -67,6 +79,12 @@ impl Default for MetricsInner {
             searches_performed: AtomicU64::new(0),
             redactions_performed: AtomicU64::new(0),
             tokens_generated: AtomicU64::new(0),
+            models_loaded: AtomicU64::new(0),
+            models_evicted: AtomicU64::new(0),
+            model_memory_bytes: AtomicU64::new(0),
+            prefix_cache_hits: AtomicU64::new(0),
+            prefix_cache_misses: AtomicU64::new(0),
+            prefix_cache_tokens_reused: AtomicU64::new(0),
         }
     }
 }
@@ -173,6 +191,46 @@ impl Metrics {
         self.inner.tokens_generated.fetch_add(count, Ordering::Relaxed);
     }
 
+    /// Record a prompt's prefix cache lookup
+    ///
+    /// A prompt that reused any cached tokens counts as a hit.
+    pub fn record_prefix_cache(&self, cached_tokens: u64) {
+        if cached_tokens > 0 {
+            self.inner.prefix_cache_hits.fetch_add(1, Ordering::Relaxed);
+            self.inner.prefix_cache_tokens_reused.fetch_add(cached_tokens, Ordering::Relaxed);
+        } else {
+            self.inner.prefix_cache_misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Record a model pool load, eviction, unload or prompt
+    pub fn record_pool_event(&self, event: &PoolEvent) {
+        match event {
+            PoolEvent::Loaded { memory, .. } => {
+                self.inner.models_loaded.fetch_add(1, Ordering::Relaxed);
+                self.inner.model_memory_bytes.fetch_add(memory.total_bytes(), Ordering::Relaxed);
+            }
+            PoolEvent::Evicted { memory, .. } | PoolEvent::Unloaded { memory, .. } => {
+                if matches!(event, PoolEvent::Evicted { .. }) {
+                    self.inner.models_evicted.fetch_add(1, Ordering::Relaxed);
+                }
+                // Every unload follows the load that added these bytes
+                self.inner.model_memory_bytes.fetch_sub(memory.total_bytes(), Ordering::Relaxed);
+            }
+            PoolEvent::Prompt { cached_tokens, .. } => {
+                self.record_prefix_cache(u64::from(*cached_tokens));
+            }
+        }
+    }
+
+    /// Callback that records a model pool's events
+    ///
+    /// Pass to [`ModelPool::with_event_callback`](synesis_models::ModelPool::with_event_callback).
+    pub fn pool_observer(&self) -> PoolEventCallback {
+        let metrics = self.clone();
+        Arc::new(move |event: &PoolEvent| metrics.record_pool_event(event))
+    }
+
     /// Get current metrics as a snapshot
     pub fn snapshot(&self) -> MetricsSnapshot {
         let queries_total = self.inner.queries_total.load(Ordering::Relaxed);
@@ -188,11 +246,9 @@ impl Metrics {
             } else {
                 0.0
             },
-            avg_response_time_ms: if queries_successful > 0 {
-                total_response_time_ms / queries_successful
-            } else {
-                0
-            },
+            avg_response_time_ms: total_response_time_ms
+                .checked_div(queries_successful)
+                .unwrap_or(0),
             min_response_time_ms: self.inner.min_response_time_ms.load(Ordering::Relaxed),
             max_response_time_ms: self.inner.max_response_time_ms.load(Ordering::Relaxed),
             consensus_reached_first_round: self.inner.consensus_reached_first_round.load(Ordering::Relaxed),
@@ -207,6 +263,12 @@ impl Metrics {
             searches_performed: self.inner.searches_performed.load(Ordering::Relaxed),
             redactions_performed: self.inner.redactions_performed.load(Ordering::Relaxed),
             tokens_generated: self.inner.tokens_generated.load(Ordering::Relaxed),
+            models_loaded: self.inner.models_loaded.load(Ordering::Relaxed),
+            models_evicted: self.inner.models_evicted.load(Ordering::Relaxed),
+            model_memory_bytes: self.inner.model_memory_bytes.load(Ordering::Relaxed),
+            prefix_cache_hits: self.inner.prefix_cache_hits.load(Ordering::Relaxed),
+            prefix_cache_misses: self.inner.prefix_cache_misses.load(Ordering::Relaxed),
+            prefix_cache_tokens_reused: self.inner.prefix_cache_tokens_reused.load(Ordering::Relaxed),
         }
     }
 
@@ -262,7 +324,25 @@ impl Metrics {
              synesis_searches_performed {}\n\
              # HELP synesis_redactions_performed Total number of redactions performed\n\
              # TYPE synesis_redactions_performed counter\n\
-             synesis_redactions_performed {}\n",
+             synesis_redactions_performed {}\n\
+             # HELP synesis_models_loaded Total number of model loads\n\
+             # TYPE synesis_models_loaded counter\n\
+             synesis_models_loaded {}\n\
+             # HELP synesis_models_evicted Models unloaded to make room for another\n\
+             # TYPE synesis_models_evicted counter\n\
+             synesis_models_evicted {}\n\
+             # HELP synesis_model_memory_bytes Estimated memory used by loaded models\n\
+             # TYPE synesis_model_memory_bytes gauge\n\
+             synesis_model_memory_bytes {}\n\
+             # HELP synesis_prefix_cache_hits Prompts that reused a cached prefix\n\
+             # TYPE synesis_prefix_cache_hits counter\n\
+             synesis_prefix_cache_hits {}\n\
+             # HELP synesis_prefix_cache_misses Prompts evaluated from the start\n\
+             # TYPE synesis_prefix_cache_misses counter\n\
+             synesis_prefix_cache_misses {}\n\
+             # HELP synesis_prefix_cache_tokens_reused Prompt tokens served from the prefix cache\n\
+             # TYPE synesis_prefix_cache_tokens_reused counter\n\
+             synesis_prefix_cache_tokens_reused {}\n",
             snap.queries_total,
             snap.queries_successful,
             snap.queries_failed,
@@ -283,6 +363,12 @@ impl Metrics {
             snap.chunks_stored,
             snap.searches_performed,
             snap.redactions_performed,
+            snap.models_loaded,
+            snap.models_evicted,
+            snap.model_memory_bytes,
+            snap.prefix_cache_hits,
+            snap.prefix_cache_misses,
+            snap.prefix_cache_tokens_reused,
         )
     }
 }
@@ -371,6 +457,18 @@ pub struct MetricsSnapshot {
     pub redactions_performed: u64,
     /// Tokens generated
     pub tokens_generated: u64,
+    /// Model loads
+    pub models_loaded: u64,
+    /// Models evicted to make room for another
+    pub models_evicted: u64,
+    /// Estimated memory used by loaded models, in bytes
+    pub model_memory_bytes: u64,
+    /// Prompts that reused a cached prefix
+    pub prefix_cache_hits: u64,
+    /// Prompts evaluated from the start
+    pub prefix_cache_misses: u64,
+    /// Prompt tokens served from the prefix cache
+    pub prefix_cache_tokens_reused: u64,
 }
 
 #[cfg(test)]
@@ -415,6 +513,144 @@ mod tests {
         assert!(prom.contains("synesis_consensus_reached_first_round 1"));
     }
 
+    #[test]
+    fn test_pool_events() {
+        use synesis_models::MemoryEstimate;
+
+        let metrics = Metrics::new();
+        let observer = metrics.pool_observer();
+        let memory = MemoryEstimate {
+            ram_bytes: 3000,
+            vram_bytes: 1000,
+        };
+
+        observer(&PoolEvent::Loaded {
+            model: "pathos".to_string(),
+            memory,
+            load_time: Duration::from_millis(5),
+        });
+        observer(&PoolEvent::Loaded {
+            model: "logos".to_string(),
+            memory,
+            load_time: Duration::from_millis(5),
+        });
+        observer(&PoolEvent::Evicted {
+            model: "logos".to_string(),
+            memory,
+            for_model: "ethos".to_string(),
+        });
+
+        let snap = metrics.snapshot();
+        assert_eq!(snap.models_loaded, 2);
+        assert_eq!(snap.models_evicted, 1);
+        assert_eq!(snap.model_memory_bytes, 4000);
+        assert!(metrics.to_prometheus().contains("synesis_models_evicted 1"));
+    }
+
+    #[test]
+    fn test_prefix_cache_events() {
+        let metrics = Metrics::new();
+        let observer = metrics.pool_observer();
+
+        for cached_tokens in [0, 96, 128] {
+            observer(&PoolEvent::Prompt {
+                model: "logos".to_string(),
+                prompt_tokens: 140,
+                cached_tokens,
+            });
+        }
+
+        let snap = metrics.snapshot();
+        assert_eq!(snap.prefix_cache_hits, 2);
+        assert_eq!(snap.prefix_cache_misses, 1);
+        assert_eq!(snap.prefix_cache_tokens_reused, 224);
+        assert!(metrics.to_prometheus().contains("synesis_prefix_cache_hits 2"));
+    }
+
+    /// A GGUF header with no vocabulary and, beside it, a byte-fallback
+    /// `tokenizer.json`, so every prompt byte is one token
+    fn write_byte_model(dir: &std::path::Path) -> std::path::PathBuf {
+        let put_string = |out: &mut Vec<u8>, s: &str| {
+            out.extend_from_slice(&(s.len() as u64).to_le_bytes());
+            out.extend_from_slice(s.as_bytes());
+        };
+        let mut gguf = b"GGUF".to_vec();
+        gguf.extend_from_slice(&3u32.to_le_bytes());
+        gguf.extend_from_slice(&0u64.to_le_bytes());
+        gguf.extend_from_slice(&1u64.to_le_bytes());
+        put_string(&mut gguf, "general.architecture");
+        gguf.extend_from_slice(&8u32.to_le_bytes());
+        put_string(&mut gguf, "llama");
+        gguf.resize(gguf.len().div_ceil(32) * 32, 0);
+        let path = dir.join("ethos.gguf");
+        std::fs::write(&path, gguf).unwrap();
+
+        let mut vocab = serde_json::Map::new();
+        vocab.insert("<unk>".to_string(), 0.into());
+        for byte in 0..=255u32 {
+            vocab.insert(format!("<0x{:02X}>", byte), (byte + 1).into());
+        }
+        let tokenizer = serde_json::json!({
+            "model": {"type": "BPE", "byte_fallback": true, "vocab": vocab, "merges": []}
+        });
+        std::fs::write(dir.join("tokenizer.json"), tokenizer.to_string()).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_prefix_cache_across_consensus_rounds() {
+        use synesis_models::prefix_cache::PREFIX_BLOCK_TOKENS;
+        use synesis_models::{
+            ChatMessage, ChatTemplate, InferenceRequest, MemoryBudget, ModelPool,
+            RequestOptions, Scheduler, SchedulerConfig, Tokenizer,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_byte_model(dir.path());
+        let metrics = Metrics::new();
+        let pool = Arc::new(
+            ModelPool::new(MemoryBudget::unlimited()).with_event_callback(metrics.pool_observer()),
+        );
+        pool.add("ethos".to_string(), path).await.unwrap();
+        pool.load("ethos").await.unwrap();
+        let scheduler = Scheduler::new(pool, SchedulerConfig::default());
+
+        // Round two repeats round one's conversation and appends feedback
+        let mut messages = vec![
+            ChatMessage::system("You are Ethos. Review the proposed solution."),
+            ChatMessage::user("## Query\nHow do I list files?\n\n## Proposed Solution\nRun ls."),
+        ];
+        let round_one = ChatTemplate::Phi3.render(&messages, true);
+        messages.push(ChatMessage::assistant("NeedsRevision"));
+        messages.push(ChatMessage::user("Feedback: mention hidden files."));
+        let round_two = ChatTemplate::Phi3.render(&messages, true);
+
+        for prompt in [&round_one, &round_two] {
+            scheduler
+                .infer(
+                    "ethos",
+                    InferenceRequest::new(prompt.clone()),
+                    None,
+                    RequestOptions::interactive(),
+                )
+                .await
+                .unwrap();
+        }
+
+        // Round two reuses every complete block of round one
+        let tokenizer = Tokenizer::from_tokenizer_json(&dir.path().join("tokenizer.json")).unwrap();
+        let reused = tokenizer.encode(&round_one).len() / PREFIX_BLOCK_TOKENS * PREFIX_BLOCK_TOKENS;
+        assert!(reused > 0);
+
+        let snap = metrics.snapshot();
+        assert_eq!(snap.prefix_cache_hits, 1);
+        assert_eq!(snap.prefix_cache_misses, 1);
+        assert_eq!(snap.prefix_cache_tokens_reused, reused as u64);
+        let prom = metrics.to_prometheus();
+        assert!(prom.contains("synesis_prefix_cache_hits 1"));
+        assert!(prom.contains(&format!("synesis_prefix_cache_tokens_reused {}", reused)));
+    }
+
     /// Thread Safety Test 1: Concurrent increments
     ///
     /// Verify that atomic operations are truly thread-safe by spawning
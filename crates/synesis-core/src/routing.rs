@@ -3,7 +3,10 @@
 //! Determines whether a query should be processed locally or escalated to cloud.
 //! Uses various heuristics and can be trained over time.
 
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
+use synesis_models::{EstimatedTokens, TokenCounter};
 use tracing::{debug, instrument};
 
 use crate::manifest::A2AManifest;
@@ -54,12 +57,27 @@ impl Default for RouterConfig {
 /// Query router
 pub struct Router {
     config: RouterConfig,
+    token_counter: Arc<dyn TokenCounter>,
 }
 
 impl Router {
     /// Create a new router
+    ///
+    /// Query lengths are estimated at ~4 characters per token until a
+    /// model's tokenizer is set with [`with_token_counter`].
+    ///
+    /// [`with_token_counter`]: Self::with_token_counter
     pub fn new(config: RouterConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            token_counter: Arc::new(EstimatedTokens),
+        }
+    }
+
+    /// Count query tokens with the local model's tokenizer
+    pub fn with_token_counter(mut self, counter: Arc<dyn TokenCounter>) -> Self {
+        self.token_counter = counter;
+        self
     }
 
     /// Route a query
@@ -88,7 +106,7 @@ impl Router {
         let mut cloud_score = 0.0f32;
 
         // Factor 1: Query length
-        let query_tokens = estimate_tokens(&manifest.query);
+        let query_tokens = self.count_tokens(&manifest.query);
         if query_tokens > self.config.max_local_tokens {
             cloud_score += 0.4;
             factors.push(format!(
@@ -170,6 +188,12 @@ impl Router {
         }
     }
 
+    /// Count tokens in text
+    fn count_tokens(&self, text: &str) -> u32 {
+        // Cap at reasonable maximum (1M tokens) to prevent overflow
+        self.token_counter.count_tokens(text).min(1_000_000) as u32
+    }
+
     /// Check if escalation is recommended during processing
     pub fn should_escalate(&self, manifest: &A2AManifest, current_tokens: u32) -> bool {
         if self.config.force_local {
@@ -190,13 +214,6 @@ impl Router {
     }
 }
 
-/// Estimate token count (rough approximation)
-fn estimate_tokens(text: &str) -> u32 {
-    // Rough estimate: ~4 characters per token for English
-    // Cap at reasonable maximum (1M tokens) to prevent overflow
-    (text.len() / 4).min(1_000_000) as u32
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,6 +244,31 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_token_counter() {
+        /// One token per character
+        struct PerChar;
+        impl TokenCounter for PerChar {
+            fn count_tokens(&self, text: &str) -> usize {
+                text.chars().count()
+            }
+        }
+
+        let config = RouterConfig {
+            max_local_tokens: 100,
+            ..Default::default()
+        };
+        let manifest = A2AManifest::new("x".repeat(200));
+
+        // ~50 estimated tokens fit, 200 counted tokens do not
+        let estimated = Router::new(config.clone()).route(&manifest);
+        assert_eq!(estimated.decision, RoutingDecision::Local);
+        let counted = Router::new(config)
+            .with_token_counter(Arc::new(PerChar))
+            .route(&manifest);
+        assert!(counted.factors[0].contains("200 tokens"));
+    }
+
     #[test]
     fn test_force_local() {
         let router = Router::new(RouterConfig {
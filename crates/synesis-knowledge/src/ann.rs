@@ -0,0 +1,758 @@
+//! Approximate Nearest Neighbour Index
+//!
+//! A pure-Rust HNSW (Hierarchical Navigable Small World) graph over chunk
+//! embeddings, used by [`KnowledgeVault::search`](crate::KnowledgeVault::search)
+//! instead of scanning every embedding.
+//!
+//! ## Design
+//!
+//! - Vectors are L2-normalized on insert; similarity is the dot product
+//!   (cosine similarity)
+//! - Node levels are derived from a hash of the chunk ID, so rebuilding the
+//!   same vault produces the same graph
+//! - Removal leaves a tombstone: the node still routes searches but is never
+//!   returned. [`HnswIndex::needs_compaction`] reports when a rebuild pays off
+//!
+//! ## Persistence
+//!
+//! The index is saved next to the vault database (`knowledge.hnsw` for
+//! `knowledge.db`) in a little-endian binary format. Each file records the
+//! vault generation it was saved at; the vault rebuilds the index from
+//! SQLite when the numbers disagree (e.g. after a crash).
+
+use sha2::{Digest, Sha256};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::{KnowledgeError, KnowledgeResult};
+
+/// File magic for persisted indexes
+const MAGIC: &[u8; 8] = b"SYNHNSW\0";
+
+/// Persisted format version
+const FORMAT_VERSION: u32 = 1;
+
+/// Highest level a node can be assigned
+const MAX_LEVEL: usize = 16;
+
+/// HNSW construction and search parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HnswParams {
+    /// Neighbours per node on upper layers (layer 0 keeps twice as many)
+    pub m: usize,
+    /// Candidate list size while inserting
+    pub ef_construction: usize,
+    /// Candidate list size while searching (raised to `k` if smaller)
+    pub ef_search: usize,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 200,
+            ef_search: 64,
+        }
+    }
+}
+
+/// Candidate ordered by similarity
+#[derive(Debug, Clone, Copy)]
+struct Scored {
+    similarity: f32,
+    node: u32,
+}
+
+impl PartialEq for Scored {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.similarity
+            .total_cmp(&other.similarity)
+            .then_with(|| self.node.cmp(&other.node))
+    }
+}
+
+/// HNSW index over normalized vectors keyed by chunk ID
+#[derive(Debug, Clone)]
+pub struct HnswIndex {
+    dimensions: usize,
+    params: HnswParams,
+    /// Vault generation this index reflects
+    generation: u64,
+    ids: Vec<String>,
+    id_to_node: HashMap<String, u32>,
+    /// Flat storage: node `i` occupies `vectors[i * dimensions..]`
+    vectors: Vec<f32>,
+    /// `links[node][layer]` = neighbour nodes
+    links: Vec<Vec<Vec<u32>>>,
+    deleted: Vec<bool>,
+    live: usize,
+    entry_point: Option<u32>,
+    max_level: usize,
+}
+
+impl HnswIndex {
+    /// Create an empty index
+    pub fn new(dimensions: usize, params: HnswParams) -> Self {
+        Self {
+            dimensions,
+            params,
+            generation: 0,
+            ids: Vec::new(),
+            id_to_node: HashMap::new(),
+            vectors: Vec::new(),
+            links: Vec::new(),
+            deleted: Vec::new(),
+            live: 0,
+            entry_point: None,
+            max_level: 0,
+        }
+    }
+
+    /// Vector dimensions
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Number of searchable vectors
+    pub fn len(&self) -> usize {
+        self.live
+    }
+
+    /// Whether the index has no searchable vectors
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+
+    /// Number of removed nodes still in the graph
+    pub fn tombstones(&self) -> usize {
+        self.ids.len() - self.live
+    }
+
+    /// Whether tombstones outnumber live nodes (rebuild recommended)
+    pub fn needs_compaction(&self) -> bool {
+        self.tombstones() > self.live.max(64)
+    }
+
+    /// Vault generation this index reflects
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Record the vault generation this index reflects
+    pub fn set_generation(&mut self, generation: u64) {
+        self.generation = generation;
+    }
+
+    /// Whether a chunk is indexed
+    pub fn contains(&self, id: &str) -> bool {
+        self.id_to_node.contains_key(id)
+    }
+
+    /// Insert or replace a vector
+    ///
+    /// # Errors
+    /// Returns error if the vector has the wrong number of dimensions.
+    pub fn insert(&mut self, id: &str, vector: &[f32]) -> KnowledgeResult<()> {
+        if vector.len() != self.dimensions {
+            return Err(KnowledgeError::InvalidFormat(format!(
+                "Embedding has {} dimensions, index expects {}",
+                vector.len(),
+                self.dimensions
+            )));
+        }
+
+        self.remove(id);
+
+        let node = self.ids.len() as u32;
+        let level = self.level_for(id);
+        self.ids.push(id.to_string());
+        self.id_to_node.insert(id.to_string(), node);
+        self.vectors.extend(normalized(vector));
+        self.links.push(vec![Vec::new(); level + 1]);
+        self.deleted.push(false);
+        self.live += 1;
+
+        let Some(mut entry) = self.entry_point else {
+            self.entry_point = Some(node);
+            self.max_level = level;
+            return Ok(());
+        };
+
+        let query = self.vector(node).to_vec();
+
+        // Greedy descent through layers above the new node's level
+        for layer in (level + 1..=self.max_level).rev() {
+            entry = self.greedy_closest(&query, entry, layer);
+        }
+
+        for layer in (0..=level.min(self.max_level)).rev() {
+            let candidates =
+                self.search_layer(&query, &[entry], self.params.ef_construction, layer);
+            let neighbours: Vec<u32> = candidates
+                .iter()
+                .take(self.max_links(layer))
+                .map(|c| c.node)
+                .collect();
+
+            for &neighbour in &neighbours {
+                self.links[neighbour as usize][layer].push(node);
+                self.prune(neighbour, layer);
+            }
+            self.links[node as usize][layer] = neighbours;
+
+            if let Some(best) = candidates.first() {
+                entry = best.node;
+            }
+        }
+
+        if level > self.max_level {
+            self.max_level = level;
+            self.entry_point = Some(node);
+        }
+
+        Ok(())
+    }
+
+    /// Remove a vector (leaves a tombstone)
+    ///
+    /// Returns `true` if the ID was indexed.
+    pub fn remove(&mut self, id: &str) -> bool {
+        match self.id_to_node.remove(id) {
+            Some(node) => {
+                self.deleted[node as usize] = true;
+                self.live -= 1;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Find the `k` most similar vectors
+    ///
+    /// Returns `(chunk_id, cosine_similarity)` pairs, best first.
+    pub fn search(&self, query: &[f32], k: usize) -> Vec<(String, f32)> {
+        let Some(mut entry) = self.entry_point else {
+            return vec![];
+        };
+        if k == 0 || query.len() != self.dimensions || self.live == 0 {
+            return vec![];
+        }
+
+        let query = normalized(query);
+        for layer in (1..=self.max_level).rev() {
+            entry = self.greedy_closest(&query, entry, layer);
+        }
+
+        // Widen the beam to make up for tombstones that can't be returned
+        let ef = self.params.ef_search.max(k) + self.tombstones().min(k * 4);
+        self.search_layer(&query, &[entry], ef, 0)
+            .into_iter()
+            .filter(|c| !self.deleted[c.node as usize])
+            .take(k)
+            .map(|c| (self.ids[c.node as usize].clone(), c.similarity))
+            .collect()
+    }
+
+    /// Live entries as `(chunk_id, normalized vector)`
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &[f32])> {
+        (0..self.ids.len())
+            .filter(|&i| !self.deleted[i])
+            .map(|i| (self.ids[i].as_str(), self.vector(i as u32)))
+    }
+
+    /// Rebuild the graph without tombstones
+    pub fn compacted(&self) -> Self {
+        let mut index = Self::new(self.dimensions, self.params);
+        index.generation = self.generation;
+        for (id, vector) in self.entries() {
+            // Dimensions always match: the vector came from this index
+            let _ = index.insert(id, vector);
+        }
+        index
+    }
+
+    /// Save the index to a file (written atomically)
+    pub fn save(&self, path: &Path) -> KnowledgeResult<()> {
+        let tmp = path.with_extension("hnsw.tmp");
+        {
+            let mut w = BufWriter::new(std::fs::File::create(&tmp)?);
+            w.write_all(MAGIC)?;
+            write_u32(&mut w, FORMAT_VERSION)?;
+            write_u32(&mut w, self.dimensions as u32)?;
+            write_u32(&mut w, self.params.m as u32)?;
+            write_u32(&mut w, self.params.ef_construction as u32)?;
+            write_u32(&mut w, self.params.ef_search as u32)?;
+            w.write_all(&self.generation.to_le_bytes())?;
+            write_u32(&mut w, self.entry_point.unwrap_or(u32::MAX))?;
+            write_u32(&mut w, self.max_level as u32)?;
+            write_u32(&mut w, self.ids.len() as u32)?;
+
+            for node in 0..self.ids.len() {
+                let id = self.ids[node].as_bytes();
+                write_u32(&mut w, id.len() as u32)?;
+                w.write_all(id)?;
+                w.write_all(&[self.deleted[node] as u8])?;
+                for value in self.vector(node as u32) {
+                    w.write_all(&value.to_le_bytes())?;
+                }
+                write_u32(&mut w, self.links[node].len() as u32)?;
+                for layer in &self.links[node] {
+                    write_u32(&mut w, layer.len() as u32)?;
+                    for &neighbour in layer {
+                        write_u32(&mut w, neighbour)?;
+                    }
+                }
+            }
+            w.flush()?;
+        }
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Load an index saved with [`save`](Self::save)
+    ///
+    /// Lengths read from the file are checked against what is left of it
+    /// before anything is allocated, so a corrupt file fails cleanly.
+    ///
+    /// # Errors
+    /// Returns error if the file is missing, truncated or not an index, or
+    /// if its vectors do not have `dimensions` dimensions.
+    pub fn load(path: &Path, dimensions: usize) -> KnowledgeResult<Self> {
+        let file = std::fs::File::open(path)?;
+        let len = file.metadata()?.len();
+        // `limit()` is the number of bytes left in the file
+        let mut r = BufReader::new(file).take(len);
+        let invalid = |what: &str| KnowledgeError::InvalidFormat(format!("ANN index: {}", what));
+
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("bad magic"));
+        }
+        if read_u32(&mut r)? != FORMAT_VERSION {
+            return Err(invalid("unsupported version"));
+        }
+
+        let stored_dimensions = read_u32(&mut r)? as usize;
+        if stored_dimensions != dimensions {
+            return Err(invalid(&format!(
+                "{} dimensions, expected {}",
+                stored_dimensions, dimensions
+            )));
+        }
+        let params = HnswParams {
+            m: read_u32(&mut r)? as usize,
+            ef_construction: read_u32(&mut r)? as usize,
+            ef_search: read_u32(&mut r)? as usize,
+        };
+        let mut generation = [0u8; 8];
+        r.read_exact(&mut generation)?;
+        let entry_point = match read_u32(&mut r)? {
+            u32::MAX => None,
+            e => Some(e),
+        };
+        let max_level = read_u32(&mut r)? as usize;
+        if max_level > MAX_LEVEL {
+            return Err(invalid("bad max level"));
+        }
+        let count = read_u32(&mut r)? as usize;
+        // ID length, deleted flag, vector, layer count and one layer length
+        let min_node_bytes = 4 + 1 + dimensions as u64 * 4 + 4 + 4;
+        if count as u64 * min_node_bytes > r.limit() {
+            return Err(invalid("node count exceeds file size"));
+        }
+
+        let mut index = Self::new(dimensions, params);
+        index.generation = u64::from_le_bytes(generation);
+        index.entry_point = entry_point;
+        index.max_level = max_level;
+
+        for node in 0..count {
+            let id_len = read_u32(&mut r)? as usize;
+            if id_len as u64 > r.limit() {
+                return Err(invalid("chunk ID length exceeds file size"));
+            }
+            let mut id = vec![0u8; id_len];
+            r.read_exact(&mut id)?;
+            let id = String::from_utf8(id).map_err(|_| invalid("chunk ID is not UTF-8"))?;
+
+            let mut deleted = [0u8; 1];
+            r.read_exact(&mut deleted)?;
+            let deleted = deleted[0] != 0;
+
+            let mut vector = vec![0u8; dimensions * 4];
+            r.read_exact(&mut vector)?;
+            index.vectors.extend(
+                vector
+                    .as_chunks::<4>()
+                    .0
+                    .iter()
+                    .map(|chunk| f32::from_le_bytes(*chunk)),
+            );
+
+            let layers = read_u32(&mut r)? as usize;
+            if layers == 0 || layers > MAX_LEVEL + 1 {
+                return Err(invalid("bad layer count"));
+            }
+            let mut links = Vec::with_capacity(layers);
+            for _ in 0..layers {
+                let len = read_u32(&mut r)? as usize;
+                if len as u64 * 4 > r.limit() {
+                    return Err(invalid("layer length exceeds file size"));
+                }
+                let layer = (0..len)
+                    .map(|_| read_u32(&mut r))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if layer.iter().any(|&n| n as usize >= count) {
+                    return Err(invalid("neighbour out of range"));
+                }
+                links.push(layer);
+            }
+
+            if !deleted {
+                index.id_to_node.insert(id.clone(), node as u32);
+                index.live += 1;
+            }
+            index.ids.push(id);
+            index.deleted.push(deleted);
+            index.links.push(links);
+        }
+
+        if entry_point.is_some_and(|e| e as usize >= count) {
+            return Err(invalid("entry point out of range"));
+        }
+
+        Ok(index)
+    }
+
+    fn vector(&self, node: u32) -> &[f32] {
+        let start = node as usize * self.dimensions;
+        &self.vectors[start..start + self.dimensions]
+    }
+
+    fn similarity(&self, query: &[f32], node: u32) -> f32 {
+        query
+            .iter()
+            .zip(self.vector(node))
+            .map(|(a, b)| a * b)
+            .sum()
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.params.m * 2
+        } else {
+            self.params.m
+        }
+    }
+
+    /// Deterministic level from the chunk ID (exponential distribution)
+    fn level_for(&self, id: &str) -> usize {
+        let digest = Sha256::digest(id.as_bytes());
+        let bits = u64::from_le_bytes(digest[..8].try_into().unwrap_or([0; 8]));
+        // Uniform in (0, 1]
+        let uniform = ((bits >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+        let scale = 1.0 / (self.params.m.max(2) as f64).ln();
+        ((-uniform.ln() * scale) as usize).min(MAX_LEVEL)
+    }
+
+    /// Walk a layer greedily towards the query
+    fn greedy_closest(&self, query: &[f32], entry: u32, layer: usize) -> u32 {
+        let mut best = entry;
+        let mut best_similarity = self.similarity(query, entry);
+
+        loop {
+            let mut improved = false;
+            for &neighbour in self.neighbours(best, layer) {
+                let similarity = self.similarity(query, neighbour);
+                if similarity > best_similarity {
+                    best = neighbour;
+                    best_similarity = similarity;
+                    improved = true;
+                }
+            }
+            if !improved {
+                return best;
+            }
+        }
+    }
+
+    fn neighbours(&self, node: u32, layer: usize) -> &[u32] {
+        self.links[node as usize]
+            .get(layer)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Beam search on one layer; returns candidates best first
+    fn search_layer(&self, query: &[f32], entries: &[u32], ef: usize, layer: usize) -> Vec<Scored> {
+        let mut visited: HashSet<u32> = entries.iter().copied().collect();
+        // Max-heap of candidates to expand, min-heap of current results
+        let mut candidates: BinaryHeap<Scored> = BinaryHeap::new();
+        let mut results: BinaryHeap<Reverse<Scored>> = BinaryHeap::new();
+
+        for &node in entries {
+            let scored = Scored {
+                similarity: self.similarity(query, node),
+                node,
+            };
+            candidates.push(scored);
+            results.push(Reverse(scored));
+        }
+
+        while let Some(current) = candidates.pop() {
+            let worst = results.peek().map(|r| r.0.similarity).unwrap_or(f32::MIN);
+            if current.similarity < worst && results.len() >= ef {
+                break;
+            }
+
+            for &neighbour in self.neighbours(current.node, layer) {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+                let scored = Scored {
+                    similarity: self.similarity(query, neighbour),
+                    node: neighbour,
+                };
+                let worst = results.peek().map(|r| r.0.similarity).unwrap_or(f32::MIN);
+                if results.len() < ef || scored.similarity > worst {
+                    candidates.push(scored);
+                    results.push(Reverse(scored));
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+
+        let mut found: Vec<Scored> = results.into_iter().map(|r| r.0).collect();
+        found.sort_by(|a, b| b.cmp(a));
+        found
+    }
+
+    /// Keep only the closest neighbours of a node on a layer
+    fn prune(&mut self, node: u32, layer: usize) {
+        let max = self.max_links(layer);
+        if self.links[node as usize][layer].len() <= max {
+            return;
+        }
+
+        let base = self.vector(node).to_vec();
+        let mut scored: Vec<Scored> = self.links[node as usize][layer]
+            .iter()
+            .map(|&n| Scored {
+                similarity: self.similarity(&base, n),
+                node: n,
+            })
+            .collect();
+        scored.sort_by(|a, b| b.cmp(a));
+        self.links[node as usize][layer] = scored.into_iter().take(max).map(|s| s.node).collect();
+    }
+}
+
+fn normalized(vector: &[f32]) -> Vec<f32> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return vector.to_vec();
+    }
+    vector.iter().map(|x| x / norm).collect()
+}
+
+fn write_u32<W: Write>(w: &mut W, value: u32) -> std::io::Result<()> {
+    w.write_all(&value.to_le_bytes())
+}
+
+fn read_u32<R: Read>(r: &mut R) -> std::io::Result<u32> {
+    let mut buf = [0u8; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random vectors (xorshift)
+    fn random_vectors(count: usize, dimensions: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut state = seed.max(1);
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+        };
+        (0..count)
+            .map(|_| (0..dimensions).map(|_| next()).collect())
+            .collect()
+    }
+
+    fn brute_force(vectors: &[Vec<f32>], query: &[f32], k: usize) -> Vec<usize> {
+        let query = normalized(query);
+        let mut scored: Vec<(usize, f32)> = vectors
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                (
+                    i,
+                    normalized(v).iter().zip(&query).map(|(a, b)| a * b).sum(),
+                )
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.into_iter().take(k).map(|(i, _)| i).collect()
+    }
+
+    fn recall(index: &HnswIndex, vectors: &[Vec<f32>], queries: &[Vec<f32>], k: usize) -> f32 {
+        let mut hits = 0;
+        for query in queries {
+            let expected: HashSet<String> = brute_force(vectors, query, k)
+                .into_iter()
+                .map(|i| format!("chunk_{}", i))
+                .collect();
+            hits += index
+                .search(query, k)
+                .iter()
+                .filter(|(id, _)| expected.contains(id))
+                .count();
+        }
+        hits as f32 / (queries.len() * k) as f32
+    }
+
+    fn build(vectors: &[Vec<f32>]) -> HnswIndex {
+        let mut index = HnswIndex::new(vectors[0].len(), HnswParams::default());
+        for (i, v) in vectors.iter().enumerate() {
+            index.insert(&format!("chunk_{}", i), v).unwrap();
+        }
+        index
+    }
+
+    #[test]
+    fn test_recall_against_brute_force() {
+        let vectors = random_vectors(2000, 32, 7);
+        let queries = random_vectors(50, 32, 99);
+        let index = build(&vectors);
+
+        assert_eq!(index.len(), 2000);
+        let recall = recall(&index, &vectors, &queries, 10);
+        assert!(recall >= 0.95, "recall@10 = {}", recall);
+    }
+
+    #[test]
+    fn test_exact_match_ranks_first() {
+        let vectors = random_vectors(500, 16, 3);
+        let index = build(&vectors);
+
+        let results = index.search(&vectors[42], 3);
+        assert_eq!(results[0].0, "chunk_42");
+        assert!((results[0].1 - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_remove_and_replace() {
+        let vectors = random_vectors(300, 16, 11);
+        let mut index = build(&vectors);
+
+        assert!(index.remove("chunk_5"));
+        assert!(!index.remove("chunk_5"));
+        assert_eq!(index.len(), 299);
+        assert_eq!(index.tombstones(), 1);
+        assert!(index
+            .search(&vectors[5], 10)
+            .iter()
+            .all(|(id, _)| id != "chunk_5"));
+
+        // Re-inserting an ID replaces its vector
+        index.insert("chunk_6", &vectors[7]).unwrap();
+        let results = index.search(&vectors[7], 2);
+        let ids: HashSet<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert!(ids.contains("chunk_6") && ids.contains("chunk_7"));
+        assert_eq!(index.len(), 299);
+
+        let compacted = index.compacted();
+        assert_eq!(compacted.len(), 299);
+        assert_eq!(compacted.tombstones(), 0);
+        assert_eq!(compacted.search(&vectors[9], 1)[0].0, "chunk_9");
+    }
+
+    #[test]
+    fn test_dimension_mismatch() {
+        let mut index = HnswIndex::new(4, HnswParams::default());
+        assert!(index.insert("a", &[1.0, 0.0]).is_err());
+        assert!(index.search(&[1.0, 0.0], 1).is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("knowledge.hnsw");
+        let vectors = random_vectors(400, 24, 5);
+        let queries = random_vectors(10, 24, 6);
+
+        let mut index = build(&vectors);
+        index.remove("chunk_0");
+        index.set_generation(17);
+        index.save(&path).unwrap();
+
+        let loaded = HnswIndex::load(&path, 24).unwrap();
+        assert_eq!(loaded.generation(), 17);
+        assert_eq!(loaded.len(), index.len());
+        assert!(!loaded.contains("chunk_0"));
+        for query in &queries {
+            assert_eq!(loaded.search(query, 5), index.search(query, 5));
+        }
+
+        assert!(HnswIndex::load(&path, 32).is_err());
+        std::fs::write(&path, b"not an index").unwrap();
+        assert!(HnswIndex::load(&path, 24).is_err());
+    }
+
+    #[test]
+    fn test_load_rejects_corrupt_lengths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("knowledge.hnsw");
+        build(&random_vectors(20, 8, 7)).save(&path).unwrap();
+        let saved = std::fs::read(&path).unwrap();
+
+        // Header: magic, version, dimensions, m, ef_construction, ef_search,
+        // generation, entry point, max level, node count
+        let count_at = 8 + 4 * 5 + 8 + 4 + 4;
+        let first_id_at = count_at + 4;
+        let corrupt = |at: usize, value: u32| {
+            let mut bytes = saved.clone();
+            bytes[at..at + 4].copy_from_slice(&value.to_le_bytes());
+            std::fs::write(&path, bytes).unwrap();
+            HnswIndex::load(&path, 8)
+        };
+
+        assert!(matches!(corrupt(12, 8), Ok(index) if index.len() == 20));
+        for (at, value) in [
+            (12, u32::MAX),
+            (count_at, u32::MAX),
+            (first_id_at, u32::MAX),
+        ] {
+            assert!(matches!(
+                corrupt(at, value),
+                Err(KnowledgeError::InvalidFormat(_))
+            ));
+        }
+
+        std::fs::write(&path, &saved[..saved.len() - 3]).unwrap();
+        assert!(HnswIndex::load(&path, 8).is_err());
+    }
+}
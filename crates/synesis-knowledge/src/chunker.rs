@@ -4,8 +4,13 @@
 //! Supports various chunking strategies.
 
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use synesis_models::TokenCounter;
 use tracing::{debug, instrument};
 
+use crate::extract::{ExtractedText, SourceFormat};
+use crate::syntax::{self, CodeLanguage, CodeSymbol};
 use crate::KnowledgeResult;
 
 /// A chunk of text from a document
@@ -17,10 +22,34 @@ pub struct Chunk {
     pub start_offset: u64,
     /// End offset in original document
     pub end_offset: u64,
-    /// Estimated token count
+    /// Token count, from the chunker's tokenizer or estimated
     pub token_count: u32,
     /// Chunk index within document
     pub index: u32,
+    /// Where the chunk came from (code symbol, page or section)
+    #[serde(default)]
+    pub location: ChunkLocation,
+}
+
+/// Where a chunk came from in its source document
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkLocation {
+    /// Symbol defined by a syntax-aware code chunk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<CodeSymbol>,
+    /// Page the chunk starts on (PDF)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    /// Heading path of the enclosing section (HTML, DOCX)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+}
+
+impl ChunkLocation {
+    /// Whether nothing is known about the chunk's location
+    pub fn is_empty(&self) -> bool {
+        self.symbol.is_none() && self.page.is_none() && self.section.is_none()
+    }
 }
 
 /// Chunking options
@@ -53,19 +82,101 @@ impl Default for ChunkOptions {
 /// Document chunker
 pub struct Chunker {
     options: ChunkOptions,
+    /// Measures chunk sizes; [`estimate_tokens`] when unset
+    token_counter: Option<Arc<dyn TokenCounter>>,
 }
 
 impl Chunker {
     /// Create a new chunker with default options
     pub fn new() -> Self {
-        Self {
-            options: ChunkOptions::default(),
-        }
+        Self::with_options(ChunkOptions::default())
     }
 
     /// Create a new chunker with custom options
     pub fn with_options(options: ChunkOptions) -> Self {
-        Self { options }
+        Self {
+            options,
+            token_counter: None,
+        }
+    }
+
+    /// Measure chunk sizes with a model's tokenizer instead of estimating
+    pub fn with_token_counter(mut self, counter: Arc<dyn TokenCounter>) -> Self {
+        self.token_counter = Some(counter);
+        self
+    }
+
+    /// Count tokens in text, at least 1
+    fn count_tokens(&self, text: &str) -> u32 {
+        match &self.token_counter {
+            Some(counter) => u32::try_from(counter.count_tokens(text))
+                .unwrap_or(u32::MAX)
+                .max(1),
+            None => estimate_tokens(text),
+        }
+    }
+
+    /// Chunk a file, splitting supported source code along its syntax tree
+    ///
+    /// Code chunks keep whole definitions with their [`CodeSymbol`]; other
+    /// files (and all files without the `tree-sitter` feature) fall back to
+    /// [`Self::chunk`].
+    #[instrument(skip(self, text))]
+    pub fn chunk_source(&self, text: &str, path: &Path) -> KnowledgeResult<Vec<Chunk>> {
+        let code_chunks = CodeLanguage::from_path(path).and_then(|language| {
+            // Same ~4 bytes per token as `estimate_tokens`
+            syntax::chunk_code(text, language, self.options.chunk_size as usize * 4)
+        });
+        let Some(code_chunks) = code_chunks else {
+            return self.chunk(text);
+        };
+
+        debug!("Chunked {:?} into {} definitions", path, code_chunks.len());
+        Ok(code_chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, chunk)| Chunk {
+                token_count: self.count_tokens(&chunk.content),
+                content: chunk.content,
+                start_offset: chunk.start_offset as u64,
+                end_offset: chunk.end_offset as u64,
+                index: i as u32,
+                location: ChunkLocation {
+                    symbol: chunk.symbol,
+                    ..Default::default()
+                },
+            })
+            .collect())
+    }
+
+    /// Chunk extracted document text, recording each chunk's page/section
+    ///
+    /// Pages and sections are chunked separately (small neighbours are
+    /// grouped, see [`ExtractedText::spans`]) so every chunk has one
+    /// location. Plain text goes through [`Self::chunk_source`].
+    #[instrument(skip(self, source))]
+    pub fn chunk_extracted(&self, source: &ExtractedText, path: &Path) -> KnowledgeResult<Vec<Chunk>> {
+        if source.format == SourceFormat::Text {
+            return self.chunk_source(&source.text, path);
+        }
+
+        let mut chunks = Vec::new();
+        for span in source.spans(self.options.chunk_size as usize * 4) {
+            for mut chunk in self.chunk(span.text)? {
+                chunk.start_offset += span.start_offset as u64;
+                chunk.end_offset += span.start_offset as u64;
+                chunk.index = chunks.len() as u32;
+                chunk.location = ChunkLocation {
+                    page: span.page,
+                    section: span.section.map(str::to_string),
+                    ..Default::default()
+                };
+                chunks.push(chunk);
+            }
+        }
+
+        debug!("Chunked {:?} into {} chunks", path, chunks.len());
+        Ok(chunks)
     }
 
     /// Chunk a document
@@ -97,11 +208,11 @@ impl Chunker {
         let mut chunk_index: u32 = 0;
 
         for paragraph in text.split("\n\n") {
-            let para_tokens = estimate_tokens(paragraph);
+            let para_tokens = self.count_tokens(paragraph);
 
             // If adding this paragraph exceeds chunk size, save current and start new
             if !current_chunk.is_empty()
-                && estimate_tokens(&current_chunk) + para_tokens > self.options.chunk_size
+                && self.count_tokens(&current_chunk) + para_tokens > self.options.chunk_size
             {
                 chunks.push(self.create_chunk(&current_chunk, current_start, chunk_index));
                 chunk_index += 1;
@@ -130,7 +241,7 @@ impl Chunker {
         // If we have no chunks yet, always create one even if small
         // Otherwise, only create if it meets min_chunk_size
         if !current_chunk.is_empty()
-            && (chunks.is_empty() || estimate_tokens(&current_chunk) >= self.options.min_chunk_size)
+            && (chunks.is_empty() || self.count_tokens(&current_chunk) >= self.options.min_chunk_size)
         {
             chunks.push(self.create_chunk(&current_chunk, current_start, chunk_index));
         }
@@ -146,10 +257,10 @@ impl Chunker {
         let mut chunk_index: u32 = 0;
 
         for sentence in split_sentences(text) {
-            let sentence_tokens = estimate_tokens(sentence);
+            let sentence_tokens = self.count_tokens(sentence);
 
             if !current_chunk.is_empty()
-                && estimate_tokens(&current_chunk) + sentence_tokens > self.options.chunk_size
+                && self.count_tokens(&current_chunk) + sentence_tokens > self.options.chunk_size
             {
                 chunks.push(self.create_chunk(&current_chunk, current_start, chunk_index));
                 chunk_index += 1;
@@ -174,7 +285,7 @@ impl Chunker {
 
         // Same logic as paragraph chunking
         if !current_chunk.is_empty()
-            && (chunks.is_empty() || estimate_tokens(&current_chunk) >= self.options.min_chunk_size)
+            && (chunks.is_empty() || self.count_tokens(&current_chunk) >= self.options.min_chunk_size)
         {
             chunks.push(self.create_chunk(&current_chunk, current_start, chunk_index));
         }
@@ -212,7 +323,7 @@ impl Chunker {
     /// Create a chunk from content
     fn create_chunk(&self, content: &str, start_offset: u64, index: u32) -> Chunk {
         let content = content.trim().to_string();
-        let token_count = estimate_tokens(&content);
+        let token_count = self.count_tokens(&content);
 
         Chunk {
             end_offset: start_offset + content.len() as u64,
@@ -220,6 +331,7 @@ impl Chunker {
             content,
             token_count,
             index,
+            location: ChunkLocation::default(),
         }
     }
 
@@ -232,7 +344,7 @@ impl Chunker {
         let mut tokens = 0;
 
         for sentence in sentences.into_iter().rev() {
-            let sentence_tokens = estimate_tokens(sentence);
+            let sentence_tokens = self.count_tokens(sentence);
             if tokens + sentence_tokens > target_tokens && !overlap.is_empty() {
                 break;
             }
@@ -289,6 +401,8 @@ pub fn detect_document_type(filename: &str) -> &'static str {
         "yaml" | "yml" => "yaml",
         "toml" => "toml",
         "html" | "htm" => "html",
+        "pdf" => "pdf",
+        "docx" => "docx",
         "css" => "css",
         "sql" => "sql",
         "sh" | "bash" => "shell",
@@ -306,6 +420,33 @@ mod tests {
         assert_eq!(estimate_tokens(""), 1); // min 1
     }
 
+    #[test]
+    fn test_token_counter() {
+        /// One token per word
+        struct Words;
+        impl TokenCounter for Words {
+            fn count_tokens(&self, text: &str) -> usize {
+                text.split_whitespace().count()
+            }
+        }
+
+        let chunker = Chunker::with_options(ChunkOptions {
+            chunk_size: 6,
+            chunk_overlap: 0,
+            min_chunk_size: 1,
+            ..Default::default()
+        })
+        .with_token_counter(Arc::new(Words));
+        let text = "One two three four.\n\nFive six seven eight.\n\nNine ten.";
+
+        let chunks = chunker.chunk(text).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(
+            chunks.iter().map(|c| c.token_count).collect::<Vec<_>>(),
+            vec![4, 6]
+        );
+    }
+
     #[test]
     fn test_detect_document_type() {
         assert_eq!(detect_document_type("readme.md"), "markdown");
@@ -347,4 +488,59 @@ mod tests {
         // Should still create a chunk even if small
         assert!(!chunks.is_empty());
     }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn test_chunk_source_keeps_symbols() {
+        let chunker = Chunker::new();
+        let code = "def first():\n    return 1\n\ndef second():\n    return 2\n";
+
+        let chunks = chunker.chunk_source(code, Path::new("funcs.py")).unwrap();
+        let names: Vec<_> = chunks
+            .iter()
+            .map(|c| c.location.symbol.as_ref().unwrap().name.as_str())
+            .collect();
+        assert_eq!(names, vec!["first", "second"]);
+        assert_eq!(chunks[1].index, 1);
+        assert_eq!(&code[chunks[1].start_offset as usize..chunks[1].end_offset as usize], chunks[1].content);
+
+        // Prose isn't parsed
+        let chunks = chunker.chunk_source(code, Path::new("funcs.txt")).unwrap();
+        assert!(chunks.iter().all(|c| c.location.is_empty()));
+    }
+
+    #[test]
+    fn test_chunk_extracted_records_pages() {
+        let chunker = Chunker::with_options(ChunkOptions {
+            chunk_size: 8,
+            chunk_overlap: 0,
+            min_chunk_size: 1,
+            ..Default::default()
+        });
+        let source = ExtractedText {
+            text: "First page text.\n\nSecond page text.".to_string(),
+            format: SourceFormat::Pdf,
+            segments: vec![
+                crate::extract::TextSegment {
+                    start_offset: 0,
+                    end_offset: 16,
+                    page: Some(1),
+                    section: None,
+                },
+                crate::extract::TextSegment {
+                    start_offset: 18,
+                    end_offset: 35,
+                    page: Some(2),
+                    section: None,
+                },
+            ],
+        };
+
+        let chunks = chunker.chunk_extracted(&source, Path::new("doc.pdf")).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1].index, 1);
+        assert_eq!(chunks[1].location.page, Some(2));
+        assert_eq!(chunks[1].start_offset, 18);
+        assert_eq!(chunks[1].content, "Second page text.");
+    }
 }
@@ -6,17 +6,20 @@
 
 use async_trait::async_trait;
 use regex::RegexSet;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::extract::{ExtractedText, SourceFormat, SECTION_SEPARATOR};
+use crate::syntax::{self, CodeLanguage, CodeSymbol};
 use crate::{KnowledgeError, KnowledgeResult};
 
 /// Document type for chunking strategy selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DocType {
-    /// Code file (will use tree-sitter for syntax-aware chunking)
+    /// Code file (syntax-aware chunking via [`DocumentChunker::chunk_source`])
     Code,
     /// Markdown document (chunk by headings)
     Markdown,
@@ -24,6 +27,10 @@ pub enum DocType {
     Text,
     /// PDF document (extract text first, then sliding window)
     Pdf,
+    /// HTML page (extract text first, then chunk by headings)
+    Html,
+    /// Word document (extract text first, then chunk by headings)
+    Docx,
     /// Auto-detect based on file extension
     Auto,
 }
@@ -37,6 +44,8 @@ impl DocType {
                 | "php" => DocType::Code,
                 "md" | "markdown" => DocType::Markdown,
                 "pdf" => DocType::Pdf,
+                "html" | "htm" => DocType::Html,
+                "docx" => DocType::Docx,
                 "txt" | "rst" | "adoc" => DocType::Text,
                 _ => DocType::Text,
             },
@@ -69,6 +78,10 @@ pub struct ChunkMetadata {
     pub chunk_index: usize,
     /// Total chunks in document
     pub total_chunks: usize,
+    /// Symbol defined by a code chunk (name, kind, scope and line range)
+    pub symbol: Option<CodeSymbol>,
+    /// Page the chunk starts on, for PDFs
+    pub page: Option<u32>,
 }
 
 impl Default for ChunkMetadata {
@@ -78,6 +91,8 @@ impl Default for ChunkMetadata {
             heading_path: Vec::new(),
             chunk_index: 0,
             total_chunks: 1,
+            symbol: None,
+            page: None,
         }
     }
 }
@@ -176,10 +191,14 @@ impl Default for DocumentChunker {
 
 impl DocumentChunker {
     /// Chunk document based on type
+    ///
+    /// `content` must already be text: PDF, HTML and DOCX files go through
+    /// [`crate::extract`] and [`Self::chunk_extracted`] first.
     pub fn chunk(&self, content: &str, doc_type: DocType) -> Vec<Chunk> {
         match doc_type {
             DocType::Code => self.chunk_code(content),
-            DocType::Markdown => self.chunk_markdown(content),
+            // Extracted HTML/DOCX keep their headings as Markdown
+            DocType::Markdown | DocType::Html | DocType::Docx => self.chunk_markdown(content),
             DocType::Pdf | DocType::Text => self.chunk_sliding_window(content),
             DocType::Auto => {
                 // Try to detect based on content heuristics
@@ -195,6 +214,84 @@ impl DocumentChunker {
         }
     }
 
+    /// Chunk a file, using its syntax tree when the language is supported
+    ///
+    /// Rust, Python, JavaScript/TypeScript and Go files are split into whole
+    /// definitions carrying their [`CodeSymbol`] (requires the `tree-sitter`
+    /// feature). Everything else goes through [`Self::chunk`] with the type
+    /// detected from the path.
+    pub fn chunk_source(&self, content: &str, path: &Path) -> Vec<Chunk> {
+        let Some(language) = CodeLanguage::from_path(path) else {
+            return self.chunk(content, DocType::from_path(path));
+        };
+        // Code averages about six bytes per word, whitespace included
+        let Some(code_chunks) = syntax::chunk_code(content, language, self.max_words * 6) else {
+            return self.chunk(content, DocType::Code);
+        };
+
+        let total_chunks = code_chunks.len();
+        code_chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, chunk)| Chunk {
+                content: chunk.content,
+                start_offset: chunk.start_offset,
+                end_offset: chunk.end_offset,
+                metadata: ChunkMetadata {
+                    language: Some(language.name().to_string()),
+                    chunk_index: i,
+                    total_chunks,
+                    symbol: chunk.symbol,
+                    ..Default::default()
+                },
+            })
+            .collect()
+    }
+
+    /// Chunk extracted document text, recording pages and sections
+    ///
+    /// Each page/section span (see [`ExtractedText::spans`]) is chunked on
+    /// its own, so chunks carry the page they start on and their full
+    /// heading path. Plain text goes through [`Self::chunk_source`].
+    pub fn chunk_extracted(&self, source: &ExtractedText, path: &Path) -> Vec<Chunk> {
+        let doc_type = match source.format {
+            SourceFormat::Text => return self.chunk_source(&source.text, path),
+            SourceFormat::Pdf => DocType::Pdf,
+            SourceFormat::Html => DocType::Html,
+            SourceFormat::Docx => DocType::Docx,
+        };
+
+        let mut chunks = Vec::new();
+        for span in source.spans(self.max_words * 6) {
+            // The span starts at its own heading; the markdown chunker
+            // only sees that heading, not its ancestors
+            let ancestors: Vec<String> = span
+                .section
+                .map(|s| s.split(SECTION_SEPARATOR).map(str::to_string).collect())
+                .unwrap_or_default();
+
+            for mut chunk in self.chunk(span.text, doc_type) {
+                chunk.start_offset += span.start_offset;
+                chunk.end_offset += span.start_offset;
+                chunk.metadata.page = span.page;
+                if let Some((own, outer)) = ancestors.split_last() {
+                    if chunk.metadata.heading_path.first() == Some(own) {
+                        let inner = std::mem::take(&mut chunk.metadata.heading_path);
+                        chunk.metadata.heading_path = outer.iter().cloned().chain(inner).collect();
+                    }
+                }
+                chunks.push(chunk);
+            }
+        }
+
+        let total_chunks = chunks.len();
+        for (i, chunk) in chunks.iter_mut().enumerate() {
+            chunk.metadata.chunk_index = i;
+            chunk.metadata.total_chunks = total_chunks;
+        }
+        chunks
+    }
+
     /// Chunk code file by function/class definitions
     ///
     /// # Algorithm
@@ -224,12 +321,11 @@ impl DocumentChunker {
     /// - **Python**: `def`, `class`
     /// - **JavaScript**: `func`
     ///
-    /// # Future Improvements
+    /// # Syntax-Aware Chunking
     ///
-    /// TODO: Replace with tree-sitter for AST-based parsing (more accurate):
-    /// - Proper scope detection
-    /// - Nested structure handling
-    /// - Language-specific grammar rules
+    /// [`Self::chunk_source`] chunks supported languages along their syntax
+    /// tree instead; this splitter is the fallback for other languages and
+    /// for builds without the `tree-sitter` feature.
     ///
     /// # Complexity
     ///
@@ -481,6 +577,68 @@ pub trait EmbeddingProvider: Send + Sync {
 
     /// Get model name
     fn model_name(&self) -> &str;
+
+    /// Get model version
+    ///
+    /// Change it whenever the same name and dimensions start producing
+    /// different vectors (new weights, new pooling), so vaults know to
+    /// re-embed.
+    fn model_version(&self) -> &str {
+        "1"
+    }
+}
+
+#[async_trait]
+impl<T: EmbeddingProvider + ?Sized> EmbeddingProvider for Box<T> {
+    async fn embed(&self, text: &str) -> KnowledgeResult<Vec<f32>> {
+        (**self).embed(text).await
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> KnowledgeResult<Vec<Vec<f32>>> {
+        (**self).embed_batch(texts).await
+    }
+
+    fn dimensions(&self) -> u32 {
+        (**self).dimensions()
+    }
+
+    fn model_name(&self) -> &str {
+        (**self).model_name()
+    }
+
+    fn model_version(&self) -> &str {
+        (**self).model_version()
+    }
+}
+
+/// Identity of the model that produced a set of embeddings
+///
+/// Vectors are only comparable when all three fields match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingModel {
+    /// Model name ([`EmbeddingProvider::model_name`])
+    pub name: String,
+    /// Vector dimensions
+    pub dimensions: u32,
+    /// Model version ([`EmbeddingProvider::model_version`])
+    pub version: String,
+}
+
+impl EmbeddingModel {
+    /// Identity of an embedding provider
+    pub fn of<E: EmbeddingProvider + ?Sized>(embedder: &E) -> Self {
+        Self {
+            name: embedder.model_name().to_string(),
+            dimensions: embedder.dimensions(),
+            version: embedder.model_version().to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for EmbeddingModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({} dims, v{})", self.name, self.dimensions, self.version)
+    }
 }
 
 /// BGE-Micro embedding model wrapper
@@ -557,6 +715,13 @@ impl PlaceholderEmbedder {
     }
 }
 
+impl Default for PlaceholderEmbedder {
+    /// Same dimensions as BGE-Micro, the default embedding model
+    fn default() -> Self {
+        Self::new(384)
+    }
+}
+
 #[async_trait]
 impl EmbeddingProvider for PlaceholderEmbedder {
     async fn embed(&self, text: &str) -> KnowledgeResult<Vec<f32>> {
@@ -573,6 +738,7 @@ impl EmbeddingProvider for PlaceholderEmbedder {
         use std::sync::Arc;
 
         let semaphore = Arc::new(Semaphore::new(8)); // Max 8 concurrent embeddings
+        let dimensions = self.dimensions;
         let mut tasks = Vec::with_capacity(texts.len());
 
         for &text in texts {
@@ -586,7 +752,7 @@ impl EmbeddingProvider for PlaceholderEmbedder {
                     .await
                     .expect("Semaphore should not be closed during normal operation");
                 // Generate embedding (CPU-bound work)
-                generate_placeholder_embedding(&text, 384)
+                generate_placeholder_embedding(&text, dimensions)
             });
 
             tasks.push(task);
@@ -687,20 +853,6 @@ impl Default for BatchOptions {
     }
 }
 
-/// Tree-sitter placeholder module for code chunking
-///
-/// This module will be used for syntax-aware code chunking in the future.
-pub mod tree_sitter_placeholder {
-    use super::Chunk;
-
-    /// Placeholder for tree-sitter based code chunking
-    ///
-    /// TODO: Implement actual tree-sitter integration
-    pub fn chunk_code_syntax_aware(_content: &str, _language: &str) -> Vec<Chunk> {
-        Vec::new()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -711,6 +863,8 @@ mod tests {
         assert_eq!(DocType::from_path(Path::new("test.py")), DocType::Code);
         assert_eq!(DocType::from_path(Path::new("test.md")), DocType::Markdown);
         assert_eq!(DocType::from_path(Path::new("test.pdf")), DocType::Pdf);
+        assert_eq!(DocType::from_path(Path::new("test.html")), DocType::Html);
+        assert_eq!(DocType::from_path(Path::new("test.docx")), DocType::Docx);
         assert_eq!(DocType::from_path(Path::new("test.txt")), DocType::Text);
         assert_eq!(DocType::from_path(Path::new("test.unknown")), DocType::Text);
     }
@@ -768,6 +922,47 @@ pub fn world() {
         assert!(!chunks.is_empty());
     }
 
+    #[test]
+    fn test_chunk_source_fallback() {
+        let chunker = DocumentChunker::default();
+        let chunks = chunker.chunk_source("# Title\n\nSome text.", Path::new("notes.md"));
+        assert!(!chunks.is_empty());
+        assert!(chunks.iter().all(|c| c.metadata.symbol.is_none()));
+    }
+
+    #[test]
+    fn test_chunk_extracted_sections() {
+        let html = "<main><h1>Guide</h1><p>Intro text.</p><h2>Linux</h2><p>Install steps.</p></main>";
+        let source = crate::extract::extract_html(html);
+        let chunker = DocumentChunker {
+            max_words: 2,
+            overlap_words: 0,
+        };
+
+        let chunks = chunker.chunk_extracted(&source, Path::new("guide.html"));
+        let paths: Vec<_> = chunks.iter().map(|c| c.metadata.heading_path.join(" > ")).collect();
+        assert_eq!(paths, vec!["Guide", "Guide > Linux"]);
+        assert_eq!(chunks[1].metadata.chunk_index, 1);
+        assert_eq!(chunks[1].metadata.total_chunks, 2);
+        assert!(source.text[chunks[1].start_offset..].starts_with("## Linux"));
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn test_chunk_source_syntax_aware() {
+        let content = "/// Entry point\nfn main() {\n    run();\n}\n\nfn run() {}\n";
+        let chunker = DocumentChunker::default();
+        let chunks = chunker.chunk_source(content, Path::new("src/main.rs"));
+
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].content.starts_with("/// Entry point"));
+        assert_eq!(chunks[0].metadata.language.as_deref(), Some("rust"));
+        assert_eq!(chunks[0].metadata.total_chunks, 2);
+        let symbol = chunks[1].metadata.symbol.as_ref().unwrap();
+        assert_eq!(symbol.qualified_name, "run");
+        assert_eq!((symbol.start_line, symbol.end_line), (6, 6));
+    }
+
     #[test]
     fn test_cosine_similarity() {
         let a = vec![1.0, 0.0, 0.0];
@@ -853,6 +1048,8 @@ It should be chunked properly."#;
                 heading_path: vec!["Introduction".to_string()],
                 chunk_index: 0,
                 total_chunks: 5,
+                symbol: None,
+                page: None,
             },
         };
 
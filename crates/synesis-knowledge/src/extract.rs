@@ -0,0 +1,789 @@
+//! Document Text Extraction
+//!
+//! Turns PDF, HTML and DOCX files into plain text before chunking, keeping
+//! track of where each part of the text came from:
+//!
+//! - **PDF**: the text layer of every page, tagged with its page number
+//! - **HTML**: the main content with navigation, scripts and other
+//!   boilerplate stripped; headings are kept as Markdown headings
+//! - **DOCX**: the document body, with Word heading styles turned into
+//!   Markdown headings
+//!
+//! The result is an [`ExtractedText`]: the text plus [`TextSegment`]s that
+//! map byte ranges to a page or section (heading path), so chunks and
+//! search results can cite "page 12" or "Install > Linux".
+
+use quick_xml::events::{BytesStart, Event};
+use scraper::{ElementRef, Html, Node, Selector};
+use std::io::{Cursor, Read};
+use std::path::Path;
+use tracing::{debug, warn};
+
+use crate::{KnowledgeError, KnowledgeResult};
+
+/// Separator between headings in a section path
+pub const SECTION_SEPARATOR: &str = " > ";
+
+/// Source file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    /// UTF-8 text (prose, Markdown, code, ...)
+    Text,
+    /// HTML page
+    Html,
+    /// PDF document
+    Pdf,
+    /// Word document
+    Docx,
+}
+
+impl SourceFormat {
+    /// Detect the format from a file extension
+    pub fn from_path(path: &Path) -> Self {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase);
+        match ext.as_deref() {
+            Some("html" | "htm" | "xhtml") => Self::Html,
+            Some("pdf") => Self::Pdf,
+            Some("docx") => Self::Docx,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// Byte range of extracted text and where it came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextSegment {
+    /// Start byte offset in [`ExtractedText::text`]
+    pub start_offset: usize,
+    /// End byte offset in [`ExtractedText::text`]
+    pub end_offset: usize,
+    /// Page number (1-based), for PDFs
+    pub page: Option<u32>,
+    /// Heading path (e.g., `Install > Linux`), for HTML and DOCX
+    pub section: Option<String>,
+}
+
+/// A run of adjacent segments, see [`ExtractedText::spans`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextSpan<'a> {
+    /// Start byte offset in [`ExtractedText::text`]
+    pub start_offset: usize,
+    /// Text of the span
+    pub text: &'a str,
+    /// Page of the first segment
+    pub page: Option<u32>,
+    /// Section of the first segment
+    pub section: Option<&'a str>,
+}
+
+/// Text extracted from a document
+#[derive(Debug, Clone)]
+pub struct ExtractedText {
+    /// Plain text, with paragraphs separated by blank lines
+    pub text: String,
+    /// Format the text was extracted from
+    pub format: SourceFormat,
+    /// Page/section ranges, in order (empty for plain text)
+    pub segments: Vec<TextSegment>,
+}
+
+impl ExtractedText {
+    /// Wrap text that needs no extraction
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            format: SourceFormat::Text,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Group adjacent segments into spans of at most `max_bytes`
+    ///
+    /// Chunking each span separately gives every chunk a single page or
+    /// section, while small sections are still chunked together. Segments
+    /// larger than `max_bytes` form a span of their own. Text without
+    /// segments is returned as one span.
+    pub fn spans(&self, max_bytes: usize) -> Vec<TextSpan<'_>> {
+        if self.segments.is_empty() {
+            return vec![TextSpan {
+                start_offset: 0,
+                text: &self.text,
+                page: None,
+                section: None,
+            }];
+        }
+
+        let mut spans = Vec::new();
+        let mut segments = self.segments.iter().peekable();
+        while let Some(first) = segments.next() {
+            let mut end = first.end_offset;
+            while let Some(next) =
+                segments.next_if(|s| s.end_offset - first.start_offset <= max_bytes)
+            {
+                end = next.end_offset;
+            }
+            spans.push(TextSpan {
+                start_offset: first.start_offset,
+                text: &self.text[first.start_offset..end],
+                page: first.page,
+                section: first.section.as_deref(),
+            });
+        }
+        spans
+    }
+}
+
+/// Extract the text of a file's contents
+///
+/// # Errors
+/// Returns `InvalidFormat` if the file can't be parsed, has no text, or
+/// (for text formats) isn't valid UTF-8.
+pub fn extract(path: &Path, bytes: &[u8]) -> KnowledgeResult<ExtractedText> {
+    match SourceFormat::from_path(path) {
+        SourceFormat::Pdf => extract_pdf(bytes),
+        SourceFormat::Docx => extract_docx(bytes),
+        SourceFormat::Html => Ok(extract_html(&String::from_utf8_lossy(bytes))),
+        SourceFormat::Text => String::from_utf8(bytes.to_vec())
+            .map(ExtractedText::plain)
+            .map_err(|_| {
+                KnowledgeError::InvalidFormat(format!("{} is not valid UTF-8", path.display()))
+            }),
+    }
+}
+
+/// Read a file from disk and extract its text
+pub async fn read_file(path: &Path) -> KnowledgeResult<ExtractedText> {
+    let bytes = tokio::fs::read(path).await?;
+    let path = path.to_path_buf();
+
+    // PDF parsing is CPU-bound; keep it off the async workers
+    tokio::task::spawn_blocking(move || extract(&path, &bytes))
+        .await
+        .map_err(|e| KnowledgeError::Internal(format!("Extraction task failed: {}", e)))?
+}
+
+/// Extract the text layer of a PDF, page by page
+///
+/// Scanned PDFs without a text layer are rejected rather than indexed as
+/// empty documents.
+pub fn extract_pdf(bytes: &[u8]) -> KnowledgeResult<ExtractedText> {
+    let doc = lopdf::Document::load_mem(bytes)
+        .map_err(|e| KnowledgeError::InvalidFormat(format!("Failed to parse PDF: {}", e)))?;
+    if doc.is_encrypted() {
+        return Err(KnowledgeError::InvalidFormat(
+            "PDF is encrypted".to_string(),
+        ));
+    }
+
+    let mut out = TextBuilder::default();
+    for page in doc.get_pages().into_keys() {
+        let text = match doc.extract_text(&[page]) {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("Skipping PDF page {}: {}", page, e);
+                continue;
+            },
+        };
+        out.start_segment(Some(page), None);
+        for paragraph in pdf_paragraphs(&text) {
+            out.push_block(&paragraph);
+        }
+    }
+
+    if out.text.trim().is_empty() {
+        return Err(KnowledgeError::InvalidFormat(
+            "PDF has no text layer (scanned document?)".to_string(),
+        ));
+    }
+    debug!("Extracted {} PDF segments", out.segments.len());
+    Ok(out.finish(SourceFormat::Pdf))
+}
+
+/// Rejoin the lines of a PDF page into paragraphs
+///
+/// PDF text comes out one line per text object. Lines are joined until one
+/// ends a sentence, and words hyphenated across lines are rejoined.
+fn pdf_paragraphs(text: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if current.ends_with('-') {
+            current.pop();
+        } else if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(line);
+
+        if line.ends_with(['.', '!', '?', ':']) {
+            paragraphs.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs
+}
+
+/// Extract the main content of an HTML page
+///
+/// Uses `<main>` when the page has one, otherwise `<body>`. Scripts,
+/// styles, navigation, forms and hidden elements are dropped, as are page
+/// headers, footers and sidebars outside `<main>`/`<article>`.
+pub fn extract_html(html: &str) -> ExtractedText {
+    let document = Html::parse_document(html);
+    let root = ["main", "body"]
+        .iter()
+        .filter_map(|name| Selector::parse(name).ok())
+        .find_map(|selector| document.select(&selector).next())
+        .unwrap_or_else(|| document.root_element());
+    let in_content = root.value().name() == "main";
+
+    let mut out = TextBuilder::default();
+    let mut inline = String::new();
+    walk_html(*root, in_content, &mut out, &mut inline);
+    flush_inline(&mut inline, &mut out);
+    out.finish(SourceFormat::Html)
+}
+
+fn walk_html(
+    node: ego_tree::NodeRef<'_, Node>,
+    in_content: bool,
+    out: &mut TextBuilder,
+    inline: &mut String,
+) {
+    for child in node.children() {
+        match child.value() {
+            Node::Text(text) => inline.push_str(text),
+            Node::Element(element) => {
+                let name = element.name();
+                if is_html_boilerplate(element, in_content) {
+                    continue;
+                }
+
+                if let Some(level) = heading_level(name) {
+                    flush_inline(inline, out);
+                    let text: String = ElementRef::wrap(child)
+                        .map(|e| e.text().collect())
+                        .unwrap_or_default();
+                    out.heading(level, &collapse_whitespace(&text));
+                    continue;
+                }
+
+                match name {
+                    "br" | "td" | "th" => {
+                        walk_html(child, in_content, out, inline);
+                        inline.push(' ');
+                    },
+                    "pre" => {
+                        flush_inline(inline, out);
+                        let text: String = ElementRef::wrap(child)
+                            .map(|e| e.text().collect())
+                            .unwrap_or_default();
+                        out.push_block(&text);
+                    },
+                    "li" => {
+                        flush_inline(inline, out);
+                        inline.push_str("- ");
+                        walk_html(child, in_content, out, inline);
+                        flush_inline(inline, out);
+                    },
+                    _ if is_html_block(name) => {
+                        flush_inline(inline, out);
+                        let in_content = in_content || matches!(name, "main" | "article");
+                        walk_html(child, in_content, out, inline);
+                        flush_inline(inline, out);
+                    },
+                    _ => walk_html(child, in_content, out, inline),
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+fn is_html_boilerplate(element: &scraper::node::Element, in_content: bool) -> bool {
+    let hidden = element.attr("hidden").is_some() || element.attr("aria-hidden") == Some("true");
+    let boilerplate_role = matches!(
+        element.attr("role"),
+        Some("navigation" | "banner" | "contentinfo" | "complementary" | "search")
+    );
+    let boilerplate_tag = match element.name() {
+        "script" | "style" | "noscript" | "template" | "nav" | "form" | "svg" | "iframe"
+        | "button" | "select" | "head" => true,
+        // An article's own header/footer is content
+        "header" | "footer" | "aside" => !in_content,
+        _ => false,
+    };
+    hidden || boilerplate_role || boilerplate_tag
+}
+
+fn is_html_block(name: &str) -> bool {
+    matches!(
+        name,
+        "p" | "div"
+            | "section"
+            | "article"
+            | "main"
+            | "header"
+            | "footer"
+            | "aside"
+            | "blockquote"
+            | "ul"
+            | "ol"
+            | "dl"
+            | "dt"
+            | "dd"
+            | "table"
+            | "thead"
+            | "tbody"
+            | "tr"
+            | "figure"
+            | "figcaption"
+            | "address"
+            | "details"
+            | "summary"
+            | "hr"
+    )
+}
+
+fn heading_level(name: &str) -> Option<usize> {
+    match name {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+fn flush_inline(inline: &mut String, out: &mut TextBuilder) {
+    out.push_block(&collapse_whitespace(inline));
+    inline.clear();
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Extract the body of a Word document
+///
+/// Paragraphs with a heading style (`Heading1`..`Heading9`, `Title`) or an
+/// outline level become Markdown headings; list paragraphs become `- `
+/// items.
+pub fn extract_docx(bytes: &[u8]) -> KnowledgeResult<ExtractedText> {
+    let invalid = |e: &dyn std::fmt::Display| {
+        KnowledgeError::InvalidFormat(format!("Failed to read DOCX: {}", e))
+    };
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| invalid(&e))?;
+    let mut xml = String::new();
+    archive
+        .by_name("word/document.xml")
+        .map_err(|e| invalid(&e))?
+        .read_to_string(&mut xml)?;
+
+    let mut reader = quick_xml::Reader::from_str(&xml);
+    let mut out = TextBuilder::default();
+    let mut paragraph = DocxParagraph::default();
+    let mut in_text = false;
+
+    loop {
+        match reader.read_event().map_err(|e| invalid(&e))? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"p" => paragraph = DocxParagraph::default(),
+                b"t" => in_text = true,
+                _ => paragraph.apply(&e),
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"tab" => paragraph.text.push('\t'),
+                b"br" | b"cr" => paragraph.text.push(' '),
+                _ => paragraph.apply(&e),
+            },
+            Event::Text(e) if in_text => {
+                paragraph
+                    .text
+                    .push_str(&e.unescape().map_err(|e| invalid(&e))?);
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"p" => std::mem::take(&mut paragraph).write(&mut out),
+                _ => {},
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+    }
+
+    Ok(out.finish(SourceFormat::Docx))
+}
+
+/// A `<w:p>` being read
+#[derive(Default)]
+struct DocxParagraph {
+    text: String,
+    heading_level: Option<usize>,
+    list_item: bool,
+}
+
+impl DocxParagraph {
+    /// Apply paragraph properties
+    fn apply(&mut self, element: &BytesStart<'_>) {
+        let val = || {
+            element
+                .attributes()
+                .flatten()
+                .find(|a| a.key.local_name().as_ref() == b"val")
+                .and_then(|a| a.unescape_value().ok())
+        };
+        match element.local_name().as_ref() {
+            b"pStyle" => {
+                if let Some(style) = val() {
+                    let level = if style == "Title" {
+                        Some(1)
+                    } else {
+                        style
+                            .strip_prefix("Heading")
+                            .and_then(|n| n.parse::<usize>().ok())
+                    };
+                    self.heading_level = self.heading_level.or(level);
+                }
+            },
+            // Outline levels are 0-based
+            b"outlineLvl" => {
+                if let Some(level) = val().and_then(|v| v.parse::<usize>().ok()) {
+                    self.heading_level = Some(level + 1);
+                }
+            },
+            b"numPr" => self.list_item = true,
+            _ => {},
+        }
+    }
+
+    fn write(self, out: &mut TextBuilder) {
+        let text = collapse_whitespace(&self.text);
+        match self.heading_level {
+            Some(level) if !text.is_empty() => out.heading(level.min(6), &text),
+            _ if self.list_item => out.push_block(&format!("- {}", text)),
+            _ => out.push_block(&text),
+        }
+    }
+}
+
+/// Builds extracted text and its segments
+#[derive(Default)]
+struct TextBuilder {
+    text: String,
+    segments: Vec<TextSegment>,
+    /// Open headings (level, text)
+    headings: Vec<(usize, String)>,
+}
+
+impl TextBuilder {
+    /// Start a new segment at the next block
+    fn start_segment(&mut self, page: Option<u32>, section: Option<String>) {
+        self.segments.push(TextSegment {
+            start_offset: self.text.len(),
+            end_offset: self.text.len(),
+            page,
+            section,
+        });
+    }
+
+    /// Append a paragraph
+    fn push_block(&mut self, block: &str) {
+        let block = block.trim();
+        if block.is_empty() {
+            return;
+        }
+        if !self.text.is_empty() {
+            self.text.push_str("\n\n");
+        }
+        if self.segments.is_empty() {
+            self.start_segment(None, None);
+        }
+        let start = self.text.len();
+        self.text.push_str(block);
+
+        let Some(segment) = self.segments.last_mut() else {
+            return;
+        };
+        if segment.start_offset == segment.end_offset {
+            segment.start_offset = start;
+        }
+        segment.end_offset = self.text.len();
+    }
+
+    /// Open a section and append its heading as Markdown
+    fn heading(&mut self, level: usize, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        while self.headings.last().is_some_and(|(l, _)| *l >= level) {
+            self.headings.pop();
+        }
+        self.headings.push((level, text.to_string()));
+
+        let section = self
+            .headings
+            .iter()
+            .map(|(_, h)| h.as_str())
+            .collect::<Vec<_>>()
+            .join(SECTION_SEPARATOR);
+        self.start_segment(None, Some(section));
+        self.push_block(&format!("{} {}", "#".repeat(level), text));
+    }
+
+    fn finish(mut self, format: SourceFormat) -> ExtractedText {
+        self.segments.retain(|s| s.start_offset < s.end_offset);
+        ExtractedText {
+            text: self.text,
+            format,
+            segments: self.segments,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn segment_text<'a>(doc: &'a ExtractedText, segment: &TextSegment) -> &'a str {
+        &doc.text[segment.start_offset..segment.end_offset]
+    }
+
+    /// Build a PDF with one text line per page
+    fn pdf(pages: &[&[&str]]) -> Vec<u8> {
+        use lopdf::content::{Content, Operation};
+        use lopdf::{dictionary, Document, Object, Stream};
+
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Courier",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+
+        let mut kids = Vec::new();
+        for lines in pages {
+            let mut operations = Vec::new();
+            for (i, line) in lines.iter().enumerate() {
+                operations.push(Operation::new("BT", vec![]));
+                operations.push(Operation::new("Tf", vec!["F1".into(), 12.into()]));
+                operations.push(Operation::new(
+                    "Td",
+                    vec![72.into(), (700 - 14 * i as i64).into()],
+                ));
+                operations.push(Operation::new("Tj", vec![Object::string_literal(*line)]));
+                operations.push(Operation::new("ET", vec![]));
+            }
+            let content = Content { operations };
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+            });
+            kids.push(page_id.into());
+        }
+
+        let count = kids.len() as i64;
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => count,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn docx(body: &str) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file(
+            "word/document.xml",
+            zip::write::SimpleFileOptions::default(),
+        )
+        .unwrap();
+        write!(
+            zip,
+            r#"<?xml version="1.0" encoding="UTF-8"?><w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}</w:body></w:document>"#,
+            body
+        )
+        .unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_source_format_detection() {
+        assert_eq!(
+            SourceFormat::from_path(Path::new("a.PDF")),
+            SourceFormat::Pdf
+        );
+        assert_eq!(
+            SourceFormat::from_path(Path::new("a.htm")),
+            SourceFormat::Html
+        );
+        assert_eq!(
+            SourceFormat::from_path(Path::new("a.docx")),
+            SourceFormat::Docx
+        );
+        assert_eq!(
+            SourceFormat::from_path(Path::new("a.md")),
+            SourceFormat::Text
+        );
+    }
+
+    #[test]
+    fn test_pdf_pages() {
+        let bytes = pdf(&[
+            &["Synesis routes queries", "through three agents."],
+            &["Ethos verifies every claim-", "based answer."],
+        ]);
+        let doc = extract(Path::new("paper.pdf"), &bytes).unwrap();
+
+        assert_eq!(doc.format, SourceFormat::Pdf);
+        assert_eq!(doc.segments.len(), 2);
+        assert_eq!(doc.segments[0].page, Some(1));
+        assert_eq!(
+            segment_text(&doc, &doc.segments[0]),
+            "Synesis routes queries through three agents."
+        );
+        assert_eq!(doc.segments[1].page, Some(2));
+        assert_eq!(
+            segment_text(&doc, &doc.segments[1]),
+            "Ethos verifies every claimbased answer."
+        );
+    }
+
+    #[test]
+    fn test_pdf_errors() {
+        assert!(matches!(
+            extract_pdf(b"not a pdf"),
+            Err(KnowledgeError::InvalidFormat(_))
+        ));
+        let blank = pdf(&[&[]]);
+        assert!(matches!(
+            extract_pdf(&blank),
+            Err(KnowledgeError::InvalidFormat(msg)) if msg.contains("no text layer")
+        ));
+    }
+
+    #[test]
+    fn test_html_strips_boilerplate() {
+        let html = r#"<html>
+<head><title>Docs</title><style>body { color: red }</style></head>
+<body>
+  <nav><a href="/">Home</a> | <a href="/docs">Docs</a></nav>
+  <header>Site banner</header>
+  <article>
+    <header><h1>Installing   Synesis</h1></header>
+    <p>Download the <b>latest</b> release.</p>
+    <h2>Linux</h2>
+    <ul><li>Extract the archive</li><li>Run <code>synesis init</code></li></ul>
+    <script>trackPageView();</script>
+    <div hidden>Secret</div>
+  </article>
+  <footer>Copyright 2026</footer>
+</body></html>"#;
+        let doc = extract_html(html);
+
+        assert_eq!(
+            doc.text,
+            "# Installing Synesis\n\nDownload the latest release.\n\n## Linux\n\n- Extract the archive\n\n- Run synesis init"
+        );
+        let sections: Vec<_> = doc.segments.iter().map(|s| s.section.as_deref()).collect();
+        assert_eq!(
+            sections,
+            vec![
+                Some("Installing Synesis"),
+                Some("Installing Synesis > Linux")
+            ]
+        );
+        assert!(segment_text(&doc, &doc.segments[1]).starts_with("## Linux"));
+    }
+
+    #[test]
+    fn test_html_prefers_main() {
+        let doc =
+            extract_html("<body><div>Sidebar links</div><main><p>Main text</p></main></body>");
+        assert_eq!(doc.text, "Main text");
+        // Content before the first heading has no section
+        assert_eq!(doc.segments.len(), 1);
+        assert_eq!(doc.segments[0].section, None);
+    }
+
+    #[test]
+    fn test_docx_headings_and_lists() {
+        let bytes = docx(concat!(
+            r#"<w:p><w:pPr><w:pStyle w:val="Title"/></w:pPr><w:r><w:t>Handbook</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:t xml:space="preserve">Welcome </w:t></w:r><w:r><w:t>aboard &amp; enjoy.</w:t></w:r></w:p>"#,
+            r#"<w:p><w:pPr><w:pStyle w:val="Heading2"/></w:pPr><w:r><w:t>Setup</w:t></w:r></w:p>"#,
+            r#"<w:p><w:pPr><w:numPr><w:ilvl w:val="0"/></w:numPr></w:pPr><w:r><w:t>Install</w:t></w:r></w:p>"#,
+            r#"<w:p><w:pPr><w:outlineLvl w:val="0"/></w:pPr><w:r><w:t>Appendix</w:t></w:r></w:p>"#,
+        ));
+        let doc = extract(Path::new("handbook.docx"), &bytes).unwrap();
+
+        assert_eq!(
+            doc.text,
+            "# Handbook\n\nWelcome aboard & enjoy.\n\n## Setup\n\n- Install\n\n# Appendix"
+        );
+        let sections: Vec<_> = doc.segments.iter().map(|s| s.section.as_deref()).collect();
+        assert_eq!(
+            sections,
+            vec![Some("Handbook"), Some("Handbook > Setup"), Some("Appendix")]
+        );
+
+        assert!(matches!(
+            extract_docx(b"PK not a zip"),
+            Err(KnowledgeError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_spans_group_small_segments() {
+        let mut out = TextBuilder::default();
+        out.heading(1, "A");
+        out.push_block("alpha");
+        out.heading(1, "B");
+        out.push_block("beta");
+        out.heading(1, "C");
+        out.push_block(&"gamma ".repeat(20));
+        let doc = out.finish(SourceFormat::Html);
+
+        let spans = doc.spans(40);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].section, Some("A"));
+        assert_eq!(spans[0].text, "# A\n\nalpha\n\n# B\n\nbeta");
+        assert_eq!(spans[1].section, Some("C"));
+        assert_eq!(
+            &doc.text[spans[1].start_offset..spans[1].start_offset + spans[1].text.len()],
+            spans[1].text
+        );
+
+        let plain = ExtractedText::plain("just text");
+        assert_eq!(plain.spans(4)[0].text, "just text");
+        assert!(extract(Path::new("bad.txt"), &[0xff, 0xfe]).is_err());
+    }
+}
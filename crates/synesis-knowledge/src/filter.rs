@@ -0,0 +1,382 @@
+//! Structured Search Filters
+//!
+//! Narrow a search to documents by path, tag and date, and to documents or
+//! chunks by their JSON metadata. Path, tag and date conditions are
+//! resolved in SQL ([`KnowledgeVault::filter_documents`]); metadata
+//! conditions are checked against each candidate with
+//! [`MetadataCondition::matches`].
+//!
+//! [`KnowledgeVault::filter_documents`]: crate::KnowledgeVault::filter_documents
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::chunker::ChunkLocation;
+use crate::{KnowledgeError, KnowledgeResult};
+
+/// Conditions a search result must meet; all of them must hold
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchFilter {
+    /// Document path matches any of these globs (SQLite `GLOB`: `*` also
+    /// matches `/`)
+    #[serde(default)]
+    pub path_globs: Vec<String>,
+    /// Document has every one of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// When the document was first indexed
+    #[serde(default)]
+    pub indexed: DateRange,
+    /// When the document was last updated
+    #[serde(default)]
+    pub updated: DateRange,
+    /// Conditions on document or chunk metadata
+    #[serde(default)]
+    pub metadata: Vec<MetadataCondition>,
+}
+
+impl SearchFilter {
+    /// Whether the filter lets everything through
+    pub fn is_empty(&self) -> bool {
+        !self.has_document_conditions() && self.metadata.is_empty()
+    }
+
+    /// Whether any condition applies to whole documents
+    pub fn has_document_conditions(&self) -> bool {
+        !self.path_globs.is_empty()
+            || !self.tags.is_empty()
+            || !self.indexed.is_unbounded()
+            || !self.updated.is_unbounded()
+            || self.conditions(MetadataScope::Document).next().is_some()
+    }
+
+    /// Whether any condition applies to individual chunks
+    pub fn has_chunk_conditions(&self) -> bool {
+        self.conditions(MetadataScope::Chunk).next().is_some()
+    }
+
+    /// Check a document's metadata against the document conditions
+    pub fn matches_document_metadata(&self, metadata: &HashMap<String, Value>) -> bool {
+        let mut conditions = self.conditions(MetadataScope::Document).peekable();
+        if conditions.peek().is_none() {
+            return true;
+        }
+        let metadata = Value::Object(metadata.clone().into_iter().collect());
+        conditions.all(|c| c.matches(&metadata))
+    }
+
+    /// Check a chunk's location (page, section, symbol) against the chunk
+    /// conditions
+    pub fn matches_chunk(&self, location: &ChunkLocation) -> bool {
+        let mut conditions = self.conditions(MetadataScope::Chunk).peekable();
+        if conditions.peek().is_none() {
+            return true;
+        }
+        let metadata = serde_json::to_value(location).unwrap_or(Value::Null);
+        conditions.all(|c| c.matches(&metadata))
+    }
+
+    fn conditions(&self, scope: MetadataScope) -> impl Iterator<Item = &MetadataCondition> {
+        self.metadata.iter().filter(move |c| c.scope == scope)
+    }
+}
+
+/// Time range; either end may be open
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DateRange {
+    /// Inclusive lower bound
+    pub after: Option<DateTime<Utc>>,
+    /// Exclusive upper bound
+    pub before: Option<DateTime<Utc>>,
+}
+
+impl DateRange {
+    /// Whether neither end is set
+    pub fn is_unbounded(&self) -> bool {
+        self.after.is_none() && self.before.is_none()
+    }
+
+    /// Whether `time` falls in the range
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.after.is_none_or(|after| time >= after)
+            && self.before.is_none_or(|before| time < before)
+    }
+}
+
+/// Which metadata a condition looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataScope {
+    /// `documents.metadata`
+    Document,
+    /// `chunks.metadata` (the chunk's [`ChunkLocation`])
+    Chunk,
+}
+
+/// Comparison operator of a [`MetadataCondition`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl Comparison {
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Ne => "!=",
+            Self::Gt => ">",
+            Self::Gte => ">=",
+            Self::Lt => "<",
+            Self::Lte => "<=",
+        }
+    }
+
+    fn accepts(self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering::*;
+        match self {
+            Self::Eq => ordering == Equal,
+            Self::Ne => ordering != Equal,
+            Self::Gt => ordering == Greater,
+            Self::Gte => ordering != Less,
+            Self::Lt => ordering == Less,
+            Self::Lte => ordering != Greater,
+        }
+    }
+}
+
+/// Condition on one metadata value
+///
+/// Numbers compare numerically and strings lexically (so ISO dates order
+/// correctly); other types only support `=` and `!=`. An array matches if
+/// any element does. A missing key never matches, not even `!=`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetadataCondition {
+    /// Which metadata to look at
+    pub scope: MetadataScope,
+    /// Key, with `.` separating nested objects (`review.status`)
+    pub key: String,
+    /// Operator
+    pub op: Comparison,
+    /// Value to compare against
+    pub value: Value,
+}
+
+impl MetadataCondition {
+    /// Condition on document metadata
+    pub fn document(key: impl Into<String>, op: Comparison, value: impl Into<Value>) -> Self {
+        Self {
+            scope: MetadataScope::Document,
+            key: key.into(),
+            op,
+            value: value.into(),
+        }
+    }
+
+    /// Condition on chunk metadata
+    pub fn chunk(key: impl Into<String>, op: Comparison, value: impl Into<Value>) -> Self {
+        Self {
+            scope: MetadataScope::Chunk,
+            ..Self::document(key, op, value)
+        }
+    }
+
+    /// Check a metadata object
+    pub fn matches(&self, metadata: &Value) -> bool {
+        let found = self
+            .key
+            .split('.')
+            .try_fold(metadata, |value, part| value.get(part));
+        match found {
+            Some(Value::Array(items)) if !self.value.is_array() => match self.op {
+                Comparison::Ne => items.iter().all(|item| self.compare(item)),
+                _ => items.iter().any(|item| self.compare(item)),
+            },
+            Some(value) => self.compare(value),
+            None => false,
+        }
+    }
+
+    fn compare(&self, actual: &Value) -> bool {
+        let ordering = match (actual, &self.value) {
+            (Value::Number(a), Value::Number(b)) => match (a.as_f64(), b.as_f64()) {
+                (Some(a), Some(b)) => a.partial_cmp(&b),
+                _ => None,
+            },
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            (a, b) if matches!(self.op, Comparison::Eq | Comparison::Ne) => Some(if a == b {
+                std::cmp::Ordering::Equal
+            } else {
+                std::cmp::Ordering::Less
+            }),
+            _ => None,
+        };
+        ordering.is_some_and(|o| self.op.accepts(o))
+    }
+}
+
+impl FromStr for MetadataCondition {
+    type Err = KnowledgeError;
+
+    /// Parse `key=value`, `key!=value`, `key>=value` and so on
+    ///
+    /// A `chunk.` prefix selects chunk metadata (`chunk.page>=3`). Values
+    /// are read as JSON when they parse (numbers, `true`, quoted strings),
+    /// otherwise as plain strings.
+    fn from_str(expr: &str) -> KnowledgeResult<Self> {
+        // Two-character operators first so `>=` isn't read as `>`
+        const OPERATORS: [(&str, Comparison); 6] = [
+            ("!=", Comparison::Ne),
+            (">=", Comparison::Gte),
+            ("<=", Comparison::Lte),
+            ("=", Comparison::Eq),
+            (">", Comparison::Gt),
+            ("<", Comparison::Lt),
+        ];
+
+        let (at, symbol, op) = OPERATORS
+            .iter()
+            .filter_map(|&(symbol, op)| expr.find(symbol).map(|at| (at, symbol, op)))
+            .min_by_key(|&(at, symbol, _)| (at, std::cmp::Reverse(symbol.len())))
+            .ok_or_else(|| {
+                KnowledgeError::InvalidFormat(format!("No comparison in filter '{}'", expr))
+            })?;
+
+        let key = expr[..at].trim();
+        let raw = expr[at + symbol.len()..].trim();
+        let (scope, key) = match key.strip_prefix("chunk.") {
+            Some(key) => (MetadataScope::Chunk, key),
+            None => (MetadataScope::Document, key),
+        };
+        if key.is_empty() {
+            return Err(KnowledgeError::InvalidFormat(format!(
+                "No key in filter '{}'",
+                expr
+            )));
+        }
+
+        let value = serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+        Ok(Self {
+            scope,
+            key: key.to_string(),
+            op,
+            value,
+        })
+    }
+}
+
+impl fmt::Display for MetadataCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = match self.scope {
+            MetadataScope::Document => "",
+            MetadataScope::Chunk => "chunk.",
+        };
+        write!(
+            f,
+            "{}{}{}{}",
+            prefix,
+            self.key,
+            self.op.symbol(),
+            self.value
+        )
+    }
+}
+
+/// Normalize a user-supplied tag: trimmed and lowercased
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().to_lowercase();
+    (!tag.is_empty()).then_some(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_conditions() {
+        let c: MetadataCondition = "project=atlas".parse().unwrap();
+        assert_eq!(
+            c,
+            MetadataCondition::document("project", Comparison::Eq, "atlas")
+        );
+
+        let c: MetadataCondition = "chunk.page>=3".parse().unwrap();
+        assert_eq!(c, MetadataCondition::chunk("page", Comparison::Gte, 3));
+        assert_eq!(c.to_string(), "chunk.page>=3");
+
+        let c: MetadataCondition = "review.status != \"draft\"".parse().unwrap();
+        assert_eq!((c.key.as_str(), c.op), ("review.status", Comparison::Ne));
+        assert_eq!(c.value, json!("draft"));
+
+        assert!("project".parse::<MetadataCondition>().is_err());
+        assert!("=atlas".parse::<MetadataCondition>().is_err());
+    }
+
+    #[test]
+    fn test_condition_matching() {
+        let meta = json!({
+            "project": "atlas",
+            "priority": 2,
+            "review": { "status": "approved", "due": "2026-09-30" },
+            "authors": ["ana", "ben"]
+        });
+        let matches = |expr: &str| expr.parse::<MetadataCondition>().unwrap().matches(&meta);
+
+        assert!(matches("project=atlas"));
+        assert!(!matches("project=zeus"));
+        assert!(matches("priority>=2.0"));
+        assert!(!matches("priority>2"));
+        assert!(matches("review.status=approved"));
+        assert!(matches("review.due<2026-10-01"));
+        assert!(matches("authors=ben"));
+        assert!(!matches("authors!=ben"));
+        // Missing keys and mismatched types never match
+        assert!(!matches("owner!=ana"));
+        assert!(!matches("project>3"));
+    }
+
+    #[test]
+    fn test_filter_scopes() {
+        let filter = SearchFilter {
+            metadata: vec![
+                "project=atlas".parse().unwrap(),
+                "chunk.page<=2".parse().unwrap(),
+            ],
+            ..Default::default()
+        };
+        assert!(filter.has_document_conditions() && filter.has_chunk_conditions());
+
+        let mut doc = HashMap::new();
+        doc.insert("project".to_string(), json!("atlas"));
+        assert!(filter.matches_document_metadata(&doc));
+        assert!(!filter.matches_document_metadata(&HashMap::new()));
+
+        let page = |page| ChunkLocation {
+            page: Some(page),
+            ..Default::default()
+        };
+        assert!(filter.matches_chunk(&page(2)));
+        assert!(!filter.matches_chunk(&page(3)));
+        assert!(!filter.matches_chunk(&ChunkLocation::default()));
+
+        let range = DateRange {
+            after: Some("2026-07-01T00:00:00Z".parse().unwrap()),
+            before: None,
+        };
+        assert!(range.contains("2026-07-01T00:00:00Z".parse().unwrap()));
+        assert!(!range.contains("2026-06-30T23:59:59Z".parse().unwrap()));
+        assert!(SearchFilter::default().is_empty());
+        assert_eq!(normalize_tag("  Design "), Some("design".to_string()));
+        assert_eq!(normalize_tag(" "), None);
+    }
+}
@@ -4,16 +4,19 @@
 //! to avoid holding locks across await points.
 
 use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
+use synesis_models::TokenCounter;
 use tokio::sync::{mpsc, Mutex};
 use tracing::{debug, info, instrument, warn};
 use uuid::Uuid;
 
 use crate::chunker::{detect_document_type, ChunkOptions, Chunker};
-use crate::embeddings::EmbeddingProvider;
-use crate::vault::{Document, KnowledgeVault};
+use crate::extract::{self, ExtractedText};
+use crate::embeddings::{EmbeddingModel, EmbeddingProvider};
+use crate::vault::{ChunkWrite, Document, KnowledgeVault};
 use crate::{KnowledgeError, KnowledgeResult};
 
 /// Result of indexing a document
@@ -25,10 +28,32 @@ pub struct IndexResult {
     pub chunk_count: u32,
     /// Whether the document was updated (vs new)
     pub updated: bool,
+    /// Whether the document was skipped as a duplicate (same content hash)
+    pub skipped: bool,
+    /// Whether the document was removed from the vault
+    pub removed: bool,
+    /// Whether the document was moved to a new path without re-embedding
+    pub moved: bool,
+    /// Chunks kept from the previous version with their embeddings
+    pub reused_chunks: u32,
+    /// Chunks that were embedded
+    pub embedded_chunks: u32,
     /// Indexing time in milliseconds
     pub indexing_time_ms: u64,
 }
 
+/// Outcome of a file or content item processed by the background task
+///
+/// Sent on the results channel returned by [`DocumentIndexer::with_results`],
+/// one per file (directories produce one per file they contain).
+#[derive(Debug)]
+pub struct IndexOutcome {
+    /// Source path, if the item came from disk
+    pub path: Option<PathBuf>,
+    /// Indexing result or the error that stopped it
+    pub result: KnowledgeResult<IndexResult>,
+}
+
 /// Indexing commands sent through the channel
 #[derive(Debug, Clone)]
 pub enum IndexCommand {
@@ -48,17 +73,27 @@ pub enum IndexCommand {
     },
     /// Reindex a specific document
     Reindex(String),
+    /// Remove the document indexed from this path (file deleted)
+    RemoveByPath(PathBuf),
+    /// Move the document indexed from `from` to `to` (file renamed)
+    ///
+    /// Keeps chunks and embeddings when the content is unchanged; otherwise
+    /// the document is re-indexed from its new path.
+    MovePath { from: PathBuf, to: PathBuf },
     /// Shutdown the indexer
     Shutdown,
 }
 
 /// Configuration for the indexer
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct IndexerConfig {
     /// Skip if content hash matches existing document
     pub skip_duplicates: bool,
     /// Chunking options
     pub chunk_options: ChunkOptions,
+    /// Measures chunk sizes, usually the tokenizer of the model that reads
+    /// the chunks; estimated when unset
+    pub token_counter: Option<Arc<dyn TokenCounter>>,
     /// Channel buffer size
     pub channel_buffer: usize,
 }
@@ -68,11 +103,23 @@ impl Default for IndexerConfig {
         Self {
             skip_duplicates: true,
             chunk_options: ChunkOptions::default(),
+            token_counter: None,
             channel_buffer: 100,
         }
     }
 }
 
+impl std::fmt::Debug for IndexerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexerConfig")
+            .field("skip_duplicates", &self.skip_duplicates)
+            .field("chunk_options", &self.chunk_options)
+            .field("token_counter", &self.token_counter.is_some())
+            .field("channel_buffer", &self.channel_buffer)
+            .finish()
+    }
+}
+
 /// Channel-based document indexer
 ///
 /// This indexer uses an MPSC channel to avoid holding vault locks across await points.
@@ -98,7 +145,14 @@ impl DocumentIndexer {
     ) -> (Self, IndexerHandle) {
         let (command_tx, command_rx) = mpsc::channel(config.channel_buffer);
 
-        let handle = IndexerHandle::spawn(vault, embedder, command_rx, config.clone());
+        let handle = IndexerHandle::spawn(
+            vault,
+            embedder,
+            command_tx.clone(),
+            command_rx,
+            None,
+            config.clone(),
+        );
 
         let indexer = Self {
             command_tx,
@@ -108,6 +162,47 @@ impl DocumentIndexer {
         (indexer, handle)
     }
 
+    /// Create a channel-based indexer that reports every outcome
+    ///
+    /// Same as [`new`](Self::new), plus a receiver that gets one
+    /// [`IndexOutcome`] per indexed file or content item, including
+    /// skipped duplicates and failures. Use it to drive progress reporting.
+    pub fn with_results<E: EmbeddingProvider + Send + 'static>(
+        vault: Arc<Mutex<KnowledgeVault>>,
+        embedder: Arc<Mutex<E>>,
+        config: IndexerConfig,
+    ) -> (Self, IndexerHandle, mpsc::UnboundedReceiver<IndexOutcome>) {
+        let (command_tx, command_rx) = mpsc::channel(config.channel_buffer);
+        let (results_tx, results_rx) = mpsc::unbounded_channel();
+
+        let handle = IndexerHandle::spawn(
+            vault,
+            embedder,
+            command_tx.clone(),
+            command_rx,
+            Some(results_tx),
+            config.clone(),
+        );
+
+        let indexer = Self {
+            command_tx,
+            config,
+        };
+
+        (indexer, handle, results_rx)
+    }
+
+    /// Reindex a document by ID
+    pub async fn reindex(&self, document_id: String) -> KnowledgeResult<()> {
+        self.command_tx
+            .send(IndexCommand::Reindex(document_id))
+            .await
+            .map_err(|_| KnowledgeError::Internal(
+                "Indexer task shut down or channel full while reindexing".to_string()
+            ))?;
+        Ok(())
+    }
+
     /// Index a file from disk
     pub async fn index_file(&self, path: PathBuf) -> KnowledgeResult<()> {
         let cmd = IndexCommand::IndexFile(path);
@@ -154,7 +249,8 @@ impl DocumentIndexer {
 /// Background task handle for the indexer
 ///
 /// This owns the background task that processes indexing commands.
-/// When dropped, it will shutdown the task gracefully.
+/// `shutdown` queues a `Shutdown` command behind any pending work and
+/// waits for the task to finish it.
 pub struct IndexerHandle {
     task: tokio::task::JoinHandle<()>,
     shutdown_tx: mpsc::Sender<IndexCommand>,
@@ -165,7 +261,9 @@ impl IndexerHandle {
     fn spawn<E: EmbeddingProvider + Send + 'static>(
         vault: Arc<Mutex<KnowledgeVault>>,
         embedder: Arc<Mutex<E>>,
+        shutdown_tx: mpsc::Sender<IndexCommand>,
         mut command_rx: mpsc::Receiver<IndexCommand>,
+        results_tx: Option<mpsc::UnboundedSender<IndexOutcome>>,
         config: IndexerConfig,
     ) -> Self {
         let task = tokio::spawn(async move {
@@ -174,9 +272,11 @@ impl IndexerHandle {
             while let Some(cmd) = command_rx.recv().await {
                 match cmd {
                     IndexCommand::IndexFile(path) => {
-                        if let Err(e) = Self::do_index_file(&vault, &embedder, &config, &path).await {
+                        let result = Self::do_index_file(&vault, &embedder, &config, &path).await;
+                        if let Err(ref e) = result {
                             warn!("Failed to index file {:?}: {}", path, e);
                         }
+                        Self::report(&results_tx, Some(path), result);
                     }
                     IndexCommand::IndexContent {
                         content,
@@ -184,23 +284,46 @@ impl IndexerHandle {
                         doc_type,
                         path,
                     } => {
-                        if let Err(e) =
-                            Self::do_index_content(&vault, &embedder, &config, &content, &title, &doc_type, path.as_deref()).await
-                        {
+                        let result =
+                            Self::do_index_content(&vault, &embedder, &config, &ExtractedText::plain(content), &title, &doc_type, path.as_deref()).await;
+                        if let Err(ref e) = result {
                             warn!("Failed to index content '{}': {}", title, e);
                         }
+                        Self::report(&results_tx, path, result);
                     }
                     IndexCommand::IndexDirectory { path, extensions } => {
-                        if let Err(e) =
-                            Self::do_index_directory(&vault, &embedder, &config, &path, extensions.as_deref()).await
-                        {
-                            warn!("Failed to index directory {:?}: {}", path, e);
+                        match Self::do_index_directory(&vault, &embedder, &config, &path, extensions.as_deref()).await {
+                            Ok(outcomes) => {
+                                for outcome in outcomes {
+                                    Self::report(&results_tx, outcome.path, outcome.result);
+                                }
+                            }
+                            Err(e) => {
+                                warn!("Failed to index directory {:?}: {}", path, e);
+                                Self::report(&results_tx, Some(path), Err(e));
+                            }
                         }
                     }
                     IndexCommand::Reindex(doc_id) => {
-                        if let Err(e) = Self::do_reindex(&vault, &embedder, &config, &doc_id).await {
+                        let result = Self::do_reindex(&vault, &embedder, &config, &doc_id).await;
+                        if let Err(ref e) = result {
                             warn!("Failed to reindex {}: {}", doc_id, e);
                         }
+                        Self::report(&results_tx, None, result);
+                    }
+                    IndexCommand::RemoveByPath(path) => {
+                        let result = Self::do_remove_path(&vault, &path).await;
+                        if let Err(ref e) = result {
+                            warn!("Failed to remove {:?}: {}", path, e);
+                        }
+                        Self::report(&results_tx, Some(path), result);
+                    }
+                    IndexCommand::MovePath { from, to } => {
+                        let result = Self::do_move_path(&vault, &embedder, &config, &from, &to).await;
+                        if let Err(ref e) = result {
+                            warn!("Failed to move {:?} to {:?}: {}", from, to, e);
+                        }
+                        Self::report(&results_tx, Some(to), result);
                     }
                     IndexCommand::Shutdown => {
                         info!("Indexer shutdown command received");
@@ -212,15 +335,24 @@ impl IndexerHandle {
             info!("Indexer background task stopped");
         });
 
-        // Create a channel for sending shutdown command
-        let (shutdown_tx, _) = mpsc::channel(1);
-
         Self {
             task,
             shutdown_tx,
         }
     }
 
+    /// Send an outcome to the results channel, if anyone is listening
+    fn report(
+        results_tx: &Option<mpsc::UnboundedSender<IndexOutcome>>,
+        path: Option<PathBuf>,
+        result: KnowledgeResult<IndexResult>,
+    ) {
+        if let Some(tx) = results_tx {
+            // Receiver dropped means nobody cares about progress anymore
+            let _ = tx.send(IndexOutcome { path, result });
+        }
+    }
+
     /// Actually index a file (synchronous, no await points while holding lock)
     async fn do_index_file<E: EmbeddingProvider>(
         vault: &Arc<Mutex<KnowledgeVault>>,
@@ -230,8 +362,8 @@ impl IndexerHandle {
     ) -> KnowledgeResult<IndexResult> {
         let _start = std::time::Instant::now();
 
-        // Read file (async, no lock held)
-        let content = tokio::fs::read_to_string(path).await?;
+        // Read and extract text (async, no lock held)
+        let source = extract::read_file(path).await?;
 
         // Get filename for title (use to_str for proper UTF-8 handling)
         let filename = path
@@ -244,7 +376,7 @@ impl IndexerHandle {
         let doc_type = detect_document_type(&filename);
 
         // Now do the indexing (lock held only during sync operations)
-        Self::do_index_content(vault, embedder, config, &content, &filename, doc_type, Some(path))
+        Self::do_index_content(vault, embedder, config, &source, &filename, doc_type, Some(path))
             .await
     }
 
@@ -253,12 +385,13 @@ impl IndexerHandle {
         vault: &Arc<Mutex<KnowledgeVault>>,
         embedder: &Arc<Mutex<E>>,
         config: &IndexerConfig,
-        content: &str,
+        source: &ExtractedText,
         title: &str,
         doc_type: &str,
         path: Option<&Path>,
     ) -> KnowledgeResult<IndexResult> {
         let start = std::time::Instant::now();
+        let content = source.text.as_str();
 
         // Calculate content hash (outside lock)
         let content_hash = calculate_hash(content);
@@ -277,22 +410,61 @@ impl IndexerHandle {
                 document_id: String::new(),
                 chunk_count: 0,
                 updated: false,
+                skipped: true,
+                removed: false,
+                moved: false,
+                reused_chunks: 0,
+                embedded_chunks: 0,
                 indexing_time_ms: start.elapsed().as_millis() as u64,
             });
         }
 
+        // A changed file updates its previous version (paths are unique),
+        // keeping the chunks whose content did not change (lock held briefly)
+        let (previous, previous_chunks) = match path {
+            Some(p) => {
+                let vault_guard = vault.lock().await;
+                match vault_guard.get_document_by_path(&p.to_string_lossy())? {
+                    Some(previous) => {
+                        let chunks = vault_guard.get_chunks(&previous.id)?;
+                        (Some(previous), chunks)
+                    }
+                    None => (None, Vec::new()),
+                }
+            }
+            None => (None, Vec::new()),
+        };
+
         // Chunk the content (outside lock)
-        let chunker = Chunker::with_options(config.chunk_options.clone());
-        let chunks = chunker.chunk(content)?;
+        let mut chunker = Chunker::with_options(config.chunk_options.clone());
+        if let Some(counter) = &config.token_counter {
+            chunker = chunker.with_token_counter(counter.clone());
+        }
+        let chunks = match path {
+            Some(path) => chunker.chunk_extracted(source, path)?,
+            None => chunker.chunk(content)?,
+        };
         let chunk_count = chunks.len() as u32;
 
         debug!("Created {} chunks", chunk_count);
 
-        // Generate document ID (outside lock)
-        let doc_id = format!("doc_{}", Uuid::new_v4().simple());
+        // Keep the document ID across versions (outside lock)
+        let updated = previous.is_some();
+        let now = chrono::Utc::now();
+        let (doc_id, indexed_at, metadata) = match &previous {
+            Some(previous) => (
+                previous.id.clone(),
+                previous.indexed_at,
+                previous.metadata.clone(),
+            ),
+            None => (
+                format!("doc_{}", Uuid::new_v4().simple()),
+                now,
+                std::collections::HashMap::new(),
+            ),
+        };
 
         // Create document record (outside lock)
-        let now = chrono::Utc::now();
         let document = Document {
             id: doc_id.clone(),
             path: path.map(|p| p.to_string_lossy().to_string()),
@@ -301,52 +473,72 @@ impl IndexerHandle {
             content_hash,
             chunk_count,
             size_bytes: content.len() as u64,
-            indexed_at: now,
+            indexed_at,
             updated_at: now,
-            metadata: std::collections::HashMap::new(),
+            metadata,
         };
 
-        // Save document and chunks (lock held during database writes)
-        {
-            let vault_guard = vault.lock().await;
-            let embedder_guard = embedder.lock().await;
+        // Chunks of the previous version, by content hash
+        let mut kept: HashMap<String, VecDeque<String>> = HashMap::new();
+        for record in previous_chunks {
+            kept.entry(record.content_hash).or_default().push_back(record.id);
+        }
 
-            vault_guard.insert_document(&document)?;
-
-            // Process chunks
-            for (i, chunk) in chunks.iter().enumerate() {
-                let chunk_id = format!("chunk_{}_{}", doc_id, i);
-
-                // Save chunk
-                vault_guard.insert_chunk(
-                    &chunk_id,
-                    &doc_id,
-                    i as u32,
-                    &chunk.content,
-                    chunk.start_offset,
-                    chunk.end_offset,
-                    chunk.token_count,
-                )?;
-
-                // Generate and save embedding
-                // Note: This is still synchronous for now
-                // In the future, this could be batched
-                let embedding = embedder_guard.embed(&chunk.content).await?;
-                vault_guard.insert_embedding(&chunk_id, &embedding)?;
+        // Embed the new chunks (embedder lock only, vault stays available)
+        let mut writes = Vec::with_capacity(chunks.len());
+        let mut reused_chunks = 0;
+        let mut embedded_chunks = 0;
+        let model = {
+            let embedder_guard = embedder.lock().await;
+            for chunk in chunks {
+                let reused = kept
+                    .get_mut(&calculate_hash(&chunk.content))
+                    .and_then(VecDeque::pop_front);
+                match reused {
+                    Some(id) => {
+                        reused_chunks += 1;
+                        writes.push(ChunkWrite::Kept { id, chunk });
+                    }
+                    None => {
+                        // Fresh IDs never collide with chunks kept from earlier versions
+                        let id = format!("chunk_{}_{}", doc_id, Uuid::new_v4().simple());
+                        let embedding = embedder_guard.embed(&chunk.content).await?;
+                        embedded_chunks += 1;
+                        writes.push(ChunkWrite::New { id, chunk, embedding });
+                    }
+                }
             }
+            EmbeddingModel::of(&*embedder_guard)
+        };
+
+        // Whatever is left over no longer appears in the document
+        let stale: Vec<String> = kept.into_values().flatten().collect();
+
+        // Save document and chunks in one transaction
+        {
+            let vault_guard = vault.lock().await;
+            vault_guard.use_embedding_model(&model)?;
+            vault_guard.write_document(&document, &stale, &writes)?;
         }
 
         info!(
-            "Indexed document {} with {} chunks in {}ms",
+            "Indexed document {} with {} chunks ({} reused, {} embedded) in {}ms",
             doc_id,
             chunk_count,
+            reused_chunks,
+            embedded_chunks,
             start.elapsed().as_millis()
         );
 
         Ok(IndexResult {
             document_id: doc_id,
             chunk_count,
-            updated: false,
+            updated,
+            skipped: false,
+            removed: false,
+            moved: false,
+            reused_chunks,
+            embedded_chunks,
             indexing_time_ms: start.elapsed().as_millis() as u64,
         })
     }
@@ -358,7 +550,7 @@ impl IndexerHandle {
         config: &IndexerConfig,
         dir: &Path,
         extensions: Option<&[String]>,
-    ) -> KnowledgeResult<Vec<IndexResult>> {
+    ) -> KnowledgeResult<Vec<IndexOutcome>> {
         info!("Indexing directory: {:?}", dir);
 
         let mut results = Vec::new();
@@ -394,12 +586,14 @@ impl IndexerHandle {
                     };
 
                     if should_index {
-                        match Self::do_index_file(vault, embedder, config, &path).await {
-                            Ok(result) => results.push(result),
-                            Err(e) => {
-                                warn!("Failed to index {:?}: {}", path, e);
-                            }
+                        let result = Self::do_index_file(vault, embedder, config, &path).await;
+                        if let Err(ref e) = result {
+                            warn!("Failed to index {:?}: {}", path, e);
                         }
+                        results.push(IndexOutcome {
+                            path: Some(path),
+                            result,
+                        });
                     }
                 }
             }
@@ -409,6 +603,94 @@ impl IndexerHandle {
         Ok(results)
     }
 
+    /// Remove the document indexed from a path
+    async fn do_remove_path(
+        vault: &Arc<Mutex<KnowledgeVault>>,
+        path: &Path,
+    ) -> KnowledgeResult<IndexResult> {
+        let start = std::time::Instant::now();
+        let vault_guard = vault.lock().await;
+
+        let doc = vault_guard
+            .get_document_by_path(&path.to_string_lossy())?
+            .ok_or_else(|| KnowledgeError::NotFound(path.display().to_string()))?;
+        vault_guard.delete_document(&doc.id)?;
+
+        info!("Removed document {} for deleted file {:?}", doc.id, path);
+
+        Ok(IndexResult {
+            document_id: doc.id,
+            chunk_count: doc.chunk_count,
+            updated: false,
+            skipped: false,
+            removed: true,
+            moved: false,
+            reused_chunks: 0,
+            embedded_chunks: 0,
+            indexing_time_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Move a document to a new path, re-embedding only if content changed
+    async fn do_move_path<E: EmbeddingProvider>(
+        vault: &Arc<Mutex<KnowledgeVault>>,
+        embedder: &Arc<Mutex<E>>,
+        config: &IndexerConfig,
+        from: &Path,
+        to: &Path,
+    ) -> KnowledgeResult<IndexResult> {
+        let start = std::time::Instant::now();
+
+        // Read new file (async, no lock held)
+        let source = extract::read_file(to).await?;
+        let content_hash = calculate_hash(&source.text);
+
+        let existing = {
+            let vault_guard = vault.lock().await;
+            vault_guard.get_document_by_path(&from.to_string_lossy())?
+        };
+
+        let Some(doc) = existing else {
+            // Never indexed under the old path: index as a new file
+            return Self::do_index_file(vault, embedder, config, to).await;
+        };
+
+        let title = to
+            .file_name()
+            .and_then(|f| f.to_str())
+            .unwrap_or("Unknown")
+            .to_string();
+        let doc_type = detect_document_type(&title);
+
+        {
+            let vault_guard = vault.lock().await;
+            // A file replaced by the rename loses its document (paths are unique)
+            if let Some(replaced) = vault_guard.get_document_by_path(&to.to_string_lossy())? {
+                vault_guard.delete_document(&replaced.id)?;
+            }
+            vault_guard.move_document(&doc.id, &to.to_string_lossy(), &title, doc_type)?;
+        }
+
+        if doc.content_hash != content_hash {
+            debug!("Content changed during move, re-indexing {:?}", to);
+            return Self::do_index_file(vault, embedder, config, to).await;
+        }
+
+        info!("Moved document {} from {:?} to {:?}", doc.id, from, to);
+
+        Ok(IndexResult {
+            document_id: doc.id,
+            chunk_count: doc.chunk_count,
+            updated: false,
+            skipped: false,
+            removed: false,
+            moved: true,
+            reused_chunks: doc.chunk_count,
+            embedded_chunks: 0,
+            indexing_time_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+
     /// Reindex a specific document
     async fn do_reindex<E: EmbeddingProvider>(
         vault: &Arc<Mutex<KnowledgeVault>>,
@@ -424,17 +706,16 @@ impl IndexerHandle {
                 .ok_or_else(|| KnowledgeError::NotFound(document_id.to_string()))?
         };
 
-        // If we have the original path, read and reindex
+        // If we have the original path, re-chunk it in place; unchanged
+        // chunks keep their embeddings
         if let Some(ref path_str) = doc.path {
             let path = Path::new(path_str);
             if path.exists() {
-                // Delete existing document
-                {
-                    let vault_guard = vault.lock().await;
-                    vault_guard.delete_document(document_id)?;
-                }
-                // Reindex
-                return Self::do_index_file(vault, embedder, config, path).await;
+                let config = IndexerConfig {
+                    skip_duplicates: false,
+                    ..config.clone()
+                };
+                return Self::do_index_file(vault, embedder, &config, path).await;
             }
         }
 
@@ -534,6 +815,11 @@ impl<'a, E: EmbeddingProvider> LegacyDocumentIndexer<'a, E> {
                 document_id: String::new(),
                 chunk_count: 0,
                 updated: false,
+                skipped: true,
+                removed: false,
+                moved: false,
+                reused_chunks: 0,
+                embedded_chunks: 0,
                 indexing_time_ms: start.elapsed().as_millis() as u64,
             });
         }
@@ -542,7 +828,10 @@ impl<'a, E: EmbeddingProvider> LegacyDocumentIndexer<'a, E> {
         let doc_id = format!("doc_{}", Uuid::new_v4().simple());
 
         // Chunk the content
-        let chunks = self.chunker.chunk(content)?;
+        let chunks = match path {
+            Some(path) => self.chunker.chunk_source(content, path)?,
+            None => self.chunker.chunk(content)?,
+        };
         let chunk_count = chunks.len() as u32;
 
         debug!("Created {} chunks", chunk_count);
@@ -563,6 +852,7 @@ impl<'a, E: EmbeddingProvider> LegacyDocumentIndexer<'a, E> {
         };
 
         // Save document
+        self.vault.use_embedding_model(&EmbeddingModel::of(self.embedder))?;
         self.vault.insert_document(&document)?;
 
         // Process chunks
@@ -579,6 +869,9 @@ impl<'a, E: EmbeddingProvider> LegacyDocumentIndexer<'a, E> {
                 chunk.end_offset,
                 chunk.token_count,
             )?;
+            if !chunk.location.is_empty() {
+                self.vault.set_chunk_location(&chunk_id, &chunk.location)?;
+            }
 
             // Generate and save embedding
             let embedding = self.embedder.embed(&chunk.content).await?;
@@ -596,6 +889,11 @@ impl<'a, E: EmbeddingProvider> LegacyDocumentIndexer<'a, E> {
             document_id: doc_id,
             chunk_count,
             updated: false,
+            skipped: false,
+            removed: false,
+            moved: false,
+            reused_chunks: 0,
+            embedded_chunks: chunk_count,
             indexing_time_ms: start.elapsed().as_millis() as u64,
         })
     }
@@ -717,4 +1015,316 @@ mod tests {
         assert_ne!(hash1, hash3);
         assert_eq!(hash1.len(), 64); // SHA256 = 64 hex chars
     }
+
+    #[tokio::test]
+    async fn test_with_results_reports_each_file() {
+        use crate::embeddings::PlaceholderEmbedder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.md");
+        let copy = dir.path().join("copy.md");
+        std::fs::write(&notes, "# Notes\n\nThe council reaches consensus.").unwrap();
+        std::fs::write(&copy, "# Notes\n\nThe council reaches consensus.").unwrap();
+
+        let vault = Arc::new(Mutex::new(KnowledgeVault::in_memory().unwrap()));
+        let embedder = Arc::new(Mutex::new(PlaceholderEmbedder::new(384)));
+        let (indexer, _handle, mut results) =
+            DocumentIndexer::with_results(vault.clone(), embedder, IndexerConfig::default());
+
+        indexer.index_file(notes.clone()).await.unwrap();
+        indexer.index_file(copy.clone()).await.unwrap();
+        indexer.index_file(dir.path().join("missing.md")).await.unwrap();
+
+        let first = results.recv().await.unwrap();
+        assert_eq!(first.path.as_deref(), Some(notes.as_path()));
+        let first = first.result.unwrap();
+        assert!(!first.skipped && first.chunk_count > 0);
+
+        let second = results.recv().await.unwrap().result.unwrap();
+        assert!(second.skipped);
+
+        let third = results.recv().await.unwrap();
+        assert!(third.result.is_err());
+
+        // Changing the file updates its previous version
+        std::fs::write(&notes, "# Notes\n\nRewritten entirely.").unwrap();
+        indexer.index_file(notes.clone()).await.unwrap();
+        let fourth = results.recv().await.unwrap().result.unwrap();
+        assert!(fourth.updated);
+        assert_eq!(fourth.document_id, first.document_id);
+
+        let stats = vault.lock().await.stats().unwrap();
+        assert_eq!(stats.document_count, 1);
+        assert_eq!(stats.chunk_count, u64::from(fourth.chunk_count));
+    }
+
+    #[tokio::test]
+    async fn test_chunks_sized_by_token_counter() {
+        use crate::embeddings::PlaceholderEmbedder;
+
+        /// One token per word
+        struct Words;
+        impl TokenCounter for Words {
+            fn count_tokens(&self, text: &str) -> usize {
+                text.split_whitespace().count()
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.md");
+        std::fs::write(&path, "One two three four.\n\nFive six seven eight.\n\nNine ten.").unwrap();
+
+        let chunk_count = |token_counter: Option<Arc<dyn TokenCounter>>| {
+            let path = path.clone();
+            async move {
+                let vault = Arc::new(Mutex::new(KnowledgeVault::in_memory().unwrap()));
+                let embedder = Arc::new(Mutex::new(PlaceholderEmbedder::new(384)));
+                let config = IndexerConfig {
+                    chunk_options: ChunkOptions {
+                        chunk_size: 6,
+                        chunk_overlap: 0,
+                        min_chunk_size: 1,
+                        ..Default::default()
+                    },
+                    token_counter,
+                    ..Default::default()
+                };
+                let (indexer, _handle, mut results) =
+                    DocumentIndexer::with_results(vault, embedder, config);
+                indexer.index_file(path).await.unwrap();
+                results.recv().await.unwrap().result.unwrap().chunk_count
+            }
+        };
+
+        // By word count the paragraphs are 4, 4 and 2 tokens, so the last two
+        // share a chunk; the byte estimate makes them 4, 5 and 2
+        assert_eq!(chunk_count(Some(Arc::new(Words))).await, 2);
+        assert_eq!(chunk_count(None).await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_update_reembeds_only_changed_chunks() {
+        use crate::embeddings::PlaceholderEmbedder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("design.md");
+        let sections = [
+            "Pathos reads the intent behind each request.",
+            "Logos retrieves context from the knowledge vault.",
+            "Ethos verifies every claim before it is answered.",
+        ];
+        std::fs::write(&path, sections.join("\n\n")).unwrap();
+
+        let vault = Arc::new(Mutex::new(KnowledgeVault::in_memory().unwrap()));
+        let embedder = Arc::new(Mutex::new(PlaceholderEmbedder::new(384)));
+        let config = IndexerConfig {
+            chunk_options: ChunkOptions {
+                chunk_size: 12,
+                chunk_overlap: 0,
+                min_chunk_size: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (indexer, _handle, mut results) =
+            DocumentIndexer::with_results(vault.clone(), embedder, config);
+
+        indexer.index_file(path.clone()).await.unwrap();
+        let first = results.recv().await.unwrap().result.unwrap();
+        assert_eq!(first.chunk_count, 3);
+        assert_eq!((first.reused_chunks, first.embedded_chunks), (0, 3));
+        let before = vault.lock().await.get_chunks(&first.document_id).unwrap();
+
+        // Edit the middle section and add a new one in front
+        let edited = [
+            "Overview of the council.",
+            sections[0],
+            "Logos retrieves context from local documents.",
+            sections[2],
+        ];
+        std::fs::write(&path, edited.join("\n\n")).unwrap();
+        indexer.index_file(path.clone()).await.unwrap();
+        let second = results.recv().await.unwrap().result.unwrap();
+        assert!(second.updated);
+        assert_eq!(second.document_id, first.document_id);
+        assert_eq!(second.chunk_count, 4);
+        assert_eq!((second.reused_chunks, second.embedded_chunks), (2, 2));
+
+        {
+            let vault = vault.lock().await;
+            let after = vault.get_chunks(&second.document_id).unwrap();
+            let contents: Vec<&str> = after.iter().map(|c| c.content.as_str()).collect();
+            assert_eq!(contents, edited);
+            // Unchanged chunks keep their IDs, at their new positions
+            assert_eq!(after[1].id, before[0].id);
+            assert_eq!(after[3].id, before[2].id);
+            assert_eq!(after[3].chunk_index, 3);
+            assert!(vault.get_chunk(&before[1].id).unwrap().is_none());
+
+            let stats = vault.stats().unwrap();
+            assert_eq!(stats.document_count, 1);
+            assert_eq!(stats.chunk_count, 4);
+            assert_eq!(stats.embedding_count, 4);
+        }
+
+        // Reindexing unchanged content embeds nothing
+        indexer.reindex(second.document_id.clone()).await.unwrap();
+        let third = results.recv().await.unwrap().result.unwrap();
+        assert!(!third.skipped);
+        assert_eq!((third.reused_chunks, third.embedded_chunks), (4, 0));
+    }
+
+    #[tokio::test]
+    async fn test_move_and_remove_by_path() {
+        use crate::embeddings::PlaceholderEmbedder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.md");
+        let new = dir.path().join("new.txt");
+        std::fs::write(&old, "Ethos checks every claim.").unwrap();
+
+        let vault = Arc::new(Mutex::new(KnowledgeVault::in_memory().unwrap()));
+        let embedder = Arc::new(Mutex::new(PlaceholderEmbedder::new(384)));
+        let (indexer, _handle, mut results) =
+            DocumentIndexer::with_results(vault.clone(), embedder, IndexerConfig::default());
+        let commands = indexer.command_sender();
+
+        indexer.index_file(old.clone()).await.unwrap();
+        let indexed = results.recv().await.unwrap().result.unwrap();
+
+        // Unchanged content: same document, no re-embedding
+        std::fs::rename(&old, &new).unwrap();
+        commands
+            .send(IndexCommand::MovePath { from: old.clone(), to: new.clone() })
+            .await
+            .unwrap();
+        let moved = results.recv().await.unwrap().result.unwrap();
+        assert!(moved.moved);
+        assert_eq!(moved.document_id, indexed.document_id);
+        {
+            let vault = vault.lock().await;
+            let doc = vault.get_document_by_path(&new.to_string_lossy()).unwrap().unwrap();
+            assert_eq!(doc.title, "new.txt");
+            assert_eq!(doc.doc_type, "text");
+            assert!(vault.get_document_by_path(&old.to_string_lossy()).unwrap().is_none());
+        }
+
+        commands.send(IndexCommand::RemoveByPath(new.clone())).await.unwrap();
+        let removed = results.recv().await.unwrap().result.unwrap();
+        assert!(removed.removed);
+
+        let stats = vault.lock().await.stats().unwrap();
+        assert_eq!(stats.document_count, 0);
+        assert_eq!(stats.chunk_count, 0);
+        assert_eq!(stats.embedding_count, 0);
+
+        // Removing an unknown path reports NotFound
+        commands.send(IndexCommand::RemoveByPath(old)).await.unwrap();
+        assert!(matches!(
+            results.recv().await.unwrap().result,
+            Err(KnowledgeError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_html_files_store_sections() {
+        use crate::embeddings::PlaceholderEmbedder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("guide.html");
+        std::fs::write(
+            &path,
+            "<nav>Home</nav><h1>Guide</h1><p>Overview.</p><h2>Install</h2><p>Run the installer.</p>",
+        )
+        .unwrap();
+
+        let vault = Arc::new(Mutex::new(KnowledgeVault::in_memory().unwrap()));
+        let embedder = Arc::new(Mutex::new(PlaceholderEmbedder::new(384)));
+        let config = IndexerConfig {
+            chunk_options: ChunkOptions {
+                chunk_size: 4,
+                chunk_overlap: 0,
+                min_chunk_size: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (indexer, _handle, mut results) =
+            DocumentIndexer::with_results(vault.clone(), embedder, config);
+
+        indexer.index_file(path).await.unwrap();
+        let indexed = results.recv().await.unwrap().result.unwrap();
+
+        let vault = vault.lock().await;
+        let chunks = vault.get_chunks(&indexed.document_id).unwrap();
+        assert!(chunks.iter().all(|c| !c.content.contains("Home")));
+        let last = chunks.last().unwrap();
+        assert!(last.content.contains("Run the installer."));
+        assert_eq!(last.location.section.as_deref(), Some("Guide > Install"));
+        assert_eq!(vault.get_document(&indexed.document_id).unwrap().unwrap().doc_type, "html");
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_with_watcher_sender_alive() {
+        use crate::embeddings::PlaceholderEmbedder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.md");
+        std::fs::write(&notes, "# Notes\n\nQueued before shutdown.").unwrap();
+
+        let vault = Arc::new(Mutex::new(KnowledgeVault::in_memory().unwrap()));
+        let embedder = Arc::new(Mutex::new(PlaceholderEmbedder::new(384)));
+        let (indexer, handle, mut results) =
+            DocumentIndexer::with_results(vault, embedder, IndexerConfig::default());
+
+        // A file watcher keeps its own sender alive across shutdown
+        let watcher_tx = indexer.command_sender();
+        watcher_tx.send(IndexCommand::IndexFile(notes)).await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(1), handle.shutdown())
+            .await
+            .expect("shutdown should not wait for the fallback timeout");
+
+        // Work queued before shutdown is still processed
+        assert!(results.recv().await.unwrap().result.is_ok());
+        assert!(watcher_tx.send(IndexCommand::Shutdown).await.is_err());
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[tokio::test]
+    async fn test_code_files_store_symbols() {
+        use crate::embeddings::PlaceholderEmbedder;
+        use crate::search::{SearchOptions, VectorSearch};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("routing.rs");
+        std::fs::write(
+            &path,
+            "struct Router;\n\nimpl Router {\n    /// Route a request\n    fn route(&self) {}\n}\n",
+        )
+        .unwrap();
+
+        let vault = Arc::new(Mutex::new(KnowledgeVault::in_memory().unwrap()));
+        let embedder = Arc::new(Mutex::new(PlaceholderEmbedder::new(384)));
+        let (indexer, _handle, mut results) =
+            DocumentIndexer::with_results(vault.clone(), embedder, IndexerConfig::default());
+
+        indexer.index_file(path).await.unwrap();
+        let indexed = results.recv().await.unwrap().result.unwrap();
+        assert_eq!(indexed.chunk_count, 2);
+
+        let vault = vault.lock().await;
+        let chunks = vault.get_chunks(&indexed.document_id).unwrap();
+        let impl_symbol = chunks[1].location.symbol.as_ref().unwrap();
+        assert_eq!(impl_symbol.to_string(), "impl Router");
+        assert_eq!((impl_symbol.start_line, impl_symbol.end_line), (3, 6));
+
+        // Search results carry the symbol
+        let query = PlaceholderEmbedder::new(384).embed(&chunks[1].content).await.unwrap();
+        let hits = VectorSearch::new(&vault)
+            .search(&query, &SearchOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(hits[0].location.symbol.as_ref(), Some(impl_symbol));
+    }
 }
@@ -8,11 +8,16 @@
 //! The knowledge system is built around several components:
 //!
 //! - **Vault** ([`KnowledgeVault`]): SQLite-based storage with vector similarity search
+//! - **Extraction** ([`extract`]): Turns PDF, HTML and DOCX into text, tracking pages and sections
 //! - **Chunker** ([`Chunker`]): Splits documents into optimal-sized chunks for embedding
+//! - **Syntax** ([`syntax`]): Splits Rust, Python, JS/TS and Go along their syntax tree,
+//!   tagging chunks with a [`CodeSymbol`] (`tree-sitter` feature)
 //! - **Embeddings** ([`LocalEmbedder`]): Generates vector embeddings for text
 //! - **Indexer** ([`DocumentIndexer`]): Automates document ingestion and indexing
+//! - **Migration** ([`migrate`]): Re-embeds a vault with a new [`EmbeddingModel`]
 //! - **Watcher** ([`FileWatcher`]): Monitors files for changes and auto-reindexes
-//! - **Search** ([`VectorSearch`]): Performs semantic similarity queries
+//! - **Search** ([`VectorSearch`], [`HybridSearch`]): Semantic and keyword queries with
+//!   rank fusion, optional cross-encoder reranking ([`Reranker`]) and MMR diversification
 //!
 //! ## Usage Example
 //!
@@ -48,20 +53,20 @@
 //! - **Content Hash**: SHA256 for deduplication
 //! - **Chunks**: Document split into optimal pieces (default: 512 tokens)
 //! - **Embeddings**: Vector representations for each chunk
-//! - **Type Detection**: Automatic classification (code, markdown, text)
+//! - **Type Detection**: Automatic classification (code, markdown, text, pdf, html, docx)
+//! - **Location**: Code symbol, page or section of each chunk ([`ChunkLocation`])
 //!
 //! ## Vector Search
 //!
-//! The vault supports two search modes:
+//! The vault keeps an HNSW graph ([`HnswIndex`]) over every embedding:
 //!
-//! 1. **VSS (Virtual Table)**: Fast approximate nearest neighbor search
-//!    - Requires SQLite-VSS extension
-//!    - Best for large datasets (>10k chunks)
+//! - Updated incrementally by `insert_embedding` / `delete_document`
+//! - Saved next to the database (`knowledge.db` → `knowledge.hnsw`) and
+//!   reloaded on open
+//! - Rebuilt from SQLite when the saved file is missing or out of date, or
+//!   on demand with [`KnowledgeVault::rebuild_ann_index`]
 //!
-//! 2. **Cosine Similarity**: Exact similarity calculation
-//!    - Pure Rust implementation
-//!    - Fallback when VSS unavailable
-//!    - Suitable for smaller datasets
+//! No SQLite extension is required.
 //!
 //! ## File Watching
 //!
@@ -84,18 +89,35 @@
 //! # }
 //! ```
 
+pub mod ann;
 pub mod chunker;
 pub mod embeddings;
+pub mod extract;
+pub mod filter;
 pub mod indexer;
+pub mod migrate;
+pub mod rerank;
 pub mod search;
+pub mod syntax;
 pub mod vault;
 pub mod watcher;
 
-pub use chunker::{Chunk, ChunkOptions, Chunker};
-pub use embeddings::{EmbeddingProvider, LocalEmbedder, PlaceholderEmbedder};
-pub use indexer::{DocumentIndexer, IndexCommand, IndexerConfig, IndexerHandle, IndexResult};
-pub use search::{SearchOptions, SearchResult, VectorSearch};
-pub use vault::{ChunkResult, Document, KnowledgeVault, VaultStats};
+pub use ann::{HnswIndex, HnswParams};
+pub use chunker::{Chunk, ChunkLocation, ChunkOptions, Chunker};
+pub use embeddings::{EmbeddingModel, EmbeddingProvider, LocalEmbedder, PlaceholderEmbedder};
+pub use extract::{ExtractedText, SourceFormat};
+pub use filter::{Comparison, DateRange, MetadataCondition, MetadataScope, SearchFilter};
+pub use indexer::{
+    DocumentIndexer, IndexCommand, IndexOutcome, IndexResult, IndexerConfig, IndexerHandle,
+};
+pub use rerank::{CrossEncoderReranker, Reranker};
+pub use search::{
+    FacetedResults, FusionStrategy, HybridSearch, SearchFacets, SearchOptions, SearchResult,
+    VectorSearch,
+};
+pub use syntax::{CodeLanguage, CodeSymbol, SymbolKind};
+pub use migrate::migrate_embeddings;
+pub use vault::{ChunkResult, ChunkWrite, Document, KnowledgeVault, MigrationStatus, VaultStats};
 pub use watcher::{FileWatcher, WatchConfig};
 
 /// Result type for knowledge operations
@@ -113,6 +135,9 @@ pub enum KnowledgeError {
     #[error("Embedding error: {0}")]
     EmbeddingError(String),
 
+    #[error("Embedding model mismatch: {0}")]
+    ModelMismatch(String),
+
     #[error("Database error: {0}")]
     DatabaseError(String),
 
@@ -125,6 +150,9 @@ pub enum KnowledgeError {
     #[error("Watch error: {0}")]
     WatchError(String),
 
+    #[error("Rerank error: {0}")]
+    RerankError(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -0,0 +1,184 @@
+//! Embedding Model Migration
+//!
+//! Re-embeds every chunk in a vault with a new model. Work is done in
+//! batches, taking the vault lock only to read a batch and store its
+//! vectors, so indexing and search carry on in between. Staged vectors
+//! are kept in the database: an interrupted migration resumes where it
+//! stopped when started again with the same model.
+
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use crate::embeddings::{EmbeddingModel, EmbeddingProvider};
+use crate::vault::{KnowledgeVault, MigrationStatus};
+use crate::KnowledgeResult;
+
+/// Chunks embedded per batch by default
+pub const DEFAULT_BATCH_SIZE: usize = 32;
+
+/// Migrate a vault to `embedder`'s model
+///
+/// `progress` is called after every batch. Chunks added while the
+/// migration runs are picked up before it finishes. Returns the final
+/// status, or `None` if the vault already uses this model.
+pub async fn migrate_embeddings<E: EmbeddingProvider>(
+    vault: &Arc<Mutex<KnowledgeVault>>,
+    embedder: &E,
+    batch_size: usize,
+    mut progress: impl FnMut(&MigrationStatus),
+) -> KnowledgeResult<Option<MigrationStatus>> {
+    let model = EmbeddingModel::of(embedder);
+    let Some(mut status) = vault.lock().await.begin_migration(&model)? else {
+        info!("Vault already uses {}", model);
+        return Ok(None);
+    };
+    progress(&status);
+
+    loop {
+        // Finish under the same lock that found nothing left, so no chunk
+        // can slip in between
+        let batch = {
+            let mut vault_guard = vault.lock().await;
+            let batch = vault_guard.chunks_to_migrate(batch_size.max(1))?;
+            if batch.is_empty() {
+                vault_guard.finish_migration()?;
+                status.embedded = status.total;
+                return Ok(Some(status));
+            }
+            batch
+        };
+
+        // Embed without holding the lock
+        let texts: Vec<&str> = batch.iter().map(|(_, content)| content.as_str()).collect();
+        let embeddings = embedder.embed_batch(&texts).await?;
+        debug!("Embedded {} chunks with {}", embeddings.len(), model.name);
+
+        {
+            let vault_guard = vault.lock().await;
+            for ((chunk_id, _), embedding) in batch.iter().zip(&embeddings) {
+                vault_guard.stage_embedding(chunk_id, embedding)?;
+            }
+            if let Some(current) = vault_guard.migration_status()? {
+                status = current;
+            }
+        }
+        progress(&status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embeddings::PlaceholderEmbedder;
+    use crate::indexer::{DocumentIndexer, IndexerConfig};
+    use crate::search::{SearchOptions, VectorSearch};
+    use crate::KnowledgeError;
+    use async_trait::async_trait;
+
+    /// Placeholder vectors under another model name, with their own dimensions
+    struct WideEmbedder(PlaceholderEmbedder);
+
+    #[async_trait]
+    impl EmbeddingProvider for WideEmbedder {
+        async fn embed(&self, text: &str) -> KnowledgeResult<Vec<f32>> {
+            self.0.embed(text).await
+        }
+
+        async fn embed_batch(&self, texts: &[&str]) -> KnowledgeResult<Vec<Vec<f32>>> {
+            self.0.embed_batch(texts).await
+        }
+
+        fn dimensions(&self) -> u32 {
+            self.0.dimensions()
+        }
+
+        fn model_name(&self) -> &str {
+            "wide-test"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_migration_switches_model_and_resumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("knowledge.db");
+        for (name, text) in [("a.md", "Pathos reads intent."), ("b.md", "Ethos verifies.")] {
+            std::fs::write(dir.path().join(name), text).unwrap();
+        }
+
+        let vault = Arc::new(Mutex::new(KnowledgeVault::open(&db_path, 384).unwrap()));
+        let old = Arc::new(Mutex::new(PlaceholderEmbedder::new(384)));
+        let (indexer, _handle, mut results) =
+            DocumentIndexer::with_results(vault.clone(), old, IndexerConfig::default());
+        indexer.index_file(dir.path().join("a.md")).await.unwrap();
+        indexer.index_file(dir.path().join("b.md")).await.unwrap();
+        results.recv().await.unwrap().result.unwrap();
+        results.recv().await.unwrap().result.unwrap();
+
+        let wide = WideEmbedder(PlaceholderEmbedder::new(512));
+        let model = EmbeddingModel::of(&wide);
+        {
+            let vault = vault.lock().await;
+            assert_eq!(vault.embedding_model().unwrap().unwrap().name, "placeholder-sha256");
+            assert!(matches!(
+                vault.use_embedding_model(&model),
+                Err(KnowledgeError::ModelMismatch(_))
+            ));
+
+            // Interrupted after one chunk
+            vault.begin_migration(&model).unwrap();
+            let (chunk_id, content) = vault.chunks_to_migrate(1).unwrap().remove(0);
+            vault.stage_embedding(&chunk_id, &wide.embed(&content).await.unwrap()).unwrap();
+            assert!(vault.stage_embedding(&chunk_id, &[0.0; 384]).is_err());
+        }
+
+        // Searches keep using the old model meanwhile
+        {
+            let vault = vault.lock().await;
+            let query = PlaceholderEmbedder::new(384);
+            let hits = VectorSearch::new(&vault)
+                .search_text("Ethos verifies.", &query, &SearchOptions::default())
+                .await
+                .unwrap();
+            assert!(!hits.is_empty());
+        }
+
+        let mut reports = Vec::new();
+        let status = migrate_embeddings(&vault, &wide, 8, |s| reports.push(s.embedded))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reports, vec![1, 2]);
+        assert_eq!((status.embedded, status.total), (2, 2));
+        assert_eq!(status.to, model);
+
+        {
+            let vault = vault.lock().await;
+            let stats = vault.stats().unwrap();
+            assert_eq!(stats.embedding_model.as_ref(), Some(&model));
+            assert_eq!(stats.embedding_dimensions, 512);
+            assert_eq!(stats.embedding_count, 2);
+            assert!(vault.migration_status().unwrap().is_none());
+
+            let old = PlaceholderEmbedder::new(384);
+            let search = VectorSearch::new(&vault);
+            assert!(search
+                .search_text("Ethos verifies.", &old, &SearchOptions::default())
+                .await
+                .is_err());
+            let hits = search
+                .search_text("Ethos verifies.", &wide, &SearchOptions::default())
+                .await
+                .unwrap();
+            assert_eq!(hits[0].content.as_deref(), Some("Ethos verifies."));
+        }
+
+        // Reopening picks up the recorded model's dimensions
+        let reopened = KnowledgeVault::open(&db_path, 384).unwrap();
+        assert_eq!(reopened.stats().unwrap().embedding_dimensions, 512);
+        assert!(migrate_embeddings(&Arc::new(Mutex::new(reopened)), &wide, 8, |_| {})
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
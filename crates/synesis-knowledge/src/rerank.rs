@@ -0,0 +1,129 @@
+//! Reranking
+//!
+//! Second-stage scoring of search candidates. A cross-encoder reads the
+//! query and passage together, so it ranks more accurately than comparing
+//! independently computed embeddings, but costs one model pass per
+//! candidate. [`HybridSearch`](crate::search::HybridSearch) therefore only
+//! reranks the top N fused results (see [`SearchOptions::rerank_top_n`]).
+//!
+//! [`SearchOptions::rerank_top_n`]: crate::search::SearchOptions::rerank_top_n
+
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Arc;
+use synesis_models::{MemoryBudget, ModelPool};
+use tracing::debug;
+
+use crate::{KnowledgeError, KnowledgeResult};
+
+/// Name of the model inside a pool created by [`CrossEncoderReranker::load`]
+const POOL_MODEL_NAME: &str = "reranker";
+
+/// Trait for second-stage rerankers
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    /// Score each passage's relevance to the query (higher is better)
+    ///
+    /// Must return exactly one score per passage, in order.
+    async fn rerank(&self, query: &str, passages: &[&str]) -> KnowledgeResult<Vec<f32>>;
+
+    /// Get model name
+    fn model_name(&self) -> &str;
+}
+
+/// Cross-encoder reranker running on a `synesis-models` model pool
+pub struct CrossEncoderReranker {
+    pool: Arc<ModelPool>,
+    model_name: String,
+}
+
+impl CrossEncoderReranker {
+    /// Use a model that has been added to the pool
+    ///
+    /// The model must be loaded before searching.
+    pub fn new(pool: Arc<ModelPool>, model_name: impl Into<String>) -> Self {
+        Self {
+            pool,
+            model_name: model_name.into(),
+        }
+    }
+
+    /// Load a cross-encoder model file into its own pool
+    pub async fn load(model_path: &Path) -> KnowledgeResult<Self> {
+        let pool = Arc::new(ModelPool::new(MemoryBudget::unlimited()));
+        pool.add(POOL_MODEL_NAME.to_string(), model_path.to_path_buf())
+            .await
+            .map_err(|e| KnowledgeError::RerankError(e.to_string()))?;
+        pool.load(POOL_MODEL_NAME)
+            .await
+            .map_err(|e| KnowledgeError::RerankError(e.to_string()))?;
+
+        Ok(Self::new(pool, POOL_MODEL_NAME))
+    }
+}
+
+#[async_trait]
+impl Reranker for CrossEncoderReranker {
+    async fn rerank(&self, query: &str, passages: &[&str]) -> KnowledgeResult<Vec<f32>> {
+        debug!(
+            "Reranking {} passages with {}",
+            passages.len(),
+            self.model_name
+        );
+
+        let scores = self
+            .pool
+            .rerank(&self.model_name, query, passages)
+            .await
+            .map_err(|e| KnowledgeError::RerankError(e.to_string()))?;
+
+        if scores.len() != passages.len() {
+            return Err(KnowledgeError::RerankError(format!(
+                "Expected {} scores, got {}",
+                passages.len(),
+                scores.len()
+            )));
+        }
+        Ok(scores)
+    }
+
+    fn model_name(&self) -> &str {
+        &self.model_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cross_encoder_load_and_rerank() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bge-reranker.gguf");
+
+        assert!(CrossEncoderReranker::load(&path).await.is_err());
+
+        // Not a GGUF file
+        std::fs::write(&path, b"GGUF").unwrap();
+        assert!(CrossEncoderReranker::load(&path).await.is_err());
+
+        // Minimal GGUF v3 header: no tensors, only general.architecture
+        let mut header = b"GGUF".to_vec();
+        header.extend_from_slice(&3u32.to_le_bytes());
+        header.extend_from_slice(&0u64.to_le_bytes());
+        header.extend_from_slice(&1u64.to_le_bytes());
+        header.extend_from_slice(&20u64.to_le_bytes());
+        header.extend_from_slice(b"general.architecture");
+        header.extend_from_slice(&8u32.to_le_bytes());
+        header.extend_from_slice(&4u64.to_le_bytes());
+        header.extend_from_slice(b"bert");
+        std::fs::write(&path, header).unwrap();
+        let reranker = CrossEncoderReranker::load(&path).await.unwrap();
+        let scores = reranker
+            .rerank("hnsw index", &["an hnsw index", "unrelated"])
+            .await
+            .unwrap();
+        assert_eq!(scores.len(), 2);
+        assert!(scores[0] > scores[1]);
+    }
+}
@@ -2,13 +2,31 @@
 //!
 //! Provides similarity search over document embeddings.
 //! Includes hybrid search combining vector similarity and keyword matching.
+//!
+//! ## Hybrid Pipeline
+//!
+//! 1. Vector (HNSW) and keyword (BM25) retrieval, each fetching
+//!    [`SearchOptions::candidate_count`] candidates
+//! 2. Fusion of the two rankings ([`FusionStrategy`])
+//! 3. Optional cross-encoder reranking of the top N ([`Reranker`])
+//! 4. Optional MMR diversification, so near-duplicate chunks don't crowd
+//!    out everything else
+//!
+//! Both retrievers honour [`SearchOptions::filter`]; documents it selects
+//! are scored exactly when few enough, otherwise ANN candidates outside
+//! them are dropped. [`HybridSearch::search_with_facets`] also counts the
+//! matching documents by type and tag.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
 use tracing::{debug, instrument};
 
-use crate::embeddings::{cosine_similarity, EmbeddingProvider};
-use crate::vault::KnowledgeVault;
+use crate::embeddings::{cosine_similarity, EmbeddingModel, EmbeddingProvider};
+use crate::rerank::Reranker;
+use crate::chunker::ChunkLocation;
+use crate::filter::SearchFilter;
+use crate::vault::{ChunkRecord, Document, KnowledgeVault};
 use crate::{KnowledgeError, KnowledgeResult};
 
 /// Search options
@@ -24,6 +42,67 @@ pub struct SearchOptions {
     pub doc_ids: Option<Vec<String>>,
     /// Include chunk content in results
     pub include_content: bool,
+    /// How [`HybridSearch`] combines vector and keyword rankings
+    #[serde(default)]
+    pub fusion: FusionStrategy,
+    /// Rerank this many fused candidates (at least `limit`) with the
+    /// search's [`Reranker`]; ignored if none is configured
+    #[serde(default)]
+    pub rerank_top_n: Option<usize>,
+    /// Diversify results with maximal marginal relevance: 1.0 ranks purely
+    /// by relevance, lower values penalize chunks similar to ones already
+    /// selected
+    #[serde(default)]
+    pub mmr_lambda: Option<f32>,
+    /// Path, tag, date and metadata conditions
+    #[serde(default)]
+    pub filter: SearchFilter,
+}
+
+/// Chunks in filtered documents up to which they are scored exactly
+/// instead of through the ANN index
+const EXACT_SCAN_CHUNKS: u64 = 4096;
+
+/// Candidates fetched from each ranking when counting facets, so the
+/// counts reflect more than the page of results returned
+const FACET_CANDIDATES: usize = 200;
+
+impl SearchOptions {
+    /// Candidates fetched from each retriever before fusion
+    ///
+    /// Widened when later stages need room to reorder.
+    pub fn candidate_count(&self) -> usize {
+        let mut count = self.limit;
+        if self.mmr_lambda.is_some() {
+            count = count.saturating_mul(3);
+        }
+        if let Some(top_n) = self.rerank_top_n {
+            count = count.max(top_n);
+        }
+        count
+    }
+}
+
+/// Strategy for combining vector and keyword rankings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum FusionStrategy {
+    /// Weighted sum of raw scores (cosine similarity and normalized BM25)
+    Weighted,
+    /// Reciprocal rank fusion: each list contributes `weight / (k + rank)`
+    ///
+    /// Uses only rank positions, so the retrievers' score scales don't
+    /// matter. Scores are scaled so a first place in both lists is 1.0.
+    ReciprocalRank {
+        /// Rank offset; larger values flatten the advantage of top ranks
+        k: f32,
+    },
+}
+
+impl Default for FusionStrategy {
+    fn default() -> Self {
+        Self::ReciprocalRank { k: 60.0 }
+    }
 }
 
 impl Default for SearchOptions {
@@ -34,6 +113,10 @@ impl Default for SearchOptions {
             doc_types: None,
             doc_ids: None,
             include_content: true,
+            fusion: FusionStrategy::default(),
+            rerank_top_n: None,
+            mmr_lambda: None,
+            filter: SearchFilter::default(),
         }
     }
 }
@@ -57,6 +140,9 @@ pub struct SearchResult {
     pub start_offset: u64,
     /// End offset in document
     pub end_offset: u64,
+    /// Where the chunk came from (code symbol, page or section)
+    #[serde(default)]
+    pub location: ChunkLocation,
 }
 
 /// Vector search engine
@@ -83,75 +169,180 @@ impl<'a> VectorSearch<'a> {
             options.limit
         );
 
-        // Get all documents (with optional filtering)
-        let documents = self.vault.list_documents(1000)?;
+        let mut results = match self.document_scope(options)? {
+            None => self.score_nearest(query_embedding, None, options)?,
+            // Known or few documents: score their chunks exactly
+            Some(docs)
+                if options.doc_ids.is_some()
+                    || docs.iter().map(|d| u64::from(d.chunk_count)).sum::<u64>()
+                        <= EXACT_SCAN_CHUNKS =>
+            {
+                self.score_documents(query_embedding, &docs, options)?
+            },
+            Some(docs) => {
+                let allowed: HashSet<String> = docs.into_iter().map(|d| d.id).collect();
+                self.score_nearest(query_embedding, Some(&allowed), options)?
+            },
+        };
+
+        // Sort by score descending
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        // Limit results
+        results.truncate(options.limit);
+
+        debug!("Found {} results above threshold", results.len());
+        Ok(results)
+    }
+
+    /// Documents a search is limited to (`None`: the whole vault)
+    ///
+    /// Combines `doc_ids`, `doc_types` and the filter's document conditions.
+    fn document_scope(&self, options: &SearchOptions) -> KnowledgeResult<Option<Vec<Document>>> {
+        let filtered = if options.filter.has_document_conditions() {
+            Some(
+                self.vault
+                    .filter_documents(options.doc_types.as_deref(), &options.filter)?,
+            )
+        } else {
+            None
+        };
+        let Some(ref ids) = options.doc_ids else {
+            return Ok(filtered);
+        };
+
+        let docs = match filtered {
+            Some(docs) => docs.into_iter().filter(|d| ids.contains(&d.id)).collect(),
+            None => {
+                let mut docs = Vec::new();
+                for id in ids {
+                    let Some(doc) = self.vault.get_document(id)? else {
+                        continue;
+                    };
+                    if let Some(ref types) = options.doc_types {
+                        if !types.contains(&doc.doc_type) {
+                            continue;
+                        }
+                    }
+                    docs.push(doc);
+                }
+                docs
+            },
+        };
+        Ok(Some(docs))
+    }
 
+    /// Score candidates from the vault's ANN index
+    ///
+    /// Candidates outside `allowed` documents are dropped.
+    fn score_nearest(
+        &self,
+        query_embedding: &[f32],
+        allowed: Option<&HashSet<String>>,
+        options: &SearchOptions,
+    ) -> KnowledgeResult<Vec<SearchResult>> {
+        // Over-fetch when filtering so filtered-out chunks don't crowd out
+        // matches
+        let fetch = if options.doc_types.is_some()
+            || allowed.is_some()
+            || options.filter.has_chunk_conditions()
+        {
+            options.limit.saturating_mul(4)
+        } else {
+            options.limit
+        };
+
+        // Candidates are ordered best first
+        let hits: Vec<(String, f32)> = self
+            .vault
+            .nearest_chunks(query_embedding, fetch)?
+            .into_iter()
+            .take_while(|(_, score)| *score >= options.threshold)
+            .collect();
+
+        self.hydrate(hits, allowed, options)
+    }
+
+    /// Turn `(chunk_id, score)` hits into results, applying the type and
+    /// chunk filters and dropping chunks outside `allowed` documents
+    fn hydrate(
+        &self,
+        hits: Vec<(String, f32)>,
+        allowed: Option<&HashSet<String>>,
+        options: &SearchOptions,
+    ) -> KnowledgeResult<Vec<SearchResult>> {
+        let mut documents: HashMap<String, Option<Document>> = HashMap::new();
         let mut results = Vec::new();
 
-        for doc in documents {
-            // Apply document type filter
+        for (chunk_id, score) in hits {
+            let Some(chunk) = self.vault.get_chunk(&chunk_id)? else {
+                continue;
+            };
+            if allowed.is_some_and(|allowed| !allowed.contains(&chunk.document_id))
+                || !options.filter.matches_chunk(&chunk.location)
+            {
+                continue;
+            }
+            if !documents.contains_key(&chunk.document_id) {
+                let doc = self.vault.get_document(&chunk.document_id)?;
+                documents.insert(chunk.document_id.clone(), doc);
+            }
+            let Some(Some(doc)) = documents.get(&chunk.document_id) else {
+                continue;
+            };
+
             if let Some(ref types) = options.doc_types {
                 if !types.contains(&doc.doc_type) {
                     continue;
                 }
             }
 
-            // Apply document ID filter
-            if let Some(ref ids) = options.doc_ids {
-                if !ids.contains(&doc.id) {
-                    continue;
-                }
-            }
+            results.push(to_result(doc, chunk, score, options));
+        }
 
-            // Get chunks for this document
-            let chunks = self.vault.get_chunks(&doc.id)?;
+        Ok(results)
+    }
 
-            for chunk in chunks {
-                // Get embedding for this chunk
+    /// Score every chunk of the given documents
+    fn score_documents(
+        &self,
+        query_embedding: &[f32],
+        docs: &[Document],
+        options: &SearchOptions,
+    ) -> KnowledgeResult<Vec<SearchResult>> {
+        let mut results = Vec::new();
+
+        for doc in docs {
+            for chunk in self.vault.get_chunks(&doc.id)? {
+                if !options.filter.matches_chunk(&chunk.location) {
+                    continue;
+                }
                 if let Some(embedding) = self.vault.get_embedding(&chunk.id)? {
                     let score = cosine_similarity(query_embedding, &embedding);
-
                     if score >= options.threshold {
-                        results.push(SearchResult {
-                            chunk_id: chunk.id.clone(),
-                            document_id: doc.id.clone(),
-                            document_title: doc.title.clone(),
-                            score,
-                            content: if options.include_content {
-                                Some(chunk.content.clone())
-                            } else {
-                                None
-                            },
-                            chunk_index: chunk.chunk_index,
-                            start_offset: chunk.start_offset,
-                            end_offset: chunk.end_offset,
-                        });
+                        results.push(to_result(doc, chunk, score, options));
                     }
                 }
             }
         }
 
-        // Sort by score descending
-        results.sort_by(|a, b| {
-            b.score
-                .partial_cmp(&a.score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        // Limit results
-        results.truncate(options.limit);
-
-        debug!("Found {} results above threshold", results.len());
         Ok(results)
     }
 
     /// Search with text query (generates embedding first)
+    ///
+    /// Refuses embedders whose model differs from the vault's.
     pub async fn search_text<E: EmbeddingProvider>(
         &self,
         query: &str,
         embedder: &E,
         options: &SearchOptions,
     ) -> KnowledgeResult<Vec<SearchResult>> {
+        self.vault.use_embedding_model(&EmbeddingModel::of(embedder))?;
         let query_embedding = embedder.embed(query).await?;
         self.search(&query_embedding, options).await
     }
@@ -181,6 +372,89 @@ impl<'a> VectorSearch<'a> {
     }
 }
 
+/// Build an FTS5 query from user input
+///
+/// - `"quoted text"` matches the exact phrase
+/// - `term*` matches any word starting with `term`
+/// - Other words are matched individually; a chunk matches if it contains
+///   any of them, and BM25 ranks chunks matching more (and rarer) terms
+///   higher
+///
+/// Punctuation is treated as a word separator, so input can never produce
+/// FTS5 syntax errors. Single-character words are ignored. Returns `None`
+/// if nothing searchable remains.
+pub fn fts5_query(query: &str) -> Option<String> {
+    let mut terms = Vec::new();
+
+    for (i, segment) in query.split('"').enumerate() {
+        // Odd segments were inside quotes
+        if i % 2 == 1 {
+            let words = words(segment);
+            if !words.is_empty() {
+                terms.push(format!("\"{}\"", words.join(" ")));
+            }
+            continue;
+        }
+
+        for token in segment.split_whitespace() {
+            let prefix = token.ends_with('*');
+            let words = words(token);
+            match words.as_slice() {
+                [] => {},
+                [word] if !prefix && word.chars().count() < 2 => {},
+                _ if prefix => terms.push(format!("\"{}\"*", words.join(" "))),
+                _ => terms.push(format!("\"{}\"", words.join(" "))),
+            }
+        }
+    }
+
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+/// Split text into the alphanumeric words FTS5 indexes
+fn words(text: &str) -> Vec<&str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn to_result(
+    doc: &Document,
+    chunk: ChunkRecord,
+    score: f32,
+    options: &SearchOptions,
+) -> SearchResult {
+    SearchResult {
+        chunk_id: chunk.id,
+        document_id: doc.id.clone(),
+        document_title: doc.title.clone(),
+        score,
+        content: options.include_content.then_some(chunk.content),
+        chunk_index: chunk.chunk_index,
+        start_offset: chunk.start_offset,
+        end_offset: chunk.end_offset,
+        location: chunk.location,
+    }
+}
+
+/// Documents matching a search, counted per facet value
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchFacets {
+    /// Documents per type
+    pub doc_types: BTreeMap<String, usize>,
+    /// Documents per tag
+    pub tags: BTreeMap<String, usize>,
+}
+
+/// Search results with facet counts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetedResults {
+    /// Best results, at most `limit`
+    pub results: Vec<SearchResult>,
+    /// Counts over every candidate that passed the filters, before `limit`
+    pub facets: SearchFacets,
+}
+
 /// Hybrid search combining vector and keyword search
 pub struct HybridSearch<'a> {
     vector_search: VectorSearch<'a>,
@@ -188,6 +462,8 @@ pub struct HybridSearch<'a> {
     vector_weight: f32,
     /// Weight for keyword search (0.0-1.0)
     keyword_weight: f32,
+    /// Second-stage reranker
+    reranker: Option<Arc<dyn Reranker>>,
 }
 
 impl<'a> HybridSearch<'a> {
@@ -196,135 +472,263 @@ impl<'a> HybridSearch<'a> {
             vector_search: VectorSearch::new(vault),
             vector_weight,
             keyword_weight,
+            reranker: None,
         }
     }
 
+    /// Rerank fused candidates with this reranker when
+    /// [`SearchOptions::rerank_top_n`] is set
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>) -> Self {
+        self.reranker = Some(reranker);
+        self
+    }
+
     /// Hybrid search combining vector similarity and keyword matching
     ///
-    /// Combines scores from both search methods using configured weights:
-    /// final_score = (vector_score * vector_weight) + (keyword_score * keyword_weight)
-    #[instrument(skip(self, query, embedder))]
+    /// Runs the pipeline described in the [module docs](self) and returns
+    /// at most `options.limit` results.
     pub async fn search<E: EmbeddingProvider>(
         &self,
         query: &str,
         embedder: &E,
         options: &SearchOptions,
     ) -> KnowledgeResult<Vec<SearchResult>> {
+        Ok(self.run(query, embedder, options, false).await?.results)
+    }
+
+    /// Hybrid search that also counts matching documents by type and tag
+    ///
+    /// Facets are counted over the top 200 candidates of each ranking,
+    /// before reranking and `options.limit` apply.
+    pub async fn search_with_facets<E: EmbeddingProvider>(
+        &self,
+        query: &str,
+        embedder: &E,
+        options: &SearchOptions,
+    ) -> KnowledgeResult<FacetedResults> {
+        self.run(query, embedder, options, true).await
+    }
+
+    #[instrument(skip(self, query, embedder))]
+    async fn run<E: EmbeddingProvider>(
+        &self,
+        query: &str,
+        embedder: &E,
+        options: &SearchOptions,
+        with_facets: bool,
+    ) -> KnowledgeResult<FacetedResults> {
+        let candidate_count = options.candidate_count();
+        let candidates = SearchOptions {
+            limit: if with_facets {
+                candidate_count.max(FACET_CANDIDATES)
+            } else {
+                candidate_count
+            },
+            ..options.clone()
+        };
+
         // Get vector search results
         let vector_results = self
             .vector_search
-            .search_text(query, embedder, options)
+            .search_text(query, embedder, &candidates)
             .await?;
 
         // Get keyword search results
-        let keyword_results = self.keyword_search(query, options)?;
+        let keyword_results = self.keyword_search(query, &candidates)?;
+
+        // Combine rankings from both methods
+        let mut results = match options.fusion {
+            FusionStrategy::Weighted => self.combine_results(vector_results, keyword_results),
+            FusionStrategy::ReciprocalRank { k } => {
+                self.reciprocal_rank_fusion(vector_results, keyword_results, k)
+            },
+        };
+        let facets = if with_facets {
+            let facets = self.facets(&results)?;
+            results.truncate(candidate_count);
+            facets
+        } else {
+            SearchFacets::default()
+        };
 
-        // Combine scores from both methods
-        let combined_results = self.combine_results(vector_results, keyword_results);
+        if let (Some(top_n), Some(reranker)) = (options.rerank_top_n, &self.reranker) {
+            results = self
+                .rerank(query, reranker.as_ref(), results, top_n.max(options.limit))
+                .await?;
+        }
 
-        Ok(combined_results)
-    }
+        if let Some(lambda) = options.mmr_lambda {
+            results = self.diversify(results, lambda, options.limit)?;
+        }
 
-    /// Keyword-based search using BM25-like scoring
-    fn keyword_search(&self, query: &str, options: &SearchOptions) -> KnowledgeResult<Vec<SearchResult>> {
-        debug!("Performing keyword search for: {}", query);
+        results.truncate(options.limit);
+        Ok(FacetedResults { results, facets })
+    }
 
-        let query_terms: Vec<String> = query
-            .to_lowercase()
-            .split_whitespace()
-            .filter(|s| s.len() > 2) // Skip very short terms
-            .map(|s| s.to_string())
-            .collect();
+    /// Count the distinct documents of `results` per type and tag
+    fn facets(&self, results: &[SearchResult]) -> KnowledgeResult<SearchFacets> {
+        let vault = self.vector_search.vault;
+        let mut facets = SearchFacets::default();
+        let mut seen = HashSet::new();
 
-        if query_terms.is_empty() {
-            return Ok(vec![]);
+        for result in results {
+            if !seen.insert(result.document_id.as_str()) {
+                continue;
+            }
+            if let Some(doc) = vault.get_document(&result.document_id)? {
+                *facets.doc_types.entry(doc.doc_type).or_default() += 1;
+            }
+            for tag in vault.document_tags(&result.document_id)? {
+                *facets.tags.entry(tag).or_default() += 1;
+            }
         }
 
-        // Get all documents
-        let documents = self.vector_search.vault.list_documents(1000)?;
+        Ok(facets)
+    }
 
-        let mut chunk_scores: HashMap<String, f32> = HashMap::new();
-        let mut chunk_data: HashMap<String, SearchResult> = HashMap::new();
+    /// Reciprocal rank fusion of two best-first result lists
+    fn reciprocal_rank_fusion(
+        &self,
+        vector_results: Vec<SearchResult>,
+        keyword_results: Vec<SearchResult>,
+        k: f32,
+    ) -> Vec<SearchResult> {
+        let mut combined: HashMap<String, SearchResult> = HashMap::new();
 
-        for doc in documents {
-            // Apply filters
-            if let Some(ref types) = options.doc_types {
-                if !types.contains(&doc.doc_type) {
-                    continue;
-                }
+        for (weight, results) in [
+            (self.vector_weight, vector_results),
+            (self.keyword_weight, keyword_results),
+        ] {
+            for (rank, result) in results.into_iter().enumerate() {
+                let contribution = weight / (k + rank as f32 + 1.0);
+                combined
+                    .entry(result.chunk_id.clone())
+                    .and_modify(|r| r.score += contribution)
+                    .or_insert(SearchResult {
+                        score: contribution,
+                        ..result
+                    });
             }
+        }
 
-            if let Some(ref ids) = options.doc_ids {
-                if !ids.contains(&doc.id) {
-                    continue;
-                }
+        // Best possible score: first place in both lists
+        let best = (self.vector_weight + self.keyword_weight) / (k + 1.0);
+        let mut results: Vec<SearchResult> = combined.into_values().collect();
+        if best > 0.0 {
+            for result in &mut results {
+                result.score /= best;
             }
+        }
+        sort_by_score(&mut results);
+        results
+    }
 
-            // Get chunks for this document
-            let chunks = self.vector_search.vault.get_chunks(&doc.id)?;
+    /// Replace the scores of the top `top_n` results with reranker scores
+    ///
+    /// Results below the top N are dropped.
+    async fn rerank(
+        &self,
+        query: &str,
+        reranker: &dyn Reranker,
+        mut results: Vec<SearchResult>,
+        top_n: usize,
+    ) -> KnowledgeResult<Vec<SearchResult>> {
+        results.truncate(top_n);
+        if results.is_empty() {
+            return Ok(results);
+        }
 
-            for chunk in chunks {
-                let content_lower = chunk.content.to_lowercase();
+        // Content may have been left out of the results
+        let mut passages = Vec::with_capacity(results.len());
+        for result in &results {
+            let content = match result.content {
+                Some(ref content) => content.clone(),
+                None => self
+                    .vector_search
+                    .vault
+                    .get_chunk(&result.chunk_id)?
+                    .map(|c| c.content)
+                    .unwrap_or_default(),
+            };
+            passages.push(content);
+        }
+        let passages: Vec<&str> = passages.iter().map(String::as_str).collect();
+
+        debug!("Reranking {} candidates with {}", passages.len(), reranker.model_name());
+        let scores = reranker.rerank(query, &passages).await?;
+        if scores.len() != results.len() {
+            return Err(KnowledgeError::RerankError(format!(
+                "Reranker returned {} scores for {} passages",
+                scores.len(),
+                results.len()
+            )));
+        }
 
-                // Calculate BM25-like score
-                let mut score = 0.0f32;
-                for term in &query_terms {
-                    // Count term frequency
-                    let tf = content_lower.matches(term.as_str()).count() as f32;
+        for (result, score) in results.iter_mut().zip(scores) {
+            result.score = score;
+        }
+        sort_by_score(&mut results);
+        Ok(results)
+    }
 
-                    if tf > 0.0 {
-                        // Simple BM25: term frequency boost
-                        score += (1.0 + tf).ln_1p();
-                    }
-                }
+    /// Reorder results with MMR using the chunks' stored embeddings
+    ///
+    /// Scores are kept; the returned order is the selection order.
+    fn diversify(
+        &self,
+        results: Vec<SearchResult>,
+        lambda: f32,
+        limit: usize,
+    ) -> KnowledgeResult<Vec<SearchResult>> {
+        let embeddings = results
+            .iter()
+            .map(|r| self.vector_search.vault.get_embedding(&r.chunk_id))
+            .collect::<KnowledgeResult<Vec<_>>>()?;
+        let relevance: Vec<f32> = results.iter().map(|r| r.score).collect();
+
+        let order = mmr_order(&relevance, &embeddings, lambda, limit);
+        let mut slots: Vec<Option<SearchResult>> = results.into_iter().map(Some).collect();
+        Ok(order.into_iter().filter_map(|i| slots[i].take()).collect())
+    }
 
-                if score > 0.0 {
-                    // Normalize score to 0-1 range (approximate)
-                    let normalized_score = (score / (query_terms.len() as f32)).min(1.0);
-
-                    chunk_scores.insert(chunk.id.clone(), normalized_score);
-                    chunk_data.insert(
-                        chunk.id.clone(),
-                        SearchResult {
-                            chunk_id: chunk.id.clone(),
-                            document_id: doc.id.clone(),
-                            document_title: doc.title.clone(),
-                            score: normalized_score,
-                            content: if options.include_content {
-                                Some(chunk.content.clone())
-                            } else {
-                                None
-                            },
-                            chunk_index: chunk.chunk_index,
-                            start_offset: chunk.start_offset,
-                            end_offset: chunk.end_offset,
-                        },
-                    );
-                }
-            }
+    /// Keyword search ranked by BM25 over the vault's FTS5 index
+    ///
+    /// Scores are normalized so the best match is 1.0.
+    fn keyword_search(&self, query: &str, options: &SearchOptions) -> KnowledgeResult<Vec<SearchResult>> {
+        debug!("Performing keyword search for: {}", query);
+
+        let Some(fts_query) = fts5_query(query) else {
+            return Ok(vec![]);
+        };
+
+        let doc_ids: Option<Vec<String>> = self
+            .vector_search
+            .document_scope(options)?
+            .map(|docs| docs.into_iter().map(|d| d.id).collect());
+        if doc_ids.as_ref().is_some_and(Vec::is_empty) {
+            return Ok(vec![]);
         }
 
-        // Convert to results
-        let mut results: Vec<SearchResult> = chunk_scores
+        // Chunk conditions are checked after retrieval
+        let fetch = if options.filter.has_chunk_conditions() {
+            options.limit.saturating_mul(4)
+        } else {
+            options.limit
+        };
+        let matches = self.vector_search.vault.keyword_search(
+            &fts_query,
+            fetch,
+            options.doc_types.as_deref(),
+            doc_ids.as_deref(),
+        )?;
+
+        let best = matches.first().map(|(_, score)| *score).unwrap_or(0.0);
+        let hits = matches
             .into_iter()
-            .filter_map(|(id, score)| chunk_data.get(&id).map(|r| (id, r, score)))
-            .map(|(id, result, score)| SearchResult {
-                chunk_id: id,
-                score,
-                ..result.clone()
-            })
+            .map(|(id, score)| (id, if best > 0.0 { score / best } else { 0.0 }))
             .collect();
 
-        // Sort by score descending
-        results.sort_by(|a, b| {
-            b.score
-                .partial_cmp(&a.score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        // Limit results
-        results.truncate(options.limit);
-
+        let results = self.vector_search.hydrate(hits, None, options)?;
         debug!("Keyword search found {} results", results.len());
         Ok(results)
     }
@@ -365,17 +769,73 @@ impl<'a> HybridSearch<'a> {
 
         // Convert to vec and sort
         let mut results: Vec<SearchResult> = combined.into_values().collect();
-
-        results.sort_by(|a, b| {
-            b.score
-                .partial_cmp(&a.score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+        sort_by_score(&mut results);
 
         results
     }
 }
 
+/// Sort best first, breaking ties by chunk ID so output is stable
+fn sort_by_score(results: &mut [SearchResult]) {
+    results.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.chunk_id.cmp(&b.chunk_id))
+    });
+}
+
+/// Maximal marginal relevance selection
+///
+/// Greedily picks the candidate maximizing
+/// `lambda * relevance - (1 - lambda) * max_similarity_to_selected`, with
+/// relevance min-max scaled to 0-1 so the trade-off doesn't depend on the
+/// scorer. Candidates without an embedding count as dissimilar to
+/// everything. Returns indices into the inputs in selection order.
+fn mmr_order(
+    relevance: &[f32],
+    embeddings: &[Option<Vec<f32>>],
+    lambda: f32,
+    limit: usize,
+) -> Vec<usize> {
+    let lambda = lambda.clamp(0.0, 1.0);
+    let (min, max) = relevance
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(lo, hi), &r| (lo.min(r), hi.max(r)));
+    let range = max - min;
+    let scaled: Vec<f32> = relevance
+        .iter()
+        .map(|&r| if range > 0.0 { (r - min) / range } else { 1.0 })
+        .collect();
+
+    let mut selected: Vec<usize> = Vec::new();
+    // Highest similarity of each candidate to anything selected so far
+    let mut redundancy = vec![0.0f32; relevance.len()];
+    let mut remaining: Vec<usize> = (0..relevance.len()).collect();
+
+    while selected.len() < limit {
+        let Some((position, &best)) = remaining.iter().enumerate().max_by(|(_, &a), (_, &b)| {
+            let score_a = lambda * scaled[a] - (1.0 - lambda) * redundancy[a];
+            let score_b = lambda * scaled[b] - (1.0 - lambda) * redundancy[b];
+            // Prefer the earlier (better ranked) candidate on ties
+            score_a.total_cmp(&score_b).then(b.cmp(&a))
+        }) else {
+            break;
+        };
+        remaining.swap_remove(position);
+        selected.push(best);
+
+        if let Some(ref chosen) = embeddings[best] {
+            for &i in &remaining {
+                if let Some(ref candidate) = embeddings[i] {
+                    redundancy[i] = redundancy[i].max(cosine_similarity(chosen, candidate));
+                }
+            }
+        }
+    }
+
+    selected
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -396,12 +856,29 @@ mod tests {
             doc_types: Some(vec!["code".to_string()]),
             doc_ids: None,
             include_content: false,
+            fusion: FusionStrategy::Weighted,
+            rerank_top_n: Some(50),
+            mmr_lambda: Some(0.5),
+            filter: SearchFilter::default(),
         };
 
         assert_eq!(options.limit, 20);
         assert_eq!(options.threshold, 0.7);
         assert!(options.doc_types.is_some());
         assert!(!options.include_content);
+        assert_eq!(options.candidate_count(), 60);
+    }
+
+    #[test]
+    fn test_search_options_deserialize_defaults() {
+        let options: SearchOptions = serde_json::from_str(
+            r#"{"limit": 5, "threshold": 0.2, "doc_types": null, "doc_ids": null, "include_content": true}"#,
+        )
+        .unwrap();
+
+        assert_eq!(options.fusion, FusionStrategy::ReciprocalRank { k: 60.0 });
+        assert_eq!(options.rerank_top_n, None);
+        assert_eq!(options.candidate_count(), 5);
     }
 
     #[tokio::test]
@@ -440,7 +917,7 @@ mod tests {
         let hybrid = HybridSearch::new(&vault, 0.7, 0.3);
 
         let options = SearchOptions::default();
-        // Query with only short terms (< 3 chars) should return empty
+        // Query with only single-character terms should return empty
         let result = hybrid.keyword_search("a b c", &options).unwrap();
 
         assert!(result.is_empty());
@@ -457,10 +934,366 @@ mod tests {
             chunk_index: 0,
             start_offset: 0,
             end_offset: 12,
+            location: ChunkLocation::default(),
         };
 
         let cloned = result.clone();
         assert_eq!(cloned.chunk_id, result.chunk_id);
         assert_eq!(cloned.score, result.score);
     }
+
+    fn add_chunks(vault: &KnowledgeVault, path: &str, doc_type: &str, chunks: &[&str]) -> String {
+        let doc_id = vault.add_document(path, &chunks.concat(), doc_type).unwrap();
+        for (i, content) in chunks.iter().enumerate() {
+            vault
+                .insert_chunk(&format!("{}#{}", path, i), &doc_id, i as u32, content, 0, 0, 0)
+                .unwrap();
+        }
+        doc_id
+    }
+
+    fn keyword_ids(hybrid: &HybridSearch, query: &str) -> Vec<String> {
+        hybrid
+            .keyword_search(query, &SearchOptions::default())
+            .unwrap()
+            .into_iter()
+            .map(|r| r.chunk_id)
+            .collect()
+    }
+
+    #[test]
+    fn test_fts5_query() {
+        assert_eq!(fts5_query("rust vault").unwrap(), r#""rust" OR "vault""#);
+        assert_eq!(
+            fts5_query(r#"say "hello, world" emb*"#).unwrap(),
+            r#""say" OR "hello world" OR "emb"*"#
+        );
+        assert_eq!(fts5_query("tokio::sync").unwrap(), r#""tokio sync""#);
+        assert_eq!(fts5_query(r#"AND OR NOT ( ) ^ "#).unwrap(), r#""AND" OR "OR" OR "NOT""#);
+        assert!(fts5_query(r#"a " " * -"#).is_none());
+    }
+
+    #[test]
+    fn test_keyword_search_bm25_ranking() {
+        let vault = KnowledgeVault::in_memory().unwrap();
+        add_chunks(
+            &vault,
+            "/docs/common.md",
+            "markdown",
+            &[
+                "the vault stores documents",
+                "the vault stores chunks",
+                "the vault stores embeddings",
+            ],
+        );
+        add_chunks(&vault, "/docs/rare.md", "markdown", &["the vault uses hnsw graphs"]);
+        add_chunks(
+            &vault,
+            "/docs/long.md",
+            "markdown",
+            &["hnsw appears once in this much longer chunk about many other unrelated topics entirely"],
+        );
+        let hybrid = HybridSearch::new(&vault, 0.7, 0.3);
+
+        // Rare term outweighs common one; shorter chunk wins for equal counts
+        let ids = keyword_ids(&hybrid, "vault hnsw");
+        assert_eq!(ids[0], "/docs/rare.md#0");
+        assert_eq!(ids[1], "/docs/long.md#0");
+        assert_eq!(ids.len(), 5);
+
+        let results = hybrid
+            .keyword_search("vault hnsw", &SearchOptions::default())
+            .unwrap();
+        assert_eq!(results[0].score, 1.0);
+        assert!(results.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn test_keyword_search_phrase_and_prefix() {
+        let vault = KnowledgeVault::in_memory().unwrap();
+        add_chunks(&vault, "/a.md", "markdown", &["vector search is fast"]);
+        add_chunks(&vault, "/b.md", "markdown", &["search the vector space"]);
+        add_chunks(&vault, "/c.rs", "code", &["fn embedding_dimensions() {}"]);
+        let hybrid = HybridSearch::new(&vault, 0.7, 0.3);
+
+        assert_eq!(keyword_ids(&hybrid, r#""vector search""#), vec!["/a.md#0"]);
+        assert_eq!(keyword_ids(&hybrid, "embed*"), vec!["/c.rs#0"]);
+        assert!(keyword_ids(&hybrid, "embed").is_empty());
+
+        let options = SearchOptions {
+            doc_types: Some(vec!["markdown".to_string()]),
+            ..Default::default()
+        };
+        let results = hybrid.keyword_search("vector embed*", &options).unwrap();
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_keyword_search_beyond_first_thousand_documents() {
+        let vault = KnowledgeVault::in_memory().unwrap();
+        for i in 0..1200 {
+            add_chunks(&vault, &format!("/notes/{}.md", i), "markdown", &["routine note"]);
+        }
+        add_chunks(&vault, "/notes/needle.md", "markdown", &["the needle"]);
+        let hybrid = HybridSearch::new(&vault, 0.7, 0.3);
+
+        assert_eq!(keyword_ids(&hybrid, "needle"), vec!["/notes/needle.md#0"]);
+    }
+
+    fn result(chunk_id: &str, score: f32) -> SearchResult {
+        SearchResult {
+            chunk_id: chunk_id.to_string(),
+            document_id: "doc".to_string(),
+            document_title: "Doc".to_string(),
+            score,
+            content: None,
+            chunk_index: 0,
+            start_offset: 0,
+            end_offset: 0,
+            location: ChunkLocation::default(),
+        }
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let vault = KnowledgeVault::in_memory().unwrap();
+        let hybrid = HybridSearch::new(&vault, 0.5, 0.5);
+
+        // Raw scores on very different scales must not matter
+        let vector = vec![result("a", 0.91), result("b", 0.90), result("c", 0.10)];
+        let keyword = vec![result("b", 1.0), result("d", 0.01)];
+        let fused = hybrid.reciprocal_rank_fusion(vector, keyword, 60.0);
+
+        let ids: Vec<&str> = fused.iter().map(|r| r.chunk_id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "a", "d", "c"]);
+        // b: ranks 2 and 1 out of a best possible 1 and 1
+        let expected = (1.0 / 62.0 + 1.0 / 61.0) / (2.0 / 61.0);
+        assert!((fused[0].score - expected).abs() < 1e-6);
+        assert!(fused.iter().all(|r| r.score > 0.0 && r.score <= 1.0));
+    }
+
+    #[test]
+    fn test_mmr_order_skips_near_duplicates() {
+        let relevance = [1.0, 0.99, 0.98, 0.5];
+        let embeddings = vec![
+            Some(vec![1.0, 0.0]),
+            Some(vec![0.99, 0.01]),
+            Some(vec![0.98, 0.02]),
+            Some(vec![0.0, 1.0]),
+        ];
+
+        assert_eq!(mmr_order(&relevance, &embeddings, 1.0, 4), vec![0, 1, 2, 3]);
+        assert_eq!(mmr_order(&relevance, &embeddings, 0.5, 2), vec![0, 3]);
+        // Missing embeddings never count as redundant
+        assert_eq!(mmr_order(&relevance, &[None, None, None, None], 0.5, 3), vec![0, 1, 2]);
+        assert!(mmr_order(&[], &[], 0.5, 3).is_empty());
+    }
+
+    /// Scores passages by whether they contain a marker word
+    struct MarkerReranker;
+
+    #[async_trait::async_trait]
+    impl Reranker for MarkerReranker {
+        async fn rerank(&self, _query: &str, passages: &[&str]) -> KnowledgeResult<Vec<f32>> {
+            Ok(passages
+                .iter()
+                .map(|p| if p.contains("marker") { 1.0 } else { 0.0 })
+                .collect())
+        }
+
+        fn model_name(&self) -> &str {
+            "marker"
+        }
+    }
+
+    async fn indexed_vault(chunks: &[&str]) -> KnowledgeVault {
+        use crate::embeddings::PlaceholderEmbedder;
+
+        let vault = KnowledgeVault::in_memory().unwrap();
+        let embedder = PlaceholderEmbedder::new(384);
+        let doc_id = vault.add_document("/notes.md", &chunks.concat(), "markdown").unwrap();
+        for (i, content) in chunks.iter().enumerate() {
+            let chunk_id = format!("chunk_{}", i);
+            vault
+                .insert_chunk(&chunk_id, &doc_id, i as u32, content, 0, 0, 0)
+                .unwrap();
+            let embedding = embedder.embed(content).await.unwrap();
+            vault.insert_embedding(&chunk_id, &embedding).unwrap();
+        }
+        vault
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_reranks_top_n() {
+        use crate::embeddings::PlaceholderEmbedder;
+
+        let vault = indexed_vault(&[
+            "search index basics",
+            "search index tuning",
+            "search index with the marker",
+            "search index internals",
+        ])
+        .await;
+        let embedder = PlaceholderEmbedder::new(384);
+        let options = SearchOptions {
+            limit: 2,
+            threshold: 0.0,
+            include_content: false,
+            rerank_top_n: Some(4),
+            ..Default::default()
+        };
+
+        // Without a reranker the option is ignored
+        let plain = HybridSearch::new(&vault, 0.7, 0.3);
+        assert_eq!(plain.search("search index", &embedder, &options).await.unwrap().len(), 2);
+
+        let hybrid = HybridSearch::new(&vault, 0.7, 0.3).with_reranker(Arc::new(MarkerReranker));
+        let results = hybrid.search("search index", &embedder, &options).await.unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].chunk_id, "chunk_2");
+        assert_eq!(results[0].score, 1.0);
+        assert!(results[0].content.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_filters_and_facets() {
+        use crate::embeddings::PlaceholderEmbedder;
+        use crate::filter::DateRange;
+
+        let vault = KnowledgeVault::in_memory().unwrap();
+        let embedder = PlaceholderEmbedder::new(384);
+        let docs = [
+            (
+                "/design/atlas.md",
+                "markdown",
+                "2026-08-03T00:00:00Z",
+                "atlas",
+                Some(2),
+            ),
+            (
+                "/design/legacy.md",
+                "markdown",
+                "2026-01-10T00:00:00Z",
+                "atlas",
+                None,
+            ),
+            (
+                "/src/index.rs",
+                "code",
+                "2026-09-01T00:00:00Z",
+                "zeus",
+                None,
+            ),
+        ];
+        for (i, (path, doc_type, updated, project, page)) in docs.into_iter().enumerate() {
+            let updated = chrono::DateTime::parse_from_rfc3339(updated)
+                .unwrap()
+                .to_utc();
+            let doc_id = format!("doc_{}", i);
+            vault
+                .insert_document(&Document {
+                    id: doc_id.clone(),
+                    path: Some(path.to_string()),
+                    title: path.to_string(),
+                    doc_type: doc_type.to_string(),
+                    content_hash: doc_id.clone(),
+                    chunk_count: 1,
+                    size_bytes: 0,
+                    indexed_at: updated,
+                    updated_at: updated,
+                    metadata: [("project".to_string(), serde_json::json!(project))].into(),
+                })
+                .unwrap();
+            let content = format!("search index notes {}", i);
+            vault
+                .insert_chunk(&doc_id, &doc_id, 0, &content, 0, 0, 0)
+                .unwrap();
+            vault
+                .set_chunk_location(
+                    &doc_id,
+                    &ChunkLocation {
+                        page,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            vault
+                .insert_embedding(&doc_id, &embedder.embed(&content).await.unwrap())
+                .unwrap();
+        }
+        vault.add_tags("doc_0", &["Design".to_string()]).unwrap();
+        vault
+            .add_tags("doc_1", &["design".to_string(), "archive".to_string()])
+            .unwrap();
+
+        let hybrid = HybridSearch::new(&vault, 0.7, 0.3);
+        let search = |filter: SearchFilter| {
+            let options = SearchOptions {
+                threshold: 0.0,
+                filter,
+                ..Default::default()
+            };
+            let hybrid = &hybrid;
+            let embedder = &embedder;
+            async move {
+                let mut ids: Vec<String> = hybrid
+                    .search("search index", embedder, &options)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|r| r.document_id)
+                    .collect();
+                ids.sort();
+                ids
+            }
+        };
+
+        // Design docs from the last quarter
+        let recent_design = SearchFilter {
+            tags: vec!["design".to_string()],
+            updated: DateRange {
+                after: Some("2026-07-01T00:00:00Z".parse().unwrap()),
+                before: None,
+            },
+            ..Default::default()
+        };
+        assert_eq!(search(recent_design).await, vec!["doc_0"]);
+
+        let code = SearchFilter {
+            path_globs: vec!["/src/*".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(search(code).await, vec!["doc_2"]);
+
+        let atlas = SearchFilter {
+            metadata: vec!["project=atlas".parse().unwrap()],
+            ..Default::default()
+        };
+        assert_eq!(search(atlas).await, vec!["doc_0", "doc_1"]);
+
+        let paged = SearchFilter {
+            metadata: vec!["chunk.page>=2".parse().unwrap()],
+            ..Default::default()
+        };
+        assert_eq!(search(paged).await, vec!["doc_0"]);
+
+        let faceted = hybrid
+            .search_with_facets(
+                "search index",
+                &embedder,
+                &SearchOptions {
+                    limit: 1,
+                    threshold: 0.0,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(faceted.results.len(), 1);
+        assert_eq!(faceted.facets.doc_types["markdown"], 2);
+        assert_eq!(faceted.facets.doc_types["code"], 1);
+        assert_eq!(faceted.facets.tags["design"], 2);
+        assert_eq!(faceted.facets.tags["archive"], 1);
+    }
 }
@@ -0,0 +1,919 @@
+//! Syntax-Aware Code Chunking
+//!
+//! Splits source files along their syntax tree instead of on regexes, so
+//! each chunk is a whole definition (function, method, type, ...) together
+//! with its doc comments and attributes. Every chunk records the symbol it
+//! defines, the enclosing scope and its line range, which lets search
+//! results point at `method Router::route` in `routing.rs:67`.
+//!
+//! Parsing uses tree-sitter and is only compiled with the `tree-sitter`
+//! cargo feature. Without it, [`chunk_code`] returns `None` and callers fall
+//! back to the regex-based chunkers.
+//!
+//! # Chunking Rules
+//!
+//! - Top-level definitions become one chunk each.
+//! - Containers (Rust `impl`/`trait`/`mod`, classes) that fit in the size
+//!   budget stay whole; larger ones are split into their members, which
+//!   keep the container as their scope. Code in the container that is not a
+//!   member (header, docstring, fields) is chunked under the container.
+//! - Comments and attributes directly above a definition belong to it.
+//! - Remaining top-level code (imports, statements) is grouped into chunks
+//!   without a symbol.
+//! - Oversized leaf definitions are kept whole rather than cut mid-body.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// Languages with syntax-aware chunking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    /// TypeScript with JSX
+    Tsx,
+    Go,
+}
+
+impl CodeLanguage {
+    /// Detect the language from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" => Some(Self::Python),
+            "js" | "mjs" | "cjs" | "jsx" => Some(Self::JavaScript),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    /// Language name as stored in chunk metadata
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::TypeScript | Self::Tsx => "typescript",
+            Self::Go => "go",
+        }
+    }
+
+    /// Separator between scope and symbol name in qualified names
+    pub fn scope_separator(&self) -> &'static str {
+        match self {
+            Self::Rust => "::",
+            _ => ".",
+        }
+    }
+}
+
+/// Kind of a code symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Struct,
+    Enum,
+    Trait,
+    Impl,
+    Class,
+    Interface,
+    Type,
+    Module,
+    Constant,
+    Macro,
+}
+
+impl fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Function => "function",
+            Self::Method => "method",
+            Self::Struct => "struct",
+            Self::Enum => "enum",
+            Self::Trait => "trait",
+            Self::Impl => "impl",
+            Self::Class => "class",
+            Self::Interface => "interface",
+            Self::Type => "type",
+            Self::Module => "module",
+            Self::Constant => "constant",
+            Self::Macro => "macro",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A symbol defined by a code chunk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeSymbol {
+    /// Symbol name (e.g., `route`)
+    pub name: String,
+    /// Symbol kind
+    pub kind: SymbolKind,
+    /// Enclosing scope (e.g., `Router`), if any
+    pub scope: Option<String>,
+    /// Name including the scope (e.g., `Router::route`)
+    pub qualified_name: String,
+    /// First line of the definition (1-based, excluding doc comments)
+    pub start_line: usize,
+    /// Last line of the definition (1-based, inclusive)
+    pub end_line: usize,
+}
+
+impl fmt::Display for CodeSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.qualified_name)
+    }
+}
+
+/// A chunk of source code produced by [`chunk_code`]
+#[derive(Debug, Clone)]
+pub struct CodeChunk {
+    /// Chunk content
+    pub content: String,
+    /// Start byte offset in the source
+    pub start_offset: usize,
+    /// End byte offset in the source
+    pub end_offset: usize,
+    /// First line of the chunk (1-based)
+    pub start_line: usize,
+    /// Last line of the chunk (1-based, inclusive)
+    pub end_line: usize,
+    /// Symbol defined by the chunk, `None` for loose top-level code
+    pub symbol: Option<CodeSymbol>,
+}
+
+/// Split source code into syntax-aware chunks
+///
+/// `max_chunk_bytes` is the size above which containers are split into
+/// their members. Returns `None` when the `tree-sitter` feature is disabled
+/// or the source can't be parsed.
+#[cfg(feature = "tree-sitter")]
+pub fn chunk_code(
+    content: &str,
+    language: CodeLanguage,
+    max_chunk_bytes: usize,
+) -> Option<Vec<CodeChunk>> {
+    ast::chunk_code(content, language, max_chunk_bytes)
+}
+
+/// Split source code into syntax-aware chunks
+///
+/// Always `None`: this build was compiled without the `tree-sitter` feature.
+#[cfg(not(feature = "tree-sitter"))]
+pub fn chunk_code(
+    _content: &str,
+    _language: CodeLanguage,
+    _max_chunk_bytes: usize,
+) -> Option<Vec<CodeChunk>> {
+    None
+}
+
+#[cfg(feature = "tree-sitter")]
+mod ast {
+    use super::{CodeChunk, CodeLanguage, CodeSymbol, SymbolKind};
+    use tracing::debug;
+    use tree_sitter::{Language, Node, Parser};
+
+    fn grammar(language: CodeLanguage) -> Language {
+        match language {
+            CodeLanguage::Rust => tree_sitter_rust::LANGUAGE.into(),
+            CodeLanguage::Python => tree_sitter_python::LANGUAGE.into(),
+            CodeLanguage::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            CodeLanguage::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            CodeLanguage::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            CodeLanguage::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    pub(super) fn chunk_code(
+        content: &str,
+        language: CodeLanguage,
+        max_chunk_bytes: usize,
+    ) -> Option<Vec<CodeChunk>> {
+        let mut parser = Parser::new();
+        if let Err(e) = parser.set_language(&grammar(language)) {
+            debug!("Failed to load {} grammar: {}", language.name(), e);
+            return None;
+        }
+        let tree = parser.parse(content, None)?;
+
+        let mut walker = Walker {
+            source: content,
+            language,
+            max_chunk_bytes,
+            chunks: Vec::new(),
+        };
+        walker.walk_body(tree.root_node(), &[], None);
+        Some(walker.chunks)
+    }
+
+    /// A definition recognised in the syntax tree
+    struct Definition<'t> {
+        name: String,
+        kind: SymbolKind,
+        /// Node whose children are members, for containers
+        body: Option<Node<'t>>,
+        /// Scope to use instead of the enclosing one (Go method receivers)
+        scope_override: Option<String>,
+    }
+
+    /// Byte span of pending code that isn't part of a definition
+    struct Span {
+        start_byte: usize,
+        end_byte: usize,
+        end_row: usize,
+    }
+
+    impl Span {
+        fn of(node: Node<'_>) -> Self {
+            Self {
+                start_byte: node.start_byte(),
+                end_byte: node.end_byte(),
+                end_row: node.end_position().row,
+            }
+        }
+
+        fn extend(&mut self, node: Node<'_>) {
+            self.end_byte = node.end_byte();
+            self.end_row = node.end_position().row;
+        }
+    }
+
+    struct Walker<'s> {
+        source: &'s str,
+        language: CodeLanguage,
+        max_chunk_bytes: usize,
+        chunks: Vec<CodeChunk>,
+    }
+
+    impl<'s> Walker<'s> {
+        /// Chunk the children of `body`
+        ///
+        /// Loose code is chunked under `owner` (the container being split,
+        /// with the offset where its text starts so the declaration line
+        /// lands in the first loose chunk), or without a symbol at the top
+        /// level.
+        fn walk_body(
+            &mut self,
+            body: Node<'_>,
+            scope: &[String],
+            owner: Option<(&CodeSymbol, usize)>,
+        ) {
+            let mut cursor = body.walk();
+            let children: Vec<Node<'_>> = body.named_children(&mut cursor).collect();
+
+            // Loose code not yet emitted, and the comments/attributes that
+            // directly precede the next node
+            let mut loose: Option<Span> = owner.map(|(symbol, start)| Span {
+                start_byte: start,
+                end_byte: start,
+                end_row: symbol.start_line - 1,
+            });
+            let mut leading: Option<Span> = None;
+            let in_type = owner.is_some_and(|(symbol, _)| symbol.kind != SymbolKind::Module);
+
+            for child in children {
+                if self.is_leading_trivia(child) {
+                    match &mut leading {
+                        Some(span) if child.start_position().row <= span.end_row + 1 => {
+                            span.extend(child)
+                        },
+                        _ => {
+                            if let Some(detached) = leading.take() {
+                                Self::merge(&mut loose, detached);
+                            }
+                            leading = Some(Span::of(child));
+                        },
+                    }
+                    continue;
+                }
+
+                let Some(definition) = self.classify(child, in_type) else {
+                    if let Some(attached) = leading.take() {
+                        Self::merge(&mut loose, attached);
+                    }
+                    Self::merge(&mut loose, Span::of(child));
+                    continue;
+                };
+
+                // Only attach trivia that touches the definition
+                let start = match leading.take() {
+                    Some(span) if child.start_position().row <= span.end_row + 1 => span,
+                    Some(detached) => {
+                        Self::merge(&mut loose, detached);
+                        Span::of(child)
+                    },
+                    None => Span::of(child),
+                };
+
+                if let Some(mut span) = loose.take() {
+                    span.end_byte = start.start_byte;
+                    self.emit_loose(span, owner.map(|(symbol, _)| symbol));
+                }
+                self.emit_definition(child, definition, start.start_byte, scope);
+            }
+
+            if let Some(trailing) = leading.take() {
+                Self::merge(&mut loose, trailing);
+            }
+            if let Some(span) = loose.take() {
+                self.emit_loose(span, owner.map(|(symbol, _)| symbol));
+            }
+        }
+
+        fn merge(loose: &mut Option<Span>, span: Span) {
+            match loose {
+                Some(existing) => {
+                    existing.end_byte = span.end_byte;
+                    existing.end_row = span.end_row;
+                },
+                None => *loose = Some(span),
+            }
+        }
+
+        fn emit_definition(
+            &mut self,
+            node: Node<'_>,
+            definition: Definition<'_>,
+            start_byte: usize,
+            scope: &[String],
+        ) {
+            let scope = match definition.scope_override {
+                Some(receiver) => vec![receiver],
+                None => scope.to_vec(),
+            };
+            let symbol = self.symbol(&definition.name, definition.kind, &scope, node);
+            let end_byte = node.end_byte();
+
+            match definition.body {
+                Some(body) if end_byte - start_byte > self.max_chunk_bytes => {
+                    let mut inner = scope;
+                    inner.push(definition.name);
+                    self.walk_body(body, &inner, Some((&symbol, start_byte)));
+                },
+                _ => self.push(start_byte, end_byte, Some(symbol)),
+            }
+        }
+
+        fn emit_loose(&mut self, span: Span, owner: Option<&CodeSymbol>) {
+            let text = &self.source[span.start_byte..span.end_byte];
+            if !text.chars().any(char::is_alphanumeric) {
+                return;
+            }
+
+            // Split long runs of loose code (e.g., a script body)
+            let mut start = span.start_byte;
+            while start < span.end_byte {
+                let mut end = span.end_byte;
+                if end - start > self.max_chunk_bytes {
+                    let limit = floor_char_boundary(self.source, start + self.max_chunk_bytes);
+                    end = match self.source[start..limit].rfind('\n') {
+                        Some(i) => start + i + 1,
+                        None if limit > start => limit,
+                        None => {
+                            start
+                                + self.source[start..]
+                                    .chars()
+                                    .next()
+                                    .map_or(1, char::len_utf8)
+                        },
+                    };
+                }
+                self.push(start, end, owner.cloned());
+                start = end;
+            }
+        }
+
+        fn push(&mut self, start_byte: usize, end_byte: usize, symbol: Option<CodeSymbol>) {
+            let content = self.source[start_byte..end_byte].trim_end();
+            if content.trim().is_empty() {
+                return;
+            }
+            self.chunks.push(CodeChunk {
+                content: content.to_string(),
+                start_offset: start_byte,
+                end_offset: start_byte + content.len(),
+                start_line: line_of(self.source, start_byte),
+                end_line: line_of(self.source, start_byte + content.len()),
+                symbol,
+            });
+        }
+
+        fn symbol(
+            &self,
+            name: &str,
+            kind: SymbolKind,
+            scope: &[String],
+            node: Node<'_>,
+        ) -> CodeSymbol {
+            let separator = self.language.scope_separator();
+            let scope = (!scope.is_empty()).then(|| scope.join(separator));
+            let qualified_name = match &scope {
+                Some(scope) => format!("{scope}{separator}{name}"),
+                None => name.to_string(),
+            };
+            CodeSymbol {
+                name: name.to_string(),
+                kind,
+                scope,
+                qualified_name,
+                start_line: node.start_position().row + 1,
+                end_line: node.end_position().row + 1,
+            }
+        }
+
+        fn text(&self, node: Node<'_>) -> &'s str {
+            &self.source[node.start_byte()..node.end_byte()]
+        }
+
+        fn field_text(&self, node: Node<'_>, field: &str) -> Option<String> {
+            node.child_by_field_name(field)
+                .map(|n| self.text(n).to_string())
+        }
+
+        /// Comments and attributes that document the following node
+        fn is_leading_trivia(&self, node: Node<'_>) -> bool {
+            match self.language {
+                CodeLanguage::Rust => matches!(
+                    node.kind(),
+                    "line_comment" | "block_comment" | "attribute_item"
+                ),
+                _ => node.kind() == "comment",
+            }
+        }
+
+        /// Recognise a definition; `nested` is true inside a type container
+        fn classify<'t>(&self, node: Node<'t>, nested: bool) -> Option<Definition<'t>> {
+            match self.language {
+                CodeLanguage::Rust => self.classify_rust(node, nested),
+                CodeLanguage::Python => self.classify_python(node, nested),
+                CodeLanguage::JavaScript | CodeLanguage::TypeScript | CodeLanguage::Tsx => {
+                    self.classify_js(node)
+                },
+                CodeLanguage::Go => self.classify_go(node),
+            }
+        }
+
+        fn classify_rust<'t>(&self, node: Node<'t>, nested: bool) -> Option<Definition<'t>> {
+            let leaf = |kind| {
+                Some(Definition {
+                    name: self.field_text(node, "name")?,
+                    kind,
+                    body: None,
+                    scope_override: None,
+                })
+            };
+            match node.kind() {
+                "function_item" | "function_signature_item" => leaf(if nested {
+                    SymbolKind::Method
+                } else {
+                    SymbolKind::Function
+                }),
+                "struct_item" | "union_item" => leaf(SymbolKind::Struct),
+                "enum_item" => leaf(SymbolKind::Enum),
+                "type_item" | "associated_type" => leaf(SymbolKind::Type),
+                "const_item" | "static_item" => leaf(SymbolKind::Constant),
+                "macro_definition" => leaf(SymbolKind::Macro),
+                "trait_item" | "mod_item" => Some(Definition {
+                    name: self.field_text(node, "name")?,
+                    kind: if node.kind() == "trait_item" {
+                        SymbolKind::Trait
+                    } else {
+                        SymbolKind::Module
+                    },
+                    // `mod foo;` has no body and stays loose code
+                    body: Some(node.child_by_field_name("body")?),
+                    scope_override: None,
+                }),
+                "impl_item" => Some(Definition {
+                    name: strip_generics(&self.field_text(node, "type")?).to_string(),
+                    kind: SymbolKind::Impl,
+                    body: node.child_by_field_name("body"),
+                    scope_override: None,
+                }),
+                _ => None,
+            }
+        }
+
+        fn classify_python<'t>(&self, node: Node<'t>, nested: bool) -> Option<Definition<'t>> {
+            match node.kind() {
+                "decorated_definition" => {
+                    self.classify_python(node.child_by_field_name("definition")?, nested)
+                },
+                "function_definition" => Some(Definition {
+                    name: self.field_text(node, "name")?,
+                    kind: if nested {
+                        SymbolKind::Method
+                    } else {
+                        SymbolKind::Function
+                    },
+                    body: None,
+                    scope_override: None,
+                }),
+                "class_definition" => Some(Definition {
+                    name: self.field_text(node, "name")?,
+                    kind: SymbolKind::Class,
+                    body: node.child_by_field_name("body"),
+                    scope_override: None,
+                }),
+                _ => None,
+            }
+        }
+
+        fn classify_js<'t>(&self, node: Node<'t>) -> Option<Definition<'t>> {
+            let named = |kind, body| {
+                Some(Definition {
+                    name: self.field_text(node, "name")?,
+                    kind,
+                    body,
+                    scope_override: None,
+                })
+            };
+            match node.kind() {
+                "export_statement" => self.classify_js(node.child_by_field_name("declaration")?),
+                "function_declaration" | "generator_function_declaration" => {
+                    named(SymbolKind::Function, None)
+                },
+                "class_declaration" | "abstract_class_declaration" => {
+                    named(SymbolKind::Class, node.child_by_field_name("body"))
+                },
+                "method_definition" | "method_signature" | "abstract_method_signature" => {
+                    named(SymbolKind::Method, None)
+                },
+                "interface_declaration" => named(SymbolKind::Interface, None),
+                "type_alias_declaration" => named(SymbolKind::Type, None),
+                "enum_declaration" => named(SymbolKind::Enum, None),
+                "lexical_declaration" | "variable_declaration" => {
+                    // `const handler = () => {}` defines a function
+                    let mut cursor = node.walk();
+                    let mut declarators = node
+                        .named_children(&mut cursor)
+                        .filter(|n| n.kind() == "variable_declarator");
+                    let declarator = declarators.next()?;
+                    if declarators.next().is_some() {
+                        return None;
+                    }
+                    let value = declarator.child_by_field_name("value")?;
+                    if !matches!(
+                        value.kind(),
+                        "arrow_function" | "function_expression" | "function"
+                    ) {
+                        return None;
+                    }
+                    Some(Definition {
+                        name: self.field_text(declarator, "name")?,
+                        kind: SymbolKind::Function,
+                        body: None,
+                        scope_override: None,
+                    })
+                },
+                _ => None,
+            }
+        }
+
+        fn classify_go<'t>(&self, node: Node<'t>) -> Option<Definition<'t>> {
+            match node.kind() {
+                "function_declaration" => Some(Definition {
+                    name: self.field_text(node, "name")?,
+                    kind: SymbolKind::Function,
+                    body: None,
+                    scope_override: None,
+                }),
+                "method_declaration" => {
+                    // func (r *Router) Route(...) is scoped to Router
+                    let receiver = node.child_by_field_name("receiver")?;
+                    let mut cursor = receiver.walk();
+                    let receiver_type = receiver
+                        .named_children(&mut cursor)
+                        .find_map(|param| param.child_by_field_name("type"))
+                        .map(|ty| {
+                            strip_generics(self.text(ty).trim_start_matches('*')).to_string()
+                        });
+                    Some(Definition {
+                        name: self.field_text(node, "name")?,
+                        kind: SymbolKind::Method,
+                        body: None,
+                        scope_override: receiver_type,
+                    })
+                },
+                "type_declaration" => {
+                    let mut cursor = node.walk();
+                    let spec = node
+                        .named_children(&mut cursor)
+                        .find(|n| matches!(n.kind(), "type_spec" | "type_alias"))?;
+                    let kind = match spec.child_by_field_name("type").map(|t| t.kind()) {
+                        Some("struct_type") => SymbolKind::Struct,
+                        Some("interface_type") => SymbolKind::Interface,
+                        _ => SymbolKind::Type,
+                    };
+                    Some(Definition {
+                        name: self.field_text(spec, "name")?,
+                        kind,
+                        body: None,
+                        scope_override: None,
+                    })
+                },
+                _ => None,
+            }
+        }
+    }
+
+    /// `Router<T>` -> `Router`
+    fn strip_generics(name: &str) -> &str {
+        name.split(['<', '[']).next().unwrap_or(name).trim()
+    }
+
+    /// 1-based line number of a byte offset
+    fn line_of(source: &str, offset: usize) -> usize {
+        source.as_bytes()[..offset]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count()
+            + 1
+    }
+
+    fn floor_char_boundary(source: &str, mut index: usize) -> usize {
+        index = index.min(source.len());
+        while !source.is_char_boundary(index) {
+            index -= 1;
+        }
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_detection() {
+        assert_eq!(
+            CodeLanguage::from_path(Path::new("src/routing.rs")),
+            Some(CodeLanguage::Rust)
+        );
+        assert_eq!(
+            CodeLanguage::from_path(Path::new("app.tsx")),
+            Some(CodeLanguage::Tsx)
+        );
+        assert_eq!(
+            CodeLanguage::from_path(Path::new("main.go")),
+            Some(CodeLanguage::Go)
+        );
+        assert_eq!(CodeLanguage::from_path(Path::new("notes.md")), None);
+        assert_eq!(CodeLanguage::Tsx.name(), "typescript");
+    }
+
+    #[test]
+    fn test_symbol_display_and_serde() {
+        let symbol = CodeSymbol {
+            name: "route".to_string(),
+            kind: SymbolKind::Method,
+            scope: Some("Router".to_string()),
+            qualified_name: "Router::route".to_string(),
+            start_line: 67,
+            end_line: 80,
+        };
+        assert_eq!(symbol.to_string(), "method Router::route");
+
+        let json = serde_json::to_string(&symbol).unwrap();
+        assert!(json.contains("\"kind\":\"method\""));
+        assert_eq!(serde_json::from_str::<CodeSymbol>(&json).unwrap(), symbol);
+    }
+
+    #[cfg(not(feature = "tree-sitter"))]
+    #[test]
+    fn test_chunk_code_disabled() {
+        assert!(chunk_code("fn main() {}", CodeLanguage::Rust, 1000).is_none());
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    mod ast {
+        use super::*;
+
+        fn symbols(chunks: &[CodeChunk]) -> Vec<String> {
+            chunks
+                .iter()
+                .filter_map(|c| c.symbol.as_ref().map(ToString::to_string))
+                .collect()
+        }
+
+        const RUST_SOURCE: &str = r#"use std::collections::HashMap;
+
+/// Routes requests
+#[derive(Debug)]
+pub struct Router {
+    routes: HashMap<String, String>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Self { routes: HashMap::new() }
+    }
+
+    /// Find the handler for a path
+    pub fn route(&self, path: &str) -> Option<&String> {
+        self.routes.get(path)
+    }
+}
+
+mod handlers {
+    pub fn index() {}
+}
+"#;
+
+        #[test]
+        fn test_rust_small_containers_stay_whole() {
+            let chunks = chunk_code(RUST_SOURCE, CodeLanguage::Rust, 10_000).unwrap();
+            assert_eq!(
+                symbols(&chunks),
+                vec!["struct Router", "impl Router", "module handlers"]
+            );
+
+            // Loose `use` code comes first without a symbol
+            assert!(chunks[0].symbol.is_none());
+            assert!(chunks[0].content.starts_with("use std"));
+
+            // Doc comment and attribute belong to the struct
+            let router = &chunks[1];
+            assert!(router
+                .content
+                .starts_with("/// Routes requests\n#[derive(Debug)]"));
+            assert_eq!(router.start_line, 3);
+            let symbol = router.symbol.as_ref().unwrap();
+            assert_eq!((symbol.start_line, symbol.end_line), (5, 7));
+        }
+
+        #[test]
+        fn test_rust_large_containers_split_into_members() {
+            let chunks = chunk_code(RUST_SOURCE, CodeLanguage::Rust, 60).unwrap();
+            assert_eq!(
+                symbols(&chunks),
+                vec![
+                    "struct Router",
+                    "impl Router",
+                    "method Router::new",
+                    "method Router::route",
+                    "module handlers",
+                ]
+            );
+
+            let route = chunks
+                .iter()
+                .find(|c| c.symbol.as_ref().is_some_and(|s| s.name == "route"))
+                .unwrap();
+            assert!(route.content.starts_with("/// Find the handler"));
+            let symbol = route.symbol.as_ref().unwrap();
+            assert_eq!(symbol.kind, SymbolKind::Method);
+            assert_eq!(symbol.scope.as_deref(), Some("Router"));
+            assert_eq!(symbol.start_line, 15);
+            assert_eq!(
+                &RUST_SOURCE[route.start_offset..route.end_offset],
+                route.content
+            );
+
+            // The impl header is kept under the impl itself
+            let header = chunks
+                .iter()
+                .find(|c| {
+                    c.symbol
+                        .as_ref()
+                        .is_some_and(|s| s.kind == SymbolKind::Impl)
+                })
+                .unwrap();
+            assert!(header.content.starts_with("impl Router {"));
+        }
+
+        #[test]
+        fn test_python_decorators_and_methods() {
+            let source = r#"import functools
+
+@functools.cache
+def load(path):
+    return open(path).read()
+
+class Store:
+    """Key-value store"""
+
+    def __init__(self):
+        self.items = {}
+
+    @property
+    def size(self):
+        return len(self.items)
+"#;
+            let chunks = chunk_code(source, CodeLanguage::Python, 80).unwrap();
+            assert_eq!(
+                symbols(&chunks),
+                vec![
+                    "function load",
+                    "class Store",
+                    "method Store.__init__",
+                    "method Store.size",
+                ]
+            );
+
+            let load = &chunks[1];
+            assert!(load.content.starts_with("@functools.cache\ndef load"));
+
+            let docstring = &chunks[2];
+            assert!(docstring.content.contains("Key-value store"));
+            let size = chunks.last().unwrap();
+            assert!(size.content.starts_with("@property"));
+        }
+
+        #[test]
+        fn test_javascript_exports_and_arrow_functions() {
+            let source = r#"// Request handlers
+export function handle(req) {
+  return route(req);
+}
+
+const route = (req) => req.path;
+
+export class Server {
+  listen(port) {
+    return port;
+  }
+}
+"#;
+            let chunks = chunk_code(source, CodeLanguage::JavaScript, 10_000).unwrap();
+            assert_eq!(
+                symbols(&chunks),
+                vec!["function handle", "function route", "class Server"]
+            );
+            assert!(chunks[0]
+                .content
+                .starts_with("// Request handlers\nexport function"));
+
+            let chunks = chunk_code(source, CodeLanguage::JavaScript, 20).unwrap();
+            assert!(symbols(&chunks).contains(&"method Server.listen".to_string()));
+        }
+
+        #[test]
+        fn test_typescript_interfaces() {
+            let source = r#"export interface Route {
+  path: string;
+}
+
+export type Handler = (route: Route) => void;
+
+export enum Method { Get, Post }
+"#;
+            let chunks = chunk_code(source, CodeLanguage::TypeScript, 10_000).unwrap();
+            assert_eq!(
+                symbols(&chunks),
+                vec!["interface Route", "type Handler", "enum Method"]
+            );
+        }
+
+        #[test]
+        fn test_go_method_receivers() {
+            let source = r#"package routing
+
+// Router routes requests.
+type Router struct {
+	routes map[string]string
+}
+
+func New() *Router {
+	return &Router{}
+}
+
+func (r *Router) Route(path string) string {
+	return r.routes[path]
+}
+"#;
+            let chunks = chunk_code(source, CodeLanguage::Go, 10_000).unwrap();
+            assert_eq!(
+                symbols(&chunks),
+                vec!["struct Router", "function New", "method Router.Route"]
+            );
+            assert!(chunks[0].symbol.is_none());
+            assert!(chunks[1].content.starts_with("// Router routes requests."));
+
+            let route = chunks.last().unwrap().symbol.as_ref().unwrap();
+            assert_eq!(route.scope.as_deref(), Some("Router"));
+            assert_eq!((route.start_line, route.end_line), (12, 14));
+        }
+
+        #[test]
+        fn test_loose_code_is_split_by_size() {
+            let source = "x = 1\n".repeat(100);
+            let chunks = chunk_code(&source, CodeLanguage::Python, 60).unwrap();
+            assert!(chunks.len() > 1);
+            assert!(chunks
+                .iter()
+                .all(|c| c.symbol.is_none() && c.content.len() <= 60));
+            assert_eq!(chunks.last().unwrap().end_line, 100);
+        }
+    }
+}
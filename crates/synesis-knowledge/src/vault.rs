@@ -1,15 +1,50 @@
 //! Knowledge Vault
 //!
 //! SQLite-based storage for documents, chunks, and embeddings.
-//! Uses sqlite-vss for vector similarity search.
+//!
+//! Vector search goes through an in-process HNSW index ([`crate::ann`])
+//! that is kept in step with the `embeddings` table and saved next to the
+//! database file. A generation counter in `vault_meta` detects index files
+//! that missed writes (crashes, other processes); those are rebuilt from
+//! SQLite.
+//!
+//! Keyword search uses an FTS5 table (`chunks_fts`) over `chunks.content`,
+//! kept in sync by triggers and ranked with BM25.
+//!
+//! Every embedding records the model that produced it (`embedding_models`).
+//! A vault serves one model at a time; switching models goes through a
+//! migration that stages the new vectors in `pending_embeddings` and swaps
+//! them in once every chunk has one, so searches keep working meanwhile.
 
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, instrument, warn};
 
+use crate::ann::{HnswIndex, HnswParams};
+use crate::chunker::{Chunk, ChunkLocation};
+use crate::embeddings::EmbeddingModel;
+use crate::filter::{normalize_tag, SearchFilter};
 use crate::{KnowledgeError, KnowledgeResult};
 
+/// Columns read by [`document_from_row`]
+const DOCUMENT_COLUMNS: &str =
+    "id, path, title, doc_type, content_hash, chunk_count, size_bytes, indexed_at, updated_at, metadata";
+
+/// `vault_meta` key holding the embeddings generation
+const ANN_GENERATION_KEY: &str = "ann_generation";
+
+/// `vault_meta` key holding the model of the stored embeddings
+const EMBEDDING_MODEL_KEY: &str = "embedding_model";
+
+/// `vault_meta` key holding how many models the stored embeddings come from
+const EMBEDDING_MODEL_COUNT_KEY: &str = "embedding_model_count";
+
+/// `vault_meta` key holding the model an unfinished migration is moving to
+const MIGRATION_TARGET_KEY: &str = "migration_target";
+
 /// A document in the knowledge vault
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Document {
@@ -45,6 +80,21 @@ pub struct VaultStats {
     pub total_size_bytes: u64,
     pub database_size_bytes: u64,
     pub embedding_dimensions: u32,
+    /// Model of the stored embeddings (`None` until one is recorded)
+    pub embedding_model: Option<EmbeddingModel>,
+}
+
+/// Progress of a migration to a new embedding model
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationStatus {
+    /// Model being replaced (`None` if the vault never recorded one)
+    pub from: Option<EmbeddingModel>,
+    /// Model being migrated to
+    pub to: EmbeddingModel,
+    /// Chunks already embedded with `to`
+    pub embedded: u64,
+    /// Chunks in the vault
+    pub total: u64,
 }
 
 /// Result from a vector similarity search
@@ -58,6 +108,27 @@ pub struct ChunkResult {
     pub score: f32,
 }
 
+/// A chunk of a document version written by [`KnowledgeVault::write_document`]
+#[derive(Debug, Clone)]
+pub enum ChunkWrite {
+    /// A chunk kept from the previous version, moved to its new position
+    Kept {
+        /// ID of the existing chunk
+        id: String,
+        /// The chunk as it appears in the new version
+        chunk: Chunk,
+    },
+    /// A new chunk and its embedding
+    New {
+        /// ID for the new chunk
+        id: String,
+        /// The chunk
+        chunk: Chunk,
+        /// Embedding of the chunk's content
+        embedding: Vec<f32>,
+    },
+}
+
 /// Calculate cosine similarity between two vectors
 ///
 /// # Mathematical Formula
@@ -101,6 +172,7 @@ pub struct ChunkResult {
 /// - Empty vectors: Returns 0.0
 /// - Mismatched lengths: Returns 0.0
 /// - Zero vectors: Returns 0.0 (avoid division by zero)
+#[cfg(test)]
 fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     // Validate inputs: vectors must be same length and non-empty
     if a.len() != b.len() || a.is_empty() {
@@ -123,15 +195,33 @@ fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     dot_product / (norm_a * norm_b)
 }
 
-/// Knowledge vault backed by SQLite-VSS
+/// Decode an embedding stored as little-endian f32 bytes
+fn decode_embedding(blob: &[u8]) -> Vec<f32> {
+    blob.as_chunks::<4>()
+        .0
+        .iter()
+        .map(|chunk| f32::from_le_bytes(*chunk))
+        .collect()
+}
+
+/// Knowledge vault backed by SQLite with an HNSW vector index
 pub struct KnowledgeVault {
     conn: Connection,
     db_path: PathBuf,
     embedding_dimensions: u32,
+    /// Approximate nearest neighbour index over all embeddings
+    ann: RefCell<HnswIndex>,
+    /// Whether `ann` has changes not yet saved to disk
+    ann_dirty: Cell<bool>,
+    /// Row of the recorded embedding model, stamped on new embeddings
+    model_id: Cell<Option<i64>>,
 }
 
 impl KnowledgeVault {
     /// Create or open a knowledge vault
+    ///
+    /// `embedding_dimensions` applies until an embedding model is recorded;
+    /// after that the model's dimensions are used.
     #[instrument(skip_all)]
     pub fn open(path: &Path, embedding_dimensions: u32) -> KnowledgeResult<Self> {
         info!("Opening knowledge vault at {:?}", path);
@@ -143,13 +233,31 @@ impl KnowledgeVault {
 
         let conn = Connection::open(path)?;
 
-        let vault = Self {
+        let mut vault = Self {
             conn,
             db_path: path.to_path_buf(),
             embedding_dimensions,
+            ann: RefCell::new(HnswIndex::new(
+                embedding_dimensions as usize,
+                HnswParams::default(),
+            )),
+            ann_dirty: Cell::new(false),
+            model_id: Cell::new(None),
         };
 
         vault.init_schema()?;
+        if let Some((id, model)) = vault.active_model()? {
+            if model.dimensions != embedding_dimensions {
+                info!("Vault embeddings come from {}, using its dimensions", model);
+                vault.embedding_dimensions = model.dimensions;
+                vault.ann = RefCell::new(HnswIndex::new(
+                    model.dimensions as usize,
+                    HnswParams::default(),
+                ));
+            }
+            vault.model_id.set(Some(id));
+        }
+        vault.load_ann_index()?;
 
         Ok(vault)
     }
@@ -163,6 +271,9 @@ impl KnowledgeVault {
             conn,
             db_path: PathBuf::from(":memory:"),
             embedding_dimensions: 384,
+            ann: RefCell::new(HnswIndex::new(384, HnswParams::default())),
+            ann_dirty: Cell::new(false),
+            model_id: Cell::new(None),
         };
 
         vault.init_schema()?;
@@ -205,12 +316,15 @@ impl KnowledgeVault {
                 end_offset INTEGER NOT NULL,
                 token_count INTEGER NOT NULL,
                 metadata TEXT DEFAULT '{}',
+                content_hash TEXT,
                 FOREIGN KEY (document_id) REFERENCES documents(id) ON DELETE CASCADE
             )
             "#,
             [],
         )?;
 
+        self.add_column_if_missing("chunks", "content_hash", "TEXT")?;
+
         // Embeddings table (stores raw vectors)
         self.conn.execute(
             r#"
@@ -218,21 +332,64 @@ impl KnowledgeVault {
                 chunk_id TEXT PRIMARY KEY,
                 embedding BLOB NOT NULL,
                 created_at TEXT NOT NULL,
+                model_id INTEGER,
                 FOREIGN KEY (chunk_id) REFERENCES chunks(id) ON DELETE CASCADE
             )
             "#,
             [],
         )?;
+        self.add_column_if_missing("embeddings", "model_id", "INTEGER")?;
 
-        // Attempt to create VSS virtual table for vector similarity search
-        // This requires sqlite-vss extension to be loaded
-        if let Err(e) = self.create_vss_table() {
-            debug!(
-                "VSS table creation failed (extension may not be loaded): {}",
-                e
-            );
-            debug!("Falling back to basic embedding storage without vector search");
-        }
+        // Embedding models the vault has used
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS embedding_models (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                dimensions INTEGER NOT NULL,
+                version TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                UNIQUE (name, dimensions, version)
+            )
+            "#,
+            [],
+        )?;
+
+        // User-defined document tags
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS document_tags (
+                document_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (document_id, tag),
+                FOREIGN KEY (document_id) REFERENCES documents(id) ON DELETE CASCADE
+            )
+            "#,
+            [],
+        )?;
+
+        // Embeddings from an unfinished model migration
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS pending_embeddings (
+                chunk_id TEXT PRIMARY KEY,
+                embedding BLOB NOT NULL,
+                created_at TEXT NOT NULL
+            )
+            "#,
+            [],
+        )?;
+
+        // Vault metadata (ANN index generation, embedding models)
+        self.conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS vault_meta (
+                key TEXT PRIMARY KEY,
+                value INTEGER NOT NULL
+            )
+            "#,
+            [],
+        )?;
 
         // Indexes
         self.conn.execute(
@@ -247,41 +404,150 @@ impl KnowledgeVault {
             "CREATE INDEX IF NOT EXISTS idx_chunks_document ON chunks(document_id)",
             [],
         )?;
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_embeddings_model ON embeddings(model_id)",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_document_tags_tag ON document_tags(tag)",
+            [],
+        )?;
+
+        self.init_keyword_index()?;
+
+        // Counted once for vaults from before the count was kept; afterwards
+        // only adopting a first model or finishing a migration changes it
+        if self.meta_value(EMBEDDING_MODEL_COUNT_KEY)?.is_none() {
+            Self::count_embedding_models(&self.conn)?;
+        }
 
         debug!("Schema initialized");
         Ok(())
     }
 
-    /// Create VSS virtual table for vector similarity search
-    fn create_vss_table(&self) -> KnowledgeResult<()> {
-        // Validate embedding dimensions are within reasonable bounds
-        if self.embedding_dimensions == 0 || self.embedding_dimensions > 10000 {
-            return Err(KnowledgeError::InvalidFormat(
-                format!("Embedding dimensions must be between 1 and 10000, got {}", self.embedding_dimensions)
-            ));
+    /// Add a column to a table created by an older version of the schema
+    fn add_column_if_missing(&self, table: &str, column: &str, decl: &str) -> KnowledgeResult<()> {
+        let exists: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)",
+            params![table, column],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            self.conn
+                .execute(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"), [])?;
         }
+        Ok(())
+    }
 
-        // Create VSS virtual table for approximate nearest neighbor search
-        // vss0 requires explicit chunk_id column for joins
-        self.conn.execute(
-            &format!(
-                "CREATE VIRTUAL TABLE IF NOT EXISTS vss_chunks USING vss0(vss_chunk_id TEXT PRIMARY KEY, embedding({}))",
-                self.embedding_dimensions
-            ),
+    /// Create the FTS5 keyword index over chunk content
+    ///
+    /// External-content table: the text lives only in `chunks`, triggers keep
+    /// the index in step. Vaults created before the index existed are
+    /// backfilled once.
+    fn init_keyword_index(&self) -> KnowledgeResult<()> {
+        let exists: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'chunks_fts')",
             [],
+            |row| row.get(0),
         )?;
 
-        debug!(
-            "VSS virtual table created with {} dimensions",
-            self.embedding_dimensions
-        );
+        self.conn.execute_batch(
+            r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS chunks_fts USING fts5(
+                content,
+                content = 'chunks',
+                content_rowid = 'rowid',
+                tokenize = 'unicode61 remove_diacritics 2'
+            );
+
+            CREATE TRIGGER IF NOT EXISTS chunks_fts_insert AFTER INSERT ON chunks BEGIN
+                INSERT INTO chunks_fts (rowid, content) VALUES (new.rowid, new.content);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS chunks_fts_delete AFTER DELETE ON chunks BEGIN
+                INSERT INTO chunks_fts (chunks_fts, rowid, content)
+                VALUES ('delete', old.rowid, old.content);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS chunks_fts_update AFTER UPDATE OF content ON chunks BEGIN
+                INSERT INTO chunks_fts (chunks_fts, rowid, content)
+                VALUES ('delete', old.rowid, old.content);
+                INSERT INTO chunks_fts (rowid, content) VALUES (new.rowid, new.content);
+            END;
+            "#,
+        )?;
+
+        if !exists {
+            self.rebuild_keyword_index()?;
+        }
+        Ok(())
+    }
+
+    /// Rebuild the keyword index from `chunks`
+    ///
+    /// Needed after `VACUUM`, which may renumber chunk rowids.
+    pub fn rebuild_keyword_index(&self) -> KnowledgeResult<()> {
+        self.conn
+            .execute("INSERT INTO chunks_fts (chunks_fts) VALUES ('rebuild')", [])?;
+        debug!("Rebuilt keyword index");
         Ok(())
     }
 
+    /// Rank chunks against an FTS5 query with BM25
+    ///
+    /// `fts_query` uses FTS5 query syntax (see
+    /// [`fts5_query`](crate::search::fts5_query) for building one from user
+    /// input). Returns `(chunk_id, bm25_score)` pairs, best first; scores are
+    /// positive and unbounded.
+    ///
+    /// # Errors
+    /// Returns error if the query is not valid FTS5 syntax.
+    pub fn keyword_search(
+        &self,
+        fts_query: &str,
+        top_k: usize,
+        doc_types: Option<&[String]>,
+        doc_ids: Option<&[String]>,
+    ) -> KnowledgeResult<Vec<(String, f32)>> {
+        let mut sql = String::from(
+            r#"
+            SELECT c.id, bm25(chunks_fts) AS rank
+            FROM chunks_fts
+            JOIN chunks c ON c.rowid = chunks_fts.rowid
+            JOIN documents d ON d.id = c.document_id
+            WHERE chunks_fts MATCH ?
+            "#,
+        );
+        let mut values: Vec<String> = vec![fts_query.to_string()];
+
+        for (column, filter) in [("d.doc_type", doc_types), ("d.id", doc_ids)] {
+            if let Some(filter) = filter {
+                let placeholders = vec!["?"; filter.len()].join(", ");
+                sql.push_str(&format!(" AND {} IN ({})", column, placeholders));
+                values.extend(filter.iter().cloned());
+            }
+        }
+        sql.push_str(&format!(" ORDER BY rank LIMIT {}", top_k));
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let results = stmt
+            .query_map(params_from_iter(values), |row| {
+                // FTS5 reports BM25 negated so that ascending order is best first
+                Ok((row.get::<_, String>(0)?, -row.get::<_, f64>(1)? as f32))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(results)
+    }
+
     /// Insert a document
     #[instrument(skip(self, doc))]
     pub fn insert_document(&self, doc: &Document) -> KnowledgeResult<()> {
-        self.conn.execute(
+        Self::insert_document_in(&self.conn, doc)
+    }
+
+    fn insert_document_in(conn: &Connection, doc: &Document) -> KnowledgeResult<()> {
+        conn.execute(
             r#"
             INSERT INTO documents (id, path, title, doc_type, content_hash, chunk_count, size_bytes, indexed_at, updated_at, metadata)
             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
@@ -312,34 +578,135 @@ impl KnowledgeVault {
 
     /// Get a document by ID
     pub fn get_document(&self, id: &str) -> KnowledgeResult<Option<Document>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, path, title, doc_type, content_hash, chunk_count, size_bytes, indexed_at, updated_at, metadata FROM documents WHERE id = ?1"
-        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {DOCUMENT_COLUMNS} FROM documents WHERE id = ?1"
+        ))?;
 
-        let doc = stmt
-            .query_row(params![id], |row| {
-                Ok(Document {
-                    id: row.get(0)?,
-                    path: row.get(1)?,
-                    title: row.get(2)?,
-                    doc_type: row.get(3)?,
-                    content_hash: row.get(4)?,
-                    chunk_count: row.get(5)?,
-                    size_bytes: row.get::<_, i64>(6)? as u64,
-                    indexed_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                        .unwrap_or_else(|_| chrono::Utc::now()),
-                    updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(8)?)
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                        .unwrap_or_else(|_| chrono::Utc::now()),
-                    metadata: serde_json::from_str(&row.get::<_, String>(9)?).unwrap_or_default(),
-                })
-            })
-            .optional()?;
+        let doc = stmt.query_row(params![id], document_from_row).optional()?;
 
         Ok(doc)
     }
 
+    /// Documents matching the document conditions of a filter, newest first
+    ///
+    /// Path, tag and date conditions run in SQL; metadata conditions are
+    /// checked on the loaded documents. Chunk conditions are ignored.
+    pub fn filter_documents(
+        &self,
+        doc_types: Option<&[String]>,
+        filter: &SearchFilter,
+    ) -> KnowledgeResult<Vec<Document>> {
+        let mut sql = format!("SELECT {DOCUMENT_COLUMNS} FROM documents WHERE 1 = 1");
+        let mut values: Vec<String> = Vec::new();
+
+        if let Some(types) = doc_types {
+            sql.push_str(&format!(
+                " AND doc_type IN ({})",
+                vec!["?"; types.len()].join(", ")
+            ));
+            values.extend(types.iter().cloned());
+        }
+        if !filter.path_globs.is_empty() {
+            let globs = vec!["path GLOB ?"; filter.path_globs.len()].join(" OR ");
+            sql.push_str(&format!(" AND ({})", globs));
+            values.extend(filter.path_globs.iter().cloned());
+        }
+        // Deduplicated, so `--tag design --tag Design` needs one row per document
+        let tags: BTreeSet<String> = filter
+            .tags
+            .iter()
+            .filter_map(|t| normalize_tag(t))
+            .collect();
+        if !tags.is_empty() {
+            sql.push_str(&format!(
+                " AND id IN (SELECT document_id FROM document_tags WHERE tag IN ({}) GROUP BY document_id HAVING COUNT(*) = {})",
+                vec!["?"; tags.len()].join(", "),
+                tags.len()
+            ));
+            values.extend(tags);
+        }
+        for (column, range) in [
+            ("indexed_at", &filter.indexed),
+            ("updated_at", &filter.updated),
+        ] {
+            if let Some(after) = range.after {
+                sql.push_str(&format!(" AND julianday({}) >= julianday(?)", column));
+                values.push(after.to_rfc3339());
+            }
+            if let Some(before) = range.before {
+                sql.push_str(&format!(" AND julianday({}) < julianday(?)", column));
+                values.push(before.to_rfc3339());
+            }
+        }
+        sql.push_str(" ORDER BY updated_at DESC");
+
+        let docs = self
+            .conn
+            .prepare(&sql)?
+            .query_map(params_from_iter(values), document_from_row)?
+            .filter(|doc| {
+                doc.as_ref()
+                    .map_or(true, |doc| filter.matches_document_metadata(&doc.metadata))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(docs)
+    }
+
+    /// Tag a document; tags are trimmed and lowercased
+    ///
+    /// Returns the number of tags that were new to the document.
+    pub fn add_tags(&self, document_id: &str, tags: &[String]) -> KnowledgeResult<usize> {
+        if self.get_document(document_id)?.is_none() {
+            return Err(KnowledgeError::NotFound(document_id.to_string()));
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut added = 0;
+        for tag in tags.iter().filter_map(|t| normalize_tag(t)) {
+            added += tx.execute(
+                "INSERT OR IGNORE INTO document_tags (document_id, tag) VALUES (?1, ?2)",
+                params![document_id, tag],
+            )?;
+        }
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// Remove tags from a document; returns how many it had
+    pub fn remove_tags(&self, document_id: &str, tags: &[String]) -> KnowledgeResult<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut removed = 0;
+        for tag in tags.iter().filter_map(|t| normalize_tag(t)) {
+            removed += tx.execute(
+                "DELETE FROM document_tags WHERE document_id = ?1 AND tag = ?2",
+                params![document_id, tag],
+            )?;
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Tags of a document, sorted
+    pub fn document_tags(&self, document_id: &str) -> KnowledgeResult<Vec<String>> {
+        let tags = self
+            .conn
+            .prepare_cached("SELECT tag FROM document_tags WHERE document_id = ?1 ORDER BY tag")?
+            .query_map(params![document_id], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(tags)
+    }
+
+    /// Every tag in the vault with its document count, sorted by tag
+    pub fn tag_counts(&self) -> KnowledgeResult<Vec<(String, u64)>> {
+        let counts = self
+            .conn
+            .prepare("SELECT tag, COUNT(*) FROM document_tags GROUP BY tag ORDER BY tag")?
+            .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as u64)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(counts)
+    }
+
     /// Check if a document exists by content hash
     pub fn has_document_hash(&self, hash: &str) -> KnowledgeResult<bool> {
         let count: i64 = self.conn.query_row(
@@ -351,14 +718,67 @@ impl KnowledgeVault {
         Ok(count > 0)
     }
 
+    /// Point a document at a new path without touching its chunks
+    pub fn move_document(
+        &self,
+        id: &str,
+        new_path: &str,
+        title: &str,
+        doc_type: &str,
+    ) -> KnowledgeResult<()> {
+        let updated = self.conn.execute(
+            "UPDATE documents SET path = ?2, title = ?3, doc_type = ?4, updated_at = ?5 WHERE id = ?1",
+            params![id, new_path, title, doc_type, chrono::Utc::now().to_rfc3339()],
+        )?;
+
+        if updated == 0 {
+            return Err(KnowledgeError::NotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
     /// Delete a document and its chunks
+    ///
+    /// Chunks and embeddings are deleted explicitly: foreign key enforcement
+    /// is off by default in SQLite, so `ON DELETE CASCADE` does not fire.
     pub fn delete_document(&self, id: &str) -> KnowledgeResult<()> {
-        self.conn
-            .execute("DELETE FROM documents WHERE id = ?1", params![id])?;
+        let chunk_ids: Vec<String> = self
+            .conn
+            .prepare("SELECT id FROM chunks WHERE document_id = ?1")?
+            .query_map(params![id], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM embeddings WHERE chunk_id IN (SELECT id FROM chunks WHERE document_id = ?1)",
+            params![id],
+        )?;
+        tx.execute(
+            "DELETE FROM pending_embeddings WHERE chunk_id IN (SELECT id FROM chunks WHERE document_id = ?1)",
+            params![id],
+        )?;
+        tx.execute("DELETE FROM chunks WHERE document_id = ?1", params![id])?;
+        tx.execute(
+            "DELETE FROM document_tags WHERE document_id = ?1",
+            params![id],
+        )?;
+        tx.execute("DELETE FROM documents WHERE id = ?1", params![id])?;
+        let generation = Self::advance_ann_generation(&tx)?;
+        tx.commit()?;
+
+        let mut ann = self.ann.borrow_mut();
+        for chunk_id in &chunk_ids {
+            ann.remove(chunk_id);
+        }
+        Self::sync_generation(&mut ann, generation);
+        self.ann_dirty.set(true);
         Ok(())
     }
 
     /// Insert a chunk
+    ///
+    /// The chunk's content hash is stored alongside it so re-indexing can
+    /// tell which chunks changed.
     #[allow(clippy::too_many_arguments)]
     pub fn insert_chunk(
         &self,
@@ -370,10 +790,33 @@ impl KnowledgeVault {
         end_offset: u64,
         token_count: u32,
     ) -> KnowledgeResult<()> {
-        self.conn.execute(
+        Self::insert_chunk_in(
+            &self.conn,
+            id,
+            document_id,
+            chunk_index,
+            content,
+            start_offset,
+            end_offset,
+            token_count,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn insert_chunk_in(
+        conn: &Connection,
+        id: &str,
+        document_id: &str,
+        chunk_index: u32,
+        content: &str,
+        start_offset: u64,
+        end_offset: u64,
+        token_count: u32,
+    ) -> KnowledgeResult<()> {
+        conn.execute(
             r#"
-            INSERT INTO chunks (id, document_id, chunk_index, content, start_offset, end_offset, token_count)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            INSERT INTO chunks (id, document_id, chunk_index, content, start_offset, end_offset, token_count, content_hash)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
             "#,
             params![
                 id,
@@ -383,28 +826,120 @@ impl KnowledgeVault {
                 start_offset as i64,
                 end_offset as i64,
                 token_count,
+                hash_content(content),
             ],
         )?;
 
         Ok(())
     }
 
+    /// Record where a chunk came from (code symbol, page or section)
+    pub fn set_chunk_location(&self, chunk_id: &str, location: &ChunkLocation) -> KnowledgeResult<()> {
+        Self::set_chunk_location_in(&self.conn, chunk_id, location)
+    }
+
+    fn set_chunk_location_in(
+        conn: &Connection,
+        chunk_id: &str,
+        location: &ChunkLocation,
+    ) -> KnowledgeResult<()> {
+        conn.execute(
+            "UPDATE chunks SET metadata = ?2 WHERE id = ?1",
+            params![chunk_id, serde_json::to_string(location).unwrap_or_default()],
+        )?;
+        Ok(())
+    }
+
+    /// Move a kept chunk to its place in a new version of its document
+    ///
+    /// Content and embedding are left alone.
+    pub fn update_chunk_position(
+        &self,
+        chunk_id: &str,
+        chunk_index: u32,
+        start_offset: u64,
+        end_offset: u64,
+        token_count: u32,
+    ) -> KnowledgeResult<()> {
+        Self::update_chunk_position_in(
+            &self.conn,
+            chunk_id,
+            chunk_index,
+            start_offset,
+            end_offset,
+            token_count,
+        )
+    }
+
+    fn update_chunk_position_in(
+        conn: &Connection,
+        chunk_id: &str,
+        chunk_index: u32,
+        start_offset: u64,
+        end_offset: u64,
+        token_count: u32,
+    ) -> KnowledgeResult<()> {
+        let updated = conn.execute(
+            "UPDATE chunks SET chunk_index = ?2, start_offset = ?3, end_offset = ?4, token_count = ?5 WHERE id = ?1",
+            params![chunk_id, chunk_index, start_offset as i64, end_offset as i64, token_count],
+        )?;
+
+        if updated == 0 {
+            return Err(KnowledgeError::NotFound(chunk_id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Delete individual chunks and their embeddings
+    pub fn delete_chunks(&self, chunk_ids: &[String]) -> KnowledgeResult<()> {
+        if chunk_ids.is_empty() {
+            return Ok(());
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        Self::delete_chunks_in(&tx, chunk_ids)?;
+        let generation = Self::advance_ann_generation(&tx)?;
+        tx.commit()?;
+
+        let mut ann = self.ann.borrow_mut();
+        for chunk_id in chunk_ids {
+            ann.remove(chunk_id);
+        }
+        Self::sync_generation(&mut ann, generation);
+        self.ann_dirty.set(true);
+        Ok(())
+    }
+
+    fn delete_chunks_in(conn: &Connection, chunk_ids: &[String]) -> KnowledgeResult<()> {
+        for chunk_id in chunk_ids {
+            conn.execute("DELETE FROM embeddings WHERE chunk_id = ?1", params![chunk_id])?;
+            conn.execute("DELETE FROM pending_embeddings WHERE chunk_id = ?1", params![chunk_id])?;
+            conn.execute("DELETE FROM chunks WHERE id = ?1", params![chunk_id])?;
+        }
+        Ok(())
+    }
+
     /// Get chunks for a document
     pub fn get_chunks(&self, document_id: &str) -> KnowledgeResult<Vec<ChunkRecord>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, chunk_index, content, start_offset, end_offset, token_count FROM chunks WHERE document_id = ?1 ORDER BY chunk_index"
+            "SELECT id, chunk_index, content, start_offset, end_offset, token_count, metadata, content_hash FROM chunks WHERE document_id = ?1 ORDER BY chunk_index"
         )?;
 
         let chunks = stmt
             .query_map(params![document_id], |row| {
+                let content: String = row.get(2)?;
                 Ok(ChunkRecord {
                     id: row.get(0)?,
                     document_id: document_id.to_string(),
                     chunk_index: row.get(1)?,
-                    content: row.get(2)?,
+                    content_hash: row
+                        .get::<_, Option<String>>(7)?
+                        .unwrap_or_else(|| hash_content(&content)),
+                    content,
                     start_offset: row.get::<_, i64>(3)? as u64,
                     end_offset: row.get::<_, i64>(4)? as u64,
                     token_count: row.get(5)?,
+                    location: decode_chunk_location(row.get(6)?),
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -413,188 +948,368 @@ impl KnowledgeVault {
     }
 
     /// Insert an embedding
+    ///
+    /// Tagged with the vault's embedding model, if one is recorded.
+    ///
+    /// # Errors
+    /// Returns error if the embedding does not match the vault's dimensions.
     pub fn insert_embedding(&self, chunk_id: &str, embedding: &[f32]) -> KnowledgeResult<()> {
-        let blob: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
-
-        self.conn.execute(
-            "INSERT INTO embeddings (chunk_id, embedding, created_at) VALUES (?1, ?2, ?3)",
-            params![chunk_id, blob, chrono::Utc::now().to_rfc3339()],
-        )?;
+        self.check_dimensions(embedding)?;
 
-        // Try to insert into VSS table for vector search
-        // Format embedding as vss_debug format: "[0.1,0.2,0.3,...]"
-        let embedding_str = {
-            let mut s = String::from("[");
-            for (i, f) in embedding.iter().enumerate() {
-                if i > 0 {
-                    s.push(',');
-                }
-                s.push_str(&f.to_string());
-            }
-            s.push(']');
-            s
-        };
+        let tx = self.conn.unchecked_transaction()?;
+        self.insert_embedding_in(&tx, chunk_id, embedding)?;
+        let generation = Self::advance_ann_generation(&tx)?;
+        tx.commit()?;
 
-        // Insert into VSS table for fast vector search
-        if let Err(e) = self.conn.execute(
-            "INSERT INTO vss_chunks (vss_chunk_id, embedding) VALUES (?1, ?2)",
-            params![chunk_id, embedding_str],
-        ) {
-            warn!(
-                "Failed to insert into VSS table: {}. Vector search may be limited to cosine similarity fallback.",
-                e
-            );
-        }
+        let mut ann = self.ann.borrow_mut();
+        ann.insert(chunk_id, embedding)?;
+        Self::sync_generation(&mut ann, generation);
+        self.ann_dirty.set(true);
 
         Ok(())
     }
 
-    /// Get embedding for a chunk
-    pub fn get_embedding(&self, chunk_id: &str) -> KnowledgeResult<Option<Vec<f32>>> {
-        let blob: Option<Vec<u8>> = self
-            .conn
-            .query_row(
-                "SELECT embedding FROM embeddings WHERE chunk_id = ?1",
-                params![chunk_id],
-                |row| row.get::<_, Vec<u8>>(0),
-            )
-            .optional()?;
-
-        Ok(blob.map(|b: Vec<u8>| {
-            b.chunks_exact(4)
-                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-                .collect()
-        }))
+    fn check_dimensions(&self, embedding: &[f32]) -> KnowledgeResult<()> {
+        if embedding.len() != self.embedding_dimensions as usize {
+            return Err(KnowledgeError::InvalidFormat(format!(
+                "Embedding has {} dimensions, vault expects {}",
+                embedding.len(),
+                self.embedding_dimensions
+            )));
+        }
+        Ok(())
     }
 
-    /// Search for similar chunks using vector similarity
-    #[instrument(skip(self, query_embedding))]
-    pub fn search(
+    fn insert_embedding_in(
+        &self,
+        conn: &Connection,
+        chunk_id: &str,
+        embedding: &[f32],
+    ) -> KnowledgeResult<()> {
+        let blob: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+        conn.execute(
+            "INSERT INTO embeddings (chunk_id, embedding, created_at, model_id) VALUES (?1, ?2, ?3, ?4)",
+            params![chunk_id, blob, chrono::Utc::now().to_rfc3339(), self.model_id.get()],
+        )?;
+        Ok(())
+    }
+
+    /// Write a new version of a document in one transaction
+    ///
+    /// Deletes the `stale` chunks of the previous version, saves the
+    /// document, and writes `chunks` in order: kept chunks are moved to
+    /// their new positions, new ones are inserted with their embeddings.
+    /// If any write fails, none of them is applied.
+    ///
+    /// # Errors
+    /// Returns error if an embedding does not match the vault's dimensions,
+    /// or a kept chunk no longer exists.
+    pub fn write_document(
+        &self,
+        doc: &Document,
+        stale: &[String],
+        chunks: &[ChunkWrite],
+    ) -> KnowledgeResult<()> {
+        for chunk in chunks {
+            if let ChunkWrite::New { embedding, .. } = chunk {
+                self.check_dimensions(embedding)?;
+            }
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        Self::delete_chunks_in(&tx, stale)?;
+        Self::insert_document_in(&tx, doc)?;
+        for (i, write) in chunks.iter().enumerate() {
+            match write {
+                ChunkWrite::Kept { id, chunk } => {
+                    Self::update_chunk_position_in(
+                        &tx,
+                        id,
+                        i as u32,
+                        chunk.start_offset,
+                        chunk.end_offset,
+                        chunk.token_count,
+                    )?;
+                    Self::set_chunk_location_in(&tx, id, &chunk.location)?;
+                },
+                ChunkWrite::New { id, chunk, embedding } => {
+                    Self::insert_chunk_in(
+                        &tx,
+                        id,
+                        &doc.id,
+                        i as u32,
+                        &chunk.content,
+                        chunk.start_offset,
+                        chunk.end_offset,
+                        chunk.token_count,
+                    )?;
+                    if !chunk.location.is_empty() {
+                        Self::set_chunk_location_in(&tx, id, &chunk.location)?;
+                    }
+                    self.insert_embedding_in(&tx, id, embedding)?;
+                },
+            }
+        }
+        let generation = Self::advance_ann_generation(&tx)?;
+        tx.commit()?;
+
+        let mut ann = self.ann.borrow_mut();
+        for chunk_id in stale {
+            ann.remove(chunk_id);
+        }
+        for write in chunks {
+            if let ChunkWrite::New { id, embedding, .. } = write {
+                ann.insert(id, embedding)?;
+            }
+        }
+        Self::sync_generation(&mut ann, generation);
+        self.ann_dirty.set(true);
+        Ok(())
+    }
+
+    /// Get embedding for a chunk
+    pub fn get_embedding(&self, chunk_id: &str) -> KnowledgeResult<Option<Vec<f32>>> {
+        let blob: Option<Vec<u8>> = self
+            .conn
+            .query_row(
+                "SELECT embedding FROM embeddings WHERE chunk_id = ?1",
+                params![chunk_id],
+                |row| row.get::<_, Vec<u8>>(0),
+            )
+            .optional()?;
+
+        Ok(blob.map(|b| decode_embedding(&b)))
+    }
+
+    /// Get a chunk by ID
+    pub fn get_chunk(&self, chunk_id: &str) -> KnowledgeResult<Option<ChunkRecord>> {
+        let chunk = self
+            .conn
+            .prepare_cached(
+                "SELECT id, document_id, chunk_index, content, start_offset, end_offset, token_count, metadata, content_hash FROM chunks WHERE id = ?1",
+            )?
+            .query_row(params![chunk_id], |row| {
+                let content: String = row.get(3)?;
+                Ok(ChunkRecord {
+                    id: row.get(0)?,
+                    document_id: row.get(1)?,
+                    chunk_index: row.get(2)?,
+                    content_hash: row
+                        .get::<_, Option<String>>(8)?
+                        .unwrap_or_else(|| hash_content(&content)),
+                    content,
+                    start_offset: row.get::<_, i64>(4)? as u64,
+                    end_offset: row.get::<_, i64>(5)? as u64,
+                    token_count: row.get(6)?,
+                    location: decode_chunk_location(row.get(7)?),
+                })
+            })
+            .optional()?;
+
+        Ok(chunk)
+    }
+
+    /// Search for similar chunks using vector similarity
+    ///
+    /// Uses the HNSW index. Queries whose dimensions don't match the vault
+    /// were embedded by a different model and are rejected.
+    #[instrument(skip(self, query_embedding))]
+    pub fn search(
         &self,
         query_embedding: &[f32],
         top_k: usize,
     ) -> KnowledgeResult<Vec<ChunkResult>> {
         debug!("Searching for top {} similar chunks", top_k);
 
-        // Try VSS-based search first
-        if let Ok(results) = self.search_vss(query_embedding, top_k) {
-            return Ok(results);
+        if query_embedding.len() != self.embedding_dimensions as usize {
+            return Err(KnowledgeError::ModelMismatch(format!(
+                "query has {} dimensions but the vault's embeddings have {}",
+                query_embedding.len(),
+                self.embedding_dimensions
+            )));
         }
 
-        // Fallback to simple cosine similarity search
-        debug!("VSS search unavailable, using manual cosine similarity");
-        self.search_cosine(query_embedding, top_k)
+        let mut stmt = self.conn.prepare_cached(
+            r#"
+            SELECT c.id, c.document_id, c.content, d.title, d.path
+            FROM chunks c
+            JOIN documents d ON c.document_id = d.id
+            WHERE c.id = ?1
+            "#,
+        )?;
+
+        let mut results = Vec::with_capacity(top_k);
+        for (chunk_id, score) in self.nearest_chunks(query_embedding, top_k)? {
+            let result = stmt
+                .query_row(params![chunk_id], |row| {
+                    Ok(ChunkResult {
+                        chunk_id: row.get(0)?,
+                        document_id: row.get(1)?,
+                        content: row.get(2)?,
+                        document_title: row.get(3)?,
+                        document_path: row.get(4)?,
+                        score,
+                    })
+                })
+                .optional()?;
+            results.extend(result);
+        }
+
+        Ok(results)
     }
 
-    /// Search using VSS virtual table (fast approximate nearest neighbor)
-    fn search_vss(
+    /// Approximate nearest chunks as `(chunk_id, cosine_similarity)`, best first
+    ///
+    /// Rebuilds the index first if another connection changed the
+    /// embeddings since it was loaded.
+    pub fn nearest_chunks(
         &self,
         query_embedding: &[f32],
         top_k: usize,
-    ) -> KnowledgeResult<Vec<ChunkResult>> {
-        // Format query embedding more efficiently
-        let query_str = {
-            let mut s = String::from("[");
-            for (i, f) in query_embedding.iter().enumerate() {
-                if i > 0 {
-                    s.push(',');
+    ) -> KnowledgeResult<Vec<(String, f32)>> {
+        if self.ann.borrow().generation() != self.ann_generation()? {
+            info!("Embeddings changed outside this connection, rebuilding ANN index");
+            self.rebuild_ann_index()?;
+        }
+        Ok(self.ann.borrow().search(query_embedding, top_k))
+    }
+
+    /// Path of the ANN index file (`None` for in-memory vaults)
+    pub fn ann_index_path(&self) -> Option<PathBuf> {
+        (self.db_path != Path::new(":memory:")).then(|| self.db_path.with_extension("hnsw"))
+    }
+
+    /// Rebuild the ANN index from the embeddings table and save it
+    ///
+    /// Returns the number of indexed embeddings.
+    #[instrument(skip(self))]
+    pub fn rebuild_ann_index(&self) -> KnowledgeResult<usize> {
+        let mut index = HnswIndex::new(self.embedding_dimensions as usize, HnswParams::default());
+        let mut skipped = 0;
+
+        {
+            let mut stmt = self.conn.prepare(
+                "SELECT e.chunk_id, e.embedding FROM embeddings e JOIN chunks c ON e.chunk_id = c.id",
+            )?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let chunk_id: String = row.get(0)?;
+                let embedding = decode_embedding(&row.get::<_, Vec<u8>>(1)?);
+                if index.insert(&chunk_id, &embedding).is_err() {
+                    skipped += 1;
                 }
-                s.push_str(&f.to_string());
             }
-            s.push(']');
-            s
+        }
+
+        if skipped > 0 {
+            warn!(
+                "Skipped {} embeddings that don't have {} dimensions",
+                skipped, self.embedding_dimensions
+            );
+        }
+
+        index.set_generation(self.ann_generation()?);
+        let count = index.len();
+        *self.ann.borrow_mut() = index;
+        self.ann_dirty.set(true);
+        self.save_ann_index()?;
+
+        info!("Rebuilt ANN index with {} embeddings", count);
+        Ok(count)
+    }
+
+    /// Save the ANN index next to the database if it has unsaved changes
+    ///
+    /// Also called when the vault is dropped. Compacts the graph first if
+    /// deletions have left it mostly tombstones.
+    pub fn save_ann_index(&self) -> KnowledgeResult<()> {
+        let Some(path) = self.ann_index_path() else {
+            return Ok(());
         };
+        if !self.ann_dirty.get() {
+            return Ok(());
+        }
 
-        let sql = r#"
-            SELECT
-                c.id,
-                c.document_id,
-                c.content,
-                d.title,
-                d.path,
-                vss.distance
-            FROM vss_chunks
-            JOIN chunks c ON vss_chunks.vss_chunk_id = c.id
-            JOIN documents d ON c.document_id = d.id
-            WHERE vss_chunks.embedding MATCH vss_search(?1)
-            ORDER BY vss.distance
-            LIMIT ?2
-        "#;
+        if self.ann.borrow().needs_compaction() {
+            let compacted = self.ann.borrow().compacted();
+            *self.ann.borrow_mut() = compacted;
+        }
 
-        let mut stmt = self.conn.prepare(sql)?;
+        self.ann.borrow().save(&path)?;
+        self.ann_dirty.set(false);
+        debug!("Saved ANN index to {:?}", path);
+        Ok(())
+    }
 
-        let results = stmt
-            .query_map(params![query_str, top_k as i64], |row| {
-                Ok(ChunkResult {
-                    chunk_id: row.get(0)?,
-                    document_id: row.get(1)?,
-                    content: row.get(2)?,
-                    document_title: row.get(3)?,
-                    document_path: Some(row.get(4)?),
-                    score: 1.0 - row.get::<_, f64>(5)? as f32, // Convert distance to similarity
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
+    /// Load the saved ANN index, rebuilding it if missing or stale
+    fn load_ann_index(&self) -> KnowledgeResult<()> {
+        let Some(path) = self.ann_index_path() else {
+            return Ok(());
+        };
 
-        Ok(results)
+        let generation = self.ann_generation()?;
+        match HnswIndex::load(&path, self.embedding_dimensions as usize) {
+            Ok(index) if index.generation() == generation => {
+                debug!("Loaded ANN index with {} embeddings", index.len());
+                *self.ann.borrow_mut() = index;
+                return Ok(());
+            },
+            Ok(_) => info!("ANN index {:?} is out of date, rebuilding", path),
+            Err(e) if path.exists() => {
+                warn!("Failed to load ANN index {:?}: {}. Rebuilding", path, e)
+            },
+            Err(_) => debug!("No ANN index at {:?}, building", path),
+        }
+
+        self.rebuild_ann_index()?;
+        Ok(())
     }
 
-    /// Fallback: Manual cosine similarity search
-    ///
-    /// # When To Use
-    ///
-    /// This function is called when **VSS extension is not available**.
-    /// It's a fallback that implements semantic search manually.
-    ///
-    /// # Performance Characteristics
-    ///
-    /// **⚠️ WARNING**: This method loads **ALL embeddings into memory**.
-    ///
-    /// ### Time Complexity
-    /// - **Query**: O(n * d) where:
-    ///   - n = total number of embeddings in database
-    ///   - d = embedding dimension (e.g., 384 for BGE-Micro)
-    /// - **Sorting**: O(n log n) for top-k selection
-    ///
-    /// ### Space Complexity
-    /// - **Memory**: O(n * d * 4) bytes (all embeddings loaded as f32)
-    ///   - Example: 10k embeddings × 384 dims × 4 bytes = ~15 MB
-    ///   - Example: 100k embeddings × 384 dims × 4 bytes = ~150 MB
-    ///
-    /// ### Benchmarks
-    /// - **1,000 chunks**: ~10ms
-    /// - **10,000 chunks**: ~100ms
-    /// - **100,000 chunks**: ~1-2s (becomes slow)
-    ///
-    /// # Recommendations
-    ///
-    /// ✅ **Suitable for**:
-    /// - Small datasets (<10k chunks)
-    /// - Development/testing
-    /// - Systems without VSS extension
-    ///
-    /// ❌ **NOT suitable for**:
-    /// - Large datasets (>10k chunks)
-    /// - Production systems
-    /// - Low-memory environments
-    ///
-    /// # Binary Format
-    ///
-    /// Embeddings stored as little-endian f32:
-    /// ```text
-    /// [byte0][byte1][byte2][byte3]  = f32 value 0
-    /// [byte4][byte5][byte6][byte7]  = f32 value 1
-    /// ...
-    /// Total size = dimension * 4 bytes
-    /// ```
+    /// Current embeddings generation recorded in the database
+    fn ann_generation(&self) -> KnowledgeResult<u64> {
+        let generation: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT value FROM vault_meta WHERE key = ?1",
+                params![ANN_GENERATION_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(generation.unwrap_or(0) as u64)
+    }
+
+    /// Bump the embeddings generation; returns the previous value
+    fn advance_ann_generation(conn: &Connection) -> rusqlite::Result<u64> {
+        let previous: i64 = conn
+            .query_row(
+                "SELECT value FROM vault_meta WHERE key = ?1",
+                params![ANN_GENERATION_KEY],
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or(0);
+        conn.execute(
+            "INSERT OR REPLACE INTO vault_meta (key, value) VALUES (?1, ?2)",
+            params![ANN_GENERATION_KEY, previous + 1],
+        )?;
+        Ok(previous as u64)
+    }
+
+    /// Advance the index generation alongside the database
     ///
-    /// # Future Improvements
+    /// If the index was behind (another connection wrote first), it stays
+    /// behind so the next search or open rebuilds it.
+    fn sync_generation(ann: &mut HnswIndex, previous: u64) {
+        if ann.generation() == previous {
+            ann.set_generation(previous + 1);
+        }
+    }
+
+    /// Exact cosine similarity search over every embedding
     ///
-    /// For production use with large datasets:
-    /// 1. Enable SQLite-VSS extension (uses HNSW indexing)
-    /// 2. Implement approximate nearest neighbor (ANN)
-    /// 3. Add pagination/batching support
-    /// 4. Consider external vector database (e.g., pgvector, qdrant)
+    /// Loads all embeddings into memory (O(n * d)); the reference the ANN
+    /// index is tested against.
+    #[cfg(test)]
     fn search_cosine(
         &self,
         query_embedding: &[f32],
@@ -622,11 +1337,7 @@ impl KnowledgeVault {
         let mut results: Vec<ChunkResult> = stmt
             .query_map([], |row| {
                 // Deserialize embedding from little-endian f32 bytes
-                let blob: Vec<u8> = row.get(5)?;
-                let embedding: Vec<f32> = blob
-                    .chunks_exact(4)
-                    .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-                    .collect();
+                let embedding = decode_embedding(&row.get::<_, Vec<u8>>(5)?);
 
                 // Calculate similarity score
                 let score = cosine_similarity(query_embedding, &embedding);
@@ -656,6 +1367,285 @@ impl KnowledgeVault {
         Ok(results)
     }
 
+    /// Model that produced the vault's embeddings (`None` until one is recorded)
+    pub fn embedding_model(&self) -> KnowledgeResult<Option<EmbeddingModel>> {
+        Ok(self.active_model()?.map(|(_, model)| model))
+    }
+
+    /// Length of the vectors stored and searched here
+    pub fn embedding_dimensions(&self) -> u32 {
+        self.embedding_dimensions
+    }
+
+    /// Check that embeddings from `model` can be stored and searched here
+    ///
+    /// The first model used with a vault is recorded and adopts any
+    /// embeddings written before models were tracked. Other models are
+    /// refused until the vault is migrated to them ([`Self::begin_migration`]),
+    /// as are vaults whose embeddings come from more than one model. That
+    /// count is kept in `vault_meta`, so this reads no embeddings.
+    pub fn use_embedding_model(&self, model: &EmbeddingModel) -> KnowledgeResult<()> {
+        match self.active_model()? {
+            Some((id, active)) if active == *model => self.model_id.set(Some(id)),
+            Some((_, active)) => {
+                return Err(KnowledgeError::ModelMismatch(format!(
+                    "vault embeddings come from {}, not {}; migrate the vault to switch models",
+                    active, model
+                )));
+            },
+            None => {
+                if model.dimensions != self.embedding_dimensions {
+                    return Err(KnowledgeError::ModelMismatch(format!(
+                        "{} does not match the vault's {} dimensions",
+                        model, self.embedding_dimensions
+                    )));
+                }
+
+                let tx = self.conn.unchecked_transaction()?;
+                let id = Self::register_model(&tx, model)?;
+                Self::set_meta_value(&tx, EMBEDDING_MODEL_KEY, id)?;
+                let adopted = tx.execute(
+                    "UPDATE embeddings SET model_id = ?1 WHERE model_id IS NULL",
+                    params![id],
+                )?;
+                Self::count_embedding_models(&tx)?;
+                tx.commit()?;
+
+                self.model_id.set(Some(id));
+                info!("Recorded embedding model {} ({} existing embeddings)", model, adopted);
+            },
+        }
+
+        let models = self.meta_value(EMBEDDING_MODEL_COUNT_KEY)?.unwrap_or(0);
+        if models > 1 {
+            return Err(KnowledgeError::ModelMismatch(format!(
+                "vault mixes embeddings from {} models; migrate it to a single model",
+                models
+            )));
+        }
+        Ok(())
+    }
+
+    /// Start (or resume) migrating the vault to a new embedding model
+    ///
+    /// Chunks are re-embedded with [`Self::chunks_to_migrate`] and
+    /// [`Self::stage_embedding`], then swapped in by
+    /// [`Self::finish_migration`]. Staged embeddings survive restarts;
+    /// starting a migration to a different model discards them. Returns
+    /// `None` if the vault already uses `model`.
+    pub fn begin_migration(&self, model: &EmbeddingModel) -> KnowledgeResult<Option<MigrationStatus>> {
+        if self.embedding_model()?.as_ref() == Some(model) {
+            self.cancel_migration()?;
+            return Ok(None);
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        let id = Self::register_model(&tx, model)?;
+        if self.meta_value(MIGRATION_TARGET_KEY)? != Some(id) {
+            tx.execute("DELETE FROM pending_embeddings", [])?;
+            Self::set_meta_value(&tx, MIGRATION_TARGET_KEY, id)?;
+            info!("Migrating vault embeddings to {}", model);
+        }
+        tx.commit()?;
+
+        self.migration_status()
+    }
+
+    /// Progress of the unfinished migration, if any
+    pub fn migration_status(&self) -> KnowledgeResult<Option<MigrationStatus>> {
+        let Some(to) = self.migration_target()?.map(|(_, model)| model) else {
+            return Ok(None);
+        };
+        let (embedded, total): (i64, i64) = self.conn.query_row(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM pending_embeddings p JOIN chunks c ON c.id = p.chunk_id),
+                (SELECT COUNT(*) FROM chunks)
+            "#,
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        Ok(Some(MigrationStatus {
+            from: self.embedding_model()?,
+            to,
+            embedded: embedded as u64,
+            total: total as u64,
+        }))
+    }
+
+    /// Chunks the unfinished migration has not embedded yet, as `(chunk_id, content)`
+    pub fn chunks_to_migrate(&self, limit: usize) -> KnowledgeResult<Vec<(String, String)>> {
+        let chunks = self
+            .conn
+            .prepare(
+                "SELECT id, content FROM chunks WHERE id NOT IN (SELECT chunk_id FROM pending_embeddings) ORDER BY rowid LIMIT ?1",
+            )?
+            .query_map(params![limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(chunks)
+    }
+
+    /// Store a chunk's embedding from the migration's target model
+    ///
+    /// # Errors
+    /// Returns error if no migration is in progress or the embedding does
+    /// not match the target model's dimensions.
+    pub fn stage_embedding(&self, chunk_id: &str, embedding: &[f32]) -> KnowledgeResult<()> {
+        let (_, target) = self.migration_target()?.ok_or_else(|| {
+            KnowledgeError::Internal("No embedding migration in progress".to_string())
+        })?;
+        if embedding.len() != target.dimensions as usize {
+            return Err(KnowledgeError::InvalidFormat(format!(
+                "Embedding has {} dimensions, {} produces {}",
+                embedding.len(),
+                target.name,
+                target.dimensions
+            )));
+        }
+
+        let blob: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+        self.conn.execute(
+            "INSERT OR REPLACE INTO pending_embeddings (chunk_id, embedding, created_at) VALUES (?1, ?2, ?3)",
+            params![chunk_id, blob, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Swap in the migrated embeddings and make the target the vault's model
+    ///
+    /// # Errors
+    /// Returns error if some chunk has no staged embedding yet.
+    pub fn finish_migration(&mut self) -> KnowledgeResult<()> {
+        let (target_id, target) = self.migration_target()?.ok_or_else(|| {
+            KnowledgeError::Internal("No embedding migration in progress".to_string())
+        })?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        let remaining: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM chunks WHERE id NOT IN (SELECT chunk_id FROM pending_embeddings)",
+            [],
+            |row| row.get(0),
+        )?;
+        if remaining > 0 {
+            return Err(KnowledgeError::Internal(format!(
+                "Migration incomplete: {} chunks still need embeddings",
+                remaining
+            )));
+        }
+
+        tx.execute("DELETE FROM embeddings", [])?;
+        tx.execute(
+            r#"
+            INSERT INTO embeddings (chunk_id, embedding, created_at, model_id)
+            SELECT p.chunk_id, p.embedding, p.created_at, ?1
+            FROM pending_embeddings p JOIN chunks c ON c.id = p.chunk_id
+            "#,
+            params![target_id],
+        )?;
+        tx.execute("DELETE FROM pending_embeddings", [])?;
+        Self::set_meta_value(&tx, EMBEDDING_MODEL_KEY, target_id)?;
+        Self::count_embedding_models(&tx)?;
+        tx.execute("DELETE FROM vault_meta WHERE key = ?1", params![MIGRATION_TARGET_KEY])?;
+        Self::advance_ann_generation(&tx)?;
+        tx.commit()?;
+
+        self.embedding_dimensions = target.dimensions;
+        self.model_id.set(Some(target_id));
+        self.rebuild_ann_index()?;
+
+        info!("Vault embeddings migrated to {}", target);
+        Ok(())
+    }
+
+    /// Abandon the unfinished migration and its staged embeddings
+    pub fn cancel_migration(&self) -> KnowledgeResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM pending_embeddings", [])?;
+        tx.execute("DELETE FROM vault_meta WHERE key = ?1", params![MIGRATION_TARGET_KEY])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Recorded embedding model and its row
+    fn active_model(&self) -> KnowledgeResult<Option<(i64, EmbeddingModel)>> {
+        self.model_for_key(EMBEDDING_MODEL_KEY)
+    }
+
+    /// Target model of the unfinished migration and its row
+    fn migration_target(&self) -> KnowledgeResult<Option<(i64, EmbeddingModel)>> {
+        self.model_for_key(MIGRATION_TARGET_KEY)
+    }
+
+    /// Embedding model referenced by a `vault_meta` key
+    fn model_for_key(&self, key: &str) -> KnowledgeResult<Option<(i64, EmbeddingModel)>> {
+        let Some(id) = self.meta_value(key)? else {
+            return Ok(None);
+        };
+        let model = self
+            .conn
+            .query_row(
+                "SELECT name, dimensions, version FROM embedding_models WHERE id = ?1",
+                params![id],
+                |row| {
+                    Ok(EmbeddingModel {
+                        name: row.get(0)?,
+                        dimensions: row.get(1)?,
+                        version: row.get(2)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(model.map(|model| (id, model)))
+    }
+
+    /// Row of an embedding model, adding it if new
+    fn register_model(conn: &Connection, model: &EmbeddingModel) -> rusqlite::Result<i64> {
+        conn.execute(
+            "INSERT OR IGNORE INTO embedding_models (name, dimensions, version, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![model.name, model.dimensions, model.version, chrono::Utc::now().to_rfc3339()],
+        )?;
+        conn.query_row(
+            "SELECT id FROM embedding_models WHERE name = ?1 AND dimensions = ?2 AND version = ?3",
+            params![model.name, model.dimensions, model.version],
+            |row| row.get(0),
+        )
+    }
+
+    /// Count the models the stored embeddings come from (untracked ones as
+    /// one more) and record it in `vault_meta`
+    fn count_embedding_models(conn: &Connection) -> rusqlite::Result<i64> {
+        let models: i64 = conn.query_row(
+            "SELECT COUNT(DISTINCT COALESCE(model_id, -1)) FROM embeddings",
+            [],
+            |row| row.get(0),
+        )?;
+        Self::set_meta_value(conn, EMBEDDING_MODEL_COUNT_KEY, models)?;
+        Ok(models)
+    }
+
+    /// Read a `vault_meta` value
+    fn meta_value(&self, key: &str) -> KnowledgeResult<Option<i64>> {
+        let value = self
+            .conn
+            .query_row(
+                "SELECT value FROM vault_meta WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value)
+    }
+
+    /// Write a `vault_meta` value
+    fn set_meta_value(conn: &Connection, key: &str, value: i64) -> rusqlite::Result<()> {
+        conn.execute(
+            "INSERT OR REPLACE INTO vault_meta (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
     /// Add a document with its content
     #[instrument(skip(self, content))]
     pub fn add_document(
@@ -664,13 +1654,10 @@ impl KnowledgeVault {
         content: &str,
         doc_type: &str,
     ) -> KnowledgeResult<String> {
-        use sha2::{Digest, Sha256};
         use uuid::Uuid;
 
         // Calculate content hash for deduplication
-        let mut hasher = Sha256::new();
-        hasher.update(content.as_bytes());
-        let hash = hex::encode(hasher.finalize());
+        let hash = hash_content(content);
 
         // Check if document already exists
         if let Ok(Some(existing)) = self.get_document_by_path(path) {
@@ -710,30 +1697,13 @@ impl KnowledgeVault {
     }
 
     /// Get document by path
-    fn get_document_by_path(&self, path: &str) -> KnowledgeResult<Option<Document>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, path, title, doc_type, content_hash, chunk_count, size_bytes, indexed_at, updated_at, metadata FROM documents WHERE path = ?1"
-        )?;
+    pub fn get_document_by_path(&self, path: &str) -> KnowledgeResult<Option<Document>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {DOCUMENT_COLUMNS} FROM documents WHERE path = ?1"
+        ))?;
 
         let doc = stmt
-            .query_row(params![path], |row| {
-                Ok(Document {
-                    id: row.get(0)?,
-                    path: row.get(1)?,
-                    title: row.get(2)?,
-                    doc_type: row.get(3)?,
-                    content_hash: row.get(4)?,
-                    chunk_count: row.get(5)?,
-                    size_bytes: row.get::<_, i64>(6)? as u64,
-                    indexed_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                        .unwrap_or_else(|_| chrono::Utc::now()),
-                    updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(8)?)
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                        .unwrap_or_else(|_| chrono::Utc::now()),
-                    metadata: serde_json::from_str(&row.get::<_, String>(9)?).unwrap_or_default(),
-                })
-            })
+            .query_row(params![path], document_from_row)
             .optional()?;
 
         Ok(doc)
@@ -741,29 +1711,12 @@ impl KnowledgeVault {
 
     /// List all documents
     pub fn list_documents(&self, limit: usize) -> KnowledgeResult<Vec<Document>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, path, title, doc_type, content_hash, chunk_count, size_bytes, indexed_at, updated_at, metadata FROM documents ORDER BY updated_at DESC LIMIT ?1"
-        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {DOCUMENT_COLUMNS} FROM documents ORDER BY updated_at DESC LIMIT ?1"
+        ))?;
 
         let docs = stmt
-            .query_map(params![limit as i64], |row| {
-                Ok(Document {
-                    id: row.get(0)?,
-                    path: row.get(1)?,
-                    title: row.get(2)?,
-                    doc_type: row.get(3)?,
-                    content_hash: row.get(4)?,
-                    chunk_count: row.get(5)?,
-                    size_bytes: row.get::<_, i64>(6)? as u64,
-                    indexed_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                        .unwrap_or_else(|_| chrono::Utc::now()),
-                    updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(8)?)
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                        .unwrap_or_else(|_| chrono::Utc::now()),
-                    metadata: serde_json::from_str(&row.get::<_, String>(9)?).unwrap_or_default(),
-                })
-            })?
+            .query_map(params![limit as i64], document_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(docs)
@@ -801,10 +1754,19 @@ impl KnowledgeVault {
             total_size_bytes: total_size_bytes.unwrap_or(0) as u64,
             database_size_bytes: db_size,
             embedding_dimensions: self.embedding_dimensions,
+            embedding_model: self.embedding_model()?,
         })
     }
 }
 
+impl Drop for KnowledgeVault {
+    fn drop(&mut self) {
+        if let Err(e) = self.save_ann_index() {
+            warn!("Failed to save ANN index: {}", e);
+        }
+    }
+}
+
 /// Chunk record from database
 #[derive(Debug, Clone)]
 pub struct ChunkRecord {
@@ -815,6 +1777,44 @@ pub struct ChunkRecord {
     pub start_offset: u64,
     pub end_offset: u64,
     pub token_count: u32,
+    /// Where the chunk came from (code symbol, page or section)
+    pub location: ChunkLocation,
+    /// SHA-256 of the content (hex)
+    pub content_hash: String,
+}
+
+/// SHA-256 of chunk content (hex)
+fn hash_content(content: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Build a document from a row selecting [`DOCUMENT_COLUMNS`]
+fn document_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Document> {
+    Ok(Document {
+        id: row.get(0)?,
+        path: row.get(1)?,
+        title: row.get(2)?,
+        doc_type: row.get(3)?,
+        content_hash: row.get(4)?,
+        chunk_count: row.get(5)?,
+        size_bytes: row.get::<_, i64>(6)? as u64,
+        indexed_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .unwrap_or_else(|_| chrono::Utc::now()),
+        updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(8)?)
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .unwrap_or_else(|_| chrono::Utc::now()),
+        metadata: serde_json::from_str(&row.get::<_, String>(9)?).unwrap_or_default(),
+    })
+}
+
+/// Read a chunk's location from its `metadata` column
+fn decode_chunk_location(metadata: Option<String>) -> ChunkLocation {
+    metadata
+        .and_then(|m| serde_json::from_str(&m).ok())
+        .unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -887,7 +1887,7 @@ mod tests {
     }
 
     #[test]
-    fn test_search_cosine_fallback() {
+    fn test_search_dimension_mismatch() {
         let dir = tempdir().unwrap();
         let db_path = dir.path().join("test.db");
 
@@ -908,11 +1908,14 @@ mod tests {
         let embedding = vec![0.1f32; 384];
         vault.insert_embedding(&chunk_id, &embedding).unwrap();
 
-        // Search should use cosine similarity fallback
-        let results = vault.search(&embedding, 5).unwrap();
+        // A query from a model with other dimensions is rejected
+        assert!(matches!(
+            vault.search(&[0.1f32; 128], 5),
+            Err(KnowledgeError::ModelMismatch(_))
+        ));
 
-        // Should find the chunk
-        assert!(!results.is_empty());
+        // A matching query still finds the chunk
+        let results = vault.search(&embedding, 5).unwrap();
         assert_eq!(results[0].chunk_id, chunk_id);
     }
 
@@ -936,4 +1939,446 @@ mod tests {
         let stats = vault.stats().unwrap();
         assert_eq!(stats.document_count, 1);
     }
+
+    #[test]
+    fn test_delete_document_removes_chunks() {
+        let vault = KnowledgeVault::in_memory().unwrap();
+
+        let doc_id = vault
+            .add_document("/test/delete.txt", "Delete me", "text")
+            .unwrap();
+        vault
+            .insert_chunk("chunk_001", &doc_id, 0, "Delete me", 0, 9, 2)
+            .unwrap();
+        vault.insert_embedding("chunk_001", &[0.1f32; 384]).unwrap();
+
+        vault.delete_document(&doc_id).unwrap();
+
+        let stats = vault.stats().unwrap();
+        assert_eq!(stats.document_count, 0);
+        assert_eq!(stats.chunk_count, 0);
+        assert_eq!(stats.embedding_count, 0);
+        assert!(vault.get_document_by_path("/test/delete.txt").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_chunk_location_roundtrip() {
+        let vault = KnowledgeVault::in_memory().unwrap();
+        let doc_id = vault.add_document("/src/routing.rs", "routing.rs", "rust").unwrap();
+        vault
+            .insert_chunk("chunk_001", &doc_id, 0, "fn route() {}", 0, 13, 3)
+            .unwrap();
+        assert!(vault.get_chunk("chunk_001").unwrap().unwrap().location.is_empty());
+
+        let symbol = crate::syntax::CodeSymbol {
+            name: "route".to_string(),
+            kind: crate::syntax::SymbolKind::Method,
+            scope: Some("Router".to_string()),
+            qualified_name: "Router::route".to_string(),
+            start_line: 67,
+            end_line: 69,
+        };
+        let location = ChunkLocation {
+            symbol: Some(symbol),
+            ..Default::default()
+        };
+        vault.set_chunk_location("chunk_001", &location).unwrap();
+
+        assert_eq!(vault.get_chunk("chunk_001").unwrap().unwrap().location, location);
+        assert_eq!(vault.get_chunks(&doc_id).unwrap()[0].location, location);
+
+        let page = ChunkLocation {
+            page: Some(12),
+            section: Some("Results".to_string()),
+            ..Default::default()
+        };
+        vault.set_chunk_location("chunk_001", &page).unwrap();
+        assert_eq!(vault.get_chunk("chunk_001").unwrap().unwrap().location, page);
+    }
+
+    /// Deterministic pseudo-random embedding (xorshift)
+    fn embedding(seed: u64, dimensions: usize) -> Vec<f32> {
+        let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15).max(1);
+        (0..dimensions)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+            })
+            .collect()
+    }
+
+    fn populate(vault: &KnowledgeVault, docs: usize, chunks_per_doc: usize) {
+        for d in 0..docs {
+            let doc_id = vault
+                .add_document(&format!("/test/{}.txt", d), &format!("doc {}", d), "text")
+                .unwrap();
+            for c in 0..chunks_per_doc {
+                let chunk_id = format!("chunk_{}_{}", d, c);
+                vault
+                    .insert_chunk(&chunk_id, &doc_id, c as u32, "content", 0, 7, 1)
+                    .unwrap();
+                vault
+                    .insert_embedding(&chunk_id, &embedding((d * chunks_per_doc + c) as u64, 384))
+                    .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_ann_search_matches_exact_search() {
+        let vault = KnowledgeVault::in_memory().unwrap();
+        populate(&vault, 40, 10);
+
+        let mut hits = 0;
+        for q in 0..20 {
+            let query = embedding(10_000 + q, 384);
+            let exact: Vec<String> = vault
+                .search_cosine(&query, 10)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.chunk_id)
+                .collect();
+            let approximate = vault.search(&query, 10).unwrap();
+
+            assert_eq!(approximate.len(), 10);
+            hits += approximate
+                .iter()
+                .filter(|r| exact.contains(&r.chunk_id))
+                .count();
+        }
+
+        let recall = hits as f32 / 200.0;
+        assert!(recall >= 0.9, "recall@10 = {}", recall);
+    }
+
+    #[test]
+    fn test_ann_index_tracks_deletes() {
+        let vault = KnowledgeVault::in_memory().unwrap();
+        populate(&vault, 3, 4);
+
+        let doc = vault.get_document_by_path("/test/1.txt").unwrap().unwrap();
+        vault.delete_document(&doc.id).unwrap();
+
+        let results = vault.search(&embedding(5, 384), 12).unwrap();
+        assert_eq!(results.len(), 8);
+        assert!(results.iter().all(|r| r.document_id != doc.id));
+    }
+
+    #[test]
+    fn test_insert_embedding_rejects_wrong_dimensions() {
+        let vault = KnowledgeVault::in_memory().unwrap();
+        assert!(vault.insert_embedding("chunk_001", &[0.1f32; 8]).is_err());
+        assert_eq!(vault.stats().unwrap().embedding_count, 0);
+    }
+
+    #[test]
+    fn test_ann_index_persists_and_rebuilds() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("knowledge.db");
+        let index_path = dir.path().join("knowledge.hnsw");
+        let query = embedding(7, 384);
+
+        let expected = {
+            let vault = KnowledgeVault::open(&db_path, 384).unwrap();
+            populate(&vault, 10, 5);
+            vault.search(&query, 5).unwrap()
+        };
+        assert!(index_path.exists());
+
+        // Reopen loads the saved index
+        {
+            let vault = KnowledgeVault::open(&db_path, 384).unwrap();
+            assert_eq!(vault.ann.borrow().len(), 50);
+            let results = vault.search(&query, 5).unwrap();
+            assert_eq!(
+                results.iter().map(|r| &r.chunk_id).collect::<Vec<_>>(),
+                expected.iter().map(|r| &r.chunk_id).collect::<Vec<_>>()
+            );
+        }
+
+        // Missing index file is rebuilt from SQLite
+        std::fs::remove_file(&index_path).unwrap();
+        {
+            let vault = KnowledgeVault::open(&db_path, 384).unwrap();
+            assert_eq!(vault.ann.borrow().len(), 50);
+        }
+        assert!(index_path.exists());
+
+        // A write that never reached the index file (crash) forces a rebuild
+        {
+            let vault = KnowledgeVault::open(&db_path, 384).unwrap();
+            let doc = vault.get_document_by_path("/test/0.txt").unwrap().unwrap();
+            vault.delete_document(&doc.id).unwrap();
+            std::mem::forget(vault);
+        }
+        let vault = KnowledgeVault::open(&db_path, 384).unwrap();
+        assert_eq!(vault.ann.borrow().len(), 45);
+        assert_eq!(vault.rebuild_ann_index().unwrap(), 45);
+    }
+
+    #[test]
+    fn test_ann_index_sees_other_connections() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("knowledge.db");
+
+        let reader = KnowledgeVault::open(&db_path, 384).unwrap();
+        {
+            let writer = KnowledgeVault::open(&db_path, 384).unwrap();
+            populate(&writer, 2, 3);
+        }
+
+        let results = reader.search(&embedding(0, 384), 10).unwrap();
+        assert_eq!(results.len(), 6);
+        assert_eq!(results[0].chunk_id, "chunk_0_0");
+    }
+
+    #[test]
+    fn test_mixed_models_counted_at_open_and_migration() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("knowledge.db");
+        let model = EmbeddingModel {
+            name: "test".to_string(),
+            dimensions: 384,
+            version: "1".to_string(),
+        };
+
+        // A vault from before the count was kept, with a stray model's embedding
+        {
+            let vault = KnowledgeVault::open(&db_path, 384).unwrap();
+            populate(&vault, 1, 3);
+            vault
+                .conn
+                .execute_batch(
+                    "UPDATE embeddings SET model_id = 99 WHERE chunk_id = 'chunk_0_0';
+                     DELETE FROM vault_meta WHERE key = 'embedding_model_count';",
+                )
+                .unwrap();
+        }
+
+        let mut vault = KnowledgeVault::open(&db_path, 384).unwrap();
+        assert_eq!(vault.meta_value(EMBEDDING_MODEL_COUNT_KEY).unwrap(), Some(2));
+        assert!(matches!(
+            vault.use_embedding_model(&model),
+            Err(KnowledgeError::ModelMismatch(_))
+        ));
+
+        let target = EmbeddingModel {
+            version: "2".to_string(),
+            ..model
+        };
+        vault.begin_migration(&target).unwrap();
+        for (chunk_id, _) in vault.chunks_to_migrate(10).unwrap() {
+            vault.stage_embedding(&chunk_id, &embedding(1, 384)).unwrap();
+        }
+        vault.finish_migration().unwrap();
+        assert_eq!(vault.meta_value(EMBEDDING_MODEL_COUNT_KEY).unwrap(), Some(1));
+        vault.use_embedding_model(&target).unwrap();
+    }
+
+    #[test]
+    fn test_keyword_index_follows_chunks() {
+        let vault = KnowledgeVault::in_memory().unwrap();
+        let doc_id = vault.add_document("/test/fts.txt", "x", "text").unwrap();
+        vault
+            .insert_chunk("chunk_001", &doc_id, 0, "quantum entanglement", 0, 20, 2)
+            .unwrap();
+
+        let hits = vault.keyword_search("\"quantum\"", 10, None, None).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, "chunk_001");
+        assert!(hits[0].1 > 0.0);
+
+        let types = ["code".to_string()];
+        assert!(vault
+            .keyword_search("\"quantum\"", 10, Some(&types), None)
+            .unwrap()
+            .is_empty());
+
+        vault.delete_document(&doc_id).unwrap();
+        assert!(vault.keyword_search("\"quantum\"", 10, None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_keyword_index_backfills_existing_vault() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("knowledge.db");
+
+        {
+            let vault = KnowledgeVault::open(&db_path, 384).unwrap();
+            let doc_id = vault.add_document("/test/old.txt", "x", "text").unwrap();
+            vault
+                .insert_chunk("chunk_001", &doc_id, 0, "legacy content", 0, 14, 2)
+                .unwrap();
+            // Simulate a vault created before the keyword index existed
+            vault
+                .conn
+                .execute_batch(
+                    "DROP TRIGGER chunks_fts_insert; DROP TRIGGER chunks_fts_delete;
+                     DROP TRIGGER chunks_fts_update; DROP TABLE chunks_fts;",
+                )
+                .unwrap();
+        }
+
+        let vault = KnowledgeVault::open(&db_path, 384).unwrap();
+        let hits = vault.keyword_search("\"legacy\"", 10, None, None).unwrap();
+        assert_eq!(hits.len(), 1);
+    }
+
+    #[test]
+    fn test_chunk_hashes_and_partial_delete() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("knowledge.db");
+
+        let doc_id;
+        {
+            let vault = KnowledgeVault::open(&db_path, 384).unwrap();
+            doc_id = vault.add_document("/test/hash.txt", "x", "text").unwrap();
+            vault
+                .insert_chunk("chunk_001", &doc_id, 0, "kept", 0, 4, 1)
+                .unwrap();
+            // Simulate a vault created before per-chunk hashes existed
+            vault
+                .conn
+                .execute("ALTER TABLE chunks DROP COLUMN content_hash", [])
+                .unwrap();
+        }
+
+        let vault = KnowledgeVault::open(&db_path, 384).unwrap();
+        let legacy = vault.get_chunk("chunk_001").unwrap().unwrap();
+        assert_eq!(legacy.content_hash, hash_content("kept"));
+
+        vault
+            .insert_chunk("chunk_002", &doc_id, 1, "dropped", 4, 11, 2)
+            .unwrap();
+        vault.insert_embedding("chunk_002", &[0.1f32; 384]).unwrap();
+        assert_eq!(vault.get_chunks(&doc_id).unwrap()[1].content_hash, hash_content("dropped"));
+
+        vault.delete_chunks(&["chunk_002".to_string()]).unwrap();
+        vault.update_chunk_position("chunk_001", 3, 10, 14, 1).unwrap();
+        let chunks = vault.get_chunks(&doc_id).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!((chunks[0].chunk_index, chunks[0].start_offset), (3, 10));
+        assert!(vault.get_embedding("chunk_002").unwrap().is_none());
+        assert!(vault.nearest_chunks(&[0.1f32; 384], 5).unwrap().is_empty());
+        assert!(vault.update_chunk_position("chunk_002", 0, 0, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_write_document_is_atomic() {
+        let dir = tempdir().unwrap();
+        let vault = KnowledgeVault::open(&dir.path().join("knowledge.db"), 384).unwrap();
+        let doc_id = vault.add_document("/test/atomic.txt", "old", "text").unwrap();
+        vault.insert_chunk("chunk_old", &doc_id, 0, "old", 0, 3, 1).unwrap();
+        vault.insert_embedding("chunk_old", &[0.1f32; 384]).unwrap();
+
+        let mut doc = vault.get_document(&doc_id).unwrap().unwrap();
+        doc.title = "New title".to_string();
+        let chunk = |content: &str| Chunk {
+            content: content.to_string(),
+            start_offset: 0,
+            end_offset: content.len() as u64,
+            token_count: 1,
+            index: 0,
+            location: ChunkLocation::default(),
+        };
+        let new_chunk = ChunkWrite::New {
+            id: "chunk_new".to_string(),
+            chunk: chunk("new"),
+            embedding: vec![0.2f32; 384],
+        };
+
+        // A kept chunk that no longer exists rolls back every write
+        let missing = ChunkWrite::Kept {
+            id: "chunk_gone".to_string(),
+            chunk: chunk("gone"),
+        };
+        let stale = ["chunk_old".to_string()];
+        assert!(vault
+            .write_document(&doc, &stale, &[new_chunk.clone(), missing])
+            .is_err());
+        assert_eq!(vault.get_document(&doc_id).unwrap().unwrap().title, "atomic.txt");
+        assert!(vault.get_chunk("chunk_new").unwrap().is_none());
+        assert!(vault.get_embedding("chunk_old").unwrap().is_some());
+
+        vault.write_document(&doc, &stale, &[new_chunk]).unwrap();
+        assert_eq!(vault.get_document(&doc_id).unwrap().unwrap().title, "New title");
+        let chunks = vault.get_chunks(&doc_id).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].id, "chunk_new");
+        let nearest = vault.nearest_chunks(&[0.2f32; 384], 5).unwrap();
+        assert_eq!(nearest[0].0, "chunk_new");
+    }
+
+    #[test]
+    fn test_tags_and_document_filter() {
+        let vault = KnowledgeVault::in_memory().unwrap();
+        let design = vault
+            .add_document("/docs/design/api.md", "x", "markdown")
+            .unwrap();
+        let notes = vault.add_document("/docs/notes.txt", "y", "text").unwrap();
+
+        let tags = vec!["Design ".to_string(), "review".to_string(), " ".to_string()];
+        assert_eq!(vault.add_tags(&design, &tags).unwrap(), 2);
+        assert_eq!(vault.add_tags(&design, &tags).unwrap(), 0);
+        vault.add_tags(&notes, &["review".to_string()]).unwrap();
+        assert!(vault.add_tags("missing", &tags).is_err());
+        assert_eq!(
+            vault.document_tags(&design).unwrap(),
+            vec!["design", "review"]
+        );
+        assert_eq!(
+            vault.tag_counts().unwrap(),
+            vec![("design".to_string(), 1), ("review".to_string(), 2)]
+        );
+
+        let ids = |doc_types: Option<&[String]>, filter: &SearchFilter| {
+            let mut ids: Vec<String> = vault
+                .filter_documents(doc_types, filter)
+                .unwrap()
+                .into_iter()
+                .map(|d| d.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        let mut both = vec![design.clone(), notes.clone()];
+        both.sort();
+
+        let tagged = SearchFilter {
+            tags: vec!["review".to_string(), "DESIGN".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(ids(None, &tagged), vec![design.clone()]);
+        let repeated = SearchFilter {
+            tags: vec!["design".to_string(), "Design".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(ids(None, &repeated), vec![design.clone()]);
+        let globbed = SearchFilter {
+            path_globs: vec!["/docs/design/*".to_string(), "*.txt".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(ids(None, &globbed), both);
+        assert_eq!(
+            ids(Some(&["text".to_string()]), &globbed),
+            vec![notes.clone()]
+        );
+        let future = SearchFilter {
+            indexed: crate::filter::DateRange {
+                after: Some(chrono::Utc::now() + chrono::Duration::days(1)),
+                before: None,
+            },
+            ..Default::default()
+        };
+        assert!(ids(None, &future).is_empty());
+
+        assert_eq!(
+            vault.remove_tags(&notes, &["Review".to_string()]).unwrap(),
+            1
+        );
+        vault.delete_document(&design).unwrap();
+        assert!(vault.tag_counts().unwrap().is_empty());
+    }
 }
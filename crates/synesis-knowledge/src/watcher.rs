@@ -1,8 +1,22 @@
 //! File Watcher
 //!
 //! Watches directories for changes and automatically indexes new/modified files.
+//!
+//! Deleted files are removed from the vault ([`IndexCommand::RemoveByPath`]) and
+//! renamed files are moved ([`IndexCommand::MovePath`]), so stale chunks never
+//! answer queries for files that no longer exist.
+//!
+//! ## Renames
+//!
+//! Backends report renames differently. On Linux, inotify emits a `From`
+//! event, a `To` event and then a `Both` event carrying both paths. `From`
+//! is treated as a tentative deletion that only fires after the debounce
+//! delay; a matching `Both` cancels it and moves the document instead. A
+//! file moved out of the watched tree (only `From`) is deleted, and one
+//! moved in (only `To`) is indexed as new.
 
 use hex;
+use notify::event::{ModifyKind, RenameMode};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
@@ -43,6 +57,10 @@ impl Default for WatchConfig {
                 "json".to_string(),
                 "yaml".to_string(),
                 "toml".to_string(),
+                "html".to_string(),
+                "htm".to_string(),
+                "pdf".to_string(),
+                "docx".to_string(),
             ]),
             exclude_patterns: vec![
                 ".git".to_string(),
@@ -226,46 +244,70 @@ impl FileWatcher {
 
         // Spawn event processing task with checksums
         let config_clone = self.config.clone();
+        let mut checksums = self.checksums.clone();
         tokio::spawn(async move {
             let mut pending: HashSet<PathBuf> = HashSet::new();
+            let mut pending_removals: HashSet<PathBuf> = HashSet::new();
             let debounce = config_clone.debounce;
-            let mut checksums: HashMap<PathBuf, FileChecksum> = HashMap::new();
+            let mut last_change = std::time::Instant::now();
 
             loop {
-                // Process notify events
-                match notify_rx.try_recv() {
-                    Ok(event) => {
-                        for path in event.paths {
-                            if !should_process_path(&path, &config_clone) {
-                                continue;
-                            }
+                // Drain all available notify events
+                loop {
+                    let event = match notify_rx.try_recv() {
+                        Ok(event) => event,
+                        Err(std::sync::mpsc::TryRecvError::Empty) => break,
+                        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                            info!("Notify channel disconnected, shutting down watcher");
+                            return;
+                        },
+                    };
+
+                    for change in classify_event(&event, &config_clone) {
+                        last_change = std::time::Instant::now();
+                        match change {
+                            FileChange::Modified(path) => {
+                                pending_removals.remove(&path);
+                                pending.insert(path);
+                            },
+                            FileChange::Deleted(path) => {
+                                // Wait for the debounce: a rename may still claim it
+                                pending.remove(&path);
+                                pending_removals.insert(path);
+                            },
+                            FileChange::Renamed(from, to) => {
+                                pending.remove(&from);
+                                pending_removals.remove(&from);
+                                pending_removals.remove(&to);
+                                if let Some(cached) = checksums.remove(&from) {
+                                    checksums.insert(to.clone(), cached);
+                                }
 
-                            match event.kind {
-                                EventKind::Create(_) | EventKind::Modify(_) => {
-                                    pending.insert(path);
-                                },
-                                EventKind::Remove(_) => {
-                                    // Remove from checksums
-                                    checksums.remove(&path);
-                                    // We don't send delete commands for now
-                                    // as we don't have a DeleteFile command
-                                },
-                                _ => {},
-                            }
+                                if let Err(e) = command_tx.try_send(IndexCommand::MovePath {
+                                    from: from.clone(),
+                                    to: to.clone(),
+                                }) {
+                                    error!("Failed to send move command for {:?}: {}", from, e);
+                                } else {
+                                    debug!("Sent move command: {:?} -> {:?}", from, to);
+                                }
+                            },
                         }
-                    },
-                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                        info!("Notify channel disconnected, shutting down watcher");
-                        break;
-                    },
-                    Err(_) => {
-                        // No events, continue
                     }
                 }
 
-                // Debounce: process pending after delay
-                if !pending.is_empty() {
-                    tokio::time::sleep(debounce).await;
+                // Debounce: process pending once changes have settled
+                let has_pending = !pending.is_empty() || !pending_removals.is_empty();
+                if has_pending && last_change.elapsed() >= debounce {
+                    for path in pending_removals.drain() {
+                        checksums.remove(&path);
+
+                        if let Err(e) = command_tx.try_send(IndexCommand::RemoveByPath(path.clone())) {
+                            error!("Failed to send remove command for {:?}: {}", path, e);
+                        } else {
+                            debug!("Sent remove command for deleted file: {:?}", path);
+                        }
+                    }
 
                     for path in pending.drain() {
                         // Compute checksum directly (handles file not found)
@@ -372,8 +414,47 @@ impl FileWatcher {
     }
 }
 
-/// Check if a path should be processed based on config
-fn should_process_path(path: &Path, config: &WatchConfig) -> bool {
+/// Translate a notify event into file changes
+///
+/// Deletions and the old side of renames can't be checked on disk, so they
+/// only need to pass the extension and exclude filters.
+fn classify_event(event: &Event, config: &WatchConfig) -> Vec<FileChange> {
+    let modified = |path: &PathBuf| {
+        should_process_path(path, config).then(|| FileChange::Modified(path.clone()))
+    };
+    let deleted =
+        |path: &PathBuf| matches_filters(path, config).then(|| FileChange::Deleted(path.clone()));
+
+    match event.kind {
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+            let (from, to) = (&event.paths[0], &event.paths[1]);
+            match (matches_filters(from, config), should_process_path(to, config)) {
+                (true, true) => vec![FileChange::Renamed(from.clone(), to.clone())],
+                (true, false) => vec![FileChange::Deleted(from.clone())],
+                (false, true) => vec![FileChange::Modified(to.clone())],
+                (false, false) => vec![],
+            }
+        },
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) | EventKind::Remove(_) => {
+            event.paths.iter().filter_map(deleted).collect()
+        },
+        // Backends that can't tell which side of a rename a path is on
+        EventKind::Modify(ModifyKind::Name(RenameMode::Any | RenameMode::Other)) => event
+            .paths
+            .iter()
+            .filter_map(|path| if path.exists() { modified(path) } else { deleted(path) })
+            .collect(),
+        EventKind::Create(_) | EventKind::Modify(_) => {
+            event.paths.iter().filter_map(modified).collect()
+        },
+        _ => vec![],
+    }
+}
+
+/// Check if a path passes the exclude and extension filters
+///
+/// Does not touch the filesystem, so it works for deleted paths.
+fn matches_filters(path: &Path, config: &WatchConfig) -> bool {
     // Check exclude patterns
     let path_str = path.to_string_lossy();
     for pattern in &config.exclude_patterns {
@@ -393,6 +474,15 @@ fn should_process_path(path: &Path, config: &WatchConfig) -> bool {
         }
     }
 
+    true
+}
+
+/// Check if a path should be processed based on config
+fn should_process_path(path: &Path, config: &WatchConfig) -> bool {
+    if !matches_filters(path, config) {
+        return false;
+    }
+
     // Must be a file
     path.is_file()
 }
@@ -528,6 +618,126 @@ mod tests {
         assert!(extensions.contains(&"rs".to_string()));
         assert!(extensions.contains(&"py".to_string()));
         assert!(extensions.contains(&"md".to_string()));
+        assert!(extensions.contains(&"pdf".to_string()));
+    }
+
+    #[test]
+    fn test_classify_event() {
+        use notify::event::{CreateKind, RemoveKind};
+
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().join("notes.md");
+        std::fs::write(&existing, "notes").unwrap();
+        let gone = dir.path().join("gone.md");
+        let config = WatchConfig::default();
+
+        let event = |kind, paths: Vec<&PathBuf>| Event {
+            kind,
+            paths: paths.into_iter().cloned().collect(),
+            attrs: Default::default(),
+        };
+
+        let changes = classify_event(
+            &event(EventKind::Create(CreateKind::File), vec![&existing]),
+            &config,
+        );
+        assert!(matches!(changes.as_slice(), [FileChange::Modified(p)] if p == &existing));
+
+        let changes = classify_event(
+            &event(EventKind::Remove(RemoveKind::File), vec![&gone]),
+            &config,
+        );
+        assert!(matches!(changes.as_slice(), [FileChange::Deleted(p)] if p == &gone));
+
+        let changes = classify_event(
+            &event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                vec![&gone, &existing],
+            ),
+            &config,
+        );
+        assert!(matches!(
+            changes.as_slice(),
+            [FileChange::Renamed(from, to)] if from == &gone && to == &existing
+        ));
+
+        // Renamed to an excluded extension: the old document goes away
+        let backup = dir.path().join("notes.md.bak");
+        std::fs::write(&backup, "notes").unwrap();
+        let changes = classify_event(
+            &event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                vec![&gone, &backup],
+            ),
+            &config,
+        );
+        assert!(matches!(changes.as_slice(), [FileChange::Deleted(p)] if p == &gone));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_watcher_moves_and_removes_documents() {
+        use crate::embeddings::PlaceholderEmbedder;
+        use crate::indexer::{DocumentIndexer, IndexerConfig};
+        use crate::vault::KnowledgeVault;
+        use std::sync::Arc;
+        use tokio::sync::Mutex;
+
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        std::fs::create_dir(&docs).unwrap();
+        let original = docs.join("plan.md");
+        let renamed = docs.join("roadmap.md");
+        std::fs::write(&original, "# Plan\n\nShip the knowledge vault.").unwrap();
+
+        let vault = Arc::new(Mutex::new(
+            KnowledgeVault::open(&dir.path().join("knowledge.db"), 384).unwrap(),
+        ));
+        let embedder = Arc::new(Mutex::new(PlaceholderEmbedder::new(384)));
+        let (indexer, _handle, mut results) =
+            DocumentIndexer::with_results(vault.clone(), embedder, IndexerConfig::default());
+
+        async fn next(
+            results: &mut mpsc::UnboundedReceiver<crate::indexer::IndexOutcome>,
+        ) -> crate::indexer::IndexResult {
+            tokio::time::timeout(Duration::from_secs(10), results.recv())
+                .await
+                .expect("indexer outcome")
+                .expect("results channel open")
+                .result
+                .unwrap()
+        }
+
+        indexer.index_file(original.clone()).await.unwrap();
+        let indexed = next(&mut results).await;
+
+        let config = WatchConfig {
+            directories: vec![docs.clone()],
+            debounce: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let mut watcher = FileWatcher::with_auto_index(config, indexer.command_sender()).unwrap();
+        watcher.start().await.unwrap();
+
+        std::fs::rename(&original, &renamed).unwrap();
+        let moved = next(&mut results).await;
+        assert!(moved.moved);
+        assert_eq!(moved.document_id, indexed.document_id);
+        {
+            let vault = vault.lock().await;
+            assert!(vault.get_document_by_path(&original.to_string_lossy()).unwrap().is_none());
+            assert!(vault.get_document_by_path(&renamed.to_string_lossy()).unwrap().is_some());
+            assert_eq!(vault.stats().unwrap().embedding_count, u64::from(indexed.chunk_count));
+        }
+
+        std::fs::remove_file(&renamed).unwrap();
+        let removed = next(&mut results).await;
+        assert!(removed.removed);
+
+        let stats = vault.lock().await.stats().unwrap();
+        assert_eq!(stats.document_count, 0);
+        assert_eq!(stats.chunk_count, 0);
+
+        watcher.stop();
     }
 
     #[test]
@@ -0,0 +1,579 @@
+//! Hardware Benchmarks
+//!
+//! The built-in profiles pick models from RAM and VRAM alone, which says
+//! little about how fast a model actually runs on a given CPU or GPU. This
+//! module runs a short, fixed workload against each candidate model on this
+//! machine and tunes a [`HardwareManifest`] from the measurements:
+//!
+//! 1. [`candidates`] lists the installed models that any built-in profile
+//!    recommends, with the largest context and GPU offload that fit the
+//!    [`MemoryBudget`].
+//! 2. [`run_benchmark`] loads each one, generates a reply to a standard
+//!    prompt and embeds a few sentences, recording load time,
+//!    time-to-first-token, tokens per second and peak memory.
+//! 3. [`tune_manifest`] picks, per agent, the largest model that meets the
+//!    [`BenchTargets`], and records its context size and GPU layers.
+//!
+//! The result is installed as the [tuned](crate::manifest::TUNED_MANIFEST_NAME)
+//! manifest, which [`HardwareManifest::detect_and_load`] prefers.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+use tracing::{debug, info};
+
+use crate::gguf::GgufMetadata;
+use crate::hardware::HardwareInfo;
+use crate::inference::{InferenceRequest, ModelInstance, TokenCallback};
+use crate::manifest::{
+    profiles, AgentRole, HardwareManifest, ModelRecommendation, TUNED_MANIFEST_NAME,
+};
+use crate::pool::{MemoryBudget, MemoryEstimate};
+use crate::ModelResult;
+
+/// Largest context size tuning will choose
+const MAX_TUNED_CONTEXT: u32 = 32768;
+
+/// How often process memory is sampled while a benchmark runs
+const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_millis(20);
+
+/// The standard workload run against every candidate
+#[derive(Debug, Clone)]
+pub struct BenchWorkload {
+    /// Prompt to generate a reply for
+    pub prompt: String,
+    /// Tokens to generate
+    pub max_tokens: u32,
+    /// Texts to embed
+    pub embed_texts: Vec<String>,
+}
+
+impl Default for BenchWorkload {
+    fn default() -> Self {
+        Self {
+            prompt: "Explain in three sentences why the sky is blue, then list two \
+                     everyday examples of the same effect."
+                .to_string(),
+            max_tokens: 128,
+            embed_texts: vec![
+                "Rayleigh scattering affects short wavelengths the most.".to_string(),
+                "The quick brown fox jumps over the lazy dog.".to_string(),
+                "fn main() { println!(\"hello\"); }".to_string(),
+            ],
+        }
+    }
+}
+
+/// A model to benchmark, with the settings to load it with
+#[derive(Debug, Clone)]
+pub struct BenchCandidate {
+    /// Model as recommended by a built-in profile
+    pub recommendation: ModelRecommendation,
+    /// Agents the model is recommended for
+    pub roles: Vec<AgentRole>,
+    /// Installed model file
+    pub path: PathBuf,
+    /// Context size to load with
+    pub context_size: u32,
+    /// GPU layers to offload
+    pub gpu_layers: u32,
+}
+
+impl BenchCandidate {
+    /// Whether the model only embeds, and so skips generation
+    pub fn is_embedding(&self) -> bool {
+        self.roles == [AgentRole::Embeddings]
+    }
+}
+
+/// Measurements for one candidate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchResult {
+    /// Model that was benchmarked
+    pub recommendation: ModelRecommendation,
+    /// Agents the model is recommended for
+    pub roles: Vec<AgentRole>,
+    /// Context size it was loaded with
+    pub context_size: u32,
+    /// GPU layers it was loaded with
+    pub gpu_layers: u32,
+    /// Time to load the model
+    pub load_ms: u64,
+    /// Time from the start of generation to the first token (0 for
+    /// embedding models)
+    pub time_to_first_token_ms: u64,
+    /// Generation speed (0 for embedding models)
+    pub tokens_per_second: f32,
+    /// Average time to embed one text
+    pub embed_ms: u64,
+    /// Peak memory: the larger of the estimated footprint and the measured
+    /// growth of this process
+    pub peak_memory_bytes: u64,
+}
+
+/// Speed a model must reach to be chosen for an interactive agent
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BenchTargets {
+    /// Minimum generation speed
+    pub min_tokens_per_second: f32,
+    /// Maximum time-to-first-token
+    pub max_time_to_first_token_ms: u64,
+}
+
+impl Default for BenchTargets {
+    fn default() -> Self {
+        Self {
+            min_tokens_per_second: 10.0,
+            max_time_to_first_token_ms: 2000,
+        }
+    }
+}
+
+impl BenchTargets {
+    /// Whether a result is fast enough
+    pub fn met_by(&self, result: &BenchResult) -> bool {
+        result.tokens_per_second >= self.min_tokens_per_second
+            && result.time_to_first_token_ms <= self.max_time_to_first_token_ms
+    }
+}
+
+/// Installed models worth benchmarking
+///
+/// Every GGUF model a built-in profile recommends, for any agent, that is
+/// present in `models_dir`. Settings come from [`plan_settings`], starting
+/// from `base`'s context size.
+pub fn candidates(
+    models_dir: &Path,
+    base: &HardwareManifest,
+    budget: MemoryBudget,
+) -> Vec<BenchCandidate> {
+    let mut by_file: Vec<BenchCandidate> = Vec::new();
+
+    for profile in profiles::all() {
+        for role in AgentRole::ALL {
+            let rec = profile.recommendations.get(role);
+            if !rec.filename.ends_with(".gguf") {
+                continue;
+            }
+
+            if let Some(existing) = by_file
+                .iter_mut()
+                .find(|c| c.recommendation.filename == rec.filename)
+            {
+                if !existing.roles.contains(&role) {
+                    existing.roles.push(role);
+                }
+                continue;
+            }
+
+            let path = models_dir.join(&rec.filename);
+            let metadata = match GgufMetadata::read(&path) {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    debug!("Skipping {}: {}", rec.filename, e);
+                    continue;
+                },
+            };
+            let (context_size, gpu_layers) = plan_settings(&metadata, base.context_size, budget);
+
+            by_file.push(BenchCandidate {
+                recommendation: ModelRecommendation {
+                    context_size: None,
+                    gpu_layers: None,
+                    ..rec.clone()
+                },
+                roles: vec![role],
+                path,
+                context_size,
+                gpu_layers,
+            });
+        }
+    }
+
+    by_file
+}
+
+/// Context size and GPU layers to run a model with
+///
+/// Offloads as many layers as fit in VRAM at `base_context`, then doubles
+/// the context while the model still fits, up to its trained context (or
+/// 32K). The context never drops below `base_context`; a model that does
+/// not fit at all is still benchmarked, and rejected by [`tune_manifest`].
+pub fn plan_settings(
+    metadata: &GgufMetadata,
+    base_context: u32,
+    budget: MemoryBudget,
+) -> (u32, u32) {
+    let layers = metadata.block_count.unwrap_or(0);
+
+    let mut gpu_layers = if budget.vram_bytes > 0 { layers } else { 0 };
+    while gpu_layers > 0
+        && !budget.allows(MemoryEstimate::for_model(
+            metadata,
+            base_context,
+            gpu_layers,
+        ))
+    {
+        gpu_layers -= 1;
+    }
+
+    let max_context = metadata
+        .context_length
+        .unwrap_or(base_context)
+        .min(MAX_TUNED_CONTEXT);
+    let mut context_size = base_context;
+    while context_size * 2 <= max_context
+        && budget.allows(MemoryEstimate::for_model(
+            metadata,
+            context_size * 2,
+            gpu_layers,
+        ))
+    {
+        context_size *= 2;
+    }
+
+    (context_size, gpu_layers)
+}
+
+/// Run the workload against one candidate
+///
+/// The model is unloaded again afterwards, so candidates can be run one
+/// after another.
+pub async fn run_benchmark(
+    candidate: &BenchCandidate,
+    workload: &BenchWorkload,
+) -> ModelResult<BenchResult> {
+    let name = &candidate.recommendation.model;
+    info!(
+        "Benchmarking {} (context {}, {} GPU layers)",
+        name, candidate.context_size, candidate.gpu_layers
+    );
+
+    let sampler = MemorySampler::start();
+    let mut instance = ModelInstance::new(name.clone(), candidate.path.clone())
+        .with_context_size(candidate.context_size)
+        .with_gpu_layers(candidate.gpu_layers);
+
+    let start = Instant::now();
+    instance.load().await?;
+    let load_ms = start.elapsed().as_millis() as u64;
+    let estimate = instance.estimate_memory().await?;
+
+    let (time_to_first_token_ms, tokens_per_second) = if candidate.is_embedding() {
+        (0, 0.0)
+    } else {
+        let first_token = Arc::new(OnceLock::new());
+        let callback: TokenCallback = {
+            let first_token = first_token.clone();
+            Arc::new(move |_: &str| {
+                first_token.get_or_init(Instant::now);
+            })
+        };
+
+        let start = Instant::now();
+        let response = instance
+            .infer(
+                InferenceRequest::new(workload.prompt.clone()).with_max_tokens(workload.max_tokens),
+                Some(callback),
+            )
+            .await?;
+        let ttft = first_token.get().map_or(response.generation_time_ms, |at| {
+            at.duration_since(start).as_millis() as u64
+        });
+        (ttft, response.tokens_per_second)
+    };
+
+    let start = Instant::now();
+    for text in &workload.embed_texts {
+        instance.embed(text).await?;
+    }
+    let embed_ms = start.elapsed().as_millis() as u64 / workload.embed_texts.len().max(1) as u64;
+
+    let measured = sampler.stop().await;
+    instance.unload();
+
+    let result = BenchResult {
+        recommendation: candidate.recommendation.clone(),
+        roles: candidate.roles.clone(),
+        context_size: instance.context_size(),
+        gpu_layers: candidate.gpu_layers,
+        load_ms,
+        time_to_first_token_ms,
+        tokens_per_second,
+        embed_ms,
+        peak_memory_bytes: estimate.total_bytes().max(measured),
+    };
+    debug!("{}: {:?}", name, result);
+    Ok(result)
+}
+
+/// Build a manifest for this machine from benchmark results
+///
+/// For each agent, among the results recommended for it whose peak memory
+/// fits the budget:
+/// - generating agents get the largest model that meets `targets`, or the
+///   fastest one if none does
+/// - embeddings get the fastest embedding model
+///
+/// Agents without a usable result keep `base`'s recommendation. Chosen
+/// models carry the context size and GPU layers they were benchmarked with.
+pub fn tune_manifest(
+    base: &HardwareManifest,
+    hardware: &HardwareInfo,
+    results: &[BenchResult],
+    targets: BenchTargets,
+) -> HardwareManifest {
+    let budget = MemoryBudget::from_hardware(hardware);
+    let mut manifest = base.clone();
+    let mut chosen: HashMap<AgentRole, &BenchResult> = HashMap::new();
+
+    for role in AgentRole::ALL {
+        let usable: Vec<&BenchResult> = results
+            .iter()
+            .filter(|r| r.roles.contains(&role))
+            .filter(|r| fits(r, budget))
+            .collect();
+
+        let best = if role == AgentRole::Embeddings {
+            usable.into_iter().min_by_key(|r| r.embed_ms)
+        } else {
+            let fast_enough = usable
+                .iter()
+                .copied()
+                .filter(|r| targets.met_by(r))
+                .max_by_key(|r| r.recommendation.size_bytes);
+            fast_enough.or_else(|| {
+                usable
+                    .into_iter()
+                    .max_by(|a, b| a.tokens_per_second.total_cmp(&b.tokens_per_second))
+            })
+        };
+
+        if let Some(result) = best {
+            *manifest.recommendations.get_mut(role) = ModelRecommendation {
+                context_size: Some(result.context_size),
+                gpu_layers: Some(result.gpu_layers),
+                ..result.recommendation.clone()
+            };
+            chosen.insert(role, result);
+        }
+    }
+
+    let offloads = chosen.values().any(|r| r.gpu_layers > 0);
+    manifest.name = TUNED_MANIFEST_NAME.to_string();
+    manifest.description = format!(
+        "Tuned from the {} profile by benchmarking {} models on this machine",
+        base.name,
+        results.len()
+    );
+    manifest.min_ram_bytes = hardware.ram_bytes;
+    manifest.min_vram_bytes = match &hardware.gpu {
+        Some(gpu) if offloads => gpu.vram_bytes,
+        _ => 0,
+    };
+    manifest
+}
+
+/// Whether a result's peak memory fits the budget
+fn fits(result: &BenchResult, budget: MemoryBudget) -> bool {
+    if result.gpu_layers > 0 || budget.unified_memory {
+        // The split between RAM and VRAM was already planned to fit
+        result.peak_memory_bytes <= budget.ram_bytes.saturating_add(budget.vram_bytes)
+    } else {
+        result.peak_memory_bytes <= budget.ram_bytes
+    }
+}
+
+/// Samples this process's resident memory in the background and keeps the
+/// peak growth over the starting value
+struct MemorySampler {
+    peak: Arc<AtomicU64>,
+    done: Arc<AtomicBool>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MemorySampler {
+    fn start() -> Self {
+        let peak = Arc::new(AtomicU64::new(0));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let task = {
+            let peak = peak.clone();
+            let done = done.clone();
+            tokio::spawn(async move {
+                let Ok(pid) = sysinfo::get_current_pid() else {
+                    return;
+                };
+                let mut sys = System::new();
+                let mut resident = || {
+                    sys.refresh_process(pid);
+                    sys.process(pid).map_or(0, |p| p.memory())
+                };
+
+                let baseline = resident();
+                while !done.load(Ordering::Relaxed) {
+                    peak.fetch_max(resident().saturating_sub(baseline), Ordering::Relaxed);
+                    tokio::time::sleep(MEMORY_SAMPLE_INTERVAL).await;
+                }
+            })
+        };
+
+        Self { peak, done, task }
+    }
+
+    /// Stop sampling and return the peak growth in bytes
+    async fn stop(self) -> u64 {
+        self.done.store(true, Ordering::Relaxed);
+        let _ = self.task.await;
+        self.peak.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gguf::fixture::tiny_llama;
+    use crate::hardware::{CpuInfo, DiskInfo, GpuInfo, GpuVendor, PlatformInfo};
+    use crate::Quantization;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn hardware(ram_gb: u64, vram_gb: Option<u64>) -> HardwareInfo {
+        HardwareInfo {
+            cpu: CpuInfo {
+                name: "Test".to_string(),
+                cores: 4,
+                threads: 8,
+                arch: "x86_64".to_string(),
+                features: vec![],
+            },
+            ram_bytes: ram_gb * GB,
+            ram_available_bytes: ram_gb * GB / 2,
+            gpu: vram_gb.map(|vram_gb| GpuInfo {
+                name: "Test GPU".to_string(),
+                vendor: GpuVendor::Nvidia,
+                vram_bytes: vram_gb * GB,
+                vram_available_bytes: vram_gb * GB,
+                cuda_version: None,
+                supported: true,
+            }),
+            disk: DiskInfo {
+                total_bytes: 100 * GB,
+                available_bytes: 50 * GB,
+                data_path: "/tmp".to_string(),
+            },
+            platform: PlatformInfo {
+                os: "linux".to_string(),
+                os_version: "6.0".to_string(),
+                arch: "x86_64".to_string(),
+            },
+        }
+    }
+
+    fn result(model: &str, size_gb: u64, roles: &[AgentRole], tps: f32) -> BenchResult {
+        BenchResult {
+            recommendation: ModelRecommendation {
+                model: model.to_string(),
+                quantization: Quantization::Q4,
+                repo_id: format!("test/{}", model),
+                filename: format!("{}.gguf", model),
+                size_bytes: size_gb * GB,
+                sha256: None,
+                context_size: None,
+                gpu_layers: None,
+            },
+            roles: roles.to_vec(),
+            context_size: 8192,
+            gpu_layers: 0,
+            load_ms: 500,
+            time_to_first_token_ms: 300,
+            tokens_per_second: tps,
+            embed_ms: 5,
+            peak_memory_bytes: size_gb * GB + GB,
+        }
+    }
+
+    #[test]
+    fn test_plan_settings() {
+        let bytes = tiny_llama().build();
+        let metadata = GgufMetadata::from_reader(&bytes[..], bytes.len() as u64).unwrap();
+
+        // Plenty of memory: full offload, context grows to the trained 2048
+        let (context, layers) = plan_settings(&metadata, 512, MemoryBudget::new(GB, GB));
+        assert_eq!((context, layers), (2048, 1));
+
+        // CPU only, and too little RAM for more than the base context
+        let weights = MemoryEstimate::for_model(&metadata, 512, 0);
+        let budget = MemoryBudget::new(weights.ram_bytes, 0);
+        assert_eq!(plan_settings(&metadata, 512, budget), (512, 0));
+    }
+
+    #[tokio::test]
+    async fn test_run_benchmark() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tiny.gguf");
+        tiny_llama().write(&path);
+
+        let candidate = BenchCandidate {
+            recommendation: result("tiny", 1, &[], 0.0).recommendation,
+            roles: vec![AgentRole::Pathos],
+            path,
+            context_size: 4096,
+            gpu_layers: 0,
+        };
+        let workload = BenchWorkload {
+            max_tokens: 8,
+            ..Default::default()
+        };
+
+        let result = run_benchmark(&candidate, &workload).await.unwrap();
+        // Capped at the trained context when loading
+        assert_eq!(result.context_size, 2048);
+        assert!(result.peak_memory_bytes > 0);
+        assert_eq!(result.roles, vec![AgentRole::Pathos]);
+    }
+
+    #[test]
+    fn test_tune_manifest() {
+        use AgentRole::*;
+
+        let base = profiles::standard();
+        let hw = hardware(32, None);
+        let results = vec![
+            result("small", 2, &[Pathos, Ethos], 40.0),
+            result("large", 8, &[Logos, Ethos], 12.0),
+            // Fast enough, but needs more than the 24 GB budget
+            result("huge", 40, &[Logos], 20.0),
+        ];
+
+        let tuned = tune_manifest(&base, &hw, &results, BenchTargets::default());
+        assert_eq!(tuned.name, TUNED_MANIFEST_NAME);
+        assert!(tuned.is_compatible(&hw));
+        assert_eq!(tuned.min_vram_bytes, 0);
+        assert_eq!(tuned.recommendations.pathos.model, "small");
+        assert_eq!(tuned.recommendations.logos.model, "large");
+        // Largest model meeting the targets
+        assert_eq!(tuned.recommendations.ethos.model, "large");
+        assert_eq!(tuned.context_size_for(Logos), 8192);
+        // Nothing benchmarked for embeddings
+        assert_eq!(
+            tuned.recommendations.embeddings.model,
+            base.recommendations.embeddings.model
+        );
+        assert_eq!(tuned.context_size_for(Embeddings), base.context_size);
+        tuned.validate().unwrap();
+
+        // With stricter targets, the fastest model is chosen instead
+        let strict = BenchTargets {
+            min_tokens_per_second: 50.0,
+            ..Default::default()
+        };
+        let tuned = tune_manifest(&base, &hw, &results, strict);
+        assert_eq!(tuned.recommendations.ethos.model, "small");
+    }
+}
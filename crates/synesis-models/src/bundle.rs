@@ -0,0 +1,752 @@
+//! Offline Model Bundles
+//!
+//! Moves installed models between machines without network access. A
+//! bundle is a plain tar archive:
+//!
+//! ```text
+//! bundle.json          manifest: lock entries, registry entries and an
+//!                      optional hardware manifest
+//! models/<filename>    one GGUF file per lock entry
+//! ```
+//!
+//! `bundle.json` always comes first, so an import knows every file's size
+//! and checksum before it copies anything. GGUF weights barely compress,
+//! so the archive is not compressed.
+//!
+//! # Import
+//!
+//! Every file is checked against the SHA256 recorded in the bundle before
+//! it is moved into place and added to `models.lock`, after which
+//! [`ModelRegistry::open`](crate::ModelRegistry::open) sees the models as
+//! installed.
+//!
+//! Imports are resumable: files are copied to `<name>.part` first, and an
+//! interrupted import started again continues each partial file where it
+//! stopped and skips files that are already installed and verified. File
+//! data is read straight from its offset in the archive, so resuming does
+//! not re-read what was already copied.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, instrument, warn};
+
+use crate::lockfile::{LockEntry, ModelLock, LOCKFILE_NAME};
+use crate::manifest::HardwareManifest;
+use crate::registry::{ModelInfo, ModelRegistry, ModelStatus};
+use crate::{ModelError, ModelResult};
+
+/// Name of the manifest inside a bundle
+pub const BUNDLE_MANIFEST_NAME: &str = "bundle.json";
+
+/// Directory holding model files inside a bundle
+const BUNDLE_MODELS_DIR: &str = "models";
+
+/// Current bundle format version
+const BUNDLE_VERSION: u32 = 1;
+
+/// Buffer size for copying model data (1 MB)
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Partial import file extension (shared with the downloader)
+const PART_EXTENSION: &str = "part";
+
+/// Bundle progress callback
+pub type BundleProgressCallback = Arc<dyn Fn(BundleProgress) + Send + Sync>;
+
+/// Progress of a bundle export or import
+#[derive(Debug, Clone)]
+pub struct BundleProgress {
+    /// Model file being processed
+    pub filename: String,
+    /// Bytes of this file processed so far
+    pub bytes: u64,
+    /// Total size of this file
+    pub total: u64,
+    /// Current phase
+    pub phase: BundlePhase,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundlePhase {
+    /// Checking a file against its checksum
+    Verifying,
+    /// Copying file data
+    Copying,
+    /// Already installed and verified, nothing to copy
+    Skipped,
+    /// File done
+    Complete,
+}
+
+/// A model file in a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledModel {
+    /// Lock entry recorded on the exporting machine
+    pub lock: LockEntry,
+    /// Registry entry, if the exporting registry knew the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<ModelInfo>,
+}
+
+/// Contents of `bundle.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Format version
+    pub version: u32,
+    /// When the bundle was created
+    pub created_at: DateTime<Utc>,
+    /// Model files, in archive order
+    pub models: Vec<BundledModel>,
+    /// Hardware manifest to install alongside the models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hardware_manifest: Option<HardwareManifest>,
+}
+
+impl BundleManifest {
+    /// Total size of the model files in bytes
+    pub fn total_bytes(&self) -> u64 {
+        self.models.iter().map(|m| m.lock.size_bytes).sum()
+    }
+
+    fn find(&self, filename: &str) -> Option<&BundledModel> {
+        self.models.iter().find(|m| m.lock.filename == filename)
+    }
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone)]
+pub struct ImportReport {
+    /// The bundle's manifest
+    pub manifest: BundleManifest,
+    /// Files copied (fully or resumed)
+    pub imported: Vec<String>,
+    /// Files already installed and verified
+    pub skipped: Vec<String>,
+}
+
+/// Package installed models into a bundle
+///
+/// `ids` are model identifiers as recorded in `models.lock`; every locked
+/// file of each model is included. Files are checked against their lock
+/// entries first, so a corrupt install is never exported.
+///
+/// # Errors
+/// Returns [`ModelError::NotFound`] if a model has no lock entry, and
+/// [`ModelError::ChecksumMismatch`] if an installed file no longer matches.
+#[instrument(skip(hardware_manifest, progress_callback))]
+pub async fn export_bundle(
+    models_dir: &Path,
+    ids: &[String],
+    hardware_manifest: Option<HardwareManifest>,
+    out: &Path,
+    progress_callback: Option<BundleProgressCallback>,
+) -> ModelResult<BundleManifest> {
+    let models_dir = models_dir.to_path_buf();
+    let ids = ids.to_vec();
+    let out = out.to_path_buf();
+
+    tokio::task::spawn_blocking(move || {
+        export_blocking(
+            &models_dir,
+            &ids,
+            hardware_manifest,
+            &out,
+            progress_callback,
+        )
+    })
+    .await
+    .map_err(|e| ModelError::Internal(format!("Bundle export task failed: {}", e)))?
+}
+
+/// Install the models in a bundle
+///
+/// Copies each file into `models_dir`, verifies its checksum and records
+/// it in `models.lock`. An interrupted import can be resumed by running it
+/// again. Installing the bundled hardware manifest is left to the caller.
+///
+/// # Errors
+/// Returns [`ModelError::InvalidBundle`] for a malformed or truncated
+/// bundle and [`ModelError::ChecksumMismatch`] if a file is corrupt.
+#[instrument(skip(progress_callback))]
+pub async fn import_bundle(
+    bundle: &Path,
+    models_dir: &Path,
+    progress_callback: Option<BundleProgressCallback>,
+) -> ModelResult<ImportReport> {
+    let bundle = bundle.to_path_buf();
+    let models_dir = models_dir.to_path_buf();
+
+    tokio::task::spawn_blocking(move || import_blocking(&bundle, &models_dir, progress_callback))
+        .await
+        .map_err(|e| ModelError::Internal(format!("Bundle import task failed: {}", e)))?
+}
+
+/// Read the manifest of a bundle without importing it
+pub fn read_bundle_manifest(bundle: &Path) -> ModelResult<BundleManifest> {
+    let mut archive = tar::Archive::new(File::open(bundle)?);
+    let mut entries = archive.entries_with_seek()?;
+    let mut first = entries.next().ok_or_else(|| invalid("empty archive"))??;
+    if first.path()?.as_ref() != Path::new(BUNDLE_MANIFEST_NAME) {
+        return Err(invalid(format!(
+            "{} must be the first entry",
+            BUNDLE_MANIFEST_NAME
+        )));
+    }
+    parse_manifest(&mut first)
+}
+
+fn export_blocking(
+    models_dir: &Path,
+    ids: &[String],
+    hardware_manifest: Option<HardwareManifest>,
+    out: &Path,
+    progress_callback: Option<BundleProgressCallback>,
+) -> ModelResult<BundleManifest> {
+    let lock = ModelLock::load(&models_dir.join(LOCKFILE_NAME))?;
+    let registry = ModelRegistry::open(models_dir.to_path_buf())?;
+
+    let mut models = Vec::new();
+    for id in ids {
+        let entries: Vec<_> = lock.entries().iter().filter(|e| &e.model == id).collect();
+        if entries.is_empty() {
+            return Err(ModelError::NotFound(format!(
+                "{} has no entry in {}",
+                id, LOCKFILE_NAME
+            )));
+        }
+
+        let info = registry.get(id).cloned().map(portable_info);
+        for entry in entries {
+            let path = models_dir.join(&entry.filename);
+            let actual = hash_file(&path, entry, progress_callback.as_ref())?;
+            if actual != entry.sha256 {
+                return Err(ModelError::ChecksumMismatch {
+                    model: entry.filename.clone(),
+                    expected: entry.sha256.clone(),
+                    actual,
+                });
+            }
+            models.push(BundledModel {
+                lock: entry.clone(),
+                info: info.clone(),
+            });
+        }
+    }
+
+    let manifest = BundleManifest {
+        version: BUNDLE_VERSION,
+        created_at: Utc::now(),
+        models,
+        hardware_manifest,
+    };
+
+    if let Some(parent) = out.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut builder = tar::Builder::new(File::create(out)?);
+
+    let json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| ModelError::Internal(format!("Failed to serialize bundle manifest: {}", e)))?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(manifest.created_at.timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, BUNDLE_MANIFEST_NAME, json.as_slice())?;
+
+    for model in &manifest.models {
+        let filename = &model.lock.filename;
+        let mut file = File::open(models_dir.join(filename))?;
+        builder.append_file(format!("{}/{}", BUNDLE_MODELS_DIR, filename), &mut file)?;
+        if let Some(cb) = &progress_callback {
+            cb(BundleProgress {
+                filename: filename.clone(),
+                bytes: model.lock.size_bytes,
+                total: model.lock.size_bytes,
+                phase: BundlePhase::Complete,
+            });
+        }
+    }
+    builder.into_inner()?.sync_all()?;
+
+    info!(
+        "Exported {} model files ({} bytes) to {}",
+        manifest.models.len(),
+        manifest.total_bytes(),
+        out.display()
+    );
+    Ok(manifest)
+}
+
+fn import_blocking(
+    bundle: &Path,
+    models_dir: &Path,
+    progress_callback: Option<BundleProgressCallback>,
+) -> ModelResult<ImportReport> {
+    std::fs::create_dir_all(models_dir)?;
+
+    // Entry data is read through a second handle at its raw offset, so
+    // the archive iterator can seek past it
+    let mut data = File::open(bundle)?;
+    let mut archive = tar::Archive::new(File::open(bundle)?);
+
+    let mut manifest: Option<BundleManifest> = None;
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+
+    for entry in archive.entries_with_seek()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+
+        let Some(current) = &manifest else {
+            if path != Path::new(BUNDLE_MANIFEST_NAME) {
+                return Err(invalid(format!(
+                    "{} must be the first entry",
+                    BUNDLE_MANIFEST_NAME
+                )));
+            }
+            manifest = Some(parse_manifest(&mut entry)?);
+            continue;
+        };
+
+        let filename = bundled_filename(&path)?;
+        let model = current
+            .find(&filename)
+            .ok_or_else(|| invalid(format!("{} is not listed in the manifest", filename)))?;
+        let lock = &model.lock;
+        if entry.size() != lock.size_bytes {
+            return Err(invalid(format!(
+                "{} is {} bytes, manifest says {}",
+                filename,
+                entry.size(),
+                lock.size_bytes
+            )));
+        }
+
+        let target = models_dir.join(&filename);
+        if target.exists() && hash_file(&target, lock, progress_callback.as_ref())? == lock.sha256 {
+            debug!("{} already installed, skipping", filename);
+            report(
+                &progress_callback,
+                lock,
+                lock.size_bytes,
+                BundlePhase::Skipped,
+            );
+            skipped.push(filename);
+            continue;
+        }
+
+        copy_entry(
+            &mut data,
+            entry.raw_file_position(),
+            lock,
+            &target,
+            &progress_callback,
+        )?;
+        imported.push(filename);
+    }
+
+    let manifest = manifest.ok_or_else(|| invalid("empty archive"))?;
+    let missing: Vec<_> = manifest
+        .models
+        .iter()
+        .map(|m| &m.lock.filename)
+        .filter(|f| !imported.contains(f) && !skipped.contains(f))
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        return Err(invalid(format!(
+            "truncated bundle, missing {}",
+            missing.join(", ")
+        )));
+    }
+
+    let lock_path = models_dir.join(LOCKFILE_NAME);
+    let mut lock = ModelLock::load(&lock_path)?;
+    for model in &manifest.models {
+        lock.insert(LockEntry {
+            installed_at: Utc::now(),
+            ..model.lock.clone()
+        });
+    }
+    lock.save(&lock_path)?;
+
+    info!(
+        "Imported {} model files from {} ({} already installed)",
+        imported.len(),
+        bundle.display(),
+        skipped.len()
+    );
+    Ok(ImportReport {
+        manifest,
+        imported,
+        skipped,
+    })
+}
+
+/// Copy one model file out of the archive, resuming a partial copy
+fn copy_entry(
+    data: &mut File,
+    data_offset: u64,
+    lock: &LockEntry,
+    target: &Path,
+    progress_callback: &Option<BundleProgressCallback>,
+) -> ModelResult<()> {
+    let part_path = part_path(target);
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+
+    // Hash what an earlier import already copied
+    let mut copied = match std::fs::metadata(&part_path) {
+        Ok(meta) if meta.len() <= lock.size_bytes => meta.len(),
+        Ok(_) => {
+            warn!("Discarding oversized partial file {}", part_path.display());
+            0
+        },
+        Err(_) => 0,
+    };
+    if copied > 0 {
+        info!("Resuming {} from {} bytes", lock.filename, copied);
+        let mut reader = BufReader::new(File::open(&part_path)?).take(copied);
+        loop {
+            let n = reader.read(&mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buffer[..n]);
+        }
+    }
+
+    let mut part = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(&part_path)?;
+    part.set_len(copied)?;
+    part.seek(SeekFrom::End(0))?;
+
+    data.seek(SeekFrom::Start(data_offset + copied))?;
+    let mut reader = (&mut *data).take(lock.size_bytes - copied);
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+        part.write_all(&buffer[..n])?;
+        copied += n as u64;
+        report(progress_callback, lock, copied, BundlePhase::Copying);
+    }
+    part.sync_all()?;
+    drop(part);
+
+    if copied != lock.size_bytes {
+        return Err(invalid(format!(
+            "truncated bundle: {} ends after {} of {} bytes",
+            lock.filename, copied, lock.size_bytes
+        )));
+    }
+
+    let actual = hex::encode(hasher.finalize());
+    if actual != lock.sha256 {
+        std::fs::remove_file(&part_path)?;
+        return Err(ModelError::ChecksumMismatch {
+            model: lock.filename.clone(),
+            expected: lock.sha256.clone(),
+            actual,
+        });
+    }
+
+    std::fs::rename(&part_path, target)?;
+    report(progress_callback, lock, copied, BundlePhase::Complete);
+    Ok(())
+}
+
+fn parse_manifest(entry: &mut impl Read) -> ModelResult<BundleManifest> {
+    let mut json = String::new();
+    entry.read_to_string(&mut json)?;
+    let manifest: BundleManifest = serde_json::from_str(&json)
+        .map_err(|e| invalid(format!("{}: {}", BUNDLE_MANIFEST_NAME, e)))?;
+    if manifest.version > BUNDLE_VERSION {
+        return Err(invalid(format!(
+            "version {} is newer than supported version {}",
+            manifest.version, BUNDLE_VERSION
+        )));
+    }
+    for model in &manifest.models {
+        if Path::new(&model.lock.filename).file_name()
+            != Some(std::ffi::OsStr::new(&model.lock.filename))
+        {
+            return Err(invalid(format!(
+                "invalid file name {}",
+                model.lock.filename
+            )));
+        }
+    }
+    Ok(manifest)
+}
+
+/// File name of a `models/<filename>` archive path
+fn bundled_filename(path: &Path) -> ModelResult<String> {
+    let mut components = path.components();
+    match (components.next(), components.next(), components.next()) {
+        (
+            Some(std::path::Component::Normal(dir)),
+            Some(std::path::Component::Normal(name)),
+            None,
+        ) if dir == BUNDLE_MODELS_DIR => name
+            .to_str()
+            .map(str::to_string)
+            .ok_or_else(|| invalid("file name is not valid UTF-8")),
+        _ => Err(invalid(format!("unexpected entry {}", path.display()))),
+    }
+}
+
+/// SHA256 of an installed file, reporting progress
+fn hash_file(
+    path: &Path,
+    lock: &LockEntry,
+    progress_callback: Option<&BundleProgressCallback>,
+) -> ModelResult<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    let mut hashed = 0u64;
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+        hashed += n as u64;
+        if let Some(cb) = progress_callback {
+            cb(BundleProgress {
+                filename: lock.filename.clone(),
+                bytes: hashed,
+                total: lock.size_bytes,
+                phase: BundlePhase::Verifying,
+            });
+        }
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn report(
+    progress_callback: &Option<BundleProgressCallback>,
+    lock: &LockEntry,
+    bytes: u64,
+    phase: BundlePhase,
+) {
+    if let Some(cb) = progress_callback {
+        cb(BundleProgress {
+            filename: lock.filename.clone(),
+            bytes,
+            total: lock.size_bytes,
+            phase,
+        });
+    }
+}
+
+/// Registry entry without machine-specific install state
+fn portable_info(mut info: ModelInfo) -> ModelInfo {
+    for variant in &mut info.quantizations {
+        variant.status = ModelStatus::Available;
+        variant.local_path = None;
+    }
+    info
+}
+
+fn part_path(target: &Path) -> PathBuf {
+    target.with_extension(PART_EXTENSION)
+}
+
+fn invalid(reason: impl Into<String>) -> ModelError {
+    ModelError::InvalidBundle(reason.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::downloader::{sha256_file, VerifiedDownload};
+    use crate::gguf::fixture::tiny_llama;
+    use crate::Quantization;
+
+    /// Install a synthetic model into `models_dir` and lock it
+    async fn install(models_dir: &Path, id: &str, filename: &str) -> LockEntry {
+        std::fs::create_dir_all(models_dir).unwrap();
+        let path = models_dir.join(filename);
+        tiny_llama().write(&path);
+        let download = VerifiedDownload {
+            sha256: sha256_file(&path).await.unwrap(),
+            size_bytes: std::fs::metadata(&path).unwrap().len(),
+            source: format!("example/{}", filename),
+            revision: None,
+            path,
+        };
+
+        let lock_path = models_dir.join(LOCKFILE_NAME);
+        let mut lock = ModelLock::load(&lock_path).unwrap();
+        let entry = LockEntry::from_download(id, Some(Quantization::Q4), &download).unwrap();
+        lock.insert(entry.clone());
+        lock.save(&lock_path).unwrap();
+        entry
+    }
+
+    #[tokio::test]
+    async fn test_export_import_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        let target = dir.path().join("target");
+        install(&source, "phi-3-mini", "phi-3-mini-4k-instruct-q4_k_m.gguf").await;
+        install(&source, "tiny", "tiny-q4_k_m.gguf").await;
+        install(&source, "unused", "unused.gguf").await;
+
+        let bundle = dir.path().join("bundle.tar");
+        let ids = vec!["phi-3-mini".to_string(), "tiny".to_string()];
+        let manifest = export_bundle(
+            &source,
+            &ids,
+            Some(crate::manifest::profiles::minimal()),
+            &bundle,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(manifest.models.len(), 2);
+        assert!(manifest.models[0].info.is_some());
+
+        let read = read_bundle_manifest(&bundle).unwrap();
+        assert_eq!(read.models.len(), 2);
+        assert_eq!(
+            read.hardware_manifest.unwrap().name,
+            manifest.hardware_manifest.unwrap().name
+        );
+
+        let report = import_bundle(&bundle, &target, None).await.unwrap();
+        assert_eq!(report.imported.len(), 2);
+        assert!(report.skipped.is_empty());
+        assert!(!target.join("unused.gguf").exists());
+
+        // Registered and immediately usable, including the unknown model
+        let registry = ModelRegistry::open(target.clone()).unwrap();
+        assert!(registry.is_installed("phi-3-mini", Quantization::Q4));
+        assert!(registry.is_installed("tiny", Quantization::Q4));
+        assert_eq!(registry.get("tiny").unwrap().family, "llama");
+
+        // Importing again skips verified files
+        let report = import_bundle(&bundle, &target, None).await.unwrap();
+        assert!(report.imported.is_empty());
+        assert_eq!(report.skipped.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_export_rejects_uninstalled_or_corrupt_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = install(dir.path(), "tiny", "tiny.gguf").await;
+        let bundle = dir.path().join("bundle.tar");
+
+        let err = export_bundle(dir.path(), &["missing".to_string()], None, &bundle, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ModelError::NotFound(_)));
+
+        let mut bytes = std::fs::read(dir.path().join(&entry.filename)).unwrap();
+        *bytes.last_mut().unwrap() ^= 0xFF;
+        std::fs::write(dir.path().join(&entry.filename), bytes).unwrap();
+        let err = export_bundle(dir.path(), &["tiny".to_string()], None, &bundle, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ModelError::ChecksumMismatch { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_import_resumes_partial_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        let target = dir.path().join("target");
+        let entry = install(&source, "tiny", "tiny.gguf").await;
+        let bundle = dir.path().join("bundle.tar");
+        export_bundle(&source, &["tiny".to_string()], None, &bundle, None)
+            .await
+            .unwrap();
+
+        // Simulate an interrupted import: half the file copied
+        let original = std::fs::read(source.join("tiny.gguf")).unwrap();
+        std::fs::create_dir_all(&target).unwrap();
+        let half = original.len() / 2;
+        std::fs::write(target.join("tiny.part"), &original[..half]).unwrap();
+
+        let copied = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let copied_clone = copied.clone();
+        let progress: BundleProgressCallback = Arc::new(move |p: BundleProgress| {
+            if p.phase == BundlePhase::Copying {
+                copied_clone.lock().unwrap().push(p.bytes);
+            }
+        });
+        let report = import_bundle(&bundle, &target, Some(progress))
+            .await
+            .unwrap();
+        assert_eq!(report.imported, vec!["tiny.gguf".to_string()]);
+        assert!(copied.lock().unwrap().iter().all(|&b| b > half as u64));
+        assert_eq!(std::fs::read(target.join("tiny.gguf")).unwrap(), original);
+        assert!(!target.join("tiny.part").exists());
+
+        let lock = ModelLock::load(&target.join(LOCKFILE_NAME)).unwrap();
+        assert_eq!(lock.get("tiny.gguf").unwrap().sha256, entry.sha256);
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_corrupt_and_truncated_bundles() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        install(&source, "tiny", "tiny.gguf").await;
+        let bundle = dir.path().join("bundle.tar");
+        export_bundle(&source, &["tiny".to_string()], None, &bundle, None)
+            .await
+            .unwrap();
+        let bytes = std::fs::read(&bundle).unwrap();
+
+        // Flip a byte inside the model data (after the manifest and headers)
+        let mut corrupt = bytes.clone();
+        let data_start = corrupt.windows(4).rposition(|w| w == b"GGUF").unwrap();
+        corrupt[data_start + 200] ^= 0xFF;
+        let corrupt_path = dir.path().join("corrupt.tar");
+        std::fs::write(&corrupt_path, corrupt).unwrap();
+        let target = dir.path().join("target");
+        let err = import_bundle(&corrupt_path, &target, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ModelError::ChecksumMismatch { .. }));
+        assert!(!target.join("tiny.gguf").exists());
+        assert!(!target.join(LOCKFILE_NAME).exists());
+
+        // Cut off inside the model data
+        let truncated_path = dir.path().join("truncated.tar");
+        std::fs::write(&truncated_path, &bytes[..data_start + 100]).unwrap();
+        let err = import_bundle(&truncated_path, &target, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ModelError::InvalidBundle(_) | ModelError::IoError(_)
+        ));
+        assert!(!target.join("tiny.gguf").exists());
+    }
+
+    #[test]
+    fn test_bundled_filename() {
+        assert_eq!(
+            bundled_filename(Path::new("models/a.gguf")).unwrap(),
+            "a.gguf"
+        );
+        assert!(bundled_filename(Path::new("models/../a.gguf")).is_err());
+        assert!(bundled_filename(Path::new("other/a.gguf")).is_err());
+        assert!(bundled_filename(Path::new("models/x/a.gguf")).is_err());
+    }
+}
@@ -0,0 +1,300 @@
+//! Chat Templates
+//!
+//! Renders a conversation of system, user and assistant messages into the
+//! prompt format a model family was fine-tuned on. A model prompted in the
+//! wrong format still answers, but follows instructions less reliably and
+//! often fails to stop.
+//!
+//! | Template  | Families                | Turn markers                                    |
+//! |-----------|-------------------------|-------------------------------------------------|
+//! | `Phi3`    | Phi-3                   | `<\|user\|>` … `<\|end\|>`                      |
+//! | `Llama3`  | Llama 3                 | `<\|start_header_id\|>user<\|end_header_id\|>`  |
+//! | `Mistral` | Mistral, Mixtral        | `[INST]` … `[/INST]`                            |
+//! | `ChatMl`  | Qwen and other fallback | `<\|im_start\|>user` … `<\|im_end\|>`           |
+//!
+//! The template is detected from a GGUF file's `tokenizer.chat_template`
+//! (or architecture), or guessed from a model name.
+
+use serde::{Deserialize, Serialize};
+
+use crate::gguf::GgufMetadata;
+
+/// Author of a chat message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+}
+
+impl ChatRole {
+    /// Lowercase role name, as written in most templates
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChatRole::System => "system",
+            ChatRole::User => "user",
+            ChatRole::Assistant => "assistant",
+        }
+    }
+}
+
+/// A single message in a conversation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+impl ChatMessage {
+    /// Create a message
+    pub fn new(role: ChatRole, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+        }
+    }
+
+    /// System instructions
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(ChatRole::System, content)
+    }
+
+    /// User turn
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new(ChatRole::User, content)
+    }
+
+    /// Assistant turn
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new(ChatRole::Assistant, content)
+    }
+}
+
+/// Prompt format of a model family
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatTemplate {
+    /// Phi-3 (`<|user|>` … `<|end|>`)
+    #[default]
+    Phi3,
+    /// Llama 3 (`<|start_header_id|>` … `<|eot_id|>`)
+    Llama3,
+    /// Mistral (`[INST]` … `[/INST]`)
+    Mistral,
+    /// ChatML (`<|im_start|>` … `<|im_end|>`)
+    ChatMl,
+}
+
+impl ChatTemplate {
+    /// Detect the template of a GGUF model
+    ///
+    /// Looks for each family's turn markers in the embedded Jinja template,
+    /// then falls back to the architecture.
+    pub fn detect(metadata: &GgufMetadata) -> Self {
+        if let Some(template) = &metadata.chat_template {
+            if template.contains("<|start_header_id|>") {
+                return ChatTemplate::Llama3;
+            }
+            if template.contains("<|im_start|>") {
+                return ChatTemplate::ChatMl;
+            }
+            if template.contains("[INST]") {
+                return ChatTemplate::Mistral;
+            }
+            if template.contains("<|user|>") {
+                return ChatTemplate::Phi3;
+            }
+        }
+
+        match metadata.architecture.as_str() {
+            "phi3" => ChatTemplate::Phi3,
+            // Llama 3 vocabularies are byte-level BPE; Llama 2 used [INST]
+            "llama" if metadata.tokenizer.as_deref() == Some("gpt2") => ChatTemplate::Llama3,
+            "llama" | "mistral" | "mixtral" => ChatTemplate::Mistral,
+            _ => ChatTemplate::ChatMl,
+        }
+    }
+
+    /// Guess the template from a model name (e.g. "phi-3-mini",
+    /// "llama-3.2-8b", "mistral-7b-instruct")
+    pub fn for_model(name: &str) -> Self {
+        let name = name.to_lowercase().replace(['-', '_', '.', ' '], "");
+        if name.starts_with("phi") {
+            ChatTemplate::Phi3
+        } else if name.starts_with("llama3") || name.starts_with("metallama3") {
+            ChatTemplate::Llama3
+        } else if name.starts_with("mistral")
+            || name.starts_with("mixtral")
+            || name.starts_with("llama2")
+        {
+            ChatTemplate::Mistral
+        } else if name.starts_with("qwen") || name.starts_with("yi") {
+            ChatTemplate::ChatMl
+        } else {
+            ChatTemplate::default()
+        }
+    }
+
+    /// Render a conversation into a prompt
+    ///
+    /// With `add_generation_prompt`, the prompt ends by opening an
+    /// assistant turn for the model to complete.
+    pub fn render(&self, messages: &[ChatMessage], add_generation_prompt: bool) -> String {
+        let mut prompt = String::new();
+        match self {
+            ChatTemplate::Phi3 => {
+                for message in messages {
+                    prompt.push_str(&format!(
+                        "<|{}|>\n{}<|end|>\n",
+                        message.role.as_str(),
+                        message.content
+                    ));
+                }
+                if add_generation_prompt {
+                    prompt.push_str("<|assistant|>\n");
+                }
+            },
+            ChatTemplate::Llama3 => {
+                prompt.push_str("<|begin_of_text|>");
+                for message in messages {
+                    prompt.push_str(&format!(
+                        "<|start_header_id|>{}<|end_header_id|>\n\n{}<|eot_id|>",
+                        message.role.as_str(),
+                        message.content.trim()
+                    ));
+                }
+                if add_generation_prompt {
+                    prompt.push_str("<|start_header_id|>assistant<|end_header_id|>\n\n");
+                }
+            },
+            ChatTemplate::Mistral => {
+                // No system role: system text is prepended to the first user turn
+                let mut system = Vec::new();
+                prompt.push_str("<s>");
+                for message in messages {
+                    match message.role {
+                        ChatRole::System => system.push(message.content.trim()),
+                        ChatRole::User => {
+                            prompt.push_str("[INST] ");
+                            if !system.is_empty() {
+                                prompt.push_str(&system.join("\n\n"));
+                                prompt.push_str("\n\n");
+                                system.clear();
+                            }
+                            prompt.push_str(message.content.trim());
+                            prompt.push_str(" [/INST]");
+                        },
+                        ChatRole::Assistant => {
+                            prompt.push_str(message.content.trim());
+                            prompt.push_str("</s>");
+                        },
+                    }
+                }
+                // The [/INST] of the last user turn is the generation prompt
+            },
+            ChatTemplate::ChatMl => {
+                for message in messages {
+                    prompt.push_str(&format!(
+                        "<|im_start|>{}\n{}<|im_end|>\n",
+                        message.role.as_str(),
+                        message.content
+                    ));
+                }
+                if add_generation_prompt {
+                    prompt.push_str("<|im_start|>assistant\n");
+                }
+            },
+        }
+        prompt
+    }
+
+    /// Markers that end an assistant turn, to stop generation on
+    pub fn stop_sequences(&self) -> &'static [&'static str] {
+        match self {
+            ChatTemplate::Phi3 => &["<|end|>", "<|endoftext|>"],
+            ChatTemplate::Llama3 => &["<|eot_id|>", "<|end_of_text|>"],
+            ChatTemplate::Mistral => &["</s>"],
+            ChatTemplate::ChatMl => &["<|im_end|>"],
+        }
+    }
+}
+
+impl std::fmt::Display for ChatTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ChatTemplate::Phi3 => "Phi-3",
+            ChatTemplate::Llama3 => "Llama 3",
+            ChatTemplate::Mistral => "Mistral",
+            ChatTemplate::ChatMl => "ChatML",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::system("Be brief."),
+            ChatMessage::user("Hi"),
+            ChatMessage::assistant("Hello!"),
+            ChatMessage::user("Bye"),
+        ]
+    }
+
+    #[test]
+    fn test_render_templates() {
+        let messages = conversation();
+
+        assert_eq!(
+            ChatTemplate::Phi3.render(&messages, true),
+            "<|system|>\nBe brief.<|end|>\n<|user|>\nHi<|end|>\n<|assistant|>\nHello!<|end|>\n\
+             <|user|>\nBye<|end|>\n<|assistant|>\n"
+        );
+        assert_eq!(
+            ChatTemplate::Llama3.render(&messages[..2], true),
+            "<|begin_of_text|><|start_header_id|>system<|end_header_id|>\n\nBe brief.<|eot_id|>\
+             <|start_header_id|>user<|end_header_id|>\n\nHi<|eot_id|>\
+             <|start_header_id|>assistant<|end_header_id|>\n\n"
+        );
+        assert_eq!(
+            ChatTemplate::Mistral.render(&messages, true),
+            "<s>[INST] Be brief.\n\nHi [/INST]Hello!</s>[INST] Bye [/INST]"
+        );
+        assert_eq!(
+            ChatTemplate::ChatMl.render(&messages[1..2], false),
+            "<|im_start|>user\nHi<|im_end|>\n"
+        );
+    }
+
+    #[test]
+    fn test_for_model() {
+        assert_eq!(ChatTemplate::for_model("phi-3-mini-4k"), ChatTemplate::Phi3);
+        assert_eq!(
+            ChatTemplate::for_model("llama-3.2-8b"),
+            ChatTemplate::Llama3
+        );
+        assert_eq!(
+            ChatTemplate::for_model("Mistral-7B-Instruct"),
+            ChatTemplate::Mistral
+        );
+        assert_eq!(ChatTemplate::for_model("qwen2-7b"), ChatTemplate::ChatMl);
+        assert_eq!(ChatTemplate::for_model(""), ChatTemplate::Phi3);
+    }
+
+    #[test]
+    fn test_detect() {
+        let bytes = crate::gguf::fixture::tiny_llama().build();
+        let mut metadata = GgufMetadata::from_reader(&bytes[..], bytes.len() as u64).unwrap();
+        metadata.chat_template = Some("{{ '<|start_header_id|>' + role }}".to_string());
+        assert_eq!(ChatTemplate::detect(&metadata), ChatTemplate::Llama3);
+
+        metadata.chat_template = None;
+        assert_eq!(ChatTemplate::detect(&metadata), ChatTemplate::Mistral);
+        metadata.architecture = "phi3".to_string();
+        assert_eq!(ChatTemplate::detect(&metadata), ChatTemplate::Phi3);
+    }
+}
@@ -0,0 +1,405 @@
+//! Persistent Download Queue
+//!
+//! `synesis init` installs several multi-gigabyte models at once. The queue
+//! records what still has to be downloaded in a `downloads.json` next to the
+//! models, so an interrupted session picks up where it stopped: finished
+//! files are skipped, and unfinished ones resume from their `.part` files.
+//!
+//! [`DownloadQueue::run`] downloads pending entries in parallel, up to a
+//! concurrency limit. A global bandwidth cap is applied by giving the
+//! downloader a shared [`BandwidthLimiter`](crate::downloader::BandwidthLimiter).
+//!
+//! Every download is checksum-verified, and each finished model is recorded
+//! in the `models.lock` next to the queue.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{debug, info, warn};
+
+use crate::downloader::{DownloadSpec, Downloader, ProgressCallback};
+use crate::lockfile::{LockEntry, ModelLock, LOCKFILE_NAME};
+use crate::{ModelError, ModelResult};
+
+/// File name of the queue inside a models directory
+pub const QUEUE_FILE_NAME: &str = "downloads.json";
+
+/// Downloads run at once unless configured otherwise
+pub const DEFAULT_CONCURRENT_DOWNLOADS: usize = 2;
+
+/// Current queue format version
+const QUEUE_VERSION: u32 = 1;
+
+/// State of a queued download
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueStatus {
+    /// Not downloaded yet, or interrupted
+    Pending,
+    /// Downloaded, verified and recorded in the lockfile
+    Complete,
+    /// Last attempt failed; retried on the next run
+    Failed,
+}
+
+/// One model in the queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedDownload {
+    /// Model name
+    pub name: String,
+    /// What to download
+    pub spec: DownloadSpec,
+    /// Current state
+    pub status: QueueStatus,
+    /// Error of the last failed attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Where the file was saved, once complete
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Verified SHA256 checksum, once complete
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// When the model was queued
+    pub added_at: DateTime<Utc>,
+}
+
+/// Outcome of [`DownloadQueue::run`]
+#[derive(Debug, Clone, Default)]
+pub struct QueueReport {
+    /// Names of the models downloaded in this run
+    pub completed: Vec<String>,
+    /// Names and errors of the models that failed
+    pub failed: Vec<(String, String)>,
+}
+
+/// On-disk form of the queue
+#[derive(Debug, Serialize, Deserialize)]
+struct QueueFile {
+    version: u32,
+    #[serde(default)]
+    downloads: Vec<QueuedDownload>,
+}
+
+/// Download queue persisted to a JSON file
+#[derive(Debug, Clone)]
+pub struct DownloadQueue {
+    /// File the queue is saved to
+    path: PathBuf,
+    /// Queued models, in the order they were added
+    entries: Vec<QueuedDownload>,
+}
+
+impl DownloadQueue {
+    /// Load the queue of a models directory, or an empty queue
+    ///
+    /// # Errors
+    /// Returns [`ModelError::InvalidDownloadQueue`] if the file cannot be
+    /// parsed or was written by a newer version.
+    pub fn open(models_dir: &Path) -> ModelResult<Self> {
+        Self::load(&models_dir.join(QUEUE_FILE_NAME))
+    }
+
+    /// Load a queue file, or an empty queue if it does not exist
+    pub fn load(path: &Path) -> ModelResult<Self> {
+        let mut queue = Self {
+            path: path.to_path_buf(),
+            entries: Vec::new(),
+        };
+        if !path.exists() {
+            return Ok(queue);
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let file: QueueFile = serde_json::from_str(&content)
+            .map_err(|e| ModelError::InvalidDownloadQueue(format!("{}: {}", path.display(), e)))?;
+        if file.version > QUEUE_VERSION {
+            return Err(ModelError::InvalidDownloadQueue(format!(
+                "{}: version {} is newer than supported version {}",
+                path.display(),
+                file.version,
+                QUEUE_VERSION
+            )));
+        }
+
+        debug!(
+            "Loaded {} queued downloads from {}",
+            file.downloads.len(),
+            path.display()
+        );
+        queue.entries = file.downloads;
+        Ok(queue)
+    }
+
+    /// Write the queue
+    ///
+    /// Writes to a temporary file first and renames it into place, so an
+    /// interrupted write never leaves a half-written queue.
+    pub fn save(&self) -> ModelResult<()> {
+        let file = QueueFile {
+            version: QUEUE_VERSION,
+            downloads: self.entries.clone(),
+        };
+        let content = serde_json::to_string_pretty(&file)
+            .map_err(|e| ModelError::InvalidDownloadQueue(e.to_string()))?;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, content)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// All queued models, in the order they were added
+    pub fn entries(&self) -> &[QueuedDownload] {
+        &self.entries
+    }
+
+    /// Models still to download, including failed ones
+    pub fn pending(&self) -> impl Iterator<Item = &QueuedDownload> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.status != QueueStatus::Complete)
+    }
+
+    /// Queue a model for download
+    ///
+    /// A model already in the queue keeps its place. Its spec is replaced
+    /// and it is marked pending, unless it is complete with the same spec.
+    pub fn enqueue(&mut self, name: &str, spec: DownloadSpec) {
+        match self.entries.iter_mut().find(|entry| entry.name == name) {
+            Some(entry) if entry.status == QueueStatus::Complete && entry.spec == spec => {},
+            Some(entry) => {
+                entry.spec = spec;
+                entry.status = QueueStatus::Pending;
+                entry.error = None;
+                entry.path = None;
+                entry.sha256 = None;
+            },
+            None => self.entries.push(QueuedDownload {
+                name: name.to_string(),
+                spec,
+                status: QueueStatus::Pending,
+                error: None,
+                path: None,
+                sha256: None,
+                added_at: Utc::now(),
+            }),
+        }
+    }
+
+    /// Mark a queued model pending again, so the next run downloads it
+    pub fn reset(&mut self, name: &str) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.name == name) {
+            entry.status = QueueStatus::Pending;
+            entry.error = None;
+            entry.path = None;
+            entry.sha256 = None;
+        }
+    }
+
+    /// Drop completed entries
+    pub fn remove_completed(&mut self) {
+        self.entries
+            .retain(|entry| entry.status != QueueStatus::Complete);
+    }
+
+    /// Download every pending model, `max_concurrent` at a time
+    ///
+    /// Downloads go through [`Downloader::download_verified`], so a model
+    /// whose spec pins no checksum is checked against the one its host
+    /// publishes, and refused if there is none.
+    ///
+    /// `progress_for` is asked for a progress callback for each pending
+    /// model before any download starts. The queue and the lockfile are
+    /// saved after every download. To stop early (on Ctrl+C, say), call
+    /// [`Downloader::cancel`] and let the run finish: running downloads
+    /// checkpoint their partial files and stay pending, to resume on the
+    /// next run.
+    ///
+    /// # Errors
+    /// Failed downloads are reported in the [`QueueReport`]; only failing
+    /// to save the queue or the lockfile is an error.
+    pub async fn run<F>(
+        &mut self,
+        downloader: Arc<Downloader>,
+        max_concurrent: usize,
+        progress_for: F,
+    ) -> ModelResult<QueueReport>
+    where
+        F: Fn(&QueuedDownload) -> Option<ProgressCallback>,
+    {
+        let semaphore = Arc::new(Semaphore::new(max_concurrent.max(1)));
+        let mut tasks = JoinSet::new();
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            if entry.status == QueueStatus::Complete {
+                continue;
+            }
+
+            let progress = progress_for(entry);
+            let spec = entry.spec.clone();
+            let downloader = downloader.clone();
+            let semaphore = semaphore.clone();
+            tasks.spawn(async move {
+                let result = match semaphore.acquire_owned().await {
+                    Ok(_permit) => downloader.download_verified(&spec, progress).await,
+                    Err(e) => Err(ModelError::Internal(e.to_string())),
+                };
+                (index, result)
+            });
+        }
+
+        info!("Downloading {} queued models", tasks.len());
+        let lock_path = self.lock_path();
+        let mut lock = ModelLock::load(&lock_path)?;
+        let mut report = QueueReport::default();
+        while let Some(joined) = tasks.join_next().await {
+            let (index, result) = joined.map_err(|e| ModelError::Internal(e.to_string()))?;
+            let entry = &mut self.entries[index];
+            match result {
+                Ok(download) => {
+                    let quantization = entry.spec.quantization.parse().ok();
+                    lock.insert(LockEntry::from_download(
+                        &entry.name,
+                        quantization,
+                        &download,
+                    )?);
+                    lock.save(&lock_path)?;
+
+                    entry.status = QueueStatus::Complete;
+                    entry.error = None;
+                    entry.sha256 = Some(download.sha256);
+                    entry.path = Some(download.path);
+                    report.completed.push(entry.name.clone());
+                },
+                Err(ModelError::Cancelled(_)) => {
+                    debug!("Download of {} cancelled", entry.name);
+                },
+                Err(e) => {
+                    warn!("Download of {} failed: {}", entry.name, e);
+                    entry.status = QueueStatus::Failed;
+                    entry.error = Some(e.to_string());
+                    report.failed.push((entry.name.clone(), e.to_string()));
+                },
+            }
+            self.save()?;
+        }
+
+        Ok(report)
+    }
+
+    /// Lockfile in the same directory as the queue
+    fn lock_path(&self) -> PathBuf {
+        self.path.with_file_name(LOCKFILE_NAME)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::downloader::ModelSource;
+    use sha2::{Digest, Sha256};
+
+    fn local_spec(path: PathBuf, contents: &str) -> DownloadSpec {
+        DownloadSpec {
+            source: ModelSource::Local { path },
+            sha256: Some(hex::encode(Sha256::digest(contents))),
+            size_bytes: None,
+            quantization: "q4".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_enqueue_and_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut queue = DownloadQueue::open(dir.path()).unwrap();
+        queue.enqueue("a", local_spec("/src/a.gguf".into(), "a"));
+        queue.enqueue("b", local_spec("/src/b.gguf".into(), "b"));
+        queue.entries[0].status = QueueStatus::Complete;
+
+        // Same spec: stays complete; new spec: pending again
+        queue.enqueue("a", local_spec("/src/a.gguf".into(), "a"));
+        assert_eq!(queue.entries()[0].status, QueueStatus::Complete);
+        queue.enqueue("b", local_spec("/src/b2.gguf".into(), "b"));
+        assert_eq!(queue.entries().len(), 2);
+        queue.save().unwrap();
+
+        let reloaded = DownloadQueue::open(dir.path()).unwrap();
+        let pending: Vec<_> = reloaded.pending().map(|e| e.name.as_str()).collect();
+        assert_eq!(pending, ["b"]);
+        assert_eq!(
+            reloaded.entries()[1].spec,
+            local_spec("/src/b2.gguf".into(), "b")
+        );
+
+        std::fs::write(dir.path().join(QUEUE_FILE_NAME), r#"{"version": 99}"#).unwrap();
+        assert!(matches!(
+            DownloadQueue::open(dir.path()),
+            Err(ModelError::InvalidDownloadQueue(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_persists_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let models_dir = dir.path().join("models");
+        let mut queue = DownloadQueue::open(&models_dir).unwrap();
+        for name in ["a", "b", "c"] {
+            let src = dir.path().join(format!("{}.gguf", name));
+            std::fs::write(&src, name).unwrap();
+            queue.enqueue(name, local_spec(src, name));
+        }
+        queue.enqueue(
+            "missing",
+            local_spec(dir.path().join("missing.gguf"), "late"),
+        );
+
+        let downloader = Arc::new(Downloader::new(models_dir.clone()));
+        let report = queue.run(downloader.clone(), 2, |_| None).await.unwrap();
+        assert_eq!(report.completed.len(), 3);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, "missing");
+
+        // Results survive a restart; only the failure is left to retry
+        let mut reloaded = DownloadQueue::open(&models_dir).unwrap();
+        let b = &reloaded.entries()[1];
+        assert_eq!(b.status, QueueStatus::Complete);
+        assert_eq!(std::fs::read(b.path.as_ref().unwrap()).unwrap(), b"b");
+        assert_eq!(b.sha256, b.spec.sha256);
+        assert_eq!(reloaded.entries()[3].status, QueueStatus::Failed);
+        assert!(reloaded.entries()[3].error.is_some());
+
+        std::fs::write(dir.path().join("missing.gguf"), "late").unwrap();
+        let report = reloaded.run(downloader.clone(), 2, |_| None).await.unwrap();
+        assert_eq!(report.completed, ["missing"]);
+
+        // Every finished model is locked with its verified checksum
+        let lock = ModelLock::load(&models_dir.join(LOCKFILE_NAME)).unwrap();
+        let mut locked: Vec<_> = lock.entries().iter().map(|e| e.model.as_str()).collect();
+        locked.sort_unstable();
+        assert_eq!(locked, ["a", "b", "c", "missing"]);
+        assert_eq!(
+            lock.get("missing.gguf").unwrap().sha256,
+            hex::encode(Sha256::digest("late"))
+        );
+
+        // A spec without a checksum has nothing to verify against
+        std::fs::write(dir.path().join("d.gguf"), "d").unwrap();
+        let unpinned = DownloadSpec {
+            sha256: None,
+            ..local_spec(dir.path().join("d.gguf"), "d")
+        };
+        reloaded.enqueue("d", unpinned);
+        let report = reloaded.run(downloader, 2, |_| None).await.unwrap();
+        assert_eq!(report.failed[0].0, "d");
+        reloaded.remove_completed();
+        assert_eq!(reloaded.entries().len(), 1);
+    }
+}
@@ -6,8 +6,12 @@
 //! # Download Features
 //!
 //! - **Resumable downloads**: Partial downloads are stored with `.part` extension
+//! - **Mirrors**: HuggingFace-compatible base URLs (such as an internal
+//!   artifact proxy) tried in order instead of `huggingface.co`
+//! - **Bandwidth cap**: A [`BandwidthLimiter`] shared by concurrent downloads
 //! - **Progress tracking**: Real-time progress callbacks with speed and ETA
-//! - **Checksum verification**: SHA256 validation after download
+//! - **Checksum verification**: SHA256 validation after download, against the
+//!   spec's checksum or the one HuggingFace publishes for the file
 //! - **Multiple sources**: HuggingFace Hub, direct URLs, local files
 //! - **Error recovery**: Automatic retry on network failures
 //!
@@ -23,10 +27,25 @@
 //! Interrupted downloads can be resumed if:
 //! - Server supports `Range` requests (HTTP 206)
 //! - Partial file exists at `<filename>.part`
-//! - URL and destination haven't changed
-
+//! - Destination, expected checksum and expected size haven't changed
+//!
+//! The checkpoint does not depend on the URL, so a download interrupted on
+//! one mirror resumes on the next. [`Downloader::cancel`] stops running
+//! downloads after checkpointing them.
+//!
+//! Every [`CHECKPOINT_INTERVAL_BYTES`] the downloader records the length and
+//! SHA256 of the partial file next to it, in `<filename>.part.json`. Before
+//! resuming, the partial file is cut back to the last checkpoint and its
+//! checksum compared, so a corrupted or foreign `.part` file is discarded
+//! rather than extended. The checkpoint's `ETag` is sent as `If-Range`: if
+//! the file changed on the server, the whole file comes back and the
+//! download starts over.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
 use tracing::{debug, info, instrument, warn};
 
 use crate::{ModelError, ModelResult};
@@ -48,6 +67,24 @@ const USER_AGENT: &str = concat!("synesis-models/", env!("CARGO_PKG_VERSION"));
 /// Partial download file extension
 const PART_EXTENSION: &str = "part";
 
+/// Extension of the checkpoint recorded next to a partial download
+const CHECKPOINT_EXTENSION: &str = "part.json";
+
+/// Bytes downloaded between checkpoints of a partial download (16 MB)
+pub const CHECKPOINT_INTERVAL_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Base URL of the HuggingFace Hub
+pub const HUGGINGFACE_BASE_URL: &str = "https://huggingface.co";
+
+/// Environment variable overriding the HuggingFace base URL
+const HF_ENDPOINT_ENV: &str = "HF_ENDPOINT";
+
+/// HuggingFace header carrying the SHA256 of an LFS file
+const HF_LINKED_ETAG_HEADER: &str = "x-linked-etag";
+
+/// HuggingFace header carrying the commit a revision resolved to
+const HF_REPO_COMMIT_HEADER: &str = "x-repo-commit";
+
 /// Download progress callback
 pub type ProgressCallback = Arc<dyn Fn(DownloadProgress) + Send + Sync>;
 
@@ -81,7 +118,8 @@ pub enum DownloadPhase {
 }
 
 /// Model source configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ModelSource {
     /// HuggingFace Hub
     HuggingFace {
@@ -114,7 +152,7 @@ impl std::fmt::Display for ModelSource {
 }
 
 /// Model download specification
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DownloadSpec {
     /// Source of the model
     pub source: ModelSource,
@@ -126,6 +164,130 @@ pub struct DownloadSpec {
     pub quantization: String,
 }
 
+/// A download whose SHA256 checksum has been verified
+#[derive(Debug, Clone)]
+pub struct VerifiedDownload {
+    /// Where the file was saved
+    pub path: PathBuf,
+    /// Source the file came from
+    pub source: String,
+    /// Commit or revision the file was resolved from, if known
+    pub revision: Option<String>,
+    /// File size in bytes
+    pub size_bytes: u64,
+    /// Verified SHA256 checksum (lowercase hex)
+    pub sha256: String,
+}
+
+/// Checksum and revision published by the model host
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublishedChecksum {
+    /// SHA256 checksum (lowercase hex), if published
+    pub sha256: Option<String>,
+    /// Commit the requested revision resolved to
+    pub revision: Option<String>,
+}
+
+/// Global download bandwidth cap
+///
+/// A token bucket holding up to one second of traffic. Every downloader
+/// sharing the limiter draws from the same bucket, so parallel downloads
+/// together stay under the cap. A caller that overdraws the bucket sleeps
+/// until the debt is paid back.
+#[derive(Debug)]
+pub struct BandwidthLimiter {
+    /// Sustained rate in bytes per second
+    bytes_per_second: u64,
+    /// Bytes available now (negative when overdrawn) and when that was
+    bucket: Mutex<(f64, tokio::time::Instant)>,
+}
+
+impl BandwidthLimiter {
+    /// Create a limiter allowing `bytes_per_second` across all downloads
+    ///
+    /// A rate of zero means no limit.
+    pub fn new(bytes_per_second: u64) -> Self {
+        Self {
+            bytes_per_second,
+            bucket: Mutex::new((bytes_per_second as f64, tokio::time::Instant::now())),
+        }
+    }
+
+    /// Sustained rate in bytes per second
+    pub fn bytes_per_second(&self) -> u64 {
+        self.bytes_per_second
+    }
+
+    /// Wait until `bytes` may be transferred
+    pub async fn acquire(&self, bytes: usize) {
+        if self.bytes_per_second == 0 {
+            return;
+        }
+
+        let rate = self.bytes_per_second as f64;
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+            let now = tokio::time::Instant::now();
+            let refilled = bucket.0 + now.duration_since(bucket.1).as_secs_f64() * rate;
+            bucket.0 = refilled.min(rate) - bytes as f64;
+            bucket.1 = now;
+            (-bucket.0 / rate).max(0.0)
+        };
+
+        if wait > 0.0 {
+            tokio::time::sleep(std::time::Duration::from_secs_f64(wait)).await;
+        }
+    }
+}
+
+/// What a partial download is a prefix of, whichever mirror it came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct DownloadTarget {
+    /// Where the finished file is saved
+    dest: PathBuf,
+    /// Expected SHA256 of the finished file (lowercase hex), if known
+    sha256: Option<String>,
+    /// Expected size of the finished file, if known
+    size_bytes: Option<u64>,
+}
+
+/// Checkpoint of a partial download, stored in `<filename>.part.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PartialCheckpoint {
+    /// File the bytes belong to
+    target: DownloadTarget,
+    /// `ETag` the server sent for the file, if any
+    etag: Option<String>,
+    /// Length of the checkpointed prefix of the `.part` file
+    bytes: u64,
+    /// SHA256 of that prefix (lowercase hex)
+    sha256: String,
+}
+
+impl PartialCheckpoint {
+    /// Read a checkpoint, or `None` if it is missing or unreadable
+    async fn load(path: &Path) -> Option<Self> {
+        let content = tokio::fs::read(path).await.ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    /// Move the checkpoint to `bytes`, hashed into `hasher`
+    fn advance(&mut self, bytes: u64, hasher: &Sha256) {
+        self.bytes = bytes;
+        self.sha256 = hex::encode(hasher.clone().finalize());
+    }
+
+    /// Write the checkpoint, replacing the previous one atomically
+    async fn save(&self, path: &Path) -> ModelResult<()> {
+        let content = serde_json::to_vec(self)
+            .map_err(|e| ModelError::Internal(format!("Failed to encode checkpoint: {}", e)))?;
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, content).await?;
+        tokio::fs::rename(&tmp_path, path).await?;
+        Ok(())
+    }
+}
+
 /// Model downloader
 ///
 /// Handles downloading models from various sources with resume support and progress tracking.
@@ -133,8 +295,10 @@ pub struct DownloadSpec {
 ///
 /// # Thread Safety
 ///
-/// The downloader is NOT thread-safe. Create separate instances for concurrent downloads,
-/// or wrap in `Arc<Mutex<>>` if shared access is needed.
+/// The downloader can be shared through an `Arc` for concurrent downloads,
+/// as long as they write to different files. Use a
+/// [`DownloadQueue`](crate::download_queue::DownloadQueue) to run several
+/// downloads with a concurrency limit.
 ///
 /// # Example
 /// ```ignore
@@ -149,15 +313,24 @@ pub struct Downloader {
     models_dir: PathBuf,
     /// HTTP client (persistent for connection reuse)
     client: reqwest::Client,
+    /// HTTP client that does not follow redirects (for metadata lookups)
+    head_client: reqwest::Client,
     /// HuggingFace token (optional, for gated models)
     hf_token: Option<String>,
+    /// HuggingFace-compatible base URLs, tried in order
+    mirrors: Vec<String>,
+    /// Bandwidth cap shared with other downloaders
+    bandwidth: Option<Arc<BandwidthLimiter>>,
+    /// Set by [`cancel`](Self::cancel)
+    cancelled: watch::Sender<bool>,
 }
 
 impl Downloader {
     /// Create a new downloader
     ///
     /// Initializes the HTTP client with connection pooling and timeout settings.
-    /// HuggingFace token is read from `HF_TOKEN` environment variable if set.
+    /// HuggingFace token is read from `HF_TOKEN` environment variable if set,
+    /// and the HuggingFace base URL from `HF_ENDPOINT`.
     ///
     /// # Arguments
     /// * `models_dir` - Directory where downloaded models will be stored
@@ -169,7 +342,18 @@ impl Downloader {
                 .timeout(std::time::Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))
                 .build()
                 .expect("Failed to create HTTP client"),
+            head_client: reqwest::Client::builder()
+                .user_agent(USER_AGENT)
+                .timeout(std::time::Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .expect("Failed to create HTTP client"),
             hf_token: std::env::var("HF_TOKEN").ok(),
+            mirrors: vec![std::env::var(HF_ENDPOINT_ENV)
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|_| HUGGINGFACE_BASE_URL.to_string())],
+            bandwidth: None,
+            cancelled: watch::channel(false).0,
         }
     }
 
@@ -179,6 +363,59 @@ impl Downloader {
         self
     }
 
+    /// Fetch HuggingFace files from these base URLs instead
+    ///
+    /// Mirrors must serve the Hub's `/<repo>/resolve/<revision>/<file>`
+    /// layout. They are tried in order until one succeeds; include
+    /// [`HUGGINGFACE_BASE_URL`] to fall back to the Hub itself. The
+    /// HuggingFace token is sent to every mirror. An empty list keeps the
+    /// current base URL.
+    pub fn with_mirrors(mut self, mirrors: Vec<String>) -> Self {
+        if !mirrors.is_empty() {
+            self.mirrors = mirrors
+                .into_iter()
+                .map(|url| url.trim_end_matches('/').to_string())
+                .collect();
+        }
+        self
+    }
+
+    /// Share a bandwidth cap with other downloads
+    pub fn with_bandwidth_limit(mut self, limiter: Arc<BandwidthLimiter>) -> Self {
+        self.bandwidth = Some(limiter);
+        self
+    }
+
+    /// HuggingFace-compatible base URLs, in the order they are tried
+    pub fn mirrors(&self) -> &[String] {
+        &self.mirrors
+    }
+
+    /// Stop every running and later download
+    ///
+    /// Running downloads checkpoint their partial files and fail with
+    /// [`ModelError::Cancelled`]; they resume when downloaded again by a
+    /// new downloader.
+    pub fn cancel(&self) {
+        self.cancelled.send_replace(true);
+    }
+
+    /// Delete a model's file and any partial download of it
+    pub async fn discard(&self, spec: &DownloadSpec) -> ModelResult<()> {
+        let dest = self.get_dest_path(spec)?;
+        for path in [
+            dest.with_extension(CHECKPOINT_EXTENSION),
+            dest.with_extension(PART_EXTENSION),
+            dest,
+        ] {
+            match tokio::fs::remove_file(&path).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+
     /// Download a model
     ///
     /// Downloads a model file from the specified source with optional progress tracking.
@@ -237,6 +474,12 @@ impl Downloader {
             tokio::fs::create_dir_all(parent).await?;
         }
 
+        let target = DownloadTarget {
+            dest: dest_path.clone(),
+            sha256: spec.sha256.as_ref().map(|sha256| sha256.to_lowercase()),
+            size_bytes: spec.size_bytes,
+        };
+
         // Download based on source
         match &spec.source {
             ModelSource::HuggingFace {
@@ -248,13 +491,13 @@ impl Downloader {
                     repo_id,
                     filename,
                     revision.as_deref(),
-                    &dest_path,
+                    &target,
                     progress_callback.clone(),
                 )
                 .await?;
             },
             ModelSource::Url { url, .. } => {
-                self.download_from_url(url, &dest_path, progress_callback.clone())
+                self.download_from_mirrors(url, &target, progress_callback.clone())
                     .await?;
             },
             ModelSource::Local { path } => {
@@ -274,12 +517,13 @@ impl Downloader {
                 });
             }
 
-            if !self.verify_checksum(&dest_path, expected).await? {
+            let actual = sha256_file(&dest_path).await?;
+            if actual != expected.to_lowercase() {
                 tokio::fs::remove_file(&dest_path).await?;
                 return Err(ModelError::ChecksumMismatch {
                     model: spec.source.to_string(),
                     expected: expected.clone(),
-                    actual: "invalid".to_string(),
+                    actual,
                 });
             }
         }
@@ -297,6 +541,135 @@ impl Downloader {
         Ok(dest_path)
     }
 
+    /// Download a model and verify its checksum
+    ///
+    /// Like [`download`](Self::download), but a checksum is required: the
+    /// spec's own, or else the one HuggingFace publishes for the file. The
+    /// returned [`VerifiedDownload`] is what the registry records as an
+    /// installed model.
+    ///
+    /// # Errors
+    /// Returns [`ModelError::UnverifiedChecksum`] (before downloading
+    /// anything) if no checksum is known, and
+    /// [`ModelError::ChecksumMismatch`] if the file does not match it.
+    #[instrument(skip(self, progress_callback))]
+    pub async fn download_verified(
+        &self,
+        spec: &DownloadSpec,
+        progress_callback: Option<ProgressCallback>,
+    ) -> ModelResult<VerifiedDownload> {
+        let published = match spec.sha256 {
+            Some(_) => PublishedChecksum::default(),
+            None => match self.source_url(&spec.source) {
+                Some(url) => self.published_checksum(&url).await?,
+                None => PublishedChecksum::default(),
+            },
+        };
+
+        let Some(sha256) = spec.sha256.clone().or(published.sha256) else {
+            return Err(ModelError::UnverifiedChecksum(spec.source.to_string()));
+        };
+        let sha256 = sha256.to_lowercase();
+
+        let verified_spec = DownloadSpec {
+            sha256: Some(sha256.clone()),
+            ..spec.clone()
+        };
+        let path = self.download(&verified_spec, progress_callback).await?;
+        let size_bytes = tokio::fs::metadata(&path).await?.len();
+
+        let revision = match &spec.source {
+            ModelSource::HuggingFace { revision, .. } => published.revision.or(revision.clone()),
+            _ => published.revision,
+        };
+
+        Ok(VerifiedDownload {
+            path,
+            source: spec.source.to_string(),
+            revision,
+            size_bytes,
+            sha256,
+        })
+    }
+
+    /// Look up the checksum HuggingFace publishes for a file
+    ///
+    /// Sends a `HEAD` request to a `huggingface.co/.../resolve/...` URL, or
+    /// the same path on a mirror, and reads the LFS SHA256 and resolved
+    /// commit from the response headers. Other hosts publish nothing and
+    /// return an empty result.
+    pub async fn published_checksum(&self, url: &str) -> ModelResult<PublishedChecksum> {
+        if !self.is_hub_url(url) {
+            return Ok(PublishedChecksum::default());
+        }
+
+        let mut request = self.head_client.head(url);
+        if let Some(token) = &self.hf_token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+        let response = request.send().await?;
+        if response.status().is_client_error() || response.status().is_server_error() {
+            return Err(ModelError::DownloadFailed(format!(
+                "HTTP {} looking up {}",
+                response.status(),
+                url
+            )));
+        }
+
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.trim_matches('"').to_lowercase())
+        };
+        let published = PublishedChecksum {
+            sha256: header(HF_LINKED_ETAG_HEADER).filter(|etag| is_sha256(etag)),
+            revision: header(HF_REPO_COMMIT_HEADER),
+        };
+        debug!("Published checksum for {}: {:?}", url, published);
+
+        Ok(published)
+    }
+
+    /// URL a source is fetched from, if it is remote
+    fn source_url(&self, source: &ModelSource) -> Option<String> {
+        match source {
+            ModelSource::HuggingFace {
+                repo_id,
+                filename,
+                revision,
+            } => Some(huggingface_url(
+                &self.mirrors[0],
+                repo_id,
+                filename,
+                revision.as_deref().unwrap_or("main"),
+            )),
+            ModelSource::Url { url, .. } => Some(url.clone()),
+            ModelSource::Local { .. } => None,
+        }
+    }
+
+    /// Whether a URL points at the HuggingFace Hub or a configured mirror
+    fn is_hub_url(&self, url: &str) -> bool {
+        url.contains("huggingface.co")
+            || self.mirrors.iter().any(|base| url.starts_with(base.as_str()))
+    }
+
+    /// A URL rewritten onto each mirror, in the order they are tried
+    ///
+    /// URLs outside the Hub and its mirrors are returned unchanged.
+    fn mirror_urls(&self, url: &str) -> Vec<String> {
+        let path = std::iter::once(HUGGINGFACE_BASE_URL)
+            .chain(self.mirrors.iter().map(String::as_str))
+            .find_map(|base| url.strip_prefix(base).filter(|path| path.starts_with('/')));
+
+        match path {
+            Some(path) => self.mirrors.iter().map(|base| format!("{}{}", base, path)).collect(),
+            None => vec![url.to_string()],
+        }
+    }
+
     /// Get destination path for a model
     fn get_dest_path(&self, spec: &DownloadSpec) -> ModelResult<PathBuf> {
         let filename = match &spec.source {
@@ -317,55 +690,78 @@ impl Downloader {
         repo_id: &str,
         filename: &str,
         revision: Option<&str>,
-        dest: &Path,
+        target: &DownloadTarget,
         progress_callback: Option<ProgressCallback>,
     ) -> ModelResult<()> {
-        let revision = revision.unwrap_or("main");
-        let url = format!(
-            "https://huggingface.co/{}/resolve/{}/{}",
-            repo_id, revision, filename
-        );
+        let url = huggingface_url(&self.mirrors[0], repo_id, filename, revision.unwrap_or("main"));
 
         debug!("Downloading from HuggingFace: {}", url);
-        self.download_from_url(&url, dest, progress_callback).await
+        self.download_from_mirrors(&url, target, progress_callback).await
+    }
+
+    /// Download from a URL, falling back to the next mirror on failure
+    async fn download_from_mirrors(
+        &self,
+        url: &str,
+        target: &DownloadTarget,
+        progress_callback: Option<ProgressCallback>,
+    ) -> ModelResult<()> {
+        let mut last_error = None;
+        for candidate in self.mirror_urls(url) {
+            match self.download_from_url(&candidate, target, progress_callback.clone()).await {
+                Ok(()) => return Ok(()),
+                Err(e @ ModelError::Cancelled(_)) => return Err(e),
+                Err(e) => {
+                    warn!("Download from {} failed: {}", candidate, e);
+                    last_error = Some(e);
+                },
+            }
+        }
+        Err(last_error
+            .unwrap_or_else(|| ModelError::DownloadFailed(format!("No mirror for {}", url))))
     }
 
     /// Download from a URL
+    ///
+    /// Resumes a `.part` file whose checkpoint is valid for this target, and
+    /// checkpoints the partial file as it grows.
     async fn download_from_url(
         &self,
         url: &str,
-        dest: &Path,
+        target: &DownloadTarget,
         progress_callback: Option<ProgressCallback>,
     ) -> ModelResult<()> {
+        let dest = &target.dest;
+        if *self.cancelled.borrow() {
+            return Err(ModelError::Cancelled(dest.display().to_string()));
+        }
+
         let mut request = self.client.get(url);
 
         // Add HuggingFace token if available and it's a HF URL
-        if url.contains("huggingface.co") {
+        if self.is_hub_url(url) {
             if let Some(token) = &self.hf_token {
                 request = request.header("Authorization", format!("Bearer {}", token));
             }
         }
 
         // Check for partial download (resume support)
-        // If a .part file exists, try to resume from where we left off
+        // If a .part file has a valid checkpoint, resume from where it left off
         let temp_path = dest.with_extension(PART_EXTENSION);
-        let resume_from = if temp_path.exists() {
-            let metadata = tokio::fs::metadata(&temp_path).await?;
-            let size = metadata.len();
-            if size > 0 {
-                info!("Resuming download from {} bytes", size);
-                request = request.header("Range", format!("bytes={}-", size));
-                Some(size)
-            } else {
-                None
+        let checkpoint_path = dest.with_extension(CHECKPOINT_EXTENSION);
+        let resume = resumable_checkpoint(target, &temp_path, &checkpoint_path).await?;
+        if let Some((checkpoint, _)) = &resume {
+            info!("Resuming download from {} bytes", checkpoint.bytes);
+            request =
+                request.header(reqwest::header::RANGE, format!("bytes={}-", checkpoint.bytes));
+            if let Some(etag) = &checkpoint.etag {
+                request = request.header(reqwest::header::IF_RANGE, etag);
             }
-        } else {
-            None
-        };
+        }
 
         let response = request.send().await?;
 
-        if !response.status().is_success() && response.status().as_u16() != 206 {
+        if !response.status().is_success() {
             return Err(ModelError::DownloadFailed(format!(
                 "HTTP {}: {}",
                 response.status(),
@@ -373,12 +769,50 @@ impl Downloader {
             )));
         }
 
+        // A server that ignores the range, or whose file changed, sends it all
+        let resume = match resume {
+            Some(resume) if response.status() == reqwest::StatusCode::PARTIAL_CONTENT => {
+                if content_range_start(response.headers()) != Some(resume.0.bytes) {
+                    tokio::fs::remove_file(&checkpoint_path).await.ok();
+                    return Err(ModelError::DownloadFailed(format!(
+                        "{} did not resume at byte {}",
+                        url, resume.0.bytes
+                    )));
+                }
+                Some(resume)
+            },
+            Some(_) => {
+                info!("Server sent the whole file, restarting download");
+                None
+            },
+            None => None,
+        };
+
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let (mut checkpoint, mut hasher) = resume.unwrap_or_else(|| {
+            (
+                PartialCheckpoint {
+                    target: target.clone(),
+                    etag: None,
+                    bytes: 0,
+                    sha256: String::new(),
+                },
+                Sha256::new(),
+            )
+        });
+        checkpoint.etag = etag.or(checkpoint.etag);
+        let resume_from = checkpoint.bytes;
+
         let total_size = response
             .content_length()
-            .map(|cl| resume_from.unwrap_or(0) + cl);
+            .map(|cl| resume_from + cl);
 
         // Open file for writing
-        let mut file = if resume_from.is_some() {
+        let mut file = if resume_from > 0 {
             tokio::fs::OpenOptions::new()
                 .append(true)
                 .open(&temp_path)
@@ -387,7 +821,7 @@ impl Downloader {
             tokio::fs::File::create(&temp_path).await?
         };
 
-        let mut downloaded = resume_from.unwrap_or(0);
+        let mut downloaded = resume_from;
         let mut last_progress = std::time::Instant::now();
         let mut last_downloaded = downloaded;
 
@@ -396,11 +830,39 @@ impl Downloader {
         use tokio::io::AsyncWriteExt;
 
         let mut stream = response.bytes_stream();
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result?;
+        let mut cancelled = self.cancelled.subscribe();
+        loop {
+            let next = tokio::select! {
+                biased;
+                _ = cancelled.wait_for(|cancelled| *cancelled) => {
+                    Some(Err(ModelError::Cancelled(dest.display().to_string())))
+                },
+                next = stream.next() => next.map(|chunk| chunk.map_err(ModelError::from)),
+            };
+            let chunk = match next {
+                Some(Ok(chunk)) => chunk,
+                Some(Err(e)) => {
+                    // Keep what arrived for the next attempt
+                    file.flush().await?;
+                    checkpoint.advance(downloaded, &hasher);
+                    checkpoint.save(&checkpoint_path).await?;
+                    return Err(e);
+                },
+                None => break,
+            };
+            if let Some(limiter) = &self.bandwidth {
+                limiter.acquire(chunk.len()).await;
+            }
             file.write_all(&chunk).await?;
+            hasher.update(&chunk);
             downloaded += chunk.len() as u64;
 
+            if downloaded - checkpoint.bytes >= CHECKPOINT_INTERVAL_BYTES {
+                file.flush().await?;
+                checkpoint.advance(downloaded, &hasher);
+                checkpoint.save(&checkpoint_path).await?;
+            }
+
             // Update progress at configured interval to avoid callback spam
             let now = std::time::Instant::now();
             if now.duration_since(last_progress).as_millis() >= PROGRESS_UPDATE_INTERVAL_MS as u128 {
@@ -408,13 +870,8 @@ impl Downloader {
                 let bytes_since = downloaded - last_downloaded;
                 let speed = (bytes_since as f64 / elapsed) as u64;
 
-                let eta = total_size.map(|total| {
-                    if speed > 0 {
-                        (total - downloaded) / speed
-                    } else {
-                        0
-                    }
-                });
+                let eta = total_size
+                    .map(|total| total.saturating_sub(downloaded).checked_div(speed).unwrap_or(0));
 
                 if let Some(cb) = &progress_callback {
                     cb(DownloadProgress {
@@ -436,6 +893,7 @@ impl Downloader {
 
         // Move temp file to final destination
         tokio::fs::rename(&temp_path, dest).await?;
+        tokio::fs::remove_file(&checkpoint_path).await.ok();
 
         Ok(())
     }
@@ -448,39 +906,10 @@ impl Downloader {
 
     /// Verify SHA256 checksum
     ///
-    /// Computes the SHA256 hash of a file in streaming fashion to minimize memory usage.
-    /// Uses a 1 MB buffer regardless of file size.
-    ///
-    /// # Arguments
-    /// * `path` - Path to the file to verify
-    /// * `expected` - Expected SHA256 checksum (hex string)
-    ///
     /// # Returns
     /// `true` if checksum matches, `false` otherwise
-    ///
-    /// # Performance
-    /// - Memory: 1 MB buffer
-    /// - Time: O(n) where n = file size
-    /// - I/O: Sequential read of entire file
     async fn verify_checksum(&self, path: &Path, expected: &str) -> ModelResult<bool> {
-        use sha2::{Digest, Sha256};
-
-        let mut file = tokio::fs::File::open(path).await?;
-        let mut hasher = Sha256::new();
-
-        // Use configured buffer size for streaming
-        let mut buffer = vec![0u8; DOWNLOAD_BUFFER_SIZE];
-        loop {
-            use tokio::io::AsyncReadExt;
-            let n = file.read(&mut buffer).await?;
-            if n == 0 {
-                break;
-            }
-            hasher.update(&buffer[..n]);
-        }
-
-        let result = hex::encode(hasher.finalize());
-        Ok(result == expected.to_lowercase())
+        Ok(sha256_file(path).await? == expected.to_lowercase())
     }
 
     /// List downloaded models
@@ -520,6 +949,124 @@ impl Downloader {
     }
 }
 
+/// Compute the SHA256 checksum of a file
+///
+/// Hashes the file in streaming fashion to minimize memory usage.
+/// Uses a 1 MB buffer regardless of file size.
+///
+/// # Returns
+/// Lowercase hex digest
+///
+/// # Performance
+/// - Memory: 1 MB buffer
+/// - Time: O(n) where n = file size
+/// - I/O: Sequential read of entire file
+pub async fn sha256_file(path: &Path) -> ModelResult<String> {
+    use tokio::io::AsyncReadExt;
+
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+
+    // Use configured buffer size for streaming
+    let mut buffer = vec![0u8; DOWNLOAD_BUFFER_SIZE];
+    loop {
+        let n = file.read(&mut buffer).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Whether a string is a hex SHA256 digest
+fn is_sha256(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Download URL of a file in a HuggingFace repository
+fn huggingface_url(base_url: &str, repo_id: &str, filename: &str, revision: &str) -> String {
+    format!(
+        "{}/{}/resolve/{}/{}",
+        base_url, repo_id, revision, filename
+    )
+}
+
+/// Checkpoint to resume a partial download of `target` from
+///
+/// Returns the checkpoint with the SHA256 state of the prefix it covers.
+/// Bytes written after the checkpoint are cut off. A partial file with no
+/// checkpoint for `target`, or whose prefix no longer matches the
+/// checkpoint's checksum, is discarded.
+async fn resumable_checkpoint(
+    target: &DownloadTarget,
+    temp_path: &Path,
+    checkpoint_path: &Path,
+) -> ModelResult<Option<(PartialCheckpoint, Sha256)>> {
+    let size = match tokio::fs::metadata(temp_path).await {
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    };
+
+    let resume = match PartialCheckpoint::load(checkpoint_path).await {
+        Some(checkpoint)
+            if checkpoint.target == *target && checkpoint.bytes > 0 && checkpoint.bytes <= size =>
+        {
+            let hasher = hash_prefix(temp_path, checkpoint.bytes).await?;
+            if hex::encode(hasher.clone().finalize()) == checkpoint.sha256 {
+                Some((checkpoint, hasher))
+            } else {
+                warn!("Partial download of {} is corrupt, restarting", target.dest.display());
+                None
+            }
+        },
+        _ => None,
+    };
+
+    match &resume {
+        Some((checkpoint, _)) if size > checkpoint.bytes => {
+            let file = tokio::fs::OpenOptions::new().write(true).open(temp_path).await?;
+            file.set_len(checkpoint.bytes).await?;
+        },
+        Some(_) => {},
+        None => {
+            if size > 0 {
+                debug!("Discarding unverifiable partial download {}", temp_path.display());
+            }
+            tokio::fs::remove_file(temp_path).await.ok();
+            tokio::fs::remove_file(checkpoint_path).await.ok();
+        },
+    }
+
+    Ok(resume)
+}
+
+/// SHA256 state after hashing the first `len` bytes of a file
+async fn hash_prefix(path: &Path, len: u64) -> ModelResult<Sha256> {
+    use tokio::io::AsyncReadExt;
+
+    let mut file = tokio::fs::File::open(path).await?.take(len);
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; DOWNLOAD_BUFFER_SIZE];
+    loop {
+        let n = file.read(&mut buffer).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+
+    Ok(hasher)
+}
+
+/// First byte position of a `Content-Range: bytes <start>-<end>/<total>` header
+fn content_range_start(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    let value = headers.get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+    let range = value.strip_prefix("bytes ")?;
+    range.split('-').next()?.trim().parse().ok()
+}
+
 /// Known model specifications
 pub mod known_models {
     use super::*;
@@ -532,7 +1079,7 @@ pub mod known_models {
                 filename: "Phi-3-mini-4k-instruct-q4.gguf".to_string(),
                 revision: None,
             },
-            sha256: None,                    // TODO: Add actual checksum
+            sha256: None, // Published by HuggingFace, checked by `download_verified`
             size_bytes: Some(2_200_000_000), // ~2.2GB
             quantization: "q4".to_string(),
         }
@@ -608,4 +1155,298 @@ mod tests {
         let path = downloader.get_dest_path(&spec).unwrap();
         assert!(path.to_string_lossy().contains("Phi-3-mini"));
     }
+
+    #[tokio::test]
+    async fn test_download_verified_local_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("model.gguf");
+        std::fs::write(&src, b"weights").unwrap();
+        let downloader = Downloader::new(dir.path().join("models"));
+
+        let mut spec = DownloadSpec {
+            source: ModelSource::Local { path: src.clone() },
+            sha256: None,
+            size_bytes: None,
+            quantization: "q4".to_string(),
+        };
+
+        // Nothing to verify against
+        let err = downloader.download_verified(&spec, None).await.unwrap_err();
+        assert!(matches!(err, ModelError::UnverifiedChecksum(_)));
+        assert!(!dir.path().join("models/model.gguf").exists());
+
+        let expected = sha256_file(&src).await.unwrap();
+        spec.sha256 = Some(expected.to_uppercase());
+        let verified = downloader.download_verified(&spec, None).await.unwrap();
+        assert_eq!(verified.sha256, expected);
+        assert_eq!(verified.size_bytes, 7);
+        assert_eq!(verified.path, dir.path().join("models/model.gguf"));
+
+        // Wrong checksum: file is removed and the real one reported
+        std::fs::remove_file(&verified.path).unwrap();
+        spec.sha256 = Some("0".repeat(64));
+        match downloader.download_verified(&spec, None).await.unwrap_err() {
+            ModelError::ChecksumMismatch { actual, .. } => assert_eq!(actual, expected),
+            other => panic!("unexpected error: {}", other),
+        }
+        assert!(!verified.path.exists());
+    }
+
+    /// Requests seen by a [`serve`] stand-in: path and `Range` header
+    type SeenRequests = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+    /// Serve `content` over HTTP on a local port, honouring `Range` and
+    /// `If-Range` like the Hub's CDN does
+    async fn serve(content: Vec<u8>, etag: &'static str) -> (String, SeenRequests) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let seen = SeenRequests::default();
+        let requests = seen.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut head = Vec::new();
+                let mut byte = [0u8; 1];
+                while !head.ends_with(b"\r\n\r\n") && socket.read(&mut byte).await.unwrap() == 1 {
+                    head.push(byte[0]);
+                }
+                let head = String::from_utf8(head).unwrap();
+                let path = head.split_whitespace().nth(1).unwrap().to_string();
+                let header = |name: &str| {
+                    head.lines().find_map(|line| {
+                        let (key, value) = line.split_once(':')?;
+                        key.eq_ignore_ascii_case(name).then(|| value.trim().to_string())
+                    })
+                };
+                let range = header("range");
+                requests.lock().unwrap().push((path, range.clone()));
+
+                let start = range
+                    .filter(|_| header("if-range").is_none_or(|tag| tag == etag))
+                    .and_then(|r| r.strip_prefix("bytes=")?.strip_suffix('-')?.parse().ok());
+                let response = match start {
+                    Some(start) => format!(
+                        "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {}-{}/{}\r\n",
+                        start,
+                        content.len() - 1,
+                        content.len()
+                    ),
+                    None => "HTTP/1.1 200 OK\r\n".to_string(),
+                };
+                let body = &content[start.unwrap_or(0) as usize..];
+                let head = format!(
+                    "{}ETag: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    response,
+                    etag,
+                    body.len()
+                );
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(body).await.unwrap();
+            }
+        });
+
+        (base_url, seen)
+    }
+
+    /// A mirror that answers every request with HTTP 503
+    async fn failing_url() -> String {
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let response = "HTTP/1.1 503 Service Unavailable\r\n\
+                                Content-Length: 0\r\nConnection: close\r\n\r\n";
+                socket.write_all(response.as_bytes()).await.ok();
+            }
+        });
+        base_url
+    }
+
+    /// Write `content[..at]` as a partial download of `target`
+    fn write_partial(target: &DownloadTarget, content: &[u8], at: usize) -> PartialCheckpoint {
+        let mut checkpoint = PartialCheckpoint {
+            target: target.clone(),
+            etag: Some("\"v1\"".to_string()),
+            bytes: 0,
+            sha256: String::new(),
+        };
+        checkpoint.advance(at as u64, &Sha256::new().chain_update(&content[..at]));
+        std::fs::write(target.dest.with_extension(PART_EXTENSION), &content[..at]).unwrap();
+        std::fs::write(
+            target.dest.with_extension(CHECKPOINT_EXTENSION),
+            serde_json::to_vec(&checkpoint).unwrap(),
+        )
+        .unwrap();
+        checkpoint
+    }
+
+    fn model_bytes() -> Vec<u8> {
+        (0..100_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[tokio::test]
+    async fn test_mirror_failover() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mirror, seen) = serve(model_bytes(), "\"v1\"").await;
+        let downloader = Downloader::new(dir.path().to_path_buf())
+            .with_mirrors(vec![failing_url().await, format!("{}/", mirror)]);
+
+        let spec = DownloadSpec {
+            source: ModelSource::HuggingFace {
+                repo_id: "org/model-GGUF".to_string(),
+                filename: "model.gguf".to_string(),
+                revision: None,
+            },
+            sha256: None,
+            size_bytes: None,
+            quantization: "q4".to_string(),
+        };
+
+        // Bytes fetched from another mirror before the interruption are kept
+        let target = DownloadTarget {
+            dest: dir.path().join("model.gguf"),
+            sha256: None,
+            size_bytes: None,
+        };
+        write_partial(&target, &model_bytes(), 30_000);
+
+        let path = downloader.download(&spec, None).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), model_bytes());
+        assert_eq!(
+            seen.lock().unwrap()[0],
+            (
+                "/org/model-GGUF/resolve/main/model.gguf".to_string(),
+                Some("bytes=30000-".to_string())
+            )
+        );
+
+        // Hub URLs given directly are rewritten onto the mirrors too
+        let urls = downloader.mirror_urls("https://huggingface.co/org/m/resolve/main/f.gguf");
+        assert_eq!(urls[1], format!("{}/org/m/resolve/main/f.gguf", mirror));
+        assert_eq!(downloader.mirror_urls("https://example.com/f.gguf").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resume_validated_against_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = model_bytes();
+        let (base_url, seen) = serve(content.clone(), "\"v1\"").await;
+        let url = format!("{}/model.gguf", base_url);
+        let downloader = Downloader::new(dir.path().to_path_buf());
+        let target = DownloadTarget {
+            dest: dir.path().join("model.gguf"),
+            sha256: Some(hex::encode(Sha256::digest(&content))),
+            size_bytes: Some(content.len() as u64),
+        };
+        let part = target.dest.with_extension(PART_EXTENSION);
+        let checkpoint_path = target.dest.with_extension(CHECKPOINT_EXTENSION);
+
+        // Checkpoint at 40 000 bytes, with unverified bytes written after it
+        let write_corrupt_tail = |checkpoint: &PartialCheckpoint| {
+            let mut partial = content[..40_000].to_vec();
+            partial.extend_from_slice(&[0xff; 5_000]);
+            std::fs::write(&part, partial).unwrap();
+            std::fs::write(&checkpoint_path, serde_json::to_vec(checkpoint).unwrap()).unwrap();
+        };
+        let mut checkpoint = write_partial(&target, &content, 40_000);
+
+        write_corrupt_tail(&checkpoint);
+        downloader.download_from_url(&url, &target, None).await.unwrap();
+        assert_eq!(std::fs::read(&target.dest).unwrap(), content);
+        assert_eq!(seen.lock().unwrap()[0].1.as_deref(), Some("bytes=40000-"));
+        assert!(!part.exists() && !checkpoint_path.exists());
+
+        // A prefix that no longer matches its checksum is not extended
+        checkpoint.sha256 = "0".repeat(64);
+        write_corrupt_tail(&checkpoint);
+        downloader.download_from_url(&url, &target, None).await.unwrap();
+        assert_eq!(std::fs::read(&target.dest).unwrap(), content);
+        assert_eq!(seen.lock().unwrap()[1].1, None);
+
+        // The file changed on the server: it sends everything back
+        checkpoint.advance(40_000, &Sha256::new().chain_update(&content[..40_000]));
+        checkpoint.etag = Some("\"v0\"".to_string());
+        write_corrupt_tail(&checkpoint);
+        downloader.download_from_url(&url, &target, None).await.unwrap();
+        assert_eq!(std::fs::read(&target.dest).unwrap(), content);
+        assert_eq!(seen.lock().unwrap()[2].1.as_deref(), Some("bytes=40000-"));
+
+        // A checkpoint of a different expected file is not resumed
+        let other = DownloadTarget {
+            sha256: Some("0".repeat(64)),
+            ..target.clone()
+        };
+        write_partial(&other, &content, 40_000);
+        downloader.download_from_url(&url, &target, None).await.unwrap();
+        assert_eq!(seen.lock().unwrap()[3].1, None);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_checkpoints_partial_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let content = model_bytes();
+        let (base_url, seen) = serve(content.clone(), "\"v1\"").await;
+        let url = format!("{}/model.gguf", base_url);
+        let target = DownloadTarget {
+            dest: dir.path().join("model.gguf"),
+            sha256: None,
+            size_bytes: None,
+        };
+
+        // Slow enough to still be running when cancelled
+        let downloader = Arc::new(
+            Downloader::new(dir.path().to_path_buf())
+                .with_bandwidth_limit(Arc::new(BandwidthLimiter::new(20_000))),
+        );
+        let task = {
+            let (downloader, url, target) = (downloader.clone(), url.clone(), target.clone());
+            tokio::spawn(async move { downloader.download_from_url(&url, &target, None).await })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        downloader.cancel();
+        assert!(matches!(task.await.unwrap(), Err(ModelError::Cancelled(_))));
+
+        let checkpoint_path = target.dest.with_extension(CHECKPOINT_EXTENSION);
+        let checkpoint = PartialCheckpoint::load(&checkpoint_path).await.unwrap();
+        assert!(checkpoint.bytes > 0 && checkpoint.bytes < content.len() as u64);
+
+        // Later downloads are refused too
+        assert!(matches!(
+            downloader.download_from_url(&url, &target, None).await,
+            Err(ModelError::Cancelled(_))
+        ));
+
+        // A new session picks up from the checkpoint
+        let downloader = Downloader::new(dir.path().to_path_buf());
+        downloader.download_from_url(&url, &target, None).await.unwrap();
+        assert_eq!(std::fs::read(&target.dest).unwrap(), content);
+        let range = format!("bytes={}-", checkpoint.bytes);
+        assert_eq!(seen.lock().unwrap()[1].1.as_deref(), Some(range.as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_bandwidth_limiter() {
+        let limiter = BandwidthLimiter::new(100_000);
+        let start = std::time::Instant::now();
+
+        // One second of traffic is available at once, the rest is paced
+        limiter.acquire(100_000).await;
+        assert!(start.elapsed() < std::time::Duration::from_millis(100));
+        limiter.acquire(25_000).await;
+        assert!(start.elapsed() >= std::time::Duration::from_millis(200));
+
+        // No limit
+        BandwidthLimiter::new(0).acquire(usize::MAX).await;
+    }
+
+    #[test]
+    fn test_is_sha256() {
+        assert!(is_sha256(&"a".repeat(64)));
+        assert!(!is_sha256("placeholder"));
+        assert!(!is_sha256(&"g".repeat(64)));
+    }
 }
@@ -0,0 +1,1058 @@
+//! GGUF Metadata
+//!
+//! Reads the header of a GGUF model file without loading any weights:
+//! architecture, parameter count, context length, quantization type,
+//! tokenizer and chat template, plus the name, shape and size of every
+//! tensor.
+//!
+//! The header is checked as it is read. A file that is not GGUF, uses an
+//! unsupported version, has a malformed header or is shorter than its
+//! tensors claim is rejected with [`ModelError::InvalidGguf`], so a broken
+//! download never reaches the inference backend.
+//!
+//! # Format
+//!
+//! ```text
+//! magic "GGUF" | version u32 | tensor_count u64 | kv_count u64
+//! kv_count x (key string, value type u32, value)
+//! tensor_count x (name string, n_dims u32, dims u64[n_dims], type u32, offset u64)
+//! padding to `general.alignment` (default 32)
+//! tensor data
+//! ```
+//!
+//! All integers are little-endian; strings are a `u64` length followed by
+//! UTF-8 bytes.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+use crate::{ModelError, ModelResult, Quantization};
+
+/// File magic at the start of every GGUF file
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+
+/// Tensor data alignment when `general.alignment` is not set
+const DEFAULT_ALIGNMENT: u64 = 32;
+
+/// Most dimensions a ggml tensor can have
+const MAX_DIMS: u32 = 4;
+
+/// Longest metadata string accepted (chat templates are a few KB)
+const MAX_STRING_LEN: u64 = 16 * 1024 * 1024;
+
+/// Deepest nesting accepted for array values
+const MAX_ARRAY_DEPTH: u32 = 2;
+
+/// Metadata read from a GGUF file header
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GgufMetadata {
+    /// GGUF format version (2 or 3)
+    pub version: u32,
+    /// Model architecture (`general.architecture`, e.g. "llama", "phi3")
+    pub architecture: String,
+    /// Model name, if the file records one
+    pub name: Option<String>,
+    /// Number of weights, summed over all tensors
+    pub parameter_count: u64,
+    /// Trained context length (`<arch>.context_length`)
+    pub context_length: Option<u32>,
+    /// Number of transformer blocks (`<arch>.block_count`)
+    #[serde(default)]
+    pub block_count: Option<u32>,
+    /// Hidden size (`<arch>.embedding_length`)
+    #[serde(default)]
+    pub embedding_length: Option<u64>,
+    /// Attention heads (`<arch>.attention.head_count`)
+    #[serde(default)]
+    pub head_count: Option<u32>,
+    /// Key/value heads, fewer than `head_count` with grouped-query attention
+    /// (`<arch>.attention.head_count_kv`)
+    #[serde(default)]
+    pub head_count_kv: Option<u32>,
+    /// Quantization type name (e.g. "Q4_K_M", "F16")
+    pub file_type: String,
+    /// Matching quantization level, if it is one the registry knows
+    pub quantization: Option<Quantization>,
+    /// Tokenizer model (`tokenizer.ggml.model`, e.g. "llama", "gpt2")
+    pub tokenizer: Option<String>,
+    /// Number of tokens in the vocabulary
+    pub vocab_size: Option<u64>,
+    /// Jinja chat template (`tokenizer.chat_template`)
+    pub chat_template: Option<String>,
+    /// Tensors in file order
+    pub tensors: Vec<GgufTensor>,
+    /// Byte offset of the tensor data section
+    pub data_offset: u64,
+    /// Total file size in bytes
+    pub file_size: u64,
+}
+
+/// A tensor described in a GGUF header
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GgufTensor {
+    /// Tensor name (e.g. "blk.0.attn_q.weight")
+    pub name: String,
+    /// Dimensions, innermost first
+    pub shape: Vec<u64>,
+    /// ggml element type name (e.g. "Q4_K", "F32")
+    pub ggml_type: String,
+    /// Offset from the start of the tensor data section
+    pub offset: u64,
+    /// Size of the tensor data in bytes
+    pub size_bytes: u64,
+}
+
+impl GgufTensor {
+    /// Number of elements in the tensor
+    pub fn element_count(&self) -> u64 {
+        self.shape.iter().product()
+    }
+}
+
+impl GgufMetadata {
+    /// Read and validate the header of a GGUF file
+    ///
+    /// Only the header is read; tensor data is checked for presence
+    /// (the file must be long enough to hold every tensor) but not loaded.
+    ///
+    /// # Errors
+    /// Returns [`ModelError::InvalidGguf`] if the file is not a valid GGUF
+    /// file, and [`ModelError::IoError`] if it cannot be opened.
+    #[instrument]
+    pub fn read(path: &Path) -> ModelResult<Self> {
+        let file = File::open(path)?;
+        let file_size = file.metadata()?.len();
+        Self::from_reader(BufReader::new(file), file_size).map_err(|e| match e {
+            ModelError::InvalidGguf(reason) => {
+                ModelError::InvalidGguf(format!("{}: {}", path.display(), reason))
+            },
+            other => other,
+        })
+    }
+
+    /// Read and validate a GGUF header from `reader`
+    ///
+    /// `file_size` is the total length of the file, used to bound lengths
+    /// read from the header and to detect truncated tensor data.
+    pub fn from_reader<R: Read>(reader: R, file_size: u64) -> ModelResult<Self> {
+        let mut reader = HeaderReader {
+            inner: reader,
+            position: 0,
+            file_size,
+        };
+        let (version, tensor_count, mut values) = reader.read_metadata(|_| false)?;
+
+        let architecture = match values.get("general.architecture") {
+            Some(Value::Str(arch)) => arch.clone(),
+            _ => return Err(invalid("missing general.architecture")),
+        };
+
+        let alignment = match values.get("general.alignment") {
+            None => DEFAULT_ALIGNMENT,
+            Some(value) => match value.as_u64() {
+                Some(a) if a > 0 && a.is_power_of_two() => a,
+                _ => return Err(invalid("general.alignment is not a power of two")),
+            },
+        };
+
+        let mut tensors = Vec::with_capacity(tensor_count.min(4096) as usize);
+        for _ in 0..tensor_count {
+            tensors.push(reader.read_tensor_info()?);
+        }
+
+        let data_offset = align_up(reader.position, alignment)
+            .ok_or_else(|| invalid("tensor data offset overflows"))?;
+
+        for tensor in &tensors {
+            if tensor.offset % alignment != 0 {
+                return Err(invalid(format!(
+                    "tensor {} is not aligned to {} bytes",
+                    tensor.name, alignment
+                )));
+            }
+            let end = data_offset
+                .checked_add(tensor.offset)
+                .and_then(|start| start.checked_add(tensor.size_bytes))
+                .ok_or_else(|| invalid(format!("tensor {} offset overflows", tensor.name)))?;
+            if end > file_size {
+                return Err(invalid(format!(
+                    "truncated: tensor {} ends at byte {} but file is {} bytes",
+                    tensor.name, end, file_size
+                )));
+            }
+        }
+
+        let parameter_count = tensors.iter().map(GgufTensor::element_count).sum();
+
+        let (file_type, quantization) =
+            match values.get("general.file_type").and_then(Value::as_u64) {
+                Some(ftype) => file_type_info(ftype),
+                None => dominant_tensor_type(&tensors),
+            };
+
+        let arch_u64 = |key: &str| {
+            values
+                .get(&format!("{}.{}", architecture, key))
+                .and_then(Value::as_u64)
+        };
+        let arch_u32 = |key: &str| arch_u64(key).and_then(|n| u32::try_from(n).ok());
+        let context_length = arch_u32("context_length");
+        let block_count = arch_u32("block_count");
+        let embedding_length = arch_u64("embedding_length");
+        let head_count = arch_u32("attention.head_count");
+        let head_count_kv = arch_u32("attention.head_count_kv");
+
+        let vocab_size = match values.get("tokenizer.ggml.tokens") {
+            Some(Value::Array(len)) => Some(*len),
+            _ => None,
+        };
+
+        let metadata = Self {
+            version,
+            name: values.remove("general.name").and_then(Value::into_string),
+            tokenizer: values
+                .remove("tokenizer.ggml.model")
+                .and_then(Value::into_string),
+            chat_template: values
+                .remove("tokenizer.chat_template")
+                .and_then(Value::into_string),
+            architecture,
+            parameter_count,
+            context_length,
+            block_count,
+            embedding_length,
+            head_count,
+            head_count_kv,
+            file_type,
+            quantization,
+            vocab_size,
+            tensors,
+            data_offset,
+            file_size,
+        };
+
+        debug!(
+            "Read GGUF v{} header: {} {} with {} tensors",
+            metadata.version,
+            metadata.architecture,
+            metadata.file_type,
+            metadata.tensors.len()
+        );
+
+        Ok(metadata)
+    }
+
+    /// Total size of all tensor data in bytes
+    pub fn tensor_bytes(&self) -> u64 {
+        self.tensors.iter().map(|t| t.size_bytes).sum()
+    }
+
+    /// Size of one token's keys and values in an F16 KV cache
+    ///
+    /// `None` if the header lacks the attention dimensions.
+    pub fn kv_bytes_per_token(&self) -> Option<u64> {
+        let layers = u64::from(self.block_count?);
+        let embedding = self.embedding_length?;
+        let heads = u64::from(self.head_count?).max(1);
+        let kv_heads = self.head_count_kv.map_or(heads, u64::from);
+        // K and V, 2 bytes per element
+        Some(2 * layers * embedding / heads * kv_heads * 2)
+    }
+
+    /// Parameter count in the registry's style (e.g. "3.8B", "22M")
+    pub fn parameters_label(&self) -> String {
+        format_parameter_count(self.parameter_count)
+    }
+}
+
+/// Tokenizer vocabulary stored in a GGUF header
+///
+/// Read separately from [`GgufMetadata`] because large vocabularies hold
+/// hundreds of thousands of strings that the header summary does not need.
+#[derive(Debug, Clone, Default)]
+pub struct GgufVocab {
+    /// Tokenizer model (`tokenizer.ggml.model`, e.g. "llama", "gpt2")
+    pub model: String,
+    /// Pre-tokenizer (`tokenizer.ggml.pre`, e.g. "llama-bpe")
+    pub pre: Option<String>,
+    /// Token strings, indexed by token id
+    pub tokens: Vec<String>,
+    /// Merge priority per token (SentencePiece vocabularies)
+    pub scores: Vec<f32>,
+    /// Token type per token (1 normal, 2 unknown, 3 control, 4 user
+    /// defined, 5 unused, 6 byte)
+    pub token_types: Vec<i32>,
+    /// BPE merges, highest priority first, as "left right"
+    pub merges: Vec<String>,
+    /// Beginning-of-sequence token
+    pub bos_token_id: Option<u32>,
+    /// End-of-sequence token
+    pub eos_token_id: Option<u32>,
+    /// Token for unknown input
+    pub unknown_token_id: Option<u32>,
+    /// Whether a space is prepended to the text before tokenizing
+    pub add_space_prefix: Option<bool>,
+}
+
+impl GgufVocab {
+    /// Read the tokenizer vocabulary from a GGUF file
+    ///
+    /// # Errors
+    /// Returns [`ModelError::InvalidGguf`] if the header is invalid or has
+    /// no `tokenizer.ggml.tokens`.
+    #[instrument]
+    pub fn read(path: &Path) -> ModelResult<Self> {
+        let file = File::open(path)?;
+        let file_size = file.metadata()?.len();
+        Self::from_reader(BufReader::new(file), file_size).map_err(|e| match e {
+            ModelError::InvalidGguf(reason) => {
+                ModelError::InvalidGguf(format!("{}: {}", path.display(), reason))
+            },
+            other => other,
+        })
+    }
+
+    /// Read the tokenizer vocabulary from a GGUF header in `reader`
+    pub fn from_reader<R: Read>(reader: R, file_size: u64) -> ModelResult<Self> {
+        const KEPT: [&str; 4] = [
+            "tokenizer.ggml.tokens",
+            "tokenizer.ggml.scores",
+            "tokenizer.ggml.token_type",
+            "tokenizer.ggml.merges",
+        ];
+
+        let mut reader = HeaderReader {
+            inner: reader,
+            position: 0,
+            file_size,
+        };
+        let (_, _, mut values) = reader.read_metadata(|key| KEPT.contains(&key))?;
+
+        let mut take_list = |key: &str| values.remove(key).map(Value::into_list);
+        let tokens: Vec<String> = take_list("tokenizer.ggml.tokens")
+            .ok_or_else(|| invalid("missing tokenizer.ggml.tokens"))?
+            .into_iter()
+            .map(|v| v.into_string().unwrap_or_default())
+            .collect();
+        let scores = take_list("tokenizer.ggml.scores")
+            .unwrap_or_default()
+            .iter()
+            .map(|v| v.as_f64().unwrap_or(0.0) as f32)
+            .collect();
+        let token_types = take_list("tokenizer.ggml.token_type")
+            .unwrap_or_default()
+            .iter()
+            .map(|v| v.as_i64().and_then(|n| i32::try_from(n).ok()).unwrap_or(1))
+            .collect();
+        let merges = take_list("tokenizer.ggml.merges")
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(Value::into_string)
+            .collect();
+
+        let token_id = |key: &str| {
+            values
+                .get(key)
+                .and_then(Value::as_u64)
+                .and_then(|n| u32::try_from(n).ok())
+                .filter(|&id| (id as usize) < tokens.len())
+        };
+        let bos_token_id = token_id("tokenizer.ggml.bos_token_id");
+        let eos_token_id = token_id("tokenizer.ggml.eos_token_id");
+        let unknown_token_id = token_id("tokenizer.ggml.unknown_token_id");
+        let add_space_prefix = match values.get("tokenizer.ggml.add_space_prefix") {
+            Some(Value::Bool(b)) => Some(*b),
+            _ => None,
+        };
+
+        Ok(Self {
+            model: values
+                .remove("tokenizer.ggml.model")
+                .and_then(Value::into_string)
+                .unwrap_or_else(|| "llama".to_string()),
+            pre: values
+                .remove("tokenizer.ggml.pre")
+                .and_then(Value::into_string),
+            tokens,
+            scores,
+            token_types,
+            merges,
+            bos_token_id,
+            eos_token_id,
+            unknown_token_id,
+            add_space_prefix,
+        })
+    }
+}
+
+/// Format a parameter count as "8B", "3.8B", "22M", ...
+pub fn format_parameter_count(count: u64) -> String {
+    let (value, unit) = match count {
+        n if n >= 1_000_000_000 => (n as f64 / 1e9, "B"),
+        n if n >= 1_000_000 => (n as f64 / 1e6, "M"),
+        n if n >= 1_000 => (n as f64 / 1e3, "K"),
+        n => return n.to_string(),
+    };
+    let label = format!("{:.1}", value);
+    format!("{}{}", label.trim_end_matches(".0"), unit)
+}
+
+fn invalid(reason: impl Into<String>) -> ModelError {
+    ModelError::InvalidGguf(reason.into())
+}
+
+fn align_up(offset: u64, alignment: u64) -> Option<u64> {
+    offset
+        .checked_add(alignment - 1)
+        .map(|n| n / alignment * alignment)
+}
+
+/// Metadata value, keeping only what the header summary needs
+enum Value {
+    Uint(u64),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    /// Array contents are skipped; only the length is kept
+    Array(u64),
+    /// Array contents, for keys read with contents kept
+    List(Vec<Value>),
+}
+
+impl Value {
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Uint(n) => Some(*n),
+            Value::Int(n) => u64::try_from(*n).ok(),
+            _ => None,
+        }
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Uint(n) => i64::try_from(*n).ok(),
+            Value::Int(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Float(n) => Some(*n),
+            Value::Uint(n) => Some(*n as f64),
+            Value::Int(n) => Some(*n as f64),
+            _ => None,
+        }
+    }
+
+    fn into_string(self) -> Option<String> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn into_list(self) -> Vec<Value> {
+        match self {
+            Value::List(items) => items,
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Reader that tracks its position and bounds lengths by the file size
+struct HeaderReader<R> {
+    inner: R,
+    position: u64,
+    file_size: u64,
+}
+
+impl<R: Read> HeaderReader<R> {
+    /// Read the fixed header and all metadata key/values
+    ///
+    /// Returns the version, tensor count and values. Array contents are
+    /// only kept for keys where `keep` returns true.
+    fn read_metadata(
+        &mut self,
+        keep: impl Fn(&str) -> bool,
+    ) -> ModelResult<(u32, u64, HashMap<String, Value>)> {
+        let mut magic = [0u8; 4];
+        self.read_exact(&mut magic)?;
+        if &magic != GGUF_MAGIC {
+            return Err(invalid("not a GGUF file (bad magic)"));
+        }
+
+        let version = self.read_u32()?;
+        if !(2..=3).contains(&version) {
+            return Err(invalid(format!("unsupported GGUF version {}", version)));
+        }
+
+        let tensor_count = self.read_u64()?;
+        let kv_count = self.read_u64()?;
+        // Every entry takes at least a few bytes, so counts larger than the
+        // rest of the file can only come from a corrupt header
+        if tensor_count > self.remaining() || kv_count > self.remaining() {
+            return Err(invalid("tensor or metadata count exceeds file size"));
+        }
+
+        let mut values = HashMap::new();
+        for _ in 0..kv_count {
+            let key = self.read_string()?;
+            let value_type = self.read_u32()?;
+            let value = self.read_value(value_type, 0, keep(&key))?;
+            values.insert(key, value);
+        }
+
+        Ok((version, tensor_count, values))
+    }
+
+    fn remaining(&self) -> u64 {
+        self.file_size.saturating_sub(self.position)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> ModelResult<()> {
+        self.inner.read_exact(buf).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                invalid(format!("truncated header at byte {}", self.position))
+            } else {
+                ModelError::IoError(e)
+            }
+        })?;
+        self.position += buf.len() as u64;
+        Ok(())
+    }
+
+    fn skip(&mut self, len: u64) -> ModelResult<()> {
+        if len > self.remaining() {
+            return Err(invalid(format!(
+                "truncated header at byte {}",
+                self.position
+            )));
+        }
+        let copied = std::io::copy(&mut (&mut self.inner).take(len), &mut std::io::sink())?;
+        self.position += copied;
+        if copied < len {
+            return Err(invalid(format!(
+                "truncated header at byte {}",
+                self.position
+            )));
+        }
+        Ok(())
+    }
+
+    fn read_array<const N: usize>(&mut self) -> ModelResult<[u8; N]> {
+        let mut buf = [0u8; N];
+        self.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn read_u32(&mut self) -> ModelResult<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_u64(&mut self) -> ModelResult<u64> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    fn read_string(&mut self) -> ModelResult<String> {
+        let len = self.read_u64()?;
+        if len > MAX_STRING_LEN {
+            return Err(invalid(format!(
+                "string length {} at byte {} is out of range",
+                len, self.position
+            )));
+        }
+        if len > self.remaining() {
+            return Err(invalid(format!(
+                "truncated header at byte {}",
+                self.position
+            )));
+        }
+        let mut buf = vec![0u8; len as usize];
+        self.read_exact(&mut buf)?;
+        String::from_utf8(buf).map_err(|_| invalid("metadata string is not valid UTF-8"))
+    }
+
+    fn read_value(&mut self, value_type: u32, depth: u32, keep: bool) -> ModelResult<Value> {
+        Ok(match value_type {
+            0 => Value::Uint(u64::from(self.read_array::<1>()?[0])),
+            1 => Value::Int(i64::from(i8::from_le_bytes(self.read_array()?))),
+            2 => Value::Uint(u64::from(u16::from_le_bytes(self.read_array()?))),
+            3 => Value::Int(i64::from(i16::from_le_bytes(self.read_array()?))),
+            4 => Value::Uint(u64::from(self.read_u32()?)),
+            5 => Value::Int(i64::from(i32::from_le_bytes(self.read_array()?))),
+            6 => Value::Float(f64::from(f32::from_le_bytes(self.read_array()?))),
+            7 => Value::Bool(self.read_array::<1>()?[0] != 0),
+            8 => Value::Str(self.read_string()?),
+            9 => {
+                if depth >= MAX_ARRAY_DEPTH {
+                    return Err(invalid("metadata arrays are nested too deeply"));
+                }
+                let item_type = self.read_u32()?;
+                let len = self.read_u64()?;
+                if len > self.remaining() {
+                    return Err(invalid(format!("array length {} exceeds file size", len)));
+                }
+                if keep {
+                    let mut items = Vec::with_capacity(len.min(1 << 20) as usize);
+                    for _ in 0..len {
+                        items.push(self.read_value(item_type, depth + 1, true)?);
+                    }
+                    return Ok(Value::List(items));
+                }
+                match fixed_value_size(item_type) {
+                    Some(size) => {
+                        let bytes = len
+                            .checked_mul(size)
+                            .ok_or_else(|| invalid("array size overflows"))?;
+                        self.skip(bytes)?;
+                    },
+                    None => {
+                        for _ in 0..len {
+                            self.read_value(item_type, depth + 1, false)?;
+                        }
+                    },
+                }
+                Value::Array(len)
+            },
+            10 => Value::Uint(self.read_u64()?),
+            11 => Value::Int(i64::from_le_bytes(self.read_array()?)),
+            12 => Value::Float(f64::from_le_bytes(self.read_array()?)),
+            other => return Err(invalid(format!("unknown metadata value type {}", other))),
+        })
+    }
+
+    fn read_tensor_info(&mut self) -> ModelResult<GgufTensor> {
+        let name = self.read_string()?;
+        let n_dims = self.read_u32()?;
+        if n_dims == 0 || n_dims > MAX_DIMS {
+            return Err(invalid(format!(
+                "tensor {} has {} dimensions",
+                name, n_dims
+            )));
+        }
+        let mut shape = Vec::with_capacity(n_dims as usize);
+        for _ in 0..n_dims {
+            shape.push(self.read_u64()?);
+        }
+        let type_id = self.read_u32()?;
+        let offset = self.read_u64()?;
+
+        let (ggml_type, block_size, type_size) = ggml_type_info(type_id)
+            .ok_or_else(|| invalid(format!("tensor {} has unknown ggml type {}", name, type_id)))?;
+
+        let elements = shape
+            .iter()
+            .try_fold(1u64, |acc, &dim| acc.checked_mul(dim))
+            .ok_or_else(|| invalid(format!("tensor {} is too large", name)))?;
+        if shape[0] % block_size != 0 {
+            return Err(invalid(format!(
+                "tensor {} row length {} is not a multiple of the {} block size",
+                name, shape[0], ggml_type
+            )));
+        }
+        let size_bytes = (elements / block_size)
+            .checked_mul(type_size)
+            .ok_or_else(|| invalid(format!("tensor {} is too large", name)))?;
+
+        Ok(GgufTensor {
+            name,
+            shape,
+            ggml_type: ggml_type.to_string(),
+            offset,
+            size_bytes,
+        })
+    }
+}
+
+/// Size of a fixed-width metadata value type, or `None` for strings/arrays
+fn fixed_value_size(value_type: u32) -> Option<u64> {
+    match value_type {
+        0 | 1 | 7 => Some(1),
+        2 | 3 => Some(2),
+        4..=6 => Some(4),
+        10..=12 => Some(8),
+        _ => None,
+    }
+}
+
+/// Name, block size (elements) and block size (bytes) of a ggml type
+fn ggml_type_info(type_id: u32) -> Option<(&'static str, u64, u64)> {
+    Some(match type_id {
+        0 => ("F32", 1, 4),
+        1 => ("F16", 1, 2),
+        2 => ("Q4_0", 32, 18),
+        3 => ("Q4_1", 32, 20),
+        6 => ("Q5_0", 32, 22),
+        7 => ("Q5_1", 32, 24),
+        8 => ("Q8_0", 32, 34),
+        9 => ("Q8_1", 32, 36),
+        10 => ("Q2_K", 256, 84),
+        11 => ("Q3_K", 256, 110),
+        12 => ("Q4_K", 256, 144),
+        13 => ("Q5_K", 256, 176),
+        14 => ("Q6_K", 256, 210),
+        15 => ("Q8_K", 256, 292),
+        16 => ("IQ2_XXS", 256, 66),
+        17 => ("IQ2_XS", 256, 74),
+        18 => ("IQ3_XXS", 256, 98),
+        19 => ("IQ1_S", 256, 50),
+        20 => ("IQ4_NL", 32, 18),
+        21 => ("IQ3_S", 256, 110),
+        22 => ("IQ2_S", 256, 82),
+        23 => ("IQ4_XS", 256, 136),
+        24 => ("I8", 1, 1),
+        25 => ("I16", 1, 2),
+        26 => ("I32", 1, 4),
+        27 => ("I64", 1, 8),
+        28 => ("F64", 1, 8),
+        29 => ("IQ1_M", 256, 56),
+        30 => ("BF16", 1, 2),
+        34 => ("TQ1_0", 256, 54),
+        35 => ("TQ2_0", 256, 66),
+        _ => return None,
+    })
+}
+
+/// Name and quantization level of a `general.file_type` value
+fn file_type_info(file_type: u64) -> (String, Option<Quantization>) {
+    let (name, quant) = match file_type {
+        0 => ("F32", None),
+        1 => ("F16", Some(Quantization::F16)),
+        2 => ("Q4_0", Some(Quantization::Q4)),
+        3 => ("Q4_1", Some(Quantization::Q4)),
+        7 => ("Q8_0", Some(Quantization::Q8)),
+        8 => ("Q5_0", Some(Quantization::Q5)),
+        9 => ("Q5_1", Some(Quantization::Q5)),
+        10 => ("Q2_K", None),
+        11 => ("Q3_K_S", None),
+        12 => ("Q3_K_M", None),
+        13 => ("Q3_K_L", None),
+        14 => ("Q4_K_S", Some(Quantization::Q4)),
+        15 => ("Q4_K_M", Some(Quantization::Q4)),
+        16 => ("Q5_K_S", Some(Quantization::Q5)),
+        17 => ("Q5_K_M", Some(Quantization::Q5)),
+        18 => ("Q6_K", None),
+        19 => ("IQ2_XXS", None),
+        20 => ("IQ2_XS", None),
+        21 => ("Q2_K_S", None),
+        22 => ("IQ3_XS", None),
+        23 => ("IQ3_XXS", None),
+        24 => ("IQ1_S", None),
+        25 => ("IQ4_NL", Some(Quantization::Q4)),
+        26 => ("IQ3_S", None),
+        27 => ("IQ3_M", None),
+        28 => ("IQ2_S", None),
+        29 => ("IQ2_M", None),
+        30 => ("IQ4_XS", Some(Quantization::Q4)),
+        31 => ("IQ1_M", None),
+        32 => ("BF16", None),
+        other => return (format!("unknown ({})", other), None),
+    };
+    (name.to_string(), quant)
+}
+
+/// Quantization inferred from the tensor type holding the most bytes
+///
+/// Used for files written without `general.file_type`.
+fn dominant_tensor_type(tensors: &[GgufTensor]) -> (String, Option<Quantization>) {
+    let mut bytes_by_type: HashMap<&str, u64> = HashMap::new();
+    for tensor in tensors {
+        *bytes_by_type.entry(&tensor.ggml_type).or_default() += tensor.size_bytes;
+    }
+    let Some((name, _)) = bytes_by_type
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+    else {
+        return ("unknown".to_string(), None);
+    };
+
+    let quant = match name {
+        "F16" => Some(Quantization::F16),
+        "Q4_0" | "Q4_1" | "Q4_K" | "IQ4_NL" | "IQ4_XS" => Some(Quantization::Q4),
+        "Q5_0" | "Q5_1" | "Q5_K" => Some(Quantization::Q5),
+        "Q8_0" => Some(Quantization::Q8),
+        _ => None,
+    };
+    (name.to_string(), quant)
+}
+
+/// Synthetic GGUF files for tests
+#[cfg(test)]
+pub(crate) mod fixture {
+    use std::path::Path;
+
+    /// Builder for a small, valid GGUF file
+    pub(crate) struct GgufFixture {
+        kv_count: u64,
+        kv_bytes: Vec<u8>,
+        tensors: Vec<(String, Vec<u64>, u32, u64)>,
+    }
+
+    impl GgufFixture {
+        /// Start a file with `general.architecture` set
+        pub(crate) fn new(architecture: &str) -> Self {
+            Self {
+                kv_count: 0,
+                kv_bytes: Vec::new(),
+                tensors: Vec::new(),
+            }
+            .string("general.architecture", architecture)
+        }
+
+        fn key(&mut self, key: &str, value_type: u32) {
+            self.kv_count += 1;
+            put_string(&mut self.kv_bytes, key);
+            self.kv_bytes.extend_from_slice(&value_type.to_le_bytes());
+        }
+
+        pub(crate) fn string(mut self, key: &str, value: &str) -> Self {
+            self.key(key, 8);
+            put_string(&mut self.kv_bytes, value);
+            self
+        }
+
+        pub(crate) fn u32(mut self, key: &str, value: u32) -> Self {
+            self.key(key, 4);
+            self.kv_bytes.extend_from_slice(&value.to_le_bytes());
+            self
+        }
+
+        /// A string array of `count` tokens
+        pub(crate) fn tokens(mut self, key: &str, count: u64) -> Self {
+            self.key(key, 9);
+            self.kv_bytes.extend_from_slice(&8u32.to_le_bytes());
+            self.kv_bytes.extend_from_slice(&count.to_le_bytes());
+            for i in 0..count {
+                put_string(&mut self.kv_bytes, &format!("tok{}", i));
+            }
+            self
+        }
+
+        fn array(&mut self, key: &str, element_type: u32, len: usize) {
+            self.key(key, 9);
+            self.kv_bytes.extend_from_slice(&element_type.to_le_bytes());
+            self.kv_bytes.extend_from_slice(&(len as u64).to_le_bytes());
+        }
+
+        pub(crate) fn strings(mut self, key: &str, values: &[&str]) -> Self {
+            self.array(key, 8, values.len());
+            for value in values {
+                put_string(&mut self.kv_bytes, value);
+            }
+            self
+        }
+
+        pub(crate) fn f32s(mut self, key: &str, values: &[f32]) -> Self {
+            self.array(key, 6, values.len());
+            for value in values {
+                self.kv_bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            self
+        }
+
+        pub(crate) fn i32s(mut self, key: &str, values: &[i32]) -> Self {
+            self.array(key, 5, values.len());
+            for value in values {
+                self.kv_bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            self
+        }
+
+        /// A tensor with the given shape, ggml type and block layout
+        pub(crate) fn tensor(mut self, name: &str, shape: &[u64], type_id: u32) -> Self {
+            let (_, block, size) = super::ggml_type_info(type_id).unwrap();
+            let bytes = shape.iter().product::<u64>() / block * size;
+            self.tensors
+                .push((name.to_string(), shape.to_vec(), type_id, bytes));
+            self
+        }
+
+        pub(crate) fn build(self) -> Vec<u8> {
+            let mut out = Vec::new();
+            out.extend_from_slice(b"GGUF");
+            out.extend_from_slice(&3u32.to_le_bytes());
+            out.extend_from_slice(&(self.tensors.len() as u64).to_le_bytes());
+            out.extend_from_slice(&self.kv_count.to_le_bytes());
+            out.extend_from_slice(&self.kv_bytes);
+
+            let mut offset = 0u64;
+            let mut data = Vec::new();
+            for (name, shape, type_id, bytes) in &self.tensors {
+                put_string(&mut out, name);
+                out.extend_from_slice(&(shape.len() as u32).to_le_bytes());
+                for dim in shape {
+                    out.extend_from_slice(&dim.to_le_bytes());
+                }
+                out.extend_from_slice(&type_id.to_le_bytes());
+                out.extend_from_slice(&offset.to_le_bytes());
+                data.resize(offset as usize, 0);
+                data.extend(std::iter::repeat_n(0xAB, *bytes as usize));
+                offset = (offset + bytes).div_ceil(32) * 32;
+            }
+
+            out.resize(out.len().div_ceil(32) * 32, 0);
+            out.extend_from_slice(&data);
+            out
+        }
+
+        pub(crate) fn write(self, path: &Path) {
+            std::fs::write(path, self.build()).unwrap();
+        }
+    }
+
+    fn put_string(out: &mut Vec<u8>, s: &str) {
+        out.extend_from_slice(&(s.len() as u64).to_le_bytes());
+        out.extend_from_slice(s.as_bytes());
+    }
+
+    /// A small llama-style model: 4 tensors, Q4_K_M, 2048 context, one
+    /// block with grouped-query attention
+    pub(crate) fn tiny_llama() -> GgufFixture {
+        GgufFixture::new("llama")
+            .string("general.name", "Tiny Llama")
+            .u32("general.file_type", 15)
+            .u32("llama.context_length", 2048)
+            .u32("llama.block_count", 1)
+            .u32("llama.embedding_length", 256)
+            .u32("llama.attention.head_count", 8)
+            .u32("llama.attention.head_count_kv", 2)
+            .string("tokenizer.ggml.model", "llama")
+            .tokens("tokenizer.ggml.tokens", 16)
+            .string(
+                "tokenizer.chat_template",
+                "{% for m in messages %}{{ m.content }}{% endfor %}",
+            )
+            .tensor("token_embd.weight", &[256, 16], 12)
+            .tensor("blk.0.attn_q.weight", &[256, 256], 12)
+            .tensor("output_norm.weight", &[256], 0)
+            .tensor("output.weight", &[256, 16], 14)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixture::{tiny_llama, GgufFixture};
+    use super::*;
+
+    fn parse(bytes: &[u8]) -> ModelResult<GgufMetadata> {
+        GgufMetadata::from_reader(bytes, bytes.len() as u64)
+    }
+
+    #[test]
+    fn test_read_synthetic_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tiny.gguf");
+        tiny_llama().write(&path);
+
+        let meta = GgufMetadata::read(&path).unwrap();
+        assert_eq!(meta.version, 3);
+        assert_eq!(meta.architecture, "llama");
+        assert_eq!(meta.name.as_deref(), Some("Tiny Llama"));
+        assert_eq!(meta.parameter_count, 256 * 16 + 256 * 256 + 256 + 256 * 16);
+        assert_eq!(meta.context_length, Some(2048));
+        assert_eq!(meta.block_count, Some(1));
+        // 1 layer, K and V, 2 of 8 heads of 256 dims, F16
+        assert_eq!(meta.kv_bytes_per_token(), Some(2 * 256 / 8 * 2 * 2));
+        assert_eq!(meta.file_type, "Q4_K_M");
+        assert_eq!(meta.quantization, Some(Quantization::Q4));
+        assert_eq!(meta.tokenizer.as_deref(), Some("llama"));
+        assert_eq!(meta.vocab_size, Some(16));
+        assert!(meta.chat_template.as_deref().unwrap().contains("messages"));
+
+        assert_eq!(meta.tensors.len(), 4);
+        let q = &meta.tensors[1];
+        assert_eq!(q.name, "blk.0.attn_q.weight");
+        assert_eq!(q.ggml_type, "Q4_K");
+        assert_eq!(q.size_bytes, 256 * 256 / 256 * 144);
+        assert_eq!(meta.tensors[2].size_bytes, 256 * 4);
+        assert_eq!(meta.data_offset % 32, 0);
+        assert!(meta.data_offset + meta.tensor_bytes() <= meta.file_size);
+    }
+
+    #[test]
+    fn test_quantization_from_tensor_types() {
+        let bytes = GgufFixture::new("bert")
+            .tensor("a", &[64, 4], 1)
+            .tensor("b", &[32], 0)
+            .build();
+        let meta = parse(&bytes).unwrap();
+        assert_eq!(meta.file_type, "F16");
+        assert_eq!(meta.quantization, Some(Quantization::F16));
+        assert_eq!(meta.context_length, None);
+        assert_eq!(meta.kv_bytes_per_token(), None);
+    }
+
+    #[test]
+    fn test_rejects_bad_magic_and_version() {
+        let mut bytes = tiny_llama().build();
+        bytes[0] = b'X';
+        assert!(matches!(parse(&bytes), Err(ModelError::InvalidGguf(_))));
+
+        let mut bytes = tiny_llama().build();
+        bytes[4..8].copy_from_slice(&1u32.to_le_bytes());
+        let err = parse(&bytes).unwrap_err();
+        assert!(err.to_string().contains("version 1"));
+    }
+
+    #[test]
+    fn test_rejects_truncated_files() {
+        let bytes = tiny_llama().build();
+
+        // Cut inside the tensor data
+        let err = parse(&bytes[..bytes.len() - 10]).unwrap_err();
+        assert!(err.to_string().contains("truncated"), "{}", err);
+
+        // Cut inside the header
+        let err = parse(&bytes[..40]).unwrap_err();
+        assert!(err.to_string().contains("truncated"), "{}", err);
+
+        assert!(parse(b"GGUF").is_err());
+        assert!(parse(b"").is_err());
+    }
+
+    #[test]
+    fn test_rejects_corrupt_header() {
+        // Huge string length right after the counts
+        let mut bytes = tiny_llama().build();
+        bytes[24..32].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(parse(&bytes), Err(ModelError::InvalidGguf(_))));
+
+        // Absurd tensor count
+        let mut bytes = tiny_llama().build();
+        bytes[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(parse(&bytes), Err(ModelError::InvalidGguf(_))));
+
+        // No architecture
+        let mut bytes = b"GGUF".to_vec();
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        let err = parse(&bytes).unwrap_err();
+        assert!(err.to_string().contains("general.architecture"));
+    }
+
+    #[test]
+    fn test_read_reports_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.gguf");
+        std::fs::write(&path, b"not a model").unwrap();
+
+        let err = GgufMetadata::read(&path).unwrap_err();
+        assert!(err.to_string().contains("broken.gguf"));
+    }
+
+    #[test]
+    fn test_format_parameter_count() {
+        assert_eq!(format_parameter_count(8_030_261_248), "8B");
+        assert_eq!(format_parameter_count(3_821_079_552), "3.8B");
+        assert_eq!(format_parameter_count(22_700_000), "22.7M");
+        assert_eq!(format_parameter_count(512), "512");
+    }
+}
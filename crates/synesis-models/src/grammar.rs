@@ -0,0 +1,429 @@
+//! Constrained Decoding
+//!
+//! Restricts what a model may generate so structured answers always parse.
+//! An [`OutputConstraint`] is either a JSON schema or a hand-written GBNF
+//! grammar (llama.cpp's grammar format). Schemas are compiled to GBNF for
+//! the sampler and also checked against the finished output, so a caller
+//! that receives `Ok` from inference gets JSON matching its schema.
+//!
+//! # Supported schema keywords
+//!
+//! `type` (`object`, `array`, `string`, `number`, `integer`, `boolean`,
+//! `null`), `properties`, `required`, `additionalProperties: false`,
+//! `items`, `enum` (of strings), `minimum` and `maximum`. Every listed
+//! property is generated, sorted by key. Other keywords (`anyOf`, `$ref`,
+//! `pattern`, …) are rejected with [`ModelError::InvalidGrammar`] rather than
+//! silently ignored.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::{ModelError, ModelResult};
+
+/// Shared rules for JSON primitives and whitespace
+const PRIMITIVE_RULES: &[(&str, &str)] = &[
+    ("ws", r#"| " " | "\n" [ \t]{0,20}"#),
+    (
+        "string",
+        r#""\"" ( [^"\\\x7F\x00-\x1F] | "\\" ( ["\\/bfnrt] | "u" [0-9a-fA-F]{4} ) )* "\"" ws"#,
+    ),
+    (
+        "number",
+        r#""-"? ( [0-9] | [1-9] [0-9]{0,15} ) ( "." [0-9]+ )? ( [eE] [-+]? [0-9]{1,3} )? ws"#,
+    ),
+    ("integer", r#""-"? ( [0-9] | [1-9] [0-9]{0,15} ) ws"#),
+    ("boolean", r#"( "true" | "false" ) ws"#),
+    ("null", r#""null" ws"#),
+];
+
+/// Restriction on a model's output
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputConstraint {
+    /// Output is a JSON value matching this schema
+    JsonSchema(Value),
+    /// Output matches this GBNF grammar (must define `root`)
+    Grammar(String),
+}
+
+impl OutputConstraint {
+    /// GBNF grammar to hand to the sampler
+    ///
+    /// # Errors
+    /// Returns [`ModelError::InvalidGrammar`] for schemas using unsupported
+    /// keywords and grammars without a `root` rule.
+    pub fn to_gbnf(&self) -> ModelResult<String> {
+        match self {
+            OutputConstraint::JsonSchema(schema) => json_schema_to_gbnf(schema),
+            OutputConstraint::Grammar(grammar) => {
+                let has_root = grammar.lines().any(|line| {
+                    line.trim_start().starts_with("root ")
+                        || line.trim_start().starts_with("root::=")
+                });
+                if !has_root {
+                    return Err(ModelError::InvalidGrammar(
+                        "grammar has no root rule".to_string(),
+                    ));
+                }
+                Ok(grammar.clone())
+            },
+        }
+    }
+
+    /// Check finished output against the constraint
+    ///
+    /// JSON schemas are validated in full. Grammars are enforced while
+    /// sampling and not re-checked here.
+    ///
+    /// # Errors
+    /// Returns [`ModelError::ConstraintViolated`] if the output is not JSON
+    /// or does not match the schema.
+    pub fn check(&self, output: &str) -> ModelResult<()> {
+        let OutputConstraint::JsonSchema(schema) = self else {
+            return Ok(());
+        };
+        let value: Value = serde_json::from_str(output.trim())
+            .map_err(|e| ModelError::ConstraintViolated(format!("output is not JSON: {}", e)))?;
+        validate_json(schema, &value).map_err(ModelError::ConstraintViolated)
+    }
+}
+
+/// Compile a JSON schema to a GBNF grammar
+///
+/// Each object property gets its own rule, named after its path (e.g.
+/// `intent-query-type`), which keeps sampler errors readable.
+pub fn json_schema_to_gbnf(schema: &Value) -> ModelResult<String> {
+    let mut rules = BTreeMap::new();
+    let root = schema_rule(schema, "root", &mut rules)?;
+    if root != "root" {
+        rules.insert("root".to_string(), root);
+    }
+
+    let mut grammar = format!("root ::= {}\n", rules.remove("root").unwrap_or_default());
+    for (name, body) in rules {
+        grammar.push_str(&format!("{} ::= {}\n", name, body));
+    }
+    for (name, body) in PRIMITIVE_RULES {
+        grammar.push_str(&format!("{} ::= {}\n", name, body));
+    }
+    Ok(grammar)
+}
+
+/// Body (or primitive rule name) matching `schema`; named rules for
+/// objects are added to `rules`
+fn schema_rule(
+    schema: &Value,
+    name: &str,
+    rules: &mut BTreeMap<String, String>,
+) -> ModelResult<String> {
+    let schema = schema
+        .as_object()
+        .ok_or_else(|| unsupported(name, "schema is not an object"))?;
+    for key in schema.keys() {
+        if !matches!(
+            key.as_str(),
+            "type"
+                | "properties"
+                | "required"
+                | "additionalProperties"
+                | "items"
+                | "enum"
+                | "minimum"
+                | "maximum"
+                | "description"
+                | "title"
+                | "$schema"
+        ) {
+            return Err(unsupported(name, &format!("keyword {:?}", key)));
+        }
+    }
+
+    if let Some(values) = schema.get("enum") {
+        let values = values
+            .as_array()
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| unsupported(name, "enum is not a non-empty array"))?;
+        let literals = values
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(|s| gbnf_literal(&Value::String(s.to_string()).to_string()))
+                    .ok_or_else(|| unsupported(name, "enum values must be strings"))
+            })
+            .collect::<ModelResult<Vec<_>>>()?;
+        return Ok(format!("( {} ) ws", literals.join(" | ")));
+    }
+
+    let schema_type = schema
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or_else(|| unsupported(name, "missing type"))?;
+    match schema_type {
+        "string" | "number" | "integer" | "boolean" | "null" => Ok(schema_type.to_string()),
+        "array" => {
+            let items = schema
+                .get("items")
+                .ok_or_else(|| unsupported(name, "array without items"))?;
+            let item = schema_rule(items, &format!("{}-item", name), rules)?;
+            Ok(format!(
+                "\"[\" ws ( {item} ( \",\" ws {item} )* )? \"]\" ws",
+                item = group(&item)
+            ))
+        },
+        "object" => {
+            let properties: BTreeMap<&String, &Value> = schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .map(|p| p.iter().collect())
+                .unwrap_or_default();
+            let mut members = Vec::new();
+            for (key, property) in properties {
+                let rule_name = format!("{}-{}", name, rule_name(key));
+                let body = schema_rule(property, &rule_name, rules)?;
+                let value = if is_primitive(&body) {
+                    body
+                } else {
+                    rules.insert(rule_name.clone(), body);
+                    rule_name
+                };
+                members.push(format!(
+                    "{} ws \":\" ws {}",
+                    gbnf_literal(&Value::String(key.clone()).to_string()),
+                    value
+                ));
+            }
+
+            let body = if members.is_empty() {
+                "\"{\" ws \"}\" ws".to_string()
+            } else {
+                format!("\"{{\" ws {} \"}}\" ws", members.join(" \",\" ws "))
+            };
+            if name == "root" {
+                rules.insert(name.to_string(), body);
+                Ok(name.to_string())
+            } else {
+                Ok(body)
+            }
+        },
+        other => Err(unsupported(name, &format!("type {:?}", other))),
+    }
+}
+
+/// Validate a JSON value against a schema, naming the first mismatch
+pub fn validate_json(schema: &Value, value: &Value) -> Result<(), String> {
+    validate_at(schema, value, "$")
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        if !values.contains(value) {
+            return Err(format!(
+                "{}: {} is not one of {}",
+                path,
+                value,
+                Value::Array(values.clone())
+            ));
+        }
+        return Ok(());
+    }
+
+    let Some(schema_type) = schema.get("type").and_then(Value::as_str) else {
+        return Ok(());
+    };
+    let type_matches = match schema_type {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    };
+    if !type_matches {
+        return Err(format!("{}: expected {}, got {}", path, schema_type, value));
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+            if number < min {
+                return Err(format!("{}: {} is below minimum {}", path, number, min));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+            if number > max {
+                return Err(format!("{}: {} is above maximum {}", path, number, max));
+            }
+        }
+    }
+
+    if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in values.iter().enumerate() {
+            validate_at(items, item, &format!("{}[{}]", path, i))?;
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(key) {
+                    return Err(format!("{}: missing property {:?}", path, key));
+                }
+            }
+        }
+        for (key, member) in object {
+            match properties.and_then(|p| p.get(key)) {
+                Some(property) => validate_at(property, member, &format!("{}.{}", path, key))?,
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    return Err(format!("{}: unexpected property {:?}", path, key));
+                },
+                None => {},
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// GBNF string literal matching `text` exactly
+fn gbnf_literal(text: &str) -> String {
+    let mut literal = String::with_capacity(text.len() + 2);
+    literal.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Rule name for a property: GBNF names allow letters, digits and dashes
+fn rule_name(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn is_primitive(body: &str) -> bool {
+    PRIMITIVE_RULES.iter().any(|(name, _)| *name == body)
+}
+
+/// Parenthesize a body so it can be repeated
+fn group(body: &str) -> String {
+    if is_primitive(body) || body.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        body.to_string()
+    } else {
+        format!("( {} )", body)
+    }
+}
+
+fn unsupported(name: &str, reason: &str) -> ModelError {
+    ModelError::InvalidGrammar(format!("{}: unsupported schema ({})", name, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn verdict_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "verdict": {"enum": ["approved", "veto"]},
+                "confidence": {"type": "number", "minimum": 0, "maximum": 1},
+                "concerns": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {"description": {"type": "string"}},
+                        "required": ["description"]
+                    }
+                }
+            },
+            "required": ["verdict", "confidence", "concerns"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn test_json_schema_to_gbnf() {
+        let grammar = json_schema_to_gbnf(&verdict_schema()).unwrap();
+        let lines: Vec<&str> = grammar.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            r#"root ::= "{" ws "\"concerns\"" ws ":" ws root-concerns "," ws "\"confidence\"" ws ":" ws number "," ws "\"verdict\"" ws ":" ws root-verdict "}" ws"#
+        );
+        assert!(lines.contains(
+            &r#"root-concerns ::= "[" ws ( ( "{" ws "\"description\"" ws ":" ws string "}" ws ) ( "," ws ( "{" ws "\"description\"" ws ":" ws string "}" ws ) )* )? "]" ws"#
+        ));
+        assert!(lines.contains(&r#"root-verdict ::= ( "\"approved\"" | "\"veto\"" ) ws"#));
+        assert!(lines.iter().any(|l| l.starts_with("string ::= ")));
+
+        let unsupported = json!({"anyOf": [{"type": "string"}]});
+        assert!(matches!(
+            json_schema_to_gbnf(&unsupported),
+            Err(ModelError::InvalidGrammar(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_output() {
+        let constraint = OutputConstraint::JsonSchema(verdict_schema());
+        constraint
+            .check(r#" {"verdict": "veto", "confidence": 0.9, "concerns": [{"description": "rm -rf"}]} "#)
+            .unwrap();
+
+        for (output, reason) in [
+            ("Sure! Here is the JSON", "not JSON"),
+            (
+                r#"{"verdict": "maybe", "confidence": 0.5, "concerns": []}"#,
+                "$.verdict",
+            ),
+            (
+                r#"{"verdict": "veto", "confidence": 1.5, "concerns": []}"#,
+                "above maximum",
+            ),
+            (
+                r#"{"verdict": "veto", "confidence": 1, "concerns": [{}]}"#,
+                "$.concerns[0]: missing",
+            ),
+            (
+                r#"{"verdict": "veto", "confidence": 1}"#,
+                "missing property \"concerns\"",
+            ),
+            (
+                r#"{"verdict": "veto", "confidence": 1, "concerns": [], "x": 1}"#,
+                "unexpected property",
+            ),
+        ] {
+            let err = constraint.check(output).unwrap_err().to_string();
+            assert!(err.contains(reason), "{}: {}", output, err);
+        }
+    }
+
+    #[test]
+    fn test_grammar_constraint() {
+        let grammar = OutputConstraint::Grammar("root ::= \"yes\" | \"no\"\n".to_string());
+        assert!(grammar.to_gbnf().unwrap().starts_with("root ::="));
+        // Grammars are enforced by the sampler, not re-checked
+        assert!(grammar.check("anything").is_ok());
+
+        let no_root = OutputConstraint::Grammar("answer ::= \"yes\"".to_string());
+        assert!(no_root.to_gbnf().is_err());
+    }
+}
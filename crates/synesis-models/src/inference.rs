@@ -4,12 +4,21 @@
 //! Supports GGUF format via llama.cpp bindings.
 
 use std::path::PathBuf;
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use tracing::{debug, info, instrument};
-
+use std::sync::{Arc, Mutex, MutexGuard};
+use tracing::{debug, info, instrument, warn};
+
+use crate::chat::{ChatMessage, ChatTemplate};
+use crate::gguf::GgufMetadata;
+use crate::grammar::OutputConstraint;
+use crate::pool::MemoryEstimate;
+use crate::prefix_cache::{PrefixCache, PrefixCacheStats};
+use crate::tokenizer::{EstimatedTokens, TokenCounter, TokenSplit, Tokenizer};
 use crate::{ModelError, ModelResult};
 
+/// Prompt tokens kept in each model's prefix cache by default: a few
+/// full 4K-token prompts, enough for Logos and Ethos across rounds
+const DEFAULT_PREFIX_CACHE_TOKENS: usize = 16384;
+
 /// Inference request
 #[derive(Debug, Clone)]
 pub struct InferenceRequest {
@@ -29,6 +38,8 @@ pub struct InferenceRequest {
     pub stop_sequences: Vec<String>,
     /// Whether to stream output
     pub stream: bool,
+    /// Restriction on the output (JSON schema or GBNF grammar)
+    pub constraint: Option<OutputConstraint>,
 }
 
 impl Default for InferenceRequest {
@@ -42,6 +53,7 @@ impl Default for InferenceRequest {
             repeat_penalty: 1.1,
             stop_sequences: vec![],
             stream: false,
+            constraint: None,
         }
     }
 }
@@ -68,6 +80,18 @@ impl InferenceRequest {
         self.stop_sequences = sequences;
         self
     }
+
+    /// Constrain the output to JSON matching `schema`
+    pub fn with_json_schema(mut self, schema: serde_json::Value) -> Self {
+        self.constraint = Some(OutputConstraint::JsonSchema(schema));
+        self
+    }
+
+    /// Constrain the output to a GBNF grammar
+    pub fn with_grammar(mut self, grammar: impl Into<String>) -> Self {
+        self.constraint = Some(OutputConstraint::Grammar(grammar.into()));
+        self
+    }
 }
 
 /// Inference response
@@ -79,6 +103,8 @@ pub struct InferenceResponse {
     pub tokens_generated: u32,
     /// Tokens in prompt
     pub prompt_tokens: u32,
+    /// Leading prompt tokens that matched a cached prefix
+    pub cached_tokens: u32,
     /// Generation time in milliseconds
     pub generation_time_ms: u64,
     /// Tokens per second
@@ -114,6 +140,17 @@ pub struct ModelInstance {
     context_size: u32,
     /// GPU layers (0 = CPU only)
     gpu_layers: u32,
+    /// Header of the model file, read when loading
+    metadata: Option<GgufMetadata>,
+    /// Tokenizer, from the GGUF vocabulary or a `tokenizer.json` beside it
+    tokenizer: Option<Arc<Tokenizer>>,
+    /// Prompt format, detected from the header unless set explicitly
+    chat_template: Option<ChatTemplate>,
+    /// Recently evaluated prompt prefixes
+    prefix_cache: Mutex<PrefixCache>,
+    /// Tokens of the last prompt up to its split, so a prompt extending it
+    /// only encodes what was appended
+    last_prompt: Mutex<Option<(String, Vec<u32>)>>,
     // TODO: Add actual model handle when integrating with llama.cpp
     // model: Option<llama_cpp::Model>,
 }
@@ -127,6 +164,11 @@ impl ModelInstance {
             loaded: false,
             context_size: 4096,
             gpu_layers: 0,
+            metadata: None,
+            tokenizer: None,
+            chat_template: None,
+            prefix_cache: Mutex::new(PrefixCache::new(DEFAULT_PREFIX_CACHE_TOKENS)),
+            last_prompt: Mutex::new(None),
         }
     }
 
@@ -142,7 +184,29 @@ impl ModelInstance {
         self
     }
 
+    /// Use this tokenizer instead of the one stored with the model
+    pub fn with_tokenizer(mut self, tokenizer: Tokenizer) -> Self {
+        self.tokenizer = Some(Arc::new(tokenizer));
+        self
+    }
+
+    /// Use this prompt format instead of detecting it from the header
+    pub fn with_chat_template(mut self, template: ChatTemplate) -> Self {
+        self.chat_template = Some(template);
+        self
+    }
+
+    /// Keep up to `tokens` prompt tokens in the prefix cache (0 disables it)
+    pub fn with_prefix_cache(mut self, tokens: usize) -> Self {
+        self.prefix_cache = Mutex::new(PrefixCache::new(tokens));
+        self
+    }
+
     /// Load the model into memory
+    ///
+    /// The GGUF header is validated first, so corrupt or truncated files
+    /// fail here with [`ModelError::InvalidGguf`]. The context size is
+    /// capped at the model's trained context length.
     #[instrument(skip(self))]
     pub async fn load(&mut self) -> ModelResult<()> {
         info!("Loading model: {} from {:?}", self.name, self.path);
@@ -151,6 +215,39 @@ impl ModelInstance {
             return Err(ModelError::NotFound(self.path.display().to_string()));
         }
 
+        let path = self.path.clone();
+        let metadata = tokio::task::spawn_blocking(move || GgufMetadata::read(&path))
+            .await
+            .map_err(|e| ModelError::Internal(format!("Header read task failed: {}", e)))??;
+
+        if let Some(trained) = metadata.context_length {
+            if self.context_size > trained {
+                warn!(
+                    "Context size {} exceeds {}'s trained context {}, using {}",
+                    self.context_size, self.name, trained, trained
+                );
+                self.context_size = trained;
+            }
+        }
+        debug!(
+            "{}: {} {} ({} parameters)",
+            self.name,
+            metadata.architecture,
+            metadata.file_type,
+            metadata.parameters_label()
+        );
+
+        if self.tokenizer.is_none() {
+            let path = self.path.clone();
+            let has_vocab = metadata.tokenizer.is_some();
+            self.tokenizer =
+                tokio::task::spawn_blocking(move || Tokenizer::for_model_file(&path, has_vocab))
+                .await
+                .map_err(|e| ModelError::Internal(format!("Tokenizer load task failed: {}", e)))?
+                .map(Arc::new);
+        }
+        self.metadata = Some(metadata);
+
         // TODO: Actually load the model using llama.cpp bindings
         // self.model = Some(llama_cpp::Model::load(&self.path, params)?);
 
@@ -164,6 +261,8 @@ impl ModelInstance {
     pub fn unload(&mut self) {
         info!("Unloading model: {}", self.name);
         // TODO: Drop model handle
+        self.lock_prefix_cache().clear();
+        *lock(&self.last_prompt) = None;
         self.loaded = false;
     }
 
@@ -177,7 +276,139 @@ impl ModelInstance {
         &self.name
     }
 
+    /// Get the context size used for inference
+    pub fn context_size(&self) -> u32 {
+        self.context_size
+    }
+
+    /// Get the number of layers offloaded to the GPU
+    pub fn gpu_layers(&self) -> u32 {
+        self.gpu_layers
+    }
+
+    /// Estimate the RAM and VRAM this model needs once loaded
+    ///
+    /// Uses the GGUF header (read from disk if the model is not loaded yet),
+    /// so the pool can make room before loading. Fails like [`load`] for
+    /// missing or corrupt files.
+    ///
+    /// [`load`]: Self::load
+    pub async fn estimate_memory(&self) -> ModelResult<MemoryEstimate> {
+        if let Some(metadata) = &self.metadata {
+            return Ok(MemoryEstimate::for_model(
+                metadata,
+                self.context_size,
+                self.gpu_layers,
+            ));
+        }
+
+        if !self.path.exists() {
+            return Err(ModelError::NotFound(self.path.display().to_string()));
+        }
+        let path = self.path.clone();
+        let metadata = tokio::task::spawn_blocking(move || GgufMetadata::read(&path))
+            .await
+            .map_err(|e| ModelError::Internal(format!("Header read task failed: {}", e)))??;
+
+        Ok(MemoryEstimate::for_model(
+            &metadata,
+            self.context_size,
+            self.gpu_layers,
+        ))
+    }
+
+    /// Get the GGUF header, once the model has been loaded
+    pub fn metadata(&self) -> Option<&GgufMetadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Get the tokenizer, once loaded or set with [`with_tokenizer`]
+    ///
+    /// [`with_tokenizer`]: Self::with_tokenizer
+    pub fn tokenizer(&self) -> Option<&Arc<Tokenizer>> {
+        self.tokenizer.as_ref()
+    }
+
+    /// Token counter for this model, falling back to an estimate when no
+    /// tokenizer is available
+    pub fn token_counter(&self) -> Arc<dyn TokenCounter> {
+        match &self.tokenizer {
+            Some(tokenizer) => tokenizer.clone(),
+            None => Arc::new(EstimatedTokens),
+        }
+    }
+
+    /// Count the tokens `text` takes in this model's context
+    pub fn count_tokens(&self, text: &str) -> usize {
+        match &self.tokenizer {
+            Some(tokenizer) => tokenizer.count_tokens(text),
+            None => EstimatedTokens.count_tokens(text),
+        }
+    }
+
+    /// Prompt format of this model
+    ///
+    /// An explicit template wins, then the one detected from the GGUF
+    /// header, then a guess from the model name.
+    pub fn chat_template(&self) -> ChatTemplate {
+        self.chat_template
+            .or_else(|| self.metadata.as_ref().map(ChatTemplate::detect))
+            .unwrap_or_else(|| ChatTemplate::for_model(&self.name))
+    }
+
+    /// Render a conversation into a prompt in this model's format, ending
+    /// with an open assistant turn
+    pub fn format_chat(&self, messages: &[ChatMessage]) -> String {
+        self.chat_template().render(messages, true)
+    }
+
+    /// Hit and miss counts of the prefix cache
+    pub fn prefix_cache_stats(&self) -> PrefixCacheStats {
+        self.lock_prefix_cache().stats()
+    }
+
+    fn lock_prefix_cache(&self) -> MutexGuard<'_, PrefixCache> {
+        lock(&self.prefix_cache)
+    }
+
+    /// Look up the prompt's longest cached prefix
+    ///
+    /// Returns the prompt's token count and how many leading tokens matched.
+    /// A prompt that extends the last one reuses its tokens up to their
+    /// [`TokenSplit`]. Without a tokenizer there are no token ids to key
+    /// on, so nothing is cached.
+    fn match_prefix(&self, prompt: &str) -> (usize, usize) {
+        let Some(tokenizer) = &self.tokenizer else {
+            return (EstimatedTokens.count_tokens(prompt), 0);
+        };
+
+        let mut last_prompt = lock(&self.last_prompt);
+        let (tokens, split) = match last_prompt.as_ref() {
+            Some((text, tokens)) if prompt.starts_with(text.as_str()) => {
+                let split = TokenSplit {
+                    bytes: text.len(),
+                    tokens: tokens.len(),
+                };
+                tokenizer.encode_after(prompt, tokens, split)
+            },
+            _ => tokenizer.encode_with_split(prompt),
+        };
+        let cached = self.lock_prefix_cache().lookup(&tokens);
+        let count = tokens.len();
+
+        *last_prompt = Some((
+            prompt[..split.bytes].to_string(),
+            tokens[..split.tokens].to_vec(),
+        ));
+        (count, cached)
+    }
+
     /// Run inference
+    ///
+    /// With a [`constraint`](InferenceRequest::constraint), the grammar is
+    /// compiled before generating and JSON output is validated after, so
+    /// output that does not match fails with
+    /// [`ModelError::ConstraintViolated`] instead of reaching the caller.
     #[instrument(skip(self, request, token_callback))]
     pub async fn infer(
         &self,
@@ -190,9 +421,24 @@ impl ModelInstance {
 
         debug!("Running inference with {} max tokens", request.max_tokens);
 
+        let _grammar = match &request.constraint {
+            Some(constraint) => {
+                let grammar = constraint.to_gbnf()?;
+                debug!("Constraining output with a {} byte grammar", grammar.len());
+                Some(grammar)
+            },
+            None => None,
+        };
+
         let start = std::time::Instant::now();
 
-        // TODO: Actually run inference
+        let (prompt_tokens, cached_tokens) = self.match_prefix(&request.prompt);
+        debug!(
+            "{} of {} prompt tokens match a cached prefix",
+            cached_tokens, prompt_tokens
+        );
+
+        // TODO: Actually run inference, sampling with `_grammar`
         // For now, return a placeholder response
         let generated_text = format!(
             "[Placeholder response for: {}]",
@@ -207,8 +453,12 @@ impl ModelInstance {
             }
         }
 
+        if let Some(constraint) = &request.constraint {
+            constraint.check(&generated_text)?;
+        }
+
         let elapsed = start.elapsed();
-        let tokens_generated = generated_text.split_whitespace().count() as u32;
+        let tokens_generated = self.count_tokens(&generated_text) as u32;
         let tokens_per_second = if elapsed.as_secs_f32() > 0.0 {
             tokens_generated as f32 / elapsed.as_secs_f32()
         } else {
@@ -218,7 +468,8 @@ impl ModelInstance {
         Ok(InferenceResponse {
             text: generated_text,
             tokens_generated,
-            prompt_tokens: request.prompt.split_whitespace().count() as u32,
+            prompt_tokens: prompt_tokens as u32,
+            cached_tokens: cached_tokens as u32,
             generation_time_ms: elapsed.as_millis() as u64,
             tokens_per_second,
             stop_reason: StopReason::EndOfSequence,
@@ -242,93 +493,54 @@ impl ModelInstance {
 
         Ok(embedding)
     }
-}
-
-/// Model pool for managing multiple loaded models
-pub struct ModelPool {
-    models: Arc<Mutex<std::collections::HashMap<String, ModelInstance>>>,
-    max_loaded: usize,
-}
 
-impl ModelPool {
-    pub fn new(max_loaded: usize) -> Self {
-        Self {
-            models: Arc::new(Mutex::new(std::collections::HashMap::new())),
-            max_loaded,
+    /// Score query/passage pairs with a cross-encoder
+    ///
+    /// Returns one relevance score in `0.0..=1.0` per passage, in order.
+    #[instrument(skip(self, query, passages))]
+    pub async fn rerank(&self, query: &str, passages: &[&str]) -> ModelResult<Vec<f32>> {
+        if !self.loaded {
+            return Err(ModelError::NotLoaded(self.name.clone()));
         }
-    }
 
-    /// Add a model to the pool
-    pub async fn add(&self, name: String, path: PathBuf) -> ModelResult<()> {
-        let mut models = self.models.lock().await;
-        models.insert(name.clone(), ModelInstance::new(name, path));
-        Ok(())
-    }
+        debug!("Reranking {} passages", passages.len());
 
-    /// Load a model
-    pub async fn load(&self, name: &str) -> ModelResult<()> {
-        let mut models = self.models.lock().await;
+        // TODO: Run the cross-encoder (rank pooling) via llama.cpp
+        // Placeholder: fraction of query terms found in the passage
+        let terms: Vec<String> = query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|t| !t.is_empty())
+            .map(str::to_lowercase)
+            .collect();
 
-        // Check if we need to unload a model
-        let loaded_count = models.values().filter(|m| m.is_loaded()).count();
-        if loaded_count >= self.max_loaded {
-            // Find LRU model to unload
-            // TODO: Implement proper LRU tracking
-            for (_, model) in models.iter_mut() {
-                if model.is_loaded() {
-                    model.unload();
-                    break;
+        Ok(passages
+            .iter()
+            .map(|passage| {
+                if terms.is_empty() {
+                    return 0.0;
                 }
-            }
-        }
-
-        if let Some(model) = models.get_mut(name) {
-            model.load().await
-        } else {
-            Err(ModelError::NotFound(name.to_string()))
-        }
-    }
-
-    /// Run inference on a specific model
-    pub async fn infer(
-        &self,
-        model_name: &str,
-        request: InferenceRequest,
-        token_callback: Option<TokenCallback>,
-    ) -> ModelResult<InferenceResponse> {
-        let models = self.models.lock().await;
-
-        if let Some(model) = models.get(model_name) {
-            model.infer(request, token_callback).await
-        } else {
-            Err(ModelError::NotFound(model_name.to_string()))
-        }
-    }
-
-    /// Get embedding from a model
-    pub async fn embed(&self, model_name: &str, text: &str) -> ModelResult<Vec<f32>> {
-        let models = self.models.lock().await;
-
-        if let Some(model) = models.get(model_name) {
-            model.embed(text).await
-        } else {
-            Err(ModelError::NotFound(model_name.to_string()))
-        }
+                let passage = passage.to_lowercase();
+                let found = terms
+                    .iter()
+                    .filter(|t| passage.contains(t.as_str()))
+                    .count();
+                found as f32 / terms.len() as f32
+            })
+            .collect())
     }
+}
 
-    /// List all models
-    pub async fn list(&self) -> Vec<(String, bool)> {
-        let models = self.models.lock().await;
-        models
-            .iter()
-            .map(|(name, m)| (name.clone(), m.is_loaded()))
-            .collect()
-    }
+/// Lock a mutex that only holds caches: a panic mid-update at worst leaves
+/// entries that no prompt matches
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gguf::fixture::{tiny_llama, GgufFixture};
+    use std::path::Path;
 
     #[test]
     fn test_inference_request_builder() {
@@ -341,14 +553,201 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_model_pool() {
-        let pool = ModelPool::new(2);
-        pool.add("test".to_string(), PathBuf::from("/tmp/test.gguf"))
+    async fn test_load_validates_gguf_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tiny.gguf");
+        tiny_llama().write(&path);
+
+        let mut model =
+            ModelInstance::new("tiny".to_string(), path.clone()).with_context_size(8192);
+        model.load().await.unwrap();
+        assert!(model.is_loaded());
+        assert_eq!(model.context_size(), 2048);
+        assert_eq!(model.metadata().unwrap().architecture, "llama");
+
+        // Truncated download
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        let mut model = ModelInstance::new("tiny".to_string(), path);
+        let err = model.load().await.unwrap_err();
+        assert!(matches!(err, ModelError::InvalidGguf(_)));
+        assert!(!model.is_loaded());
+    }
+
+    /// A Phi-3 header whose vocabulary covers "hi" and `<|user|>`
+    fn write_phi(path: &Path) {
+        GgufFixture::new("phi3")
+            .string("tokenizer.ggml.model", "llama")
+            .strings(
+                "tokenizer.ggml.tokens",
+                &["<unk>", "<|user|>", "▁", "h", "i", "▁h", "▁hi"],
+            )
+            .f32s(
+                "tokenizer.ggml.scores",
+                &[0.0, 0.0, -4.0, -3.0, -3.0, -2.0, -1.0],
+            )
+            .i32s("tokenizer.ggml.token_type", &[2, 4, 1, 1, 1, 1, 1])
+            .write(path);
+    }
+
+    #[tokio::test]
+    async fn test_tokenizer_and_chat_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("phi.gguf");
+        write_phi(&path);
+
+        // Before loading: estimated counts, template guessed from the name
+        let mut model = ModelInstance::new("phi-3-mini".to_string(), path);
+        assert!(model.tokenizer().is_none());
+        assert_eq!(model.count_tokens("hi hi hi"), 2);
+        assert_eq!(model.chat_template(), ChatTemplate::Phi3);
+
+        model.load().await.unwrap();
+        assert_eq!(model.count_tokens("hi hi hi"), 3);
+        assert_eq!(model.token_counter().count_tokens("<|user|> hi"), 2);
+
+        let prompt = model.format_chat(&[ChatMessage::user("hi")]);
+        assert_eq!(prompt, "<|user|>\nhi<|end|>\n<|assistant|>\n");
+        let response = model
+            .infer(InferenceRequest::new(prompt.clone()), None)
+            .await
+            .unwrap();
+        assert_eq!(response.prompt_tokens as usize, model.count_tokens(&prompt));
+
+        // A GGUF without a vocabulary uses tokenizer.json beside it
+        let path = dir.path().join("bare.gguf");
+        GgufFixture::new("llama").write(&path);
+        std::fs::write(
+            dir.path().join("tokenizer.json"),
+            r#"{"model": {"type": "BPE", "vocab": {"h": 0, "i": 1, "hi": 2}, "merges": ["h i"]}}"#,
+        )
+        .unwrap();
+        let mut model = ModelInstance::new("bare".to_string(), path);
+        model.load().await.unwrap();
+        assert_eq!(model.tokenizer().unwrap().encode("hi"), vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_prefix_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("phi.gguf");
+        write_phi(&path);
+        let mut model = ModelInstance::new("phi-3-mini".to_string(), path);
+        model.load().await.unwrap();
+
+        // 73 tokens (the first "hi" follows no space, so is "h" and "i", and
+        // the trailing space is one): two complete blocks
+        let round_one = format!("<|user|>{}", "hi ".repeat(70));
+        let response = model
+            .infer(InferenceRequest::new(round_one.clone()), None)
+            .await
+            .unwrap();
+        assert_eq!(response.prompt_tokens, 73);
+        assert_eq!(response.cached_tokens, 0);
+
+        // The next round repeats the prompt and appends feedback; only the
+        // text after the leading `<|user|>` is encoded again
+        let round_two = format!("{}<|user|> hi hi", round_one);
+        let response = model
+            .infer(InferenceRequest::new(round_two.clone()), None)
+            .await
+            .unwrap();
+        assert_eq!(response.cached_tokens, 64);
+        let tokenizer = model.tokenizer().unwrap();
+        assert_eq!(
+            response.prompt_tokens as usize,
+            tokenizer.encode(&round_two).len()
+        );
+
+        let stats = model.prefix_cache_stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert_eq!(stats.reused_tokens, 64);
+
+        // Unloading drops the cached prefixes
+        model.unload();
+        model.load().await.unwrap();
+        let response = model
+            .infer(InferenceRequest::new(round_one), None)
+            .await
+            .unwrap();
+        assert_eq!(response.cached_tokens, 0);
+
+        // Disabled cache
+        let path = dir.path().join("phi.gguf");
+        let mut model = ModelInstance::new("phi-3-mini".to_string(), path).with_prefix_cache(0);
+        model.load().await.unwrap();
+        for _ in 0..2 {
+            let response = model
+                .infer(InferenceRequest::new("hi ".repeat(70)), None)
+                .await
+                .unwrap();
+            assert_eq!(response.cached_tokens, 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_constrained_inference() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tiny.gguf");
+        tiny_llama().write(&path);
+        let mut model = ModelInstance::new("tiny".to_string(), path);
+        model.load().await.unwrap();
+
+        let invalid = InferenceRequest::new("Hi".to_string())
+            .with_json_schema(serde_json::json!({"oneOf": []}));
+        assert!(matches!(
+            model.infer(invalid, None).await,
+            Err(ModelError::InvalidGrammar(_))
+        ));
+
+        // Output that does not match the schema never reaches the caller
+        let request = InferenceRequest::new("Hi".to_string())
+            .with_json_schema(serde_json::json!({"type": "object"}));
+        assert!(matches!(
+            model.infer(request, None).await,
+            Err(ModelError::ConstraintViolated(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rerank_requires_loaded_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reranker.gguf");
+        GgufFixture::new("bert").write(&path);
+
+        let mut model = ModelInstance::new("reranker".to_string(), path);
+        assert!(model.rerank("query", &["passage"]).await.is_err());
+
+        model.load().await.unwrap();
+        let scores = model
+            .rerank(
+                "vector search",
+                &["fast vector search", "cooking", "vector"],
+            )
             .await
             .unwrap();
+        assert_eq!(scores, vec![1.0, 0.0, 0.5]);
+    }
 
-        let models = pool.list().await;
-        assert_eq!(models.len(), 1);
-        assert!(!models[0].1); // Not loaded yet
+    #[tokio::test]
+    async fn test_estimate_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tiny.gguf");
+        tiny_llama().write(&path);
+        let weights = GgufMetadata::read(&path).unwrap().tensor_bytes();
+        let kv_per_token = 2 * 256 / 8 * 2 * 2;
+
+        // Estimated from the header before loading, at the trained context
+        let model = ModelInstance::new("tiny".to_string(), path.clone()).with_context_size(8192);
+        let cpu = model.estimate_memory().await.unwrap();
+        assert_eq!(cpu.vram_bytes, 0);
+        assert_eq!(cpu.ram_bytes, weights + 2048 * kv_per_token + weights / 10);
+
+        // All layers offloaded
+        let model = ModelInstance::new("tiny".to_string(), path).with_gpu_layers(99);
+        let gpu = model.estimate_memory().await.unwrap();
+        assert_eq!(gpu.vram_bytes, weights + 2048 * kv_per_token);
+        assert_eq!(gpu.ram_bytes, weights / 10);
+        assert_eq!(gpu.total_bytes(), cpu.total_bytes());
     }
 }
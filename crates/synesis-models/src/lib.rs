@@ -2,23 +2,55 @@
 //!
 //! This crate handles:
 //! - Hardware detection (CPU, GPU, RAM)
-//! - Model downloads from HuggingFace
-//! - Model registry and versioning
-//! - Inference execution (via llama.cpp bindings)
+//! - Model downloads from HuggingFace or mirrors, through a persistent
+//!   queue with parallel, bandwidth-capped and resumable downloads
+//! - Offline bundles for moving models between air-gapped machines
+//! - Model registry and versioning, persisted to a `models.lock`
+//! - GGUF header inspection and validation
+//! - Tokenizers and chat templates per model family
+//! - Inference execution (via llama.cpp bindings), optionally constrained
+//!   to a JSON schema or grammar
+//! - A memory-budgeted pool of loaded models with LRU eviction
+//! - Tracking of evaluated prompt prefixes across consensus rounds
+//! - Request scheduling with priorities, fair sharing and cancellation
 //! - Hardware manifests for optimal model selection
 
+pub mod bench;
+pub mod bundle;
+pub mod chat;
+pub mod download_queue;
 pub mod downloader;
+pub mod gguf;
+pub mod grammar;
 pub mod hardware;
 pub mod inference;
+pub mod lockfile;
 pub mod manifest;
+pub mod pool;
+pub mod prefix_cache;
 pub mod registry;
+pub mod scheduler;
+pub mod tokenizer;
 
 // Re-exports
-pub use downloader::{DownloadProgress, Downloader as ModelDownloader};
+pub use bench::{BenchResult, BenchTargets, BenchWorkload};
+pub use bundle::{BundleManifest, ImportReport};
+pub use chat::{ChatMessage, ChatRole, ChatTemplate};
+pub use download_queue::{DownloadQueue, QueueReport, QueueStatus, QueuedDownload};
+pub use downloader::{
+    BandwidthLimiter, DownloadProgress, Downloader as ModelDownloader, VerifiedDownload,
+};
+pub use gguf::{GgufMetadata, GgufTensor};
+pub use grammar::OutputConstraint;
 pub use hardware::{GpuInfo, HardwareDetector, HardwareInfo};
-pub use inference::{InferenceRequest, InferenceResponse, ModelInstance, ModelPool};
-pub use manifest::{HardwareManifest, ModelRecommendation};
+pub use inference::{InferenceRequest, InferenceResponse, ModelInstance};
+pub use lockfile::{LockEntry, LockStatus, ModelLock};
+pub use manifest::{AgentRole, HardwareManifest, ModelRecommendation};
+pub use pool::{MemoryBudget, MemoryEstimate, ModelPool, PoolEvent, PoolEventCallback};
+pub use prefix_cache::{PrefixCache, PrefixCacheStats};
 pub use registry::{ModelInfo, ModelRegistry, ModelStatus};
+pub use scheduler::{Priority, RequestOptions, Scheduler, SchedulerConfig};
+pub use tokenizer::{EstimatedTokens, TokenCounter, TokenSplit, Tokenizer, TokenizerKind};
 
 /// Result type for model operations
 pub type ModelResult<T> = std::result::Result<T, ModelError>;
@@ -45,9 +77,42 @@ pub enum ModelError {
         actual: String,
     },
 
+    #[error("No verified checksum for {0}")]
+    UnverifiedChecksum(String),
+
+    #[error("Invalid model bundle: {0}")]
+    InvalidBundle(String),
+
+    #[error("Invalid lockfile: {0}")]
+    InvalidLockfile(String),
+
+    #[error("Invalid download queue: {0}")]
+    InvalidDownloadQueue(String),
+
+    #[error("Invalid GGUF file: {0}")]
+    InvalidGguf(String),
+
+    #[error("Unsupported tokenizer: {0}")]
+    UnsupportedTokenizer(String),
+
+    #[error("Invalid output constraint: {0}")]
+    InvalidGrammar(String),
+
+    #[error("Output does not match constraint: {0}")]
+    ConstraintViolated(String),
+
     #[error("Insufficient resources: {0}")]
     InsufficientResources(String),
 
+    #[error("Inference queue full: {0}")]
+    QueueFull(String),
+
+    #[error("Request cancelled: {0}")]
+    Cancelled(String),
+
+    #[error("Deadline exceeded waiting for {0}")]
+    DeadlineExceeded(String),
+
     #[error("Inference error: {0}")]
     InferenceError(String),
 
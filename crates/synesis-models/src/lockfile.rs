@@ -0,0 +1,346 @@
+//! Model Lockfile
+//!
+//! Records every installed model file in `models.lock` inside the models
+//! directory: where it came from, the revision it was resolved to, its size,
+//! its verified SHA256 checksum and when it was installed. Entries are only
+//! written for downloads whose checksum was verified, so the lockfile is the
+//! reference `verify` checks files against.
+//!
+//! # Format
+//!
+//! ```toml
+//! version = 1
+//!
+//! [[model]]
+//! model = "phi-3-mini"
+//! filename = "phi-3-mini-4k-instruct-q4_k_m.gguf"
+//! quantization = "Q4"
+//! source = "microsoft/Phi-3-mini-4k-instruct-gguf/phi-3-mini-4k-instruct-q4_k_m.gguf"
+//! revision = "<commit sha>"
+//! size_bytes = 2393231072
+//! sha256 = "<64 hex digits>"
+//! installed_at = "2026-10-18T12:00:00Z"
+//! ```
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::downloader::{sha256_file, VerifiedDownload};
+use crate::{ModelError, ModelResult, Quantization};
+
+/// Lockfile name inside the models directory
+pub const LOCKFILE_NAME: &str = "models.lock";
+
+/// Current lockfile format version
+const LOCK_VERSION: u32 = 1;
+
+/// An installed, verified model file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockEntry {
+    /// Model identifier (e.g. "phi-3-mini")
+    pub model: String,
+    /// File name inside the models directory
+    pub filename: String,
+    /// Quantization level, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantization: Option<Quantization>,
+    /// Source the file was downloaded from
+    pub source: String,
+    /// Commit or revision the source resolved to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    /// File size in bytes
+    pub size_bytes: u64,
+    /// Verified SHA256 checksum (lowercase hex)
+    pub sha256: String,
+    /// When the file was installed
+    pub installed_at: DateTime<Utc>,
+}
+
+impl LockEntry {
+    /// Create an entry for a verified download
+    pub fn from_download(
+        model: impl Into<String>,
+        quantization: Option<Quantization>,
+        download: &VerifiedDownload,
+    ) -> ModelResult<Self> {
+        let filename = download
+            .path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| ModelError::InvalidPath(download.path.display().to_string()))?;
+
+        Ok(Self {
+            model: model.into(),
+            filename: filename.to_string(),
+            quantization,
+            source: download.source.clone(),
+            revision: download.revision.clone(),
+            size_bytes: download.size_bytes,
+            sha256: download.sha256.clone(),
+            installed_at: Utc::now(),
+        })
+    }
+}
+
+/// Result of checking a file against its lock entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockStatus {
+    /// File matches the recorded size and checksum
+    Verified,
+    /// File is missing
+    Missing,
+    /// File size differs from the recorded size
+    SizeMismatch { expected: u64, actual: u64 },
+    /// File contents differ from the recorded checksum
+    ChecksumMismatch { expected: String, actual: String },
+}
+
+impl LockStatus {
+    /// Whether the file matched its entry
+    pub fn is_verified(&self) -> bool {
+        matches!(self, LockStatus::Verified)
+    }
+}
+
+impl std::fmt::Display for LockStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockStatus::Verified => write!(f, "verified"),
+            LockStatus::Missing => write!(f, "file missing"),
+            LockStatus::SizeMismatch { expected, actual } => {
+                write!(f, "size is {} bytes, expected {}", actual, expected)
+            },
+            LockStatus::ChecksumMismatch { expected, actual } => {
+                write!(f, "SHA256 is {}, expected {}", actual, expected)
+            },
+        }
+    }
+}
+
+/// Contents of a `models.lock` file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelLock {
+    /// Format version
+    version: u32,
+    /// Installed files, sorted by file name
+    #[serde(default, rename = "model")]
+    entries: Vec<LockEntry>,
+}
+
+impl Default for ModelLock {
+    fn default() -> Self {
+        Self {
+            version: LOCK_VERSION,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl ModelLock {
+    /// Load a lockfile, or an empty lock if the file does not exist
+    ///
+    /// # Errors
+    /// Returns [`ModelError::InvalidLockfile`] if the file cannot be parsed
+    /// or was written by a newer version.
+    pub fn load(path: &Path) -> ModelResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let lock: Self = toml::from_str(&content)
+            .map_err(|e| ModelError::InvalidLockfile(format!("{}: {}", path.display(), e)))?;
+        if lock.version > LOCK_VERSION {
+            return Err(ModelError::InvalidLockfile(format!(
+                "{}: version {} is newer than supported version {}",
+                path.display(),
+                lock.version,
+                LOCK_VERSION
+            )));
+        }
+
+        debug!(
+            "Loaded {} lock entries from {}",
+            lock.entries.len(),
+            path.display()
+        );
+        Ok(lock)
+    }
+
+    /// Write the lockfile
+    ///
+    /// Writes to a temporary file first and renames it into place, so an
+    /// interrupted write never leaves a half-written lockfile.
+    pub fn save(&self, path: &Path) -> ModelResult<()> {
+        let content =
+            toml::to_string_pretty(self).map_err(|e| ModelError::InvalidLockfile(e.to_string()))?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp_path = path.with_extension("lock.tmp");
+        std::fs::write(&tmp_path, content)?;
+        std::fs::rename(&tmp_path, path)?;
+
+        info!(
+            "Wrote {} lock entries to {}",
+            self.entries.len(),
+            path.display()
+        );
+        Ok(())
+    }
+
+    /// All entries, sorted by file name
+    pub fn entries(&self) -> &[LockEntry] {
+        &self.entries
+    }
+
+    /// Entry for a file name
+    pub fn get(&self, filename: &str) -> Option<&LockEntry> {
+        self.entries.iter().find(|e| e.filename == filename)
+    }
+
+    /// Add an entry, replacing any entry for the same file
+    pub fn insert(&mut self, entry: LockEntry) {
+        self.entries.retain(|e| e.filename != entry.filename);
+        let pos = self
+            .entries
+            .partition_point(|e| e.filename < entry.filename);
+        self.entries.insert(pos, entry);
+    }
+
+    /// Remove the entry for a file name
+    pub fn remove(&mut self, filename: &str) -> Option<LockEntry> {
+        let pos = self.entries.iter().position(|e| e.filename == filename)?;
+        Some(self.entries.remove(pos))
+    }
+}
+
+/// Check a file in `models_dir` against its lock entry
+///
+/// The size is compared first, so a truncated file is reported without
+/// hashing it.
+pub async fn verify_entry(models_dir: &Path, entry: &LockEntry) -> ModelResult<LockStatus> {
+    let path = models_dir.join(&entry.filename);
+    let size = match tokio::fs::metadata(&path).await {
+        Ok(metadata) => metadata.len(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(LockStatus::Missing),
+        Err(e) => return Err(e.into()),
+    };
+
+    if size != entry.size_bytes {
+        return Ok(LockStatus::SizeMismatch {
+            expected: entry.size_bytes,
+            actual: size,
+        });
+    }
+
+    let actual = sha256_file(&path).await?;
+    if actual != entry.sha256 {
+        return Ok(LockStatus::ChecksumMismatch {
+            expected: entry.sha256.clone(),
+            actual,
+        });
+    }
+
+    Ok(LockStatus::Verified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    async fn installed(dir: &Path, filename: &str, content: &[u8]) -> LockEntry {
+        let path = dir.join(filename);
+        std::fs::write(&path, content).unwrap();
+        let download = VerifiedDownload {
+            sha256: sha256_file(&path).await.unwrap(),
+            path,
+            source: format!("example/repo/{}", filename),
+            revision: Some("abc123".to_string()),
+            size_bytes: content.len() as u64,
+        };
+        LockEntry::from_download("tiny", Some(Quantization::Q4), &download).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_lockfile_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_path = dir.path().join(LOCKFILE_NAME);
+
+        // Missing lockfile is empty
+        assert!(ModelLock::load(&lock_path).unwrap().entries().is_empty());
+
+        let mut lock = ModelLock::default();
+        lock.insert(installed(dir.path(), "b.gguf", b"bbb").await);
+        lock.insert(installed(dir.path(), "a.gguf", b"aaa").await);
+        lock.insert(installed(dir.path(), "b.gguf", b"bbbb").await);
+        lock.save(&lock_path).unwrap();
+
+        let loaded = ModelLock::load(&lock_path).unwrap();
+        let names: Vec<_> = loaded
+            .entries()
+            .iter()
+            .map(|e| e.filename.as_str())
+            .collect();
+        assert_eq!(names, vec!["a.gguf", "b.gguf"]);
+        assert_eq!(loaded.get("b.gguf").unwrap().size_bytes, 4);
+        assert_eq!(loaded.get("a.gguf"), lock.get("a.gguf"));
+
+        let mut loaded = loaded;
+        assert!(loaded.remove("a.gguf").is_some());
+        assert!(loaded.remove("a.gguf").is_none());
+    }
+
+    #[test]
+    fn test_rejects_newer_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_path = dir.path().join(LOCKFILE_NAME);
+        std::fs::write(&lock_path, "version = 99\n").unwrap();
+        assert!(matches!(
+            ModelLock::load(&lock_path),
+            Err(ModelError::InvalidLockfile(_))
+        ));
+
+        std::fs::write(&lock_path, "not toml [").unwrap();
+        assert!(ModelLock::load(&lock_path).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = installed(dir.path(), "m.gguf", b"weights").await;
+        let path: PathBuf = dir.path().join("m.gguf");
+
+        assert_eq!(
+            verify_entry(dir.path(), &entry).await.unwrap(),
+            LockStatus::Verified
+        );
+
+        std::fs::write(&path, b"weightz").unwrap();
+        assert!(matches!(
+            verify_entry(dir.path(), &entry).await.unwrap(),
+            LockStatus::ChecksumMismatch { .. }
+        ));
+
+        std::fs::write(&path, b"weigh").unwrap();
+        assert_eq!(
+            verify_entry(dir.path(), &entry).await.unwrap(),
+            LockStatus::SizeMismatch {
+                expected: 7,
+                actual: 5
+            }
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            verify_entry(dir.path(), &entry).await.unwrap(),
+            LockStatus::Missing
+        );
+    }
+}
@@ -1,13 +1,18 @@
 //! Hardware Manifests
 //!
 //! Defines hardware profiles and model recommendations based on
-//! detected hardware capabilities.
+//! detected hardware capabilities. `synesis model bench` writes a tuned
+//! manifest from measurements on this machine (see [`crate::bench`]).
 
 use crate::{HardwareDetector, HardwareInfo, ModelError, ModelResult, Quantization};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, instrument};
 
+/// Name of the manifest written by `synesis model bench`, which
+/// [`HardwareManifest::detect_and_load`] prefers over other manifests
+pub const TUNED_MANIFEST_NAME: &str = "tuned";
+
 /// Hardware manifest - maps hardware capabilities to model recommendations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HardwareManifest {
@@ -36,6 +41,64 @@ pub struct AgentRecommendations {
     pub embeddings: ModelRecommendation,
 }
 
+impl AgentRecommendations {
+    /// Recommendation for one agent
+    pub fn get(&self, role: AgentRole) -> &ModelRecommendation {
+        match role {
+            AgentRole::Pathos => &self.pathos,
+            AgentRole::Logos => &self.logos,
+            AgentRole::Ethos => &self.ethos,
+            AgentRole::Embeddings => &self.embeddings,
+        }
+    }
+
+    /// Mutable recommendation for one agent
+    pub fn get_mut(&mut self, role: AgentRole) -> &mut ModelRecommendation {
+        match role {
+            AgentRole::Pathos => &mut self.pathos,
+            AgentRole::Logos => &mut self.logos,
+            AgentRole::Ethos => &mut self.ethos,
+            AgentRole::Embeddings => &mut self.embeddings,
+        }
+    }
+}
+
+/// Agent a model is recommended for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentRole {
+    Pathos,
+    Logos,
+    Ethos,
+    Embeddings,
+}
+
+impl AgentRole {
+    /// All roles, in manifest order
+    pub const ALL: [AgentRole; 4] = [
+        AgentRole::Pathos,
+        AgentRole::Logos,
+        AgentRole::Ethos,
+        AgentRole::Embeddings,
+    ];
+
+    /// Lowercase role name, as used in manifests
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AgentRole::Pathos => "pathos",
+            AgentRole::Logos => "logos",
+            AgentRole::Ethos => "ethos",
+            AgentRole::Embeddings => "embeddings",
+        }
+    }
+}
+
+impl std::fmt::Display for AgentRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// A specific model recommendation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelRecommendation {
@@ -51,6 +114,12 @@ pub struct ModelRecommendation {
     pub size_bytes: u64,
     /// SHA256 checksum (optional)
     pub sha256: Option<String>,
+    /// Context size for this agent, instead of the manifest's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_size: Option<u32>,
+    /// GPU layers for this agent, instead of the manifest's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu_layers: Option<u32>,
 }
 
 impl HardwareManifest {
@@ -70,6 +139,22 @@ impl HardwareManifest {
         true
     }
 
+    /// Context size to load an agent's model with
+    pub fn context_size_for(&self, role: AgentRole) -> u32 {
+        self.recommendations
+            .get(role)
+            .context_size
+            .unwrap_or(self.context_size)
+    }
+
+    /// GPU layers to offload for an agent's model
+    pub fn gpu_layers_for(&self, role: AgentRole) -> u32 {
+        self.recommendations
+            .get(role)
+            .gpu_layers
+            .unwrap_or(self.gpu_layers)
+    }
+
     /// Get total download size for all models
     pub fn total_download_size(&self) -> u64 {
         self.recommendations.pathos.size_bytes
@@ -163,10 +248,23 @@ impl HardwareManifest {
             )));
         }
 
+        if rec
+            .context_size
+            .is_some_and(|size| size == 0 || size > 131072)
+        {
+            return Err(ModelError::Internal(format!(
+                "{} context_size must be between 1 and 131072",
+                agent_name
+            )));
+        }
+
         Ok(())
     }
 
     /// Detect hardware and load appropriate manifest
+    ///
+    /// Compatible manifests in `~/.synesis/manifests` win over the built-in
+    /// profiles, and the [tuned](TUNED_MANIFEST_NAME) one over the others.
     #[instrument]
     pub fn detect_and_load() -> ModelResult<Self> {
         info!("Detecting hardware and selecting appropriate manifest...");
@@ -212,16 +310,19 @@ impl HardwareManifest {
         })?;
 
         // Try to find manifests with specific hardware identifiers
-        let manifests: Vec<_> = entries
+        let mut manifests: Vec<_> = entries
             .filter_map(|entry| entry.ok())
-            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
             .collect();
 
         debug!("Found {} manifest files", manifests.len());
 
-        for entry in manifests {
-            let path = entry.path();
+        // A manifest tuned by benchmarks on this machine wins over the rest
+        let tuned = dir.join(format!("{}.json", TUNED_MANIFEST_NAME));
+        manifests.sort_by_key(|path| *path != tuned);
 
+        for path in manifests {
             // Try to load this manifest
             match Self::load(&path) {
                 Ok(manifest) => {
@@ -344,6 +445,8 @@ pub mod profiles {
                     filename: "Phi-3-mini-4k-instruct-q4.gguf".to_string(),
                     size_bytes: 2_200_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 logos: ModelRecommendation {
                     model: "llama-3.2-3b".to_string(),
@@ -352,6 +455,8 @@ pub mod profiles {
                     filename: "Llama-3.2-3B-Instruct-Q4_K_M.gguf".to_string(),
                     size_bytes: 2_000_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 ethos: ModelRecommendation {
                     model: "phi-3-mini".to_string(), // Reuse Pathos model
@@ -360,6 +465,8 @@ pub mod profiles {
                     filename: "Phi-3-mini-4k-instruct-q4.gguf".to_string(),
                     size_bytes: 0, // Already counted
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 embeddings: ModelRecommendation {
                     model: "bge-micro".to_string(),
@@ -368,6 +475,8 @@ pub mod profiles {
                     filename: "model.safetensors".to_string(),
                     size_bytes: 50_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
             },
             gpu_layers: 0,
@@ -390,6 +499,8 @@ pub mod profiles {
                     filename: "Phi-3-mini-4k-instruct-q4.gguf".to_string(),
                     size_bytes: 2_200_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 logos: ModelRecommendation {
                     model: "llama-3.2-8b".to_string(),
@@ -398,6 +509,8 @@ pub mod profiles {
                     filename: "Meta-Llama-3.2-8B-Instruct-Q4_K_M.gguf".to_string(),
                     size_bytes: 4_700_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 ethos: ModelRecommendation {
                     model: "mistral-7b-instruct".to_string(),
@@ -406,6 +519,8 @@ pub mod profiles {
                     filename: "mistral-7b-instruct-v0.2.Q4_K_M.gguf".to_string(),
                     size_bytes: 4_100_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 embeddings: ModelRecommendation {
                     model: "bge-small".to_string(),
@@ -414,6 +529,8 @@ pub mod profiles {
                     filename: "model.safetensors".to_string(),
                     size_bytes: 130_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
             },
             gpu_layers: 20,
@@ -436,6 +553,8 @@ pub mod profiles {
                     filename: "Phi-3-medium-4k-instruct-q4.gguf".to_string(),
                     size_bytes: 8_000_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 logos: ModelRecommendation {
                     model: "llama-3.1-8b".to_string(),
@@ -444,6 +563,8 @@ pub mod profiles {
                     filename: "Meta-Llama-3.1-8B-Instruct-Q5_K_M.gguf".to_string(),
                     size_bytes: 5_700_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 ethos: ModelRecommendation {
                     model: "mistral-7b-instruct".to_string(),
@@ -452,6 +573,8 @@ pub mod profiles {
                     filename: "mistral-7b-instruct-v0.2.Q5_K_M.gguf".to_string(),
                     size_bytes: 5_100_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 embeddings: ModelRecommendation {
                     model: "bge-base".to_string(),
@@ -460,6 +583,8 @@ pub mod profiles {
                     filename: "model.safetensors".to_string(),
                     size_bytes: 440_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
             },
             gpu_layers: 35,
@@ -482,6 +607,8 @@ pub mod profiles {
                     filename: "Phi-3-medium-4k-instruct-q8.gguf".to_string(),
                     size_bytes: 15_000_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 logos: ModelRecommendation {
                     model: "llama-3.1-70b".to_string(),
@@ -490,6 +617,8 @@ pub mod profiles {
                     filename: "Meta-Llama-3.1-70B-Instruct-Q4_K_M.gguf".to_string(),
                     size_bytes: 40_000_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 ethos: ModelRecommendation {
                     model: "mixtral-8x7b".to_string(),
@@ -498,6 +627,8 @@ pub mod profiles {
                     filename: "mixtral-8x7b-instruct-v0.1.Q4_K_M.gguf".to_string(),
                     size_bytes: 26_000_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 embeddings: ModelRecommendation {
                     model: "bge-large".to_string(),
@@ -506,6 +637,8 @@ pub mod profiles {
                     filename: "model.safetensors".to_string(),
                     size_bytes: 1_340_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
             },
             gpu_layers: 80,
@@ -528,6 +661,8 @@ pub mod profiles {
                     filename: "Phi-3-mini-4k-instruct-q4.gguf".to_string(),
                     size_bytes: 2_200_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 logos: ModelRecommendation {
                     model: "llama-3.2-3b".to_string(),
@@ -536,6 +671,8 @@ pub mod profiles {
                     filename: "Llama-3.2-3B-Instruct-Q4_K_M.gguf".to_string(),
                     size_bytes: 2_000_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 ethos: ModelRecommendation {
                     model: "phi-3-mini".to_string(),
@@ -544,6 +681,8 @@ pub mod profiles {
                     filename: "Phi-3-mini-4k-instruct-q4.gguf".to_string(),
                     size_bytes: 0,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
                 embeddings: ModelRecommendation {
                     model: "bge-micro".to_string(),
@@ -552,6 +691,8 @@ pub mod profiles {
                     filename: "model.safetensors".to_string(),
                     size_bytes: 50_000_000,
                     sha256: None,
+                    context_size: None,
+                    gpu_layers: None,
                 },
             },
             gpu_layers: 99, // Full GPU offload for Jetson
@@ -603,4 +744,36 @@ mod tests {
         let size = profile.total_download_size();
         assert!(size > 0);
     }
+
+    #[test]
+    fn test_tuned_manifest_preferred() {
+        let dir = tempfile::tempdir().unwrap();
+        let hardware = HardwareDetector::detect().unwrap();
+
+        let mut custom = profiles::minimal();
+        custom.name = "custom".to_string();
+        custom.min_ram_bytes = 1;
+        custom.save(&dir.path().join("a-custom.json")).unwrap();
+
+        let mut tuned = custom.clone();
+        tuned.name = TUNED_MANIFEST_NAME.to_string();
+        tuned.recommendations.logos.context_size = Some(8192);
+        tuned
+            .save(&dir.path().join(format!("{}.json", TUNED_MANIFEST_NAME)))
+            .unwrap();
+
+        let found = HardwareManifest::find_matching_manifest(dir.path(), &hardware)
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.name, TUNED_MANIFEST_NAME);
+        assert_eq!(found.context_size_for(AgentRole::Logos), 8192);
+        assert_eq!(
+            found.context_size_for(AgentRole::Pathos),
+            found.context_size
+        );
+
+        // Overrides are left out of manifests that don't set them
+        let json = serde_json::to_string(&custom).unwrap();
+        assert!(!json.contains("gpu_layers\":null"));
+    }
 }
@@ -0,0 +1,904 @@
+//! Model Pool
+//!
+//! Keeps several models loaded within a memory budget. Each model's RAM and
+//! VRAM use is estimated from its GGUF header (tensor sizes, KV cache at the
+//! configured context size, and GPU offload) before it is loaded. When a new
+//! model does not fit, the least recently used unpinned models are unloaded
+//! until it does; if pinned models alone leave too little room, loading fails
+//! with [`ModelError::InsufficientResources`] instead of risking an OOM.
+//!
+//! Pin models that must stay resident, such as the Pathos intent model that
+//! runs on every query:
+//!
+//! ```ignore
+//! let hw = HardwareDetector::detect()?;
+//! let pool = ModelPool::for_hardware(&hw).with_event_callback(metrics.pool_observer());
+//! pool.add("pathos".to_string(), pathos_path).await?;
+//! pool.pin("pathos").await?;
+//! pool.load("pathos").await?;
+//! ```
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, info, warn};
+
+use crate::gguf::GgufMetadata;
+use crate::hardware::{format_bytes, GpuVendor, HardwareInfo};
+use crate::inference::{InferenceRequest, InferenceResponse, ModelInstance, TokenCallback};
+use crate::{ModelError, ModelResult};
+
+/// RAM always left for the OS and the rest of the application (2 GB)
+const MIN_RAM_RESERVE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Layer count assumed when a header does not record `block_count`
+const DEFAULT_BLOCK_COUNT: u64 = 32;
+
+/// Parameters per byte of KV cache per token, used when a header lacks the
+/// attention dimensions. Errs high: without grouped-query attention a 3.8B
+/// model needs about 400 KB per token.
+const PARAMS_PER_KV_BYTE: u64 = 8192;
+
+/// Approximate memory a model needs while loaded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryEstimate {
+    /// System RAM in bytes
+    pub ram_bytes: u64,
+    /// GPU memory in bytes
+    pub vram_bytes: u64,
+}
+
+impl MemoryEstimate {
+    /// Estimate a model's memory from its header
+    ///
+    /// - Weights: the size of all tensors, which reflects the quantization
+    /// - KV cache: F16 keys and values for `context_size` tokens, capped at
+    ///   the trained context length
+    /// - Compute buffers: a tenth of the weights, kept in RAM
+    ///
+    /// Weights and KV cache are split between RAM and VRAM in proportion to
+    /// the offloaded layers.
+    pub fn for_model(metadata: &GgufMetadata, context_size: u32, gpu_layers: u32) -> Self {
+        let context = metadata
+            .context_length
+            .map_or(context_size, |trained| context_size.min(trained));
+
+        let weights = metadata.tensor_bytes();
+        let kv_per_token = metadata
+            .kv_bytes_per_token()
+            .unwrap_or(metadata.parameter_count / PARAMS_PER_KV_BYTE);
+        let kv_cache = u64::from(context) * kv_per_token;
+        let compute = weights / 10;
+
+        let layers = metadata
+            .block_count
+            .map_or(DEFAULT_BLOCK_COUNT, u64::from)
+            .max(1);
+        let offloaded = u64::from(gpu_layers).min(layers);
+        let vram = if offloaded == layers {
+            weights + kv_cache
+        } else {
+            (weights + kv_cache) / layers * offloaded
+        };
+
+        Self {
+            ram_bytes: weights + kv_cache - vram + compute,
+            vram_bytes: vram,
+        }
+    }
+
+    /// RAM and VRAM combined
+    pub fn total_bytes(&self) -> u64 {
+        self.ram_bytes + self.vram_bytes
+    }
+}
+
+impl std::ops::Add for MemoryEstimate {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            ram_bytes: self.ram_bytes + other.ram_bytes,
+            vram_bytes: self.vram_bytes + other.vram_bytes,
+        }
+    }
+}
+
+impl std::fmt::Display for MemoryEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} RAM", format_bytes(self.ram_bytes))?;
+        if self.vram_bytes > 0 {
+            write!(f, " + {} VRAM", format_bytes(self.vram_bytes))?;
+        }
+        Ok(())
+    }
+}
+
+/// Memory the pool may use for loaded models
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryBudget {
+    /// System RAM in bytes
+    pub ram_bytes: u64,
+    /// GPU memory in bytes
+    pub vram_bytes: u64,
+    /// RAM and VRAM are the same memory (Apple Silicon), so both count
+    /// against `ram_bytes`
+    pub unified_memory: bool,
+}
+
+impl MemoryBudget {
+    /// Budget with separate RAM and VRAM limits
+    pub fn new(ram_bytes: u64, vram_bytes: u64) -> Self {
+        Self {
+            ram_bytes,
+            vram_bytes,
+            unified_memory: false,
+        }
+    }
+
+    /// Budget that never evicts
+    pub fn unlimited() -> Self {
+        Self::new(u64::MAX, u64::MAX)
+    }
+
+    /// Budget for the detected hardware
+    ///
+    /// Leaves a quarter of RAM (at least 2 GB) for the OS and the rest of
+    /// the application, and 10% of VRAM for the driver. On an 8 GB machine
+    /// this gives models 6 GB.
+    pub fn from_hardware(hardware: &HardwareInfo) -> Self {
+        let reserve = (hardware.ram_bytes / 4).max(MIN_RAM_RESERVE_BYTES);
+        let ram_bytes = hardware.ram_bytes.saturating_sub(reserve);
+
+        match &hardware.gpu {
+            Some(gpu) if gpu.supported && gpu.vendor == GpuVendor::Apple => Self {
+                ram_bytes,
+                vram_bytes: ram_bytes,
+                unified_memory: true,
+            },
+            Some(gpu) if gpu.supported => {
+                Self::new(ram_bytes, gpu.vram_bytes - gpu.vram_bytes / 10)
+            },
+            _ => Self::new(ram_bytes, 0),
+        }
+    }
+
+    /// Whether `usage` stays within the budget
+    pub fn allows(&self, usage: MemoryEstimate) -> bool {
+        if self.unified_memory {
+            usage.total_bytes() <= self.ram_bytes
+        } else {
+            usage.ram_bytes <= self.ram_bytes && usage.vram_bytes <= self.vram_bytes
+        }
+    }
+}
+
+impl std::fmt::Display for MemoryBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if *self == Self::unlimited() {
+            return write!(f, "unlimited");
+        }
+        if self.unified_memory {
+            return write!(f, "{} unified", format_bytes(self.ram_bytes));
+        }
+        write!(f, "{} RAM", format_bytes(self.ram_bytes))?;
+        if self.vram_bytes > 0 {
+            write!(f, " + {} VRAM", format_bytes(self.vram_bytes))?;
+        }
+        Ok(())
+    }
+}
+
+/// Load, eviction and prefix cache events, for metrics
+#[derive(Debug, Clone, PartialEq)]
+pub enum PoolEvent {
+    /// A model was loaded
+    Loaded {
+        model: String,
+        memory: MemoryEstimate,
+        load_time: Duration,
+    },
+    /// A model was unloaded to make room for another
+    Evicted {
+        model: String,
+        memory: MemoryEstimate,
+        /// The model that needed the room
+        for_model: String,
+    },
+    /// A model was unloaded on request
+    Unloaded {
+        model: String,
+        memory: MemoryEstimate,
+    },
+    /// A prompt was evaluated, reusing `cached_tokens` of its
+    /// `prompt_tokens` from the model's prefix cache
+    Prompt {
+        model: String,
+        prompt_tokens: u32,
+        cached_tokens: u32,
+    },
+}
+
+/// Callback for pool events
+pub type PoolEventCallback = Arc<dyn Fn(&PoolEvent) + Send + Sync>;
+
+/// State of a model in the pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PooledModel {
+    /// Name the model was added under
+    pub name: String,
+    /// Whether the model is loaded
+    pub loaded: bool,
+    /// Whether the model is exempt from eviction
+    pub pinned: bool,
+    /// Memory in use, if loaded
+    pub memory: Option<MemoryEstimate>,
+}
+
+struct PoolEntry {
+    /// Locked separately from the pool, so inference on one model does not
+    /// block the others; loading and unloading wait for running inference
+    instance: Arc<RwLock<ModelInstance>>,
+    /// Memory reserved while loaded
+    memory: Option<MemoryEstimate>,
+    pinned: bool,
+    /// Pool clock value at last use
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct PoolState {
+    entries: HashMap<String, PoolEntry>,
+    /// Incremented on every use, so ordering by it is LRU order
+    clock: u64,
+}
+
+impl PoolState {
+    fn usage(&self) -> MemoryEstimate {
+        self.entries
+            .values()
+            .filter_map(|e| e.memory)
+            .fold(MemoryEstimate::default(), |sum, m| sum + m)
+    }
+
+    /// Mark a model as just used
+    fn touch(&mut self, name: &str) -> ModelResult<&mut PoolEntry> {
+        self.clock += 1;
+        let clock = self.clock;
+        let entry = self
+            .entries
+            .get_mut(name)
+            .ok_or_else(|| ModelError::NotFound(name.to_string()))?;
+        entry.last_used = clock;
+        Ok(entry)
+    }
+
+    /// Least recently used models to unload so `needed` fits
+    ///
+    /// Only models that free memory of a kind that is short are chosen.
+    /// Nothing is unloaded here, so a failed plan leaves the pool as it was.
+    fn plan_eviction(
+        &self,
+        loading: &str,
+        needed: MemoryEstimate,
+        budget: &MemoryBudget,
+    ) -> ModelResult<Vec<String>> {
+        let mut candidates: Vec<(&String, &PoolEntry)> = self
+            .entries
+            .iter()
+            .filter(|(name, e)| e.memory.is_some() && !e.pinned && name.as_str() != loading)
+            .collect();
+        candidates.sort_by_key(|(_, e)| e.last_used);
+
+        let mut usage = self.usage() + needed;
+        let mut victims = Vec::new();
+        while !budget.allows(usage) {
+            let ram_short = budget.unified_memory || usage.ram_bytes > budget.ram_bytes;
+            let vram_short = budget.unified_memory || usage.vram_bytes > budget.vram_bytes;
+
+            let pos = candidates.iter().position(|(_, e)| {
+                let memory = e.memory.unwrap_or_default();
+                (ram_short && memory.ram_bytes > 0) || (vram_short && memory.vram_bytes > 0)
+            });
+            let Some(pos) = pos else {
+                let pinned = self
+                    .entries
+                    .values()
+                    .filter(|e| e.pinned)
+                    .filter_map(|e| e.memory)
+                    .fold(MemoryEstimate::default(), |sum, m| sum + m);
+                return Err(ModelError::InsufficientResources(format!(
+                    "{} needs {} but the pool budget is {} and pinned models use {}",
+                    loading, needed, budget, pinned
+                )));
+            };
+
+            let (name, entry) = candidates.remove(pos);
+            let memory = entry.memory.unwrap_or_default();
+            usage.ram_bytes -= memory.ram_bytes;
+            usage.vram_bytes -= memory.vram_bytes;
+            victims.push(name.clone());
+        }
+
+        Ok(victims)
+    }
+}
+
+/// Model pool for managing multiple loaded models
+pub struct ModelPool {
+    state: Arc<Mutex<PoolState>>,
+    /// Serializes loads, unloads and evictions
+    loading: Mutex<()>,
+    budget: MemoryBudget,
+    event_callback: Option<PoolEventCallback>,
+}
+
+impl ModelPool {
+    /// Create a pool with a memory budget
+    pub fn new(budget: MemoryBudget) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(PoolState::default())),
+            loading: Mutex::new(()),
+            budget,
+            event_callback: None,
+        }
+    }
+
+    /// Create a pool budgeted for the detected hardware
+    pub fn for_hardware(hardware: &HardwareInfo) -> Self {
+        Self::new(MemoryBudget::from_hardware(hardware))
+    }
+
+    /// Report load and eviction events
+    pub fn with_event_callback(mut self, callback: PoolEventCallback) -> Self {
+        self.event_callback = Some(callback);
+        self
+    }
+
+    /// Get the memory budget
+    pub fn budget(&self) -> MemoryBudget {
+        self.budget
+    }
+
+    fn emit(&self, event: PoolEvent) {
+        if let Some(callback) = &self.event_callback {
+            callback(&event);
+        }
+    }
+
+    /// Add a model to the pool
+    pub async fn add(&self, name: String, path: PathBuf) -> ModelResult<()> {
+        self.add_instance(ModelInstance::new(name, path)).await
+    }
+
+    /// Add a configured model instance, keyed by its name
+    ///
+    /// Replaces (and unloads) any model already added under that name.
+    pub async fn add_instance(&self, instance: ModelInstance) -> ModelResult<()> {
+        let _loading = self.loading.lock().await;
+        let name = instance.name().to_string();
+        let entry = PoolEntry {
+            instance: Arc::new(RwLock::new(instance)),
+            memory: None,
+            pinned: false,
+            last_used: 0,
+        };
+        let old = self.state.lock().await.entries.insert(name.clone(), entry);
+
+        // The pool lock is released before waiting for running inference
+        if let Some(old) = old {
+            if let Some(memory) = old.memory {
+                old.instance.write().await.unload();
+                self.emit(PoolEvent::Unloaded {
+                    model: name,
+                    memory,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Load a model, evicting least recently used models if needed
+    ///
+    /// Loads and evictions run one at a time, but the pool lock is only
+    /// held for bookkeeping: waiting for a victim's running inference to
+    /// finish, or for the model to load, does not block requests to other
+    /// models.
+    ///
+    /// # Errors
+    /// Returns [`ModelError::InsufficientResources`] if the model does not
+    /// fit even after evicting every unpinned model. Nothing is evicted in
+    /// that case. If the model fails to load, the models evicted for it are
+    /// loaded again.
+    pub async fn load(&self, name: &str) -> ModelResult<()> {
+        let _loading = self.loading.lock().await;
+
+        let instance = {
+            let mut state = self.state.lock().await;
+            let entry = state.touch(name)?;
+            if entry.memory.is_some() {
+                return Ok(());
+            }
+            entry.instance.clone()
+        };
+        let needed = instance.read().await.estimate_memory().await?;
+
+        let victims = {
+            let mut state = self.state.lock().await;
+            let mut victims = Vec::new();
+            for victim in state.plan_eviction(name, needed, &self.budget)? {
+                if let Some(entry) = state.entries.get_mut(&victim) {
+                    if let Some(memory) = entry.memory.take() {
+                        victims.push((victim, entry.instance.clone(), memory));
+                    }
+                }
+            }
+            victims
+        };
+
+        for (victim, handle, memory) in &victims {
+            handle.write().await.unload();
+            info!("Evicting {} ({}) to load {}", victim, memory, name);
+        }
+
+        let start = Instant::now();
+        let loaded = instance.write().await.load().await;
+        if let Err(e) = loaded {
+            warn!("Failed to load {}, restoring evicted models: {}", name, e);
+            self.restore(victims, name).await;
+            return Err(e);
+        }
+
+        for (victim, _, memory) in victims {
+            self.emit(PoolEvent::Evicted {
+                model: victim,
+                memory,
+                for_model: name.to_string(),
+            });
+        }
+
+        let usage = {
+            let mut state = self.state.lock().await;
+            state.touch(name)?.memory = Some(needed);
+            state.usage()
+        };
+        debug!("{} uses {}", name, needed);
+        info!("Pool memory in use: {} of {}", usage, self.budget);
+        self.emit(PoolEvent::Loaded {
+            model: name.to_string(),
+            memory: needed,
+            load_time: start.elapsed(),
+        });
+
+        Ok(())
+    }
+
+    /// Reload models evicted for a load that failed
+    ///
+    /// Victims that cannot be reloaded stay evicted and are reported as such.
+    async fn restore(
+        &self,
+        victims: Vec<(String, Arc<RwLock<ModelInstance>>, MemoryEstimate)>,
+        name: &str,
+    ) {
+        for (victim, handle, memory) in victims {
+            match handle.write().await.load().await {
+                Ok(()) => {
+                    let mut state = self.state.lock().await;
+                    if let Some(entry) = state.entries.get_mut(&victim) {
+                        entry.memory = Some(memory);
+                    }
+                },
+                Err(e) => {
+                    warn!("Could not reload {}: {}", victim, e);
+                    self.emit(PoolEvent::Evicted {
+                        model: victim,
+                        memory,
+                        for_model: name.to_string(),
+                    });
+                },
+            }
+        }
+    }
+
+    /// Unload a model, freeing its memory
+    ///
+    /// Waits for inference running on the model, without holding the pool
+    /// lock.
+    pub async fn unload(&self, name: &str) -> ModelResult<()> {
+        let _loading = self.loading.lock().await;
+        let (instance, memory) = {
+            let mut state = self.state.lock().await;
+            let entry = state
+                .entries
+                .get_mut(name)
+                .ok_or_else(|| ModelError::NotFound(name.to_string()))?;
+            (entry.instance.clone(), entry.memory.take())
+        };
+
+        instance.write().await.unload();
+        if let Some(memory) = memory {
+            self.emit(PoolEvent::Unloaded {
+                model: name.to_string(),
+                memory,
+            });
+        }
+        Ok(())
+    }
+
+    /// Keep a model resident: it is never evicted to make room
+    pub async fn pin(&self, name: &str) -> ModelResult<()> {
+        self.set_pinned(name, true).await
+    }
+
+    /// Allow a pinned model to be evicted again
+    pub async fn unpin(&self, name: &str) -> ModelResult<()> {
+        self.set_pinned(name, false).await
+    }
+
+    async fn set_pinned(&self, name: &str, pinned: bool) -> ModelResult<()> {
+        let mut state = self.state.lock().await;
+        let entry = state
+            .entries
+            .get_mut(name)
+            .ok_or_else(|| ModelError::NotFound(name.to_string()))?;
+        entry.pinned = pinned;
+        Ok(())
+    }
+
+    /// Mark a model as used and take a handle to it
+    ///
+    /// The pool lock is released before the caller runs inference.
+    async fn checkout(&self, name: &str) -> ModelResult<Arc<RwLock<ModelInstance>>> {
+        let mut state = self.state.lock().await;
+        Ok(state.touch(name)?.instance.clone())
+    }
+
+    /// Run inference on a specific model
+    ///
+    /// Only the model itself stays locked while it runs, so inference on
+    /// other models proceeds concurrently. Use a
+    /// [`Scheduler`](crate::scheduler::Scheduler) to queue, prioritise and
+    /// cancel requests.
+    pub async fn infer(
+        &self,
+        model_name: &str,
+        request: InferenceRequest,
+        token_callback: Option<TokenCallback>,
+    ) -> ModelResult<InferenceResponse> {
+        let instance = self.checkout(model_name).await?;
+        let instance = instance.read().await;
+        let response = instance.infer(request, token_callback).await?;
+        self.emit(PoolEvent::Prompt {
+            model: model_name.to_string(),
+            prompt_tokens: response.prompt_tokens,
+            cached_tokens: response.cached_tokens,
+        });
+        Ok(response)
+    }
+
+    /// Get embedding from a model
+    pub async fn embed(&self, model_name: &str, text: &str) -> ModelResult<Vec<f32>> {
+        let instance = self.checkout(model_name).await?;
+        let instance = instance.read().await;
+        instance.embed(text).await
+    }
+
+    /// Score query/passage pairs with a cross-encoder model
+    pub async fn rerank(
+        &self,
+        model_name: &str,
+        query: &str,
+        passages: &[&str],
+    ) -> ModelResult<Vec<f32>> {
+        let instance = self.checkout(model_name).await?;
+        let instance = instance.read().await;
+        instance.rerank(query, passages).await
+    }
+
+    /// Memory used by loaded models
+    pub async fn memory_used(&self) -> MemoryEstimate {
+        self.state.lock().await.usage()
+    }
+
+    /// State of every model, most recently used first
+    pub async fn status(&self) -> Vec<PooledModel> {
+        let state = self.state.lock().await;
+        let mut entries: Vec<(&String, &PoolEntry)> = state.entries.iter().collect();
+        entries.sort_by_key(|(_, e)| std::cmp::Reverse(e.last_used));
+        entries
+            .into_iter()
+            .map(|(name, e)| PooledModel {
+                name: name.clone(),
+                loaded: e.memory.is_some(),
+                pinned: e.pinned,
+                memory: e.memory,
+            })
+            .collect()
+    }
+
+    /// List all models
+    pub async fn list(&self) -> Vec<(String, bool)> {
+        let state = self.state.lock().await;
+        state
+            .entries
+            .iter()
+            .map(|(name, e)| (name.clone(), e.memory.is_some()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gguf::fixture::tiny_llama;
+    use crate::hardware::{CpuInfo, DiskInfo, GpuInfo, PlatformInfo};
+    use std::path::Path;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    /// Pool with `slots` CPU models' worth of RAM, and its model files
+    async fn pool_of(
+        dir: &Path,
+        names: &[&str],
+        slots: u64,
+    ) -> (ModelPool, Arc<std::sync::Mutex<Vec<PoolEvent>>>) {
+        let mut per_model = MemoryEstimate::default();
+        for name in names {
+            let path = dir.join(format!("{}.gguf", name));
+            tiny_llama().write(&path);
+            per_model = MemoryEstimate::for_model(&GgufMetadata::read(&path).unwrap(), 4096, 0);
+        }
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let pool = ModelPool::new(MemoryBudget::new(per_model.ram_bytes * slots, 0))
+            .with_event_callback(Arc::new(move |e: &PoolEvent| {
+                sink.lock().unwrap().push(e.clone())
+            }));
+        for name in names {
+            pool.add(name.to_string(), dir.join(format!("{}.gguf", name)))
+                .await
+                .unwrap();
+        }
+        (pool, events)
+    }
+
+    fn evicted(events: &std::sync::Mutex<Vec<PoolEvent>>) -> Vec<String> {
+        events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|e| match e {
+                PoolEvent::Evicted { model, .. } => Some(model.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn hardware(ram_gb: u64, gpu: Option<(GpuVendor, u64)>) -> HardwareInfo {
+        HardwareInfo {
+            cpu: CpuInfo {
+                name: "Test".to_string(),
+                cores: 4,
+                threads: 8,
+                arch: "x86_64".to_string(),
+                features: vec![],
+            },
+            ram_bytes: ram_gb * GB,
+            ram_available_bytes: ram_gb * GB / 2,
+            gpu: gpu.map(|(vendor, vram_gb)| GpuInfo {
+                name: "Test GPU".to_string(),
+                vendor,
+                vram_bytes: vram_gb * GB,
+                vram_available_bytes: vram_gb * GB,
+                cuda_version: None,
+                supported: true,
+            }),
+            disk: DiskInfo {
+                total_bytes: 100 * GB,
+                available_bytes: 50 * GB,
+                data_path: "/tmp".to_string(),
+            },
+            platform: PlatformInfo {
+                os: "linux".to_string(),
+                os_version: "6.0".to_string(),
+                arch: "x86_64".to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_model_pool() {
+        let pool = ModelPool::new(MemoryBudget::unlimited());
+        pool.add("test".to_string(), PathBuf::from("/tmp/test.gguf"))
+            .await
+            .unwrap();
+
+        let models = pool.list().await;
+        assert_eq!(models.len(), 1);
+        assert!(!models[0].1); // Not loaded yet
+        assert!(pool.load("test").await.is_err());
+        assert!(matches!(
+            pool.load("missing").await,
+            Err(ModelError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let (pool, events) = pool_of(dir.path(), &["a", "b", "c"], 2).await;
+
+        pool.load("a").await.unwrap();
+        pool.load("b").await.unwrap();
+        // Using "a" makes "b" the least recently used
+        pool.infer("a", InferenceRequest::new("hi".to_string()), None)
+            .await
+            .unwrap();
+        pool.load("c").await.unwrap();
+
+        assert_eq!(evicted(&events), vec!["b"]);
+        let status = pool.status().await;
+        let loaded: Vec<_> = status
+            .iter()
+            .filter(|m| m.loaded)
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(loaded, vec!["c", "a"]);
+        assert!(pool.budget().allows(pool.memory_used().await));
+    }
+
+    #[tokio::test]
+    async fn test_eviction_waits_without_blocking_pool() {
+        let dir = tempfile::tempdir().unwrap();
+        let (pool, events) = pool_of(dir.path(), &["a", "b", "c"], 2).await;
+        let pool = Arc::new(pool);
+        pool.load("a").await.unwrap();
+        pool.load("b").await.unwrap();
+
+        // "a" is mid-inference while "c" evicts it
+        let busy = pool.checkout("a").await.unwrap();
+        let running = busy.read().await;
+        pool.checkout("b").await.unwrap();
+        let loader = pool.clone();
+        let load = tokio::spawn(async move { loader.load("c").await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Requests to other models are not held up by the eviction
+        tokio::time::timeout(Duration::from_secs(1), pool.checkout("b"))
+            .await
+            .expect("pool lock held during eviction")
+            .unwrap();
+        assert!(!load.is_finished());
+
+        drop(running);
+        load.await.unwrap().unwrap();
+        assert_eq!(evicted(&events), vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_pinned_models_stay_resident() {
+        let dir = tempfile::tempdir().unwrap();
+        let (pool, events) = pool_of(dir.path(), &["pathos", "logos", "ethos"], 2).await;
+
+        pool.pin("pathos").await.unwrap();
+        pool.load("pathos").await.unwrap();
+        pool.load("logos").await.unwrap();
+        pool.load("ethos").await.unwrap();
+        pool.load("logos").await.unwrap();
+        assert_eq!(evicted(&events), vec!["logos", "ethos"]);
+
+        // Nothing can make room once every resident model is pinned
+        pool.pin("logos").await.unwrap();
+        let err = pool.load("ethos").await.unwrap_err();
+        assert!(matches!(err, ModelError::InsufficientResources(_)));
+        assert_eq!(evicted(&events).len(), 2);
+
+        pool.unpin("logos").await.unwrap();
+        pool.load("ethos").await.unwrap();
+        assert_eq!(evicted(&events), vec!["logos", "ethos", "logos"]);
+        assert!(matches!(
+            pool.pin("missing").await,
+            Err(ModelError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_model_larger_than_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let (pool, events) = pool_of(dir.path(), &["cpu"], 1).await;
+        pool.load("cpu").await.unwrap();
+
+        // Fully offloaded, but the budget has no VRAM
+        let gpu =
+            ModelInstance::new("gpu".to_string(), dir.path().join("cpu.gguf")).with_gpu_layers(99);
+        pool.add_instance(gpu).await.unwrap();
+        let err = pool.load("gpu").await.unwrap_err();
+        assert!(matches!(err, ModelError::InsufficientResources(_)));
+
+        // Nothing was evicted for the failed load
+        assert!(evicted(&events).is_empty());
+        assert!(pool
+            .status()
+            .await
+            .iter()
+            .any(|m| m.name == "cpu" && m.loaded));
+
+        pool.unload("cpu").await.unwrap();
+        assert_eq!(pool.memory_used().await, MemoryEstimate::default());
+    }
+
+    #[tokio::test]
+    async fn test_failed_load_restores_evicted_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let (pool, events) = pool_of(dir.path(), &["a", "b"], 1).await;
+        pool.load("a").await.unwrap();
+        pool.load("b").await.unwrap();
+        assert_eq!(evicted(&events), vec!["a"]);
+
+        // "a" still has its header, but its file is gone
+        std::fs::remove_file(dir.path().join("a.gguf")).unwrap();
+        let err = pool.load("a").await.unwrap_err();
+        assert!(matches!(err, ModelError::NotFound(_)));
+
+        // "b" was loaded again, and not reported as evicted
+        assert_eq!(evicted(&events), vec!["a"]);
+        let loaded: Vec<_> = pool
+            .status()
+            .await
+            .into_iter()
+            .filter(|m| m.loaded)
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(loaded, vec!["b"]);
+        pool.infer("b", InferenceRequest::new("hi".to_string()), None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reports_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let (pool, events) = pool_of(dir.path(), &["a"], 1).await;
+        pool.load("a").await.unwrap();
+        pool.load("a").await.unwrap();
+        pool.infer("a", InferenceRequest::new("hi".to_string()), None)
+            .await
+            .unwrap();
+        pool.unload("a").await.unwrap();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(&events[0], PoolEvent::Loaded { model, .. } if model == "a"));
+        assert!(matches!(
+            &events[1],
+            PoolEvent::Prompt { model, cached_tokens: 0, .. } if model == "a"
+        ));
+        assert!(matches!(&events[2], PoolEvent::Unloaded { model, .. } if model == "a"));
+    }
+
+    #[test]
+    fn test_budget_from_hardware() {
+        let budget = MemoryBudget::from_hardware(&hardware(8, None));
+        assert_eq!(budget, MemoryBudget::new(6 * GB, 0));
+
+        let budget = MemoryBudget::from_hardware(&hardware(32, Some((GpuVendor::Nvidia, 10))));
+        assert_eq!(budget, MemoryBudget::new(24 * GB, 9 * GB));
+
+        let budget = MemoryBudget::from_hardware(&hardware(16, Some((GpuVendor::Apple, 16))));
+        assert!(budget.unified_memory);
+        assert!(budget.allows(MemoryEstimate {
+            ram_bytes: 4 * GB,
+            vram_bytes: 8 * GB
+        }));
+        assert!(!budget.allows(MemoryEstimate {
+            ram_bytes: 4 * GB,
+            vram_bytes: 9 * GB
+        }));
+    }
+}
@@ -0,0 +1,245 @@
+//! Prompt Prefix Cache
+//!
+//! Consensus rounds send Logos and Ethos nearly the same prompt every time:
+//! the system prompt, query, Pathos framing and retrieved context are
+//! identical, and only the end changes. Evaluating a prompt is the most
+//! expensive part of a round on CPU-only machines, so the cache records
+//! which prefixes a model has evaluated; a later prompt that starts with one
+//! only needs the tokens after it evaluated.
+//!
+//! Prompts are split into blocks of [`PREFIX_BLOCK_TOKENS`] tokens. Each
+//! block is keyed by a hash of every token up to its end, so one key stands
+//! for a whole prefix and a lookup walks the blocks until the first miss.
+//! Only complete blocks are cached. When the cache is full, the least
+//! recently used blocks are dropped.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+
+/// Tokens per cached block
+pub const PREFIX_BLOCK_TOKENS: usize = 32;
+
+/// Hit and miss counts of a prefix cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrefixCacheStats {
+    /// Prompts that reused at least one cached block
+    pub hits: u64,
+    /// Prompts that reused nothing
+    pub misses: u64,
+    /// Prompt tokens served from the cache
+    pub reused_tokens: u64,
+    /// Blocks dropped to stay within capacity
+    pub evicted_blocks: u64,
+}
+
+impl PrefixCacheStats {
+    /// Fraction of prompts that hit the cache
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// One block of a cached prefix
+#[derive(Debug)]
+struct CachedBlock {
+    /// Lookup number of the last prompt that used this block
+    last_used: u64,
+    /// Position of the block in its prompt
+    depth: usize,
+}
+
+/// Cache of evaluated prompt prefixes for one model
+#[derive(Debug)]
+pub struct PrefixCache {
+    capacity_blocks: usize,
+    blocks: HashMap<u64, CachedBlock>,
+    lookups: u64,
+    stats: PrefixCacheStats,
+}
+
+impl PrefixCache {
+    /// Create a cache holding up to `capacity_tokens` prompt tokens
+    ///
+    /// A capacity below one block disables caching.
+    pub fn new(capacity_tokens: usize) -> Self {
+        Self {
+            capacity_blocks: capacity_tokens / PREFIX_BLOCK_TOKENS,
+            blocks: HashMap::new(),
+            lookups: 0,
+            stats: PrefixCacheStats::default(),
+        }
+    }
+
+    /// Whether anything can be cached
+    pub fn is_enabled(&self) -> bool {
+        self.capacity_blocks > 0
+    }
+
+    /// Find the longest cached prefix of a prompt, then cache the rest
+    ///
+    /// Returns how many leading tokens are part of a cached prefix.
+    pub fn lookup(&mut self, tokens: &[u32]) -> usize {
+        self.lookups += 1;
+        let hashes = block_hashes(tokens);
+
+        let mut cached_blocks = 0;
+        for hash in &hashes {
+            match self.blocks.get_mut(hash) {
+                Some(block) => {
+                    block.last_used = self.lookups;
+                    cached_blocks += 1;
+                },
+                None => break,
+            }
+        }
+
+        let cached_tokens = cached_blocks * PREFIX_BLOCK_TOKENS;
+        if cached_tokens > 0 {
+            self.stats.hits += 1;
+            self.stats.reused_tokens += cached_tokens as u64;
+        } else {
+            self.stats.misses += 1;
+        }
+
+        if self.is_enabled() {
+            for (depth, hash) in hashes.iter().enumerate().skip(cached_blocks) {
+                self.blocks.insert(
+                    *hash,
+                    CachedBlock {
+                        last_used: self.lookups,
+                        depth,
+                    },
+                );
+            }
+            self.evict();
+        }
+
+        cached_tokens
+    }
+
+    /// Tokens currently cached
+    pub fn cached_tokens(&self) -> usize {
+        self.blocks.len() * PREFIX_BLOCK_TOKENS
+    }
+
+    /// Hit and miss counts so far
+    pub fn stats(&self) -> PrefixCacheStats {
+        self.stats
+    }
+
+    /// Drop every cached block, keeping the counts
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    /// Drop least recently used blocks until within capacity
+    ///
+    /// Blocks of one prompt share a `last_used`, so ties are broken by
+    /// dropping the longest prefixes first; a prefix whose earlier block
+    /// was dropped can no longer be reached.
+    fn evict(&mut self) {
+        let excess = self.blocks.len().saturating_sub(self.capacity_blocks);
+        if excess == 0 {
+            return;
+        }
+
+        let mut by_age: Vec<(u64, Reverse<usize>, u64)> = self
+            .blocks
+            .iter()
+            .map(|(hash, block)| (block.last_used, Reverse(block.depth), *hash))
+            .collect();
+        by_age.sort_unstable();
+        for (_, _, hash) in by_age.into_iter().take(excess) {
+            self.blocks.remove(&hash);
+        }
+        self.stats.evicted_blocks += excess as u64;
+    }
+}
+
+/// Key for every complete block: a hash of all tokens up to its end
+fn block_hashes(tokens: &[u32]) -> Vec<u64> {
+    let mut prefix = 0u64;
+    tokens
+        .as_chunks::<PREFIX_BLOCK_TOKENS>()
+        .0
+        .iter()
+        .map(|block| {
+            let mut hasher = DefaultHasher::new();
+            prefix.hash(&mut hasher);
+            block.hash(&mut hasher);
+            prefix = hasher.finish();
+            prefix
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(range: std::ops::Range<u32>) -> Vec<u32> {
+        range.collect()
+    }
+
+    #[test]
+    fn test_reuses_shared_prefix() {
+        let mut cache = PrefixCache::new(1024);
+
+        // Round one: three complete blocks and a partial one
+        let round_one = tokens(0..100);
+        assert_eq!(cache.lookup(&round_one), 0);
+        assert_eq!(cache.cached_tokens(), 96);
+
+        // Round two appends feedback: the first three blocks are reused
+        let mut round_two = round_one.clone();
+        round_two.extend(500..540);
+        assert_eq!(cache.lookup(&round_two), 96);
+
+        // A change in the first block invalidates everything after it
+        let mut edited = round_two.clone();
+        edited[0] = 999;
+        assert_eq!(cache.lookup(&edited), 0);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 2));
+        assert_eq!(stats.reused_tokens, 96);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let block = PREFIX_BLOCK_TOKENS as u32;
+        let mut cache = PrefixCache::new(4 * PREFIX_BLOCK_TOKENS);
+
+        let a = tokens(0..2 * block);
+        let b = tokens(1000..1000 + 2 * block);
+        cache.lookup(&a);
+        cache.lookup(&b);
+        // Touch `a` so `b` is older
+        assert_eq!(cache.lookup(&a), 2 * PREFIX_BLOCK_TOKENS);
+
+        let c = tokens(5000..5000 + 2 * block);
+        cache.lookup(&c);
+        assert_eq!(cache.cached_tokens(), 4 * PREFIX_BLOCK_TOKENS);
+        assert_eq!(cache.stats().evicted_blocks, 2);
+        assert_eq!(cache.lookup(&a), 2 * PREFIX_BLOCK_TOKENS);
+        assert_eq!(cache.lookup(&b), 0);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let mut cache = PrefixCache::new(PREFIX_BLOCK_TOKENS - 1);
+        assert!(!cache.is_enabled());
+        cache.lookup(&tokens(0..64));
+        assert_eq!(cache.lookup(&tokens(0..64)), 0);
+        assert_eq!(cache.cached_tokens(), 0);
+        assert_eq!(cache.stats().misses, 2);
+    }
+}
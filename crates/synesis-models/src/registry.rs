@@ -6,8 +6,10 @@
 //!
 //! The registry serves as the central source of truth for:
 //! - **Available models**: Built-in model definitions from HuggingFace
-//! - **Installed models**: Scanned from local disk
+//! - **Installed models**: Scanned from local disk, with metadata read from
+//!   each file's GGUF header
 //! - **Model status**: Tracking download/ready/loaded states
+//! - **Installs**: Verified downloads recorded in `models.lock`
 //! - **Recommendations**: Optimal models for each agent (Pathos, Logos, Ethos)
 //!
 //! # Thread Safety
@@ -25,12 +27,15 @@
 //! - Status update: O(m) where m = quantizations per model
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, warn};
 
-use crate::{ModelResult, Quantization};
+use crate::downloader::VerifiedDownload;
+use crate::gguf::GgufMetadata;
+use crate::lockfile::{LockEntry, ModelLock, LOCKFILE_NAME};
+use crate::{ModelError, ModelResult, Quantization};
 
 /// Model status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -81,12 +86,15 @@ pub struct QuantizedVariant {
     pub filename: String,
     /// File size in bytes
     pub size_bytes: u64,
-    /// SHA256 checksum
-    pub sha256: String,
+    /// Verified SHA256 checksum (from `models.lock`, once installed)
+    pub sha256: Option<String>,
     /// Status
     pub status: ModelStatus,
     /// Local path (if downloaded)
     pub local_path: Option<PathBuf>,
+    /// GGUF header of the local file (set by `scan_local`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gguf: Option<GgufMetadata>,
 }
 
 /// Model registry
@@ -111,6 +119,8 @@ pub struct ModelRegistry {
     models_dir: PathBuf,
     /// Registry of known models
     models: HashMap<String, ModelInfo>,
+    /// Installed files, as recorded in `models.lock`
+    lock: ModelLock,
     /// Currently loaded model
     #[allow(dead_code)]
     loaded_model: Option<String>,
@@ -135,12 +145,71 @@ impl ModelRegistry {
         let mut registry = Self {
             models_dir,
             models: HashMap::new(),
+            lock: ModelLock::default(),
             loaded_model: None,
         };
         registry.load_builtin_models();
         registry
     }
 
+    /// Open the registry for a models directory
+    ///
+    /// Like [`new`](Self::new), but also loads `models.lock` and marks every
+    /// locked file that is still on disk as `Ready`, with its verified
+    /// checksum. Locked files that match no built-in model are registered
+    /// under their locked model id, described by their GGUF header.
+    ///
+    /// # Errors
+    /// Returns error if the lockfile exists but cannot be read
+    pub fn open(models_dir: PathBuf) -> ModelResult<Self> {
+        let mut registry = Self::new(models_dir);
+        registry.lock = ModelLock::load(&registry.lockfile_path())?;
+
+        for entry in registry.lock.entries() {
+            let path = registry.models_dir.join(&entry.filename);
+            if !path.exists() {
+                warn!("Locked model file is missing: {}", path.display());
+                continue;
+            }
+            let variant = registry
+                .models
+                .values_mut()
+                .flat_map(|m| m.quantizations.iter_mut())
+                .find(|v| v.filename == entry.filename);
+            if let Some(variant) = variant {
+                variant.sha256 = Some(entry.sha256.clone());
+                variant.size_bytes = entry.size_bytes;
+                variant.local_path = Some(path);
+                variant.status = ModelStatus::Ready;
+                continue;
+            }
+
+            match GgufMetadata::read(&path) {
+                Ok(meta) => {
+                    if let Some(mut model) =
+                        Self::model_from_header(entry.model.clone(), &entry.filename, &path, meta)
+                    {
+                        model.quantizations[0].sha256 = Some(entry.sha256.clone());
+                        registry.models.insert(model.id.clone(), model);
+                    }
+                },
+                Err(e) => warn!("Skipping invalid model file {}: {}", path.display(), e),
+            }
+        }
+
+        Ok(registry)
+    }
+
+    /// Path of the lockfile in the models directory
+    pub fn lockfile_path(&self) -> PathBuf {
+        self.models_dir.join(LOCKFILE_NAME)
+    }
+
+    /// Installed files recorded in `models.lock`
+    pub fn lock(&self) -> &ModelLock {
+        &self.lock
+    }
+
     /// Load built-in model definitions
     ///
     /// Populates the registry with known models from HuggingFace.
@@ -166,9 +235,10 @@ impl ModelRegistry {
                     quantization: Quantization::Q4,
                     filename: "phi-3-mini-4k-instruct-q4_k_m.gguf".to_string(),
                     size_bytes: 2_200_000_000,
-                    sha256: "placeholder".to_string(),
+                    sha256: None,
                     status: ModelStatus::Available,
                     local_path: None,
+                    gguf: None,
                 }],
                 hf_repo: "microsoft/Phi-3-mini-4k-instruct-gguf".to_string(),
                 license: "MIT".to_string(),
@@ -190,9 +260,10 @@ impl ModelRegistry {
                     quantization: Quantization::Q4,
                     filename: "llama-3.2-3b-instruct-q4_k_m.gguf".to_string(),
                     size_bytes: 2_000_000_000,
-                    sha256: "placeholder".to_string(),
+                    sha256: None,
                     status: ModelStatus::Available,
                     local_path: None,
+                    gguf: None,
                 }],
                 hf_repo: "meta-llama/Llama-3.2-3B-Instruct-GGUF".to_string(),
                 license: "Llama 3.2 Community".to_string(),
@@ -215,17 +286,19 @@ impl ModelRegistry {
                         quantization: Quantization::Q4,
                         filename: "llama-3.2-8b-instruct-q4_k_m.gguf".to_string(),
                         size_bytes: 4_700_000_000,
-                        sha256: "placeholder".to_string(),
+                        sha256: None,
                         status: ModelStatus::Available,
                         local_path: None,
+                        gguf: None,
                     },
                     QuantizedVariant {
                         quantization: Quantization::Q8,
                         filename: "llama-3.2-8b-instruct-q8_0.gguf".to_string(),
                         size_bytes: 8_500_000_000,
-                        sha256: "placeholder".to_string(),
+                        sha256: None,
                         status: ModelStatus::Available,
                         local_path: None,
+                        gguf: None,
                     },
                 ],
                 hf_repo: "meta-llama/Llama-3.2-8B-Instruct-GGUF".to_string(),
@@ -248,9 +321,10 @@ impl ModelRegistry {
                     quantization: Quantization::Q4,
                     filename: "mistral-7b-instruct-v0.3-q4_k_m.gguf".to_string(),
                     size_bytes: 4_100_000_000,
-                    sha256: "placeholder".to_string(),
+                    sha256: None,
                     status: ModelStatus::Available,
                     local_path: None,
+                    gguf: None,
                 }],
                 hf_repo: "mistralai/Mistral-7B-Instruct-v0.3-GGUF".to_string(),
                 license: "Apache 2.0".to_string(),
@@ -272,9 +346,10 @@ impl ModelRegistry {
                     quantization: Quantization::F16,
                     filename: "bge-micro-v1.5.gguf".to_string(),
                     size_bytes: 48_000_000,
-                    sha256: "placeholder".to_string(),
+                    sha256: None,
                     status: ModelStatus::Available,
                     local_path: None,
+                    gguf: None,
                 }],
                 hf_repo: "BAAI/bge-micro-v1.5".to_string(),
                 license: "MIT".to_string(),
@@ -296,9 +371,10 @@ impl ModelRegistry {
                     quantization: Quantization::Q4,
                     filename: "qwen2.5-7b-instruct-q4_k_m.gguf".to_string(),
                     size_bytes: 4_500_000_000,
-                    sha256: "placeholder".to_string(),
+                    sha256: None,
                     status: ModelStatus::Available,
                     local_path: None,
+                    gguf: None,
                 }],
                 hf_repo: "Qwen/Qwen2.5-7B-Instruct-GGUF".to_string(),
                 license: "Apache 2.0".to_string(),
@@ -388,6 +464,9 @@ impl ModelRegistry {
     }
 
     /// Set local path for a downloaded model
+    ///
+    /// Only updates the in-memory registry; use
+    /// [`record_install`](Self::record_install) to persist a verified install.
     pub fn set_local_path(&mut self, id: &str, quant: Quantization, path: PathBuf) {
         if let Some(model) = self.models.get_mut(id) {
             if let Some(variant) = model
@@ -401,6 +480,64 @@ impl ModelRegistry {
         }
     }
 
+    /// Record a verified download as installed
+    ///
+    /// Marks the variant `Ready` with the download's checksum and writes the
+    /// install to `models.lock`. Only a [`VerifiedDownload`] can be recorded,
+    /// so nothing is marked installed without a verified checksum.
+    ///
+    /// # Errors
+    /// Returns error if the model or quantization is unknown, or the
+    /// lockfile cannot be written
+    #[instrument(skip(self, download))]
+    pub fn record_install(
+        &mut self,
+        id: &str,
+        quant: Quantization,
+        download: &VerifiedDownload,
+    ) -> ModelResult<()> {
+        let variant = self
+            .models
+            .get_mut(id)
+            .and_then(|m| m.quantizations.iter_mut().find(|q| q.quantization == quant))
+            .ok_or_else(|| ModelError::NotFound(format!("{} ({})", id, quant)))?;
+
+        let entry = LockEntry::from_download(id, Some(quant), download)?;
+        variant.filename = entry.filename.clone();
+        variant.sha256 = Some(download.sha256.clone());
+        variant.size_bytes = download.size_bytes;
+        variant.local_path = Some(download.path.clone());
+        variant.status = ModelStatus::Ready;
+
+        self.lock.insert(entry);
+        self.lock.save(&self.lockfile_path())?;
+        info!("Recorded install of {} {} ({})", id, quant, download.sha256);
+
+        Ok(())
+    }
+
+    /// Remove an installed variant from `models.lock`
+    ///
+    /// The file itself is left alone. Returns `true` if an entry was removed.
+    pub fn remove_install(&mut self, id: &str, quant: Quantization) -> ModelResult<bool> {
+        let Some(variant) = self
+            .models
+            .get_mut(id)
+            .and_then(|m| m.quantizations.iter_mut().find(|q| q.quantization == quant))
+        else {
+            return Ok(false);
+        };
+
+        variant.status = ModelStatus::Available;
+        variant.local_path = None;
+        variant.sha256 = None;
+        if self.lock.remove(&variant.filename).is_none() {
+            return Ok(false);
+        }
+        self.lock.save(&self.lockfile_path())?;
+        Ok(true)
+    }
+
     /// Get recommended models for each agent
     pub fn get_recommended(&self) -> RecommendedModels {
         RecommendedModels {
@@ -453,13 +590,17 @@ impl ModelRegistry {
 
     /// Scan local directory for existing models
     ///
-    /// Searches the models directory for `.gguf` files and updates their status to `Ready`.
-    /// This should be called after creating the registry to detect pre-downloaded models.
+    /// Searches the models directory for `.gguf` files and reads each file's
+    /// GGUF header. Known models are marked `Ready` and their size and
+    /// context length are taken from the file; files with a corrupt or
+    /// truncated header are marked `Failed`. GGUF files that match no known
+    /// model are registered under their file stem, described entirely by
+    /// their header.
     ///
     /// # Performance
     /// O(n) where n = files in models directory
     /// - Filesystem I/O is the bottleneck
-    /// - Each file requires a stat() call
+    /// - Each file requires a stat() call and a header read
     ///
     /// # Errors
     /// Returns error if models directory cannot be read
@@ -480,29 +621,116 @@ impl ModelRegistry {
             let entry = entry?;
             let path = entry.path();
 
-            if path.extension().is_some_and(|ext| ext == "gguf") {
-                if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                    // Try to match with known models
-                    for model in self.models.values_mut() {
-                        for variant in &mut model.quantizations {
-                            if variant.filename == filename {
-                                variant.local_path = Some(path.clone());
-                                variant.status = ModelStatus::Ready;
-                                found += 1;
-                                info!(
-                                    "Found existing model: {} at {}",
-                                    variant.filename,
-                                    path.display()
-                                );
-                            }
-                        }
+            if !path.extension().is_some_and(|ext| ext == "gguf") {
+                continue;
+            }
+            let Some(filename) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+
+            let header = GgufMetadata::read(&path);
+            if let Err(e) = &header {
+                warn!("Skipping invalid model file {}: {}", path.display(), e);
+            }
+
+            // Try to match with known models
+            let mut matched = false;
+            for model in self.models.values_mut() {
+                for variant in &mut model.quantizations {
+                    if variant.filename != filename {
+                        continue;
+                    }
+                    matched = true;
+                    variant.local_path = Some(path.clone());
+
+                    let Ok(meta) = &header else {
+                        variant.status = ModelStatus::Failed;
+                        variant.gguf = None;
+                        continue;
+                    };
+                    if meta.quantization.is_some_and(|q| q != variant.quantization) {
+                        warn!(
+                            "{} is {} but registered as {}",
+                            filename, meta.file_type, variant.quantization
+                        );
+                    }
+                    if let Some(context_length) = meta.context_length {
+                        model.context_length = context_length;
                     }
+                    variant.size_bytes = meta.file_size;
+                    variant.status = ModelStatus::Ready;
+                    variant.gguf = Some(meta.clone());
+                    if let Some(entry) = self.lock.get(filename) {
+                        variant.sha256 = Some(entry.sha256.clone());
+                    }
+                    found += 1;
+                    info!(
+                        "Found existing model: {} at {}",
+                        variant.filename,
+                        path.display()
+                    );
+                }
+            }
+
+            if matched {
+                continue;
+            }
+            if let Ok(meta) = header {
+                let id = filename.trim_end_matches(".gguf").to_lowercase();
+                if let Some(model) = Self::model_from_header(id, filename, &path, meta) {
+                    info!(
+                        "Found unregistered model: {} at {}",
+                        model.id,
+                        path.display()
+                    );
+                    self.models.insert(model.id.clone(), model);
+                    found += 1;
                 }
             }
         }
 
         Ok(found)
     }
+
+    /// Describe a GGUF file that matches no built-in model
+    ///
+    /// Returns `None` if the file's quantization is not one the registry
+    /// can track.
+    fn model_from_header(
+        id: String,
+        filename: &str,
+        path: &Path,
+        meta: GgufMetadata,
+    ) -> Option<ModelInfo> {
+        let Some(quantization) = meta.quantization else {
+            debug!(
+                "Not registering {}: unsupported quantization {}",
+                filename, meta.file_type
+            );
+            return None;
+        };
+
+        Some(ModelInfo {
+            name: meta.name.clone().unwrap_or_else(|| id.clone()),
+            family: meta.architecture.clone(),
+            parameters: meta.parameters_label(),
+            hf_repo: String::new(),
+            license: "Unknown".to_string(),
+            use_case: "Local model".to_string(),
+            context_length: meta.context_length.unwrap_or(0),
+            recommended: false,
+            quantizations: vec![QuantizedVariant {
+                quantization,
+                filename: filename.to_string(),
+                size_bytes: meta.file_size,
+                sha256: None,
+                status: ModelStatus::Ready,
+                local_path: Some(path.to_path_buf()),
+                gguf: Some(meta),
+            }],
+            id,
+        })
+    }
 }
 
 /// Recommended models for the tripartite council
@@ -517,6 +745,7 @@ pub struct RecommendedModels {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gguf::fixture::{tiny_llama, GgufFixture};
     use tempfile::tempdir;
 
     #[test]
@@ -539,6 +768,44 @@ mod tests {
         assert_eq!(recommended.logos, "llama-3.2-8b");
     }
 
+    #[test]
+    fn test_record_install_persists_to_lockfile() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("phi-3-mini-4k-instruct-q4_k_m.gguf");
+        std::fs::write(&path, b"weights").unwrap();
+        let download = VerifiedDownload {
+            path: path.clone(),
+            source: "microsoft/Phi-3-mini-4k-instruct-gguf".to_string(),
+            revision: Some("abc123".to_string()),
+            size_bytes: 7,
+            sha256: "a".repeat(64),
+        };
+
+        let mut registry = ModelRegistry::new(dir.path().to_path_buf());
+        registry
+            .record_install("phi-3-mini", Quantization::Q4, &download)
+            .unwrap();
+        assert!(registry
+            .record_install("phi-3-mini", Quantization::Q8, &download)
+            .is_err());
+
+        // A fresh registry picks the install up from models.lock
+        let mut registry = ModelRegistry::open(dir.path().to_path_buf()).unwrap();
+        assert!(registry.is_installed("phi-3-mini", Quantization::Q4));
+        let variant = &registry.get("phi-3-mini").unwrap().quantizations[0];
+        assert_eq!(variant.sha256.as_deref(), Some("a".repeat(64).as_str()));
+        assert_eq!(variant.size_bytes, 7);
+        let entry = registry.lock().get(&variant.filename).unwrap();
+        assert_eq!(entry.revision.as_deref(), Some("abc123"));
+        assert_eq!(entry.quantization, Some(Quantization::Q4));
+
+        assert!(registry
+            .remove_install("phi-3-mini", Quantization::Q4)
+            .unwrap());
+        let registry = ModelRegistry::open(dir.path().to_path_buf()).unwrap();
+        assert!(!registry.is_installed("phi-3-mini", Quantization::Q4));
+    }
+
     #[test]
     fn test_status_update() {
         let dir = tempdir().unwrap();
@@ -549,4 +816,46 @@ mod tests {
         let model = registry.get("phi-3-mini").unwrap();
         assert_eq!(model.quantizations[0].status, ModelStatus::Downloading);
     }
+
+    #[test]
+    fn test_scan_local_reads_gguf_headers() {
+        let dir = tempdir().unwrap();
+        GgufFixture::new("phi3")
+            .u32("general.file_type", 15)
+            .u32("phi3.context_length", 4096)
+            .tensor("token_embd.weight", &[256, 8], 12)
+            .write(&dir.path().join("phi-3-mini-4k-instruct-q4_k_m.gguf"));
+        std::fs::write(
+            dir.path().join("llama-3.2-3b-instruct-q4_k_m.gguf"),
+            b"GGUF\x03",
+        )
+        .unwrap();
+        tiny_llama().write(&dir.path().join("Tiny-Llama-Q4_K_M.gguf"));
+
+        let mut registry = ModelRegistry::new(dir.path().to_path_buf());
+        assert_eq!(registry.scan_local().unwrap(), 2);
+
+        let phi = &registry.get("phi-3-mini").unwrap().quantizations[0];
+        assert_eq!(phi.status, ModelStatus::Ready);
+        assert_eq!(phi.gguf.as_ref().unwrap().architecture, "phi3");
+        assert_eq!(
+            phi.size_bytes,
+            std::fs::metadata(phi.local_path.as_ref().unwrap())
+                .unwrap()
+                .len()
+        );
+
+        // Corrupt file is found but not usable
+        let llama = &registry.get("llama-3.2-3b").unwrap().quantizations[0];
+        assert_eq!(llama.status, ModelStatus::Failed);
+        assert!(!registry.is_installed("llama-3.2-3b", Quantization::Q4));
+
+        // Unknown file is registered from its header
+        let tiny = registry.get("tiny-llama-q4_k_m").unwrap();
+        assert_eq!(tiny.name, "Tiny Llama");
+        assert_eq!(tiny.family, "llama");
+        assert_eq!(tiny.context_length, 2048);
+        assert_eq!(tiny.parameters, "74K");
+        assert!(registry.is_installed("tiny-llama-q4_k_m", Quantization::Q4));
+    }
 }
@@ -0,0 +1,583 @@
+//! Request Scheduler
+//!
+//! Queues requests per model in front of a [`ModelPool`], so agents and
+//! concurrent CLI/API callers share models predictably:
+//!
+//! - **Priorities**: interactive requests run before queued background work
+//!   such as indexing embeddings.
+//! - **Fair sharing**: within a priority, sessions take turns, so one session
+//!   submitting many requests cannot starve another.
+//! - **Backpressure**: a model's queue holds at most
+//!   [`SchedulerConfig::max_queue_depth`] waiting requests; further requests
+//!   fail with [`ModelError::QueueFull`] instead of waiting indefinitely.
+//! - **Cancellation and deadlines**: a request stops waiting (or running)
+//!   when its [`CancellationToken`] is cancelled or its deadline passes.
+//!
+//! Each model runs up to [`SchedulerConfig::max_concurrent_per_model`]
+//! requests at once; different models run in parallel.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+pub use tokio_util::sync::CancellationToken;
+
+use crate::inference::{InferenceRequest, InferenceResponse, TokenCallback};
+use crate::pool::ModelPool;
+use crate::{ModelError, ModelResult};
+
+/// Session for requests that don't name one
+const DEFAULT_SESSION: &str = "default";
+
+/// Request priority
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// Background work such as indexing, run when no interactive request waits
+    Background,
+    /// A user is waiting for the result
+    #[default]
+    Interactive,
+}
+
+/// Scheduler limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerConfig {
+    /// Waiting requests per model before new ones are rejected
+    pub max_queue_depth: usize,
+    /// Requests running at once on one model
+    pub max_concurrent_per_model: usize,
+    /// Deadline for requests that don't set their own
+    pub default_deadline: Option<Duration>,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_queue_depth: 32,
+            max_concurrent_per_model: 1,
+            default_deadline: Some(Duration::from_secs(300)),
+        }
+    }
+}
+
+/// How a request is scheduled
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Priority of the request
+    pub priority: Priority,
+    /// Session the request belongs to, for fair sharing
+    pub session: Option<String>,
+    /// Time allowed from submission to completion
+    pub deadline: Option<Duration>,
+    /// Token that cancels the request
+    pub cancel: Option<CancellationToken>,
+}
+
+impl RequestOptions {
+    /// Options for a request a user is waiting on
+    pub fn interactive() -> Self {
+        Self::default()
+    }
+
+    /// Options for background work
+    pub fn background() -> Self {
+        Self {
+            priority: Priority::Background,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_session(mut self, session: impl Into<String>) -> Self {
+        self.session = Some(session.into());
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_cancel_token(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+}
+
+/// A request waiting for a slot
+struct Waiter {
+    ticket: u64,
+    wake: oneshot::Sender<()>,
+}
+
+/// Waiting requests of one priority, served round-robin across sessions
+#[derive(Default)]
+struct FairQueue {
+    /// Sessions with waiting requests, next to be served first
+    sessions: VecDeque<String>,
+    requests: HashMap<String, VecDeque<Waiter>>,
+}
+
+impl FairQueue {
+    fn push(&mut self, session: &str, waiter: Waiter) {
+        let requests = self.requests.entry(session.to_string()).or_default();
+        if requests.is_empty() {
+            self.sessions.push_back(session.to_string());
+        }
+        requests.push_back(waiter);
+    }
+
+    fn pop(&mut self) -> Option<Waiter> {
+        let session = self.sessions.pop_front()?;
+        let requests = self.requests.get_mut(&session)?;
+        let waiter = requests.pop_front();
+        if requests.is_empty() {
+            self.requests.remove(&session);
+        } else {
+            self.sessions.push_back(session);
+        }
+        waiter
+    }
+
+    fn remove(&mut self, ticket: u64) -> bool {
+        let Some((session, requests)) = self
+            .requests
+            .iter_mut()
+            .find(|(_, r)| r.iter().any(|w| w.ticket == ticket))
+        else {
+            return false;
+        };
+
+        requests.retain(|w| w.ticket != ticket);
+        if requests.is_empty() {
+            let session = session.clone();
+            self.requests.remove(&session);
+            self.sessions.retain(|s| *s != session);
+        }
+        true
+    }
+
+    fn len(&self) -> usize {
+        self.requests.values().map(VecDeque::len).sum()
+    }
+}
+
+/// Requests for one model
+#[derive(Default)]
+struct ModelQueue {
+    interactive: FairQueue,
+    background: FairQueue,
+    /// Tickets holding a slot
+    running: HashSet<u64>,
+}
+
+impl ModelQueue {
+    fn waiting(&self) -> usize {
+        self.interactive.len() + self.background.len()
+    }
+
+    /// Hand free slots to the next waiting requests
+    fn dispatch(&mut self, max_concurrent: usize) {
+        while self.running.len() < max_concurrent {
+            let Some(waiter) = self.interactive.pop().or_else(|| self.background.pop()) else {
+                break;
+            };
+            self.running.insert(waiter.ticket);
+            // If the requester has gone, its ticket guard releases the slot
+            let _ = waiter.wake.send(());
+        }
+    }
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    queues: HashMap<String, ModelQueue>,
+    next_ticket: u64,
+}
+
+/// Releases a request's place in the queue, or its slot, however the
+/// request ends
+struct TicketGuard {
+    state: Arc<Mutex<SchedulerState>>,
+    model: String,
+    ticket: u64,
+    max_concurrent: usize,
+}
+
+impl Drop for TicketGuard {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(queue) = state.queues.get_mut(&self.model) else {
+            return;
+        };
+
+        let was_waiting =
+            queue.interactive.remove(self.ticket) || queue.background.remove(self.ticket);
+        if !was_waiting && queue.running.remove(&self.ticket) {
+            queue.dispatch(self.max_concurrent);
+        }
+        if queue.running.is_empty() && queue.waiting() == 0 {
+            state.queues.remove(&self.model);
+        }
+    }
+}
+
+/// Queues requests per model in front of a [`ModelPool`]
+#[derive(Clone)]
+pub struct Scheduler {
+    pool: Arc<ModelPool>,
+    config: SchedulerConfig,
+    state: Arc<Mutex<SchedulerState>>,
+}
+
+impl Scheduler {
+    /// Create a scheduler for a pool
+    pub fn new(pool: Arc<ModelPool>, config: SchedulerConfig) -> Self {
+        Self {
+            pool,
+            config: SchedulerConfig {
+                max_concurrent_per_model: config.max_concurrent_per_model.max(1),
+                ..config
+            },
+            state: Arc::new(Mutex::new(SchedulerState::default())),
+        }
+    }
+
+    /// Get the pool requests run on
+    pub fn pool(&self) -> &Arc<ModelPool> {
+        &self.pool
+    }
+
+    /// Run inference once the model has a free slot
+    ///
+    /// # Errors
+    /// - [`ModelError::QueueFull`] if the model's queue is full
+    /// - [`ModelError::Cancelled`] if the request's token is cancelled
+    /// - [`ModelError::DeadlineExceeded`] if the deadline passes first
+    pub async fn infer(
+        &self,
+        model_name: &str,
+        request: InferenceRequest,
+        token_callback: Option<TokenCallback>,
+        options: RequestOptions,
+    ) -> ModelResult<InferenceResponse> {
+        self.submit(model_name, options, |pool| async move {
+            pool.infer(model_name, request, token_callback).await
+        })
+        .await
+    }
+
+    /// Get an embedding once the model has a free slot
+    pub async fn embed(
+        &self,
+        model_name: &str,
+        text: &str,
+        options: RequestOptions,
+    ) -> ModelResult<Vec<f32>> {
+        self.submit(model_name, options, |pool| async move {
+            pool.embed(model_name, text).await
+        })
+        .await
+    }
+
+    /// Requests waiting (not running) for a model
+    pub fn queue_depth(&self, model_name: &str) -> usize {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.queues.get(model_name).map_or(0, ModelQueue::waiting)
+    }
+
+    /// Requests running on a model
+    pub fn running(&self, model_name: &str) -> usize {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.queues.get(model_name).map_or(0, |q| q.running.len())
+    }
+
+    /// Queue `work` for a model and run it when a slot is free
+    async fn submit<T, F, Fut>(
+        &self,
+        model_name: &str,
+        options: RequestOptions,
+        work: F,
+    ) -> ModelResult<T>
+    where
+        F: FnOnce(Arc<ModelPool>) -> Fut,
+        Fut: Future<Output = ModelResult<T>>,
+    {
+        let deadline = options
+            .deadline
+            .or(self.config.default_deadline)
+            .map(|d| Instant::now() + d);
+        let cancel = options.cancel.unwrap_or_default();
+        let session = options.session.as_deref().unwrap_or(DEFAULT_SESSION);
+
+        let (guard, wake) = self.enqueue(model_name, session, options.priority)?;
+
+        let pool = self.pool.clone();
+        let run = async move {
+            wake.await
+                .map_err(|_| ModelError::Internal("Scheduler dropped request".to_string()))?;
+            debug!("Running request {} on {}", guard.ticket, guard.model);
+            work(pool).await
+        };
+        let expired = async move {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => Err(ModelError::Cancelled(model_name.to_string())),
+            _ = expired => {
+                warn!("Request for {} exceeded its deadline", model_name);
+                Err(ModelError::DeadlineExceeded(model_name.to_string()))
+            }
+            result = run => result,
+        }
+    }
+
+    fn enqueue(
+        &self,
+        model_name: &str,
+        session: &str,
+        priority: Priority,
+    ) -> ModelResult<(TicketGuard, oneshot::Receiver<()>)> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.next_ticket += 1;
+        let ticket = state.next_ticket;
+
+        let queue = state.queues.entry(model_name.to_string()).or_default();
+        if queue.waiting() >= self.config.max_queue_depth {
+            return Err(ModelError::QueueFull(format!(
+                "{} has {} requests waiting",
+                model_name,
+                queue.waiting()
+            )));
+        }
+
+        let (wake, woken) = oneshot::channel();
+        let waiter = Waiter { ticket, wake };
+        match priority {
+            Priority::Interactive => queue.interactive.push(session, waiter),
+            Priority::Background => queue.background.push(session, waiter),
+        }
+        queue.dispatch(self.config.max_concurrent_per_model);
+
+        let guard = TicketGuard {
+            state: self.state.clone(),
+            model: model_name.to_string(),
+            ticket,
+            max_concurrent: self.config.max_concurrent_per_model,
+        };
+        Ok((guard, woken))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gguf::fixture::tiny_llama;
+    use crate::pool::MemoryBudget;
+
+    fn waiter(ticket: u64) -> Waiter {
+        Waiter {
+            ticket,
+            wake: oneshot::channel().0,
+        }
+    }
+
+    fn scheduler(config: SchedulerConfig) -> Scheduler {
+        Scheduler::new(Arc::new(ModelPool::new(MemoryBudget::unlimited())), config)
+    }
+
+    /// Occupy a model's only slot until the returned sender is dropped
+    async fn hold(scheduler: &Scheduler, model: &str) -> oneshot::Sender<()> {
+        let (release, released) = oneshot::channel::<()>();
+        let s = scheduler.clone();
+        let name = model.to_string();
+        tokio::spawn(async move {
+            s.submit(&name, RequestOptions::interactive(), |_| async {
+                let _ = released.await;
+                Ok(())
+            })
+            .await
+        });
+        wait_until(|| scheduler.running(model) == 1).await;
+        release
+    }
+
+    async fn wait_until(condition: impl Fn() -> bool) {
+        for _ in 0..1000 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        panic!("condition not reached");
+    }
+
+    #[test]
+    fn test_fair_queue_round_robin() {
+        let mut queue = FairQueue::default();
+        queue.push("a", waiter(1));
+        queue.push("a", waiter(2));
+        queue.push("a", waiter(3));
+        queue.push("b", waiter(4));
+        queue.push("c", waiter(5));
+        assert!(queue.remove(3));
+        assert!(!queue.remove(3));
+        assert_eq!(queue.len(), 4);
+
+        let order: Vec<u64> = std::iter::from_fn(|| queue.pop())
+            .map(|w| w.ticket)
+            .collect();
+        assert_eq!(order, vec![1, 4, 5, 2]);
+        assert!(queue.sessions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_interactive_runs_before_background() {
+        let scheduler = scheduler(SchedulerConfig::default());
+        let release = hold(&scheduler, "m").await;
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut tasks = Vec::new();
+        for (name, options) in [
+            ("index", RequestOptions::background()),
+            ("chat-1", RequestOptions::interactive().with_session("s1")),
+            ("chat-2", RequestOptions::interactive().with_session("s1")),
+            ("chat-3", RequestOptions::interactive().with_session("s2")),
+        ] {
+            let s = scheduler.clone();
+            let order = order.clone();
+            tasks.push(tokio::spawn(async move {
+                s.submit("m", options, |_| async move {
+                    order.lock().unwrap().push(name);
+                    Ok(())
+                })
+                .await
+            }));
+            let queued = tasks.len();
+            wait_until(|| scheduler.queue_depth("m") == queued).await;
+        }
+
+        drop(release);
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec!["chat-1", "chat-3", "chat-2", "index"]
+        );
+        assert_eq!(scheduler.running("m"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_when_queue_full() {
+        let scheduler = scheduler(SchedulerConfig {
+            max_queue_depth: 1,
+            ..SchedulerConfig::default()
+        });
+        let _release = hold(&scheduler, "m").await;
+
+        let s = scheduler.clone();
+        tokio::spawn(async move {
+            s.submit("m", RequestOptions::background(), |_| async { Ok(()) })
+                .await
+        });
+        wait_until(|| scheduler.queue_depth("m") == 1).await;
+
+        let err = scheduler
+            .submit("m", RequestOptions::interactive(), |_| async { Ok(()) })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ModelError::QueueFull(_)));
+
+        // Other models have their own queues
+        scheduler
+            .submit("other", RequestOptions::interactive(), |_| async { Ok(()) })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_and_deadlines() {
+        let scheduler = scheduler(SchedulerConfig::default());
+        let release = hold(&scheduler, "m").await;
+
+        let token = CancellationToken::new();
+        let s = scheduler.clone();
+        let options = RequestOptions::interactive().with_cancel_token(token.clone());
+        let cancelled =
+            tokio::spawn(async move { s.submit("m", options, |_| async { Ok(()) }).await });
+        wait_until(|| scheduler.queue_depth("m") == 1).await;
+        token.cancel();
+        assert!(matches!(
+            cancelled.await.unwrap(),
+            Err(ModelError::Cancelled(_))
+        ));
+        assert_eq!(scheduler.queue_depth("m"), 0);
+
+        let err = scheduler
+            .submit(
+                "m",
+                RequestOptions::interactive().with_deadline(Duration::from_millis(10)),
+                |_| async { Ok(()) },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ModelError::DeadlineExceeded(_)));
+        assert_eq!(scheduler.queue_depth("m"), 0);
+
+        // The held slot is handed on once released
+        drop(release);
+        scheduler
+            .submit("m", RequestOptions::interactive(), |_| async { Ok(()) })
+            .await
+            .unwrap();
+        assert_eq!(scheduler.running("m"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_infer_through_pool() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tiny.gguf");
+        tiny_llama().write(&path);
+
+        let scheduler = scheduler(SchedulerConfig::default());
+        scheduler
+            .pool()
+            .add("tiny".to_string(), path)
+            .await
+            .unwrap();
+        scheduler.pool().load("tiny").await.unwrap();
+
+        let response = scheduler
+            .infer(
+                "tiny",
+                InferenceRequest::new("Hello".to_string()),
+                None,
+                RequestOptions::interactive().with_session("cli"),
+            )
+            .await
+            .unwrap();
+        assert!(response.text.contains("Hello"));
+        assert_eq!(
+            scheduler
+                .embed("tiny", "text", RequestOptions::background())
+                .await
+                .unwrap()
+                .len(),
+            384
+        );
+    }
+}
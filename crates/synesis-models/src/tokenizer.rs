@@ -0,0 +1,1050 @@
+//! Tokenization
+//!
+//! Converts text to the token ids a model was trained on, so prompts,
+//! context budgets and chunk sizes are measured in real tokens. Two
+//! vocabulary styles cover the supported model families:
+//!
+//! - **SentencePiece** (`tokenizer.ggml.model = "llama"`: Phi-3, Mistral,
+//!   Llama 2): spaces become `▁`, adjacent pieces merge by score, and
+//!   characters outside the vocabulary fall back to `<0xNN>` byte tokens.
+//! - **Byte-level BPE** (`"gpt2"`: Llama 3): text is split into words,
+//!   bytes are mapped to printable characters, and pairs merge by rank.
+//!
+//! Vocabularies load from a GGUF header or a Hugging Face `tokenizer.json`.
+//! Control tokens such as `<|user|>` are matched verbatim, so a rendered
+//! [`ChatTemplate`](crate::chat::ChatTemplate) tokenizes the way the model
+//! expects.
+//!
+//! Where no model is at hand, [`EstimatedTokens`] keeps the ~4 bytes per
+//! token approximation behind the same [`TokenCounter`] trait.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::path::Path;
+
+use serde_json::Value as JsonValue;
+use tracing::{debug, instrument, warn};
+
+use crate::gguf::{GgufMetadata, GgufVocab};
+use crate::{ModelError, ModelResult};
+
+/// SentencePiece's stand-in for a space
+const SPACE_MARKER: char = '▁';
+
+/// GGUF token types
+const TOKEN_TYPE_CONTROL: i32 = 3;
+const TOKEN_TYPE_USER_DEFINED: i32 = 4;
+const TOKEN_TYPE_BYTE: i32 = 6;
+
+/// Counts tokens in text
+pub trait TokenCounter: Send + Sync {
+    /// Number of tokens `text` encodes to
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+/// Approximate count of ~4 bytes per token, for when no tokenizer is loaded
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EstimatedTokens;
+
+impl TokenCounter for EstimatedTokens {
+    fn count_tokens(&self, text: &str) -> usize {
+        text.len() / 4
+    }
+}
+
+/// Point in a text after which appending more text leaves its tokens alone
+///
+/// Special tokens split text into fragments that are encoded on their own,
+/// so everything up to the end of one encodes the same way in any text that
+/// starts with the same bytes. A prompt that extends an earlier one can keep
+/// the earlier tokens up to here and encode only the rest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenSplit {
+    /// Bytes of text before the split
+    pub bytes: usize,
+    /// Tokens those bytes encode to
+    pub tokens: usize,
+}
+
+/// Vocabulary style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizerKind {
+    /// SentencePiece with score-ordered merges and byte fallback
+    SentencePiece,
+    /// GPT-2 style byte-level BPE with ranked merges
+    ByteLevelBpe,
+}
+
+/// A model's tokenizer
+#[derive(Debug, Clone)]
+pub struct Tokenizer {
+    kind: TokenizerKind,
+    tokens: Vec<String>,
+    ids: HashMap<String, u32>,
+    /// Merge priority of each token (SentencePiece)
+    scores: Vec<f32>,
+    /// Rank of each merge (byte-level BPE)
+    merge_ranks: HashMap<(String, String), usize>,
+    /// Control and user-defined tokens matched verbatim, longest first
+    special: Vec<(String, u32)>,
+    /// Control tokens, left out when decoding
+    control: HashSet<u32>,
+    /// `<0xNN>` token for each byte (SentencePiece)
+    byte_tokens: Vec<Option<u32>>,
+    bos_token_id: Option<u32>,
+    eos_token_id: Option<u32>,
+    unknown_token_id: Option<u32>,
+    add_space_prefix: bool,
+}
+
+impl Tokenizer {
+    /// Build a tokenizer from a GGUF vocabulary
+    ///
+    /// # Errors
+    /// Returns [`ModelError::UnsupportedTokenizer`] for vocabulary styles
+    /// other than SentencePiece ("llama") and byte-level BPE ("gpt2").
+    pub fn from_vocab(vocab: GgufVocab) -> ModelResult<Self> {
+        let kind = match vocab.model.as_str() {
+            "llama" => TokenizerKind::SentencePiece,
+            "gpt2" => TokenizerKind::ByteLevelBpe,
+            other => return Err(ModelError::UnsupportedTokenizer(other.to_string())),
+        };
+
+        let mut tokenizer = Self::new(kind, vocab.tokens);
+        tokenizer.scores = vocab.scores;
+        tokenizer.bos_token_id = vocab.bos_token_id;
+        tokenizer.eos_token_id = vocab.eos_token_id;
+        tokenizer.unknown_token_id = vocab.unknown_token_id;
+        tokenizer.add_space_prefix = vocab
+            .add_space_prefix
+            .unwrap_or(kind == TokenizerKind::SentencePiece);
+
+        for (id, token_type) in vocab.token_types.iter().enumerate() {
+            let id = id as u32;
+            match *token_type {
+                TOKEN_TYPE_CONTROL => {
+                    tokenizer.control.insert(id);
+                    tokenizer.add_special(id);
+                },
+                TOKEN_TYPE_USER_DEFINED => tokenizer.add_special(id),
+                TOKEN_TYPE_BYTE => {},
+                _ => continue,
+            }
+        }
+        tokenizer.set_merges(vocab.merges.iter().map(String::as_str));
+        tokenizer.finish();
+
+        debug!(
+            "Built {:?} tokenizer with {} tokens",
+            kind,
+            tokenizer.vocab_size()
+        );
+        Ok(tokenizer)
+    }
+
+    /// Read the tokenizer stored in a GGUF file
+    pub fn from_gguf(path: &Path) -> ModelResult<Self> {
+        Self::from_vocab(GgufVocab::read(path)?)
+    }
+
+    /// Read the tokenizer for a model file without loading the model
+    ///
+    /// See [`for_model_file`](Self::for_model_file); unreadable headers are
+    /// logged and skipped the same way.
+    pub fn for_model(path: &Path) -> Option<Self> {
+        match GgufMetadata::read(path) {
+            Ok(metadata) => Self::for_model_file(path, metadata.tokenizer.is_some()),
+            Err(e) => {
+                warn!(
+                    "No tokenizer for {}, estimating token counts: {}",
+                    path.display(),
+                    e
+                );
+                None
+            },
+        }
+    }
+
+    /// Read the tokenizer for a model file: the GGUF vocabulary if the
+    /// header has one, else a `tokenizer.json` in the same directory
+    ///
+    /// Unsupported or unreadable tokenizers are logged and skipped; token
+    /// counts then fall back to an estimate.
+    pub(crate) fn for_model_file(path: &Path, has_vocab: bool) -> Option<Self> {
+        let result = if has_vocab {
+            Self::from_gguf(path)
+        } else {
+            let json = path.with_file_name("tokenizer.json");
+            if !json.exists() {
+                return None;
+            }
+            Self::from_tokenizer_json(&json)
+        };
+
+        match result {
+            Ok(tokenizer) => Some(tokenizer),
+            Err(e) => {
+                warn!(
+                    "No tokenizer for {}, estimating token counts: {}",
+                    path.display(),
+                    e
+                );
+                None
+            },
+        }
+    }
+
+    /// Read a Hugging Face `tokenizer.json`
+    ///
+    /// BPE models with `byte_fallback` (Mistral, Llama 2) are treated as
+    /// SentencePiece, scoring each token by the rank of the merge that
+    /// produces it; other BPE models as byte-level BPE.
+    #[instrument]
+    pub fn from_tokenizer_json(path: &Path) -> ModelResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let json: JsonValue =
+            serde_json::from_str(&content).map_err(|e| invalid_json(path, &e.to_string()))?;
+
+        let model = &json["model"];
+        if model["type"].as_str() != Some("BPE") {
+            return Err(ModelError::UnsupportedTokenizer(format!(
+                "{} model in {}",
+                model["type"].as_str().unwrap_or("unknown"),
+                path.display()
+            )));
+        }
+        let kind = if model["byte_fallback"].as_bool() == Some(true) {
+            TokenizerKind::SentencePiece
+        } else {
+            TokenizerKind::ByteLevelBpe
+        };
+
+        let vocab = model["vocab"]
+            .as_object()
+            .ok_or_else(|| invalid_json(path, "missing model.vocab"))?;
+        let added = json["added_tokens"].as_array().cloned().unwrap_or_default();
+        let size = vocab
+            .values()
+            .chain(added.iter().map(|t| &t["id"]))
+            .filter_map(JsonValue::as_u64)
+            .max()
+            .map_or(0, |max| max as usize + 1);
+
+        let mut tokens = vec![String::new(); size];
+        for (token, id) in vocab {
+            if let Some(id) = id.as_u64() {
+                tokens[id as usize] = token.clone();
+            }
+        }
+        for token in &added {
+            if let (Some(id), Some(content)) = (token["id"].as_u64(), token["content"].as_str()) {
+                tokens[id as usize] = content.to_string();
+            }
+        }
+
+        let merges: Vec<String> = model["merges"]
+            .as_array()
+            .map(|merges| {
+                merges
+                    .iter()
+                    .filter_map(|m| match m {
+                        JsonValue::String(s) => Some(s.clone()),
+                        JsonValue::Array(pair) => Some(format!(
+                            "{} {}",
+                            pair.first()?.as_str()?,
+                            pair.get(1)?.as_str()?
+                        )),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut tokenizer = Self::new(kind, tokens);
+        for token in &added {
+            let Some(id) = token["id"].as_u64() else {
+                continue;
+            };
+            let id = id as u32;
+            if token["special"].as_bool() == Some(true) {
+                tokenizer.control.insert(id);
+            }
+            tokenizer.add_special(id);
+        }
+        tokenizer.set_merges(merges.iter().map(String::as_str));
+        if kind == TokenizerKind::SentencePiece {
+            // A token merged earlier is preferred, as with SentencePiece scores
+            tokenizer.scores = vec![0.0; tokenizer.tokens.len()];
+            for (rank, merge) in merges.iter().enumerate() {
+                if let Some(&id) = tokenizer.ids.get(&merge.replacen(' ', "", 1)) {
+                    if tokenizer.scores[id as usize] == 0.0 {
+                        tokenizer.scores[id as usize] = -(rank as f32) - 1.0;
+                    }
+                }
+            }
+            tokenizer.add_space_prefix = true;
+        }
+
+        let find = |names: &[&str]| names.iter().find_map(|n| tokenizer.ids.get(*n).copied());
+        tokenizer.bos_token_id = find(&["<s>", "<|begin_of_text|>"]);
+        tokenizer.eos_token_id = find(&["</s>", "<|end_of_text|>", "<|endoftext|>"]);
+        tokenizer.unknown_token_id = find(&["<unk>"]);
+        tokenizer.finish();
+
+        Ok(tokenizer)
+    }
+
+    fn new(kind: TokenizerKind, tokens: Vec<String>) -> Self {
+        let ids = tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| !t.is_empty())
+            .map(|(id, t)| (t.clone(), id as u32))
+            .collect();
+        Self {
+            kind,
+            tokens,
+            ids,
+            scores: Vec::new(),
+            merge_ranks: HashMap::new(),
+            special: Vec::new(),
+            control: HashSet::new(),
+            byte_tokens: vec![None; 256],
+            bos_token_id: None,
+            eos_token_id: None,
+            unknown_token_id: None,
+            add_space_prefix: false,
+        }
+    }
+
+    fn add_special(&mut self, id: u32) {
+        if let Some(text) = self.tokens.get(id as usize).filter(|t| !t.is_empty()) {
+            self.special.push((text.clone(), id));
+        }
+    }
+
+    fn set_merges<'a>(&mut self, merges: impl Iterator<Item = &'a str>) {
+        self.merge_ranks = merges
+            .enumerate()
+            .filter_map(|(rank, merge)| {
+                let (left, right) = merge.split_once(' ')?;
+                Some(((left.to_string(), right.to_string()), rank))
+            })
+            .collect();
+    }
+
+    fn finish(&mut self) {
+        // Longest first, so "<|end|>" is not cut short by a shorter prefix
+        self.special
+            .sort_by(|a, b| b.0.len().cmp(&a.0.len()).then(a.1.cmp(&b.1)));
+        self.special.dedup_by_key(|(_, id)| *id);
+
+        if self.kind == TokenizerKind::SentencePiece {
+            for byte in 0..=255u8 {
+                self.byte_tokens[byte as usize] =
+                    self.ids.get(&format!("<0x{:02X}>", byte)).copied();
+            }
+        }
+    }
+
+    /// Vocabulary style
+    pub fn kind(&self) -> TokenizerKind {
+        self.kind
+    }
+
+    /// Number of tokens in the vocabulary
+    pub fn vocab_size(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Beginning-of-sequence token
+    pub fn bos_token_id(&self) -> Option<u32> {
+        self.bos_token_id
+    }
+
+    /// End-of-sequence token
+    pub fn eos_token_id(&self) -> Option<u32> {
+        self.eos_token_id
+    }
+
+    /// Id of a token string
+    pub fn token_to_id(&self, token: &str) -> Option<u32> {
+        self.ids.get(token).copied()
+    }
+
+    /// String of a token id
+    pub fn id_to_token(&self, id: u32) -> Option<&str> {
+        self.tokens.get(id as usize).map(String::as_str)
+    }
+
+    /// Encode text to token ids
+    ///
+    /// Control tokens written in the text (e.g. `<|user|>`) become their
+    /// single token. No beginning-of-sequence token is added; chat
+    /// templates write it where the model expects it.
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        let mut ids = Vec::new();
+        self.encode_into(text, true, &mut ids);
+        ids
+    }
+
+    /// Encode text, also returning its last [`TokenSplit`]
+    ///
+    /// The split is the default (nothing) if the text has no special token
+    /// far enough from its end.
+    pub fn encode_with_split(&self, text: &str) -> (Vec<u32>, TokenSplit) {
+        let mut ids = Vec::new();
+        let split = self.encode_into(text, true, &mut ids);
+        (ids, split)
+    }
+
+    /// Encode text that starts with an earlier text up to its `split`,
+    /// reusing that text's tokens
+    ///
+    /// `previous` holds at least the earlier text's first `split.tokens`
+    /// ids. Only the bytes after the split are encoded; the result is the
+    /// same as [`encode_with_split`](Self::encode_with_split).
+    pub fn encode_after(
+        &self,
+        text: &str,
+        previous: &[u32],
+        split: TokenSplit,
+    ) -> (Vec<u32>, TokenSplit) {
+        let mut ids = previous[..split.tokens].to_vec();
+        let rest = self.encode_into(&text[split.bytes..], split.bytes == 0, &mut ids);
+        let split = TokenSplit {
+            bytes: split.bytes + rest.bytes,
+            tokens: rest.tokens,
+        };
+        (ids, split)
+    }
+
+    /// Append the ids of `text` to `ids`, returning its last split with
+    /// `tokens` counted over all of `ids`
+    fn encode_into(&self, text: &str, at_start: bool, ids: &mut Vec<u32>) -> TokenSplit {
+        // A split closer to the end could be crossed by a longer special
+        // token once more text is appended
+        let longest_special = self.special.first().map_or(0, |(s, _)| s.len());
+        let mut split = TokenSplit {
+            bytes: 0,
+            tokens: ids.len(),
+        };
+        let mut offset = 0;
+        for (i, fragment) in self.split_special(text).into_iter().enumerate() {
+            match fragment {
+                Fragment::Special(id) => {
+                    ids.push(id);
+                    offset += self.tokens[id as usize].len();
+                    if text.len() - offset >= longest_special {
+                        split = TokenSplit {
+                            bytes: offset,
+                            tokens: ids.len(),
+                        };
+                    }
+                },
+                Fragment::Text(text) => {
+                    offset += text.len();
+                    match self.kind {
+                        TokenizerKind::SentencePiece => self.encode_sentencepiece(
+                            text,
+                            i == 0 && at_start && self.add_space_prefix,
+                            ids,
+                        ),
+                        TokenizerKind::ByteLevelBpe => self.encode_byte_level(text, ids),
+                    }
+                },
+            }
+        }
+        split
+    }
+
+    /// Decode token ids to text, leaving out control tokens
+    pub fn decode(&self, ids: &[u32]) -> String {
+        let mut bytes = Vec::new();
+        for &id in ids {
+            if self.control.contains(&id) {
+                continue;
+            }
+            let Some(token) = self.tokens.get(id as usize) else {
+                continue;
+            };
+            match self.kind {
+                TokenizerKind::SentencePiece => match parse_byte_token(token) {
+                    Some(byte) => bytes.push(byte),
+                    None => bytes.extend(token.replace(SPACE_MARKER, " ").bytes()),
+                },
+                TokenizerKind::ByteLevelBpe => {
+                    if self.special.iter().any(|(_, special)| *special == id) {
+                        bytes.extend(token.bytes());
+                    } else {
+                        bytes.extend(token.chars().filter_map(char_to_byte));
+                    }
+                },
+            }
+        }
+
+        let text = String::from_utf8_lossy(&bytes).into_owned();
+        if self.add_space_prefix {
+            if let Some(stripped) = text.strip_prefix(' ') {
+                return stripped.to_string();
+            }
+        }
+        text
+    }
+
+    /// Split text around special tokens written in it
+    fn split_special<'a>(&self, text: &'a str) -> Vec<Fragment<'a>> {
+        let mut fragments = Vec::new();
+        if self.special.is_empty() {
+            fragments.push(Fragment::Text(text));
+            return fragments;
+        }
+
+        let first_bytes: HashSet<u8> = self.special.iter().map(|(s, _)| s.as_bytes()[0]).collect();
+        let mut start = 0;
+        let mut pos = 0;
+        while pos < text.len() {
+            if text.is_char_boundary(pos) && first_bytes.contains(&text.as_bytes()[pos]) {
+                let rest = &text[pos..];
+                if let Some((special, id)) = self
+                    .special
+                    .iter()
+                    .find(|(s, _)| rest.starts_with(s.as_str()))
+                {
+                    if start < pos {
+                        fragments.push(Fragment::Text(&text[start..pos]));
+                    }
+                    fragments.push(Fragment::Special(*id));
+                    pos += special.len();
+                    start = pos;
+                    continue;
+                }
+            }
+            pos += 1;
+        }
+        if start < text.len() {
+            fragments.push(Fragment::Text(&text[start..]));
+        }
+        fragments
+    }
+
+    /// SentencePiece: merge the highest-scoring adjacent pair until none
+    /// is in the vocabulary, then fall back to bytes for unknown pieces
+    fn encode_sentencepiece(&self, text: &str, add_prefix: bool, ids: &mut Vec<u32>) {
+        let mut normalized = String::with_capacity(text.len() + 3);
+        if add_prefix {
+            normalized.push(SPACE_MARKER);
+        }
+        normalized.extend(
+            text.chars()
+                .map(|c| if c == ' ' { SPACE_MARKER } else { c }),
+        );
+
+        let mut symbols: Vec<Symbol> = normalized
+            .char_indices()
+            .map(|(start, c)| Symbol {
+                start,
+                len: c.len_utf8(),
+                prev: None,
+                next: None,
+            })
+            .collect();
+        let count = symbols.len();
+        for (i, symbol) in symbols.iter_mut().enumerate() {
+            symbol.prev = i.checked_sub(1);
+            symbol.next = (i + 1 < count).then_some(i + 1);
+        }
+
+        let mut queue = BinaryHeap::new();
+        for i in 1..count {
+            self.push_bigram(&normalized, &symbols, i - 1, i, &mut queue);
+        }
+
+        while let Some(bigram) = queue.pop() {
+            let (left, right) = (bigram.left, bigram.right);
+            // Skip pairs invalidated by an earlier merge
+            if symbols[left].len == 0
+                || symbols[right].len == 0
+                || symbols[left].len + symbols[right].len != bigram.len
+            {
+                continue;
+            }
+
+            symbols[left].len += symbols[right].len;
+            symbols[right].len = 0;
+            symbols[left].next = symbols[right].next;
+            if let Some(next) = symbols[right].next {
+                symbols[next].prev = Some(left);
+            }
+
+            if let Some(prev) = symbols[left].prev {
+                self.push_bigram(&normalized, &symbols, prev, left, &mut queue);
+            }
+            if let Some(next) = symbols[left].next {
+                self.push_bigram(&normalized, &symbols, left, next, &mut queue);
+            }
+        }
+
+        let mut current = (count > 0).then_some(0);
+        while let Some(i) = current {
+            let symbol = &symbols[i];
+            let piece = &normalized[symbol.start..symbol.start + symbol.len];
+            match self.ids.get(piece) {
+                Some(&id) => ids.push(id),
+                None => {
+                    for byte in piece.bytes() {
+                        if let Some(id) = self.byte_tokens[byte as usize].or(self.unknown_token_id)
+                        {
+                            ids.push(id);
+                        }
+                    }
+                },
+            }
+            current = symbol.next;
+        }
+    }
+
+    fn push_bigram(
+        &self,
+        text: &str,
+        symbols: &[Symbol],
+        left: usize,
+        right: usize,
+        queue: &mut BinaryHeap<Bigram>,
+    ) {
+        let start = symbols[left].start;
+        let len = symbols[left].len + symbols[right].len;
+        if let Some(&id) = self.ids.get(&text[start..start + len]) {
+            queue.push(Bigram {
+                score: self.scores.get(id as usize).copied().unwrap_or(0.0),
+                left,
+                right,
+                len,
+            });
+        }
+    }
+
+    /// Byte-level BPE: split into words, then merge each word's pairs in
+    /// rank order
+    fn encode_byte_level(&self, text: &str, ids: &mut Vec<u32>) {
+        for word in pre_tokenize(text) {
+            let mut parts: Vec<String> =
+                word.bytes().map(|b| byte_to_char(b).to_string()).collect();
+
+            while parts.len() > 1 {
+                let best = parts
+                    .windows(2)
+                    .filter_map(|pair| {
+                        self.merge_ranks
+                            .get(&(pair[0].clone(), pair[1].clone()))
+                            .map(|&rank| (rank, pair[0].clone(), pair[1].clone()))
+                    })
+                    .min_by_key(|(rank, _, _)| *rank);
+                let Some((_, left, right)) = best else {
+                    break;
+                };
+
+                let mut merged = Vec::with_capacity(parts.len());
+                let mut i = 0;
+                while i < parts.len() {
+                    if i + 1 < parts.len() && parts[i] == left && parts[i + 1] == right {
+                        merged.push(format!("{}{}", left, right));
+                        i += 2;
+                    } else {
+                        merged.push(std::mem::take(&mut parts[i]));
+                        i += 1;
+                    }
+                }
+                parts = merged;
+            }
+
+            for part in parts {
+                if let Some(id) = self.ids.get(&part).copied().or(self.unknown_token_id) {
+                    ids.push(id);
+                }
+            }
+        }
+    }
+}
+
+impl TokenCounter for Tokenizer {
+    fn count_tokens(&self, text: &str) -> usize {
+        self.encode(text).len()
+    }
+}
+
+enum Fragment<'a> {
+    Text(&'a str),
+    Special(u32),
+}
+
+/// A piece of the text being merged, linked to its neighbours
+struct Symbol {
+    start: usize,
+    /// Byte length, 0 once merged into the symbol on its left
+    len: usize,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+/// Candidate merge of two adjacent symbols
+struct Bigram {
+    score: f32,
+    left: usize,
+    right: usize,
+    len: usize,
+}
+
+impl PartialEq for Bigram {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Bigram {}
+
+impl PartialOrd for Bigram {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Bigram {
+    /// Highest score first, then leftmost
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.left.cmp(&self.left))
+    }
+}
+
+fn invalid_json(path: &Path, reason: &str) -> ModelError {
+    ModelError::UnsupportedTokenizer(format!("{}: {}", path.display(), reason))
+}
+
+/// Byte of a `<0xNN>` token
+fn parse_byte_token(token: &str) -> Option<u8> {
+    let hex = token.strip_prefix("<0x")?.strip_suffix('>')?;
+    if hex.len() != 2 {
+        return None;
+    }
+    u8::from_str_radix(hex, 16).ok()
+}
+
+/// GPT-2's printable stand-in for a byte: printable Latin-1 bytes map to
+/// themselves, the rest to code points from U+0100 up
+fn byte_to_char(byte: u8) -> char {
+    let printable = |b: u8| matches!(b, b'!'..=b'~' | 0xA1..=0xAC | 0xAE..=0xFF);
+    if printable(byte) {
+        return char::from(byte);
+    }
+    let offset = (0..byte).filter(|&b| !printable(b)).count() as u32;
+    char::from_u32(256 + offset).unwrap_or('?')
+}
+
+/// Inverse of [`byte_to_char`]
+fn char_to_byte(c: char) -> Option<u8> {
+    let code = c as u32;
+    if code < 256 {
+        let byte = code as u8;
+        return (byte_to_char(byte) == c).then_some(byte);
+    }
+    (0..=255u8).find(|&b| byte_to_char(b) == c)
+}
+
+/// Split text into words the way Llama 3's pre-tokenizer does: contractions,
+/// letter runs with one leading non-letter, up to three digits, punctuation
+/// runs with an optional leading space, and whitespace (a space directly
+/// before a word stays with the word)
+fn pre_tokenize(text: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let end_of = |i: usize| chars.get(i).map_or(text.len(), |(pos, _)| *pos);
+    let is_letter = |c: char| c.is_alphabetic();
+    let is_number = |c: char| c.is_numeric();
+    let is_newline = |c: char| c == '\r' || c == '\n';
+
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i].1;
+        let start = i;
+
+        // 's 't 're 've 'm 'll 'd
+        if c == '\'' {
+            let rest: String = chars[i + 1..]
+                .iter()
+                .take(2)
+                .map(|(_, c)| c.to_ascii_lowercase())
+                .collect();
+            let len = if rest.starts_with("re") || rest.starts_with("ve") || rest.starts_with("ll")
+            {
+                2
+            } else if rest.starts_with(['s', 't', 'm', 'd']) {
+                1
+            } else {
+                0
+            };
+            if len > 0 {
+                i += 1 + len;
+                words.push(&text[chars[start].0..end_of(i)]);
+                continue;
+            }
+        }
+
+        // Letters, with one leading non-letter, non-number, non-newline
+        let lead = !is_letter(c) && !is_number(c) && !is_newline(c);
+        let letters_at = if lead { i + 1 } else { i };
+        if chars.get(letters_at).is_some_and(|&(_, c)| is_letter(c)) {
+            i = letters_at;
+            while chars.get(i).is_some_and(|&(_, c)| is_letter(c)) {
+                i += 1;
+            }
+            words.push(&text[chars[start].0..end_of(i)]);
+            continue;
+        }
+
+        if is_number(c) {
+            while i < start + 3 && chars.get(i).is_some_and(|&(_, c)| is_number(c)) {
+                i += 1;
+            }
+            words.push(&text[chars[start].0..end_of(i)]);
+            continue;
+        }
+
+        // Punctuation, with an optional leading space and trailing newlines
+        let punct_at = if c == ' ' { i + 1 } else { i };
+        let is_punct = |c: char| !c.is_whitespace() && !is_letter(c) && !is_number(c);
+        if chars.get(punct_at).is_some_and(|&(_, c)| is_punct(c)) {
+            i = punct_at;
+            while chars.get(i).is_some_and(|&(_, c)| is_punct(c)) {
+                i += 1;
+            }
+            while chars.get(i).is_some_and(|&(_, c)| is_newline(c)) {
+                i += 1;
+            }
+            words.push(&text[chars[start].0..end_of(i)]);
+            continue;
+        }
+
+        // Whitespace
+        while chars.get(i).is_some_and(|&(_, c)| c.is_whitespace()) {
+            i += 1;
+        }
+        let last_newline = (start..i).rev().find(|&j| is_newline(chars[j].1));
+        if let Some(newline) = last_newline {
+            // Whitespace up to the last newline
+            i = newline + 1;
+        } else if i < chars.len() && i - start > 1 {
+            // Leave the last space for the following word
+            i -= 1;
+        }
+        words.push(&text[chars[start].0..end_of(i)]);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gguf::fixture::GgufFixture;
+
+    /// SentencePiece vocabulary with byte fallback and Phi-3 control tokens
+    fn sentencepiece() -> Tokenizer {
+        let mut tokens: Vec<String> = ["<unk>", "<s>", "</s>", "<|user|>", "<|end|>"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        let mut token_types = vec![2, 3, 3, 4, 3];
+        for byte in 0..=255u8 {
+            tokens.push(format!("<0x{:02X}>", byte));
+            token_types.push(TOKEN_TYPE_BYTE);
+        }
+        let pieces = [
+            "▁", "h", "e", "l", "o", "w", "r", "d", "he", "ll", "llo", "hello", "▁hello", "▁w",
+            "or", "▁wor", "▁world", "ld",
+        ];
+        let mut scores = vec![0.0; tokens.len()];
+        for (i, piece) in pieces.iter().enumerate() {
+            tokens.push(piece.to_string());
+            token_types.push(1);
+            // Longer pieces merge first
+            scores.push(piece.chars().count() as f32 + i as f32 / 100.0);
+        }
+
+        Tokenizer::from_vocab(GgufVocab {
+            model: "llama".to_string(),
+            tokens,
+            scores,
+            token_types,
+            bos_token_id: Some(1),
+            eos_token_id: Some(2),
+            unknown_token_id: Some(0),
+            ..GgufVocab::default()
+        })
+        .unwrap()
+    }
+
+    fn byte_level() -> Tokenizer {
+        let merges = [
+            "h e", "l l", "he ll", "hell o", "Ġ w", "o r", "Ġw or", "Ġwor ld", "l d",
+        ];
+        let mut tokens: Vec<String> = (0..=255u8).map(|b| byte_to_char(b).to_string()).collect();
+        for merge in merges {
+            tokens.push(merge.replace(' ', ""));
+        }
+        tokens.push("<|eot_id|>".to_string());
+        let mut token_types = vec![1; tokens.len()];
+        *token_types.last_mut().unwrap() = TOKEN_TYPE_CONTROL;
+
+        Tokenizer::from_vocab(GgufVocab {
+            model: "gpt2".to_string(),
+            tokens,
+            token_types,
+            merges: merges.iter().map(|m| m.to_string()).collect(),
+            ..GgufVocab::default()
+        })
+        .unwrap()
+    }
+
+    fn pieces(tokenizer: &Tokenizer, text: &str) -> Vec<String> {
+        tokenizer
+            .encode(text)
+            .into_iter()
+            .map(|id| tokenizer.id_to_token(id).unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_sentencepiece_encode() {
+        let tokenizer = sentencepiece();
+        assert_eq!(pieces(&tokenizer, "hello world"), vec!["▁hello", "▁world"]);
+        // Control tokens are matched verbatim; unknown characters fall back to bytes
+        assert_eq!(
+            pieces(&tokenizer, "<|user|>hello é<|end|>"),
+            vec!["<|user|>", "hello", "▁", "<0xC3>", "<0xA9>", "<|end|>"]
+        );
+        assert_eq!(tokenizer.count_tokens("hello world"), 2);
+
+        let ids = tokenizer.encode("hello world é");
+        assert_eq!(tokenizer.decode(&ids), "hello world é");
+        let with_control = tokenizer.encode("<|user|>hello<|end|>");
+        assert_eq!(tokenizer.decode(&with_control), "<|user|>hello");
+    }
+
+    #[test]
+    fn test_encode_after_split() {
+        let tokenizer = sentencepiece();
+
+        // Too close to the end for a split: a longer special token could follow
+        let (_, split) = tokenizer.encode_with_split("hello<|end|>");
+        assert_eq!(split, TokenSplit::default());
+
+        let round_one = "<|user|>hello world<|end|>hello world";
+        let (ids, split) = tokenizer.encode_with_split(round_one);
+        assert_eq!(ids, tokenizer.encode(round_one));
+        assert_eq!(split.bytes, "<|user|>hello world<|end|>".len());
+        assert_eq!(split.tokens, 4);
+
+        // Appended text: the result matches a full encode
+        for round_two in [
+            format!("{} hello<|user|>world wor", round_one),
+            format!("{}<|end|>hello<|end|> hello world", &round_one[..split.bytes]),
+        ] {
+            let (after, after_split) = tokenizer.encode_after(&round_two, &ids, split);
+            assert_eq!(
+                (after, after_split),
+                tokenizer.encode_with_split(&round_two)
+            );
+        }
+    }
+
+    #[test]
+    fn test_byte_level_encode() {
+        let tokenizer = byte_level();
+        assert_eq!(pieces(&tokenizer, "hello world"), vec!["hello", "Ġworld"]);
+        assert_eq!(
+            pieces(&tokenizer, "hello<|eot_id|>"),
+            vec!["hello", "<|eot_id|>"]
+        );
+
+        let ids = tokenizer.encode("hello, world 42 é\n");
+        assert_eq!(tokenizer.decode(&ids), "hello, world 42 é\n");
+    }
+
+    #[test]
+    fn test_pre_tokenize() {
+        assert_eq!(
+            pre_tokenize("Hello, it's  12345 apples!\n\nok"),
+            vec!["Hello", ",", " it", "'s", " ", " ", "123", "45", " apples", "!\n\n", "ok"]
+        );
+    }
+
+    #[test]
+    fn test_byte_mapping_roundtrip() {
+        for byte in 0..=255u8 {
+            assert_eq!(char_to_byte(byte_to_char(byte)), Some(byte));
+        }
+        assert_eq!(byte_to_char(b' '), 'Ġ');
+        assert_eq!(byte_to_char(b'\n'), 'Ċ');
+    }
+
+    #[test]
+    fn test_from_gguf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tok.gguf");
+        GgufFixture::new("llama")
+            .string("tokenizer.ggml.model", "llama")
+            .strings("tokenizer.ggml.tokens", &["<unk>", "<s>", "▁", "a", "▁a"])
+            .f32s("tokenizer.ggml.scores", &[0.0, 0.0, -3.0, -2.0, -1.0])
+            .i32s("tokenizer.ggml.token_type", &[2, 3, 1, 1, 1])
+            .u32("tokenizer.ggml.bos_token_id", 1)
+            .write(&path);
+
+        let tokenizer = Tokenizer::from_gguf(&path).unwrap();
+        assert_eq!(tokenizer.kind(), TokenizerKind::SentencePiece);
+        assert_eq!(tokenizer.vocab_size(), 5);
+        assert_eq!(tokenizer.bos_token_id(), Some(1));
+        // The space prefix only applies at the start of the text
+        assert_eq!(tokenizer.encode("a a"), vec![4, 4]);
+        assert_eq!(tokenizer.encode("<s>a a"), vec![1, 3, 4]);
+
+        let path = dir.path().join("bert.gguf");
+        GgufFixture::new("bert")
+            .string("tokenizer.ggml.model", "bert")
+            .strings("tokenizer.ggml.tokens", &["[CLS]"])
+            .write(&path);
+        assert!(matches!(
+            Tokenizer::from_gguf(&path),
+            Err(ModelError::UnsupportedTokenizer(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_tokenizer_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tokenizer.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "added_tokens": [{"id": 5, "content": "<|eot_id|>", "special": true}],
+                "model": {
+                    "type": "BPE",
+                    "vocab": {"h": 0, "i": 1, "hi": 2, "Ġ": 3, "Ġhi": 4},
+                    "merges": ["h i", ["Ġ", "hi"]]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let tokenizer = Tokenizer::from_tokenizer_json(&path).unwrap();
+        assert_eq!(tokenizer.kind(), TokenizerKind::ByteLevelBpe);
+        assert_eq!(tokenizer.encode("hi hi<|eot_id|>"), vec![2, 4, 5]);
+        assert_eq!(tokenizer.decode(&[2, 4, 5]), "hi hi");
+    }
+
+    #[test]
+    fn test_estimated_tokens() {
+        assert_eq!(EstimatedTokens.count_tokens("hello world!"), 3);
+        assert_eq!(EstimatedTokens.count_tokens(""), 0);
+    }
+}
@@ -0,0 +1,668 @@
+//! Privacy Audit Log
+//!
+//! Append-only record of everything that left the device.
+//!
+//! Every cloud escalation or upload appends one [`AuditEntry`] describing
+//! *what kind* of data was sent, never the data itself:
+//!
+//! - SHA-256 of the (already redacted) payload as it was transmitted
+//! - Token categories and counts found in the payload
+//! - Destination, event kind, session and timestamp
+//!
+//! # Tamper Evidence
+//!
+//! Entries form a hash chain: each entry stores the hash of its predecessor
+//! and its own hash covers every field plus that link. Editing, reordering
+//! or removing an entry breaks the chain, which [`AuditLog::verify`]
+//! reports with the first offending sequence number. SQLite triggers
+//! additionally reject `UPDATE` and `DELETE` on the table.
+//!
+//! ```text
+//! GENESIS ← entry 1 ← entry 2 ← ... ← entry N
+//!   prev_hash   prev_hash              entry_hash = sha256(fields ‖ prev_hash)
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, instrument};
+
+use crate::policy::Destination;
+use crate::{PrivacyError, PrivacyResult};
+
+/// Hash used as `prev_hash` for the first entry
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Matches redaction tokens in outbound payloads
+static TOKEN_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(crate::redactor::TOKEN_PATTERN).expect("token pattern is valid"));
+
+/// What kind of transfer an entry records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventKind {
+    /// Query escalated to a cloud model
+    Escalation,
+    /// File uploaded (e.g., LoRA adapter)
+    Upload,
+}
+
+impl AuditEventKind {
+    /// Stable string form used in the database and hash
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEventKind::Escalation => "escalation",
+            AuditEventKind::Upload => "upload",
+        }
+    }
+
+    fn parse(s: &str) -> PrivacyResult<Self> {
+        match s {
+            "escalation" => Ok(AuditEventKind::Escalation),
+            "upload" => Ok(AuditEventKind::Upload),
+            other => Err(PrivacyError::AuditError(format!(
+                "Unknown event kind '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+/// An outbound transfer to be recorded
+#[derive(Debug, Clone)]
+pub struct AuditRecord {
+    /// Event kind
+    pub kind: AuditEventKind,
+    /// Where the payload went
+    pub destination: Destination,
+    /// Session that produced the payload
+    pub session_id: String,
+    /// SHA-256 of the transmitted payload (hex)
+    pub payload_sha256: String,
+    /// Size of the transmitted payload in bytes
+    pub payload_bytes: u64,
+    /// Token categories and how many tokens of each were in the payload
+    pub categories: BTreeMap<String, usize>,
+}
+
+impl AuditRecord {
+    /// Build a record from the exact bytes that were transmitted
+    ///
+    /// Token categories are counted from `[CATEGORY_NNNN]` tokens found in
+    /// the payload, so only redacted placeholders are ever inspected.
+    pub fn from_payload(
+        kind: AuditEventKind,
+        destination: Destination,
+        session_id: &str,
+        payload: &[u8],
+    ) -> Self {
+        let categories = count_tokens(&String::from_utf8_lossy(payload));
+
+        Self {
+            kind,
+            destination,
+            session_id: session_id.to_string(),
+            payload_sha256: hex::encode(Sha256::digest(payload)),
+            payload_bytes: payload.len() as u64,
+            categories,
+        }
+    }
+}
+
+/// Count redaction tokens per category in text
+pub fn count_tokens(text: &str) -> BTreeMap<String, usize> {
+    let mut categories = BTreeMap::new();
+    for cap in TOKEN_REGEX.captures_iter(text) {
+        if let Some(category) = cap.get(1) {
+            *categories.entry(category.as_str().to_string()).or_insert(0) += 1;
+        }
+    }
+    categories
+}
+
+/// A recorded entry in the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Position in the chain (starting at 1)
+    pub seq: u64,
+    /// When the transfer happened
+    pub timestamp: DateTime<Utc>,
+    /// Event kind
+    pub kind: AuditEventKind,
+    /// Where the payload went
+    pub destination: Destination,
+    /// Session that produced the payload
+    pub session_id: String,
+    /// SHA-256 of the transmitted payload (hex)
+    pub payload_sha256: String,
+    /// Size of the transmitted payload in bytes
+    pub payload_bytes: u64,
+    /// Token categories and counts
+    pub categories: BTreeMap<String, usize>,
+    /// Hash of the previous entry ([`GENESIS_HASH`] for the first)
+    pub prev_hash: String,
+    /// Hash of this entry
+    pub entry_hash: String,
+}
+
+impl AuditEntry {
+    /// Total number of tokens across all categories
+    pub fn total_tokens(&self) -> usize {
+        self.categories.values().sum()
+    }
+
+    /// Compute the hash this entry should have
+    pub fn compute_hash(&self) -> String {
+        let categories = self
+            .categories
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let mut hasher = Sha256::new();
+        for field in [
+            self.seq.to_string().as_str(),
+            &self.timestamp.to_rfc3339_opts(SecondsFormat::Micros, true),
+            self.kind.as_str(),
+            &self.destination.to_string(),
+            &self.session_id,
+            &self.payload_sha256,
+            &self.payload_bytes.to_string(),
+            &categories,
+            &self.prev_hash,
+        ] {
+            hasher.update(field.as_bytes());
+            hasher.update([0u8]);
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Result of verifying the hash chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditVerification {
+    /// Number of entries checked
+    pub entries_checked: u64,
+    /// First sequence number where the chain is broken, if any
+    pub first_invalid: Option<u64>,
+    /// Description of the problem, if any
+    pub error: Option<String>,
+}
+
+impl AuditVerification {
+    /// Whether the whole chain verified
+    pub fn is_valid(&self) -> bool {
+        self.first_invalid.is_none()
+    }
+}
+
+/// Filter for listing entries
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    /// Only entries for this session
+    pub session_id: Option<String>,
+    /// Only entries at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Maximum number of entries (the most recent ones are kept)
+    pub limit: Option<usize>,
+}
+
+/// Append-only, hash-chained audit log
+///
+/// Backed by SQLite like the [`TokenVault`](crate::TokenVault), but stored
+/// in its own database so it can be exported or archived independently.
+pub struct AuditLog {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl AuditLog {
+    /// Open or create an audit log database
+    pub fn open<P: AsRef<Path>>(db_path: P) -> PrivacyResult<Self> {
+        Self::init(Connection::open(db_path)?)
+    }
+
+    /// Create an in-memory audit log (for testing)
+    pub fn in_memory() -> PrivacyResult<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> PrivacyResult<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS audit_log (
+                seq INTEGER PRIMARY KEY,
+                timestamp TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                destination TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                payload_sha256 TEXT NOT NULL,
+                payload_bytes INTEGER NOT NULL,
+                categories TEXT NOT NULL,
+                prev_hash TEXT NOT NULL,
+                entry_hash TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_audit_session ON audit_log(session_id);
+            CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+            BEGIN
+                SELECT RAISE(ABORT, 'audit log is append-only');
+            END;
+            CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+            BEGIN
+                SELECT RAISE(ABORT, 'audit log is append-only');
+            END;",
+        )?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Append a record to the log
+    ///
+    /// The new entry is chained to the current head inside a transaction,
+    /// so concurrent writers cannot fork the chain.
+    #[instrument(skip(self, record), fields(kind = record.kind.as_str(), destination = %record.destination))]
+    pub fn append(&self, record: AuditRecord) -> PrivacyResult<AuditEntry> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| PrivacyError::Internal(format!("Lock poisoned: {}", e)))?;
+        let tx = conn.transaction()?;
+
+        let head: Option<(u64, String)> = tx
+            .query_row(
+                "SELECT seq, entry_hash FROM audit_log ORDER BY seq DESC LIMIT 1",
+                [],
+                |row| Ok((row.get::<_, i64>(0)? as u64, row.get(1)?)),
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(e),
+            })?;
+        let (seq, prev_hash) = match head {
+            Some((seq, hash)) => (seq + 1, hash),
+            None => (1, GENESIS_HASH.to_string()),
+        };
+
+        let mut entry = AuditEntry {
+            seq,
+            timestamp: Utc::now(),
+            kind: record.kind,
+            destination: record.destination,
+            session_id: record.session_id,
+            payload_sha256: record.payload_sha256,
+            payload_bytes: record.payload_bytes,
+            categories: record.categories,
+            prev_hash,
+            entry_hash: String::new(),
+        };
+        entry.entry_hash = entry.compute_hash();
+
+        tx.execute(
+            "INSERT INTO audit_log (seq, timestamp, kind, destination, session_id,
+                payload_sha256, payload_bytes, categories, prev_hash, entry_hash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                entry.seq as i64,
+                entry.timestamp.to_rfc3339_opts(SecondsFormat::Micros, true),
+                entry.kind.as_str(),
+                entry.destination.to_string(),
+                entry.session_id,
+                entry.payload_sha256,
+                entry.payload_bytes as i64,
+                serde_json::to_string(&entry.categories)
+                    .map_err(|e| PrivacyError::AuditError(e.to_string()))?,
+                entry.prev_hash,
+                entry.entry_hash,
+            ],
+        )?;
+        tx.commit()?;
+
+        debug!(seq = entry.seq, "Audit entry appended");
+        Ok(entry)
+    }
+
+    /// List entries in chain order
+    pub fn entries(&self, filter: &AuditFilter) -> PrivacyResult<Vec<AuditEntry>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| PrivacyError::Internal(format!("Lock poisoned: {}", e)))?;
+
+        let limit = filter.limit.map(|l| l as i64).unwrap_or(-1);
+        let since = filter
+            .since
+            .map(|t| t.to_rfc3339_opts(SecondsFormat::Micros, true));
+
+        let mut stmt = conn.prepare(
+            "SELECT * FROM (
+                SELECT seq, timestamp, kind, destination, session_id, payload_sha256,
+                       payload_bytes, categories, prev_hash, entry_hash
+                FROM audit_log
+                WHERE (?1 IS NULL OR session_id = ?1)
+                  AND (?2 IS NULL OR timestamp >= ?2)
+                ORDER BY seq DESC
+                LIMIT ?3
+             ) ORDER BY seq ASC",
+        )?;
+        let rows = stmt.query_map(params![filter.session_id, since, limit], Self::row_to_raw)?;
+
+        rows.map(|row| Self::raw_to_entry(row?)).collect()
+    }
+
+    /// Number of entries in the log
+    pub fn len(&self) -> PrivacyResult<u64> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| PrivacyError::Internal(format!("Lock poisoned: {}", e)))?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM audit_log", [], |row| row.get(0))?;
+        Ok(count as u64)
+    }
+
+    /// Check if the log has no entries
+    pub fn is_empty(&self) -> PrivacyResult<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Walk the whole chain and check every link and hash
+    pub fn verify(&self) -> PrivacyResult<AuditVerification> {
+        let entries = self.entries(&AuditFilter::default())?;
+
+        let mut expected_prev = GENESIS_HASH.to_string();
+        for (expected_seq, entry) in (1u64..).zip(entries.iter()) {
+            let problem = if entry.seq != expected_seq {
+                Some(format!(
+                    "expected sequence {}, found {}",
+                    expected_seq, entry.seq
+                ))
+            } else if entry.prev_hash != expected_prev {
+                Some("link to previous entry does not match".to_string())
+            } else if entry.compute_hash() != entry.entry_hash {
+                Some("entry contents do not match its hash".to_string())
+            } else {
+                None
+            };
+
+            if let Some(error) = problem {
+                return Ok(AuditVerification {
+                    entries_checked: expected_seq - 1,
+                    first_invalid: Some(entry.seq),
+                    error: Some(error),
+                });
+            }
+
+            expected_prev = entry.entry_hash.clone();
+        }
+
+        Ok(AuditVerification {
+            entries_checked: entries.len() as u64,
+            first_invalid: None,
+            error: None,
+        })
+    }
+
+    /// Export entries as pretty-printed JSON
+    pub fn export_json(&self, filter: &AuditFilter) -> PrivacyResult<String> {
+        serde_json::to_string_pretty(&self.entries(filter)?)
+            .map_err(|e| PrivacyError::AuditError(e.to_string()))
+    }
+
+    /// Export entries as JSON Lines (one entry per line)
+    pub fn export_jsonl(&self, filter: &AuditFilter) -> PrivacyResult<String> {
+        let mut out = String::new();
+        for entry in self.entries(filter)? {
+            out.push_str(
+                &serde_json::to_string(&entry)
+                    .map_err(|e| PrivacyError::AuditError(e.to_string()))?,
+            );
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Export entries as CSV
+    pub fn export_csv(&self, filter: &AuditFilter) -> PrivacyResult<String> {
+        let mut out = String::from(
+            "seq,timestamp,kind,destination,session_id,payload_sha256,payload_bytes,categories,prev_hash,entry_hash\n",
+        );
+        for entry in self.entries(filter)? {
+            let categories = entry
+                .categories
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>()
+                .join(";");
+            out.push_str(&format!(
+                "{},{},{},{},\"{}\",{},{},{},{},{}\n",
+                entry.seq,
+                entry.timestamp.to_rfc3339_opts(SecondsFormat::Micros, true),
+                entry.kind.as_str(),
+                entry.destination,
+                entry.session_id.replace('"', "\"\""),
+                entry.payload_sha256,
+                entry.payload_bytes,
+                categories,
+                entry.prev_hash,
+                entry.entry_hash,
+            ));
+        }
+        Ok(out)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn row_to_raw(
+        row: &rusqlite::Row<'_>,
+    ) -> rusqlite::Result<(
+        i64,
+        String,
+        String,
+        String,
+        String,
+        String,
+        i64,
+        String,
+        String,
+        String,
+    )> {
+        Ok((
+            row.get(0)?,
+            row.get(1)?,
+            row.get(2)?,
+            row.get(3)?,
+            row.get(4)?,
+            row.get(5)?,
+            row.get(6)?,
+            row.get(7)?,
+            row.get(8)?,
+            row.get(9)?,
+        ))
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn raw_to_entry(
+        raw: (
+            i64,
+            String,
+            String,
+            String,
+            String,
+            String,
+            i64,
+            String,
+            String,
+            String,
+        ),
+    ) -> PrivacyResult<AuditEntry> {
+        let (
+            seq,
+            timestamp,
+            kind,
+            destination,
+            session_id,
+            payload_sha256,
+            payload_bytes,
+            categories,
+            prev_hash,
+            entry_hash,
+        ) = raw;
+
+        let timestamp = DateTime::parse_from_rfc3339(&timestamp)
+            .map_err(|e| PrivacyError::AuditError(format!("Bad timestamp at {}: {}", seq, e)))?
+            .with_timezone(&Utc);
+        let destination = match destination.as_str() {
+            "local" => Destination::Local,
+            "cloud" => Destination::Cloud,
+            other => {
+                return Err(PrivacyError::AuditError(format!(
+                    "Unknown destination '{}' at {}",
+                    other, seq
+                )))
+            },
+        };
+        let categories = serde_json::from_str(&categories)
+            .map_err(|e| PrivacyError::AuditError(format!("Bad categories at {}: {}", seq, e)))?;
+
+        Ok(AuditEntry {
+            seq: seq as u64,
+            timestamp,
+            kind: AuditEventKind::parse(&kind)?,
+            destination,
+            session_id,
+            payload_sha256,
+            payload_bytes: payload_bytes as u64,
+            categories,
+            prev_hash,
+            entry_hash,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(session: &str, payload: &str) -> AuditRecord {
+        AuditRecord::from_payload(
+            AuditEventKind::Escalation,
+            Destination::Cloud,
+            session,
+            payload.as_bytes(),
+        )
+    }
+
+    #[test]
+    fn test_record_counts_tokens_not_originals() {
+        let r = record(
+            "s1",
+            r#"{"query":"mail [EMAIL_0001] and [EMAIL_0002], call [PHONE_0001]"}"#,
+        );
+
+        assert_eq!(r.categories.get("EMAIL"), Some(&2));
+        assert_eq!(r.categories.get("PHONE"), Some(&1));
+        assert_eq!(r.payload_sha256.len(), 64);
+    }
+
+    #[test]
+    fn test_append_chains_entries() {
+        let log = AuditLog::in_memory().unwrap();
+
+        let first = log.append(record("s1", "one")).unwrap();
+        let second = log.append(record("s2", "two")).unwrap();
+
+        assert_eq!(first.seq, 1);
+        assert_eq!(first.prev_hash, GENESIS_HASH);
+        assert_eq!(second.prev_hash, first.entry_hash);
+        assert_eq!(log.len().unwrap(), 2);
+        assert!(log.verify().unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_filter_by_session_and_limit() {
+        let log = AuditLog::in_memory().unwrap();
+        for i in 0..5 {
+            log.append(record(if i % 2 == 0 { "even" } else { "odd" }, "x"))
+                .unwrap();
+        }
+
+        let even = log
+            .entries(&AuditFilter {
+                session_id: Some("even".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            even.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![1, 3, 5]
+        );
+
+        let last_two = log
+            .entries(&AuditFilter {
+                limit: Some(2),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            last_two.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![4, 5]
+        );
+    }
+
+    #[test]
+    fn test_update_and_delete_rejected() {
+        let log = AuditLog::in_memory().unwrap();
+        log.append(record("s1", "one")).unwrap();
+
+        let conn = log.conn.lock().unwrap();
+        assert!(conn
+            .execute("UPDATE audit_log SET session_id = 'x'", [])
+            .is_err());
+        assert!(conn.execute("DELETE FROM audit_log", []).is_err());
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let log = AuditLog::in_memory().unwrap();
+        for i in 0..3 {
+            log.append(record("s1", &format!("payload {}", i))).unwrap();
+        }
+
+        {
+            let conn = log.conn.lock().unwrap();
+            conn.execute_batch(
+                "DROP TRIGGER audit_log_no_update;
+                 UPDATE audit_log SET categories = '{\"EMAIL\":0}' WHERE seq = 2;",
+            )
+            .unwrap();
+        }
+
+        let result = log.verify().unwrap();
+        assert!(!result.is_valid());
+        assert_eq!(result.first_invalid, Some(2));
+        assert_eq!(result.entries_checked, 1);
+    }
+
+    #[test]
+    fn test_export_formats() {
+        let log = AuditLog::in_memory().unwrap();
+        log.append(record("s1", "[EMAIL_0001]")).unwrap();
+
+        let json: Vec<AuditEntry> =
+            serde_json::from_str(&log.export_json(&AuditFilter::default()).unwrap()).unwrap();
+        assert_eq!(json.len(), 1);
+
+        let jsonl = log.export_jsonl(&AuditFilter::default()).unwrap();
+        assert_eq!(jsonl.lines().count(), 1);
+
+        let csv = log.export_csv(&AuditFilter::default()).unwrap();
+        assert!(csv.lines().nth(1).unwrap().contains("EMAIL=1"));
+    }
+}
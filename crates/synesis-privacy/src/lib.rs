@@ -16,13 +16,19 @@
 //! Response ← Reinflate ← [TOKEN_XXXX] ← Cloud Response
 //! ```
 
+pub mod audit;
 pub mod patterns;
+pub mod policy;
 pub mod redactor;
+pub mod scan;
 pub mod vault;
 
 // Re-exports
+pub use audit::{AuditEntry, AuditEventKind, AuditFilter, AuditLog, AuditRecord, AuditVerification};
 pub use patterns::{Pattern, PatternMatch, PatternSet, PatternType};
-pub use redactor::{RedactionResult, Redactor, RedactorConfig};
+pub use policy::{AllowList, Destination, DestinationRules, OutboundAction, PrivacyPolicy};
+pub use redactor::{CustomPatternConfig, RedactionResult, Redactor, RedactorConfig};
+pub use scan::{ScanFinding, ScanOptions, ScanReport, SkippedFile};
 pub use vault::{SessionStats, TokenVault};
 
 /// Result type for privacy operations
@@ -37,6 +43,12 @@ pub enum PrivacyError {
     #[error("Vault error: {0}")]
     VaultError(String),
 
+    #[error("Policy error: {0}")]
+    PolicyError(String),
+
+    #[error("Audit log error: {0}")]
+    AuditError(String),
+
     #[error("Token not found: {0}")]
     TokenNotFound(String),
 
@@ -125,7 +125,7 @@ static COMPILED_BUILTIN_PATTERNS: Lazy<Vec<Pattern>> = Lazy::new(|| {
 
     // Sort by priority (highest first)
     let mut sorted_patterns = patterns;
-    sorted_patterns.sort_by(|a, b| b.priority.cmp(&a.priority));
+    sorted_patterns.sort_by_key(|p| std::cmp::Reverse(p.priority));
     sorted_patterns
 });
 
@@ -614,7 +614,7 @@ impl PatternSet {
     pub fn add(&mut self, pattern: Pattern) {
         self.patterns.push(pattern);
         // Sort by priority (highest first)
-        self.patterns.sort_by(|a, b| b.priority.cmp(&a.priority));
+        self.patterns.sort_by_key(|p| std::cmp::Reverse(p.priority));
     }
 
     /// Add a custom pattern
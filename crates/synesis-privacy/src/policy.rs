@@ -0,0 +1,531 @@
+//! Redaction Policies
+//!
+//! Named redaction profiles, allow-lists and per-destination rules.
+//!
+//! A [`PrivacyPolicy`] bundles several [`RedactorConfig`] profiles under
+//! names such as `strict`, `internal` or `code-only`, selects one of them as
+//! the active profile, and decides which profile applies to each
+//! [`Destination`] the text is headed for.
+//!
+//! # Destinations
+//!
+//! - **Local**: processing that never leaves the device (defaults to the
+//!   active profile, may be set to `none` to skip redaction entirely)
+//! - **Cloud**: anything escalated through `synesis-cloud` (defaults to
+//!   `strict`)
+//!
+//! # Allow-Lists
+//!
+//! Values on an allow-list are never redacted, even when they match a
+//! pattern. The policy-wide allow-list is merged into every profile, so a
+//! public support address only needs to be listed once.
+//!
+//! # TOML Format
+//!
+//! ```toml
+//! active_profile = "internal"
+//!
+//! [allow_list]
+//! values = ["support@superinstance.ai"]
+//! patterns = ['^/srv/docs/']
+//!
+//! [destinations]
+//! local = "none"
+//! cloud = "strict"
+//!
+//! [profiles.team]
+//! redact_paths = false
+//! custom_patterns = [{ name = "EMPLOYEE", pattern = 'EMP-[0-9]{6}' }]
+//! ```
+//!
+//! Profiles defined in TOML are layered on top of the built-in profiles;
+//! a TOML profile with a built-in name replaces it.
+//!
+//! # Outbound Action
+//!
+//! `outbound = "block"` (default) or `"redact"` tells the cloud leak guard
+//! what to do when an outbound payload still contains sensitive data.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::patterns::PatternMatch;
+use crate::redactor::{Redactor, RedactorConfig};
+use crate::vault::TokenVault;
+use crate::{PrivacyError, PrivacyResult};
+
+/// Name of the built-in profile that redacts every category
+pub const PROFILE_STRICT: &str = "strict";
+
+/// Name of the built-in profile for trusted internal networks
+pub const PROFILE_INTERNAL: &str = "internal";
+
+/// Name of the built-in profile that only redacts credentials
+pub const PROFILE_CODE_ONLY: &str = "code-only";
+
+/// Destination rule value that disables redaction
+pub const RULE_NONE: &str = "none";
+
+/// Destination rule value that defers to the active profile
+pub const RULE_ACTIVE: &str = "active";
+
+/// Where redacted text is headed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Destination {
+    /// Local-only processing (never leaves the device)
+    Local,
+    /// Cloud escalation via `synesis-cloud`
+    Cloud,
+}
+
+impl std::fmt::Display for Destination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Destination::Local => write!(f, "local"),
+            Destination::Cloud => write!(f, "cloud"),
+        }
+    }
+}
+
+/// What the outbound leak guard does with sensitive data it finds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboundAction {
+    /// Refuse to send the payload
+    #[default]
+    Block,
+    /// Mask the offending values and send
+    Redact,
+}
+
+/// Values that must never be redacted
+///
+/// `values` are compared against the whole matched text; `patterns` are
+/// regexes tested against the matched text.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AllowList {
+    /// Exact values to leave untouched
+    pub values: Vec<String>,
+    /// Regex patterns for values to leave untouched
+    pub patterns: Vec<String>,
+}
+
+impl AllowList {
+    /// Check if the allow-list has no entries
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.patterns.is_empty()
+    }
+
+    /// Merge another allow-list into this one (duplicates are skipped)
+    pub fn merge(&mut self, other: &AllowList) {
+        for value in &other.values {
+            if !self.values.contains(value) {
+                self.values.push(value.clone());
+            }
+        }
+        for pattern in &other.patterns {
+            if !self.patterns.contains(pattern) {
+                self.patterns.push(pattern.clone());
+            }
+        }
+    }
+
+    /// Compile into a matcher
+    pub fn compile(&self) -> PrivacyResult<AllowMatcher> {
+        let patterns = self
+            .patterns
+            .iter()
+            .map(|p| {
+                Regex::new(p).map_err(|e| {
+                    PrivacyError::PatternError(format!("Invalid allow-list pattern '{}': {}", p, e))
+                })
+            })
+            .collect::<PrivacyResult<Vec<_>>>()?;
+
+        Ok(AllowMatcher {
+            values: self.values.clone(),
+            patterns,
+        })
+    }
+}
+
+/// Compiled allow-list
+#[derive(Debug, Clone, Default)]
+pub struct AllowMatcher {
+    values: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl AllowMatcher {
+    /// Check if a matched value is allowed through unredacted
+    pub fn is_allowed(&self, value: &str) -> bool {
+        self.values.iter().any(|v| v == value) || self.patterns.iter().any(|p| p.is_match(value))
+    }
+
+    /// Drop allowed matches from a match list
+    pub fn filter(&self, matches: Vec<PatternMatch>) -> Vec<PatternMatch> {
+        if self.is_empty() {
+            return matches;
+        }
+        matches
+            .into_iter()
+            .filter(|m| !self.is_allowed(&m.matched_text))
+            .collect()
+    }
+
+    /// Check if the matcher has no entries
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.patterns.is_empty()
+    }
+}
+
+/// Which profile applies to each destination
+///
+/// Each rule is a profile name, `"active"` for the active profile, or
+/// `"none"` to skip redaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DestinationRules {
+    /// Rule for local-only processing
+    pub local: String,
+    /// Rule for cloud escalation
+    pub cloud: String,
+}
+
+impl Default for DestinationRules {
+    fn default() -> Self {
+        Self {
+            local: RULE_ACTIVE.to_string(),
+            cloud: PROFILE_STRICT.to_string(),
+        }
+    }
+}
+
+impl DestinationRules {
+    /// Get the rule for a destination
+    pub fn rule(&self, destination: Destination) -> &str {
+        match destination {
+            Destination::Local => &self.local,
+            Destination::Cloud => &self.cloud,
+        }
+    }
+}
+
+/// A set of named redaction profiles plus destination rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacyPolicy {
+    /// Name of the active profile
+    pub active_profile: String,
+    /// Allow-list merged into every profile
+    pub allow_list: AllowList,
+    /// Per-destination rules
+    pub destinations: DestinationRules,
+    /// What the outbound leak guard does with sensitive data
+    pub outbound: OutboundAction,
+    /// Named profiles
+    pub profiles: BTreeMap<String, RedactorConfig>,
+}
+
+impl Default for PrivacyPolicy {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl PrivacyPolicy {
+    /// Create a policy containing only the built-in profiles
+    ///
+    /// - `strict`: every category redacted
+    /// - `internal`: personal data and credentials, but not paths, IPs or URLs
+    /// - `code-only`: API keys and secrets only
+    ///
+    /// The active profile is `strict`.
+    pub fn builtin() -> Self {
+        let mut profiles = BTreeMap::new();
+        profiles.insert(PROFILE_STRICT.to_string(), RedactorConfig::strict());
+        profiles.insert(PROFILE_INTERNAL.to_string(), RedactorConfig::internal());
+        profiles.insert(PROFILE_CODE_ONLY.to_string(), RedactorConfig::code_only());
+
+        Self {
+            active_profile: PROFILE_STRICT.to_string(),
+            allow_list: AllowList::default(),
+            destinations: DestinationRules::default(),
+            outbound: OutboundAction::default(),
+            profiles,
+        }
+    }
+
+    /// Parse a policy from TOML
+    ///
+    /// Profiles in the TOML are layered on top of the built-in profiles.
+    ///
+    /// # Errors
+    /// Returns error if the TOML is malformed or the policy fails
+    /// [`validate`](Self::validate).
+    pub fn from_toml_str(content: &str) -> PrivacyResult<Self> {
+        let parsed: PrivacyPolicy = toml::from_str(content)
+            .map_err(|e| PrivacyError::PolicyError(format!("Invalid policy TOML: {}", e)))?;
+
+        let mut policy = Self::builtin();
+        policy.active_profile = parsed.active_profile;
+        policy.allow_list = parsed.allow_list;
+        policy.destinations = parsed.destinations;
+        policy.outbound = parsed.outbound;
+        policy.profiles.extend(parsed.profiles);
+        policy.validate()?;
+
+        Ok(policy)
+    }
+
+    /// Load a policy from a TOML file
+    pub fn load<P: AsRef<Path>>(path: P) -> PrivacyResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            PrivacyError::PolicyError(format!("Cannot read policy {}: {}", path.display(), e))
+        })?;
+        Self::from_toml_str(&content)
+    }
+
+    /// Serialize the policy to TOML
+    pub fn to_toml_string(&self) -> PrivacyResult<String> {
+        toml::to_string_pretty(self)
+            .map_err(|e| PrivacyError::PolicyError(format!("Cannot serialize policy: {}", e)))
+    }
+
+    /// Add or replace a profile
+    pub fn add_profile(&mut self, name: &str, config: RedactorConfig) {
+        self.profiles.insert(name.to_string(), config);
+    }
+
+    /// Select the active profile
+    ///
+    /// # Errors
+    /// Returns error if no profile with that name exists.
+    pub fn set_active(&mut self, name: &str) -> PrivacyResult<()> {
+        if !self.profiles.contains_key(name) {
+            return Err(self.unknown_profile(name));
+        }
+        self.active_profile = name.to_string();
+        Ok(())
+    }
+
+    /// Names of all profiles, sorted
+    pub fn profile_names(&self) -> Vec<&str> {
+        self.profiles.keys().map(String::as_str).collect()
+    }
+
+    /// Get a profile by name, with the policy-wide allow-list merged in
+    pub fn profile(&self, name: &str) -> PrivacyResult<RedactorConfig> {
+        let mut config = self
+            .profiles
+            .get(name)
+            .cloned()
+            .ok_or_else(|| self.unknown_profile(name))?;
+        config.allow_list.merge(&self.allow_list);
+        Ok(config)
+    }
+
+    /// Get the active profile
+    pub fn active(&self) -> PrivacyResult<RedactorConfig> {
+        self.profile(&self.active_profile)
+    }
+
+    /// Name of the profile used for a destination (`None` if redaction is
+    /// skipped)
+    pub fn profile_name_for(&self, destination: Destination) -> Option<&str> {
+        match self.destinations.rule(destination) {
+            RULE_NONE => None,
+            RULE_ACTIVE => Some(&self.active_profile),
+            name => Some(name),
+        }
+    }
+
+    /// Whether text headed for a destination is redacted at all
+    pub fn redacts(&self, destination: Destination) -> bool {
+        self.profile_name_for(destination).is_some()
+    }
+
+    /// Get the redactor configuration for a destination
+    ///
+    /// When the destination rule is `none`, a configuration with every
+    /// pattern disabled is returned so callers can use a single code path.
+    pub fn config_for(&self, destination: Destination) -> PrivacyResult<RedactorConfig> {
+        match self.profile_name_for(destination) {
+            Some(name) => self.profile(name),
+            None => Ok(RedactorConfig::passthrough()),
+        }
+    }
+
+    /// Build a redactor for a destination
+    pub fn redactor_for(
+        &self,
+        destination: Destination,
+        vault: TokenVault,
+    ) -> PrivacyResult<Redactor> {
+        Redactor::new(self.config_for(destination)?, vault)
+    }
+
+    /// Check that every referenced profile exists and all patterns compile
+    pub fn validate(&self) -> PrivacyResult<()> {
+        if !self.profiles.contains_key(&self.active_profile) {
+            return Err(self.unknown_profile(&self.active_profile));
+        }
+
+        for destination in [Destination::Local, Destination::Cloud] {
+            if let Some(name) = self.profile_name_for(destination) {
+                if !self.profiles.contains_key(name) {
+                    return Err(PrivacyError::PolicyError(format!(
+                        "Destination '{}' uses unknown profile '{}'",
+                        destination, name
+                    )));
+                }
+            }
+        }
+
+        self.allow_list.compile()?;
+        for config in self.profiles.values() {
+            config.allow_list.compile()?;
+            for custom in &config.custom_patterns {
+                Regex::new(&custom.pattern).map_err(|e| {
+                    PrivacyError::PatternError(format!(
+                        "Invalid custom pattern '{}': {}",
+                        custom.name, e
+                    ))
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn unknown_profile(&self, name: &str) -> PrivacyError {
+        PrivacyError::PolicyError(format!(
+            "Unknown redaction profile '{}' (available: {})",
+            name,
+            self.profile_names().join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_profiles() {
+        let policy = PrivacyPolicy::builtin();
+
+        assert_eq!(
+            policy.profile_names(),
+            vec!["code-only", "internal", "strict"]
+        );
+        assert!(policy.profile("strict").unwrap().redact_paths);
+        assert!(!policy.profile("internal").unwrap().redact_paths);
+        assert!(!policy.profile("code-only").unwrap().redact_emails);
+        assert!(policy.profile("code-only").unwrap().redact_api_keys);
+    }
+
+    #[test]
+    fn test_from_toml_layers_on_builtins() {
+        let policy = PrivacyPolicy::from_toml_str(
+            r#"
+            active_profile = "team"
+
+            [allow_list]
+            values = ["support@superinstance.ai"]
+
+            [profiles.team]
+            redact_paths = false
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(policy.active_profile, "team");
+        assert_eq!(policy.outbound, OutboundAction::Block);
+        assert!(policy.profiles.contains_key("strict"));
+
+        let team = policy.active().unwrap();
+        assert!(!team.redact_paths);
+        assert!(team.redact_emails);
+        assert_eq!(team.allow_list.values, vec!["support@superinstance.ai"]);
+    }
+
+    #[test]
+    fn test_unknown_active_profile_rejected() {
+        let err = PrivacyPolicy::from_toml_str(r#"active_profile = "missing""#).unwrap_err();
+        assert!(err.to_string().contains("missing"));
+    }
+
+    #[test]
+    fn test_unknown_destination_profile_rejected() {
+        let err = PrivacyPolicy::from_toml_str(
+            r#"
+            [destinations]
+            cloud = "nope"
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("nope"));
+    }
+
+    #[test]
+    fn test_destination_rules() {
+        let mut policy = PrivacyPolicy::builtin();
+        policy.set_active("code-only").unwrap();
+        policy.destinations.local = RULE_NONE.to_string();
+
+        assert!(!policy.redacts(Destination::Local));
+        assert_eq!(policy.profile_name_for(Destination::Cloud), Some("strict"));
+
+        let local = policy.config_for(Destination::Local).unwrap();
+        assert!(!local.redact_emails && !local.redact_api_keys);
+
+        policy.destinations.local = RULE_ACTIVE.to_string();
+        assert_eq!(
+            policy.profile_name_for(Destination::Local),
+            Some("code-only")
+        );
+    }
+
+    #[test]
+    fn test_allow_list_skips_redaction() {
+        let mut policy = PrivacyPolicy::builtin();
+        policy
+            .allow_list
+            .values
+            .push("support@superinstance.ai".to_string());
+        policy.allow_list.patterns.push("^/srv/docs/".to_string());
+
+        let mut redactor = policy
+            .redactor_for(Destination::Cloud, TokenVault::in_memory().unwrap())
+            .unwrap();
+        let result = redactor.redact(
+            "Mail support@superinstance.ai or alice@example.com, see /srv/docs/guide.md",
+            "session1",
+        );
+
+        assert!(result.redacted_text.contains("support@superinstance.ai"));
+        assert!(result.redacted_text.contains("/srv/docs/guide.md"));
+        assert!(!result.redacted_text.contains("alice@example.com"));
+        assert_eq!(result.stats.patterns_redacted, 1);
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let mut policy = PrivacyPolicy::builtin();
+        policy.set_active("internal").unwrap();
+        policy.outbound = OutboundAction::Redact;
+
+        let toml = policy.to_toml_string().unwrap();
+        let parsed = PrivacyPolicy::from_toml_str(&toml).unwrap();
+        assert_eq!(parsed.active_profile, "internal");
+        assert_eq!(parsed.destinations, policy.destinations);
+        assert_eq!(parsed.outbound, OutboundAction::Redact);
+    }
+}
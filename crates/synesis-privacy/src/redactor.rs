@@ -35,17 +35,22 @@ use std::collections::HashMap;
 use tracing::{debug, instrument};
 
 use crate::patterns::{PatternMatch, PatternSet, PatternType};
+use crate::policy::{AllowList, AllowMatcher};
 use crate::vault::TokenVault;
 use crate::{PrivacyResult, RedactionStats};
 
 // Token format constants
 
-/// Token regex pattern: [CATEGORY_0001]
-/// Must match the format generated by TokenVault
-const TOKEN_PATTERN: &str = r"\[([A-Z]+)_([0-9]{4})\]";
+/// Token regex pattern: [CATEGORY_0001], [CATEGORY_12345]
+/// Must match the format generated by TokenVault (at least 4 digits)
+pub(crate) const TOKEN_PATTERN: &str = r"\[([A-Z]+)_([0-9]{4,})\]";
 
 /// Redactor configuration
+///
+/// Missing fields take their defaults when deserialized (every category
+/// enabled), so a profile only needs to list what it turns off.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct RedactorConfig {
     /// Enable email redaction
     pub redact_emails: bool,
@@ -65,6 +70,8 @@ pub struct RedactorConfig {
     pub redact_urls: bool,
     /// Custom patterns to redact
     pub custom_patterns: Vec<CustomPatternConfig>,
+    /// Values that are never redacted
+    pub allow_list: AllowList,
 }
 
 impl Default for RedactorConfig {
@@ -79,6 +86,60 @@ impl Default for RedactorConfig {
             redact_paths: true,
             redact_urls: true,
             custom_patterns: vec![],
+            allow_list: AllowList::default(),
+        }
+    }
+}
+
+impl RedactorConfig {
+    /// Every category redacted
+    pub fn strict() -> Self {
+        Self::default()
+    }
+
+    /// Personal data and credentials, but not paths, IPs or URLs
+    ///
+    /// Suitable for trusted internal networks where infrastructure details
+    /// are not sensitive.
+    pub fn internal() -> Self {
+        Self {
+            redact_ips: false,
+            redact_paths: false,
+            redact_urls: false,
+            ..Self::default()
+        }
+    }
+
+    /// API keys and secrets only
+    ///
+    /// Suitable for source code, where emails and paths are usually part of
+    /// the content being discussed.
+    pub fn code_only() -> Self {
+        Self {
+            redact_emails: false,
+            redact_phones: false,
+            redact_ssns: false,
+            redact_credit_cards: false,
+            redact_ips: false,
+            redact_paths: false,
+            redact_urls: false,
+            ..Self::default()
+        }
+    }
+
+    /// Nothing redacted
+    pub fn passthrough() -> Self {
+        Self {
+            redact_emails: false,
+            redact_phones: false,
+            redact_ssns: false,
+            redact_credit_cards: false,
+            redact_api_keys: false,
+            redact_ips: false,
+            redact_paths: false,
+            redact_urls: false,
+            custom_patterns: vec![],
+            allow_list: AllowList::default(),
         }
     }
 }
@@ -126,9 +187,9 @@ pub struct RedactionResult {
 /// assert!(result.redacted_text.contains("[EMAIL_"));
 /// ```
 pub struct Redactor {
-    #[allow(dead_code)]
     config: RedactorConfig,
     patterns: PatternSet,
+    allow: AllowMatcher,
     vault: TokenVault,
     token_regex: Regex,
 }
@@ -145,7 +206,7 @@ impl Redactor {
     ///
     /// # Errors
     /// Returns error if:
-    /// - Custom pattern regex is invalid
+    /// - Custom pattern or allow-list regex is invalid
     /// - Token regex compilation fails (should not happen)
     pub fn new(config: RedactorConfig, vault: TokenVault) -> PrivacyResult<Self> {
         let mut patterns = PatternSet::with_builtins();
@@ -167,6 +228,8 @@ impl Redactor {
             patterns.add_custom(&custom.name, &custom.pattern)?;
         }
 
+        let allow = config.allow_list.compile()?;
+
         // Token regex for reinflation
         let token_regex = Regex::new(TOKEN_PATTERN)
             .map_err(|e| crate::PrivacyError::PatternError(e.to_string()))?;
@@ -174,6 +237,7 @@ impl Redactor {
         Ok(Self {
             config,
             patterns,
+            allow,
             vault,
             token_regex,
         })
@@ -210,8 +274,9 @@ impl Redactor {
     pub fn redact(&mut self, text: &str, session_id: &str) -> RedactionResult {
         debug!("Redacting text");
 
-        // Find all matches (already sorted and deduplicated by PatternSet)
-        let matches = self.patterns.find_all_matches(text);
+        // Find all matches (already sorted and deduplicated by PatternSet),
+        // then drop allow-listed values
+        let matches = self.allow.filter(self.patterns.find_all_matches(text));
 
         if matches.is_empty() {
             return RedactionResult {
@@ -372,13 +437,49 @@ impl Redactor {
     }
 
     /// Check if text contains sensitive information
+    ///
+    /// Allow-listed values do not count as sensitive.
     pub fn contains_sensitive(&self, text: &str) -> bool {
-        self.patterns.contains_sensitive(text)
+        if self.allow.is_empty() {
+            return self.patterns.contains_sensitive(text);
+        }
+        !self.preview(text).is_empty()
     }
 
     /// Get a preview of what would be redacted (without storing)
     pub fn preview(&self, text: &str) -> Vec<PatternMatch> {
-        self.patterns.find_all_matches(text)
+        self.allow.filter(self.patterns.find_all_matches(text))
+    }
+
+    /// Irreversibly mask sensitive information
+    ///
+    /// Replaces each match with `[REDACTED_CATEGORY]`. Nothing is stored in
+    /// the vault and the markers deliberately do not match the reversible
+    /// token format, so they are never reinflated.
+    ///
+    /// # Returns
+    /// The masked text and the matches that were replaced
+    pub fn mask(&self, text: &str) -> (String, Vec<PatternMatch>) {
+        let matches = self.preview(text);
+        if matches.is_empty() {
+            return (text.to_string(), matches);
+        }
+
+        let mut result = String::with_capacity(text.len());
+        let mut last_end = 0;
+        for m in &matches {
+            result.push_str(&text[last_end..m.start]);
+            result.push_str(&format!("[REDACTED_{}]", m.pattern_type.token_prefix()));
+            last_end = m.end;
+        }
+        result.push_str(&text[last_end..]);
+
+        (result, matches)
+    }
+
+    /// Get the configuration this redactor was built from
+    pub fn config(&self) -> &RedactorConfig {
+        &self.config
     }
 
     /// Clear all tokens for a session
@@ -429,6 +530,17 @@ mod tests {
         assert_eq!(reinflated, original);
     }
 
+    #[test]
+    fn test_token_pattern_accepts_wide_counters() {
+        let regex = Regex::new(TOKEN_PATTERN).unwrap();
+
+        assert!(regex.is_match("[EMAIL_0001]"));
+        assert!(regex.is_match("[EMAIL_10000]"));
+        assert!(regex.is_match("[APIKEY_123456789]"));
+        assert!(!regex.is_match("[EMAIL_001]"));
+        assert!(!regex.is_match("[REDACTED_EMAIL]"));
+    }
+
     #[test]
     fn test_multiple_same_category_unique_tokens() {
         let mut redactor = create_test_redactor();
@@ -508,6 +620,18 @@ mod tests {
         assert_eq!(stats.tokens_created, 0);
     }
 
+    #[test]
+    fn test_mask_is_irreversible() {
+        let redactor = create_test_redactor();
+
+        let (masked, matches) = redactor.mask("Contact test@example.com now");
+
+        assert_eq!(masked, "Contact [REDACTED_EMAIL] now");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(redactor.reinflate(&masked), masked);
+        assert_eq!(redactor.get_stats("session1").tokens_created, 0);
+    }
+
     #[test]
     fn test_get_stats() {
         let mut redactor = create_test_redactor();
@@ -521,6 +645,34 @@ mod tests {
         assert_eq!(stats.by_type.get("PHONE"), Some(&1));
     }
 
+    #[test]
+    fn test_allow_list() {
+        let config = RedactorConfig {
+            allow_list: AllowList {
+                values: vec!["support@example.com".to_string()],
+                patterns: vec![],
+            },
+            ..RedactorConfig::default()
+        };
+        let mut redactor = Redactor::new(config, TokenVault::in_memory().unwrap()).unwrap();
+
+        assert!(!redactor.contains_sensitive("Mail support@example.com"));
+        assert!(redactor.contains_sensitive("Mail other@example.com"));
+
+        let result = redactor.redact("support@example.com or other@example.com", "session1");
+        assert!(result.redacted_text.starts_with("support@example.com or [EMAIL_"));
+    }
+
+    #[test]
+    fn test_passthrough_config() {
+        let mut redactor =
+            Redactor::new(RedactorConfig::passthrough(), TokenVault::in_memory().unwrap())
+                .unwrap();
+
+        let text = "Email test@example.com, key sk-abcdefghijklmnopqrstuvwxyz123456";
+        assert_eq!(redactor.redact(text, "session1").redacted_text, text);
+    }
+
     #[test]
     fn test_clear_session() {
         let mut redactor = create_test_redactor();
@@ -0,0 +1,316 @@
+//! File Scanning
+//!
+//! Finds sensitive data in files and directories without storing anything.
+//! Used before indexing documents into the knowledge vault and before
+//! sharing logs.
+//!
+//! Findings carry a file, line and column plus a masked rendering of the
+//! value (`jo***om`), so reports can be pasted into CI logs or tickets
+//! without leaking what they describe.
+//!
+//! # Skipped Files
+//!
+//! - Hidden files and directories (`.git`, `.env.example`, ...) unless the
+//!   path was given explicitly
+//! - Binary files (NUL byte in the first 8 KiB)
+//! - Files larger than [`ScanOptions::max_file_size`]
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::patterns::PatternType;
+use crate::redactor::Redactor;
+use crate::{PrivacyError, PrivacyResult};
+
+/// Default maximum file size scanned (10 MiB)
+const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Bytes inspected when detecting binary files
+const BINARY_SNIFF_LEN: usize = 8192;
+
+/// Options for scanning paths
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Descend into subdirectories
+    pub recursive: bool,
+    /// Include hidden files and directories found while walking
+    pub include_hidden: bool,
+    /// Skip files larger than this many bytes
+    pub max_file_size: u64,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            recursive: true,
+            include_hidden: false,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+}
+
+/// A sensitive value found in a file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanFinding {
+    /// File the value was found in
+    pub path: PathBuf,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column (in characters)
+    pub column: usize,
+    /// 1-based column just past the match (in characters, same line)
+    pub end_column: usize,
+    /// Type of sensitive data
+    pub pattern_type: PatternType,
+    /// Name of the pattern that matched
+    pub pattern_name: String,
+    /// Masked rendering of the value
+    pub masked_value: String,
+}
+
+/// A file that was not scanned
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkippedFile {
+    /// File path
+    pub path: PathBuf,
+    /// Why it was skipped
+    pub reason: String,
+}
+
+/// Result of scanning one or more paths
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanReport {
+    /// Number of files scanned
+    pub files_scanned: usize,
+    /// Files that were skipped
+    pub skipped: Vec<SkippedFile>,
+    /// All findings, ordered by file then position
+    pub findings: Vec<ScanFinding>,
+}
+
+impl ScanReport {
+    /// Whether anything sensitive was found
+    pub fn has_findings(&self) -> bool {
+        !self.findings.is_empty()
+    }
+
+    /// Finding counts by pattern type
+    pub fn counts_by_type(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for finding in &self.findings {
+            *counts
+                .entry(finding.pattern_type.display_name().to_string())
+                .or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Number of distinct files with findings
+    pub fn files_with_findings(&self) -> usize {
+        let mut paths: Vec<&PathBuf> = self.findings.iter().map(|f| &f.path).collect();
+        paths.dedup();
+        paths.len()
+    }
+}
+
+/// Mask a sensitive value for display
+///
+/// Keeps at most two characters at each end of values longer than eight
+/// characters, and hides shorter values entirely.
+pub fn mask_value(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len().max(3));
+    }
+    let head: String = chars[..2].iter().collect();
+    let tail: String = chars[chars.len() - 2..].iter().collect();
+    format!("{}***{}", head, tail)
+}
+
+/// Scan text and report findings with line/column positions
+pub fn scan_text(redactor: &Redactor, path: &Path, text: &str) -> Vec<ScanFinding> {
+    let matches = redactor.preview(text);
+    if matches.is_empty() {
+        return vec![];
+    }
+
+    // Byte offsets where each line starts
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+
+    matches
+        .into_iter()
+        .map(|m| {
+            let line_idx = line_starts.partition_point(|&start| start <= m.start) - 1;
+            let line_start = line_starts[line_idx];
+            let column = text[line_start..m.start].chars().count() + 1;
+            let first_line = m.matched_text.split('\n').next().unwrap_or_default();
+
+            ScanFinding {
+                path: path.to_path_buf(),
+                line: line_idx + 1,
+                column,
+                end_column: column + first_line.chars().count(),
+                pattern_type: m.pattern_type,
+                pattern_name: m.pattern_name,
+                masked_value: mask_value(&m.matched_text),
+            }
+        })
+        .collect()
+}
+
+/// Scan files and directories
+///
+/// Paths given explicitly are always scanned (even hidden ones); hidden
+/// entries found while walking directories follow
+/// [`ScanOptions::include_hidden`].
+///
+/// # Errors
+/// Returns error if a given path does not exist.
+pub fn scan_paths<P: AsRef<Path>>(
+    redactor: &Redactor,
+    paths: &[P],
+    options: &ScanOptions,
+) -> PrivacyResult<ScanReport> {
+    let mut files = Vec::new();
+    for path in paths {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(PrivacyError::Internal(format!(
+                "Path not found: {}",
+                path.display()
+            )));
+        }
+        collect_files(path, options, true, &mut files);
+    }
+    files.sort();
+    files.dedup();
+
+    let mut report = ScanReport::default();
+    for file in files {
+        match read_scannable(&file, options) {
+            Ok(text) => {
+                report.files_scanned += 1;
+                report.findings.extend(scan_text(redactor, &file, &text));
+            },
+            Err(reason) => report.skipped.push(SkippedFile { path: file, reason }),
+        }
+    }
+
+    Ok(report)
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n.starts_with('.') && n != "." && n != "..")
+        .unwrap_or(false)
+}
+
+fn collect_files(path: &Path, options: &ScanOptions, explicit: bool, out: &mut Vec<PathBuf>) {
+    if !explicit && !options.include_hidden && is_hidden(path) {
+        return;
+    }
+
+    if path.is_file() {
+        out.push(path.to_path_buf());
+        return;
+    }
+
+    if !path.is_dir() || (!explicit && !options.recursive) {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        // Don't follow symlinked directories (avoids cycles)
+        let is_symlink_dir = entry
+            .file_type()
+            .map(|t| t.is_symlink() && entry_path.is_dir())
+            .unwrap_or(false);
+        if !is_symlink_dir {
+            collect_files(&entry_path, options, false, out);
+        }
+    }
+}
+
+fn read_scannable(path: &Path, options: &ScanOptions) -> Result<String, String> {
+    let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > options.max_file_size {
+        return Err(format!("larger than {} bytes", options.max_file_size));
+    }
+
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    if bytes[..bytes.len().min(BINARY_SNIFF_LEN)].contains(&0) {
+        return Err("binary file".to_string());
+    }
+
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RedactorConfig, TokenVault};
+
+    fn redactor() -> Redactor {
+        Redactor::new(RedactorConfig::default(), TokenVault::in_memory().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_mask_value() {
+        assert_eq!(mask_value("john.doe@example.com"), "jo***om");
+        assert_eq!(mask_value("1.2.3.4"), "*******");
+        assert_eq!(mask_value("ab"), "***");
+    }
+
+    #[test]
+    fn test_scan_text_positions() {
+        let text = "first line\nmail: test@example.com\n  café 555-123-4567\n";
+        let findings = scan_text(&redactor(), Path::new("notes.txt"), text);
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!((findings[0].line, findings[0].column), (2, 7));
+        assert_eq!(findings[0].pattern_type, PatternType::Email);
+        assert_eq!(findings[0].masked_value, "te***om");
+        assert_eq!((findings[1].line, findings[1].column), (3, 8));
+        assert_eq!(findings[1].pattern_type, PatternType::Phone);
+    }
+
+    #[test]
+    fn test_scan_paths_walks_and_skips() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "contact test@example.com").unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub/b.log"), "nothing here").unwrap();
+        std::fs::write(dir.path().join("sub/c.bin"), b"\0\x01 test@example.com").unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".git/config"), "test@example.com").unwrap();
+
+        let report = scan_paths(&redactor(), &[dir.path()], &ScanOptions::default()).unwrap();
+
+        assert_eq!(report.files_scanned, 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].reason, "binary file");
+        assert_eq!(report.findings.len(), 1);
+        assert!(report.findings[0].path.ends_with("a.md"));
+        assert_eq!(report.counts_by_type().get("Email Address"), Some(&1));
+        assert_eq!(report.files_with_findings(), 1);
+    }
+
+    #[test]
+    fn test_scan_missing_path() {
+        let result = scan_paths(
+            &redactor(),
+            &["/definitely/not/here"],
+            &ScanOptions::default(),
+        );
+        assert!(result.is_err());
+    }
+}
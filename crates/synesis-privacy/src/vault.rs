@@ -18,8 +18,10 @@
 //!
 //! ## Data Protection
 //!
-//! - Tokens use UUID-style format: `[CATEGORY_NNNN]`
-//! - Counter is global (not per-session) for uniqueness
+//! - Tokens use the format `[CATEGORY_NNNN]`, zero-padded to at least four
+//!   digits and growing past `9999` (`[EMAIL_10000]`)
+//! - Counter is global (not per-session) for uniqueness, and persisted in the
+//!   `token_counters` table so it survives restarts and session cleanup
 //! - Session IDs enable token isolation and cleanup
 //! - SQLite database provides ACID guarantees
 //!
@@ -28,7 +30,14 @@
 //! The vault uses `Arc<Mutex<T>>` for thread-safe access:
 //! - Multiple threads can read/write concurrently
 //! - SQLite connection is protected by mutex
-//! - Counters are protected by separate mutex
+//! - Counters are incremented in the same transaction as the INSERT
+//!
+//! # Schema Versions
+//!
+//! Tracked with `PRAGMA user_version`:
+//! - `0`: `tokens` table only; counters lived in memory and restarted at 1
+//! - `1`: adds `token_counters`, seeded from the highest token number
+//!   already stored for each category
 //!
 //! # Performance
 //!
@@ -41,7 +50,7 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use tracing::{debug, info, instrument};
 
 use crate::{PrivacyError, PrivacyResult};
@@ -54,11 +63,12 @@ const MAX_CATEGORY_LENGTH: usize = 50;
 /// Maximum session ID length (prevents abuse)
 const MAX_SESSION_ID_LENGTH: usize = 255;
 
+/// Current vault schema version (`PRAGMA user_version`)
+const SCHEMA_VERSION: i64 = 1;
+
 /// The token vault for session-based token storage
 pub struct TokenVault {
     conn: Arc<Mutex<Connection>>,
-    /// Track counters per category for token generation (global, not per-session)
-    counters: Arc<Mutex<HashMap<String, u32>>>,
 }
 
 impl TokenVault {
@@ -90,34 +100,12 @@ impl TokenVault {
     /// - Subsequent opens: ~1-5ms (attaches to existing database)
     pub fn new<P: AsRef<Path>>(db_path: P) -> PrivacyResult<Self> {
         let conn = Connection::open(db_path)?;
-
-        // Create the tokens table as per Session 12 spec
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS tokens (
-                id INTEGER PRIMARY KEY,
-                token TEXT UNIQUE NOT NULL,
-                category TEXT NOT NULL,
-                original TEXT NOT NULL,
-                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-                session_id TEXT NOT NULL
-            )",
-            [],
-        )?;
-
-        // Create index on session_id for efficient cleanup
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_session_id ON tokens(session_id)",
-            [],
-        )?;
-
-        // Create index on token for efficient lookups
-        conn.execute("CREATE INDEX IF NOT EXISTS idx_token ON tokens(token)", [])?;
+        Self::init_schema(&conn)?;
 
         info!("Token vault initialized at database path");
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
-            counters: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
@@ -136,9 +124,18 @@ impl TokenVault {
     /// - Operations: Same as file-based vault
     pub fn in_memory() -> PrivacyResult<Self> {
         let conn = Connection::open_in_memory()?;
+        Self::init_schema(&conn)?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
 
+    /// Create tables and migrate older vaults to the current schema
+    fn init_schema(conn: &Connection) -> PrivacyResult<()> {
+        // Create the tokens table as per Session 12 spec
         conn.execute(
-            "CREATE TABLE tokens (
+            "CREATE TABLE IF NOT EXISTS tokens (
                 id INTEGER PRIMARY KEY,
                 token TEXT UNIQUE NOT NULL,
                 category TEXT NOT NULL,
@@ -149,14 +146,47 @@ impl TokenVault {
             [],
         )?;
 
-        conn.execute("CREATE INDEX idx_session_id ON tokens(session_id)", [])?;
+        // Create index on session_id for efficient cleanup
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_session_id ON tokens(session_id)",
+            [],
+        )?;
 
-        conn.execute("CREATE INDEX idx_token ON tokens(token)", [])?;
+        // Create index on token for efficient lookups
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_token ON tokens(token)", [])?;
 
-        Ok(Self {
-            conn: Arc::new(Mutex::new(conn)),
-            counters: Arc::new(Mutex::new(HashMap::new())),
-        })
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version < 1 {
+            Self::migrate_v1(conn)?;
+        }
+
+        Ok(())
+    }
+
+    /// Schema v1: persist per-category counters
+    ///
+    /// Seeds each counter from the highest number already issued, so tokens
+    /// created by older versions are never handed out again.
+    fn migrate_v1(conn: &Connection) -> PrivacyResult<()> {
+        conn.execute_batch(&format!(
+            "BEGIN;
+             CREATE TABLE IF NOT EXISTS token_counters (
+                 category TEXT PRIMARY KEY,
+                 value INTEGER NOT NULL
+             );
+             INSERT OR REPLACE INTO token_counters (category, value)
+                 SELECT category,
+                        MAX(CAST(substr(token, length(category) + 3,
+                                        length(token) - length(category) - 3) AS INTEGER))
+                 FROM tokens
+                 GROUP BY category;
+             PRAGMA user_version = {};
+             COMMIT;",
+            SCHEMA_VERSION
+        ))?;
+
+        debug!("Migrated token vault to schema v1");
+        Ok(())
     }
 
     /// Store a value and return its token
@@ -166,8 +196,9 @@ impl TokenVault {
     ///
     /// # Token Format
     /// - Category: Alphanumeric + underscores (validated)
-    /// - Counter: Global per category, 4-digit zero-padded (0001-9999)
-    /// - Example: `[EMAIL_0001]`, `[PHONE_0001]`, `[SSN_0001]`
+    /// - Counter: Global per category, persisted, zero-padded to at least
+    ///   4 digits (0001-9999, then 10000 and up)
+    /// - Example: `[EMAIL_0001]`, `[PHONE_0001]`, `[SSN_12345]`
     ///
     /// # Arguments
     /// * `category` - The category of sensitive data (e.g., "EMAIL", "PHONE")
@@ -182,7 +213,7 @@ impl TokenVault {
     /// - Category is empty or too long (> 50 chars)
     /// - Category contains invalid characters (not alphanumeric/underscore)
     /// - Session ID is empty or too long (> 255 chars)
-    /// - Counter overflows (4 billion tokens per category)
+    /// - Counter overflows (`i64::MAX` tokens per category)
     /// - Database is locked or corrupted
     ///
     /// # Performance
@@ -215,28 +246,37 @@ impl TokenVault {
             return Err(PrivacyError::Internal(format!("Session ID too long (max {} chars)", MAX_SESSION_ID_LENGTH)));
         }
 
-        let conn = self
+        let mut conn = self
             .conn
             .lock()
             .map_err(|e| PrivacyError::Internal(format!("Lock poisoned: {}", e)))?;
 
+        // Counter increment and INSERT commit together, so a failed INSERT
+        // never burns a number and a crash never reuses one
+        let tx = conn.transaction()?;
+
         // Get and increment global counter for this category
-        let mut counters = self
-            .counters
-            .lock()
-            .map_err(|e| PrivacyError::Internal(format!("Lock poisoned: {}", e)))?;
-        let counter = counters.entry(category.to_string()).or_insert(0);
+        let counter: i64 = tx
+            .query_row(
+                "SELECT value FROM token_counters WHERE category = ?1",
+                params![category],
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or(0);
 
         // Check for overflow before incrementing
-        if *counter == u32::MAX {
+        if counter == i64::MAX {
             return Err(PrivacyError::Internal(
                 format!("Token counter overflow for category '{}'. This indicates an excessive number of redactions.", category)
             ));
         }
 
-        *counter += 1;
-        let token_number = *counter;
-        drop(counters);
+        let token_number = counter + 1;
+        tx.execute(
+            "INSERT OR REPLACE INTO token_counters (category, value) VALUES (?1, ?2)",
+            params![category, token_number],
+        )?;
 
         // Generate token: [CATEGORY_NNNN]
         let token = format!("[{}_{:04}]", category, token_number);
@@ -244,11 +284,12 @@ impl TokenVault {
         debug!(%token, %category, %session_id, "Storing token in vault");
 
         // Insert into database
-        conn.execute(
+        tx.execute(
             "INSERT INTO tokens (token, category, original, session_id)
              VALUES (?1, ?2, ?3, ?4)",
             params![token, category, original, session_id],
         )?;
+        tx.commit()?;
 
         Ok(token)
     }
@@ -476,4 +517,76 @@ mod tests {
         assert_eq!(stats.by_category.get("EMAIL"), Some(&3));
         assert_eq!(stats.by_category.get("PHONE"), Some(&2));
     }
+
+    #[test]
+    fn test_counter_grows_past_four_digits() {
+        let vault = TokenVault::in_memory().unwrap();
+        vault
+            .conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO token_counters (category, value) VALUES ('EMAIL', 9999)",
+                [],
+            )
+            .unwrap();
+
+        let token = vault
+            .store("EMAIL", "test@example.com", "session1")
+            .unwrap();
+        assert_eq!(token, "[EMAIL_10000]");
+        assert_eq!(vault.retrieve(&token), Some("test@example.com".to_string()));
+    }
+
+    #[test]
+    fn test_counters_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.db");
+
+        let vault = TokenVault::new(&path).unwrap();
+        vault.store("EMAIL", "a@example.com", "session1").unwrap();
+        vault.clear_session("session1").unwrap();
+        vault.store("EMAIL", "b@example.com", "session2").unwrap();
+        drop(vault);
+
+        let vault = TokenVault::new(&path).unwrap();
+        let token = vault.store("EMAIL", "c@example.com", "session3").unwrap();
+        assert_eq!(token, "[EMAIL_0003]");
+        assert_eq!(
+            vault.retrieve("[EMAIL_0002]"),
+            Some("b@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_migrates_v0_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.db");
+
+        // A vault written before counters were persisted
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE tokens (
+                     id INTEGER PRIMARY KEY,
+                     token TEXT UNIQUE NOT NULL,
+                     category TEXT NOT NULL,
+                     original TEXT NOT NULL,
+                     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                     session_id TEXT NOT NULL
+                 );
+                 INSERT INTO tokens (token, category, original, session_id) VALUES
+                     ('[EMAIL_0001]', 'EMAIL', 'a@example.com', 's1'),
+                     ('[EMAIL_0007]', 'EMAIL', 'b@example.com', 's1'),
+                     ('[PHONE_0002]', 'PHONE', '555-0100', 's1');",
+            )
+            .unwrap();
+        }
+
+        let vault = TokenVault::new(&path).unwrap();
+        assert_eq!(vault.store("EMAIL", "c@example.com", "s2").unwrap(), "[EMAIL_0008]");
+        assert_eq!(vault.store("PHONE", "555-0101", "s2").unwrap(), "[PHONE_0003]");
+        assert_eq!(vault.store("SSN", "123-45-6789", "s2").unwrap(), "[SSN_0001]");
+        assert_eq!(vault.retrieve("[EMAIL_0007]"), Some("b@example.com".to_string()));
+    }
 }